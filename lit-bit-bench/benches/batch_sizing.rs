@@ -0,0 +1,152 @@
+//! Compares the default `max_batch_size` with the one `BatchStats::suggest_batch_size`
+//! derives from a calibration run, under a bursty load.
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use lit_bit_core::actor::BatchActor;
+use lit_bit_core::actor::spawn::{spawn_batch_actor_tokio, spawn_batch_actor_tokio_with_stats};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::runtime::Builder as RuntimeBuilder;
+
+/// Messages per load run.
+const MESSAGES: u64 = 10_000;
+/// Largest burst the load generator sends before yielding.
+const MAX_BURST: u64 = 48;
+/// `BatchActor::max_batch_size` default.
+const DEFAULT_BATCH_SIZE: usize = 32;
+
+struct SummingActor {
+    batch_size: usize,
+    sum: u64,
+    handled: Arc<AtomicU64>,
+}
+
+impl BatchActor for SummingActor {
+    type Message = u64;
+    type Future<'a>
+        = std::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle_batch(&mut self, messages: &[u64]) -> Self::Future<'_> {
+        for &msg in messages {
+            self.sum = black_box(self.sum.wrapping_add(msg));
+        }
+        self.handled
+            .fetch_add(messages.len() as u64, Ordering::Relaxed);
+        std::future::ready(())
+    }
+
+    fn max_batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+/// Deterministic burst sizes in `1..=MAX_BURST`, mostly small with occasional spikes.
+fn bursts() -> impl Iterator<Item = u64> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    std::iter::from_fn(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let roll = (state >> 33) % 100;
+        Some(if roll < 80 {
+            1 + roll % 4
+        } else {
+            1 + (state >> 40) % MAX_BURST
+        })
+    })
+}
+
+/// Sends `MESSAGES` messages in bursts, yielding between bursts, and waits until the
+/// actor has handled all of them.
+async fn run_bursty_load(address: lit_bit_core::Address<u64>, handled: &AtomicU64) {
+    let mut sent = 0;
+    for burst in bursts() {
+        for _ in 0..burst.min(MESSAGES - sent) {
+            address.send(sent).await.expect("actor is running");
+            sent += 1;
+        }
+        if sent == MESSAGES {
+            break;
+        }
+        tokio::task::yield_now().await;
+    }
+    while handled.load(Ordering::Relaxed) < MESSAGES {
+        tokio::task::yield_now().await;
+    }
+}
+
+fn suggested_batch_size(rt: &tokio::runtime::Runtime) -> usize {
+    rt.block_on(async {
+        let handled = Arc::new(AtomicU64::new(0));
+        let actor = SummingActor {
+            batch_size: DEFAULT_BATCH_SIZE,
+            sum: 0,
+            handled: Arc::clone(&handled),
+        };
+        let (address, stats) = spawn_batch_actor_tokio_with_stats(actor, 256);
+        run_bursty_load(address, &handled).await;
+
+        let stats = stats.snapshot();
+        let suggested = stats.suggest_batch_size();
+        println!(
+            "\n📊 Calibration run ({MESSAGES} messages, default batch size {DEFAULT_BATCH_SIZE}):"
+        );
+        println!(
+            "Wake-ups: {} ({} single-message)",
+            stats.wakeups,
+            stats.single_message_wakeups()
+        );
+        println!("Batch-size histogram (log2 buckets): {:?}", stats.histogram);
+        if let Some(latency) = stats.average_drain_latency() {
+            println!("Average drain latency: {latency:?}");
+        }
+        println!("Suggested max_batch_size: {suggested}");
+        suggested
+    })
+}
+
+fn bench_batch_sizing(c: &mut Criterion) {
+    let rt = RuntimeBuilder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let suggested = suggested_batch_size(&rt);
+
+    let mut group = c.benchmark_group("batch_sizing");
+    group.throughput(Throughput::Elements(MESSAGES));
+
+    for (label, batch_size) in [("default", DEFAULT_BATCH_SIZE), ("suggested", suggested)] {
+        group.bench_with_input(
+            BenchmarkId::new(label, batch_size),
+            &batch_size,
+            |b, &batch_size| {
+                b.iter_custom(|iters| {
+                    let mut total = Duration::ZERO;
+                    for _ in 0..iters {
+                        total += rt.block_on(async {
+                            let handled = Arc::new(AtomicU64::new(0));
+                            let actor = SummingActor {
+                                batch_size,
+                                sum: 0,
+                                handled: Arc::clone(&handled),
+                            };
+                            let start = Instant::now();
+                            let address = spawn_batch_actor_tokio(actor, 256);
+                            run_bursty_load(address, &handled).await;
+                            start.elapsed()
+                        });
+                    }
+                    total
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch_sizing);
+criterion_main!(benches);
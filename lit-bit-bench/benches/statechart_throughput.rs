@@ -177,6 +177,47 @@ fn bench_event_processing(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_batch_replay(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch_replay");
+
+    // A recorded 10k-event log: mostly increments, with the occasional stop/start and reset.
+    let replay_log: Vec<BenchmarkEvent> = (0..10_000)
+        .map(|i| match i % 50 {
+            0 => BenchmarkEvent::Start,
+            25 => BenchmarkEvent::Stop,
+            49 => BenchmarkEvent::Reset,
+            _ => BenchmarkEvent::Increment,
+        })
+        .collect();
+
+    group.throughput(Throughput::Elements(replay_log.len() as u64));
+    group.bench_function("send_loop", |b| {
+        b.iter(|| {
+            let mut machine =
+                BenchmarkMachine::new(BenchmarkContext::default(), &BenchmarkEvent::default())
+                    .expect("Failed to create benchmark machine");
+
+            for event in &replay_log {
+                black_box(machine.send(event));
+            }
+
+            black_box(machine.context().count);
+        });
+    });
+    group.bench_function("send_batch", |b| {
+        b.iter(|| {
+            let mut machine =
+                BenchmarkMachine::new(BenchmarkContext::default(), &BenchmarkEvent::default())
+                    .expect("Failed to create benchmark machine");
+
+            black_box(machine.send_batch(&replay_log));
+            black_box(machine.context().count);
+        });
+    });
+
+    group.finish();
+}
+
 fn bench_state_machine_creation(c: &mut Criterion) {
     let mut group = c.benchmark_group("state_machine_creation");
 
@@ -216,7 +257,7 @@ fn bench_guard_evaluation(c: &mut Criterion) {
                     .expect("Failed to create benchmark machine");
 
             // Start the machine
-            machine.send(&BenchmarkEvent::Start);
+            let _ = machine.send(&BenchmarkEvent::Start);
 
             // Test guard evaluation - should succeed for first 1000 increments
             for _ in 0..500 {
@@ -253,7 +294,7 @@ fn bench_throughput_target_validation(c: &mut Criterion) {
                 BenchmarkMachine::new(BenchmarkContext::default(), &BenchmarkEvent::default())
                     .expect("Failed to create benchmark machine");
 
-            machine.send(&BenchmarkEvent::Start);
+            let _ = machine.send(&BenchmarkEvent::Start);
 
             // Process pre-allocated events to measure only statechart processing time
             for event in &events {
@@ -270,6 +311,7 @@ criterion_group!(
     benches,
     bench_statechart_transitions,
     bench_event_processing,
+    bench_batch_replay,
     bench_state_machine_creation,
     bench_guard_evaluation,
     bench_throughput_target_validation
@@ -1,6 +1,7 @@
 use criterion::{Criterion, Throughput, criterion_group, criterion_main};
-use lit_bit_core::statechart;
+use lit_bit_core::{SendResult, StateMachine, statechart};
 use lit_bit_macro::statechart_event;
+use std::hint::black_box;
 
 // Simple state machine for benchmarking
 #[derive(Debug, Clone, Default)]
@@ -50,10 +51,102 @@ pub fn bench_state_transitions(c: &mut Criterion) {
     group.finish();
 }
 
+type BenchMachineObject =
+    dyn StateMachine<State = BenchStateMachineStateId, Event = BenchEvent, Context = BenchContext>;
+
+#[inline(never)]
+fn send_generic<M: StateMachine<Event = BenchEvent>>(machine: &mut M) -> SendResult {
+    machine.send(black_box(&BenchEvent::Toggle))
+}
+
+#[inline(never)]
+fn send_dyn(machine: &mut BenchMachineObject) -> SendResult {
+    machine.send(black_box(&BenchEvent::Toggle))
+}
+
+/// Documents the cost of dynamic dispatch: the inherent and generic paths should be
+/// indistinguishable, while the trait-object path pays for an indirect call.
+pub fn bench_send_dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("send_dispatch");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("inherent_send", |b| {
+        let mut machine = BenchStateMachine::new(BenchContext::default(), &BenchEvent::Toggle)
+            .expect("Failed to create state machine");
+        b.iter(|| machine.send(black_box(&BenchEvent::Toggle)));
+    });
+
+    group.bench_function("generic_trait_send", |b| {
+        let mut machine = BenchStateMachine::new(BenchContext::default(), &BenchEvent::Toggle)
+            .expect("Failed to create state machine");
+        b.iter(|| send_generic(&mut machine));
+    });
+
+    group.bench_function("trait_object_send", |b| {
+        let mut machine = BenchStateMachine::new(BenchContext::default(), &BenchEvent::Toggle)
+            .expect("Failed to create state machine");
+        let machine: &mut BenchMachineObject = &mut machine;
+        b.iter(|| send_dyn(machine));
+    });
+
+    group.finish();
+}
+
+/// Quantifies what a flat chart saves by running on `FlatRuntime` instead of the general
+/// multi-region `Runtime`. Both runtimes execute the same machine definition.
+#[cfg(feature = "single-region")]
+pub fn bench_single_region(c: &mut Criterion) {
+    use lit_bit_core::{FlatRuntime, MAX_ACTIVE_REGIONS, Runtime};
+
+    type MultiRegion = Runtime<
+        BenchStateMachineStateId,
+        BenchEvent,
+        BenchContext,
+        2,
+        MAX_ACTIVE_REGIONS,
+        { 2 * MAX_ACTIVE_REGIONS },
+    >;
+    type SingleRegion = FlatRuntime<BenchStateMachineStateId, BenchEvent, BenchContext, 2>;
+
+    println!(
+        "runtime size: multi-region {} bytes, single-region {} bytes",
+        core::mem::size_of::<MultiRegion>(),
+        core::mem::size_of::<SingleRegion>()
+    );
+
+    let mut group = c.benchmark_group("single_region");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("multi_region_runtime", |b| {
+        let mut runtime = MultiRegion::new(
+            &BENCHSTATEMACHINE_MACHINE_DEFINITION,
+            BenchContext::default(),
+            &BenchEvent::Toggle,
+        )
+        .expect("Failed to create runtime");
+        b.iter(|| runtime.send(black_box(&BenchEvent::Toggle)));
+    });
+
+    group.bench_function("flat_runtime", |b| {
+        let mut runtime = SingleRegion::new(
+            &BENCHSTATEMACHINE_MACHINE_DEFINITION,
+            BenchContext::default(),
+            &BenchEvent::Toggle,
+        )
+        .expect("Failed to create runtime");
+        b.iter(|| runtime.send(black_box(&BenchEvent::Toggle)));
+    });
+
+    group.finish();
+}
+
+#[cfg(not(feature = "single-region"))]
+pub fn bench_single_region(_c: &mut Criterion) {}
+
 criterion_group!(
     name = benches;
     config = Criterion::default();
-    targets = bench_state_transitions
+    targets = bench_state_transitions, bench_send_dispatch, bench_single_region
 );
 
 criterion_main!(benches);
@@ -0,0 +1,95 @@
+//! Runs a soak scenario and writes its time series as CSV.
+//!
+//! ```text
+//! soak [--smoke] [--minutes N] [--actors N] [--rate MSGS_PER_SEC]
+//!      [--failure-rate SHARE] [--csv PATH]
+//! ```
+//!
+//! Exits with an error if any growth detector fails. Usually run through
+//! `cargo xtask soak`.
+
+use anyhow::{Context, Result};
+use lit_bit_bench::soak::{SoakConfig, run_soak};
+use std::path::PathBuf;
+
+fn main() -> Result<()> {
+    let mut config = SoakConfig::default();
+    let mut csv_path = PathBuf::from("target/soak/soak.csv");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .with_context(|| format!("`{arg}` needs a value"))
+        };
+        match arg.as_str() {
+            "--smoke" => {
+                config = SoakConfig {
+                    actors: config.actors,
+                    message_rate: config.message_rate,
+                    failure_rate: config.failure_rate,
+                    ..SoakConfig::smoke()
+                };
+            }
+            "--minutes" => {
+                let minutes = value()?
+                    .parse()
+                    .context("`--minutes` takes a whole number")?;
+                config = SoakConfig {
+                    actors: config.actors,
+                    message_rate: config.message_rate,
+                    failure_rate: config.failure_rate,
+                    ..SoakConfig::minutes(minutes)
+                };
+            }
+            "--actors" => config.actors = value()?.parse().context("`--actors` takes a count")?,
+            "--rate" => {
+                config.message_rate = value()?
+                    .parse()
+                    .context("`--rate` takes messages per second")?;
+            }
+            "--failure-rate" => {
+                config.failure_rate = value()?
+                    .parse()
+                    .context("`--failure-rate` takes a share between 0 and 1")?;
+            }
+            "--csv" => csv_path = PathBuf::from(value()?),
+            other => anyhow::bail!("unknown argument `{other}`"),
+        }
+    }
+
+    println!(
+        "Soaking {} actors at {} msg/s ({:.3}% failures) for {:?}...",
+        config.actors,
+        config.message_rate,
+        config.failure_rate * 100.0,
+        config.duration
+    );
+    let report = run_soak(&config)?;
+
+    if let Some(dir) = csv_path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&csv_path, report.to_csv())
+        .with_context(|| format!("writing {}", csv_path.display()))?;
+    if let Some(last) = report.samples.last() {
+        println!(
+            "{} samples: {} processed, {} dropped, {} restarts -> {}",
+            report.samples.len(),
+            last.processed,
+            last.dropped,
+            last.restarts,
+            csv_path.display()
+        );
+    }
+
+    for violation in &report.violations {
+        eprintln!(
+            "✗ {:?} grew {:.1}/min (limit {:.1}/min)",
+            violation.metric, violation.slope_per_minute, violation.max_slope_per_minute
+        );
+    }
+    anyhow::ensure!(report.passed(), "soak detected monotonic growth");
+    println!("✓ No drift or leaks detected");
+    Ok(())
+}
@@ -6,6 +6,7 @@
 pub mod fixtures;
 pub mod metrics;
 pub mod runtime;
+pub mod soak;
 
 // Re-export commonly used types
 pub use metrics::{
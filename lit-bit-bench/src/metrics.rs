@@ -108,6 +108,12 @@ impl LatencyMeter {
         Duration::from_nanos((sum / self.samples.len() as u128) as u64)
     }
 
+    /// Drops the recorded samples but keeps their allocation.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.sorted = false;
+    }
+
     pub fn to_metrics(&mut self) -> LatencyMetrics {
         LatencyMetrics {
             p50: self.percentile(0.50),
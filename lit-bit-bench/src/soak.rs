@@ -0,0 +1,525 @@
+//! Soak testing for long-running actor systems
+//!
+//! A scenario spawns a fleet of actors, feeds them at a fixed message rate with a share of
+//! injected failures, and samples the process every `sample_interval`: resident memory,
+//! queued mailbox messages, throughput, restarts and handling latency. The samples form a
+//! time series that can be written as CSV, and growth detectors fit a least-squares line
+//! to each watched metric and fail the scenario if it keeps climbing.
+
+use crate::metrics::{LatencyMeter, LatencyMetrics};
+use lit_bit_core::actor::{
+    Actor, ActorError, ActorId, Inbox, Outbox, create_controlled_failure, create_mailbox,
+};
+use parking_lot::Mutex;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// How often the producer tops up the mailboxes.
+const SEND_TICK: Duration = Duration::from_millis(5);
+
+/// A soak scenario.
+#[derive(Debug, Clone)]
+pub struct SoakConfig {
+    /// Number of actors messages are spread over (round robin).
+    pub actors: usize,
+    /// Messages per second, across all actors.
+    pub message_rate: u32,
+    /// Share of messages, between 0 and 1, that make their actor fail and restart.
+    pub failure_rate: f64,
+    /// How long the scenario runs.
+    pub duration: Duration,
+    /// Time between two samples.
+    pub sample_interval: Duration,
+    /// Mailbox capacity of each actor; messages sent to a full mailbox are dropped.
+    pub mailbox_capacity: usize,
+    /// Samples taken before this point are recorded but ignored by the detectors, so
+    /// start-up allocations do not read as growth.
+    pub warmup: Duration,
+    /// Checks applied to the samples once the run is over.
+    pub detectors: Vec<GrowthDetector>,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self::minutes(10)
+    }
+}
+
+impl SoakConfig {
+    /// A full soak of `minutes`, sampled every five seconds.
+    pub fn minutes(minutes: u64) -> Self {
+        Self {
+            actors: 64,
+            message_rate: 20_000,
+            failure_rate: 0.001,
+            duration: Duration::from_secs(minutes * 60),
+            sample_interval: Duration::from_secs(5),
+            mailbox_capacity: 256,
+            warmup: Duration::from_secs(30),
+            detectors: GrowthDetector::defaults(),
+        }
+    }
+
+    /// The 30-second variant run in CI.
+    pub fn smoke() -> Self {
+        Self {
+            duration: Duration::from_secs(30),
+            sample_interval: Duration::from_secs(1),
+            warmup: Duration::from_secs(5),
+            ..Self::minutes(0)
+        }
+    }
+}
+
+/// A metric the growth detectors can watch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoakMetric {
+    /// Resident set size of the process, in bytes (only sampled on Linux).
+    RssBytes,
+    /// Messages waiting in all mailboxes together.
+    QueuedMessages,
+    /// 99th percentile handling latency of the sample window, in microseconds.
+    P99LatencyMicros,
+}
+
+impl SoakMetric {
+    fn value(self, sample: &SoakSample) -> Option<f64> {
+        match self {
+            Self::RssBytes => sample.rss_bytes.map(|bytes| bytes as f64),
+            Self::QueuedMessages => Some(sample.queued as f64),
+            Self::P99LatencyMicros => Some(sample.latency.p99.as_micros() as f64),
+        }
+    }
+}
+
+/// Fails a scenario when a metric's regression slope exceeds `max_slope_per_minute`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowthDetector {
+    pub metric: SoakMetric,
+    /// Largest allowed growth of the metric, in its own unit per minute.
+    pub max_slope_per_minute: f64,
+}
+
+impl GrowthDetector {
+    /// Half a megabyte of RSS, 64 queued messages and one millisecond of p99 latency per
+    /// minute.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self {
+                metric: SoakMetric::RssBytes,
+                max_slope_per_minute: 512.0 * 1024.0,
+            },
+            Self {
+                metric: SoakMetric::QueuedMessages,
+                max_slope_per_minute: 64.0,
+            },
+            Self {
+                metric: SoakMetric::P99LatencyMicros,
+                max_slope_per_minute: 1_000.0,
+            },
+        ]
+    }
+
+    /// The least-squares slope of the metric per minute, or `None` if fewer than two
+    /// samples carry it.
+    pub fn slope_per_minute(&self, samples: &[SoakSample]) -> Option<f64> {
+        let points: Vec<(f64, f64)> = samples
+            .iter()
+            .filter_map(|sample| {
+                let value = self.metric.value(sample)?;
+                Some((sample.elapsed.as_secs_f64() / 60.0, value))
+            })
+            .collect();
+        linear_slope(&points)
+    }
+
+    /// A violation if the metric grew faster than allowed over `samples`.
+    pub fn check(&self, samples: &[SoakSample]) -> Option<GrowthViolation> {
+        let slope = self.slope_per_minute(samples)?;
+        (slope > self.max_slope_per_minute).then_some(GrowthViolation {
+            metric: self.metric,
+            slope_per_minute: slope,
+            max_slope_per_minute: self.max_slope_per_minute,
+        })
+    }
+}
+
+/// A metric that grew faster than its detector allows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowthViolation {
+    pub metric: SoakMetric,
+    pub slope_per_minute: f64,
+    pub max_slope_per_minute: f64,
+}
+
+/// One point of the time series.
+#[derive(Debug, Clone)]
+pub struct SoakSample {
+    /// Time since the scenario started.
+    pub elapsed: Duration,
+    pub rss_bytes: Option<u64>,
+    /// Messages waiting in all mailboxes together.
+    pub queued: usize,
+    /// Messages handled so far.
+    pub processed: u64,
+    /// Messages dropped so far because a mailbox was full.
+    pub dropped: u64,
+    /// Actor restarts after injected failures so far.
+    pub restarts: u64,
+    /// Handling latency (send to handle) of the messages since the previous sample.
+    pub latency: LatencyMetrics,
+}
+
+/// The outcome of a scenario.
+#[derive(Debug, Clone)]
+pub struct SoakReport {
+    pub samples: Vec<SoakSample>,
+    pub violations: Vec<GrowthViolation>,
+}
+
+impl SoakReport {
+    /// Whether every detector passed.
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// The samples as CSV, one row per sample; durations are in seconds and latencies in
+    /// microseconds, and an unavailable RSS is left empty.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "elapsed_s,rss_bytes,queued,processed,dropped,restarts,p50_us,p95_us,p99_us,max_us\n",
+        );
+        for sample in &self.samples {
+            let rss = sample.rss_bytes.map(|bytes| bytes.to_string());
+            let _ = writeln!(
+                csv,
+                "{:.3},{},{},{},{},{},{},{},{},{}",
+                sample.elapsed.as_secs_f64(),
+                rss.as_deref().unwrap_or(""),
+                sample.queued,
+                sample.processed,
+                sample.dropped,
+                sample.restarts,
+                sample.latency.p50.as_micros(),
+                sample.latency.p95.as_micros(),
+                sample.latency.p99.as_micros(),
+                sample.latency.max.as_micros(),
+            );
+        }
+        csv
+    }
+}
+
+/// Runs a scenario on a multi-threaded Tokio runtime and checks its detectors.
+pub fn run_soak(config: &SoakConfig) -> anyhow::Result<SoakReport> {
+    anyhow::ensure!(
+        config.actors > 0,
+        "a soak scenario needs at least one actor"
+    );
+    anyhow::ensure!(
+        (0.0..=1.0).contains(&config.failure_rate),
+        "failure rate must be between 0 and 1, got {}",
+        config.failure_rate
+    );
+    anyhow::ensure!(
+        !config.sample_interval.is_zero(),
+        "sample interval must not be zero"
+    );
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_time()
+        .build()?;
+    let samples = runtime.block_on(run_scenario(config));
+
+    let checked: Vec<_> = samples
+        .iter()
+        .filter(|sample| sample.elapsed >= config.warmup)
+        .cloned()
+        .collect();
+    let violations = config
+        .detectors
+        .iter()
+        .filter_map(|detector| detector.check(&checked))
+        .collect();
+    Ok(SoakReport {
+        samples,
+        violations,
+    })
+}
+
+/// Counters shared by the producer, the actors and the sampler.
+#[derive(Default)]
+struct SoakStats {
+    processed: AtomicU64,
+    dropped: AtomicU64,
+    restarts: AtomicU64,
+    latency: Mutex<LatencyMeter>,
+}
+
+struct SoakMessage {
+    sent_at: Instant,
+    fail: bool,
+}
+
+/// The actor under test: counts what it handles and fails on request.
+struct SoakWorker {
+    id: ActorId,
+    stats: Arc<SoakStats>,
+    failure: Option<ActorError>,
+}
+
+impl SoakWorker {
+    fn new(id: ActorId, stats: Arc<SoakStats>) -> Self {
+        Self {
+            id,
+            stats,
+            failure: None,
+        }
+    }
+}
+
+impl Actor for SoakWorker {
+    type Message = SoakMessage;
+    type Future<'a> = core::future::Ready<()>;
+
+    fn handle(&mut self, msg: SoakMessage) -> Self::Future<'_> {
+        self.stats.latency.lock().record(msg.sent_at.elapsed());
+        self.stats.processed.fetch_add(1, Ordering::Relaxed);
+        if msg.fail {
+            self.failure = Some(create_controlled_failure(
+                "soak: injected failure",
+                Some(self.id),
+            ));
+        }
+        core::future::ready(())
+    }
+}
+
+/// Runs one actor, replacing it with a fresh instance on the same mailbox whenever it
+/// reports a failure.
+async fn supervised_worker(id: ActorId, mut inbox: Inbox<SoakMessage>, stats: Arc<SoakStats>) {
+    let mut worker = SoakWorker::new(id, Arc::clone(&stats));
+    while let Some(msg) = inbox.recv().await {
+        worker.handle(msg).await;
+        if worker.failure.take().is_some() {
+            worker = SoakWorker::new(id, Arc::clone(&stats));
+            stats.restarts.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+async fn run_scenario(config: &SoakConfig) -> Vec<SoakSample> {
+    let stats = Arc::new(SoakStats::default());
+    let mut outboxes: Vec<Outbox<SoakMessage>> = Vec::with_capacity(config.actors);
+    let mut workers = Vec::with_capacity(config.actors);
+    for index in 0..config.actors {
+        let (outbox, inbox) = create_mailbox(config.mailbox_capacity);
+        outboxes.push(outbox);
+        workers.push(tokio::spawn(supervised_worker(
+            ActorId::new(index as u32),
+            inbox,
+            Arc::clone(&stats),
+        )));
+    }
+
+    let producer = tokio::spawn(produce(
+        outboxes.clone(),
+        config.message_rate,
+        config.failure_rate,
+        Arc::clone(&stats),
+    ));
+
+    let start = Instant::now();
+    let mut samples = Vec::new();
+    let mut ticker = tokio::time::interval_at(
+        tokio::time::Instant::now() + config.sample_interval,
+        config.sample_interval,
+    );
+    while start.elapsed() < config.duration {
+        ticker.tick().await;
+        samples.push(take_sample(start.elapsed(), &outboxes, &stats));
+    }
+
+    producer.abort();
+    drop(outboxes);
+    for worker in workers {
+        let _ = worker.await;
+    }
+    samples
+}
+
+/// Sends `message_rate` messages per second round robin, marking every
+/// `1 / failure_rate`-th one as a failure.
+async fn produce(
+    outboxes: Vec<Outbox<SoakMessage>>,
+    message_rate: u32,
+    failure_rate: f64,
+    stats: Arc<SoakStats>,
+) {
+    let per_tick = f64::from(message_rate) * SEND_TICK.as_secs_f64();
+    let mut ticker = tokio::time::interval(SEND_TICK);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut send_credit = 0.0;
+    let mut failure_credit = 0.0;
+    let mut next = 0;
+    loop {
+        ticker.tick().await;
+        send_credit += per_tick;
+        while send_credit >= 1.0 {
+            send_credit -= 1.0;
+            failure_credit += failure_rate;
+            let fail = failure_credit >= 1.0;
+            if fail {
+                failure_credit -= 1.0;
+            }
+            let msg = SoakMessage {
+                sent_at: Instant::now(),
+                fail,
+            };
+            if outboxes[next].try_send(msg).is_err() {
+                stats.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            next = (next + 1) % outboxes.len();
+        }
+    }
+}
+
+fn take_sample(
+    elapsed: Duration,
+    outboxes: &[Outbox<SoakMessage>],
+    stats: &SoakStats,
+) -> SoakSample {
+    // Cleared rather than replaced so the window's buffer is not reallocated every sample.
+    let latency = {
+        let mut window = stats.latency.lock();
+        let metrics = window.to_metrics();
+        window.clear();
+        metrics
+    };
+    SoakSample {
+        elapsed,
+        rss_bytes: resident_set_bytes(),
+        queued: outboxes
+            .iter()
+            .map(|outbox| outbox.max_capacity() - outbox.capacity())
+            .sum(),
+        processed: stats.processed.load(Ordering::Relaxed),
+        dropped: stats.dropped.load(Ordering::Relaxed),
+        restarts: stats.restarts.load(Ordering::Relaxed),
+        latency,
+    }
+}
+
+/// The resident set size from `/proc/self/status`; `None` where that is unavailable.
+fn resident_set_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Ordinary least-squares slope of `points` (`(x, y)` pairs).
+fn linear_slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (covariance, variance) = points.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+        let dx = x - mean_x;
+        (cov + dx * (y - mean_y), var + dx * dx)
+    });
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(seconds: u64, queued: usize) -> SoakSample {
+        SoakSample {
+            elapsed: Duration::from_secs(seconds),
+            rss_bytes: None,
+            queued,
+            processed: 0,
+            dropped: 0,
+            restarts: 0,
+            latency: LatencyMeter::new().to_metrics(),
+        }
+    }
+
+    #[test]
+    fn slope_is_fitted_per_minute() {
+        let detector = GrowthDetector {
+            metric: SoakMetric::QueuedMessages,
+            max_slope_per_minute: 10.0,
+        };
+        // One more queued message every six seconds: ten per minute.
+        let samples: Vec<_> = (0..10).map(|i| sample(i * 6, i as usize)).collect();
+        let slope = detector.slope_per_minute(&samples).unwrap();
+        assert!((slope - 10.0).abs() < 1e-9, "slope {slope}");
+        assert_eq!(detector.check(&samples), None);
+
+        let steeper: Vec<_> = (0..10).map(|i| sample(i * 6, 2 * i as usize)).collect();
+        let violation = detector.check(&steeper).expect("growth is over the limit");
+        assert_eq!(violation.metric, SoakMetric::QueuedMessages);
+    }
+
+    #[test]
+    fn flat_or_missing_metrics_pass() {
+        let detector = GrowthDetector {
+            metric: SoakMetric::RssBytes,
+            max_slope_per_minute: 0.0,
+        };
+        let samples: Vec<_> = (0..5).map(|i| sample(i, 3)).collect();
+        assert_eq!(detector.slope_per_minute(&samples), None);
+
+        let queued = GrowthDetector {
+            metric: SoakMetric::QueuedMessages,
+            max_slope_per_minute: 0.0,
+        };
+        assert_eq!(queued.check(&samples), None);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_sample() {
+        let report = SoakReport {
+            samples: vec![sample(1, 4), sample(2, 0)],
+            violations: Vec::new(),
+        };
+        let csv = report.to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("elapsed_s,rss_bytes,queued"));
+        assert_eq!(lines[1], "1.000,,4,0,0,0,0,0,0,0");
+    }
+
+    #[test]
+    fn short_scenario_restarts_failed_actors() {
+        let config = SoakConfig {
+            actors: 4,
+            message_rate: 2_000,
+            failure_rate: 0.01,
+            duration: Duration::from_millis(600),
+            sample_interval: Duration::from_millis(200),
+            warmup: Duration::ZERO,
+            detectors: Vec::new(),
+            ..SoakConfig::smoke()
+        };
+        let report = run_soak(&config).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.samples.len(), 3);
+        let last = report.samples.last().unwrap();
+        assert!(last.processed > 0);
+        assert!(last.restarts > 0);
+    }
+}
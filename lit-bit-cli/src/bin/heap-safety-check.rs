@@ -76,7 +76,7 @@ fn main() {
             .expect("Failed to read geiger_report.json content");
 
         eprintln!("🐛 DEBUG_GEIGER: Raw JSON content (first 500 chars):");
-        eprintln!("{}", &content.chars().take(500).collect::<String>());
+        eprintln!("{}", content.chars().take(500).collect::<String>());
         if content.len() > 500 {
             eprintln!("... (truncated, total length: {} chars)", content.len());
         }
@@ -0,0 +1,402 @@
+//! Reads `statechart!` invocations straight from Rust source files.
+//!
+//! This is a lightweight token scanner, not a Rust parser: it understands the chart DSL
+//! (`state`, `initial:`, `on ... => ...;` with an optional `else => ...`, `after(...) => ...;`,
+//! `[parallel]`) and skips everything else, which is enough to build an [`ErasedDefinition`]
+//! for diffing without compiling the crate that contains the chart.
+
+use lit_bit_core::upgrade::{ErasedDefinition, ErasedState, ErasedTransition};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(String),
+    Punct(String),
+}
+
+impl Token {
+    fn text(&self) -> &str {
+        match self {
+            Token::Ident(text) | Token::Literal(text) | Token::Punct(text) => text,
+        }
+    }
+
+    fn is(&self, text: &str) -> bool {
+        self.text() == text
+    }
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if c == '"' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i += 1;
+            tokens.push(Token::Literal(
+                chars[start..i.min(chars.len())].iter().collect(),
+            ));
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let pair: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            if matches!(pair.as_str(), "=>" | "::" | "..") {
+                tokens.push(Token::Punct(pair));
+                i += 2;
+            } else {
+                tokens.push(Token::Punct(c.to_string()));
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+struct RawState {
+    path: String,
+    parent: Option<usize>,
+    declared_initial: Option<String>,
+    is_parallel: bool,
+}
+
+struct RawTransition {
+    from: usize,
+    target: String,
+    event: String,
+    guarded: bool,
+}
+
+struct ChartParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    states: Vec<RawState>,
+    transitions: Vec<RawTransition>,
+}
+
+impl<'a> ChartParser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token.is(text) => Ok(()),
+            Some(token) => Err(format!("expected `{text}`, found `{}`", token.text())),
+            None => Err(format!("expected `{text}`, found end of input")),
+        }
+    }
+
+    /// Collects tokens up to (not including) the first top-level token accepted by `stop`.
+    fn take_until(&mut self, stop: impl Fn(&ChartParser<'a>) -> bool) -> Vec<&'a Token> {
+        let mut taken = Vec::new();
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            if depth == 0 && (stop(self) || token.is("}")) {
+                break;
+            }
+            match token.text() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            taken.push(token);
+            self.pos += 1;
+        }
+        taken
+    }
+
+    fn parse_header(&mut self) -> Result<Option<String>, String> {
+        let mut initial = None;
+        while let Some(token) = self.peek() {
+            if token.is("state") {
+                break;
+            }
+            let key = self.next().map(Token::text).unwrap_or_default();
+            self.expect(":")?;
+            let value = self.take_until(|parser| parser.peek().is_some_and(|t| t.is(",")));
+            if key == "initial" {
+                initial = Some(join_path(&value));
+            }
+            if self.peek().is_some_and(|t| t.is(",")) {
+                self.pos += 1;
+            }
+        }
+        Ok(initial)
+    }
+
+    fn parse_state(&mut self, parent: Option<usize>) -> Result<(), String> {
+        self.expect("state")?;
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name.clone(),
+            other => {
+                return Err(format!(
+                    "expected a state name, found `{}`",
+                    other.map_or("end of input", Token::text)
+                ));
+            }
+        };
+        let mut is_parallel = false;
+        if self.peek().is_some_and(|t| t.is("[")) {
+            let attributes = self.take_until(|parser| parser.peek().is_some_and(|t| t.is("{")));
+            is_parallel = attributes.iter().any(|t| t.is("parallel"));
+        }
+        self.expect("{")?;
+
+        let path = match parent {
+            Some(parent) => format!("{}.{name}", self.states[parent].path),
+            None => name,
+        };
+        let index = self.states.len();
+        self.states.push(RawState {
+            path,
+            parent,
+            declared_initial: None,
+            is_parallel,
+        });
+
+        loop {
+            let Some(token) = self.peek() else {
+                return Err("unterminated state body".to_string());
+            };
+            match token.text() {
+                "}" => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                "state" => self.parse_state(Some(index))?,
+                "initial" => {
+                    self.pos += 1;
+                    self.expect(":")?;
+                    let value = self.take_until(|parser| parser.peek().is_some_and(|t| t.is(";")));
+                    self.states[index].declared_initial = Some(join_path(&value));
+                    self.expect(";")?;
+                }
+                "on" | "after" => {
+                    let is_after = token.is("after");
+                    self.pos += 1;
+                    let pattern = self.take_until(|parser| {
+                        parser.peek().is_some_and(|t| t.is("=>"))
+                            || (parser.peek().is_some_and(|t| t.is("["))
+                                && parser
+                                    .tokens
+                                    .get(parser.pos + 1)
+                                    .is_some_and(|t| t.is("guard")))
+                    });
+                    let guarded = self.peek().is_some_and(|t| t.is("["));
+                    if guarded {
+                        self.take_until(|parser| parser.peek().is_some_and(|t| t.is("=>")));
+                    }
+                    self.expect("=>")?;
+                    let target = self
+                        .take_until(|parser| parser.peek().is_some_and(|t| t.is(";") || t.is("[")));
+                    self.take_until(|parser| {
+                        parser.peek().is_some_and(|t| t.is(";") || t.is("else"))
+                    });
+                    let else_target = if self.peek().is_some_and(|t| t.is("else")) {
+                        self.pos += 1;
+                        self.expect("=>")?;
+                        let target = self.take_until(|parser| {
+                            parser.peek().is_some_and(|t| t.is(";") || t.is("["))
+                        });
+                        self.take_until(|parser| parser.peek().is_some_and(|t| t.is(";")));
+                        Some(join_path(&target))
+                    } else {
+                        None
+                    };
+                    self.expect(";")?;
+                    let pattern = join_tokens(&pattern);
+                    let event = if is_after {
+                        format!("after{pattern}")
+                    } else {
+                        pattern
+                    };
+                    self.transitions.push(RawTransition {
+                        from: index,
+                        target: join_path(&target),
+                        event: event.clone(),
+                        guarded,
+                    });
+                    // `else => B` is an unguarded transition on the same event.
+                    if let Some(target) = else_target {
+                        self.transitions.push(RawTransition {
+                            from: index,
+                            target,
+                            event,
+                            guarded: false,
+                        });
+                    }
+                }
+                _ => {
+                    // `entry:`, `exit:` and anything else the diff does not care about.
+                    self.take_until(|parser| parser.peek().is_some_and(|t| t.is(";")));
+                    if self.peek().is_some_and(|t| t.is(";")) {
+                        self.pos += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.states.len()).filter(move |&i| self.states[i].parent == Some(index))
+    }
+
+    /// Resolves a target the way `statechart!` does: a single name is looked up as a child,
+    /// then a sibling, then a top-level state; a `A::B` path is looked up in full.
+    fn resolve(&self, from: usize, target: &str) -> Option<usize> {
+        let by_path = |path: &str| self.states.iter().position(|state| state.path == path);
+        if !target.contains('.') {
+            let current = &self.states[from];
+            if let Some(child) = by_path(&format!("{}.{target}", current.path)) {
+                return Some(child);
+            }
+            if let Some(parent) = current.parent
+                && let Some(sibling) = by_path(&format!("{}.{target}", self.states[parent].path))
+            {
+                return Some(sibling);
+            }
+        }
+        by_path(target)
+    }
+
+    fn into_definition(self, initial: &str) -> Result<ErasedDefinition, String> {
+        let mut states = Vec::new();
+        for (index, raw) in self.states.iter().enumerate() {
+            let initial_child = match &raw.declared_initial {
+                Some(declared) => Some(self.resolve(index, declared).ok_or_else(|| {
+                    format!("unknown initial state `{declared}` in `{}`", raw.path)
+                })?),
+                None if raw.is_parallel => None,
+                None => self.children(index).next(),
+            };
+            states.push(ErasedState {
+                path: raw.path.clone(),
+                parent: raw.parent.map(|parent| self.states[parent].path.clone()),
+                initial_child: initial_child.map(|child| self.states[child].path.clone()),
+                is_parallel: raw.is_parallel,
+            });
+        }
+        let mut transitions = Vec::new();
+        for raw in &self.transitions {
+            let to = self
+                .resolve(raw.from, &raw.target)
+                .ok_or_else(|| format!("unknown transition target `{}`", raw.target))?;
+            transitions.push(ErasedTransition {
+                from: self.states[raw.from].path.clone(),
+                to: self.states[to].path.clone(),
+                event: Some(raw.event.clone()),
+                guarded: raw.guarded,
+            });
+        }
+        Ok(ErasedDefinition::new(states, transitions, initial))
+    }
+}
+
+fn join_path(tokens: &[&Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| if token.is("::") { "." } else { token.text() })
+        .collect()
+}
+
+fn join_tokens(tokens: &[&Token]) -> String {
+    let mut text = String::new();
+    let mut previous: Option<&Token> = None;
+    for token in tokens {
+        let spaced = match (previous, token) {
+            (Some(Token::Ident(_) | Token::Literal(_)), Token::Ident(_) | Token::Literal(_)) => {
+                true
+            }
+            (Some(prev), _) if prev.is(",") => true,
+            (Some(_), _) if ["|", "=>", "{", "}"].iter().any(|p| token.is(p)) => true,
+            (Some(prev), _) if ["|", "=>", "{"].iter().any(|p| prev.is(p)) => true,
+            _ => false,
+        };
+        if spaced {
+            text.push(' ');
+        }
+        text.push_str(token.text());
+        previous = Some(token);
+    }
+    text
+}
+
+/// Parses the chart named `name` (or the first chart, if `name` is `None`) in `source`.
+///
+/// # Errors
+///
+/// Returns a message if no matching `statechart!` invocation is found or it is malformed.
+pub fn parse_chart(source: &str, name: Option<&str>) -> Result<ErasedDefinition, String> {
+    let tokens = tokenize(source);
+    let mut search = 0;
+    while let Some(offset) = tokens[search..].windows(3).position(|window| {
+        window[0].is("statechart") && window[1].is("!") && (window[2].is("{") || window[2].is("("))
+    }) {
+        let start = search + offset + 3;
+        search = start;
+        let mut parser = ChartParser {
+            tokens: &tokens,
+            pos: start,
+            states: Vec::new(),
+            transitions: Vec::new(),
+        };
+        let chart_name = parser
+            .tokens
+            .get(start..start + 3)
+            .filter(|header| header[0].is("name") && header[1].is(":"))
+            .map(|header| header[2].text().to_string());
+        if name.is_some_and(|wanted| chart_name.as_deref() != Some(wanted)) {
+            continue;
+        }
+
+        let initial = parser
+            .parse_header()?
+            .ok_or_else(|| "chart has no `initial:` state".to_string())?;
+        while parser.peek().is_some_and(|t| t.is("state")) {
+            parser.parse_state(None)?;
+        }
+        return parser.into_definition(&initial);
+    }
+    Err(match name {
+        Some(name) => format!("no `statechart!` named `{name}` found"),
+        None => "no `statechart!` invocation found".to_string(),
+    })
+}
@@ -1,3 +1,107 @@
-fn main() {
-    println!("lit-bit CLI: stub (core + serde_json loaded)");
+mod chart_source;
+mod stats;
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use lit_bit_core::upgrade::diff_definitions;
+
+const USAGE: &str = "\
+usage: lit-bit-cli <command>
+
+commands:
+  diff <old.rs> <new.rs> [--chart <name>]
+      Print the structural differences between the `statechart!` charts in two
+      source files (the first chart in each file, or the one named by --chart).
+  stats <dir> [--baseline <file>] [--threshold <percent>] [--save-baseline <file>]
+      Tabulate the chart statistics `statechart!` writes to <dir> when built with
+      LIT_BIT_STATS_DIR=<dir>. With --baseline, list what changed since and fail if a
+      figure grew by more than --threshold percent (25 by default).";
+
+fn diff(args: &[String]) -> Result<(), String> {
+    let mut paths = Vec::new();
+    let mut chart = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--chart" {
+            chart = Some(args.next().ok_or("--chart needs a chart name")?.as_str());
+        } else {
+            paths.push(arg);
+        }
+    }
+    let [old_path, new_path] = paths.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let load = |path: &String| {
+        let source =
+            std::fs::read_to_string(path).map_err(|err| format!("cannot read {path}: {err}"))?;
+        chart_source::parse_chart(&source, chart).map_err(|err| format!("{path}: {err}"))
+    };
+    let old = load(old_path)?;
+    let new = load(new_path)?;
+
+    println!("--- {old_path}");
+    println!("+++ {new_path}");
+    println!("{}", diff_definitions(&old, &new));
+    Ok(())
+}
+
+fn stats(args: &[String]) -> Result<(), String> {
+    let mut dir = None;
+    let mut baseline = None;
+    let mut save = None;
+    let mut threshold = 25;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--baseline" => baseline = Some(value()?),
+            "--save-baseline" => save = Some(value()?),
+            "--threshold" => {
+                threshold = value()?
+                    .parse()
+                    .map_err(|_| "--threshold needs a whole percentage")?;
+            }
+            _ if dir.is_none() => dir = Some(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let dir = dir.ok_or(USAGE)?;
+
+    let current = stats::load_dir(Path::new(dir))?;
+    print!("{}", stats::table(&current));
+    if let Some(path) = save {
+        stats::save_baseline(Path::new(path), &current)?;
+    }
+    let Some(path) = baseline else {
+        return Ok(());
+    };
+    let comparison = stats::compare(&stats::load_baseline(Path::new(path))?, &current, threshold);
+    println!();
+    print!("{comparison}");
+    if comparison.jumps.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} figure(s) grew by more than {threshold}% since {path}",
+            comparison.jumps.len()
+        ))
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("diff") => diff(&args[1..]),
+        Some("stats") => stats(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
 }
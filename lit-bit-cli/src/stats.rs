@@ -0,0 +1,245 @@
+//! `lit-bit-cli stats`: the chart statistics `statechart!` writes when built with
+//! `LIT_BIT_STATS_DIR` set, as a table, and compared against a saved baseline.
+
+use std::fmt::{self, Write as _};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// One chart's figures, as in the macro's `<crate>.<Machine>.json` files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartStats {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub machine: String,
+    pub states: usize,
+    pub transitions: usize,
+    pub max_depth: usize,
+    pub parallel_regions: usize,
+    pub guards: usize,
+    pub actions: usize,
+    pub average_out_degree: f64,
+    pub definition_hash: String,
+}
+
+impl ChartStats {
+    /// `crate::Machine`, which identifies a chart across runs.
+    pub fn key(&self) -> String {
+        format!("{}::{}", self.crate_name, self.machine)
+    }
+
+    /// The figures compared against a baseline, by name.
+    fn metrics(&self) -> [(&'static str, usize); 6] {
+        [
+            ("states", self.states),
+            ("transitions", self.transitions),
+            ("max_depth", self.max_depth),
+            ("parallel_regions", self.parallel_regions),
+            ("guards", self.guards),
+            ("actions", self.actions),
+        ]
+    }
+}
+
+/// Reads every `*.json` file in `dir`, sorted by chart.
+pub fn load_dir(dir: &Path) -> Result<Vec<ChartStats>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|err| format!("cannot read {}: {err}", dir.display()))?;
+    let mut stats = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| format!("cannot read {}: {err}", dir.display()))?
+            .path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+            stats.push(
+                serde_json::from_str(&text).map_err(|err| format!("{}: {err}", path.display()))?,
+            );
+        }
+    }
+    stats.sort_by_key(ChartStats::key);
+    Ok(stats)
+}
+
+/// Reads a baseline written by `save_baseline`.
+pub fn load_baseline(path: &Path) -> Result<Vec<ChartStats>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+    serde_json::from_str(&text).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Writes `stats` as one JSON array, for a later `--baseline`.
+pub fn save_baseline(path: &Path, stats: &[ChartStats]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(stats).map_err(|err| err.to_string())?;
+    std::fs::write(path, json + "\n")
+        .map_err(|err| format!("cannot write {}: {err}", path.display()))
+}
+
+/// Renders one row per chart.
+pub fn table(stats: &[ChartStats]) -> String {
+    let keys: Vec<String> = stats.iter().map(ChartStats::key).collect();
+    let width = keys
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max("machine".len());
+    let mut out = format!(
+        "{:<width$}  states  transitions  depth  regions  guards  actions  out-degree  hash\n",
+        "machine"
+    );
+    for (key, chart) in keys.iter().zip(stats) {
+        let _ = writeln!(
+            out,
+            "{key:<width$}  {:>6}  {:>11}  {:>5}  {:>7}  {:>6}  {:>7}  {:>10.2}  {}",
+            chart.states,
+            chart.transitions,
+            chart.max_depth,
+            chart.parallel_regions,
+            chart.guards,
+            chart.actions,
+            chart.average_out_degree,
+            chart.definition_hash,
+        );
+    }
+    out
+}
+
+/// A figure that grew by more than the threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jump {
+    pub chart: String,
+    pub metric: &'static str,
+    pub old: usize,
+    pub new: usize,
+}
+
+/// How the current charts differ from a baseline.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Comparison {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Charts whose definition hash changed.
+    pub changed: Vec<String>,
+    pub jumps: Vec<Jump>,
+}
+
+/// Compares `current` with `baseline`; a figure jumps when it grows by more than
+/// `threshold_percent`, or at all from zero.
+pub fn compare(
+    baseline: &[ChartStats],
+    current: &[ChartStats],
+    threshold_percent: usize,
+) -> Comparison {
+    let mut comparison = Comparison::default();
+    for chart in current {
+        let key = chart.key();
+        let Some(old) = baseline.iter().find(|old| old.key() == key) else {
+            comparison.added.push(key);
+            continue;
+        };
+        if old.definition_hash != chart.definition_hash {
+            comparison.changed.push(key.clone());
+        }
+        for ((metric, old), (_, new)) in old.metrics().into_iter().zip(chart.metrics()) {
+            if new > old && (new - old) * 100 > old * threshold_percent {
+                comparison.jumps.push(Jump {
+                    chart: key.clone(),
+                    metric,
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+    comparison.removed = baseline
+        .iter()
+        .map(ChartStats::key)
+        .filter(|key| !current.iter().any(|chart| chart.key() == *key))
+        .collect();
+    comparison
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chart in &self.added {
+            writeln!(f, "+ {chart} (new)")?;
+        }
+        for chart in &self.removed {
+            writeln!(f, "- {chart} (removed)")?;
+        }
+        for chart in &self.changed {
+            writeln!(f, "~ {chart} (definition changed)")?;
+        }
+        for jump in &self.jumps {
+            writeln!(
+                f,
+                "! {}: {} {} -> {}",
+                jump.chart, jump.metric, jump.old, jump.new
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixtures() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/stats")
+    }
+
+    #[test]
+    fn charts_are_read_sorted_and_tabulated() {
+        let stats = load_dir(&fixtures().join("current")).unwrap();
+        let keys: Vec<_> = stats.iter().map(ChartStats::key).collect();
+        assert_eq!(keys, ["door::Door", "pump::Pump", "pump::Valve"]);
+        assert_eq!(
+            table(&stats),
+            "\
+machine      states  transitions  depth  regions  guards  actions  out-degree  hash
+door::Door        4            5      2        0       2        4        1.25  5d1f0c2e8a7b6433
+pump::Pump        9           16      3        2       5        7        1.78  a04c9e1b2f3d5e67
+pump::Valve       2            2      1        0       0        1        1.00  77e2c1d0b9a85f34
+"
+        );
+    }
+
+    #[test]
+    fn growth_past_the_threshold_is_flagged() {
+        let current = load_dir(&fixtures().join("current")).unwrap();
+        let baseline = load_baseline(&fixtures().join("baseline.json")).unwrap();
+
+        let comparison = compare(&baseline, &current, 25);
+        assert_eq!(comparison.added, ["pump::Valve"]);
+        assert_eq!(comparison.removed, ["pump::Legacy"]);
+        assert_eq!(comparison.changed, ["pump::Pump"]);
+        // States 8 -> 9 is within 25%; transitions 11 -> 16 and the first parallel
+        // regions are not.
+        assert_eq!(
+            comparison.to_string(),
+            "\
++ pump::Valve (new)
+- pump::Legacy (removed)
+~ pump::Pump (definition changed)
+! pump::Pump: transitions 11 -> 16
+! pump::Pump: parallel_regions 0 -> 2
+"
+        );
+        assert_eq!(compare(&baseline, &current, 100).jumps.len(), 1);
+    }
+
+    #[test]
+    fn a_saved_baseline_reads_back_unchanged() {
+        let current = load_dir(&fixtures().join("current")).unwrap();
+        let path = std::env::temp_dir().join(format!("lit-bit-stats-{}.json", std::process::id()));
+        save_baseline(&path, &current).unwrap();
+        let reread = load_baseline(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(reread, current);
+        assert_eq!(compare(&reread, &current, 0), Comparison::default());
+    }
+}
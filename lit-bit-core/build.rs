@@ -27,6 +27,10 @@ fn main() {
         } else {
             panic!("Error: Source script {source_path} not found for target {target}");
         }
+        // defmt keeps its interned strings in a linker section of its own.
+        if env::var_os("CARGO_FEATURE_DEFMT").is_some() {
+            println!("cargo:rustc-link-arg-examples=-Tdefmt.x");
+        }
     }
 
     println!("cargo:rustc-link-search={}", out_dir.display());
@@ -16,12 +16,6 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
-#[cfg(feature = "std")]
-use std::vec::Vec;
-
-#[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
-
 // Dummy allocator for no_std builds
 #[cfg(not(feature = "std"))]
 #[global_allocator]
@@ -268,9 +262,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n🔗 Simulating connection lifecycle...");
 
     // Connect some clients
-    connection_manager.send(&ConnectionEvent::Connect { client_id: 1 });
-    connection_manager.send(&ConnectionEvent::Connect { client_id: 2 });
-    connection_manager.send(&ConnectionEvent::Connect { client_id: 3 });
+    let _ = connection_manager.send(&ConnectionEvent::Connect { client_id: 1 });
+    let _ = connection_manager.send(&ConnectionEvent::Connect { client_id: 2 });
+    let _ = connection_manager.send(&ConnectionEvent::Connect { client_id: 3 });
 
     println!("State after connections: {:?}", connection_manager.state());
     println!(
@@ -279,11 +273,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Simulate heartbeats
-    connection_manager.send(&ConnectionEvent::Heartbeat { client_id: 1 });
-    connection_manager.send(&ConnectionEvent::Heartbeat { client_id: 2 });
+    let _ = connection_manager.send(&ConnectionEvent::Heartbeat { client_id: 1 });
+    let _ = connection_manager.send(&ConnectionEvent::Heartbeat { client_id: 2 });
 
     // Disconnect a client
-    connection_manager.send(&ConnectionEvent::Disconnect { client_id: 2 });
+    let _ = connection_manager.send(&ConnectionEvent::Disconnect { client_id: 2 });
     println!(
         "Active connections after disconnect: {}",
         connection_manager.context().active_connections.len()
@@ -291,9 +285,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Simulate network errors
     println!("\n🚨 Simulating network errors...");
-    connection_manager.send(&ConnectionEvent::NetworkError);
-    connection_manager.send(&ConnectionEvent::NetworkError);
-    connection_manager.send(&ConnectionEvent::NetworkError); // This should trigger error recovery
+    let _ = connection_manager.send(&ConnectionEvent::NetworkError);
+    let _ = connection_manager.send(&ConnectionEvent::NetworkError);
+    let _ = connection_manager.send(&ConnectionEvent::NetworkError); // This should trigger error recovery
 
     println!("State after errors: {:?}", connection_manager.state());
     println!(
@@ -303,12 +297,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Recover by connecting again
     println!("\n🔄 Recovering from errors...");
-    connection_manager.send(&ConnectionEvent::Connect { client_id: 4 });
+    let _ = connection_manager.send(&ConnectionEvent::Connect { client_id: 4 });
     println!("State after recovery: {:?}", connection_manager.state());
 
     // Shutdown
     println!("\n🛑 Shutting down...");
-    connection_manager.send(&ConnectionEvent::Shutdown);
+    let _ = connection_manager.send(&ConnectionEvent::Shutdown);
     println!("Final state: {:?}", connection_manager.state());
 
     println!("\n✅ StateMachine-Actor integration example completed!");
@@ -334,9 +328,9 @@ fn main() {
             .expect("Failed to create connection manager");
 
     // Simulate some basic operations
-    connection_manager.send(&ConnectionEvent::Connect { client_id: 1 });
-    connection_manager.send(&ConnectionEvent::Heartbeat { client_id: 1 });
-    connection_manager.send(&ConnectionEvent::Disconnect { client_id: 1 });
+    let _ = connection_manager.send(&ConnectionEvent::Connect { client_id: 1 });
+    let _ = connection_manager.send(&ConnectionEvent::Heartbeat { client_id: 1 });
+    let _ = connection_manager.send(&ConnectionEvent::Disconnect { client_id: 1 });
 
     // In a real embedded application, this would be part of a larger event loop
 }
@@ -415,12 +409,12 @@ mod tests {
                 .unwrap();
 
         // Connect first
-        manager.send(&ConnectionEvent::Connect { client_id: 1 });
+        let _ = manager.send(&ConnectionEvent::Connect { client_id: 1 });
 
         // Trigger multiple network errors
-        manager.send(&ConnectionEvent::NetworkError);
-        manager.send(&ConnectionEvent::NetworkError);
-        manager.send(&ConnectionEvent::NetworkError);
+        let _ = manager.send(&ConnectionEvent::NetworkError);
+        let _ = manager.send(&ConnectionEvent::NetworkError);
+        let _ = manager.send(&ConnectionEvent::NetworkError);
 
         // Should be in error recovery state
         // Note: In a complete test, we'd verify the exact state
@@ -0,0 +1,27 @@
+//! Embassy thermostat: a statechart, actors and timers on one Embassy executor.
+//!
+//! A `Thermostat` chart (Idle, Heating, Cooling, Off) runs inside an actor fed by a
+//! sensor that samples the room once a second, and switches off once the sensor is done. Heating and Cooling are each cut off by an
+//! `after` timer: the chart arms a `TimerToken` on entry, a timer task sleeps on it and
+//! hands it back to the thermostat, and the chart drops it if the state was left in the
+//! meantime. A supervisor restarts the sensor with backoff whenever it fails on an
+//! injected ADC timeout.
+//!
+//! The executor runs on the host with a virtual-time driver: whenever every task is
+//! waiting, time jumps to the next deadline, so the minute-long run finishes at once and
+//! prints the same log every time. On a board, the same tasks run under the HAL's
+//! executor and time driver instead.
+//!
+//! Run it with:
+//!
+//! ```sh
+//! cargo run -p lit-bit-core --example embassy_thermostat --features embassy
+//! ```
+
+mod thermostat;
+
+fn main() {
+    for line in thermostat::run() {
+        println!("{line}");
+    }
+}
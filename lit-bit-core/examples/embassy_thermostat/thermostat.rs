@@ -0,0 +1,431 @@
+//! The thermostat assembly shared by the `embassy_thermostat` example and its integration
+//! test: the chart, the actors and tasks around it, and a host executor on virtual time.
+
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use core::task::Waker;
+use std::cell::RefCell;
+use std::string::String;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::vec::Vec;
+
+use embassy_executor::{Spawner, raw};
+use embassy_sync::blocking_mutex::raw::{CriticalSectionRawMutex, NoopRawMutex};
+use embassy_sync::channel::{Channel, Receiver};
+use embassy_time::{Duration, Instant, Ticker, Timer};
+use lit_bit_core::actor::address::Address;
+use lit_bit_core::actor::{
+    Actor, ActorError, RestartStrategy, SupervisorActor, actor_task_embassy,
+};
+use lit_bit_core::{SendResult, StateMachine, TimerToken, static_embassy_channel};
+use lit_bit_macro::statechart;
+use static_cell::StaticCell;
+
+/// Temperatures are in tenths of a degree Celsius.
+const HEAT_BELOW: i16 = 190;
+const HEATED: i16 = 210;
+const COOL_ABOVE: i16 = 250;
+const COOLED: i16 = 230;
+
+/// How often the sensor samples the room.
+const SAMPLE_PERIOD: Duration = Duration::from_secs(1);
+/// Sensor restarts the supervisor allows within a minute before giving up.
+const MAX_RESTARTS: usize = 3;
+/// A run that has not finished by then is stuck.
+const TIME_LIMIT: Duration = Duration::from_secs(120);
+const MAILBOX: usize = 8;
+
+/// What the sensor reads, one entry per sample. `None` is an ADC timeout: the sensor
+/// fails and has to be restarted.
+const SCRIPT: &[Option<i16>] = &[
+    Some(200),
+    Some(185),
+    Some(190),
+    None,
+    Some(196),
+    Some(201),
+    Some(204),
+    Some(206),
+    Some(212),
+    Some(240),
+    Some(256),
+    Some(258),
+    None,
+    Some(245),
+    Some(236),
+    Some(229),
+    Some(222),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Climate {
+    Boot,
+    Reading(i16),
+    Shutdown,
+    TimerFired {
+        state_id: ThermostatStateId,
+        timer_id: usize,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Hvac;
+
+fn too_cold(_: &Hvac, event: &Climate) -> bool {
+    matches!(event, Climate::Reading(t) if *t < HEAT_BELOW)
+}
+
+fn too_warm(_: &Hvac, event: &Climate) -> bool {
+    matches!(event, Climate::Reading(t) if *t > COOL_ABOVE)
+}
+
+fn heated(_: &Hvac, event: &Climate) -> bool {
+    matches!(event, Climate::Reading(t) if *t >= HEATED)
+}
+
+fn cooled(_: &Hvac, event: &Climate) -> bool {
+    matches!(event, Climate::Reading(t) if *t <= COOLED)
+}
+
+// The heater and the compressor each run at most six seconds at a stretch, and everything
+// is switched off once the sensor has no more readings.
+statechart! {
+    name: Thermostat,
+    context: Hvac,
+    event: Climate,
+    initial: Idle,
+    state Idle {
+        on Climate::Reading(_) [guard too_cold] => Heating;
+        on Climate::Reading(_) [guard too_warm] => Cooling;
+        on Climate::Shutdown => Off;
+    }
+    state Heating {
+        on Climate::Reading(_) [guard heated] => Idle;
+        on Climate::Shutdown => Off;
+        after(6000) => Idle;
+    }
+    state Cooling {
+        on Climate::Reading(_) [guard cooled] => Idle;
+        on Climate::Shutdown => Off;
+        after(6000) => Idle;
+    }
+    state Off {}
+}
+
+static LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn log(line: String) {
+    let line = format!("{:>6} ms  {line}", Instant::now().as_millis());
+    LOG.lock().unwrap().push(line);
+}
+
+fn celsius(tenths: i16) -> String {
+    format!("{}.{} °C", tenths / 10, tenths % 10)
+}
+
+// ---------------------------------------------------------------------------
+// Virtual time and the host executor
+// ---------------------------------------------------------------------------
+
+/// Time only moves when the executor has nothing left to poll: it then jumps straight to
+/// the earliest deadline any task waits for.
+struct VirtualClock {
+    now: AtomicU64,
+    wakers: Mutex<Vec<(u64, Waker)>>,
+}
+
+impl VirtualClock {
+    /// Moves to the earliest registered deadline and wakes everything due by then.
+    /// Returns `false` when no task waits for time.
+    fn advance(&self) -> bool {
+        let mut wakers = self.wakers.lock().unwrap();
+        let Some(at) = wakers.iter().map(|(at, _)| *at).min() else {
+            return false;
+        };
+        self.now.fetch_max(at, Ordering::SeqCst);
+        let (due, waiting) = wakers.drain(..).partition(|(deadline, _)| *deadline <= at);
+        *wakers = waiting;
+        drop(wakers);
+        for (_, waker) in due {
+            waker.wake();
+        }
+        true
+    }
+}
+
+impl embassy_time_driver::Driver for VirtualClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        if at <= self.now() {
+            waker.wake_by_ref();
+        } else {
+            self.wakers.lock().unwrap().push((at, waker.clone()));
+        }
+    }
+}
+
+embassy_time_driver::time_driver_impl!(static CLOCK: VirtualClock = VirtualClock {
+    now: AtomicU64::new(0),
+    wakers: Mutex::new(Vec::new()),
+});
+
+/// Set whenever a task is woken; the run loop polls the executor until it is clear.
+static WORK: AtomicBool = AtomicBool::new(false);
+/// Set by the thermostat once the sensor has delivered its last reading.
+static FINISHED: AtomicBool = AtomicBool::new(false);
+
+#[unsafe(export_name = "__pender")]
+fn pender(_context: *mut ()) {
+    WORK.store(true, Ordering::SeqCst);
+}
+
+/// Critical sections for `CriticalSectionRawMutex`: a process-wide lock the thread holding
+/// it may take again. A board gets them from its HAL or `cortex-m` instead.
+struct HostCriticalSection;
+critical_section::set_impl!(HostCriticalSection);
+
+static CRITICAL: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    /// How deeply this thread is nested in critical sections, and the lock while it is.
+    static HELD: RefCell<(usize, Option<MutexGuard<'static, ()>>)> =
+        const { RefCell::new((0, None)) };
+}
+
+// SAFETY: `acquire` blocks until no other thread is in a critical section, and the lock
+// stays held until the outermost `release` on this thread.
+unsafe impl critical_section::Impl for HostCriticalSection {
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        HELD.with_borrow_mut(|(depth, lock)| {
+            if *depth == 0 {
+                *lock = Some(CRITICAL.lock().unwrap_or_else(PoisonError::into_inner));
+            }
+            *depth += 1;
+        });
+    }
+
+    unsafe fn release(_: critical_section::RawRestoreState) {
+        HELD.with_borrow_mut(|(depth, lock)| {
+            *depth -= 1;
+            if *depth == 0 {
+                *lock = None;
+            }
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Thermostat actor and its timer tasks
+// ---------------------------------------------------------------------------
+
+pub enum ThermostatMsg {
+    Reading(i16),
+    Expired(TimerToken),
+    SensorDone,
+}
+
+/// Tokens of the timers the chart armed, handed from the thermostat to `timer_task`; the
+/// actor has to be `Send`, so it cannot hold a spawner itself.
+static ARMED: Channel<CriticalSectionRawMutex, TimerToken, 2> = Channel::new();
+
+struct ThermostatActor {
+    machine: Thermostat,
+}
+
+impl ThermostatActor {
+    fn step(&mut self, event: Climate) {
+        let from = self.machine.state()[0];
+        let result = self.machine.send(&event);
+        self.settle(from, result, format!("on {event:?}"));
+    }
+
+    /// Delivers an expired timer; the chart ignores it if its state was left meanwhile.
+    fn expire(&mut self, token: TimerToken) {
+        let from = self.machine.state()[0];
+        match self.machine.fire_timer(token) {
+            SendResult::NoMatch => log("stale timer ignored".into()),
+            result => self.settle(
+                from,
+                result,
+                format!("after {} ms", token.duration().as_millis()),
+            ),
+        }
+    }
+
+    fn settle(&mut self, from: ThermostatStateId, result: SendResult, cause: String) {
+        match result {
+            SendResult::Transitioned => {
+                log(format!("{from:?} -> {:?} {cause}", self.machine.state()[0]));
+                self.spawn_timers();
+            }
+            SendResult::NoMatch => {}
+            other => log(format!("{cause} rejected: {other:?}")),
+        }
+    }
+
+    /// Hands every timer the chart armed on the last step to `timer_task`.
+    fn spawn_timers(&self) {
+        for token in self.machine.entered_timers() {
+            ARMED.try_send(token).expect("the timer task keeps up");
+        }
+    }
+}
+
+impl Actor for ThermostatActor {
+    type Message = ThermostatMsg;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, msg: ThermostatMsg) -> Self::Future<'_> {
+        match msg {
+            ThermostatMsg::Reading(t) => self.step(Climate::Reading(t)),
+            ThermostatMsg::Expired(token) => self.expire(token),
+            ThermostatMsg::SensorDone => {
+                log("sensor done".into());
+                self.step(Climate::Shutdown);
+                FINISHED.store(true, Ordering::SeqCst);
+            }
+        }
+        core::future::ready(())
+    }
+}
+
+#[embassy_executor::task]
+async fn thermostat_task(
+    actor: ThermostatActor,
+    mailbox: Receiver<'static, NoopRawMutex, ThermostatMsg, MAILBOX>,
+) {
+    let _ = actor_task_embassy(actor, mailbox).await;
+}
+
+/// Starts a `sleep_task` for every armed timer. `statechart!` only generates the tasks
+/// that sleep on a chart's timers for Tokio (`spawn_timers`); on Embassy they are spawned
+/// by hand, as here.
+#[embassy_executor::task]
+async fn timer_task(spawner: Spawner, thermostat: Address<ThermostatMsg, MAILBOX>) {
+    loop {
+        let token = ARMED.receive().await;
+        if spawner
+            .spawn(sleep_task(token, thermostat.clone()))
+            .is_err()
+        {
+            log("no sleep task free".into());
+        }
+    }
+}
+
+/// Sleeps for the duration of `token` and reports its expiry to the thermostat. One `after`
+/// state is active at a time; the second slot is for a timer whose state was left before
+/// it ran out.
+#[embassy_executor::task(pool_size = 2)]
+async fn sleep_task(token: TimerToken, thermostat: Address<ThermostatMsg, MAILBOX>) {
+    let micros = u64::try_from(token.duration().as_micros()).unwrap_or(u64::MAX);
+    Timer::after(Duration::from_micros(micros)).await;
+    let _ = thermostat.send(ThermostatMsg::Expired(token)).await;
+}
+
+// ---------------------------------------------------------------------------
+// Sensor and its supervisor
+// ---------------------------------------------------------------------------
+
+/// One incarnation of the sensor: samples the room every `SAMPLE_PERIOD` until the
+/// script runs out, or fails on an ADC timeout.
+struct Sensor<'a> {
+    room: &'a mut core::slice::Iter<'static, Option<i16>>,
+    thermostat: &'a Address<ThermostatMsg, MAILBOX>,
+}
+
+impl Sensor<'_> {
+    async fn run(self) -> Result<(), ActorError> {
+        let mut ticker = Ticker::every(SAMPLE_PERIOD);
+        loop {
+            ticker.next().await;
+            match self.room.next() {
+                None => return Ok(()),
+                Some(None) => return Err(ActorError::Custom("ADC timeout")),
+                Some(Some(t)) => {
+                    log(format!("sensor reads {}", celsius(*t)));
+                    let _ = self.thermostat.send(ThermostatMsg::Reading(*t)).await;
+                }
+            }
+        }
+    }
+}
+
+const SENSOR: u32 = 1;
+
+#[embassy_executor::task]
+async fn sensor_supervisor_task(thermostat: Address<ThermostatMsg, MAILBOX>) {
+    let mut supervisor: SupervisorActor<u32, 2> =
+        SupervisorActor::with_config(RestartStrategy::OneForOne, MAX_RESTARTS, 60_000);
+    supervisor
+        .add_child(SENSOR, None)
+        .expect("the supervisor has room for the sensor");
+    let mut room = SCRIPT.iter();
+    loop {
+        let sensor = Sensor {
+            room: &mut room,
+            thermostat: &thermostat,
+        };
+        let Err(error) = sensor.run().await else {
+            break;
+        };
+        log(format!("sensor failed: {error:?}"));
+        if supervisor.handle_child_failure(&SENSOR).is_none() {
+            log("supervisor gives up on the sensor".into());
+            break;
+        }
+        let backoff = supervisor.calculate_backoff_delay(&SENSOR);
+        log(format!(
+            "supervisor restarts the sensor in {} ms",
+            backoff.as_millis()
+        ));
+        Timer::after(Duration::from_millis(backoff.as_millis() as u64)).await;
+    }
+    let _ = thermostat.send(ThermostatMsg::SensorDone).await;
+}
+
+/// Runs the assembly on virtual time until the sensor's script is used up and the
+/// thermostat has handled every reading, then returns the log.
+///
+/// # Panics
+///
+/// If called twice in one process (the executor and channels are statics), or if the run
+/// exceeds `TIME_LIMIT` of virtual time.
+pub fn run() -> Vec<String> {
+    static EXECUTOR: StaticCell<raw::Executor> = StaticCell::new();
+    let executor: &'static raw::Executor = EXECUTOR.init(raw::Executor::new(core::ptr::null_mut()));
+    let spawner = executor.spawner();
+
+    let (sender, mailbox) = static_embassy_channel!(THERMOSTAT_MAILBOX: ThermostatMsg, MAILBOX);
+    let thermostat = Address::from_embassy_sender(sender);
+    let machine = Thermostat::new(Hvac, &Climate::Boot).expect("thermostat starts");
+    log(format!("thermostat starts {:?}", machine.state()[0]));
+
+    let actor = ThermostatActor { machine };
+    actor.spawn_timers();
+    spawner.must_spawn(thermostat_task(actor, mailbox));
+    spawner.must_spawn(timer_task(spawner, thermostat.clone()));
+    spawner.must_spawn(sensor_supervisor_task(thermostat));
+
+    while !FINISHED.load(Ordering::SeqCst) {
+        if WORK.swap(false, Ordering::SeqCst) {
+            // SAFETY: polled from this thread only, never reentrantly.
+            unsafe { executor.poll() };
+        } else {
+            assert!(
+                CLOCK.advance(),
+                "every task waits on something other than time"
+            );
+            assert!(
+                Instant::now().as_ticks() <= TIME_LIMIT.as_ticks(),
+                "run is stuck"
+            );
+        }
+    }
+    core::mem::take(&mut *LOG.lock().unwrap())
+}
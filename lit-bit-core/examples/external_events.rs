@@ -137,6 +137,22 @@ fn process_external_event(
             #[cfg(feature = "std")]
             eprintln!("  -> Error processing event: {e:?}");
         }
+        lit_bit_core::SendResult::BudgetExhausted { remaining_internal } => {
+            #[cfg(feature = "std")]
+            println!("  -> Event deferred: {remaining_internal} internal events pending");
+        }
+        lit_bit_core::SendResult::InvariantViolated(violation) => {
+            #[cfg(feature = "std")]
+            eprintln!("  -> Invariant violated: {violation}");
+        }
+        lit_bit_core::SendResult::PolicyRejected => {
+            #[cfg(feature = "std")]
+            println!("  -> Vetoed by the transition policy");
+        }
+        lit_bit_core::SendResult::OscillationDetected => {
+            #[cfg(feature = "std")]
+            println!("  -> Oscillation detected");
+        }
     }
 }
 
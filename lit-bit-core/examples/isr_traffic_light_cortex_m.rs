@@ -0,0 +1,125 @@
+//! Feeding statechart events from an interrupt handler on Cortex-M.
+//!
+//! The SysTick exception pushes `Tick` events into the traffic light actor's mailbox through
+//! an `IsrSender` (lock-free, single producer); the main loop drains the mailbox and hands
+//! each event to the actor. After a few full light cycles the example exits QEMU through
+//! semihosting.
+//!
+//! Run with:
+//! ```sh
+//! cargo run --example isr_traffic_light_cortex_m --target thumbv7m-none-eabi \
+//!     --features panic-halt,critical-section
+//! ```
+//! using a QEMU runner such as
+//! `qemu-system-arm -cpu cortex-m3 -machine lm3s6965evb -nographic -semihosting-config enable=on,target=native -kernel`.
+
+#![cfg_attr(target_arch = "arm", no_std)]
+#![cfg_attr(target_arch = "arm", no_main)]
+
+#[cfg(target_arch = "arm")]
+mod cortex_m_logic {
+    use core::cell::RefCell;
+
+    use cortex_m::peripheral::syst::SystClkSource;
+    use cortex_m_rt::{entry, exception};
+    use cortex_m_semihosting::{debug, hprintln};
+    use panic_halt as _;
+
+    use lit_bit_core::actor::{Actor, IsrSender, StatechartActor};
+    use lit_bit_core::{statechart, static_mailbox};
+
+    #[derive(Debug, Clone, Default)]
+    pub struct LightContext {
+        cycles: u32,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum LightEvent {
+        Tick,
+    }
+
+    fn count_cycle(ctx: &mut LightContext, _event: &LightEvent) {
+        ctx.cycles += 1;
+    }
+
+    statechart! {
+        name: TrafficLight,
+        context: LightContext,
+        event: LightEvent,
+        initial: Red,
+        state Red {
+            entry: count_cycle;
+            on LightEvent::Tick => Green;
+        }
+        state Green {
+            on LightEvent::Tick => Yellow;
+        }
+        state Yellow {
+            on LightEvent::Tick => Red;
+        }
+    }
+
+    const MAILBOX_CAPACITY: usize = 8;
+    const CYCLES_TO_RUN: u32 = 3;
+
+    /// One-time handoff of the ISR sender from `main` to the SysTick handler.
+    static TICK_SENDER: critical_section::Mutex<
+        RefCell<Option<IsrSender<LightEvent, MAILBOX_CAPACITY>>>,
+    > = critical_section::Mutex::new(RefCell::new(None));
+
+    #[entry]
+    fn main() -> ! {
+        let (tick_tx, mut inbox) = static_mailbox!(TICK_MAILBOX: LightEvent, MAILBOX_CAPACITY; isr);
+        critical_section::with(|cs| TICK_SENDER.borrow_ref_mut(cs).replace(tick_tx));
+
+        let machine = TrafficLight::new(LightContext::default(), &LightEvent::Tick)
+            .expect("Failed to create traffic light");
+        let mut actor: StatechartActor<TrafficLight> = StatechartActor::new(machine);
+
+        let mut core = cortex_m::Peripherals::take().expect("Peripherals already taken");
+        core.SYST.set_clock_source(SystClkSource::Core);
+        core.SYST.set_reload(120_000); // ~10 ms at 12 MHz
+        core.SYST.clear_current();
+        core.SYST.enable_counter();
+        core.SYST.enable_interrupt();
+
+        loop {
+            while let Some(event) = inbox.dequeue() {
+                // The statechart actor processes the event synchronously; its future is
+                // already complete when returned.
+                drop(actor.handle(event));
+                let light = match actor.machine().current() {
+                    TrafficLightStateId::Red => "red",
+                    TrafficLightStateId::Green => "green",
+                    TrafficLightStateId::Yellow => "yellow",
+                };
+                hprintln!("light: {}", light);
+            }
+            if actor.machine().context().cycles > CYCLES_TO_RUN {
+                hprintln!("completed {} cycles", CYCLES_TO_RUN);
+                debug::exit(debug::EXIT_SUCCESS);
+            }
+            cortex_m::asm::wfi();
+        }
+    }
+
+    #[exception]
+    fn SysTick() {
+        static mut SENDER: Option<IsrSender<LightEvent, MAILBOX_CAPACITY>> = None;
+
+        // Take the sender once; every later tick sends without any locking.
+        if SENDER.is_none() {
+            *SENDER = critical_section::with(|cs| TICK_SENDER.borrow_ref_mut(cs).take());
+        }
+        if let Some(sender) = SENDER.as_mut() {
+            // A full mailbox means the main loop is behind; dropping a tick is acceptable.
+            let _ = sender.send_from_isr(LightEvent::Tick);
+        }
+    }
+}
+
+// Dummy main for non-ARM targets.
+#[cfg(not(target_arch = "arm"))]
+fn main() {
+    println!("This isr_traffic_light_cortex_m example is intended for target_arch = \"arm\".");
+}
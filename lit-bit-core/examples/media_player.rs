@@ -306,29 +306,29 @@ fn main() {
 
     // Demonstrate parallel region independence
     println!("\n🎵 Loading and playing a track...");
-    player.send(&MediaPlayerEvent::LoadTrack {
+    let _ = player.send(&MediaPlayerEvent::LoadTrack {
         path: String::try_from("awesome_song.mp3").unwrap(),
     });
-    player.send(&MediaPlayerEvent::Play);
+    let _ = player.send(&MediaPlayerEvent::Play);
 
     println!("\n🔊 Adjusting audio while playing...");
-    player.send(&MediaPlayerEvent::VolumeUp);
-    player.send(&MediaPlayerEvent::VolumeUp);
-    player.send(&MediaPlayerEvent::ToggleMute);
+    let _ = player.send(&MediaPlayerEvent::VolumeUp);
+    let _ = player.send(&MediaPlayerEvent::VolumeUp);
+    let _ = player.send(&MediaPlayerEvent::ToggleMute);
 
     println!("\n💡 Controlling display independently...");
-    player.send(&MediaPlayerEvent::BrightnessDown);
-    player.send(&MediaPlayerEvent::ScreenToggle);
+    let _ = player.send(&MediaPlayerEvent::BrightnessDown);
+    let _ = player.send(&MediaPlayerEvent::ScreenToggle);
 
     println!("\n📊 Current State (Playing + Muted + ScreenOff):");
     println!("Active states: {:?}", player.state());
 
     println!("\n🔄 Unmuting and turning screen back on...");
-    player.send(&MediaPlayerEvent::ToggleMute);
-    player.send(&MediaPlayerEvent::ScreenToggle);
+    let _ = player.send(&MediaPlayerEvent::ToggleMute);
+    let _ = player.send(&MediaPlayerEvent::ScreenToggle);
 
     println!("\n⏸️  Pausing playback (audio/display unaffected)...");
-    player.send(&MediaPlayerEvent::Pause);
+    let _ = player.send(&MediaPlayerEvent::Pause);
 
     println!("\n📊 Final State (Paused + Normal + ScreenOn):");
     println!("Active states: {:?}", player.state());
@@ -344,7 +344,7 @@ fn main() {
     }
 
     println!("\n⚡ Testing global power off...");
-    player.send(&MediaPlayerEvent::PowerOff);
+    let _ = player.send(&MediaPlayerEvent::PowerOff);
     println!("Active states after power off: {:?}", player.state());
 
     println!("\n✅ Parallel states demo complete!");
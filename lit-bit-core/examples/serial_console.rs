@@ -0,0 +1,116 @@
+//! Example: A Diagnostic Console over a Serial Line
+//!
+//! A field engineer's terminal sends `fsm ...` commands over a UART. The firmware collects
+//! received bytes into a line buffer and hands each complete line to a `MachineConsole`,
+//! which writes its reply straight back to the UART through `core::fmt::Write`. Nothing
+//! here allocates; the mock UART below stands in for the HAL's serial driver and prints
+//! what it transmits so the example runs on the host.
+
+use core::fmt::Write;
+
+use lit_bit_core::console::MachineConsole;
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PumpEvent {
+    Boot,
+    Prime,
+    SetFlow(u8),
+    Fault,
+    Reset,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Pump {
+    pub flow: u8,
+}
+
+fn set_flow(ctx: &mut Pump, event: &PumpEvent) {
+    if let PumpEvent::SetFlow(flow) = event {
+        ctx.flow = *flow;
+    }
+}
+
+statechart! {
+    name: PumpMachine,
+    context: Pump,
+    event: PumpEvent,
+    initial: Standby,
+    state Standby {
+        on Prime => Active;
+    }
+    state Active {
+        initial: Priming;
+        on Fault => Lockout;
+        on SetFlow(_) => Active::Pumping [action set_flow];
+        state Priming {}
+        state Pumping {}
+    }
+    state Lockout {
+        on Reset => Standby;
+    }
+}
+
+/// The app-specific part of the console: event names and payload syntax.
+fn parse_event(text: &str) -> Option<PumpEvent> {
+    match text {
+        "prime" => Some(PumpEvent::Prime),
+        "fault" => Some(PumpEvent::Fault),
+        "reset" => Some(PumpEvent::Reset),
+        _ => text
+            .strip_prefix("flow ")?
+            .parse()
+            .ok()
+            .map(PumpEvent::SetFlow),
+    }
+}
+
+/// Stand-in for a HAL serial port: `rx` is what the terminal will type, and transmitted
+/// bytes go to stdout.
+struct MockUart {
+    rx: core::slice::Iter<'static, u8>,
+}
+
+impl MockUart {
+    fn read_byte(&mut self) -> Option<u8> {
+        self.rx.next().copied()
+    }
+}
+
+impl Write for MockUart {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        // A real driver would block on the TX FIFO here.
+        print!("{}", s.replace("\r\n", "\n"));
+        Ok(())
+    }
+}
+
+fn main() {
+    println!("=== Serial Console Example ===");
+    let mut pump = PumpMachine::new(Pump::default(), &PumpEvent::Boot).expect("pump starts");
+    let mut console =
+        MachineConsole::<_, 4>::new(&mut pump, PumpMachineStateId::user_path, parse_event);
+
+    let mut uart = MockUart {
+        rx: b"fsm state\rfsm send prime\rfsm send flow 30\rfsm state\rfsm send flow x\r\
+              fsm send fault\rfsm trace\rstatus\r"
+            .iter(),
+    };
+
+    // The RX loop of the firmware: echo, collect a line, run it.
+    let mut line: heapless::String<64> = heapless::String::new();
+    while let Some(byte) = uart.read_byte() {
+        if byte != b'\r' {
+            // Overlong lines are truncated; the console then rejects them.
+            let _ = line.push(char::from(byte));
+            continue;
+        }
+        let _ = write!(uart, "> {line}\r\n");
+        if let Err(error) = console.handle_line(&line, &mut uart) {
+            let _ = write!(uart, "error: {error}\r\n");
+        }
+        line.clear();
+    }
+
+    println!("flow setting: {}", console.machine().context().flow);
+}
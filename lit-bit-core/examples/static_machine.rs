@@ -0,0 +1,91 @@
+//! Example: A Machine in a `static`
+//!
+//! RTIC resources and other `static`s must be initialized at compile time, so a machine
+//! cannot be built with `new`, which runs entry actions. `const_new` builds it without
+//! entering the initial state; `init` then calls `start` once, and the tasks share the
+//! machine through the mutex. `std::sync::Mutex` stands in for the RTIC resource lock
+//! here so the example runs on the host.
+
+use std::sync::Mutex;
+
+use lit_bit_core::StateMachine;
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PumpEvent {
+    Boot,
+    PressureLow,
+    PressureOk,
+    Fault,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pump {
+    pub cycles: u32,
+}
+
+impl Pump {
+    pub const IDLE: Self = Self { cycles: 0 };
+}
+
+fn enter_standby(_ctx: &mut Pump, event: &PumpEvent) {
+    println!("  [entry] Standby (on {event:?})");
+}
+
+fn enter_pumping(ctx: &mut Pump, _event: &PumpEvent) {
+    ctx.cycles += 1;
+    println!("  [entry] Pumping, cycle {}", ctx.cycles);
+}
+
+fn enter_lockout(_ctx: &mut Pump, _event: &PumpEvent) {
+    println!("  [entry] Lockout");
+}
+
+statechart! {
+    name: PumpMachine,
+    context: Pump,
+    event: PumpEvent,
+    initial: Standby,
+    state Standby {
+        entry: enter_standby;
+        on PumpEvent::PressureLow => Pumping;
+        on PumpEvent::Fault => Lockout;
+    }
+    state Pumping {
+        entry: enter_pumping;
+        on PumpEvent::PressureOk => Standby;
+        on PumpEvent::Fault => Lockout;
+    }
+    state Lockout {
+        entry: enter_lockout;
+    }
+}
+
+// The equivalent of an RTIC `#[shared]` resource: built at compile time, nothing has run.
+static PUMP: Mutex<PumpMachine> = Mutex::new(PumpMachine::const_new(Pump::IDLE));
+
+/// RTIC `#[init]`: enters the initial state exactly once.
+fn init() {
+    let mut pump = PUMP.lock().unwrap();
+    println!("init: started = {}", pump.is_started());
+    pump.start(&PumpEvent::Boot)
+        .expect("initial state is valid");
+    println!("init: started = {}", pump.is_started());
+}
+
+/// A hardware task reacting to the pressure sensor.
+fn pressure_sensor_task(event: PumpEvent) {
+    let mut pump = PUMP.lock().unwrap();
+    let result = pump.send(&event);
+    println!("sensor: {event:?} -> {result:?}, now {:?}", pump.state()[0]);
+}
+
+fn main() {
+    println!("=== Machine in a static ===");
+    init();
+    pressure_sensor_task(PumpEvent::PressureLow);
+    pressure_sensor_task(PumpEvent::PressureOk);
+    pressure_sensor_task(PumpEvent::PressureLow);
+    pressure_sensor_task(PumpEvent::Fault);
+    println!("pump ran {} cycles", PUMP.lock().unwrap().context().cycles);
+}
@@ -0,0 +1,131 @@
+//! Drives a `SupervisorActor` through a child failure on a Cortex-M target.
+//!
+//! Built with the `defmt` feature, the supervisor's message flow shows up on the defmt
+//! timeline; `cargo xtask size-report` builds it with and without the feature to check
+//! what the instrumentation costs in flash. The logger below forwards defmt frames over
+//! semihosting, so under QEMU they can be decoded with `defmt-print`.
+
+#![cfg_attr(target_arch = "arm", no_std)]
+#![cfg_attr(target_arch = "arm", no_main)]
+
+// Supervision pulls in `alloc` for restart factories; this example registers none, so
+// nothing is ever allocated.
+#[cfg(target_arch = "arm")]
+#[global_allocator]
+static DUMMY: DummyAlloc = DummyAlloc;
+
+#[cfg(target_arch = "arm")]
+struct DummyAlloc;
+
+#[cfg(target_arch = "arm")]
+unsafe impl core::alloc::GlobalAlloc for DummyAlloc {
+    unsafe fn alloc(&self, _layout: core::alloc::Layout) -> *mut u8 {
+        panic!("DummyAlloc: heap allocation attempted in no_std context")
+    }
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: core::alloc::Layout) {}
+}
+
+#[cfg(all(target_arch = "arm", feature = "defmt"))]
+mod semihosting_logger {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    #[defmt::global_logger]
+    struct Logger;
+
+    static TAKEN: AtomicBool = AtomicBool::new(false);
+    static mut ENCODER: defmt::Encoder = defmt::Encoder::new();
+
+    fn write_frame_bytes(bytes: &[u8]) {
+        if let Ok(mut stdout) = cortex_m_semihosting::hio::hstdout() {
+            let _ = stdout.write_all(bytes);
+        }
+    }
+
+    // SAFETY: the example is single-threaded and never logs from an interrupt, so
+    // `TAKEN` is enough to keep the encoder to one frame at a time.
+    unsafe impl defmt::Logger for Logger {
+        fn acquire() {
+            assert!(
+                !TAKEN.swap(true, Ordering::Acquire),
+                "defmt logger acquired twice"
+            );
+            // SAFETY: exclusive access is guarded by `TAKEN`.
+            unsafe { (*(&raw mut ENCODER)).start_frame(write_frame_bytes) }
+        }
+
+        unsafe fn flush() {}
+
+        unsafe fn release() {
+            // SAFETY: called after `acquire`, which holds `TAKEN`.
+            unsafe { (*(&raw mut ENCODER)).end_frame(write_frame_bytes) }
+            TAKEN.store(false, Ordering::Release);
+        }
+
+        unsafe fn write(bytes: &[u8]) {
+            // SAFETY: called between `acquire` and `release`.
+            unsafe { (*(&raw mut ENCODER)).write(bytes, write_frame_bytes) }
+        }
+    }
+}
+
+#[cfg(target_arch = "arm")]
+mod cortex_m_logic {
+    use core::pin::pin;
+    use core::task::{Context, Waker};
+
+    use cortex_m_rt::entry;
+    use cortex_m_semihosting::debug;
+    use lit_bit_core::actor::{ActorError, ActorId, SupervisorActor, SupervisorMessage};
+    use lit_bit_core::{Actor, RestartStrategy};
+    use panic_halt as _;
+
+    const SENSOR: ActorId = ActorId::new(1);
+    const LOGGER: ActorId = ActorId::new(2);
+
+    /// Handles one message; the supervisor's futures are always ready.
+    fn deliver(supervisor: &mut SupervisorActor<ActorId, 4>, msg: SupervisorMessage<ActorId>) {
+        let mut future = pin!(supervisor.handle(msg));
+        let mut cx = Context::from_waker(Waker::noop());
+        while future.as_mut().poll(&mut cx).is_pending() {}
+    }
+
+    #[entry]
+    fn main_cortex_m_entry() -> ! {
+        let mut supervisor: SupervisorActor<ActorId, 4> =
+            SupervisorActor::with_config(RestartStrategy::OneForOne, 2, 1_000);
+
+        deliver(
+            &mut supervisor,
+            SupervisorMessage::StartChild { id: SENSOR },
+        );
+        deliver(
+            &mut supervisor,
+            SupervisorMessage::StartChild { id: LOGGER },
+        );
+        deliver(
+            &mut supervisor,
+            SupervisorMessage::ChildStarted { id: SENSOR },
+        );
+        for _ in 0..3 {
+            deliver(
+                &mut supervisor,
+                SupervisorMessage::ChildPanicked {
+                    id: SENSOR,
+                    error: ActorError::Timeout,
+                },
+            );
+        }
+        deliver(&mut supervisor, SupervisorMessage::StopChild { id: LOGGER });
+
+        debug::exit(debug::EXIT_SUCCESS);
+        loop {
+            cortex_m::asm::nop();
+        }
+    }
+}
+
+// Dummy main for non-ARM targets.
+#[cfg(not(target_arch = "arm"))]
+fn main() {
+    println!("This supervision_cortex_m example is intended for target_arch = \"arm\".");
+}
@@ -106,6 +106,10 @@ mod riscv_logic {
             action: None,
             guard: None,
             match_fn: Some(matches_timer_elapsed),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: TrafficLightState::Green,
@@ -113,6 +117,10 @@ mod riscv_logic {
             action: None,
             guard: None,
             match_fn: Some(matches_timer_elapsed),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: TrafficLightState::Yellow,
@@ -120,6 +128,10 @@ mod riscv_logic {
             action: Some(increment_cycle as ActionFn<TrafficLightContext, TrafficLightEvent>),
             guard: None,
             match_fn: Some(matches_timer_elapsed),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
     ];
 
@@ -135,6 +147,7 @@ mod riscv_logic {
             entry_action: Some(log_red as ActionFn<TrafficLightContext, TrafficLightEvent>),
             exit_action: None,
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: TrafficLightState::Green,
@@ -143,6 +156,7 @@ mod riscv_logic {
             entry_action: Some(log_green as ActionFn<TrafficLightContext, TrafficLightEvent>),
             exit_action: None,
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: TrafficLightState::Yellow,
@@ -151,6 +165,7 @@ mod riscv_logic {
             entry_action: Some(log_yellow as ActionFn<TrafficLightContext, TrafficLightEvent>),
             exit_action: None,
             is_parallel: false,
+            is_final: false,
         },
     ];
 
@@ -208,6 +223,18 @@ mod riscv_logic {
                 SendResult::Error(_) => unsafe {
                     uart_print_str("UART: ERROR during transition!\n");
                 },
+                SendResult::BudgetExhausted { .. } => unsafe {
+                    uart_print_str("UART: Transition deferred.\n");
+                },
+                SendResult::InvariantViolated(_) => unsafe {
+                    uart_print_str("UART: Invariant violated!\n");
+                },
+                SendResult::PolicyRejected => unsafe {
+                    uart_print_str("UART: Transition vetoed by policy.\n");
+                },
+                SendResult::OscillationDetected => unsafe {
+                    uart_print_str("UART: Oscillation detected!\n");
+                },
             }
         }
 
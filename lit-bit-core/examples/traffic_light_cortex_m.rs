@@ -71,6 +71,10 @@ mod cortex_m_logic {
             action: None,
             guard: None,
             match_fn: Some(matches_toggle),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: LightState::On,
@@ -78,6 +82,10 @@ mod cortex_m_logic {
             action: None,
             guard: None,
             match_fn: Some(matches_toggle),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
     ];
 
@@ -90,6 +98,7 @@ mod cortex_m_logic {
             entry_action: None,
             exit_action: None,
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: LightState::On,
@@ -98,6 +107,7 @@ mod cortex_m_logic {
             entry_action: None,
             exit_action: None,
             is_parallel: false,
+            is_final: false,
         },
     ];
 
@@ -140,6 +150,18 @@ mod cortex_m_logic {
                 // Runtime error occurred - in a real application this might
                 // trigger a system reset or enter a safe mode
             }
+            SendResult::BudgetExhausted { .. } => {
+                // Only returned by budgeted sends
+            }
+            SendResult::InvariantViolated(_) => {
+                // Only returned by charts with an `invariant`
+            }
+            SendResult::PolicyRejected => {
+                // Only returned with a transition policy attached
+            }
+            SendResult::OscillationDetected => {
+                // Only returned with an oscillation guard attached
+            }
         }
 
         match runtime.send(&LightEvent::Toggle) {
@@ -152,6 +174,18 @@ mod cortex_m_logic {
             SendResult::Error(_e) => {
                 // Runtime error occurred
             }
+            SendResult::BudgetExhausted { .. } => {
+                // Only returned by budgeted sends
+            }
+            SendResult::InvariantViolated(_) => {
+                // Only returned by charts with an `invariant`
+            }
+            SendResult::PolicyRejected => {
+                // Only returned with a transition policy attached
+            }
+            SendResult::OscillationDetected => {
+                // Only returned with an oscillation guard attached
+            }
         }
 
         let _ = runtime.state();
@@ -0,0 +1,185 @@
+//! Batch sizes that follow the load of a Tokio batch actor.
+//!
+//! A fixed [`max_batch_size`](super::BatchActor::max_batch_size) settles the trade-off between
+//! latency and throughput once: small batches keep each message's wait short, large ones
+//! amortise wake-ups under load. [`spawn_batch_actor_tokio_adaptive`] lets the actor loop
+//! pick the drain limit as it goes instead, within [`AdaptiveBatching`] bounds:
+//!
+//! - a batch that hit the limit while more messages were still queued doubles it;
+//! - a batch that emptied the mailbox at half the limit or less halves it.
+//!
+//! The limit starts at the lower bound, so an idle actor answers its first messages one
+//! small batch at a time. [`ActorMetrics`] shows the limit the loop is using.
+//!
+//! [`spawn_batch_actor_tokio_adaptive`]: super::spawn::spawn_batch_actor_tokio_adaptive
+
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Bounds for an adaptive drain limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdaptiveBatching {
+    min: usize,
+    max: usize,
+}
+
+impl AdaptiveBatching {
+    /// Keeps the drain limit between `min` and `max`. A `min` of 0 is raised to 1, and a
+    /// `max` below `min` to `min`.
+    #[must_use]
+    pub const fn new(min: usize, max: usize) -> Self {
+        let min = if min == 0 { 1 } else { min };
+        let max = if max < min { min } else { max };
+        Self { min, max }
+    }
+
+    /// Returns the smallest drain limit.
+    #[must_use]
+    pub const fn min(&self) -> usize {
+        self.min
+    }
+
+    /// Returns the largest drain limit.
+    #[must_use]
+    pub const fn max(&self) -> usize {
+        self.max
+    }
+}
+
+/// Live figures of a running actor loop, shared with whoever spawned it.
+#[derive(Debug, Default)]
+pub struct ActorMetrics {
+    batch_limit: AtomicUsize,
+    last_batch_size: AtomicUsize,
+    batches: AtomicU64,
+    messages: AtomicU64,
+}
+
+impl ActorMetrics {
+    /// Returns the drain limit the loop will use for its next batch.
+    #[must_use]
+    pub fn batch_limit(&self) -> usize {
+        self.batch_limit.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many messages the last batch held, or 0 before the first one.
+    #[must_use]
+    pub fn last_batch_size(&self) -> usize {
+        self.last_batch_size.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many batches the loop has handled.
+    #[must_use]
+    pub fn batches(&self) -> u64 {
+        self.batches.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many messages the loop has handled, over all batches.
+    #[must_use]
+    pub fn messages(&self) -> u64 {
+        self.messages.load(Ordering::Relaxed)
+    }
+}
+
+/// Picks the drain limit of a batch actor loop; see the [module docs](self).
+#[derive(Debug)]
+pub(crate) struct BatchController {
+    bounds: AdaptiveBatching,
+    limit: usize,
+    metrics: Arc<ActorMetrics>,
+}
+
+impl BatchController {
+    pub(crate) fn new(bounds: AdaptiveBatching, metrics: Arc<ActorMetrics>) -> Self {
+        metrics.batch_limit.store(bounds.min, Ordering::Relaxed);
+        Self {
+            bounds,
+            limit: bounds.min,
+            metrics,
+        }
+    }
+
+    pub(crate) fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Adjusts the limit after a batch of `drained` messages that left `still_queued`
+    /// behind.
+    pub(crate) fn observe(&mut self, drained: usize, still_queued: usize) {
+        if drained >= self.limit && still_queued > 0 {
+            self.limit = self.limit.saturating_mul(2).min(self.bounds.max);
+        } else if still_queued == 0 && drained <= self.limit / 2 {
+            self.limit = (self.limit / 2).max(self.bounds.min);
+        }
+        self.metrics
+            .batch_limit
+            .store(self.limit, Ordering::Relaxed);
+        self.metrics
+            .last_batch_size
+            .store(drained, Ordering::Relaxed);
+        self.metrics.batches.fetch_add(1, Ordering::Relaxed);
+        self.metrics
+            .messages
+            .fetch_add(drained as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller(min: usize, max: usize) -> (BatchController, Arc<ActorMetrics>) {
+        let metrics = Arc::new(ActorMetrics::default());
+        let controller = BatchController::new(AdaptiveBatching::new(min, max), metrics.clone());
+        (controller, metrics)
+    }
+
+    #[test]
+    fn a_backlog_doubles_the_limit_up_to_the_upper_bound() {
+        let (mut controller, metrics) = controller(2, 12);
+        assert_eq!(metrics.batch_limit(), 2);
+
+        for expected in [4, 8, 12, 12] {
+            controller.observe(controller.limit(), 100);
+            assert_eq!(controller.limit(), expected);
+        }
+        assert_eq!(metrics.batch_limit(), 12);
+        assert_eq!(metrics.last_batch_size(), 12);
+        assert_eq!(metrics.batches(), 4);
+        assert_eq!(metrics.messages(), 2 + 4 + 8 + 12);
+    }
+
+    #[test]
+    fn light_load_halves_the_limit_down_to_the_lower_bound() {
+        let (mut controller, metrics) = controller(3, 64);
+        for _ in 0..5 {
+            controller.observe(controller.limit(), 1);
+        }
+        assert_eq!(controller.limit(), 64);
+
+        for expected in [32, 16, 8, 4, 3, 3] {
+            controller.observe(1, 0);
+            assert_eq!(controller.limit(), expected);
+        }
+        assert_eq!(metrics.last_batch_size(), 1);
+    }
+
+    #[test]
+    fn a_batch_that_just_empties_the_mailbox_keeps_the_limit() {
+        let (mut controller, _) = controller(1, 64);
+        controller.observe(1, 5);
+        controller.observe(2, 5);
+        assert_eq!(controller.limit(), 4);
+
+        controller.observe(4, 0);
+        controller.observe(3, 0);
+        assert_eq!(controller.limit(), 4);
+    }
+
+    #[test]
+    fn bounds_are_normalised() {
+        assert_eq!(AdaptiveBatching::new(0, 0), AdaptiveBatching::new(1, 1));
+        let bounds = AdaptiveBatching::new(8, 4);
+        assert_eq!((bounds.min(), bounds.max()), (8, 8));
+    }
+}
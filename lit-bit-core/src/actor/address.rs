@@ -73,6 +73,7 @@ impl<Event: 'static, const N: usize> Address<Event, N> {
     /// // More typical Embassy usage - assume infallible
     /// address.send(MyMessage::DoWork).await.expect("Embassy send should never fail");
     /// ```
+    #[must_use = "check whether the mailbox accepted the event"]
     pub async fn send(&self, event: Event) -> Result<(), SendError<Event>> {
         // Embassy channels are infallible by design - this will never fail
         // unless we add custom "actor alive" checks in the future
@@ -119,6 +120,7 @@ impl<Event: 'static, const N: usize> Address<Event, N> {
     ///     }
     /// }
     /// ```
+    #[must_use = "check whether the mailbox accepted the event"]
     pub fn try_send(&self, event: Event) -> Result<(), SendError<Event>> {
         match self.sender.try_send(event) {
             Ok(()) => Ok(()),
@@ -151,10 +153,11 @@ pub struct ActorCell<Event> {
 #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
 pub struct Address<Event> {
     sender: tokio::sync::mpsc::Sender<Event>,
-    actor_id: usize, // Placeholder for ActorId type
+    actor_id: super::ActorId,
     parent: Option<std::sync::Weak<ActorCell<Event>>>,
     children: std::sync::Arc<std::sync::Mutex<Vec<std::sync::Weak<ActorCell<Event>>>>>,
     cell: std::sync::Arc<ActorCell<Event>>, // For test access
+    links: std::sync::Arc<super::link::LinkCell>,
 }
 
 #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
@@ -172,12 +175,14 @@ impl<Event> Address<Event> {
         capacity: usize,
     ) -> (Self, tokio::sync::mpsc::Receiver<Event>) {
         let (sender, receiver) = tokio::sync::mpsc::channel(capacity);
+        let actor_id = super::ActorId::next();
         let address = Self {
             sender,
-            actor_id: 0,
+            actor_id,
             parent: None,
             children: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
             cell,
+            links: super::link::LinkCell::new(actor_id),
         };
         (address, receiver)
     }
@@ -188,15 +193,55 @@ impl<Event> Address<Event> {
         let cell = std::sync::Arc::new(ActorCell::<Event> {
             _phantom: std::marker::PhantomData,
         });
+        let actor_id = super::ActorId::next();
         Self {
             sender,
-            actor_id: 0,
+            actor_id,
             parent: None,
             children: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
             cell,
+            links: super::link::LinkCell::new(actor_id),
         }
     }
 
+    /// Returns the id assigned to this actor at spawn time.
+    #[must_use]
+    pub fn actor_id(&self) -> super::ActorId {
+        self.actor_id
+    }
+
+    /// Links this actor with `other` in both directions, so that if either terminates
+    /// abnormally the other is notified through
+    /// [`Actor::on_link_exit`](super::Actor::on_link_exit) and, by default, terminates
+    /// too. See the [`link`](super::link) module for how links differ from supervision.
+    ///
+    /// Linking to an actor that has already failed notifies this actor at once; linking
+    /// to one that has stopped cleanly, or to itself, does nothing.
+    pub fn link<Other>(&self, other: &Address<Other>) {
+        super::link::LinkCell::link(&self.links, &other.links);
+    }
+
+    /// Removes the link between this actor and `other`, if there is one.
+    pub fn unlink<Other>(&self, other: &Address<Other>) {
+        super::link::LinkCell::unlink(&self.links, &other.links);
+    }
+
+    /// Asks the actor to stop once it finishes the message it is handling. Messages still
+    /// in its mailbox are dropped, and
+    /// [`Actor::on_stop_with_reason`](super::Actor::on_stop_with_reason) is passed
+    /// [`StopReason::StopRequested`](super::StopReason::StopRequested).
+    ///
+    /// Only the loops that deliver link exits honour the request; see the
+    /// [`link`](super::link) module. Asking an actor that has already stopped does nothing.
+    pub fn stop(&self) {
+        self.links.request_stop(super::StopReason::StopRequested);
+    }
+
+    /// Returns the link bookkeeping the actor task shares with this address.
+    pub(crate) fn link_cell(&self) -> std::sync::Arc<super::link::LinkCell> {
+        self.links.clone()
+    }
+
     /// Returns a reference to the parent Weak pointer, if any.
     #[must_use]
     pub fn parent(&self) -> Option<&std::sync::Weak<ActorCell<Event>>> {
@@ -223,6 +268,7 @@ impl<Event> Address<Event> {
     ///
     /// # Errors
     /// Returns `SendError::Closed(msg)` if the receiver has been dropped.
+    #[must_use = "check whether the mailbox accepted the event"]
     pub async fn send(&self, event: Event) -> Result<(), SendError<Event>> {
         self.sender
             .send(event)
@@ -230,11 +276,49 @@ impl<Event> Address<Event> {
             .map_err(|err| SendError::Closed(err.0))
     }
 
+    /// Sends the message `request` builds around a reply channel, and waits for the
+    /// actor to answer on it.
+    ///
+    /// Returns `None` if the actor has stopped, or drops the reply channel without
+    /// answering.
+    pub async fn ask<R>(
+        &self,
+        request: impl FnOnce(tokio::sync::oneshot::Sender<R>) -> Event,
+    ) -> Option<R> {
+        let (reply, answer) = tokio::sync::oneshot::channel();
+        self.send(request(reply)).await.ok()?;
+        answer.await.ok()
+    }
+
+    /// Send a message from synchronous code, blocking the calling thread while the
+    /// mailbox is full.
+    ///
+    /// Meant for threads the runtime does not drive: plain `std::thread`s, rayon workers,
+    /// FFI callbacks and `spawn_blocking` closures. From async code, blocking would stall a
+    /// runtime worker (Tokio's own `blocking_send` panics there), so the message is handed
+    /// back instead; use [`send`](Self::send) there, or [`try_send`](Self::try_send) when
+    /// the caller cannot await and may drop or retry the message if the mailbox is full.
+    ///
+    /// # Errors
+    /// Returns `SendError::Closed(msg)` if the receiver has been dropped.
+    /// Returns `SendError::WouldBlockInAsyncContext(msg)` if called from async code running
+    /// on a Tokio runtime.
+    #[must_use = "check whether the mailbox accepted the event"]
+    pub fn send_blocking(&self, event: Event) -> Result<(), SendError<Event>> {
+        if in_async_context() {
+            return Err(SendError::WouldBlockInAsyncContext(event));
+        }
+        self.sender
+            .blocking_send(event)
+            .map_err(|err| SendError::Closed(err.0))
+    }
+
     /// Try to send a message without blocking.
     ///
     /// # Errors
     /// Returns `SendError::Full(msg)` if the mailbox is full.
     /// Returns `SendError::Closed(msg)` if the receiver has been dropped.
+    #[must_use = "check whether the mailbox accepted the event"]
     pub fn try_send(&self, event: Event) -> Result<(), SendError<Event>> {
         match self.sender.try_send(event) {
             Ok(()) => Ok(()),
@@ -245,6 +329,19 @@ impl<Event> Address<Event> {
         }
     }
 
+    /// Turns this address into a [`futures::Sink`] that waits for mailbox capacity, so a
+    /// stream can be forwarded into the actor with `stream.forward(addr.into_sink())`.
+    ///
+    /// See [`AddressSink`](super::sink::AddressSink) for ordering and for what happens once
+    /// the actor has stopped.
+    #[must_use]
+    pub fn into_sink(self) -> super::sink::AddressSink<Event>
+    where
+        Event: Send + 'static,
+    {
+        super::sink::AddressSink::new(self.sender)
+    }
+
     /// Spawns a child actor, linking parent and child.
     ///
     /// Returns both the child Address and the receiver end of the channel.
@@ -275,6 +372,41 @@ impl<Event> Address<Event> {
     }
 }
 
+/// Clones share the actor's mailbox and identity, so a clone can be stored wherever the
+/// actor needs to be reached from, such as a statechart context.
+/// Whether the calling thread is running async code on a Tokio runtime, where Tokio's
+/// blocking calls panic.
+///
+/// A current runtime handle alone does not tell: `spawn_blocking` threads have one too, and
+/// may block. Inside a runtime, a blocking receive on a oneshot whose sender is already
+/// gone returns at once where blocking is allowed; elsewhere Tokio panics before waiting,
+/// and the panic is caught. The panic hook still reports that panic.
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+fn in_async_context() -> bool {
+    if tokio::runtime::Handle::try_current().is_err() {
+        return false;
+    }
+    std::panic::catch_unwind(|| {
+        let (_, receiver) = tokio::sync::oneshot::channel::<()>();
+        let _ = receiver.blocking_recv();
+    })
+    .is_err()
+}
+
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+impl<Event> Clone for Address<Event> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            actor_id: self.actor_id,
+            parent: self.parent.clone(),
+            children: self.children.clone(),
+            cell: self.cell.clone(),
+            links: self.links.clone(),
+        }
+    }
+}
+
 // No-std Address implementation (existing heapless-based)
 #[cfg(all(not(feature = "async-tokio"), not(feature = "async-embassy")))]
 pub struct Address<Event: 'static, const N: usize> {
@@ -297,6 +429,7 @@ impl<Event: 'static, const N: usize> Address<Event, N> {
     ///
     /// # Errors
     /// Returns `SendError::Full(event)` if the mailbox is full.
+    #[must_use = "check whether the mailbox accepted the event"]
     pub fn try_send(&mut self, event: Event) -> Result<(), SendError<Event>> {
         self.sender.enqueue(event).map_err(SendError::Full)
     }
@@ -408,6 +541,7 @@ mod std_hierarchy_tests {
         });
         let (parent_addr, _parent_receiver) = Address::from_cell(parent_cell, 4);
         let (child_addr, mut child_receiver) = parent_addr.spawn_child(4).unwrap();
+        assert_ne!(child_addr.actor_id(), parent_addr.actor_id());
 
         // Test that we can send messages to the child successfully
         assert!(child_addr.try_send(123).is_ok());
@@ -14,6 +14,9 @@ pub enum SendError<T> {
     Full(T),
     /// Receiver has been dropped (both platforms)
     Closed(T),
+    /// A blocking send was made from async code on a Tokio runtime, where it would stall
+    /// the worker thread (std only)
+    WouldBlockInAsyncContext(T),
 }
 
 impl<T> core::fmt::Display for SendError<T> {
@@ -21,6 +24,9 @@ impl<T> core::fmt::Display for SendError<T> {
         match self {
             SendError::Full(_) => write!(f, "mailbox is full"),
             SendError::Closed(_) => write!(f, "receiver has been dropped"),
+            SendError::WouldBlockInAsyncContext(_) => {
+                write!(f, "blocking send called from an async context")
+            }
         }
     }
 }
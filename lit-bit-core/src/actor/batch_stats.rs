@@ -0,0 +1,257 @@
+//! Queue-drain statistics for batch actors.
+//!
+//! A [`BatchActor`](super::BatchActor) that returns a recorder from
+//! [`batch_stats`](super::BatchActor::batch_stats) has every batch recorded by its
+//! `batch_actor_task` loop: the batch size (in power-of-two buckets), the number of
+//! wake-ups and messages, and how long draining the mailbox took. [`BatchStats`] is a
+//! snapshot of those counters, and [`BatchStats::suggest_batch_size`] turns it into a
+//! `max_batch_size` for the next run.
+//!
+//! The recorder only uses 32-bit atomic loads and stores, so it can live in a `static`
+//! even on targets without compare-and-swap (e.g. `thumbv6m`). It expects a single writer:
+//! give each actor its own recorder. On Tokio, [`spawn_batch_actor_tokio_with_stats`] attaches a
+//! fresh recorder to any batch actor and returns it as a shared handle.
+//!
+//! [`spawn_batch_actor_tokio_with_stats`]: super::spawn::spawn_batch_actor_tokio_with_stats
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Number of batch-size buckets: `1`, `2..=3`, `4..=7`, ..., `64..=127`, `128..`.
+pub const BATCH_SIZE_BUCKETS: usize = 8;
+
+/// Largest batch size [`BatchStats::suggest_batch_size`] recommends on this platform.
+#[cfg(feature = "async-tokio")]
+pub const PLATFORM_MAX_BATCH_SIZE: usize = 256;
+
+/// Largest batch size [`BatchStats::suggest_batch_size`] recommends on this platform.
+#[cfg(not(feature = "async-tokio"))]
+pub const PLATFORM_MAX_BATCH_SIZE: usize = 32;
+
+/// Counters updated by a batch actor loop; see the [module docs](self).
+#[derive(Debug, Default)]
+pub struct BatchStatsRecorder {
+    histogram: [AtomicU32; BATCH_SIZE_BUCKETS],
+    wakeups: AtomicU32,
+    messages: AtomicU32,
+    timed_wakeups: AtomicU32,
+    /// Total drain time in nanoseconds, split so that only 32-bit atomics are needed.
+    drain_nanos_low: AtomicU32,
+    drain_nanos_high: AtomicU32,
+}
+
+impl BatchStatsRecorder {
+    /// Creates a recorder with all counters at zero.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            histogram: [const { AtomicU32::new(0) }; BATCH_SIZE_BUCKETS],
+            wakeups: AtomicU32::new(0),
+            messages: AtomicU32::new(0),
+            timed_wakeups: AtomicU32::new(0),
+            drain_nanos_low: AtomicU32::new(0),
+            drain_nanos_high: AtomicU32::new(0),
+        }
+    }
+
+    /// Records one wake-up that drained `batch_size` messages in `drain` (`None` when the
+    /// platform has no clock). Counters wrap on overflow.
+    pub fn record(&self, batch_size: usize, drain: Option<core::time::Duration>) {
+        bump(&self.histogram[bucket_index(batch_size)], 1);
+        bump(&self.wakeups, 1);
+        bump(
+            &self.messages,
+            u32::try_from(batch_size).unwrap_or(u32::MAX),
+        );
+        if let Some(drain) = drain {
+            bump(&self.timed_wakeups, 1);
+            let nanos = self
+                .drain_nanos()
+                .wrapping_add(u64::try_from(drain.as_nanos()).unwrap_or(u64::MAX));
+            self.drain_nanos_low.store(nanos as u32, Ordering::Relaxed);
+            self.drain_nanos_high
+                .store((nanos >> 32) as u32, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the current counters.
+    ///
+    /// The counters are read one at a time, so a snapshot taken while the actor runs may
+    /// be off by the batch being recorded.
+    #[must_use]
+    pub fn snapshot(&self) -> BatchStats {
+        BatchStats {
+            histogram: core::array::from_fn(|i| self.histogram[i].load(Ordering::Relaxed)),
+            wakeups: self.wakeups.load(Ordering::Relaxed),
+            messages: self.messages.load(Ordering::Relaxed),
+            timed_wakeups: self.timed_wakeups.load(Ordering::Relaxed),
+            drain_nanos: self.drain_nanos(),
+        }
+    }
+
+    fn drain_nanos(&self) -> u64 {
+        u64::from(self.drain_nanos_high.load(Ordering::Relaxed)) << 32
+            | u64::from(self.drain_nanos_low.load(Ordering::Relaxed))
+    }
+
+    /// Sets all counters back to zero, e.g. after applying a suggested batch size.
+    pub fn reset(&self) {
+        for bucket in &self.histogram {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.wakeups.store(0, Ordering::Relaxed);
+        self.messages.store(0, Ordering::Relaxed);
+        self.timed_wakeups.store(0, Ordering::Relaxed);
+        self.drain_nanos_low.store(0, Ordering::Relaxed);
+        self.drain_nanos_high.store(0, Ordering::Relaxed);
+    }
+}
+
+/// A snapshot of a [`BatchStatsRecorder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchStats {
+    /// Wake-ups per batch-size bucket; bucket `i` counts batches of `2^i..2^(i+1)`
+    /// messages, and the last bucket everything larger.
+    pub histogram: [u32; BATCH_SIZE_BUCKETS],
+    /// Number of times the loop woke up and handled a batch.
+    pub wakeups: u32,
+    /// Total number of messages handled.
+    pub messages: u32,
+    timed_wakeups: u32,
+    drain_nanos: u64,
+}
+
+impl BatchStats {
+    /// Returns how often the actor woke up for a single message.
+    #[must_use]
+    pub const fn single_message_wakeups(&self) -> u32 {
+        self.histogram[0]
+    }
+
+    /// Returns the largest batch size in the bucket that holds the `percent`-th percentile
+    /// of observed batches, or `None` if nothing was recorded. The last bucket reports
+    /// [`PLATFORM_MAX_BATCH_SIZE`].
+    #[must_use]
+    pub fn percentile_batch_size(&self, percent: u8) -> Option<usize> {
+        let total: u64 = self.histogram.iter().map(|&count| u64::from(count)).sum();
+        if total == 0 {
+            return None;
+        }
+        let rank = (total * u64::from(percent.min(100))).div_ceil(100).max(1);
+        let mut seen = 0;
+        let bucket = self
+            .histogram
+            .iter()
+            .position(|&count| {
+                seen += u64::from(count);
+                seen >= rank
+            })
+            .unwrap_or(BATCH_SIZE_BUCKETS - 1);
+        Some(bucket_upper_bound(bucket))
+    }
+
+    /// Returns the average time spent draining the mailbox per wake-up, or `None` when no
+    /// wake-up was timed (e.g. on `no_std` without Embassy).
+    #[must_use]
+    pub fn average_drain_latency(&self) -> Option<core::time::Duration> {
+        if self.timed_wakeups == 0 {
+            return None;
+        }
+        Some(core::time::Duration::from_nanos(
+            self.drain_nanos / u64::from(self.timed_wakeups),
+        ))
+    }
+
+    /// Suggests a `max_batch_size`: the 95th percentile of observed batch sizes, clamped
+    /// to `1..=`[`PLATFORM_MAX_BATCH_SIZE`].
+    ///
+    /// Batches that were cut off at the current `max_batch_size` land in a bucket whose
+    /// upper bound is above it, so an actor that keeps filling its batches gets a larger
+    /// suggestion. With nothing recorded, the [`BatchActor`](super::BatchActor) default of
+    /// 32 is returned (clamped likewise).
+    #[must_use]
+    pub fn suggest_batch_size(&self) -> usize {
+        self.percentile_batch_size(95)
+            .unwrap_or(32)
+            .clamp(1, PLATFORM_MAX_BATCH_SIZE)
+    }
+}
+
+/// Adds `amount` to a counter that only the recording loop writes.
+fn bump(counter: &AtomicU32, amount: u32) {
+    counter.store(
+        counter.load(Ordering::Relaxed).wrapping_add(amount),
+        Ordering::Relaxed,
+    );
+}
+
+fn bucket_index(batch_size: usize) -> usize {
+    (batch_size.max(1).ilog2() as usize).min(BATCH_SIZE_BUCKETS - 1)
+}
+
+fn bucket_upper_bound(bucket: usize) -> usize {
+    if bucket == BATCH_SIZE_BUCKETS - 1 {
+        PLATFORM_MAX_BATCH_SIZE
+    } else {
+        (1 << (bucket + 1)) - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+
+    #[test]
+    fn batch_sizes_land_in_power_of_two_buckets() {
+        let recorder = BatchStatsRecorder::new();
+        for size in [1, 2, 3, 4, 7, 8, 127, 128, 1000] {
+            recorder.record(size, None);
+        }
+
+        let stats = recorder.snapshot();
+        assert_eq!(stats.histogram, [1, 2, 2, 1, 0, 0, 1, 2]);
+        assert_eq!(stats.wakeups, 9);
+        assert_eq!(stats.messages, 1 + 2 + 3 + 4 + 7 + 8 + 127 + 128 + 1000);
+        assert_eq!(stats.single_message_wakeups(), 1);
+        assert_eq!(stats.average_drain_latency(), None);
+    }
+
+    #[test]
+    fn suggestion_follows_the_95th_percentile() {
+        let recorder = BatchStatsRecorder::new();
+        for _ in 0..95 {
+            recorder.record(5, Some(Duration::from_micros(10)));
+        }
+        for _ in 0..5 {
+            recorder.record(20, Some(Duration::from_micros(30)));
+        }
+
+        let stats = recorder.snapshot();
+        assert_eq!(stats.percentile_batch_size(95), Some(7));
+        assert_eq!(stats.percentile_batch_size(100), Some(31));
+        assert_eq!(stats.suggest_batch_size(), 7);
+        assert_eq!(
+            stats.average_drain_latency(),
+            Some(Duration::from_micros(11))
+        );
+    }
+
+    #[test]
+    fn suggestion_is_clamped_to_the_platform_maximum() {
+        assert_eq!(BatchStats::default().percentile_batch_size(95), None);
+        assert_eq!(
+            BatchStats::default().suggest_batch_size(),
+            32.min(PLATFORM_MAX_BATCH_SIZE)
+        );
+
+        let recorder = BatchStatsRecorder::new();
+        recorder.record(500, None);
+        assert_eq!(
+            recorder.snapshot().suggest_batch_size(),
+            PLATFORM_MAX_BATCH_SIZE
+        );
+
+        recorder.reset();
+        assert_eq!(recorder.snapshot(), BatchStats::default());
+    }
+}
@@ -0,0 +1,295 @@
+//! Time-sliced actions for cooperative executors.
+//!
+//! Statechart actions are synchronous, so an action that runs for tens of milliseconds
+//! (erasing flash, say) holds the executor thread for all of that time: on Embassy no
+//! other task runs until it returns. Until actions can be `async`, a long action is written
+//! as a small state machine of its own. The context keeps the action's progress; the
+//! action does the first slice of the work and calls [`Yielder::checkpoint`]; each call of
+//! [`Cooperative::resume`] does the next slice and checkpoints again while work remains.
+//!
+//! [`CooperativeActor`] runs the machine and drives the slices: after the event has been
+//! sent it yields to the executor (the way `embassy_futures::yield_now` does) before every
+//! `resume`, so other tasks run between slices. The actor finishes the whole action before
+//! it takes its next message, so run-to-completion holds for the machine's own events.
+//!
+//! ```rust,ignore
+//! struct Storage {
+//!     yielder: Yielder,
+//!     next_sector: u8,
+//! }
+//!
+//! impl Cooperative for Storage {
+//!     fn yielder(&mut self) -> &mut Yielder {
+//!         &mut self.yielder
+//!     }
+//!
+//!     fn resume(&mut self) {
+//!         flash::erase_sector(self.next_sector); // ~10 ms
+//!         self.next_sector += 1;
+//!         if self.next_sector < SECTORS {
+//!             self.yielder.checkpoint();
+//!         }
+//!     }
+//! }
+//!
+//! fn start_erase(storage: &mut Storage, _event: &Command) {
+//!     storage.next_sector = 0;
+//!     storage.resume();
+//! }
+//!
+//! let actor = CooperativeActor::new(StatechartActor::new(Flasher::new(storage, &Command::Boot)?));
+//! ```
+//!
+//! A machine driven with plain `send` leaves a raised checkpoint in place; call
+//! [`finish`] to run the remaining slices back to back.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use super::{Actor, ActorError, StatechartActor, StopReason};
+use crate::{MAX_ACTIVE_REGIONS, StateMachine};
+
+/// Flag a long action raises to hand the executor back between two slices of its work.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Yielder {
+    pending: bool,
+}
+
+impl Yielder {
+    /// Returns a yielder with no checkpoint raised.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { pending: false }
+    }
+
+    /// Asks the actor running the machine to yield and then call
+    /// [`Cooperative::resume`] for the next slice.
+    pub fn checkpoint(&mut self) {
+        self.pending = true;
+    }
+
+    /// Returns `true` while a checkpoint is raised and not yet resumed.
+    #[must_use]
+    pub const fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Clears the checkpoint, returning whether one was raised.
+    pub fn take(&mut self) -> bool {
+        core::mem::take(&mut self.pending)
+    }
+}
+
+/// A context whose actions can be split into slices with a [`Yielder`].
+pub trait Cooperative {
+    /// The yielder the context's actions raise checkpoints on.
+    fn yielder(&mut self) -> &mut Yielder;
+
+    /// Runs the next slice of the work the last checkpoint interrupted, raising another
+    /// checkpoint if some remains.
+    fn resume(&mut self);
+}
+
+/// Runs every remaining slice of `context`'s interrupted work without yielding.
+///
+/// Returns how many slices ran.
+pub fn finish<C: Cooperative + ?Sized>(context: &mut C) -> usize {
+    let mut slices = 0;
+    while context.yielder().take() {
+        context.resume();
+        slices += 1;
+    }
+    slices
+}
+
+/// Adapter that runs a [`StatechartActor`] whose context is [`Cooperative`], yielding to
+/// the executor between the slices of a long action.
+///
+/// Messages go through the wrapped actor, so its output and stop-event settings apply.
+pub struct CooperativeActor<M, const N_ACTIVE: usize = MAX_ACTIVE_REGIONS>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    inner: StatechartActor<M, N_ACTIVE>,
+}
+
+impl<M, const N_ACTIVE: usize> CooperativeActor<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE>,
+    M::Context: Cooperative,
+{
+    /// Wraps `actor` so that its long actions are run in slices.
+    #[must_use]
+    pub fn new(actor: StatechartActor<M, N_ACTIVE>) -> Self {
+        Self { inner: actor }
+    }
+
+    /// Returns the wrapped actor.
+    pub fn inner(&self) -> &StatechartActor<M, N_ACTIVE> {
+        &self.inner
+    }
+
+    /// Returns the wrapped actor mutably.
+    pub fn inner_mut(&mut self) -> &mut StatechartActor<M, N_ACTIVE> {
+        &mut self.inner
+    }
+
+    /// Unwraps the adapter, returning the wrapped actor.
+    pub fn into_inner(self) -> StatechartActor<M, N_ACTIVE> {
+        self.inner
+    }
+}
+
+impl<M, const N_ACTIVE: usize> Actor for CooperativeActor<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE> + Send,
+    M::Event: Send,
+    M::Context: Cooperative + Send,
+{
+    type Message = M::Event;
+    type Future<'a>
+        = Slices<'a, M::Context>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, event: Self::Message) -> Self::Future<'_> {
+        // The wrapped actor's future is already complete.
+        drop(Actor::handle(&mut self.inner, event));
+        Slices {
+            context: self.inner.machine_mut().context_mut(),
+            yielded: false,
+        }
+    }
+
+    fn on_stop(self) -> Result<(), ActorError> {
+        self.inner.on_stop()
+    }
+
+    fn on_stop_with_reason(self, reason: StopReason) -> Result<(), ActorError> {
+        self.inner.on_stop_with_reason(reason)
+    }
+}
+
+/// Future that yields to the executor before each slice of a context's interrupted work;
+/// returned by [`CooperativeActor`]'s `handle`.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Slices<'a, C: ?Sized> {
+    context: &'a mut C,
+    yielded: bool,
+}
+
+impl<C: Cooperative + ?Sized> Future for Slices<'_, C> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            self.yielded = false;
+            self.context.resume();
+        }
+        if self.context.yielder().take() {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        Poll::Ready(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SendResult;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Phase {
+        Idle,
+        Erasing,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum Command {
+        Erase,
+    }
+
+    #[derive(Clone, Default)]
+    struct Storage {
+        yielder: Yielder,
+        erased: u8,
+    }
+
+    impl Cooperative for Storage {
+        fn yielder(&mut self) -> &mut Yielder {
+            &mut self.yielder
+        }
+
+        fn resume(&mut self) {
+            self.erased += 1;
+            if self.erased < 3 {
+                self.yielder.checkpoint();
+            }
+        }
+    }
+
+    struct Flasher {
+        phase: Phase,
+        storage: Storage,
+    }
+
+    impl StateMachine for Flasher {
+        type State = Phase;
+        type Event = Command;
+        type Context = Storage;
+
+        fn send(&mut self, _event: &Command) -> SendResult {
+            self.phase = Phase::Erasing;
+            self.storage.resume();
+            SendResult::Transitioned
+        }
+
+        fn state(&self) -> heapless::Vec<Phase, 4> {
+            heapless::Vec::from_slice(&[self.phase]).unwrap()
+        }
+
+        fn context(&self) -> &Storage {
+            &self.storage
+        }
+
+        fn context_mut(&mut self) -> &mut Storage {
+            &mut self.storage
+        }
+    }
+
+    fn flasher() -> Flasher {
+        Flasher {
+            phase: Phase::Idle,
+            storage: Storage::default(),
+        }
+    }
+
+    #[test]
+    fn the_actor_yields_once_between_slices() {
+        let mut actor = CooperativeActor::new(StatechartActor::new(flasher()));
+        let mut cx = Context::from_waker(core::task::Waker::noop());
+        let mut slices = core::pin::pin!(actor.handle(Command::Erase));
+
+        let mut pending = 0;
+        while slices.as_mut().poll(&mut cx).is_pending() {
+            pending += 1;
+        }
+        assert_eq!(pending, 2);
+        assert_eq!(actor.inner().machine().context().erased, 3);
+        assert_eq!(actor.inner().machine().state()[0], Phase::Erasing);
+    }
+
+    #[test]
+    fn finish_runs_the_remaining_slices_back_to_back() {
+        let mut machine = flasher();
+        let _ = machine.send(&Command::Erase);
+        assert!(machine.context().yielder.is_pending());
+
+        assert_eq!(finish(machine.context_mut()), 2);
+        assert_eq!(machine.context().erased, 3);
+        assert!(!machine.context().yielder.is_pending());
+    }
+}
@@ -0,0 +1,245 @@
+//! Correlation IDs that follow one request through a chain of actors.
+//!
+//! A [`Traced`] envelope pairs a message with a `u64` correlation ID. Actors that should
+//! take part in tracing receive `Traced<M>` instead of `M`:
+//!
+//! - **Tokio**: wrap the actor in [`TracedActor`]. Each envelope is unwrapped before the
+//!   inner actor sees it, handling runs inside an `actor.handle` span carrying the
+//!   `correlation` field, and the ID is published as [`CorrelationScope::current`] for the
+//!   duration of the handler. Sends made through
+//!   [`Address::send_correlated`](super::address::Address::send_correlated) from inside the
+//!   handler (including from statechart actions) carry the same ID downstream.
+//! - **no_std / Embassy**: there is no task-local storage, so the scope is passed
+//!   explicitly: take it from the incoming envelope with [`CorrelationScope::of`] and
+//!   [`stamp`](CorrelationScope::stamp) outgoing messages with it.
+//!
+//! ```rust,ignore
+//! // Inside an Embassy actor handling `Traced<Reading>`:
+//! let scope = CorrelationScope::of(&traced);
+//! downstream.try_send(scope.stamp(Alarm::High))?;
+//! ```
+
+/// A message tagged with the correlation ID of the request it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Traced<M> {
+    /// ID shared by every message sent on behalf of the same request.
+    pub correlation: u64,
+    /// The wrapped message.
+    pub msg: M,
+}
+
+impl<M> Traced<M> {
+    /// Tags `msg` with an explicit correlation ID.
+    #[must_use]
+    pub const fn new(correlation: u64, msg: M) -> Self {
+        Self { correlation, msg }
+    }
+
+    /// Tags `msg` with a fresh, process-unique correlation ID, starting a new trace.
+    #[cfg(feature = "async-tokio")]
+    #[must_use]
+    pub fn start(msg: M) -> Self {
+        Self::new(next_correlation(), msg)
+    }
+
+    /// Discards the correlation ID, returning the wrapped message.
+    pub fn into_inner(self) -> M {
+        self.msg
+    }
+}
+
+/// The correlation ID in effect while a traced message is being handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CorrelationScope(u64);
+
+impl CorrelationScope {
+    /// Creates a scope for an explicit correlation ID.
+    #[must_use]
+    pub const fn new(correlation: u64) -> Self {
+        Self(correlation)
+    }
+
+    /// Returns the scope of an incoming envelope (the explicit-passing path on `no_std`).
+    #[must_use]
+    pub const fn of<M>(traced: &Traced<M>) -> Self {
+        Self(traced.correlation)
+    }
+
+    /// Returns the correlation ID of this scope.
+    #[must_use]
+    pub const fn correlation(self) -> u64 {
+        self.0
+    }
+
+    /// Tags an outgoing message with this scope's correlation ID.
+    #[must_use]
+    pub const fn stamp<M>(self, msg: M) -> Traced<M> {
+        Traced::new(self.0, msg)
+    }
+
+    /// Returns the scope of the traced message the current task is handling, if any.
+    ///
+    /// Set by [`TracedActor`] for the duration of each handler call and of the future it
+    /// returns.
+    #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(|scope| *scope).ok()
+    }
+
+    /// Returns the current scope, or starts a new trace when there is none.
+    #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+    #[must_use]
+    pub fn current_or_new() -> Self {
+        Self::current().unwrap_or_else(|| Self(next_correlation()))
+    }
+}
+
+/// Allocates a fresh correlation ID from the global counter.
+#[cfg(feature = "async-tokio")]
+fn next_correlation() -> u64 {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    // Start at 1 so that 0 stays available for caller-chosen sentinels.
+    static NEXT_CORRELATION: AtomicU64 = AtomicU64::new(1);
+    NEXT_CORRELATION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Correlation ID of the current task, for the std tracing instrumentation.
+#[cfg(feature = "std")]
+pub(crate) fn current_correlation() -> Option<u64> {
+    #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+    {
+        CorrelationScope::current().map(CorrelationScope::correlation)
+    }
+    #[cfg(not(all(feature = "async-tokio", not(feature = "async-embassy"))))]
+    {
+        None
+    }
+}
+
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+tokio::task_local! {
+    static CURRENT: CorrelationScope;
+}
+
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+pub use tokio_impl::TracedActor;
+
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+mod tokio_impl {
+    use tokio::task::futures::TaskLocalFuture;
+    use tracing::Instrument;
+    use tracing::instrument::Instrumented;
+
+    use super::{CURRENT, CorrelationScope, Traced};
+    use crate::actor::address::Address;
+    use crate::actor::backpressure::SendError;
+    use crate::actor::{Actor, ActorError, LinkAction, LinkExit, RestartStrategy, StopReason};
+
+    /// Adapter that lets an [`Actor`] receive [`Traced`] messages.
+    ///
+    /// The envelope is unwrapped before the inner actor sees the message. The inner
+    /// `handle` call and the future it returns both run inside an `actor.handle` span
+    /// recording the `correlation` field, with [`CorrelationScope::current`] set to the
+    /// envelope's ID.
+    ///
+    /// ```rust,ignore
+    /// let addr = spawn_actor_tokio(TracedActor::new(worker), 16);
+    /// addr.send(Traced::new(42, Job::Resize)).await?;
+    /// ```
+    pub struct TracedActor<A> {
+        inner: A,
+    }
+
+    impl<A> TracedActor<A> {
+        /// Wraps `inner` so it receives traced messages.
+        #[must_use]
+        pub fn new(inner: A) -> Self {
+            Self { inner }
+        }
+
+        /// Returns the wrapped actor.
+        pub fn inner(&self) -> &A {
+            &self.inner
+        }
+
+        /// Returns the wrapped actor mutably.
+        pub fn inner_mut(&mut self) -> &mut A {
+            &mut self.inner
+        }
+
+        /// Unwraps the adapter, returning the wrapped actor.
+        pub fn into_inner(self) -> A {
+            self.inner
+        }
+    }
+
+    impl<A: Actor> Actor for TracedActor<A> {
+        type Message = Traced<A::Message>;
+        type Future<'a>
+            = TaskLocalFuture<CorrelationScope, Instrumented<A::Future<'a>>>
+        where
+            Self: 'a;
+
+        fn handle(&mut self, traced: Self::Message) -> Self::Future<'_> {
+            let scope = CorrelationScope::of(&traced);
+            let span = tracing::info_span!("actor.handle", correlation = traced.correlation);
+            // Synchronous work done by `handle` itself (statechart actions, `try_send`s)
+            // needs the scope as much as the returned future does.
+            let future = {
+                let _entered = span.enter();
+                CURRENT.sync_scope(scope, || self.inner.handle(traced.msg))
+            };
+            CURRENT.scope(scope, future.instrument(span))
+        }
+
+        fn on_start(&mut self) -> Result<(), ActorError> {
+            self.inner.on_start()
+        }
+
+        fn on_stop(self) -> Result<(), ActorError> {
+            self.inner.on_stop()
+        }
+
+        fn on_stop_with_reason(self, reason: StopReason) -> Result<(), ActorError> {
+            self.inner.on_stop_with_reason(reason)
+        }
+
+        fn on_panic(&self, info: &core::panic::PanicInfo) -> RestartStrategy {
+            self.inner.on_panic(info)
+        }
+
+        fn on_restart(&mut self) -> Result<(), ActorError> {
+            self.inner.on_restart()
+        }
+
+        fn on_link_exit(&mut self, exit: &LinkExit) -> LinkAction {
+            self.inner.on_link_exit(exit)
+        }
+    }
+
+    impl<M> Address<Traced<M>> {
+        /// Sends `msg` tagged with the current correlation ID, starting a new trace when
+        /// called outside a traced handler. Waits for mailbox capacity like
+        /// [`send`](Address::send).
+        ///
+        /// # Errors
+        /// Returns `SendError::Closed(traced)` if the receiver has been dropped.
+        #[must_use = "check whether the mailbox accepted the event"]
+        pub async fn send_correlated(&self, msg: M) -> Result<(), SendError<Traced<M>>> {
+            self.send(CorrelationScope::current_or_new().stamp(msg))
+                .await
+        }
+
+        /// Non-blocking form of [`send_correlated`](Address::send_correlated).
+        ///
+        /// # Errors
+        /// Returns `SendError::Full(traced)` if the mailbox is full.
+        /// Returns `SendError::Closed(traced)` if the receiver has been dropped.
+        #[must_use = "check whether the mailbox accepted the event"]
+        pub fn try_send_correlated(&self, msg: M) -> Result<(), SendError<Traced<M>>> {
+            self.try_send(CorrelationScope::current_or_new().stamp(msg))
+        }
+    }
+}
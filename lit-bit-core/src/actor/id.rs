@@ -0,0 +1,156 @@
+//! Compact actor identifiers for panic capture and supervision.
+//!
+//! `ActorId` is a `Copy` `u32` newtype that travels through `ActorError::Panic`,
+//! `SupervisorMessage` and the spawn helpers instead of an owned string. This keeps
+//! the failure path free of string copies and keeps supervision messages small.
+//!
+//! - **Tokio**: ids are assigned at spawn time from a global atomic counter
+//!   ([`ActorId::next`]), optionally paired with a static name ([`ActorId::named`]).
+//! - **no_std / Embassy**: ids are caller-provided constants ([`ActorId::new`]).
+//!
+//! Human-readable names are looked up in a small registry on `std`
+//! ([`ActorId::name`]); on `no_std` an id is displayed as its raw number.
+
+/// Compact identifier for an actor, used in panic reports and supervision messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ActorId(u32);
+
+impl ActorId {
+    /// Creates an id from a caller-provided constant (the `no_std` assignment path).
+    #[must_use]
+    pub const fn new(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the raw numeric value of this id.
+    #[must_use]
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Allocates a fresh, process-unique id from the global spawn counter.
+    #[cfg(feature = "async-tokio")]
+    #[must_use]
+    pub fn next() -> Self {
+        use core::sync::atomic::{AtomicU32, Ordering};
+
+        // Start at 1 so that 0 stays available for caller-chosen sentinels.
+        static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Allocates a fresh id and records `name` for it in the name registry.
+    #[cfg(feature = "async-tokio")]
+    #[must_use]
+    pub fn named(name: &'static str) -> Self {
+        let id = Self::next();
+        registry::register(id, name);
+        id
+    }
+
+    /// Looks up the human-readable name registered for this id, if any.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn name(self) -> Option<&'static str> {
+        registry::lookup(self)
+    }
+}
+
+impl From<u32> for ActorId {
+    fn from(raw: u32) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<ActorId> for u32 {
+    fn from(id: ActorId) -> Self {
+        id.0
+    }
+}
+
+impl core::fmt::Display for ActorId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        #[cfg(feature = "std")]
+        if let Some(name) = self.name() {
+            return write!(f, "{name}#{}", self.0);
+        }
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ActorId {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "#{=u32}", self.0);
+    }
+}
+
+/// Name registry for `std` builds; names never appear on the failure hot path.
+#[cfg(feature = "std")]
+pub mod registry {
+    use super::ActorId;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    static NAMES: Mutex<BTreeMap<ActorId, &'static str>> = Mutex::new(BTreeMap::new());
+
+    /// Associates `name` with `id`, replacing any previous name.
+    pub fn register(id: ActorId, name: &'static str) {
+        if let Ok(mut names) = NAMES.lock() {
+            names.insert(id, name);
+        }
+    }
+
+    /// Removes the name registered for `id`, returning it if present.
+    pub fn unregister(id: ActorId) -> Option<&'static str> {
+        NAMES.lock().ok().and_then(|mut names| names.remove(&id))
+    }
+
+    /// Returns the name registered for `id`, if any.
+    #[must_use]
+    pub fn lookup(id: ActorId) -> Option<&'static str> {
+        NAMES.lock().ok().and_then(|names| names.get(&id).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::SupervisorMessage;
+
+    #[test]
+    fn actor_id_is_compact() {
+        assert_eq!(core::mem::size_of::<ActorId>(), 4);
+        assert_eq!(core::mem::size_of::<Option<ActorId>>(), 8);
+    }
+
+    #[test]
+    fn supervisor_message_with_actor_id_is_smaller_than_string_ids() {
+        let with_id = core::mem::size_of::<SupervisorMessage<ActorId>>();
+        let with_string = core::mem::size_of::<SupervisorMessage<super::super::ActorString>>();
+        assert!(
+            with_id < with_string,
+            "SupervisorMessage<ActorId> ({with_id} bytes) should be smaller than string ids ({with_string} bytes)"
+        );
+    }
+
+    #[test]
+    fn const_ids_round_trip() {
+        const SENSOR: ActorId = ActorId::new(7);
+        assert_eq!(SENSOR.get(), 7);
+        assert_eq!(u32::from(SENSOR), 7);
+        assert_eq!(ActorId::from(7u32), SENSOR);
+    }
+
+    #[cfg(feature = "async-tokio")]
+    #[test]
+    fn next_ids_are_unique_and_names_resolve() {
+        let a = ActorId::next();
+        let b = ActorId::named("worker");
+        assert_ne!(a, b);
+        assert_eq!(a.name(), None);
+        assert_eq!(b.name(), Some("worker"));
+        assert_eq!(registry::unregister(b), Some("worker"));
+        assert_eq!(b.name(), None);
+    }
+}
@@ -1,9 +1,381 @@
 //! `StateMachine` integration examples showing how to implement Actor for statechart types.
 
-#[cfg(test)]
-use super::Actor;
-#[cfg(test)]
-use crate::{SendResult, StateMachine};
+use core::marker::PhantomData;
+
+use super::{Actor, ActorError, BatchActor};
+use crate::{
+    BatchSendSummary, MAX_ACTIVE_REGIONS, PolicyHost, SendResult, StateMachine, TransitionPolicy,
+};
+
+/// Observer invoked after every event a [`StatechartActor`] feeds into its machine.
+///
+/// Receives the machine (already updated) and the result of the `send`, so it can emit
+/// outputs, forward state changes, or report `SendResult::Error`s and
+/// `SendResult::InvariantViolated`s.
+pub type OutputFn<M> = fn(&M, SendResult);
+
+/// Observer invoked before a [`StatechartActor`] feeds a batch of events into its machine,
+/// with the number of events in the batch.
+pub type BatchStartFn<M> = fn(&M, usize);
+
+/// Observer invoked after a [`StatechartActor`] has fed a batch of events into its machine,
+/// with the summary of the batch.
+pub type BatchEndFn<M, const N_ACTIVE: usize> =
+    fn(&M, &BatchSendSummary<<M as StateMachine<N_ACTIVE>>::State, N_ACTIVE>);
+
+/// Adapter that runs any [`StateMachine`] as an [`Actor`].
+///
+/// Each received message is fed into the embedded machine as an event. An optional
+/// [`OutputFn`] observes the machine after every event. Use
+/// [`spawn_statechart_tokio`](crate::actor::spawn::spawn_statechart_tokio) to run one
+/// on Tokio in a single call.
+///
+/// Run as a [`BatchActor`], each drained batch goes through
+/// [`StateMachine::send_batch`], bracketed by the optional [`BatchStartFn`] and
+/// [`BatchEndFn`] observers. If an [`OutputFn`] is set it still sees every event, so the
+/// batch is sent event by event instead.
+///
+/// On `std`, every processed event is also reported as a `debug` tracing event carrying the
+/// `SendResult` and, when the actor runs inside a
+/// [`TracedActor`](crate::actor::correlation::TracedActor), the `correlation` field.
+///
+/// Given a stop event with [`StatechartActor::with_stop_event`], the actor resets its machine
+/// with it when it stops, so the exit actions and the chart's `exit` hook run; see
+/// [`StateMachine::reset`].
+///
+/// On Tokio, [`StatechartActor::with_queries`] switches the actor's message to
+/// [`StatechartMessage`], which also carries [`StatechartQuery`]s: asks answered with the
+/// machine's active configuration or a snapshot of its context.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let machine = TrafficLight::new(TrafficContext::default(), &TrafficEvent::Timer)?;
+/// let actor = StatechartActor::new(machine).with_output(|m, result| {
+///     println!("{result:?} -> {:?}", m.state());
+/// });
+/// ```
+pub struct StatechartActor<M, const N_ACTIVE: usize = MAX_ACTIVE_REGIONS, Q = EventsOnly>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    machine: M,
+    output: Option<OutputFn<M>>,
+    batch_start: Option<BatchStartFn<M>>,
+    batch_end: Option<BatchEndFn<M, N_ACTIVE>>,
+    stop_event: Option<M::Event>,
+    queries: PhantomData<Q>,
+}
+
+/// Marks a [`StatechartActor`] whose messages are the machine's events.
+pub struct EventsOnly;
+
+/// Marks a [`StatechartActor`] whose messages are [`StatechartMessage`]s, so it also
+/// answers [`StatechartQuery`]s.
+pub struct WithQueries;
+
+impl<M, const N_ACTIVE: usize> StatechartActor<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    /// Wraps `machine` so it can be spawned as an actor.
+    #[must_use]
+    pub fn new(machine: M) -> Self {
+        Self {
+            machine,
+            output: None,
+            batch_start: None,
+            batch_end: None,
+            stop_event: None,
+            queries: PhantomData,
+        }
+    }
+
+    /// Makes the actor take [`StatechartMessage`]s instead of bare events, so that it also
+    /// answers [`StatechartQuery`]s. Queries are answered between events and are not
+    /// reported to the [`OutputFn`].
+    #[must_use]
+    pub fn with_queries(self) -> StatechartActor<M, N_ACTIVE, WithQueries> {
+        StatechartActor {
+            machine: self.machine,
+            output: self.output,
+            batch_start: self.batch_start,
+            batch_end: self.batch_end,
+            stop_event: self.stop_event,
+            queries: PhantomData,
+        }
+    }
+}
+
+impl<M, const N_ACTIVE: usize, Q> StatechartActor<M, N_ACTIVE, Q>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    /// Sets the observer called after every processed event.
+    #[must_use]
+    pub fn with_output(mut self, output: OutputFn<M>) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Sets the observers called before and after every batch of events.
+    #[must_use]
+    pub fn with_batch_observers(
+        mut self,
+        start: BatchStartFn<M>,
+        end: BatchEndFn<M, N_ACTIVE>,
+    ) -> Self {
+        self.batch_start = Some(start);
+        self.batch_end = Some(end);
+        self
+    }
+
+    /// Sets the event the machine is reset with when the actor stops.
+    #[must_use]
+    pub fn with_stop_event(mut self, event: M::Event) -> Self {
+        self.stop_event = Some(event);
+        self
+    }
+
+    /// Attaches a [`TransitionPolicy`] to the embedded machine, or removes it with `None`;
+    /// vetoed events are reported to the [`OutputFn`] as `SendResult::PolicyRejected`.
+    pub fn set_policy(&mut self, policy: Option<&'static dyn TransitionPolicy<M::State, M::Event>>)
+    where
+        M: PolicyHost<M::State, M::Event>,
+    {
+        self.machine.set_policy(policy);
+    }
+
+    /// Returns the embedded machine.
+    pub fn machine(&self) -> &M {
+        &self.machine
+    }
+
+    /// Returns the embedded machine mutably.
+    pub fn machine_mut(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    /// Unwraps the adapter, returning the embedded machine.
+    pub fn into_inner(self) -> M {
+        self.machine
+    }
+
+    /// Feeds `event` into the machine and reports the result.
+    fn process(&mut self, event: &M::Event) {
+        let result = self.machine.send(event);
+        #[cfg(feature = "debug-log")]
+        if let SendResult::Error(error) = result {
+            log::warn!("Statechart actor failed to process {event:?}: {error}");
+        }
+        #[cfg(feature = "std")]
+        tracing::debug!(
+            correlation = super::correlation::current_correlation(),
+            ?result,
+            "statechart event processed"
+        );
+        if let Some(output) = self.output {
+            output(&self.machine, result);
+        }
+    }
+
+    /// Resets the machine with the stop event, if one was set; `false` if the reset failed.
+    fn reset_on_stop(&mut self) -> bool {
+        let event = self.stop_event.take();
+        event.is_none_or(|event| self.machine.reset(&event).is_ok())
+    }
+}
+
+impl<M, const N_ACTIVE: usize> Actor for StatechartActor<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE> + Send,
+    M::Event: Send,
+{
+    type Message = M::Event;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, event: Self::Message) -> Self::Future<'_> {
+        self.process(&event);
+        core::future::ready(())
+    }
+
+    fn on_stop(mut self) -> Result<(), ActorError> {
+        if self.reset_on_stop() {
+            Ok(())
+        } else {
+            Err(ActorError::ShutdownFailure)
+        }
+    }
+}
+
+/// Message of a [`StatechartActor`] built with [`StatechartActor::with_queries`].
+#[cfg(feature = "async-tokio")]
+pub enum StatechartMessage<M, const N_ACTIVE: usize = MAX_ACTIVE_REGIONS>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    /// An event for the machine, handled as a plain `StatechartActor` handles it.
+    Event(M::Event),
+    /// A question about the machine, answered without changing it.
+    Query(StatechartQuery<M, N_ACTIVE>),
+}
+
+/// Asks a running [`StatechartActor`] about its machine; the answer goes back on the
+/// oneshot sender.
+///
+/// Usually sent through [`Address::configuration`](super::address::Address::configuration)
+/// or [`Address::context_snapshot`](super::address::Address::context_snapshot), which wait
+/// for the answer.
+#[cfg(feature = "async-tokio")]
+pub enum StatechartQuery<M, const N_ACTIVE: usize = MAX_ACTIVE_REGIONS>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    /// Answered with the active leaf states, as [`StateMachine::state`] returns them.
+    Configuration(tokio::sync::oneshot::Sender<heapless::Vec<M::State, N_ACTIVE>>),
+    /// Answered with a clone of the machine's context.
+    Context(tokio::sync::oneshot::Sender<M::Context>),
+}
+
+#[cfg(feature = "async-tokio")]
+impl<M, const N_ACTIVE: usize> core::fmt::Debug for StatechartMessage<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Event(event) => f.debug_tuple("Event").field(event).finish(),
+            Self::Query(query) => f.debug_tuple("Query").field(query).finish(),
+        }
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<M, const N_ACTIVE: usize> core::fmt::Debug for StatechartQuery<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Configuration(_) => "Configuration",
+            Self::Context(_) => "Context",
+        })
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<M, const N_ACTIVE: usize> From<StatechartQuery<M, N_ACTIVE>> for StatechartMessage<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    fn from(query: StatechartQuery<M, N_ACTIVE>) -> Self {
+        Self::Query(query)
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<M, const N_ACTIVE: usize> Actor for StatechartActor<M, N_ACTIVE, WithQueries>
+where
+    M: StateMachine<N_ACTIVE> + Send + 'static,
+    M::Event: Send,
+    M::State: Send,
+    M::Context: Send,
+{
+    type Message = StatechartMessage<M, N_ACTIVE>;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, message: Self::Message) -> Self::Future<'_> {
+        match message {
+            StatechartMessage::Event(event) => self.process(&event),
+            // The asker may have given up waiting, so an undelivered answer is dropped.
+            StatechartMessage::Query(StatechartQuery::Configuration(reply)) => {
+                let _ = reply.send(self.machine.state());
+            }
+            StatechartMessage::Query(StatechartQuery::Context(reply)) => {
+                let _ = reply.send(self.machine.context().clone());
+            }
+        }
+        core::future::ready(())
+    }
+
+    fn on_stop(mut self) -> Result<(), ActorError> {
+        if self.reset_on_stop() {
+            Ok(())
+        } else {
+            Err(ActorError::ShutdownFailure)
+        }
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<M, const N_ACTIVE: usize> super::address::Address<StatechartMessage<M, N_ACTIVE>>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    /// Asks the statechart actor for its active configuration.
+    ///
+    /// Returns `None` if the actor has stopped, or stops before answering.
+    pub async fn configuration(&self) -> Option<heapless::Vec<M::State, N_ACTIVE>> {
+        self.ask(|reply| StatechartQuery::Configuration(reply).into())
+            .await
+    }
+
+    /// Asks the statechart actor for a clone of its machine's context.
+    ///
+    /// Returns `None` if the actor has stopped, or stops before answering.
+    pub async fn context_snapshot(&self) -> Option<M::Context> {
+        self.ask(|reply| StatechartQuery::Context(reply).into())
+            .await
+    }
+}
+
+impl<M, const N_ACTIVE: usize> BatchActor for StatechartActor<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE> + Send,
+    M::Event: Send,
+{
+    type Message = M::Event;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle_batch(&mut self, events: &[Self::Message]) -> Self::Future<'_> {
+        if let Some(start) = self.batch_start {
+            start(&self.machine, events.len());
+        }
+        let summary = if let Some(output) = self.output {
+            let mut summary = BatchSendSummary::default();
+            for event in events {
+                let result = self.machine.send(event);
+                output(&self.machine, result);
+                summary.record(result);
+            }
+            summary.final_state = self.machine.state();
+            summary
+        } else {
+            self.machine.send_batch(events)
+        };
+        #[cfg(feature = "debug-log")]
+        if let Some(error) = summary.first_error {
+            log::warn!(
+                "Statechart actor failed to process {} of {} events, first error: {error}",
+                summary.failed,
+                events.len()
+            );
+        }
+        if let Some(end) = self.batch_end {
+            end(&self.machine, &summary);
+        }
+        core::future::ready(())
+    }
+}
 
 /// Example showing how to implement Actor for any `StateMachine` type.
 /// This demonstrates the direct integration pattern from Task 1.4.
@@ -109,7 +481,12 @@ mod tests {
         fn handle(&mut self, event: Self::Message) -> Self::Future<'_> {
             // Forward event to StateMachine and handle the result
             match self.send(&event) {
-                SendResult::Transitioned | SendResult::NoMatch => {
+                SendResult::Transitioned
+                | SendResult::NoMatch
+                | SendResult::BudgetExhausted { .. }
+                | SendResult::InvariantViolated(_)
+                | SendResult::PolicyRejected
+                | SendResult::OscillationDetected => {
                     // State transition completed successfully or no matching transition
                 }
                 SendResult::Error(_error) => {
@@ -200,7 +577,12 @@ mod tests {
         fn handle(&mut self, event: Self::Message) -> Self::Future<'_> {
             // Forward event to StateMachine and handle the result
             match self.send(&event) {
-                SendResult::Transitioned | SendResult::NoMatch => {
+                SendResult::Transitioned
+                | SendResult::NoMatch
+                | SendResult::BudgetExhausted { .. }
+                | SendResult::InvariantViolated(_)
+                | SendResult::PolicyRejected
+                | SendResult::OscillationDetected => {
                     // State transition completed successfully or no matching transition
                 }
                 SendResult::Error(_error) => {
@@ -223,4 +605,97 @@ mod tests {
         // Verify the machine is still functional
         assert_eq!(error_machine.state()[0], MockState::Idle);
     }
+
+    #[test]
+    fn statechart_actor_feeds_messages_as_events() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static TRANSITIONS: AtomicUsize = AtomicUsize::new(0);
+
+        fn count_transitions(_machine: &MockStateMachine, result: SendResult) {
+            if result == SendResult::Transitioned {
+                TRANSITIONS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mut actor =
+            StatechartActor::new(MockStateMachine::new()).with_output(count_transitions);
+
+        drop(Actor::handle(&mut actor, MockEvent::Start));
+        assert_eq!(actor.machine().state()[0], MockState::Working);
+
+        drop(Actor::handle(&mut actor, MockEvent::Start)); // No transition from Working
+        drop(Actor::handle(&mut actor, MockEvent::Stop));
+        assert_eq!(actor.into_inner().state()[0], MockState::Idle);
+        assert_eq!(TRANSITIONS.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn statechart_actor_sends_batches_through_send_batch() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static STARTED_WITH: AtomicUsize = AtomicUsize::new(0);
+        static TRANSITIONED: AtomicUsize = AtomicUsize::new(0);
+
+        fn on_start(_machine: &MockStateMachine, len: usize) {
+            STARTED_WITH.store(len, Ordering::Relaxed);
+        }
+
+        fn on_end(machine: &MockStateMachine, summary: &BatchSendSummary<MockState, 4>) {
+            assert_eq!(summary.final_state, machine.state());
+            TRANSITIONED.store(summary.transitioned, Ordering::Relaxed);
+        }
+
+        let mut actor =
+            StatechartActor::new(MockStateMachine::new()).with_batch_observers(on_start, on_end);
+        drop(actor.handle_batch(&[MockEvent::Start, MockEvent::Start, MockEvent::Stop]));
+
+        assert_eq!(STARTED_WITH.load(Ordering::Relaxed), 3);
+        assert_eq!(TRANSITIONED.load(Ordering::Relaxed), 2);
+        assert_eq!(actor.machine().state()[0], MockState::Idle);
+    }
+
+    #[test]
+    fn send_batch_counts_results_and_keeps_going_after_errors() {
+        let summary = ErrorStateMachine::new(true).send_batch(&[MockEvent::Start, MockEvent::Stop]);
+        assert_eq!(summary.failed, 2);
+        assert_eq!(
+            summary.first_error,
+            Some(crate::runtime::ProcessingError::EntryLogicFailure)
+        );
+        assert_eq!(summary.processed(), 2);
+
+        let summary = MockStateMachine::new().send_batch(&[
+            MockEvent::Stop,
+            MockEvent::Start,
+            MockEvent::Start,
+        ]);
+        assert_eq!((summary.transitioned, summary.ignored), (1, 2));
+        assert_eq!(summary.final_state.as_slice(), &[MockState::Working]);
+    }
+
+    #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+    #[tokio::test]
+    async fn spawned_statechart_actor_processes_events() {
+        use std::sync::Mutex;
+        static STATES: Mutex<std::vec::Vec<MockState>> = Mutex::new(std::vec::Vec::new());
+
+        fn record_state(machine: &MockStateMachine, _result: SendResult) {
+            STATES.lock().unwrap().push(machine.state()[0]);
+        }
+
+        let actor = StatechartActor::new(MockStateMachine::new()).with_output(record_state);
+        let address = crate::actor::spawn::spawn_actor_tokio(actor, 8);
+        address.send(MockEvent::Start).await.unwrap();
+        address.send(MockEvent::Stop).await.unwrap();
+
+        for _ in 0..50 {
+            if STATES.lock().unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert_eq!(
+            STATES.lock().unwrap().as_slice(),
+            &[MockState::Working, MockState::Idle]
+        );
+    }
 }
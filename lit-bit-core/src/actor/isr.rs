@@ -0,0 +1,258 @@
+//! Interrupt-safe senders for feeding events from ISRs into actor mailboxes.
+//!
+//! Both senders wrap the producer half of a heapless SPSC mailbox (see
+//! [`static_mailbox!`](crate::static_mailbox)) and never lock, block, or allocate on the
+//! send path:
+//!
+//! - [`IsrSender`] owns the producer. It is lock-free and wait-free, and is the right choice
+//!   when exactly **one** interrupt handler produces messages. The type system enforces
+//!   this: sending needs `&mut self`, and the sender is moved into the one handler that
+//!   uses it (e.g. an RTIC local resource or a `static mut` owned by the handler).
+//! - [`SharedIsrSender`] (feature `critical-section`) lets several interrupt handlers (or
+//!   an ISR plus thread-mode code) share one mailbox. Each send runs inside
+//!   `critical_section::with`, so producers at different priorities cannot interleave on
+//!   the queue. The critical section only covers a single enqueue.
+//!
+//! In both cases the consumer side stays lock-free and is drained by the actor task.
+//!
+//! On targets without compare-and-swap (`thumbv6m-none-eabi`) enable the `portable-atomic`
+//! feature: the mailbox statics are claimed with an atomic swap, which it emulates with a
+//! critical section.
+//!
+//! ```rust,no_run
+//! use lit_bit_core::static_mailbox;
+//!
+//! // At init: split the mailbox and keep the ISR handle for the timer interrupt.
+//! let (mut isr_tx, mut inbox) = static_mailbox!(TICKS: u32, 8; isr);
+//!
+//! // In the timer interrupt:
+//! isr_tx.send_from_isr(1).ok();
+//!
+//! // In the actor task:
+//! assert_eq!(inbox.dequeue(), Some(1));
+//! ```
+
+use super::Outbox;
+use super::backpressure::SendError;
+
+/// Single-producer interrupt sender.
+///
+/// # Guarantees
+///
+/// - `send_from_isr` is lock-free and wait-free: one load, one store, no retries.
+/// - No allocation and no blocking; a full mailbox is reported as [`SendError::Full`] and
+///   the message is handed back.
+/// - Correct only with a single producer. Owning the sender (and needing `&mut self` to
+///   send) prevents two contexts from producing at once; use [`SharedIsrSender`] when
+///   several interrupts must feed the same mailbox.
+pub struct IsrSender<M: 'static, const N: usize> {
+    producer: Outbox<M, N>,
+}
+
+impl<M: 'static, const N: usize> IsrSender<M, N> {
+    /// Wraps the producer half of a mailbox.
+    #[must_use]
+    pub const fn new(producer: Outbox<M, N>) -> Self {
+        Self { producer }
+    }
+
+    /// Enqueues `msg` without locking or allocating.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SendError::Full`] with the message if the mailbox has no free slot.
+    #[inline]
+    pub fn send_from_isr(&mut self, msg: M) -> Result<(), SendError<M>> {
+        self.producer.enqueue(msg).map_err(SendError::Full)
+    }
+
+    /// Returns `true` if the mailbox currently has room for another message.
+    #[must_use]
+    pub fn ready(&self) -> bool {
+        self.producer.ready()
+    }
+
+    /// Converts this sender into one that can be shared between several producers.
+    ///
+    /// `slot` provides the static storage that the shared handle points at.
+    #[cfg(feature = "critical-section")]
+    #[must_use]
+    pub fn into_shared(self, slot: &'static SharedProducerSlot<M, N>) -> SharedIsrSender<M, N> {
+        SharedIsrSender::new(
+            slot.init(critical_section::Mutex::new(core::cell::RefCell::new(
+                self.producer,
+            ))),
+        )
+    }
+
+    /// Returns the wrapped producer.
+    #[must_use]
+    pub fn into_inner(self) -> Outbox<M, N> {
+        self.producer
+    }
+}
+
+impl<M: 'static, const N: usize> core::fmt::Debug for IsrSender<M, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IsrSender")
+            .field("len", &self.producer.len())
+            .field("capacity", &self.producer.capacity())
+            .finish()
+    }
+}
+
+/// Static storage for the producer behind a [`SharedIsrSender`].
+#[cfg(feature = "critical-section")]
+pub type SharedProducerSlot<M, const N: usize> =
+    static_cell::StaticCell<critical_section::Mutex<core::cell::RefCell<Outbox<M, N>>>>;
+
+/// Multi-producer interrupt sender protected by a critical section.
+///
+/// # Guarantees
+///
+/// - Any number of interrupt handlers and thread-mode code may hold a copy and send
+///   concurrently; each enqueue runs inside `critical_section::with`.
+/// - The critical section spans exactly one enqueue, so the added interrupt latency is
+///   bounded and independent of the queue length.
+/// - No allocation and no blocking beyond the critical section itself.
+#[cfg(feature = "critical-section")]
+pub struct SharedIsrSender<M: 'static, const N: usize> {
+    producer: &'static critical_section::Mutex<core::cell::RefCell<Outbox<M, N>>>,
+}
+
+#[cfg(feature = "critical-section")]
+impl<M: 'static, const N: usize> Clone for SharedIsrSender<M, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<M: 'static, const N: usize> Copy for SharedIsrSender<M, N> {}
+
+#[cfg(feature = "critical-section")]
+impl<M: 'static, const N: usize> core::fmt::Debug for SharedIsrSender<M, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedIsrSender").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<M: 'static, const N: usize> SharedIsrSender<M, N> {
+    /// Wraps a producer that has already been placed in static, critical-section protected
+    /// storage.
+    #[must_use]
+    pub const fn new(
+        producer: &'static critical_section::Mutex<core::cell::RefCell<Outbox<M, N>>>,
+    ) -> Self {
+        Self { producer }
+    }
+
+    /// Enqueues `msg` inside a critical section.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SendError::Full`] with the message if the mailbox has no free slot.
+    #[inline]
+    pub fn send_from_isr(&self, msg: M) -> Result<(), SendError<M>> {
+        critical_section::with(|cs| {
+            self.producer
+                .borrow_ref_mut(cs)
+                .enqueue(msg)
+                .map_err(SendError::Full)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isr_sender_reports_full_mailbox_and_returns_message() {
+        let (mut tx, mut rx) = crate::static_mailbox!(ISR_FULL_TEST: u32, 3; isr);
+
+        assert!(tx.ready());
+        assert_eq!(tx.send_from_isr(1), Ok(()));
+        assert_eq!(tx.send_from_isr(2), Ok(()));
+        assert!(!tx.ready());
+        assert_eq!(tx.send_from_isr(3), Err(SendError::Full(3)));
+
+        assert_eq!(rx.dequeue(), Some(1));
+        assert_eq!(rx.dequeue(), Some(2));
+        assert_eq!(rx.dequeue(), None);
+    }
+
+    #[cfg(all(feature = "critical-section", feature = "std"))]
+    #[test]
+    fn shared_sender_accepts_concurrent_producers() {
+        const PER_PRODUCER: u32 = 200;
+        let (tx, mut rx) = crate::static_mailbox!(ISR_SHARED_TEST: (u8, u32), 16; shared_isr);
+
+        // Two "interrupt handlers" at different priorities, simulated by host threads,
+        // race on the same mailbox while the actor drains it.
+        let producers: Vec<_> = (0..2u8)
+            .map(|id| {
+                std::thread::spawn(move || {
+                    for seq in 0..PER_PRODUCER {
+                        while tx.send_from_isr((id, seq)).is_err() {
+                            std::thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut next = [0u32; 2];
+        while next.iter().any(|&n| n < PER_PRODUCER) {
+            if let Some((id, seq)) = rx.dequeue() {
+                assert_eq!(
+                    seq, next[id as usize],
+                    "per-producer order must be preserved"
+                );
+                next[id as usize] += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+        for producer in producers {
+            producer.join().expect("producer thread panicked");
+        }
+        assert_eq!(rx.dequeue(), None);
+    }
+
+    /// Explores every interleaving of two producers and a consumer at send granularity.
+    #[cfg(feature = "loom")]
+    #[test]
+    fn loom_shared_sender_never_loses_or_reorders_messages() {
+        loom::model(|| {
+            let queue: &'static mut heapless::spsc::Queue<(u8, u8), 8> =
+                Box::leak(Box::new(heapless::spsc::Queue::new()));
+            let (producer, mut consumer) = queue.split();
+            let slot: &'static SharedProducerSlot<(u8, u8), 8> =
+                Box::leak(Box::new(static_cell::StaticCell::new()));
+            let tx = IsrSender::new(producer).into_shared(slot);
+
+            let handles: Vec<_> = (0..2u8)
+                .map(|id| {
+                    loom::thread::spawn(move || {
+                        for seq in 0..2u8 {
+                            tx.send_from_isr((id, seq)).expect("queue has room");
+                            loom::thread::yield_now();
+                        }
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().expect("producer panicked");
+            }
+
+            let mut next = [0u8; 2];
+            while let Some((id, seq)) = consumer.dequeue() {
+                assert_eq!(seq, next[id as usize]);
+                next[id as usize] += 1;
+            }
+            assert_eq!(next, [2, 2]);
+        });
+    }
+}
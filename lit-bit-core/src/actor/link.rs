@@ -0,0 +1,281 @@
+//! Erlang-style links between peer actors.
+//!
+//! [`Address::link`](super::address::Address::link) ties two actors together in both
+//! directions. When either one terminates abnormally (it panics, or its loop ends with an
+//! `ActorError`), every actor linked to it is handed a [`LinkExit`] through
+//! [`Actor::on_link_exit`](super::Actor::on_link_exit) before its next message. By default
+//! the linked actor terminates as well, with `ActorError::LinkedExit`, which in turn
+//! reaches its own links; returning [`LinkAction::Ignore`] traps the exit instead. A clean
+//! shutdown (the mailbox closing) drops the actor's links without notifying anyone.
+//!
+//! # Links and supervision
+//!
+//! Supervision is a parent/child relationship: the supervisor owns its children's tasks
+//! and its restart strategy decides what happens after a failure. Links are between
+//! peers: neither side owns the other and nothing is restarted; a link only makes two
+//! actors share a failure. The two compose. A supervised child that terminates because of
+//! a link reports `ActorError::LinkedExit` to its supervisor like any other failure, and
+//! the restarted child starts with a new address and no links.
+//!
+//! Links are available on Tokio. Actors spawned with `spawn_actor_tokio`,
+//! `spawn_statechart_tokio`, `spawn_supervised_actor_tokio` and `SpawnContext` run a loop
+//! that delivers the exits; the batch actor loops do not.
+//!
+//! # Stopping
+//!
+//! The same loop can be stopped before its mailbox closes, by
+//! [`Address::stop`](super::address::Address::stop) or by a supervisor that was given the
+//! actor's address. It finishes the message it is handling, drops the rest of its mailbox
+//! and passes the [`StopReason`](super::StopReason) to
+//! [`Actor::on_stop_with_reason`](super::Actor::on_stop_with_reason). Being stopped is a
+//! clean shutdown, so linked actors are not notified.
+
+use super::{ActorError, ActorId};
+
+/// Notification that a linked actor terminated abnormally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkExit {
+    /// The linked actor that terminated.
+    pub peer: ActorId,
+    /// Why it terminated.
+    pub error: ActorError,
+}
+
+/// What an actor does when a linked actor terminates abnormally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinkAction {
+    /// Terminate too, with `ActorError::LinkedExit` (the default).
+    #[default]
+    Terminate,
+    /// Trap the exit and keep processing messages.
+    Ignore,
+}
+
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+pub(crate) use tokio_impl::{LinkCell, linked_actor_task};
+
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+mod tokio_impl {
+    use std::panic::AssertUnwindSafe;
+    use std::sync::{Arc, Mutex, Weak};
+
+    use futures::FutureExt;
+    use tokio::sync::Notify;
+
+    use super::{LinkAction, LinkExit};
+    use crate::actor::panic_handling::capture_panic_info_from_payload_with_id;
+    use crate::actor::{Actor, ActorError, ActorId, Inbox, StopReason, enter_handle};
+
+    /// Link bookkeeping shared by an actor's addresses and its task.
+    pub(crate) struct LinkCell {
+        id: ActorId,
+        state: Mutex<LinkState>,
+        /// Wakes the task for pending exits and stop requests.
+        wake: Notify,
+    }
+
+    #[derive(Default)]
+    struct LinkState {
+        peers: Vec<Weak<LinkCell>>,
+        pending: Vec<LinkExit>,
+        stop: Option<StopReason>,
+        status: Status,
+    }
+
+    #[derive(Default)]
+    enum Status {
+        #[default]
+        Running,
+        Stopped,
+        Failed(ActorError),
+    }
+
+    impl LinkCell {
+        pub(crate) fn new(id: ActorId) -> Arc<Self> {
+            Arc::new(Self {
+                id,
+                state: Mutex::new(LinkState::default()),
+                wake: Notify::new(),
+            })
+        }
+
+        fn state(&self) -> std::sync::MutexGuard<'_, LinkState> {
+            // The lock is never held across user code, so poisoning can only come from a
+            // panic inside this module; the state is still consistent in that case.
+            self.state
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+        }
+
+        /// Links `a` and `b` in both directions.
+        pub(crate) fn link(a: &Arc<Self>, b: &Arc<Self>) {
+            if a.id == b.id {
+                return;
+            }
+            a.add_peer(b);
+            b.add_peer(a);
+        }
+
+        /// Removes the link between `a` and `b`, if any.
+        pub(crate) fn unlink(a: &Self, b: &Self) {
+            a.remove_peer(b.id);
+            b.remove_peer(a.id);
+        }
+
+        fn add_peer(&self, peer: &Arc<Self>) {
+            let mut state = self.state();
+            match &state.status {
+                Status::Running => {
+                    let known = state
+                        .peers
+                        .iter()
+                        .any(|known| known.upgrade().is_some_and(|known| known.id == peer.id));
+                    if !known {
+                        state.peers.push(Arc::downgrade(peer));
+                    }
+                }
+                Status::Stopped => {}
+                // Linking to an actor that has already failed reports the failure at once.
+                Status::Failed(error) => {
+                    let exit = LinkExit {
+                        peer: self.id,
+                        error: error.clone(),
+                    };
+                    drop(state);
+                    peer.deliver(exit);
+                }
+            }
+        }
+
+        fn remove_peer(&self, id: ActorId) {
+            self.state()
+                .peers
+                .retain(|peer| peer.upgrade().is_some_and(|peer| peer.id != id));
+        }
+
+        fn deliver(&self, exit: LinkExit) {
+            let mut state = self.state();
+            if matches!(state.status, Status::Running) {
+                state.pending.push(exit);
+                drop(state);
+                self.wake.notify_one();
+            }
+        }
+
+        /// Asks the actor's loop to stop with `reason` after the message it is handling.
+        /// The first request wins, and none has any effect once the actor has terminated.
+        pub(crate) fn request_stop(&self, reason: StopReason) {
+            let mut state = self.state();
+            if matches!(state.status, Status::Running) && state.stop.is_none() {
+                state.stop = Some(reason);
+                drop(state);
+                self.wake.notify_one();
+            }
+        }
+
+        /// Records how the actor's task ended and tells its peers if it failed.
+        fn exit(&self, error: Option<&ActorError>) {
+            let peers = {
+                let mut state = self.state();
+                state.status = match error {
+                    Some(error) => Status::Failed(error.clone()),
+                    None => Status::Stopped,
+                };
+                state.pending.clear();
+                state.stop = None;
+                core::mem::take(&mut state.peers)
+            };
+            for peer in peers.iter().filter_map(Weak::upgrade) {
+                peer.remove_peer(self.id);
+                if let Some(error) = error {
+                    peer.deliver(LinkExit {
+                        peer: self.id,
+                        error: error.clone(),
+                    });
+                }
+            }
+        }
+
+        fn take_pending(&self) -> Vec<LinkExit> {
+            core::mem::take(&mut self.state().pending)
+        }
+
+        fn take_stop(&self) -> Option<StopReason> {
+            self.state().stop.take()
+        }
+    }
+
+    /// Runs an actor's message loop like [`actor_task`](crate::actor::actor_task), and also
+    /// delivers the exits of the actors linked to it.
+    ///
+    /// Exits are handled between messages, each through
+    /// [`Actor::on_link_exit`](crate::actor::Actor::on_link_exit), and so are stop
+    /// requests, which end the loop without reading the rest of the mailbox. When the loop
+    /// ends with
+    /// an error, or the actor panics, the linked actors are notified in turn; a panic is
+    /// then resumed so the task's `JoinHandle` still reports it.
+    ///
+    /// # Errors
+    /// Returns `ActorError` if actor startup, shutdown, or message processing fails, and
+    /// `ActorError::LinkedExit` when the actor terminates because a linked actor failed.
+    pub(crate) async fn linked_actor_task<A>(
+        actor: A,
+        inbox: Inbox<A::Message>,
+        links: Arc<LinkCell>,
+    ) -> Result<(), ActorError>
+    where
+        A: Actor + Send + 'static,
+        A::Message: Send + 'static,
+    {
+        match AssertUnwindSafe(run(actor, inbox, &links))
+            .catch_unwind()
+            .await
+        {
+            Ok(result) => {
+                links.exit(result.as_ref().err());
+                result
+            }
+            Err(payload) => {
+                links.exit(Some(&capture_panic_info_from_payload_with_id(
+                    &payload, links.id,
+                )));
+                std::panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    async fn run<A>(
+        mut actor: A,
+        mut inbox: Inbox<A::Message>,
+        links: &LinkCell,
+    ) -> Result<(), ActorError>
+    where
+        A: Actor + Send + 'static,
+        A::Message: Send + 'static,
+    {
+        actor.on_start()?;
+
+        let reason = loop {
+            tokio::select! {
+                biased;
+                () = links.wake.notified() => {
+                    for exit in links.take_pending() {
+                        if actor.on_link_exit(&exit) == LinkAction::Terminate {
+                            return Err(ActorError::LinkedExit { peer: exit.peer });
+                        }
+                    }
+                    if let Some(reason) = links.take_stop() {
+                        break reason;
+                    }
+                }
+                msg = inbox.recv() => {
+                    let Some(msg) = msg else { break StopReason::SendersDropped };
+                    let _scope = enter_handle(&actor)?;
+                    actor.handle(msg).await;
+                }
+            }
+        };
+
+        actor.on_stop_with_reason(reason)
+    }
+}
@@ -0,0 +1,117 @@
+//! [`ActorMetrics`] published through the [`metrics`] crate facade.
+//!
+//! A [`MetricsExporter`] registers an actor's figures with whatever recorder the process
+//! installed (Prometheus, StatsD, ...) and copies the current values over on each
+//! [`publish`](MetricsExporter::publish), or every period once
+//! [`spawn`](MetricsExporter::spawn)ed:
+//!
+//! | Metric                          | Kind    | Value                                        |
+//! |---------------------------------|---------|----------------------------------------------|
+//! | `lit_bit_actor_batch_limit`     | gauge   | [`ActorMetrics::batch_limit`]                |
+//! | `lit_bit_actor_last_batch_size` | gauge   | [`ActorMetrics::last_batch_size`]            |
+//! | `lit_bit_actor_batches_total`   | counter | [`ActorMetrics::batches`]                    |
+//! | `lit_bit_actor_messages_total`  | counter | [`ActorMetrics::messages`]                   |
+//!
+//! Every metric carries an `actor_id` label with the [`ActorId`]'s raw number, and an
+//! `actor_name` label when a name is registered for it ([`ActorId::named`]).
+//! [`describe`] adds help texts for the metric names; call it once after installing the
+//! recorder.
+//!
+//! ```rust,no_run
+//! # async fn run<A: lit_bit_core::actor::BatchActor<Message = u32> + Send + 'static>(actor: A) {
+//! use std::time::Duration;
+//!
+//! use lit_bit_core::actor::metrics_export::{self, MetricsExporter};
+//! use lit_bit_core::actor::spawn::spawn_batch_actor_tokio_adaptive;
+//! use lit_bit_core::actor::{ActorId, AdaptiveBatching};
+//!
+//! // ... install a recorder, e.g. `metrics_exporter_prometheus::PrometheusBuilder` ...
+//! metrics_export::describe();
+//! let (address, metrics) = spawn_batch_actor_tokio_adaptive(actor, 256, AdaptiveBatching::new(1, 128));
+//! MetricsExporter::new(ActorId::named("ingest"), metrics).spawn(Duration::from_secs(1));
+//! # }
+//! ```
+//!
+//! [`ActorId::named`]: super::ActorId::named
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use metrics::{Counter, Gauge, Label};
+
+use super::ActorId;
+use super::adaptive_batch::ActorMetrics;
+
+/// Name of the gauge with the drain limit for the next batch.
+pub const BATCH_LIMIT: &str = "lit_bit_actor_batch_limit";
+/// Name of the gauge with the size of the last batch.
+pub const LAST_BATCH_SIZE: &str = "lit_bit_actor_last_batch_size";
+/// Name of the counter of handled batches.
+pub const BATCHES_TOTAL: &str = "lit_bit_actor_batches_total";
+/// Name of the counter of handled messages.
+pub const MESSAGES_TOTAL: &str = "lit_bit_actor_messages_total";
+
+/// Describes the exported metrics to the installed recorder.
+pub fn describe() {
+    metrics::describe_gauge!(BATCH_LIMIT, "Drain limit of the actor's next batch.");
+    metrics::describe_gauge!(LAST_BATCH_SIZE, "Messages in the actor's last batch.");
+    metrics::describe_counter!(BATCHES_TOTAL, "Batches the actor has handled.");
+    metrics::describe_counter!(MESSAGES_TOTAL, "Messages the actor has handled.");
+}
+
+/// Copies one actor's [`ActorMetrics`] to the `metrics` facade; see the
+/// [module docs](self).
+#[derive(Debug)]
+pub struct MetricsExporter {
+    metrics: Arc<ActorMetrics>,
+    batch_limit: Gauge,
+    last_batch_size: Gauge,
+    batches: Counter,
+    messages: Counter,
+}
+
+impl MetricsExporter {
+    /// Registers the metrics of the actor `id` with the recorder installed now.
+    #[must_use]
+    pub fn new(id: ActorId, metrics: Arc<ActorMetrics>) -> Self {
+        let labels = labels(id);
+        Self {
+            metrics,
+            batch_limit: metrics::gauge!(BATCH_LIMIT, labels.iter()),
+            last_batch_size: metrics::gauge!(LAST_BATCH_SIZE, labels.iter()),
+            batches: metrics::counter!(BATCHES_TOTAL, labels.iter()),
+            messages: metrics::counter!(MESSAGES_TOTAL, labels.iter()),
+        }
+    }
+
+    /// Sets the metrics to the actor's current figures.
+    #[allow(clippy::cast_precision_loss)] // Batch sizes are far below 2^52.
+    pub fn publish(&self) {
+        self.batch_limit.set(self.metrics.batch_limit() as f64);
+        self.last_batch_size
+            .set(self.metrics.last_batch_size() as f64);
+        self.batches.absolute(self.metrics.batches());
+        self.messages.absolute(self.metrics.messages());
+    }
+
+    /// Publishes every `period` on a Tokio task, until the task is aborted.
+    pub fn spawn(self, period: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(period);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                self.publish();
+            }
+        })
+    }
+}
+
+fn labels(id: ActorId) -> Vec<Label> {
+    let mut labels = Vec::with_capacity(2);
+    labels.push(Label::new("actor_id", id.get().to_string()));
+    if let Some(name) = id.name() {
+        labels.push(Label::from_static_parts("actor_name", name));
+    }
+    labels
+}
@@ -48,7 +48,7 @@ pub enum ActorError {
         /// Panic message extracted from the panic payload, if available
         message: Option<ActorString>,
         /// Actor identifier for supervision context, if available
-        actor_id: Option<ActorString>,
+        actor_id: Option<ActorId>,
     },
     /// Actor mailbox was closed unexpectedly
     MailboxClosed,
@@ -58,6 +58,30 @@ pub enum ActorError {
     SupervisionFailure(ActorString),
     /// Custom error with static message for no_std compatibility
     Custom(&'static str),
+    /// An actor linked to this one terminated abnormally, and this actor's
+    /// [`Actor::on_link_exit`] chose to terminate with it
+    LinkedExit {
+        /// The linked actor whose failure ended this one
+        peer: ActorId,
+    },
+}
+
+/// Logs only the variant (and the actor id of a panic), so the defmt timeline carries a
+/// single interned string per error.
+#[cfg(feature = "defmt")]
+impl defmt::Format for ActorError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            ActorError::StartupFailure => defmt::write!(f, "StartupFailure"),
+            ActorError::ShutdownFailure => defmt::write!(f, "ShutdownFailure"),
+            ActorError::Panic { actor_id, .. } => defmt::write!(f, "Panic({})", actor_id),
+            ActorError::MailboxClosed => defmt::write!(f, "MailboxClosed"),
+            ActorError::Timeout => defmt::write!(f, "Timeout"),
+            ActorError::SupervisionFailure(_) => defmt::write!(f, "SupervisionFailure"),
+            ActorError::Custom(_) => defmt::write!(f, "Custom"),
+            ActorError::LinkedExit { peer } => defmt::write!(f, "LinkedExit({})", peer),
+        }
+    }
 }
 
 /// Restart strategy for actor supervision (OTP-inspired).
@@ -79,6 +103,7 @@ pub enum ActorError {
 /// - **Escalate**: Don't restart, escalate failure to parent supervisor
 /// - **Never**: Explicit no-restart policy (clearer than Temporary)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum RestartStrategy {
     // Classic restart patterns
     /// Restart only this actor (default, classic isolation pattern)
@@ -108,6 +133,23 @@ pub enum RestartStrategy {
     Never,
 }
 
+/// Why an actor's loop ended, passed to [`Actor::on_stop_with_reason`].
+///
+/// Only the Tokio loops that deliver link exits (see the [`link`] module) can be stopped
+/// before their mailbox closes; every other loop stops with `SendersDropped`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StopReason {
+    /// Every sender was dropped and the mailbox drained.
+    SendersDropped,
+    /// [`Address::stop`](address::Address::stop) was called.
+    StopRequested,
+    /// The supervisor is restarting this actor alongside a failed sibling.
+    SupervisorRestart,
+    /// The supervisor gave up on a failing child and escalated, stopping its other children.
+    Escalated,
+}
+
 /// Restart intensity configuration for supervision with deterministic backoff.
 ///
 /// Controls restart rate limiting and backoff behavior to prevent crash loops
@@ -223,6 +265,7 @@ impl PanicAnalyzer for DefaultPanicAnalyzer {
             ActorError::StartupFailure => true,
             ActorError::MailboxClosed => true,
             ActorError::Timeout => true,
+            ActorError::LinkedExit { .. } => true,
             ActorError::SupervisionFailure(_) => false,
             ActorError::ShutdownFailure => false, // Don't restart on clean shutdown failure
         }
@@ -476,7 +519,6 @@ pub trait BatchActor: Send {
     /// })
     /// #     }
     /// # }
-    #[must_use]
     fn handle_batch(&mut self, messages: &[Self::Message]) -> Self::Future<'_>;
 
     /// Maximum number of messages to process in a single batch.
@@ -498,6 +540,13 @@ pub trait BatchActor: Send {
         32
     }
 
+    /// Recorder the batch loops update after every batch; see
+    /// [`batch_stats`](crate::actor::batch_stats). Default: `None`, nothing is recorded.
+    #[cfg(feature = "batch-stats")]
+    fn batch_stats(&self) -> Option<&batch_stats::BatchStatsRecorder> {
+        None
+    }
+
     /// Called when the actor starts. Default: Ok(())
     ///
     /// # Errors
@@ -519,6 +568,19 @@ pub trait BatchActor: Send {
         Ok(())
     }
 
+    /// Called when the actor stops, with why its loop ended. Default: forwards to
+    /// [`Self::on_stop`], so actors that don't need the reason can keep implementing that.
+    ///
+    /// # Errors
+    /// Returns `Err(ActorError)` if actor shutdown fails.
+    #[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+    fn on_stop_with_reason(self, _reason: StopReason) -> Result<(), ActorError>
+    where
+        Self: Sized,
+    {
+        self.on_stop()
+    }
+
     /// Called if the actor panics. Default: `RestartStrategy::OneForOne`
     fn on_panic(&self, _info: &PanicInfo) -> RestartStrategy {
         RestartStrategy::OneForOne
@@ -596,6 +658,12 @@ pub trait Actor: Send {
     /// - No new messages are dequeued until the current future completes
     /// - Actor state is protected during async operations (Actix-style atomicity)
     ///
+    /// `actor_task` owns the actor and awaits each `handle()` future before dequeuing the
+    /// next message, so safe code cannot break this. State shared outside the actor (a
+    /// `static`, or a second actor value aliasing the same resources) can; actors with such
+    /// state should return a guard from [`Actor::reentrancy_guard`] so `actor_task` rejects
+    /// overlapping calls instead of letting them interleave.
+    ///
     /// ## Examples
     ///
     /// ### Sync-style handler (compiles to sync code)
@@ -628,7 +696,6 @@ pub trait Actor: Send {
     /// })
     /// #     }
     /// # }
-    #[must_use]
     fn handle(&mut self, msg: Self::Message) -> Self::Future<'_>;
 
     /// Called when the actor starts. Default: Ok(())
@@ -652,6 +719,19 @@ pub trait Actor: Send {
         Ok(())
     }
 
+    /// Called when the actor stops, with why its loop ended. Default: forwards to
+    /// [`Self::on_stop`], so actors that don't need the reason can keep implementing that.
+    ///
+    /// # Errors
+    /// Returns `Err(ActorError)` if actor shutdown fails.
+    #[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+    fn on_stop_with_reason(self, _reason: StopReason) -> Result<(), ActorError>
+    where
+        Self: Sized,
+    {
+        self.on_stop()
+    }
+
     /// Called if the actor panics. Default: `RestartStrategy::OneForOne`
     fn on_panic(&self, _info: &PanicInfo) -> RestartStrategy {
         RestartStrategy::OneForOne
@@ -764,6 +844,108 @@ pub trait Actor: Send {
         // Default: no cleanup needed
         Ok(())
     }
+
+    /// Guard `actor_task` holds for the duration of every `handle()` call.
+    ///
+    /// Return the same `'static` guard from every value that shares this actor's state; a
+    /// second `handle()` starting while one is still running then fails with
+    /// `ActorError::Custom("reentrancy")` (and panics in debug builds). Default: `None`,
+    /// no check.
+    fn reentrancy_guard(&self) -> Option<&'static ReentrancyGuard> {
+        None
+    }
+
+    /// Called between messages when an actor linked to this one terminates abnormally
+    /// (see the [`link`] module). Default: `LinkAction::Terminate`, so the failure spreads
+    /// to this actor too; return `LinkAction::Ignore` to trap the exit and keep running.
+    fn on_link_exit(&mut self, _exit: &LinkExit) -> LinkAction {
+        LinkAction::Terminate
+    }
+}
+
+/// Flag enforcing the one-`handle()`-at-a-time invariant for actors whose state is shared
+/// outside the actor value; see [`Actor::reentrancy_guard`].
+///
+/// Entering is a single atomic swap, so two cores entering at once cannot both succeed.
+/// Targets without atomic read-modify-write (e.g. `thumbv6m`) fall back to a load and a
+/// store: re-entry on the same executor is still always caught, simultaneous entry from
+/// two cores only on a best-effort basis.
+#[derive(Debug, Default)]
+pub struct ReentrancyGuard {
+    in_handle: core::sync::atomic::AtomicBool,
+}
+
+impl ReentrancyGuard {
+    /// A guard no `handle()` call holds yet; `const` so it can live in the `static`
+    /// returned from [`Actor::reentrancy_guard`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            in_handle: core::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Marks a `handle()` call as running until the returned scope is dropped.
+    ///
+    /// # Errors
+    /// Returns `ActorError::Custom("reentrancy")` if another call is still running.
+    ///
+    /// # Panics
+    /// Panics instead of returning the error when debug assertions are enabled.
+    #[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+    pub fn enter(&self) -> Result<HandleScope<'_>, ActorError> {
+        use core::sync::atomic::Ordering;
+
+        #[cfg(target_has_atomic = "8")]
+        let already_handling = self.in_handle.swap(true, Ordering::AcqRel);
+        #[cfg(not(target_has_atomic = "8"))]
+        let already_handling = self.in_handle.load(Ordering::Acquire);
+        debug_assert!(
+            !already_handling,
+            "Actor::handle re-entered while a previous call was still running"
+        );
+        if already_handling {
+            return Err(ActorError::Custom("reentrancy"));
+        }
+        #[cfg(not(target_has_atomic = "8"))]
+        self.in_handle.store(true, Ordering::Release);
+        Ok(HandleScope { guard: self })
+    }
+
+    /// Returns `true` while a `handle()` call holds the guard.
+    #[must_use]
+    pub fn is_handling(&self) -> bool {
+        self.in_handle.load(core::sync::atomic::Ordering::Acquire)
+    }
+}
+
+/// Releases a [`ReentrancyGuard`] when dropped.
+#[derive(Debug)]
+#[must_use = "the guard is released as soon as the scope is dropped"]
+pub struct HandleScope<'a> {
+    guard: &'a ReentrancyGuard,
+}
+
+impl Drop for HandleScope<'_> {
+    fn drop(&mut self) {
+        self.guard
+            .in_handle
+            .store(false, core::sync::atomic::Ordering::Release);
+    }
+}
+
+/// Enters `actor`'s re-entrancy guard, if it has one.
+#[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+fn enter_handle<A: Actor>(actor: &A) -> Result<Option<HandleScope<'static>>, ActorError> {
+    let scope = actor
+        .reentrancy_guard()
+        .map(ReentrancyGuard::enter)
+        .transpose();
+    #[cfg(feature = "defmt")]
+    if scope.is_err() {
+        defmt::warn!("actor: reentrant handle rejected");
+    }
+    scope
 }
 
 /// Ergonomic async trait for use when heap allocation is available.
@@ -817,7 +999,6 @@ pub trait AsyncActor: Send {
     ///
     /// Note: This method returns a boxed future for ergonomic use when heap allocation
     /// is available. The actual implementation should use async fn syntax when possible.
-    #[must_use]
     fn handle(&mut self, msg: Self::Message) -> futures::future::BoxFuture<'_, ()>;
 
     /// Called when the actor starts. Default: Ok(())
@@ -839,6 +1020,18 @@ pub trait AsyncActor: Send {
         Ok(())
     }
 
+    /// Called when the actor stops, with why its loop ended. Default: forwards to
+    /// [`Self::on_stop`], so actors that don't need the reason can keep implementing that.
+    ///
+    /// # Errors
+    /// Returns `Err(ActorError)` if actor shutdown fails.
+    fn on_stop_with_reason(self, _reason: StopReason) -> Result<(), ActorError>
+    where
+        Self: Sized,
+    {
+        self.on_stop()
+    }
+
     /// Called if the actor panics. Default: `RestartStrategy::OneForOne`
     fn on_panic(&self, _info: &PanicInfo) -> RestartStrategy {
         RestartStrategy::OneForOne
@@ -888,6 +1081,13 @@ where
         AsyncActor::on_stop(self)
     }
 
+    fn on_stop_with_reason(self, reason: StopReason) -> Result<(), ActorError>
+    where
+        Self: Sized,
+    {
+        AsyncActor::on_stop_with_reason(self, reason)
+    }
+
     fn on_panic(&self, info: &PanicInfo) -> RestartStrategy {
         AsyncActor::on_panic(self, info)
     }
@@ -897,6 +1097,164 @@ where
     }
 }
 
+/// Ergonomic counterpart of [`BatchActor`] for use when heap allocation is available, as
+/// [`AsyncActor`] is for [`Actor`]: `handle_batch` returns a boxed future instead of naming
+/// a GAT, and a blanket impl provides `BatchActor`.
+///
+/// The future may borrow `self` but not `messages`, which the batch loop reuses for the
+/// next batch: read what the asynchronous part needs out of the slice first.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # #[cfg(any(feature = "std", feature = "alloc"))]
+/// # {
+/// use lit_bit_core::actor::AsyncBatchActor;
+/// use futures::future::BoxFuture;
+///
+/// struct Uplink {
+///     sent: u32,
+/// }
+///
+/// impl AsyncBatchActor for Uplink {
+///     type Message = u16;
+///
+///     fn handle_batch(&mut self, samples: &[u16]) -> BoxFuture<'_, ()> {
+///         let frame: Vec<u16> = samples.to_vec();
+///         Box::pin(async move {
+///             // self.radio.transmit(&frame).await;
+///             self.sent += frame.len() as u32;
+///         })
+///     }
+///
+///     fn max_batch_size(&self) -> usize {
+///         64
+///     }
+/// }
+/// # }
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub trait AsyncBatchActor: Send {
+    /// The message type this actor handles
+    type Message: Send + 'static;
+
+    /// Handle a batch of messages asynchronously; see [`BatchActor::handle_batch`].
+    fn handle_batch(&mut self, messages: &[Self::Message]) -> futures::future::BoxFuture<'_, ()>;
+
+    /// Maximum number of messages to process in a single batch; see
+    /// [`BatchActor::max_batch_size`]. Default: 32
+    fn max_batch_size(&self) -> usize {
+        32
+    }
+
+    /// Recorder the batch loops update after every batch. Default: `None`
+    #[cfg(feature = "batch-stats")]
+    fn batch_stats(&self) -> Option<&batch_stats::BatchStatsRecorder> {
+        None
+    }
+
+    /// Called when the actor starts. Default: Ok(())
+    ///
+    /// # Errors
+    /// Returns `Err(ActorError)` if actor startup fails.
+    #[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+    fn on_start(&mut self) -> Result<(), ActorError> {
+        Ok(())
+    }
+
+    /// Called when the actor stops. Default: Ok(())
+    ///
+    /// # Errors
+    /// Returns `Err(ActorError)` if actor shutdown fails.
+    #[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+    fn on_stop(self) -> Result<(), ActorError>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    /// Called when the actor stops, with why its loop ended. Default: forwards to
+    /// [`Self::on_stop`], so actors that don't need the reason can keep implementing that.
+    ///
+    /// # Errors
+    /// Returns `Err(ActorError)` if actor shutdown fails.
+    #[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+    fn on_stop_with_reason(self, _reason: StopReason) -> Result<(), ActorError>
+    where
+        Self: Sized,
+    {
+        self.on_stop()
+    }
+
+    /// Called if the actor panics. Default: `RestartStrategy::OneForOne`
+    fn on_panic(&self, _info: &PanicInfo) -> RestartStrategy {
+        RestartStrategy::OneForOne
+    }
+
+    /// Called before restart to reset state; see [`BatchActor::on_restart`].
+    ///
+    /// # Errors
+    /// Returns `Err(ActorError)` if restart preparation fails.
+    #[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+    fn on_restart(&mut self) -> Result<(), ActorError> {
+        Ok(())
+    }
+}
+
+/// Blanket implementation of `BatchActor` for any `AsyncBatchActor` when heap allocation is
+/// available.
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<T> BatchActor for T
+where
+    T: AsyncBatchActor,
+{
+    type Message = T::Message;
+    type Future<'a>
+        = futures::future::BoxFuture<'a, ()>
+    where
+        Self: 'a;
+
+    fn handle_batch(&mut self, messages: &[Self::Message]) -> Self::Future<'_> {
+        AsyncBatchActor::handle_batch(self, messages)
+    }
+
+    fn max_batch_size(&self) -> usize {
+        AsyncBatchActor::max_batch_size(self)
+    }
+
+    #[cfg(feature = "batch-stats")]
+    fn batch_stats(&self) -> Option<&batch_stats::BatchStatsRecorder> {
+        AsyncBatchActor::batch_stats(self)
+    }
+
+    fn on_start(&mut self) -> Result<(), ActorError> {
+        AsyncBatchActor::on_start(self)
+    }
+
+    fn on_stop(self) -> Result<(), ActorError>
+    where
+        Self: Sized,
+    {
+        AsyncBatchActor::on_stop(self)
+    }
+
+    fn on_stop_with_reason(self, reason: StopReason) -> Result<(), ActorError>
+    where
+        Self: Sized,
+    {
+        AsyncBatchActor::on_stop_with_reason(self, reason)
+    }
+
+    fn on_panic(&self, info: &PanicInfo) -> RestartStrategy {
+        AsyncBatchActor::on_panic(self, info)
+    }
+
+    fn on_restart(&mut self) -> Result<(), ActorError> {
+        AsyncBatchActor::on_restart(self)
+    }
+}
+
 // Conditional mailbox type aliases (Task 2.1)
 #[cfg(not(feature = "async-tokio"))]
 pub type Inbox<T, const N: usize> = heapless::spsc::Consumer<'static, T, N>;
@@ -981,11 +1339,40 @@ pub type Outbox<T> = tokio::sync::mpsc::Sender<T>;
 /// evt_tx.enqueue(EventMsg::Started).unwrap();
 /// ```
 ///
+/// ## Interrupt Producers
+/// ```rust,ignore
+/// // Wrap the producer in an `IsrSender` for a single interrupt handler...
+/// let (mut tick_tx, tick_rx) = static_mailbox!(TICK_MAILBOX: u32, 8; isr);
+/// tick_tx.send_from_isr(1).ok();
+///
+/// // ...or in a critical-section protected `SharedIsrSender` for several handlers
+/// // (requires the `critical-section` feature).
+/// let (irq_tx, irq_rx) = static_mailbox!(IRQ_MAILBOX: u32, 8; shared_isr);
+/// irq_tx.send_from_isr(2).ok();
+/// ```
+///
 /// # Panics
 ///
 /// Panics if called more than once for the same static queue (prevents double-split).
 #[macro_export]
 macro_rules! static_mailbox {
+    // Single-producer interrupt handle (see `actor::isr::IsrSender`)
+    ($(#[$attr:meta])* $name:ident: $msg_type:ty, $capacity:expr; isr) => {{
+        let (producer, consumer) = $crate::static_mailbox!($(#[$attr])* $name: $msg_type, $capacity);
+        ($crate::actor::isr::IsrSender::new(producer), consumer)
+    }};
+
+    // Critical-section protected multi-producer interrupt handle (see `actor::isr::SharedIsrSender`)
+    ($(#[$attr:meta])* $name:ident: $msg_type:ty, $capacity:expr; shared_isr) => {{
+        static PRODUCER_SLOT: $crate::actor::isr::SharedProducerSlot<$msg_type, $capacity> =
+            ::static_cell::StaticCell::new();
+        let (producer, consumer) = $crate::static_mailbox!($(#[$attr])* $name: $msg_type, $capacity);
+        (
+            $crate::actor::isr::IsrSender::new(producer).into_shared(&PRODUCER_SLOT),
+            consumer,
+        )
+    }};
+
     ($(#[$attr:meta])* $name:ident: $msg_type:ty, $capacity:expr) => {{
         $(#[$attr])*
         static $name: ::static_cell::StaticCell<::heapless::spsc::Queue<$msg_type, $capacity>> = ::static_cell::StaticCell::new();
@@ -1177,8 +1564,11 @@ async fn yield_control() {
 ///
 /// # Errors
 /// Returns `ActorError` if actor startup, shutdown, or message processing fails.
+/// A message arriving while the actor's [`Actor::reentrancy_guard`] is held by another
+/// `handle()` call stops the loop with `ActorError::Custom("reentrancy")`.
 #[allow(unreachable_code)] // no_std path has infinite loop, cleanup only reachable on std
 #[cfg(all(not(feature = "async-tokio"), not(feature = "async-embassy")))]
+#[allow(clippy::result_large_err)] // ActorError provides detailed failure information
 pub async fn actor_task<A: Actor, const N: usize>(
     mut actor: A,
     mut inbox: Inbox<A::Message, N>,
@@ -1189,6 +1579,11 @@ pub async fn actor_task<A: Actor, const N: usize>(
     if let Err(ref e) = startup_result {
         log::error!("Actor startup failed: {e:?}");
     }
+    #[cfg(feature = "defmt")]
+    match &startup_result {
+        Ok(()) => defmt::info!("actor: started"),
+        Err(e) => defmt::warn!("actor: start failed: {}", e),
+    }
     startup_result?;
 
     // Main processing loop (Ector pattern)
@@ -1207,13 +1602,14 @@ pub async fn actor_task<A: Actor, const N: usize>(
                 yield_control().await;
             }
         };
+        let _scope = enter_handle(&actor)?;
         actor.handle(msg).await;
     }
 
     // Cleanup hook (unreachable in no_std)
     #[allow(unreachable_code)]
     {
-        let stop_result = actor.on_stop();
+        let stop_result = actor.on_stop_with_reason(StopReason::SendersDropped);
         #[cfg(feature = "debug-log")]
         if let Err(ref e) = stop_result {
             log::error!("Actor shutdown failed: {e:?}");
@@ -1248,8 +1644,10 @@ pub async fn actor_task<A: Actor, const N: usize>(
 /// * `receiver` - Embassy channel receiver for incoming messages
 ///
 /// # Errors
-/// Returns `ActorError` if actor startup or shutdown fails.
-/// Message processing errors are handled internally.
+/// Returns `ActorError` if actor startup or shutdown fails, or
+/// `ActorError::Custom("reentrancy")` if the actor's [`Actor::reentrancy_guard`] is
+/// already held when a message arrives. Other message processing errors are handled
+/// internally.
 #[cfg(feature = "async-embassy")]
 pub async fn actor_task_embassy<A, const N: usize>(
     mut actor: A,
@@ -1270,6 +1668,11 @@ where
     if let Err(ref e) = startup_result {
         log::error!("Actor startup failed: {e:?}");
     }
+    #[cfg(feature = "defmt")]
+    match &startup_result {
+        Ok(()) => defmt::info!("actor: started"),
+        Err(e) => defmt::warn!("actor: start failed: {}", e),
+    }
     startup_result?;
 
     // Main message processing loop
@@ -1281,6 +1684,7 @@ where
 
         // Process the message atomically (one at a time)
         // This ensures deterministic execution and prevents re-entrancy
+        let _scope = enter_handle(&actor)?;
         actor.handle(msg).await;
     }
 
@@ -1289,7 +1693,7 @@ where
     // run forever until device reset.
     #[allow(unreachable_code)]
     {
-        let stop_result = actor.on_stop();
+        let stop_result = actor.on_stop_with_reason(StopReason::SendersDropped);
         #[cfg(feature = "debug-log")]
         if let Err(ref e) = stop_result {
             log::error!("Actor shutdown failed: {e:?}");
@@ -1303,6 +1707,8 @@ where
 ///
 /// # Errors
 /// Returns `ActorError` if actor startup, shutdown, or message processing fails.
+/// A message arriving while the actor's [`Actor::reentrancy_guard`] is held by another
+/// `handle()` call stops the loop with `ActorError::Custom("reentrancy")`.
 #[cfg(feature = "async-tokio")]
 pub async fn actor_task<A>(mut actor: A, mut inbox: Inbox<A::Message>) -> Result<(), ActorError>
 where
@@ -1314,12 +1720,13 @@ where
 
     // Process messages until the channel is closed
     while let Some(msg) = inbox.recv().await {
+        let _scope = enter_handle(&actor)?;
         let future = actor.handle(msg);
         future.await;
     }
 
     // Cleanup hook - call on_stop when the channel is closed
-    let stop_result = actor.on_stop();
+    let stop_result = actor.on_stop_with_reason(StopReason::SendersDropped);
     #[cfg(feature = "debug-log")]
     if let Err(ref e) = stop_result {
         log::error!("Actor shutdown failed: {e:?}");
@@ -1376,6 +1783,14 @@ where
     if let Err(ref e) = startup_result {
         log::error!("Batch actor startup failed: {e:?}");
     }
+    #[cfg(feature = "defmt")]
+    match &startup_result {
+        Ok(()) => defmt::info!(
+            "batch actor: started, max batch {=usize}",
+            actor.max_batch_size()
+        ),
+        Err(e) => defmt::warn!("batch actor: start failed: {}", e),
+    }
     startup_result?;
 
     // Prepare a static buffer for batching messages
@@ -1386,6 +1801,8 @@ where
     loop {
         // Wait for at least one message
         let first_message = receiver.receive().await;
+        #[cfg(feature = "batch-stats")]
+        let drain_started = embassy_time::Instant::now();
         batch_buffer.clear();
         batch_buffer.push(first_message).ok(); // Safe: buffer is empty
 
@@ -1401,9 +1818,15 @@ where
                 Err(_) => break, // No more messages available
             }
         }
+        #[cfg(feature = "batch-stats")]
+        let drain_time = core::time::Duration::from_micros(drain_started.elapsed().as_micros());
 
         // Process the batch
         actor.handle_batch(&batch_buffer).await;
+        #[cfg(feature = "batch-stats")]
+        if let Some(stats) = actor.batch_stats() {
+            stats.record(batch_buffer.len(), Some(drain_time));
+        }
 
         // Yield control to maintain cooperative scheduling
         #[cfg(feature = "embassy")]
@@ -1413,7 +1836,7 @@ where
     // Cleanup hook (unreachable in embedded)
     #[allow(unreachable_code)]
     {
-        let stop_result = actor.on_stop();
+        let stop_result = actor.on_stop_with_reason(StopReason::SendersDropped);
         #[cfg(feature = "debug-log")]
         if let Err(ref e) = stop_result {
             log::error!("Batch actor shutdown failed: {e:?}");
@@ -1450,9 +1873,21 @@ where
 /// # Errors
 /// Returns `ActorError` if actor startup or shutdown fails.
 #[cfg(feature = "async-tokio")]
-pub async fn batch_actor_task<A>(
+pub async fn batch_actor_task<A>(actor: A, inbox: Inbox<A::Message>) -> Result<(), ActorError>
+where
+    A: BatchActor + Send + 'static,
+    A::Message: Send + 'static,
+{
+    batch_actor_loop(actor, inbox, None).await
+}
+
+/// Runs the Tokio batch loop, draining up to `controller`'s limit per batch if there is
+/// one and up to [`BatchActor::max_batch_size`] otherwise.
+#[cfg(feature = "async-tokio")]
+pub(crate) async fn batch_actor_loop<A>(
     mut actor: A,
     mut inbox: Inbox<A::Message>,
+    mut controller: Option<adaptive_batch::BatchController>,
 ) -> Result<(), ActorError>
 where
     A: BatchActor + Send + 'static,
@@ -1466,25 +1901,41 @@ where
 
     // Main batch processing loop - exit when channel closes
     while let Some(first_msg) = inbox.recv().await {
+        #[cfg(feature = "batch-stats")]
+        let drain_started = std::time::Instant::now();
+
         // Start with the first message
         batch.clear();
         batch.push(first_msg);
 
         // Try to drain additional messages without blocking
-        while batch.len() < actor.max_batch_size() {
+        let limit = controller.as_ref().map_or_else(
+            || actor.max_batch_size(),
+            adaptive_batch::BatchController::limit,
+        );
+        while batch.len() < limit {
             match inbox.try_recv() {
                 Ok(msg) => batch.push(msg),
                 Err(_) => break, // No more messages available right now
             }
         }
+        #[cfg(feature = "batch-stats")]
+        let drain_time = drain_started.elapsed();
+        if let Some(controller) = controller.as_mut() {
+            controller.observe(batch.len(), inbox.len());
+        }
 
         // Process the batch
         let future = actor.handle_batch(&batch);
         future.await;
+        #[cfg(feature = "batch-stats")]
+        if let Some(stats) = actor.batch_stats() {
+            stats.record(batch.len(), Some(drain_time));
+        }
     }
 
     // Cleanup hook - call on_stop when the channel is closed
-    let stop_result = actor.on_stop();
+    let stop_result = actor.on_stop_with_reason(StopReason::SendersDropped);
     #[cfg(feature = "debug-log")]
     if let Err(ref e) = stop_result {
         log::error!("Batch actor shutdown failed: {e:?}");
@@ -1514,6 +1965,7 @@ where
 /// # Errors
 /// Returns `ActorError` if actor startup or shutdown fails.
 #[cfg(all(not(feature = "async-tokio"), not(feature = "async-embassy")))]
+#[allow(clippy::result_large_err)] // ActorError provides detailed failure information
 pub async fn batch_actor_task<A: BatchActor, const N: usize>(
     mut actor: A,
     mut inbox: Inbox<A::Message, N>,
@@ -1524,6 +1976,14 @@ pub async fn batch_actor_task<A: BatchActor, const N: usize>(
     if let Err(ref e) = startup_result {
         log::error!("Batch actor startup failed: {e:?}");
     }
+    #[cfg(feature = "defmt")]
+    match &startup_result {
+        Ok(()) => defmt::info!(
+            "batch actor: started, max batch {=usize}",
+            actor.max_batch_size()
+        ),
+        Err(e) => defmt::warn!("batch actor: start failed: {}", e),
+    }
     startup_result?;
 
     // Prepare a static buffer for batching messages
@@ -1557,6 +2017,11 @@ pub async fn batch_actor_task<A: BatchActor, const N: usize>(
 
         // Process the batch
         actor.handle_batch(&batch_buffer).await;
+        // No clock without an executor, so drain latency is not recorded here.
+        #[cfg(feature = "batch-stats")]
+        if let Some(stats) = actor.batch_stats() {
+            stats.record(batch_buffer.len(), None);
+        }
 
         // Yield control to allow other tasks to run
         yield_control().await;
@@ -1565,7 +2030,7 @@ pub async fn batch_actor_task<A: BatchActor, const N: usize>(
     // Cleanup hook (unreachable in no_std)
     #[allow(unreachable_code)]
     {
-        let stop_result = actor.on_stop();
+        let stop_result = actor.on_stop_with_reason(StopReason::SendersDropped);
         #[cfg(feature = "debug-log")]
         if let Err(ref e) = stop_result {
             log::error!("Batch actor shutdown failed: {e:?}");
@@ -1575,24 +2040,70 @@ pub async fn batch_actor_task<A: BatchActor, const N: usize>(
     }
 }
 
+#[cfg(feature = "async-tokio")]
+pub mod adaptive_batch;
 pub mod address;
 pub mod backpressure;
+#[cfg(feature = "batch-stats")]
+pub mod batch_stats;
+pub mod cooperative;
+pub mod correlation;
+pub mod id;
 pub mod integration;
+#[cfg(not(feature = "async-tokio"))]
+pub mod isr;
+pub mod link;
+#[cfg(feature = "metrics-integration")]
+pub mod metrics_export;
 pub mod panic_handling;
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+pub mod sink;
 pub mod spawn;
+#[cfg(any(feature = "async-tokio", feature = "async-embassy"))]
+pub mod spawn_context;
 pub mod supervision; // Task 5.1: Supervision with Async // Task 5.4: Advanced Error Handling
 
 // Re-export spawn functions for convenience
+#[cfg(feature = "async-tokio")]
+pub use adaptive_batch::{ActorMetrics, AdaptiveBatching};
+#[cfg(all(feature = "async-tokio", feature = "batch-stats"))]
+pub use spawn::spawn_batch_actor_tokio_with_stats;
 #[cfg(feature = "async-embassy")]
 pub use spawn::spawn_counter_actor_embassy;
 #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
 pub use spawn::{
-    spawn_actor_tokio, spawn_batch_actor_tokio, spawn_supervised_actor_tokio,
-    spawn_supervised_batch_actor_tokio,
+    ActorJoinHandle, spawn_actor_tokio, spawn_actor_tokio_with_handle, spawn_batch_actor_tokio,
+    spawn_batch_actor_tokio_adaptive, spawn_statechart_tokio, spawn_statechart_tokio_with_handle,
+    spawn_supervised_actor_tokio, spawn_supervised_batch_actor_tokio,
 };
+#[cfg(not(feature = "async-embassy"))]
+pub use spawn::{ActorLoop, Coalesced, SingleMessage, spawn_actor};
+#[cfg(any(feature = "async-tokio", feature = "async-embassy"))]
+pub use spawn_context::{ParentActor, SpawnContext};
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+pub use spawn_context::{parent_actor_task, spawn_parent_actor_tokio};
+
+#[cfg(feature = "batch-stats")]
+pub use batch_stats::{BatchStats, BatchStatsRecorder};
+pub use cooperative::{Cooperative, CooperativeActor, Yielder};
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+pub use correlation::TracedActor;
+pub use correlation::{CorrelationScope, Traced};
+pub use id::ActorId;
+pub use integration::{EventsOnly, StatechartActor, WithQueries};
+#[cfg(feature = "async-tokio")]
+pub use integration::{StatechartMessage, StatechartQuery};
+#[cfg(not(feature = "async-tokio"))]
+pub use isr::IsrSender;
+#[cfg(all(not(feature = "async-tokio"), feature = "critical-section"))]
+pub use isr::SharedIsrSender;
+pub use link::{LinkAction, LinkExit};
 
 // Re-export supervision types for convenience (Task 5.1 & 5.4)
-pub use supervision::{SupervisorActor, SupervisorError, SupervisorTimer};
+pub use supervision::{
+    ChildSnapshot, CircuitState, RootEscalation, RootEscalationAction, SupervisionSnapshot,
+    SupervisorActor, SupervisorError, SupervisorTimer, TimelineId,
+};
 
 // Re-export panic handling utilities for convenience (Task 5.4)
 pub use panic_handling::create_controlled_failure;
@@ -1668,6 +2179,100 @@ mod tests {
         assert!(actor.on_stop().is_ok());
     }
 
+    #[test]
+    fn reentrancy_guard_allows_sequential_handles() {
+        let guard = ReentrancyGuard::new();
+        assert!(TestActor::new().reentrancy_guard().is_none());
+
+        let scope = guard.enter().expect("first handle enters");
+        assert!(guard.is_handling());
+        drop(scope);
+        assert!(!guard.is_handling());
+        assert!(guard.enter().is_ok());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "re-entered")]
+    fn reentrancy_guard_panics_on_overlap_in_debug() {
+        let guard = ReentrancyGuard::new();
+        let _first = guard.enter();
+        let _second = guard.enter();
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn reentrancy_guard_rejects_overlap_in_release() {
+        let guard = ReentrancyGuard::new();
+        let _first = guard.enter();
+        assert_eq!(guard.enter().unwrap_err(), ActorError::Custom("reentrancy"));
+    }
+
+    #[cfg(feature = "async-tokio")]
+    #[tokio::test]
+    async fn actor_task_rejects_overlapping_handle_calls() {
+        use core::future::Future;
+        use core::pin::Pin;
+
+        static GUARD: ReentrancyGuard = ReentrancyGuard::new();
+
+        // Two actor values standing in for the same shared resource.
+        struct SharedResourceActor {
+            release: Option<tokio::sync::oneshot::Receiver<()>>,
+        }
+
+        impl Actor for SharedResourceActor {
+            type Message = u32;
+            type Future<'a>
+                = Pin<Box<dyn Future<Output = ()> + Send + 'a>>
+            where
+                Self: 'a;
+
+            fn handle(&mut self, _msg: u32) -> Self::Future<'_> {
+                Box::pin(async move {
+                    if let Some(release) = self.release.take() {
+                        let _ = release.await;
+                    }
+                })
+            }
+
+            fn reentrancy_guard(&self) -> Option<&'static ReentrancyGuard> {
+                Some(&GUARD)
+            }
+        }
+
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel();
+        let (slow_outbox, slow_inbox) = create_mailbox::<u32>(4);
+        let slow = tokio::spawn(actor_task(
+            SharedResourceActor {
+                release: Some(release_rx),
+            },
+            slow_inbox,
+        ));
+        slow_outbox.send(1).await.expect("slow actor is running");
+        while !GUARD.is_handling() {
+            tokio::task::yield_now().await;
+        }
+
+        let (outbox, inbox) = create_mailbox::<u32>(4);
+        let overlapping = tokio::spawn(actor_task(SharedResourceActor { release: None }, inbox));
+        outbox.send(2).await.expect("second actor is running");
+        let outcome = overlapping.await;
+        if cfg!(debug_assertions) {
+            assert!(outcome.expect_err("debug builds panic").is_panic());
+        } else {
+            assert_eq!(
+                outcome.expect("task completes"),
+                Err(ActorError::Custom("reentrancy"))
+            );
+        }
+
+        release_tx.send(()).expect("slow handle is waiting");
+        drop(slow_outbox);
+        assert_eq!(slow.await.expect("slow actor completes"), Ok(()));
+        assert!(!GUARD.is_handling());
+    }
+
     #[cfg(all(not(feature = "std"), not(feature = "embassy")))]
     #[test]
     fn yield_control_compiles() {
@@ -3,7 +3,7 @@
 //! Based on research from Actix, Ractor, and Bastion panic handling patterns.
 //! Provides unified panic information extraction across Tokio and Embassy runtimes.
 
-use super::ActorError;
+use super::{ActorError, ActorId};
 use core::any::Any;
 
 // Platform-dual string support for panic information
@@ -171,7 +171,7 @@ pub fn capture_panic_info_from_payload(
 #[cfg(feature = "async-tokio")]
 pub fn capture_panic_info_from_payload_with_id(
     panic_payload: &Box<dyn std::any::Any + Send>,
-    actor_id: impl Into<String>,
+    actor_id: ActorId,
 ) -> ActorError {
     let mut error = capture_panic_info_from_payload(panic_payload);
 
@@ -180,7 +180,7 @@ pub fn capture_panic_info_from_payload_with_id(
         ..
     } = error
     {
-        *id = Some(actor_id.into());
+        *id = Some(actor_id);
     }
 
     error
@@ -193,7 +193,7 @@ pub fn capture_panic_info_from_payload_with_id(
 #[cfg(feature = "async-tokio")]
 pub fn capture_panic_info_with_id(
     join_error: tokio::task::JoinError,
-    actor_id: impl Into<String>,
+    actor_id: ActorId,
 ) -> ActorError {
     let mut error = capture_panic_info(join_error);
 
@@ -202,7 +202,7 @@ pub fn capture_panic_info_with_id(
         ..
     } = error
     {
-        *id = Some(actor_id.into());
+        *id = Some(actor_id);
     }
 
     error
@@ -232,7 +232,7 @@ pub fn simulate_panic_for_testing(message: &str) -> ActorError {
 
 /// Embassy-specific panic simulation with actor ID context.
 #[cfg(feature = "async-embassy")]
-pub fn simulate_panic_with_id(message: &str, actor_id: impl AsRef<str>) -> ActorError {
+pub fn simulate_panic_with_id(message: &str, actor_id: ActorId) -> ActorError {
     let mut error = simulate_panic_for_testing(message);
 
     if let ActorError::Panic {
@@ -240,17 +240,7 @@ pub fn simulate_panic_with_id(message: &str, actor_id: impl AsRef<str>) -> Actor
         ..
     } = error
     {
-        #[cfg(any(feature = "std", feature = "alloc"))]
-        {
-            *id = Some(actor_id.as_ref().to_string());
-        }
-
-        #[cfg(not(any(feature = "std", feature = "alloc")))]
-        {
-            let mut s = HeaplessString::<128>::new();
-            push_str_truncate(&mut s, actor_id.as_ref());
-            *id = Some(s);
-        }
+        *id = Some(actor_id);
     }
 
     error
@@ -261,12 +251,12 @@ pub fn simulate_panic_with_id(message: &str, actor_id: impl AsRef<str>) -> Actor
 /// This function can be used by actors to signal controlled failures that
 /// should be treated like panics by the supervision system. Useful for
 /// implementing graceful degradation or testing supervision logic.
-pub fn create_controlled_failure(message: &str, actor_id: Option<&str>) -> ActorError {
+pub fn create_controlled_failure(message: &str, actor_id: Option<ActorId>) -> ActorError {
     #[cfg(any(feature = "std", feature = "alloc"))]
     {
         ActorError::Panic {
             message: Some(message.to_string()),
-            actor_id: actor_id.map(|id| id.to_string()),
+            actor_id,
         }
     }
 
@@ -275,15 +265,9 @@ pub fn create_controlled_failure(message: &str, actor_id: Option<&str>) -> Actor
         let mut panic_msg = HeaplessString::<128>::new();
         push_str_truncate(&mut panic_msg, message);
 
-        let actor_id_string = actor_id.map(|id| {
-            let mut s = HeaplessString::<128>::new();
-            push_str_truncate(&mut s, id);
-            s
-        });
-
         ActorError::Panic {
             message: Some(panic_msg),
-            actor_id: actor_id_string,
+            actor_id,
         }
     }
 }
@@ -298,14 +282,14 @@ mod tests {
 
     #[test]
     fn create_controlled_failure_works() {
-        let error = create_controlled_failure("test failure", Some("test_actor"));
+        let error = create_controlled_failure("test failure", Some(ActorId::new(42)));
 
         match error {
             ActorError::Panic { message, actor_id } => {
                 assert!(message.is_some());
                 assert!(actor_id.is_some());
                 assert!(message.unwrap().contains("test failure"));
-                assert!(actor_id.unwrap().contains("test_actor"));
+                assert_eq!(actor_id, Some(ActorId::new(42)));
             }
             _ => panic!("Expected Panic variant"),
         }
@@ -344,14 +328,14 @@ mod tests {
     #[cfg(feature = "async-embassy")]
     #[test]
     fn embassy_panic_simulation_with_id_works() {
-        let error = simulate_panic_with_id("embassy test panic", "embassy_actor");
+        let error = simulate_panic_with_id("embassy test panic", ActorId::new(3));
 
         match error {
             ActorError::Panic { message, actor_id } => {
                 assert!(message.is_some());
                 assert!(actor_id.is_some());
                 assert!(message.unwrap().contains("embassy test panic"));
-                assert!(actor_id.unwrap().contains("embassy_actor"));
+                assert_eq!(actor_id, Some(ActorId::new(3)));
             }
             _ => panic!("Expected Panic variant"),
         }
@@ -388,7 +372,7 @@ mod tests {
                 "Should end at char boundary"
             );
 
-            let error = create_controlled_failure(&input, Some("actor_é€😀"));
+            let error = create_controlled_failure(&input, Some(ActorId::new(1)));
             match error {
                 ActorError::Panic {
                     message,
@@ -443,7 +427,7 @@ mod tests {
             let base = "a".repeat(120);
             let multi = "é€😀";
             let input = format!("{base}{multi}");
-            let error = create_controlled_failure(&input, Some("actor_é€😀"));
+            let error = create_controlled_failure(&input, Some(ActorId::new(1)));
             match error {
                 ActorError::Panic {
                     message,
@@ -604,18 +588,14 @@ mod tests {
             let base = "d".repeat(120);
             let multi = "é€😀";
             let input = format!("{base}{multi}");
-            let error = simulate_panic_with_id(&input, "actor_é€😀");
+            let error = simulate_panic_with_id(&input, ActorId::new(1));
             match error {
                 ActorError::Panic { message, actor_id } => {
                     let msg = message.unwrap();
                     let msg_bytes = msg.as_bytes();
                     assert!(from_utf8(msg_bytes).is_ok());
                     assert!(msg.is_char_boundary(msg.len()));
-                    if let Some(actor) = actor_id {
-                        let actor_bytes = actor.as_bytes();
-                        assert!(from_utf8(actor_bytes).is_ok());
-                        assert!(actor.is_char_boundary(actor.len()));
-                    }
+                    assert_eq!(actor_id, Some(ActorId::new(1)));
                 }
                 _ => panic!("Expected Panic variant"),
             }
@@ -629,18 +609,14 @@ mod tests {
             let multi = "é€😀";
             let mut input = base.clone();
             input.push_str(multi).unwrap();
-            let error = simulate_panic_with_id(&input, "actor_é€😀");
+            let error = simulate_panic_with_id(&input, ActorId::new(1));
             match error {
                 ActorError::Panic { message, actor_id } => {
                     let msg = message.unwrap();
                     let msg_bytes = msg.as_bytes();
                     assert!(from_utf8(msg_bytes).is_ok());
                     assert!(msg.is_char_boundary(msg.len()));
-                    if let Some(actor) = actor_id {
-                        let actor_bytes = actor.as_bytes();
-                        assert!(from_utf8(actor_bytes).is_ok());
-                        assert!(actor.is_char_boundary(actor.len()));
-                    }
+                    assert_eq!(actor_id, Some(ActorId::new(1)));
                 }
                 _ => panic!("Expected Panic variant"),
             }
@@ -0,0 +1,174 @@
+//! A [`futures::Sink`] over an actor's mailbox, for feeding actors from stream pipelines.
+//!
+//! [`Address::into_sink`](super::address::Address::into_sink) turns an address into an
+//! [`AddressSink`], so a stream can be forwarded straight into an actor:
+//!
+//! ```rust,no_run
+//! # async fn demo(addr: lit_bit_core::actor::address::Address<u32>) {
+//! use futures::StreamExt;
+//!
+//! let readings = futures::stream::iter([1, 2, 3]).map(Ok);
+//! readings.forward(addr.into_sink()).await.expect("actor stopped early");
+//! # }
+//! ```
+//!
+//! # Back-pressure and ordering
+//!
+//! `poll_ready` reserves a slot in the mailbox and stays pending while the mailbox is full,
+//! so a fast stream is slowed down to the actor's pace instead of buffering without bound.
+//! `start_send` places the item in the reserved slot, which means items are in the mailbox,
+//! in the order they were sent, as soon as `start_send` returns; `poll_flush` has nothing
+//! left to do. Items from other senders of the same actor may interleave with them.
+//!
+//! # Closed mailboxes
+//!
+//! Once the actor has stopped, `poll_ready` fails with [`SendError::Closed`]. Because
+//! `poll_ready` runs before the sink is handed an item, the error carries `()` rather than
+//! a message: the item the stream was about to send stays with the caller. An item
+//! accepted by `start_send` is dropped if the actor stops before receiving it, just as
+//! with [`Address::send`](super::address::Address::send).
+//!
+//! `poll_close` releases the sink's sender. The mailbox closes once every other address to
+//! the actor is gone too.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use tokio::sync::mpsc::{OwnedPermit, Sender};
+
+use super::backpressure::SendError;
+
+type Reservation<Event> = Pin<
+    Box<
+        dyn Future<Output = Result<OwnedPermit<Event>, tokio::sync::mpsc::error::SendError<()>>>
+            + Send,
+    >,
+>;
+
+/// A [`futures::Sink`] that sends to an actor's mailbox; see the [module docs](self).
+pub struct AddressSink<Event> {
+    /// `None` once the mailbox has been found closed or the sink has been closed.
+    sender: Option<Sender<Event>>,
+    reservation: Option<Reservation<Event>>,
+    permit: Option<OwnedPermit<Event>>,
+}
+
+impl<Event: Send + 'static> AddressSink<Event> {
+    pub(super) fn new(sender: Sender<Event>) -> Self {
+        Self {
+            sender: Some(sender),
+            reservation: None,
+            permit: None,
+        }
+    }
+}
+
+impl<Event> core::fmt::Debug for AddressSink<Event> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AddressSink")
+            .field("closed", &self.sender.is_none())
+            .field("ready", &self.permit.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Event: Send + 'static> futures::Sink<Event> for AddressSink<Event> {
+    type Error = SendError<()>;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = &mut *self;
+        if this.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        let Some(sender) = &this.sender else {
+            return Poll::Ready(Err(SendError::Closed(())));
+        };
+        let reservation = this
+            .reservation
+            .get_or_insert_with(|| Box::pin(sender.clone().reserve_owned()));
+        let Poll::Ready(reserved) = reservation.as_mut().poll(cx) else {
+            return Poll::Pending;
+        };
+        this.reservation = None;
+        match reserved {
+            Ok(permit) => {
+                this.permit = Some(permit);
+                Poll::Ready(Ok(()))
+            }
+            Err(_) => {
+                this.sender = None;
+                Poll::Ready(Err(SendError::Closed(())))
+            }
+        }
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
+        if let Some(permit) = self.permit.take() {
+            permit.send(item);
+            return Ok(());
+        }
+        // Called without a successful `poll_ready`: only send if there is room right now.
+        let Some(sender) = &self.sender else {
+            return Err(SendError::Closed(()));
+        };
+        sender.try_send(item).map_err(|err| match err {
+            tokio::sync::mpsc::error::TrySendError::Full(_) => SendError::Full(()),
+            tokio::sync::mpsc::error::TrySendError::Closed(_) => SendError::Closed(()),
+        })
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.permit = None;
+        self.reservation = None;
+        self.sender = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::address::Address;
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+
+    #[tokio::test]
+    async fn forwarding_waits_for_mailbox_capacity() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(2);
+        let addr = Address::from_tokio_sender(sender);
+
+        let forward = tokio::spawn(
+            futures::stream::iter(0..10u32)
+                .map(Ok)
+                .forward(addr.into_sink()),
+        );
+
+        let mut received = Vec::new();
+        while let Some(n) = receiver.recv().await {
+            // Never more queued than the mailbox holds.
+            assert!(receiver.len() <= 2);
+            received.push(n);
+        }
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+        assert_eq!(forward.await.unwrap(), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn closed_mailbox_fails_before_taking_the_item() {
+        let (sender, receiver) = tokio::sync::mpsc::channel::<u32>(1);
+        let mut sink = Address::from_tokio_sender(sender).into_sink();
+        sink.send(1).await.expect("mailbox is open");
+
+        drop(receiver);
+        assert_eq!(sink.send(2).await, Err(SendError::Closed(())));
+        // Stays closed.
+        assert_eq!(sink.send(3).await, Err(SendError::Closed(())));
+    }
+}
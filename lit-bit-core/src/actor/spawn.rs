@@ -1,7 +1,7 @@
 //! Actor spawning functions for Embassy and Tokio runtimes.
 
 use super::ActorError;
-use super::ActorString;
+use super::ActorId;
 
 // Conditional Box import for any code that uses alloc
 #[cfg(any(feature = "std", feature = "alloc"))]
@@ -13,7 +13,13 @@ use alloc::boxed::Box;
 use super::{Actor, BatchActor};
 
 #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
-use super::{Actor, BatchActor, actor_task, batch_actor_task, create_mailbox};
+use super::{Actor, BatchActor, Inbox, actor_task, batch_actor_task, create_mailbox};
+
+#[cfg(all(not(feature = "async-tokio"), not(feature = "async-embassy")))]
+use super::{Actor, BatchActor, Inbox, Outbox, actor_task, batch_actor_task};
+
+#[cfg(all(not(feature = "async-tokio"), not(feature = "async-embassy")))]
+use super::address::Address;
 
 #[cfg(any(feature = "async-embassy", feature = "async-tokio"))]
 use super::address::Address;
@@ -43,28 +49,19 @@ impl From<crate::actor::supervision::SupervisorError> for SpawnError {
     }
 }
 
-/// Helper to create a SupervisorMessage::ChildPanicked with boxed error and actor ID conversion.
+/// Helper to create a SupervisorMessage::ChildPanicked with boxed error.
 #[inline]
 fn create_supervisor_panic_message(
     error: ActorError,
-    actor_id: &str,
-) -> crate::actor::SupervisorMessage<ActorString> {
-    #[cfg(any(feature = "std", feature = "alloc"))]
-    let actor_id_string: ActorString = ActorString::from(actor_id);
-    #[cfg(not(any(feature = "std", feature = "alloc")))]
-    let actor_id_string: ActorString = {
-        let mut s = ActorString::new();
-        let _ = s.push_str(actor_id);
-        s
-    };
-
+    actor_id: ActorId,
+) -> crate::actor::SupervisorMessage<ActorId> {
     #[cfg(any(feature = "std", feature = "alloc"))]
     let boxed_error = Box::new(error);
     #[cfg(not(any(feature = "std", feature = "alloc")))]
     let boxed_error = error;
 
     crate::actor::SupervisorMessage::ChildPanicked {
-        id: actor_id_string,
+        id: actor_id,
         error: boxed_error,
     }
 }
@@ -396,16 +393,289 @@ macro_rules! static_embassy_channel {
 // Tokio spawning function (existing implementation)
 #[cfg(feature = "async-tokio")]
 pub fn spawn_actor_tokio<A>(actor: A, capacity: usize) -> Address<A::Message>
+where
+    A: Actor + Send + 'static,
+    A::Message: Send + 'static,
+{
+    spawn_actor_tokio_with_handle(actor, capacity).0
+}
+
+/// Spawns an actor on the Tokio runtime, like [`spawn_actor_tokio`], and also returns the
+/// handle of the actor task.
+///
+/// The handle resolves to `Err(ActorError::LinkedExit { .. })` when the actor terminates
+/// because an actor [linked](super::address::Address::link) to it failed.
+///
+/// # Arguments
+/// * `actor` - The actor instance to spawn
+/// * `capacity` - Mailbox capacity for the actor
+#[cfg(feature = "async-tokio")]
+pub fn spawn_actor_tokio_with_handle<A>(
+    actor: A,
+    capacity: usize,
+) -> (Address<A::Message>, ActorJoinHandle)
 where
     A: Actor + Send + 'static,
     A::Message: Send + 'static,
 {
     let (outbox, inbox) = create_mailbox::<A::Message>(capacity);
+    let address = Address::from_tokio_sender(outbox);
 
     // Spawn on current Tokio runtime
-    tokio::spawn(actor_task::<A>(actor, inbox));
+    let handle = tokio::spawn(super::link::linked_actor_task::<A>(
+        actor,
+        inbox,
+        address.link_cell(),
+    ));
+    (address, handle)
+}
 
-    // Create Address from the Tokio sender
+/// Spawns a statechart as an actor on the Tokio runtime.
+///
+/// Wraps `machine` in a [`StatechartActor`](super::integration::StatechartActor) and
+/// spawns it with [`spawn_actor_tokio`]. Every message sent to the returned `Address`
+/// is fed into the machine as an event. Use `StatechartActor::with_output` and
+/// [`spawn_actor_tokio`] directly when you need to observe the results.
+///
+/// # Arguments
+/// * `machine` - The state machine to run
+/// * `capacity` - Mailbox capacity for the actor
+#[cfg(feature = "async-tokio")]
+pub fn spawn_statechart_tokio<M, const N_ACTIVE: usize>(
+    machine: M,
+    capacity: usize,
+) -> Address<M::Event>
+where
+    M: crate::StateMachine<N_ACTIVE> + Send + 'static,
+    M::Event: Send + 'static,
+{
+    spawn_actor_tokio(
+        super::integration::StatechartActor::<M, N_ACTIVE>::new(machine),
+        capacity,
+    )
+}
+
+/// Handle to an actor task spawned on Tokio; resolves to the actor loop's result.
+#[cfg(feature = "async-tokio")]
+pub type ActorJoinHandle = tokio::task::JoinHandle<Result<(), ActorError>>;
+
+/// Spawns a statechart as an actor on the Tokio runtime, like [`spawn_statechart_tokio`],
+/// and also returns the handle of the actor task.
+///
+/// This is what a `statechart!` machine's generated `spawn_tokio` calls with the chart's
+/// declared `mailbox_capacity`.
+///
+/// # Arguments
+/// * `machine` - The state machine to run
+/// * `capacity` - Mailbox capacity for the actor
+#[cfg(feature = "async-tokio")]
+pub fn spawn_statechart_tokio_with_handle<M, const N_ACTIVE: usize>(
+    machine: M,
+    capacity: usize,
+) -> (Address<M::Event>, ActorJoinHandle)
+where
+    M: crate::StateMachine<N_ACTIVE> + Send + 'static,
+    M::Event: Send + 'static,
+{
+    spawn_actor_tokio_with_handle(
+        super::integration::StatechartActor::<M, N_ACTIVE>::new(machine),
+        capacity,
+    )
+}
+
+/// Spawns a `statechart!` machine as an actor on the Embassy executor, with a mailbox of
+/// the chart's declared `mailbox_capacity`.
+///
+/// Embassy tasks cannot be generic, so the macro declares a task and a static channel for
+/// the machine type at the call site. Each expansion may spawn its task once. Pass
+/// `capacity = N` to override the declared capacity; the chart then needs no
+/// `mailbox_capacity` at all.
+///
+/// Expands to a `Result<Address<Event, N>, embassy_executor::SpawnError>`. The calling
+/// crate needs `embassy-executor`, `embassy-sync` and `static_cell`, as for
+/// [`static_embassy_channel!`](crate::static_embassy_channel).
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let machine = TrafficLight::new(TrafficContext::default(), &TrafficEvent::Timer)?;
+/// let address = lit_bit_core::spawn_statechart_embassy!(spawner, TrafficLight, machine)?;
+/// address.send(TrafficEvent::Timer).await;
+/// ```
+#[cfg(feature = "async-embassy")]
+#[macro_export]
+macro_rules! spawn_statechart_embassy {
+    ($spawner:expr, $machine_type:ty, $machine:expr) => {
+        $crate::spawn_statechart_embassy!(
+            $spawner,
+            $machine_type,
+            $machine,
+            capacity = <$machine_type>::MAILBOX_CAPACITY
+        )
+    };
+    ($spawner:expr, $machine_type:ty, $machine:expr, capacity = $capacity:expr) => {{
+        const CAPACITY: usize = $capacity;
+
+        #[embassy_executor::task]
+        async fn statechart_actor_task(
+            actor: $crate::actor::StatechartActor<$machine_type>,
+            receiver: embassy_sync::channel::Receiver<
+                'static,
+                embassy_sync::blocking_mutex::raw::NoopRawMutex,
+                <$machine_type as $crate::StateMachine>::Event,
+                CAPACITY,
+            >,
+        ) {
+            let _ = $crate::actor::actor_task_embassy(actor, receiver).await;
+        }
+
+        let (sender, receiver) = $crate::static_embassy_channel!(
+            STATECHART_MAILBOX: <$machine_type as $crate::StateMachine>::Event,
+            CAPACITY
+        );
+        $spawner
+            .spawn(statechart_actor_task(
+                $crate::actor::StatechartActor::new($machine),
+                receiver,
+            ))
+            .map(|()| $crate::Address::from_embassy_sender(sender))
+    }};
+}
+
+/// Marker for the [`ActorLoop`] that hands an [`Actor`] one message at a time.
+#[cfg(not(feature = "async-embassy"))]
+#[derive(Debug)]
+pub enum SingleMessage {}
+
+/// Marker for the [`ActorLoop`] that coalesces queued messages into
+/// [`BatchActor::handle_batch`] calls.
+#[cfg(not(feature = "async-embassy"))]
+#[derive(Debug)]
+pub enum Coalesced {}
+
+/// The message loop [`spawn_actor`] runs an actor with.
+///
+/// Every [`Actor`] implements `ActorLoop<SingleMessage>`, which runs [`actor_task`], and
+/// every [`BatchActor`] implements `ActorLoop<Coalesced>`, which runs [`batch_actor_task`]:
+/// each wake-up drains whatever is queued, up to [`BatchActor::max_batch_size`], into a
+/// single `handle_batch` call. `spawn_actor` leaves `Mode` to type inference, so the loop
+/// follows the trait the actor implements. An actor implementing both traits, such as
+/// [`StatechartActor`](super::StatechartActor), is ambiguous and must name the mode, e.g.
+/// `spawn_actor::<_, Coalesced>(actor, 32)` on Tokio.
+#[cfg(not(feature = "async-embassy"))]
+pub trait ActorLoop<Mode>: Send + Sized {
+    /// The message type the actor's mailbox carries.
+    type Message: Send + 'static;
+
+    /// Runs the actor until its mailbox closes; on `no_std` the loop never returns after a
+    /// successful start.
+    #[cfg(not(feature = "async-tokio"))]
+    fn run<const N: usize>(
+        self,
+        inbox: Inbox<Self::Message, N>,
+    ) -> impl Future<Output = Result<(), ActorError>>;
+
+    /// Runs the actor until its mailbox closes.
+    #[cfg(feature = "async-tokio")]
+    fn run(
+        self,
+        inbox: Inbox<Self::Message>,
+    ) -> impl Future<Output = Result<(), ActorError>> + Send;
+}
+
+#[cfg(not(feature = "async-embassy"))]
+impl<A: Actor + 'static> ActorLoop<SingleMessage> for A {
+    type Message = A::Message;
+
+    #[cfg(not(feature = "async-tokio"))]
+    fn run<const N: usize>(
+        self,
+        inbox: Inbox<Self::Message, N>,
+    ) -> impl Future<Output = Result<(), ActorError>> {
+        actor_task(self, inbox)
+    }
+
+    #[cfg(feature = "async-tokio")]
+    fn run(
+        self,
+        inbox: Inbox<Self::Message>,
+    ) -> impl Future<Output = Result<(), ActorError>> + Send {
+        actor_task(self, inbox)
+    }
+}
+
+#[cfg(not(feature = "async-embassy"))]
+impl<A: BatchActor + 'static> ActorLoop<Coalesced> for A {
+    type Message = A::Message;
+
+    #[cfg(not(feature = "async-tokio"))]
+    fn run<const N: usize>(
+        self,
+        inbox: Inbox<Self::Message, N>,
+    ) -> impl Future<Output = Result<(), ActorError>> {
+        batch_actor_task(self, inbox)
+    }
+
+    #[cfg(feature = "async-tokio")]
+    fn run(
+        self,
+        inbox: Inbox<Self::Message>,
+    ) -> impl Future<Output = Result<(), ActorError>> + Send {
+        batch_actor_task(self, inbox)
+    }
+}
+
+/// Connects an actor to a static mailbox and returns its address together with the
+/// message loop [`ActorLoop`] picks for it: per message for an [`Actor`], coalesced for a
+/// [`BatchActor`]. Run the loop on your executor.
+///
+/// ```rust,no_run
+/// # use lit_bit_core::actor::{BatchActor, create_mailbox, spawn_actor};
+/// struct Sampler;
+///
+/// impl BatchActor for Sampler {
+///     type Message = u16;
+///     type Future<'a> = core::future::Ready<()> where Self: 'a;
+///
+///     fn handle_batch(&mut self, samples: &[u16]) -> Self::Future<'_> {
+///         core::future::ready(())
+///     }
+/// }
+///
+/// lit_bit_core::define_static_mailbox!(SAMPLES, u16, 16);
+/// let (outbox, inbox) = create_mailbox(&SAMPLES);
+/// let (address, task) = spawn_actor(Sampler, outbox, inbox);
+/// ```
+#[cfg(all(not(feature = "async-tokio"), not(feature = "async-embassy")))]
+pub fn spawn_actor<A, Mode, const N: usize>(
+    actor: A,
+    outbox: Outbox<A::Message, N>,
+    inbox: Inbox<A::Message, N>,
+) -> (
+    Address<A::Message, N>,
+    impl Future<Output = Result<(), ActorError>>,
+)
+where
+    A: ActorLoop<Mode>,
+{
+    (Address::from_producer(outbox), actor.run(inbox))
+}
+
+/// Spawns an actor on the Tokio runtime with the message loop [`ActorLoop`] picks for it:
+/// per message for an [`Actor`] (like [`spawn_actor_tokio`]), coalesced for a
+/// [`BatchActor`] (like [`spawn_batch_actor_tokio`]).
+///
+/// # Arguments
+/// * `actor` - The actor instance to spawn
+/// * `capacity` - Mailbox capacity for the actor
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+pub fn spawn_actor<A, Mode>(actor: A, capacity: usize) -> Address<A::Message>
+where
+    A: ActorLoop<Mode> + 'static,
+    Mode: 'static,
+{
+    let (outbox, inbox) = create_mailbox::<A::Message>(capacity);
+    tokio::spawn(actor.run(inbox));
     Address::from_tokio_sender(outbox)
 }
 
@@ -467,6 +737,138 @@ where
     Address::from_tokio_sender(outbox)
 }
 
+/// Spawns a batch actor on the Tokio runtime whose drain limit adapts to its queue depth.
+///
+/// Works like [`spawn_batch_actor_tokio`], but each batch drains up to a limit that grows
+/// while messages pile up and shrinks when the mailbox runs dry, within `batching`'s
+/// bounds; see [`adaptive_batch`](super::adaptive_batch). The actor's own
+/// [`BatchActor::max_batch_size`] is not used. The returned [`ActorMetrics`] shows the
+/// current limit.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "async-tokio")]
+/// # async fn run<A: lit_bit_core::actor::BatchActor<Message = u32> + Send + 'static>(actor: A) {
+/// use lit_bit_core::actor::AdaptiveBatching;
+/// use lit_bit_core::actor::spawn::spawn_batch_actor_tokio_adaptive;
+///
+/// let (address, metrics) = spawn_batch_actor_tokio_adaptive(actor, 256, AdaptiveBatching::new(1, 128));
+/// // ... send load to `address` ...
+/// println!("draining up to {} messages per wake-up", metrics.batch_limit());
+/// # }
+/// ```
+///
+/// [`ActorMetrics`]: super::adaptive_batch::ActorMetrics
+#[cfg(feature = "async-tokio")]
+pub fn spawn_batch_actor_tokio_adaptive<A>(
+    actor: A,
+    capacity: usize,
+    batching: super::adaptive_batch::AdaptiveBatching,
+) -> (
+    Address<A::Message>,
+    std::sync::Arc<super::adaptive_batch::ActorMetrics>,
+)
+where
+    A: BatchActor + Send + 'static,
+    A::Message: Send + 'static,
+{
+    let (outbox, inbox) = create_mailbox::<A::Message>(capacity);
+    let metrics = std::sync::Arc::new(super::adaptive_batch::ActorMetrics::default());
+    let controller =
+        super::adaptive_batch::BatchController::new(batching, std::sync::Arc::clone(&metrics));
+    tokio::spawn(super::batch_actor_loop::<A>(actor, inbox, Some(controller)));
+    (Address::from_tokio_sender(outbox), metrics)
+}
+
+/// Spawns a batch actor on the Tokio runtime and records its queue-drain statistics.
+///
+/// Works like [`spawn_batch_actor_tokio`], but every batch is recorded in a fresh
+/// [`BatchStatsRecorder`](super::batch_stats::BatchStatsRecorder), which is returned
+/// alongside the address. The actor's own [`BatchActor::batch_stats`] is not used.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(all(feature = "async-tokio", feature = "batch-stats"))]
+/// # async fn tune<A: lit_bit_core::actor::BatchActor<Message = u32> + 'static>(actor: A) {
+/// use lit_bit_core::actor::spawn::spawn_batch_actor_tokio_with_stats;
+///
+/// let (address, stats) = spawn_batch_actor_tokio_with_stats(actor, 256);
+/// // ... send load to `address` ...
+/// let stats = stats.snapshot();
+/// println!("{} single-message wake-ups", stats.single_message_wakeups());
+/// println!("suggested max_batch_size: {}", stats.suggest_batch_size());
+/// # }
+/// ```
+#[cfg(all(feature = "async-tokio", feature = "batch-stats"))]
+pub fn spawn_batch_actor_tokio_with_stats<A>(
+    actor: A,
+    capacity: usize,
+) -> (
+    Address<A::Message>,
+    std::sync::Arc<super::batch_stats::BatchStatsRecorder>,
+)
+where
+    A: BatchActor + Send + 'static,
+    A::Message: Send + 'static,
+{
+    let stats = std::sync::Arc::new(super::batch_stats::BatchStatsRecorder::new());
+    let actor = RecordingBatchActor {
+        actor,
+        stats: std::sync::Arc::clone(&stats),
+    };
+    (spawn_batch_actor_tokio(actor, capacity), stats)
+}
+
+/// Batch actor wrapper that reports its batches to a shared recorder.
+#[cfg(all(feature = "async-tokio", feature = "batch-stats"))]
+struct RecordingBatchActor<A> {
+    actor: A,
+    stats: std::sync::Arc<super::batch_stats::BatchStatsRecorder>,
+}
+
+#[cfg(all(feature = "async-tokio", feature = "batch-stats"))]
+impl<A: BatchActor> BatchActor for RecordingBatchActor<A> {
+    type Message = A::Message;
+    type Future<'a>
+        = A::Future<'a>
+    where
+        Self: 'a;
+
+    fn handle_batch(&mut self, messages: &[Self::Message]) -> Self::Future<'_> {
+        self.actor.handle_batch(messages)
+    }
+
+    fn max_batch_size(&self) -> usize {
+        self.actor.max_batch_size()
+    }
+
+    fn batch_stats(&self) -> Option<&super::batch_stats::BatchStatsRecorder> {
+        Some(&self.stats)
+    }
+
+    fn on_start(&mut self) -> Result<(), ActorError> {
+        self.actor.on_start()
+    }
+
+    fn on_stop(self) -> Result<(), ActorError> {
+        self.actor.on_stop()
+    }
+
+    fn on_stop_with_reason(self, reason: super::StopReason) -> Result<(), ActorError> {
+        self.actor.on_stop_with_reason(reason)
+    }
+
+    fn on_panic(&self, info: &core::panic::PanicInfo) -> crate::actor::RestartStrategy {
+        self.actor.on_panic(info)
+    }
+
+    fn on_restart(&mut self) -> Result<(), ActorError> {
+        self.actor.on_restart()
+    }
+}
+
 /// Spawns a supervised actor on the Tokio runtime.
 ///
 /// This function spawns an actor under supervision, registering it with a supervisor
@@ -479,6 +881,8 @@ where
 /// - Configurable restart strategies (OneForOne, OneForAll, RestForOne)
 /// - Rate limiting to prevent restart loops
 /// - JoinHandle monitoring for failure detection
+/// - Custom restart policies through the supervisor's
+///   [`with_panic_analyzer`](crate::actor::supervision::SupervisorActor::with_panic_analyzer)
 ///
 /// # Arguments
 /// * `actor` - The actor instance to spawn under supervision
@@ -531,20 +935,26 @@ where
     ChildId: Clone + PartialEq + core::fmt::Debug + core::hash::Hash + Eq,
 {
     let (outbox, inbox) = create_mailbox::<A::Message>(capacity);
+    let address = Address::from_tokio_sender(outbox);
 
     // Spawn on current Tokio runtime
-    let join_handle = tokio::spawn(actor_task::<A>(actor, inbox));
+    let join_handle = tokio::spawn(super::link::linked_actor_task::<A>(
+        actor,
+        inbox,
+        address.link_cell(),
+    ));
 
     // Add child to supervisor with handle atomically
     // If this fails, abort the spawned task to prevent orphaned actors
-    if let Err(err) = supervisor.add_child_with_handle(child_id, join_handle, None) {
+    if let Err(err) = supervisor.add_child_with_handle(child_id.clone(), join_handle, None) {
         // Note: The JoinHandle was consumed by add_child_with_handle, so we can't abort it
         // However, this is much safer as the child is only added if the handle can be tracked
         return Err(err.into());
     }
+    supervisor.set_child_address(&child_id, &address)?;
 
     // Success - return the address
-    Ok(Address::from_tokio_sender(outbox))
+    Ok(address)
 }
 
 /// Spawns a supervised batch actor on the Tokio runtime.
@@ -826,7 +1236,7 @@ pub fn spawn_counter_batch_actor_embassy_32(
 /// * `actor` - The actor instance to run with panic protection
 /// * `mailbox` - Tokio MPSC receiver for actor messages
 /// * `supervisor_address` - Optional address to send panic notifications
-/// * `actor_id` - String identifier for this actor (for supervision context)
+/// * `actor_id` - Compact identifier for this actor, assigned at spawn time
 ///
 /// # Returns
 ///
@@ -837,9 +1247,9 @@ pub async fn panic_safe_actor_task<A: Actor>(
     mut actor: A,
     mut mailbox: tokio::sync::mpsc::Receiver<A::Message>,
     supervisor_address: Option<
-        crate::actor::address::Address<crate::actor::SupervisorMessage<String>>,
+        crate::actor::address::Address<crate::actor::SupervisorMessage<ActorId>>,
     >,
-    actor_id: String,
+    actor_id: ActorId,
 ) -> Result<(), crate::actor::ActorError> {
     use futures::FutureExt;
     use std::panic::AssertUnwindSafe;
@@ -849,7 +1259,7 @@ pub async fn panic_safe_actor_task<A: Actor>(
         if let Some(supervisor_addr) = supervisor_address {
             let _ = supervisor_addr
                 .send(crate::actor::SupervisorMessage::ChildPanicked {
-                    id: actor_id.clone(),
+                    id: actor_id,
                     error: Box::new(startup_error.clone()),
                 })
                 .await;
@@ -869,14 +1279,14 @@ pub async fn panic_safe_actor_task<A: Actor>(
                 let actor_error =
                     crate::actor::panic_handling::capture_panic_info_from_payload_with_id(
                         &panic_payload,
-                        actor_id.clone(),
+                        actor_id,
                     );
 
                 // Notify supervisor about the panic
                 if let Some(supervisor_addr) = supervisor_address {
                     let _ = supervisor_addr
                         .send(crate::actor::SupervisorMessage::ChildPanicked {
-                            id: actor_id.clone(),
+                            id: actor_id,
                             error: Box::new(actor_error),
                         })
                         .await;
@@ -889,9 +1299,8 @@ pub async fn panic_safe_actor_task<A: Actor>(
     }
 
     // Call actor shutdown hook on normal termination
-    // Note: on_stop takes &mut self, so the actor still exists after this call
     // This is only reached on normal mailbox closure
-    let _ = actor.on_stop();
+    let _ = actor.on_stop_with_reason(crate::actor::StopReason::SendersDropped);
     Ok(())
 }
 
@@ -912,7 +1321,8 @@ pub async fn panic_safe_actor_task<A: Actor>(
 ///
 /// * `actor` - The actor instance to spawn with panic protection
 /// * `supervisor` - Mutable reference to the supervisor actor
-/// * `child_id` - Unique identifier for this child actor
+/// * `child_id` - Unique identifier for this child actor; it doubles as the
+///   [`ActorId`] reported in panic notifications
 /// * `capacity` - Mailbox capacity for the actor
 /// * `supervisor_address` - Address to send supervision messages
 ///
@@ -928,7 +1338,7 @@ pub async fn panic_safe_actor_task<A: Actor>(
 /// # {
 /// use lit_bit_core::actor::supervision::SupervisorActor;
 /// use lit_bit_core::actor::spawn::spawn_supervised_actor_with_panic_handling;
-/// use lit_bit_core::actor::{Actor, RestartStrategy, RestartIntensity};
+/// use lit_bit_core::actor::{Actor, ActorId};
 ///
 /// struct MyActor {
 ///     count: u32,
@@ -946,17 +1356,15 @@ pub async fn panic_safe_actor_task<A: Actor>(
 ///
 /// #[tokio::main]
 /// async fn main() {
-///     let mut supervisor = SupervisorActor::new(
-///         RestartStrategy::Transient,
-///         RestartIntensity::default(),
-///     );
-///     
+///     let mut supervisor = SupervisorActor::<ActorId, 8>::new();
+///
 ///     let actor = MyActor { count: 0 };
 ///     let address = spawn_supervised_actor_with_panic_handling(
 ///         actor,
 ///         &mut supervisor,
-///         "my_actor".to_string(),
+///         ActorId::named("my_actor"),
 ///         100,
+///         None,
 ///     ).unwrap();
 ///     
 ///     // Actor is now running with panic protection and supervision
@@ -970,23 +1378,23 @@ pub fn spawn_supervised_actor_with_panic_handling<A, ChildId, const MAX_CHILDREN
     child_id: ChildId,
     capacity: usize,
     supervisor_address: Option<
-        crate::actor::address::Address<crate::actor::SupervisorMessage<String>>,
+        crate::actor::address::Address<crate::actor::SupervisorMessage<ActorId>>,
     >,
 ) -> Result<Address<A::Message>, SpawnError>
 where
     A: Actor + Send + 'static,
     A::Message: Send + 'static,
-    ChildId: Clone + ToString + Send + 'static + core::fmt::Debug + core::hash::Hash + Eq,
+    ChildId: Clone + Into<ActorId> + Send + 'static + core::fmt::Debug + core::hash::Hash + Eq,
 {
     let (tx, rx) = tokio::sync::mpsc::channel(capacity);
-    let actor_id_string = child_id.to_string();
+    let actor_id: ActorId = child_id.clone().into();
 
     // Spawn the panic-safe actor task
     let handle = tokio::task::spawn(panic_safe_actor_task(
         actor,
         rx,
         supervisor_address,
-        actor_id_string,
+        actor_id,
     ));
 
     // Register the child with the supervisor (using JoinHandle for monitoring)
@@ -1021,7 +1429,7 @@ where
 /// * `actor` - The actor instance to run with loop-based restart
 /// * `mailbox` - Embassy channel receiver for actor messages  
 /// * `supervisor_signal` - Signal for notifying supervisor of failures
-/// * `actor_id` - String identifier for this actor
+/// * `actor_id` - Caller-provided constant identifier for this actor
 ///
 /// # Returns
 ///
@@ -1038,22 +1446,33 @@ pub async fn embassy_actor_loop_task<A: Actor>(
     >,
     supervisor_signal: &'static embassy_sync::signal::Signal<
         embassy_sync::blocking_mutex::raw::NoopRawMutex,
-        crate::actor::SupervisorMessage<ActorString>,
+        crate::actor::SupervisorMessage<ActorId>,
     >,
-    actor_id: &'static str,
+    actor_id: ActorId,
 ) where
     A::Message: 'static,
 {
+    // Iterations of the restart loop so far, for the defmt timeline
+    #[cfg(feature = "defmt")]
+    let mut restart_count: u32 = 0;
+
     // Embassy pattern: Internal loop with cooperative restart
     loop {
+        #[cfg(feature = "defmt")]
+        defmt::info!("actor {}: (re)start #{=u32}", actor_id, restart_count);
+
         // Initialize/reset actor state for restart
         if let Err(init_error) = actor.on_restart() {
+            #[cfg(feature = "defmt")]
+            defmt::warn!("actor {}: restart failed: {}", actor_id, init_error);
             supervisor_signal.signal(create_supervisor_panic_message(init_error, actor_id));
             break; // Cannot restart - actor terminates
         }
 
         // Call actor startup hook
         if let Err(startup_error) = actor.on_start() {
+            #[cfg(feature = "defmt")]
+            defmt::warn!("actor {}: start failed: {}", actor_id, startup_error);
             supervisor_signal.signal(create_supervisor_panic_message(startup_error, actor_id));
             break; // Cannot start - actor terminates
         }
@@ -1064,6 +1483,8 @@ pub async fn embassy_actor_loop_task<A: Actor>(
             match actor.handle_safe(message).await {
                 Ok(()) => continue, // Normal processing
                 Err(actor_error) => {
+                    #[cfg(feature = "defmt")]
+                    defmt::warn!("actor {}: failed: {}", actor_id, actor_error);
                     supervisor_signal
                         .signal(create_supervisor_panic_message(actor_error, actor_id));
                     break; // Exit message loop to restart
@@ -1071,6 +1492,11 @@ pub async fn embassy_actor_loop_task<A: Actor>(
             }
         }
 
+        #[cfg(feature = "defmt")]
+        {
+            restart_count = restart_count.wrapping_add(1);
+        }
+
         // Perform cleanup before restart iteration
         let _ = actor.on_cleanup();
 
@@ -1100,7 +1526,7 @@ pub async fn embassy_actor_loop_task<A: Actor>(
 /// * `actor` - The actor instance to run until termination
 /// * `mailbox` - Embassy channel receiver for actor messages
 /// * `supervisor_signal` - Signal for notifying supervisor of failures  
-/// * `actor_id` - String identifier for this actor
+/// * `actor_id` - Caller-provided constant identifier for this actor
 ///
 /// # Returns
 ///
@@ -1117,17 +1543,21 @@ pub async fn embassy_external_respawn_task<A: Actor>(
     >,
     supervisor_signal: &'static embassy_sync::signal::Signal<
         embassy_sync::blocking_mutex::raw::NoopRawMutex,
-        crate::actor::SupervisorMessage<ActorString>,
+        crate::actor::SupervisorMessage<ActorId>,
     >,
-    actor_id: &'static str,
+    actor_id: ActorId,
 ) where
     A::Message: 'static,
 {
     // Call actor startup hook
     if let Err(startup_error) = actor.on_start() {
+        #[cfg(feature = "defmt")]
+        defmt::warn!("actor {}: start failed: {}", actor_id, startup_error);
         supervisor_signal.signal(create_supervisor_panic_message(startup_error, actor_id));
         return; // Task terminates - supervisor will respawn if configured
     }
+    #[cfg(feature = "defmt")]
+    defmt::info!("actor {}: started", actor_id);
 
     // External respawn pattern: Task runs once, supervisor respawns
     loop {
@@ -1135,6 +1565,8 @@ pub async fn embassy_external_respawn_task<A: Actor>(
         match actor.handle_safe(message).await {
             Ok(()) => continue,
             Err(error) => {
+                #[cfg(feature = "defmt")]
+                defmt::warn!("actor {}: failed, awaiting respawn: {}", actor_id, error);
                 supervisor_signal.signal(create_supervisor_panic_message(error, actor_id));
                 break; // Task terminates - supervisor will respawn if configured
             }
@@ -1193,6 +1625,201 @@ mod tests {
             assert_eq!(final_count, 15);
         }
 
+        #[tokio::test]
+        async fn spawn_actor_runs_single_message_actors_per_message() {
+            let counter = Arc::new(Mutex::new(0));
+            let address = crate::actor::spawn_actor(TestActor::new(counter.clone()), 10);
+
+            address.send(5).await.unwrap();
+            address.send(10).await.unwrap();
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+            assert_eq!(*counter.lock().unwrap(), 15);
+        }
+
+        #[tokio::test]
+        async fn spawn_actor_coalesces_queued_messages_for_batch_actors() {
+            struct Recorder {
+                batches: Arc<Mutex<Vec<Vec<u32>>>>,
+            }
+
+            impl crate::actor::BatchActor for Recorder {
+                type Message = u32;
+                type Future<'a>
+                    = core::future::Ready<()>
+                where
+                    Self: 'a;
+
+                fn handle_batch(&mut self, messages: &[u32]) -> Self::Future<'_> {
+                    self.batches.lock().unwrap().push(messages.to_vec());
+                    core::future::ready(())
+                }
+
+                fn max_batch_size(&self) -> usize {
+                    4
+                }
+            }
+
+            let batches = Arc::new(Mutex::new(Vec::new()));
+            let address = crate::actor::spawn_actor(
+                Recorder {
+                    batches: batches.clone(),
+                },
+                16,
+            );
+
+            // Queued before the actor first runs, so they are drained in two batches.
+            for msg in 1..=6 {
+                address.send(msg).await.unwrap();
+            }
+            while batches.lock().unwrap().concat().len() < 6 {
+                tokio::task::yield_now().await;
+            }
+
+            assert_eq!(*batches.lock().unwrap(), [vec![1, 2, 3, 4], vec![5, 6]]);
+        }
+
+        #[tokio::test]
+        async fn async_batch_actors_await_inside_their_batches() {
+            struct Uplink {
+                frames: Arc<Mutex<Vec<u32>>>,
+            }
+
+            impl crate::actor::AsyncBatchActor for Uplink {
+                type Message = u32;
+
+                fn handle_batch(&mut self, messages: &[u32]) -> futures::future::BoxFuture<'_, ()> {
+                    let frame: u32 = messages.iter().sum();
+                    Box::pin(async move {
+                        tokio::task::yield_now().await;
+                        self.frames.lock().unwrap().push(frame);
+                    })
+                }
+
+                fn max_batch_size(&self) -> usize {
+                    3
+                }
+            }
+
+            let frames = Arc::new(Mutex::new(Vec::new()));
+            let address = crate::actor::spawn_actor(
+                Uplink {
+                    frames: frames.clone(),
+                },
+                16,
+            );
+
+            for msg in 1..=5 {
+                address.send(msg).await.unwrap();
+            }
+            while frames.lock().unwrap().len() < 2 {
+                tokio::task::yield_now().await;
+            }
+
+            assert_eq!(*frames.lock().unwrap(), [6, 9]);
+        }
+
+        #[cfg(feature = "batch-stats")]
+        #[tokio::test]
+        async fn spawn_with_stats_records_every_batch() {
+            struct Summer {
+                total: Arc<Mutex<u32>>,
+            }
+
+            impl crate::actor::BatchActor for Summer {
+                type Message = u32;
+                type Future<'a>
+                    = core::future::Ready<()>
+                where
+                    Self: 'a;
+
+                fn handle_batch(&mut self, messages: &[u32]) -> Self::Future<'_> {
+                    *self.total.lock().unwrap() += messages.iter().sum::<u32>();
+                    core::future::ready(())
+                }
+
+                fn max_batch_size(&self) -> usize {
+                    8
+                }
+            }
+
+            let total = Arc::new(Mutex::new(0));
+            let (address, stats) = crate::actor::spawn::spawn_batch_actor_tokio_with_stats(
+                Summer {
+                    total: total.clone(),
+                },
+                32,
+            );
+
+            // The current-thread runtime only runs the actor once this task yields, so
+            // all 20 messages are queued before the first batch is drained.
+            for _ in 0..20 {
+                address.send(1).await.unwrap();
+            }
+            while stats.snapshot().messages < 20 {
+                tokio::task::yield_now().await;
+            }
+
+            let snapshot = stats.snapshot();
+            assert_eq!(*total.lock().unwrap(), 20);
+            assert_eq!(snapshot.wakeups, 3);
+            // Two full batches of 8 and one of 4.
+            assert_eq!(snapshot.histogram[3], 2);
+            assert_eq!(snapshot.histogram[2], 1);
+            assert!(snapshot.average_drain_latency().is_some());
+        }
+
+        #[tokio::test]
+        async fn adaptive_batches_grow_with_a_backlog_and_shrink_when_it_clears() {
+            struct Recorder {
+                batches: Arc<Mutex<Vec<usize>>>,
+            }
+
+            impl crate::actor::BatchActor for Recorder {
+                type Message = u32;
+                type Future<'a>
+                    = core::future::Ready<()>
+                where
+                    Self: 'a;
+
+                fn handle_batch(&mut self, messages: &[u32]) -> Self::Future<'_> {
+                    self.batches.lock().unwrap().push(messages.len());
+                    core::future::ready(())
+                }
+            }
+
+            let batches = Arc::new(Mutex::new(Vec::new()));
+            let (address, metrics) = crate::actor::spawn::spawn_batch_actor_tokio_adaptive(
+                Recorder {
+                    batches: batches.clone(),
+                },
+                64,
+                crate::actor::AdaptiveBatching::new(1, 16),
+            );
+            assert_eq!(metrics.batch_limit(), 1);
+
+            // All queued before the actor first runs: the limit doubles while a backlog is
+            // left behind each batch.
+            for msg in 0..40 {
+                address.send(msg).await.unwrap();
+            }
+            while batches.lock().unwrap().iter().sum::<usize>() < 40 {
+                tokio::task::yield_now().await;
+            }
+            assert_eq!(*batches.lock().unwrap(), [1, 2, 4, 8, 16, 9]);
+            assert_eq!(metrics.batch_limit(), 16);
+            assert_eq!(metrics.last_batch_size(), 9);
+
+            // Single messages from then on bring it back down.
+            for msg in 0..5 {
+                address.send(msg).await.unwrap();
+                while batches.lock().unwrap().len() < 7 + msg as usize {
+                    tokio::task::yield_now().await;
+                }
+            }
+            assert_eq!(metrics.batch_limit(), 1);
+        }
+
         use std::future::Future;
         use std::pin::Pin;
 
@@ -1265,7 +1892,7 @@ mod tests {
                 actor,
                 rx,
                 None, // No supervisor
-                "test_actor".to_string(),
+                crate::actor::ActorId::new(1),
             ));
 
             // Send some messages
@@ -1296,7 +1923,7 @@ mod tests {
                 actor,
                 rx,
                 None, // No supervisor for this test
-                "test_actor".to_string(),
+                crate::actor::ActorId::new(1),
             ));
 
             // Send normal message first
@@ -1332,7 +1959,7 @@ mod tests {
                 actor,
                 actor_rx,
                 Some(supervisor_address),
-                "test_panic_actor".to_string(),
+                crate::actor::ActorId::new(2),
             ));
 
             // Send normal messages first
@@ -1354,7 +1981,7 @@ mod tests {
             let supervisor_msg = supervisor_rx.recv().await.unwrap();
             match supervisor_msg {
                 crate::actor::SupervisorMessage::ChildPanicked { id, error } => {
-                    assert_eq!(id, "test_panic_actor");
+                    assert_eq!(id, crate::actor::ActorId::new(2));
                     match *error {
                         crate::actor::ActorError::Panic { .. } => {
                             // Expected panic error
@@ -1400,7 +2027,7 @@ mod tests {
                 actor,
                 actor_rx,
                 Some(supervisor_address),
-                "failing_startup_actor".to_string(),
+                crate::actor::ActorId::new(3),
             ));
 
             // Task should fail during startup
@@ -1418,7 +2045,7 @@ mod tests {
             let supervisor_msg = supervisor_rx.recv().await.unwrap();
             match supervisor_msg {
                 crate::actor::SupervisorMessage::ChildPanicked { id, error } => {
-                    assert_eq!(id, "failing_startup_actor");
+                    assert_eq!(id, crate::actor::ActorId::new(3));
                     match *error {
                         crate::actor::ActorError::StartupFailure => {
                             // Expected startup failure
@@ -1435,7 +2062,8 @@ mod tests {
             use crate::actor::spawn::spawn_supervised_actor_with_panic_handling;
             use crate::actor::supervision::SupervisorActor;
 
-            let mut supervisor = SupervisorActor::<String, 8>::new();
+            let mut supervisor = SupervisorActor::<crate::actor::ActorId, 8>::new();
+            let child_id = crate::actor::ActorId::named("supervised_panic_actor");
 
             let (supervisor_tx, _supervisor_rx) = tokio::sync::mpsc::channel(10);
             let supervisor_address =
@@ -1448,7 +2076,7 @@ mod tests {
             let actor_address = spawn_supervised_actor_with_panic_handling(
                 actor,
                 &mut supervisor,
-                "supervised_panic_actor".to_string(),
+                child_id,
                 32,
                 Some(supervisor_address),
             )
@@ -1466,7 +2094,7 @@ mod tests {
             assert_eq!(processed_count, 300);
 
             // Verify child was added to supervisor (use public method)
-            assert!(supervisor.apply_restart_intensity(&"supervised_panic_actor".to_string()));
+            assert!(supervisor.apply_restart_intensity(&child_id));
         }
 
         #[tokio::test]
@@ -1504,19 +2132,19 @@ mod tests {
                 actor1,
                 rx1,
                 Some(supervisor_address1),
-                "actor1".to_string(),
+                crate::actor::ActorId::new(1),
             ));
             let task2 = tokio::spawn(crate::actor::spawn::panic_safe_actor_task(
                 actor2,
                 rx2,
                 Some(supervisor_address2),
-                "actor2".to_string(),
+                crate::actor::ActorId::new(2),
             ));
             let task3 = tokio::spawn(crate::actor::spawn::panic_safe_actor_task(
                 actor3,
                 rx3,
                 Some(supervisor_address3),
-                "actor3".to_string(),
+                crate::actor::ActorId::new(3),
             ));
 
             // Send messages to all actors
@@ -1608,7 +2236,28 @@ mod tests {
             drop(address);
 
             // Wait for actor to stop
-            probe.expect_actor_stopped().await.unwrap();
+            assert_eq!(
+                probe.expect_actor_stopped().await.unwrap(),
+                crate::actor::StopReason::SendersDropped
+            );
+        }
+
+        #[tokio::test]
+        async fn test_actor_probe_reports_stop_requested() {
+            use crate::test_utils::{ActorProbe, TestKit};
+
+            let test_kit = TestKit::new();
+            let counter = Arc::new(Mutex::new(0));
+            let (address, mut probe): (_, ActorProbe<TestActor>) =
+                test_kit.spawn_actor_with_probe::<_, 32>(TestActor::new(counter));
+            probe.expect_actor_started().await.unwrap();
+
+            address.stop();
+
+            assert_eq!(
+                probe.expect_actor_stopped().await.unwrap(),
+                crate::actor::StopReason::StopRequested
+            );
         }
 
         #[tokio::test]
@@ -0,0 +1,526 @@
+//! Spawning child actors from inside a message handler.
+//!
+//! An actor that implements [`ParentActor`] receives a [`SpawnContext`] with every message.
+//! Children spawned through it are registered with the context's [`SupervisorActor`] under
+//! a fresh [`ActorId`], so the parent can track them and react when they stop.
+//!
+//! - **Tokio**: [`SpawnContext::spawn_child`] starts the child with `tokio::spawn`, and
+//!   [`parent_actor_task`] reports finished children to [`ParentActor::on_child_exit`]
+//!   after every message.
+//! - **Embassy**: tasks cannot be generic, so children run in a task pool the application
+//!   declares up front (`#[embassy_executor::task(pool_size = N)]`).
+//!   [`SpawnContext::spawn_child`] takes the `SpawnToken` produced by that task and the
+//!   sender half of the child's channel.
+
+use super::supervision::SupervisorActor;
+use super::{Actor, ActorError, ActorId};
+
+#[cfg(feature = "async-tokio")]
+use super::address::Address;
+#[cfg(feature = "async-tokio")]
+use super::{Inbox, StopReason, create_mailbox, enter_handle};
+
+#[cfg(feature = "async-tokio")]
+extern crate alloc;
+#[cfg(feature = "async-tokio")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "async-embassy")]
+use super::address::Address;
+#[cfg(feature = "async-embassy")]
+use super::supervision::SupervisorError;
+#[cfg(feature = "async-embassy")]
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+
+/// An actor whose handler can spawn children.
+///
+/// [`parent_actor_task`] calls [`handle_in`](Self::handle_in) instead of
+/// [`Actor::handle`]; `handle` is only used when the actor runs without a context, e.g.
+/// under [`spawn_actor_tokio`](super::spawn::spawn_actor_tokio).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+/// # {
+/// use lit_bit_core::actor::spawn_context::{ParentActor, SpawnContext};
+/// use lit_bit_core::actor::Actor;
+/// use lit_bit_core::Address;
+///
+/// struct Worker;
+/// impl Actor for Worker {
+///     type Message = u32;
+///     type Future<'a> = core::future::Ready<()> where Self: 'a;
+///     fn handle(&mut self, _job: u32) -> Self::Future<'_> {
+///         core::future::ready(())
+///     }
+/// }
+///
+/// struct Pool {
+///     workers: Vec<Address<u32>>,
+/// }
+/// impl Actor for Pool {
+///     type Message = u32;
+///     type Future<'a> = core::future::Ready<()> where Self: 'a;
+///     fn handle(&mut self, _job: u32) -> Self::Future<'_> {
+///         core::future::ready(())
+///     }
+/// }
+/// impl ParentActor for Pool {
+///     fn handle_in(&mut self, job: u32, spawn: &mut SpawnContext) -> Self::Future<'_> {
+///         if self.workers.is_empty() {
+///             self.workers.push(spawn.spawn_child(Worker, 16));
+///         }
+///         let _ = self.workers[0].try_send(job);
+///         core::future::ready(())
+///     }
+/// }
+/// # }
+/// ```
+pub trait ParentActor: Actor {
+    /// Handles `msg` with access to the parent's [`SpawnContext`].
+    ///
+    /// Spawning is synchronous, so the returned future does not borrow the context.
+    fn handle_in(&mut self, msg: Self::Message, spawn: &mut SpawnContext) -> Self::Future<'_>;
+
+    /// Called by [`parent_actor_task`] for every child that has stopped, with the result
+    /// of its task. The child is no longer supervised; spawn a replacement through
+    /// `spawn` to restart it.
+    #[cfg(feature = "async-tokio")]
+    fn on_child_exit(
+        &mut self,
+        _child: ActorId,
+        _result: Result<(), ActorError>,
+        _spawn: &mut SpawnContext,
+    ) {
+    }
+}
+
+/// Spawns children on behalf of a [`ParentActor`] and registers them with its supervisor.
+#[cfg(feature = "async-tokio")]
+#[derive(Default)]
+pub struct SpawnContext {
+    supervisor: SupervisorActor<ActorId>,
+}
+
+#[cfg(feature = "async-tokio")]
+impl SpawnContext {
+    /// Creates a context with a default [`SupervisorActor`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a context that registers children with `supervisor`, e.g. one with a
+    /// custom restart strategy or circuit breaker.
+    #[must_use]
+    pub fn with_supervisor(supervisor: SupervisorActor<ActorId>) -> Self {
+        Self { supervisor }
+    }
+
+    /// Spawns `actor` on the current Tokio runtime with a mailbox of `capacity` and
+    /// registers it with the supervisor under a fresh [`ActorId`].
+    ///
+    /// # Panics
+    /// Panics if called outside a Tokio runtime, like `tokio::spawn`.
+    pub fn spawn_child<A>(&mut self, actor: A, capacity: usize) -> Address<A::Message>
+    where
+        A: Actor + Send + 'static,
+        A::Message: Send + 'static,
+    {
+        self.spawn_child_with_id(actor, capacity).1
+    }
+
+    /// Like [`spawn_child`](Self::spawn_child), but also returns the child's id, which is
+    /// what [`ParentActor::on_child_exit`] and the supervisor's snapshot report.
+    pub fn spawn_child_with_id<A>(
+        &mut self,
+        actor: A,
+        capacity: usize,
+    ) -> (ActorId, Address<A::Message>)
+    where
+        A: Actor + Send + 'static,
+        A::Message: Send + 'static,
+    {
+        let id = ActorId::next();
+        let (outbox, inbox) = create_mailbox::<A::Message>(capacity);
+        let address = Address::from_tokio_sender(outbox);
+        let join_handle = tokio::spawn(crate::actor::link::linked_actor_task::<A>(
+            actor,
+            inbox,
+            address.link_cell(),
+        ));
+        // Ids from `ActorId::next` are unique and the Tokio supervisor is unbounded, so
+        // registration cannot fail.
+        let _ = self.supervisor.add_child_with_handle(id, join_handle, None);
+        (id, address)
+    }
+
+    /// Returns the supervisor the children are registered with.
+    #[must_use]
+    pub fn supervisor(&self) -> &SupervisorActor<ActorId> {
+        &self.supervisor
+    }
+
+    /// Returns the supervisor the children are registered with.
+    pub fn supervisor_mut(&mut self) -> &mut SupervisorActor<ActorId> {
+        &mut self.supervisor
+    }
+
+    /// Returns the number of children that are still supervised.
+    #[must_use]
+    pub fn child_count(&self) -> usize {
+        self.supervisor.snapshot().children.len()
+    }
+
+    /// Removes the children that have stopped from supervision and returns their results.
+    pub fn reap_children(&mut self) -> Vec<(ActorId, Result<(), ActorError>)> {
+        self.supervisor.poll_children()
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl core::fmt::Debug for SpawnContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SpawnContext")
+            .field("children", &self.child_count())
+            .finish()
+    }
+}
+
+/// Runs a [`ParentActor`]'s message loop, passing `spawn` to every
+/// [`handle_in`](ParentActor::handle_in) call.
+///
+/// After each message, children that have stopped are reported to
+/// [`ParentActor::on_child_exit`]. Children keep running when the parent stops; they stop
+/// once every [`Address`] to them is dropped.
+///
+/// # Errors
+/// Returns `ActorError` if actor startup, shutdown, or message processing fails, like
+/// [`actor_task`](super::actor_task).
+#[cfg(feature = "async-tokio")]
+pub async fn parent_actor_task<A>(
+    mut actor: A,
+    mut inbox: Inbox<A::Message>,
+    mut spawn: SpawnContext,
+) -> Result<(), ActorError>
+where
+    A: ParentActor + Send + 'static,
+    A::Message: Send + 'static,
+{
+    actor.on_start()?;
+
+    while let Some(msg) = inbox.recv().await {
+        {
+            let _scope = enter_handle(&actor)?;
+            let future = actor.handle_in(msg, &mut spawn);
+            future.await;
+        }
+        for (child, result) in spawn.reap_children() {
+            actor.on_child_exit(child, result, &mut spawn);
+        }
+    }
+
+    let stop_result = actor.on_stop_with_reason(StopReason::SendersDropped);
+    #[cfg(feature = "debug-log")]
+    if let Err(ref e) = stop_result {
+        log::error!("Actor shutdown failed: {e:?}");
+    }
+    stop_result?;
+
+    Ok(())
+}
+
+/// Spawns a [`ParentActor`] on the Tokio runtime with an empty [`SpawnContext`].
+#[cfg(feature = "async-tokio")]
+pub fn spawn_parent_actor_tokio<A>(actor: A, capacity: usize) -> Address<A::Message>
+where
+    A: ParentActor + Send + 'static,
+    A::Message: Send + 'static,
+{
+    spawn_parent_actor_tokio_with(actor, capacity, SpawnContext::new())
+}
+
+/// Spawns a [`ParentActor`] on the Tokio runtime with the given [`SpawnContext`].
+#[cfg(feature = "async-tokio")]
+pub fn spawn_parent_actor_tokio_with<A>(
+    actor: A,
+    capacity: usize,
+    spawn: SpawnContext,
+) -> Address<A::Message>
+where
+    A: ParentActor + Send + 'static,
+    A::Message: Send + 'static,
+{
+    let (outbox, inbox) = create_mailbox::<A::Message>(capacity);
+    tokio::spawn(parent_actor_task::<A>(actor, inbox, spawn));
+    Address::from_tokio_sender(outbox)
+}
+
+/// Spawns children on behalf of a [`ParentActor`] into pre-registered Embassy task pools.
+///
+/// Ids are assigned from a per-context counter starting at `first_id`, so parents sharing
+/// a supervisor tree should use disjoint ranges. At most [`MAX_CHILDREN`](Self::MAX_CHILDREN)
+/// children are supervised at a time.
+#[cfg(feature = "async-embassy")]
+pub struct SpawnContext {
+    spawner: embassy_executor::Spawner,
+    supervisor: SupervisorActor<ActorId>,
+    next_id: u32,
+}
+
+#[cfg(feature = "async-embassy")]
+impl SpawnContext {
+    /// Capacity of the supervisor's child table.
+    pub const MAX_CHILDREN: usize = 16;
+
+    /// Creates a context that spawns onto `spawner` and numbers children from `first_id`.
+    #[must_use]
+    pub fn new(spawner: embassy_executor::Spawner, first_id: u32) -> Self {
+        Self {
+            spawner,
+            supervisor: SupervisorActor::new(),
+            next_id: first_id,
+        }
+    }
+
+    /// Spawns a child from a pool task and registers it with the supervisor.
+    ///
+    /// `token` is the result of calling the application's pool task with the child actor
+    /// and the receiver half of its channel (see
+    /// [`static_embassy_channel!`](crate::static_embassy_channel)); `sender` is the other
+    /// half.
+    ///
+    /// # Errors
+    /// Returns [`SpawnError::SpawnFailed`](super::spawn::SpawnError::SpawnFailed) when the
+    /// pool has no free slot, and `SpawnError::Supervisor` when the supervisor already
+    /// tracks [`MAX_CHILDREN`](Self::MAX_CHILDREN) children.
+    pub fn spawn_child<S, M, const N: usize>(
+        &mut self,
+        token: embassy_executor::SpawnToken<S>,
+        sender: embassy_sync::channel::Sender<'static, NoopRawMutex, M, N>,
+    ) -> Result<(ActorId, Address<M, N>), super::spawn::SpawnError> {
+        if self.child_count() >= Self::MAX_CHILDREN {
+            return Err(SupervisorError::CapacityExceeded.into());
+        }
+        self.spawner
+            .spawn(token)
+            .map_err(|_| super::spawn::SpawnError::SpawnFailed)?;
+        let id = ActorId::new(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        self.supervisor.add_child(id, None)?;
+        Ok((id, Address::from_embassy_sender(sender)))
+    }
+
+    /// Returns the supervisor the children are registered with.
+    #[must_use]
+    pub fn supervisor(&self) -> &SupervisorActor<ActorId> {
+        &self.supervisor
+    }
+
+    /// Returns the supervisor the children are registered with.
+    pub fn supervisor_mut(&mut self) -> &mut SupervisorActor<ActorId> {
+        &mut self.supervisor
+    }
+
+    /// Returns the number of children that are still supervised.
+    #[must_use]
+    pub fn child_count(&self) -> usize {
+        self.supervisor.snapshot().children.len()
+    }
+}
+
+/// Runs a [`ParentActor`]'s message loop on Embassy, passing `spawn` to every
+/// [`handle_in`](ParentActor::handle_in) call.
+///
+/// # Errors
+/// Returns `ActorError` if actor startup, shutdown, or message processing fails.
+#[cfg(feature = "async-embassy")]
+#[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+pub async fn parent_actor_task_embassy<A, const N: usize>(
+    mut actor: A,
+    inbox: embassy_sync::channel::Receiver<'static, NoopRawMutex, A::Message, N>,
+    mut spawn: SpawnContext,
+) -> Result<(), ActorError>
+where
+    A: ParentActor,
+    A::Message: 'static,
+{
+    actor.on_start()?;
+
+    loop {
+        let msg = inbox.receive().await;
+        let _scope = super::enter_handle(&actor)?;
+        actor.handle_in(msg, &mut spawn).await;
+    }
+}
+
+#[cfg(all(test, feature = "async-tokio", not(feature = "async-embassy")))]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct Counter {
+        total: Arc<AtomicU32>,
+        fail_on_start: bool,
+    }
+
+    impl Actor for Counter {
+        type Message = u32;
+        type Future<'a>
+            = core::future::Ready<()>
+        where
+            Self: 'a;
+
+        fn handle(&mut self, msg: u32) -> Self::Future<'_> {
+            self.total.fetch_add(msg, Ordering::SeqCst);
+            core::future::ready(())
+        }
+
+        fn on_start(&mut self) -> Result<(), ActorError> {
+            if self.fail_on_start {
+                Err(ActorError::StartupFailure)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    enum Command {
+        Spawn { fail_on_start: bool },
+        Forward(u32),
+    }
+
+    #[derive(Default)]
+    struct Observed {
+        child_counts: Vec<usize>,
+        exits: Vec<(ActorId, Result<(), ActorError>)>,
+    }
+
+    struct Parent {
+        total: Arc<AtomicU32>,
+        children: Vec<Address<u32>>,
+        observed: Arc<Mutex<Observed>>,
+    }
+
+    impl Actor for Parent {
+        type Message = Command;
+        type Future<'a>
+            = core::future::Ready<()>
+        where
+            Self: 'a;
+
+        fn handle(&mut self, _msg: Command) -> Self::Future<'_> {
+            core::future::ready(())
+        }
+    }
+
+    impl ParentActor for Parent {
+        fn handle_in(&mut self, msg: Command, spawn: &mut SpawnContext) -> Self::Future<'_> {
+            match msg {
+                Command::Spawn { fail_on_start } => {
+                    let child = Counter {
+                        total: Arc::clone(&self.total),
+                        fail_on_start,
+                    };
+                    self.children.push(spawn.spawn_child(child, 8));
+                    let count = spawn.child_count();
+                    self.observed.lock().unwrap().child_counts.push(count);
+                }
+                Command::Forward(value) => {
+                    for child in &self.children {
+                        let _ = child.try_send(value);
+                    }
+                }
+            }
+            core::future::ready(())
+        }
+
+        fn on_child_exit(
+            &mut self,
+            child: ActorId,
+            result: Result<(), ActorError>,
+            _spawn: &mut SpawnContext,
+        ) {
+            self.observed.lock().unwrap().exits.push((child, result));
+        }
+    }
+
+    fn parent() -> (Parent, Arc<AtomicU32>, Arc<Mutex<Observed>>) {
+        let total = Arc::new(AtomicU32::new(0));
+        let observed = Arc::new(Mutex::new(Observed::default()));
+        let parent = Parent {
+            total: Arc::clone(&total),
+            children: Vec::new(),
+            observed: Arc::clone(&observed),
+        };
+        (parent, total, observed)
+    }
+
+    #[tokio::test]
+    async fn handler_spawns_supervised_children() {
+        let (parent, total, observed) = parent();
+        let address = spawn_parent_actor_tokio(parent, 8);
+
+        for command in [
+            Command::Spawn {
+                fail_on_start: false,
+            },
+            Command::Spawn {
+                fail_on_start: false,
+            },
+            Command::Forward(5),
+        ] {
+            address.send(command).await.expect("parent is running");
+        }
+        while total.load(Ordering::SeqCst) < 10 {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(total.load(Ordering::SeqCst), 10);
+        assert_eq!(observed.lock().unwrap().child_counts, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn stopped_children_are_reported_to_the_parent() {
+        let (parent, _total, observed) = parent();
+        let address = spawn_parent_actor_tokio(parent, 8);
+
+        address
+            .send(Command::Spawn {
+                fail_on_start: true,
+            })
+            .await
+            .expect("parent is running");
+        while observed.lock().unwrap().exits.is_empty() {
+            address
+                .send(Command::Forward(0))
+                .await
+                .expect("parent is running");
+            tokio::task::yield_now().await;
+        }
+
+        let observed = observed.lock().unwrap();
+        assert_eq!(observed.exits.len(), 1);
+        assert_eq!(observed.exits[0].1, Err(ActorError::StartupFailure));
+    }
+
+    #[tokio::test]
+    async fn spawn_child_with_id_registers_the_returned_id() {
+        let mut spawn = SpawnContext::new();
+        let (id, _address) = spawn.spawn_child_with_id(
+            Counter {
+                total: Arc::new(AtomicU32::new(0)),
+                fail_on_start: false,
+            },
+            4,
+        );
+
+        let snapshot = spawn.supervisor().snapshot();
+        assert_eq!(snapshot.children.len(), 1);
+        assert_eq!(snapshot.children[0].id, id);
+    }
+}
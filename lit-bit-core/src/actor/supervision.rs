@@ -4,7 +4,10 @@
 //! providing platform-dual supervision that works with both Tokio (JoinHandle monitoring) and
 //! Embassy (message signaling) environments.
 
-use super::{Actor, RestartStrategy, Supervisor, SupervisorMessage};
+use super::{
+    Actor, BackoffStrategy, PanicAnalyzer, RestartIntensity, RestartStrategy, Supervisor,
+    SupervisorMessage,
+};
 
 // Import ActorError when needed (async-tokio features or test contexts)
 #[cfg(any(feature = "async-tokio", test))]
@@ -19,11 +22,15 @@ use super::BoxedActorError;
 #[cfg(all(not(any(feature = "std", feature = "alloc")), feature = "async-tokio"))]
 use super::ActorError;
 
+#[cfg(feature = "async-tokio")]
+use super::StopReason;
 #[cfg(feature = "async-tokio")]
 use futures::FutureExt;
 #[cfg(feature = "async-tokio")]
 use std::collections::HashMap;
 #[cfg(feature = "async-tokio")]
+use std::sync::Arc;
+#[cfg(feature = "async-tokio")]
 use tokio::task::JoinHandle;
 
 #[cfg(not(feature = "async-tokio"))]
@@ -42,6 +49,8 @@ use alloc::vec::Vec;
 extern crate alloc;
 #[cfg(not(any(feature = "std", feature = "alloc")))]
 use alloc::boxed::Box;
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+use alloc::vec::Vec;
 
 /// Trait for providing platform-specific timer functionality.
 ///
@@ -76,8 +85,29 @@ pub trait SupervisorTimer {
     fn current_time_ms() -> u64;
 }
 
+/// Child ids a [`SupervisorActor`] can put on the defmt timeline.
+///
+/// With the `defmt` feature this is `defmt::Format`, which [`ActorId`](super::ActorId) and
+/// the integer types implement; without it every type qualifies.
+#[cfg(feature = "defmt")]
+pub trait TimelineId: defmt::Format {}
+
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format> TimelineId for T {}
+
+/// Child ids a [`SupervisorActor`] can put on the defmt timeline.
+///
+/// With the `defmt` feature this is `defmt::Format`, which [`ActorId`](super::ActorId) and
+/// the integer types implement; without it every type qualifies.
+#[cfg(not(feature = "defmt"))]
+pub trait TimelineId {}
+
+#[cfg(not(feature = "defmt"))]
+impl<T> TimelineId for T {}
+
 /// Error types for supervisor operations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SupervisorError {
     /// Child capacity limit reached (no_std environments)
     CapacityExceeded,
@@ -89,6 +119,98 @@ pub enum SupervisorError {
     RestartFailed,
 }
 
+/// Circuit-breaker state of a supervised child.
+///
+/// With a breaker enabled (see [`SupervisorActor::with_circuit_breaker`]), a child whose
+/// failures escalate is not dropped from supervision but tripped:
+///
+/// - **Closed**: normal operation; failures are restarted within the restart intensity.
+/// - **Open**: the child escalated. Restart attempts are refused and
+///   [`SupervisorActor::check_child`] reports [`ActorError::SupervisionFailure`] until the
+///   cool-down ends.
+/// - **HalfOpen**: the cool-down ended and one restart was let through as a probe. The
+///   breaker closes when the child reports `ChildStarted`, and trips again if it fails
+///   first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CircuitState {
+    Closed,
+    Open {
+        /// Supervisor clock reading (milliseconds) at which the cool-down ends.
+        until_ms: u64,
+    },
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Returns the state as seen at `now_ms`: an open breaker whose cool-down has ended is
+    /// ready to let a probe through.
+    #[must_use]
+    pub fn at(self, now_ms: u64) -> Self {
+        match self {
+            CircuitState::Open { until_ms } if now_ms >= until_ms => CircuitState::HalfOpen,
+            state => state,
+        }
+    }
+}
+
+/// A supervised child as recorded in a [`SupervisionSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChildSnapshot<ChildId> {
+    pub id: ChildId,
+    pub restart_strategy: RestartStrategy,
+    /// Restarts within the current restart window.
+    pub restart_count: usize,
+    pub circuit: CircuitState,
+}
+
+/// The children of a supervisor at one point in time, in the order they were added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupervisionSnapshot<ChildId> {
+    pub children: Vec<ChildSnapshot<ChildId>>,
+}
+
+#[cfg(feature = "defmt")]
+impl<ChildId: defmt::Format> defmt::Format for SupervisionSnapshot<ChildId> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=usize} children:", self.children.len());
+        for child in &self.children {
+            defmt::write!(f, " {}", child);
+        }
+    }
+}
+
+/// A failure that escalated to a supervisor with no parent.
+#[derive(Debug)]
+pub struct RootEscalation<ChildId> {
+    /// The child whose failure escalated.
+    pub child_id: ChildId,
+    /// The error that triggered the escalation.
+    pub error: super::ActorError,
+    /// The supervisor's children just before the failed child was removed.
+    pub snapshot: SupervisionSnapshot<ChildId>,
+}
+
+/// What the top-level supervisor does when a failure escalates to it.
+///
+/// Configured with [`SupervisorActor::with_root_escalation`]; a supervisor without one
+/// just drops the failed child. Before the action runs, buffered output is flushed and
+/// the [`SupervisionSnapshot`] is dumped (to stderr with `std`, through `log` with
+/// `debug-log`), so post-mortem logs show the state of the tree at the time.
+#[derive(Debug)]
+pub enum RootEscalationAction<ChildId> {
+    /// Only dump the escalation and carry on without the failed child.
+    Log,
+    /// Panic, letting the panic strategy decide whether the process unwinds or aborts.
+    PanicProcess,
+    /// Exit the process with this code so an orchestrator restarts it.
+    #[cfg(feature = "std")]
+    ExitProcess(i32),
+    /// Hand the escalation to the application.
+    Custom(fn(&RootEscalation<ChildId>)),
+}
+
 // Timer implementations are provided for different feature combinations
 // Default no_std implementation uses an atomic counter for basic timing
 
@@ -147,6 +269,18 @@ where
 
     /// Sequence counter for tracking child start order (for RestForOne strategy)
     next_start_sequence: u64,
+
+    /// Cool-down applied when a child's breaker trips; `None` disables the breaker
+    circuit_cooldown_ms: Option<u64>,
+
+    /// Monotonic millisecond clock driving the circuit breaker
+    clock: fn() -> u64,
+
+    /// Applied when a failure escalates past this supervisor; `None` for nested supervisors
+    root_escalation: Option<RootEscalationAction<ChildId>>,
+
+    /// Policy consulted on child failures; `None` keeps the built-in rules
+    panic_analyzer: Option<&'static (dyn PanicAnalyzer + Sync)>,
 }
 
 /// Information about a supervised child actor.
@@ -160,6 +294,9 @@ struct ChildInfo {
     /// Sequence number indicating the order this child was added (for RestForOne strategy)
     start_sequence: u64,
 
+    /// Circuit-breaker state (always `Closed` when the breaker is disabled)
+    circuit: CircuitState,
+
     /// Timestamp of the first restart in the current window
     #[cfg(feature = "std")]
     window_start: std::time::Instant,
@@ -171,6 +308,10 @@ struct ChildInfo {
     #[cfg(feature = "async-tokio")]
     join_handle: Option<JoinHandle<Result<(), ActorError>>>,
 
+    /// Tokio-specific: how to stop the running task, if its address was registered
+    #[cfg(feature = "async-tokio")]
+    links: Option<Arc<super::link::LinkCell>>,
+
     /// Embassy-specific: Flag indicating if child is currently running
     #[cfg(not(feature = "async-tokio"))]
     is_running: bool,
@@ -203,6 +344,10 @@ where
             max_restarts: 5,
             restart_window_ms: 60_000, // 60 seconds
             next_start_sequence: 0,
+            circuit_cooldown_ms: None,
+            clock: Self::current_time_ms,
+            root_escalation: None,
+            panic_analyzer: None,
         }
     }
 
@@ -229,9 +374,136 @@ where
             max_restarts,
             restart_window_ms,
             next_start_sequence: 0,
+            circuit_cooldown_ms: None,
+            clock: Self::current_time_ms,
+            root_escalation: None,
+            panic_analyzer: None,
         }
     }
 
+    /// Enables the circuit breaker: a child whose failures escalate stays supervised but
+    /// tripped for `cooldown_ms` before a restart is attempted again. See [`CircuitState`].
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, cooldown_ms: u64) -> Self {
+        self.circuit_cooldown_ms = Some(cooldown_ms);
+        self
+    }
+
+    /// Drives the circuit breaker from `T` instead of the platform clock.
+    #[must_use]
+    pub fn with_clock<T: SupervisorTimer>(mut self) -> Self {
+        self.clock = T::current_time_ms;
+        self
+    }
+
+    /// Makes this the top-level supervisor: failures that escalate to it are handled by
+    /// `action` instead of only dropping the failed child. See [`RootEscalationAction`].
+    #[must_use]
+    pub fn with_root_escalation(mut self, action: RootEscalationAction<ChildId>) -> Self {
+        self.root_escalation = Some(action);
+        self
+    }
+
+    /// Lets `analyzer` decide on child failures: whether a failed child is restarted at
+    /// all, when its restarts escalate and how long to back off before the next one.
+    ///
+    /// Without an analyzer every failure is restarted until a child fails more than
+    /// `max_restarts` times within the window, with a backoff of 100 ms doubling up to 5 s;
+    /// the same [`BackoffStrategy::Exponential`] is handed to the analyzer.
+    #[must_use]
+    pub fn with_panic_analyzer(mut self, analyzer: &'static (dyn PanicAnalyzer + Sync)) -> Self {
+        self.panic_analyzer = Some(analyzer);
+        self
+    }
+
+    /// Returns the supervised children, in the order they were added.
+    #[must_use]
+    pub fn snapshot(&self) -> SupervisionSnapshot<ChildId> {
+        let now = (self.clock)();
+        let mut children: Vec<_> = self
+            .children
+            .iter()
+            .map(|(id, child_info)| (child_info.start_sequence, id, child_info))
+            .collect();
+        children.sort_unstable_by_key(|&(sequence, _, _)| sequence);
+        SupervisionSnapshot {
+            children: children
+                .into_iter()
+                .map(|(_, id, child_info)| ChildSnapshot {
+                    id: id.clone(),
+                    restart_strategy: child_info.restart_strategy,
+                    restart_count: child_info.restart_count,
+                    circuit: child_info.circuit.at(now),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the circuit-breaker state of a child as of now, or `None` if the child is
+    /// not supervised.
+    #[must_use]
+    pub fn circuit_state(&self, child_id: &ChildId) -> Option<CircuitState> {
+        let now = (self.clock)();
+        self.children
+            .get(child_id)
+            .map(|child_info| child_info.circuit.at(now))
+    }
+
+    /// Checks whether a child may be messaged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ActorError::SupervisionFailure`] while the child's breaker is open.
+    #[allow(clippy::result_large_err)] // ActorError provides detailed failure information
+    pub fn check_child(&self, child_id: &ChildId) -> Result<(), super::ActorError> {
+        match self.circuit_state(child_id) {
+            Some(CircuitState::Open { until_ms }) => {
+                let mut message = super::ActorString::new();
+                let _ = core::fmt::Write::write_fmt(
+                    &mut message,
+                    format_args!("child {child_id:?} is tripped until {until_ms} ms"),
+                );
+                Err(super::ActorError::SupervisionFailure(message))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records that a child came back up, closing a half-open breaker and starting a
+    /// fresh restart window.
+    pub fn record_child_recovered(&mut self, child_id: &ChildId) {
+        let Some(child_info) = self.children.get_mut(child_id) else {
+            return;
+        };
+        if child_info.circuit != CircuitState::HalfOpen {
+            return;
+        }
+        child_info.circuit = CircuitState::Closed;
+        child_info.restart_count = 0;
+
+        #[cfg(feature = "std")]
+        {
+            child_info.window_start = std::time::Instant::now();
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            child_info.window_start_ms = (self.clock)();
+        }
+    }
+
+    /// Trips a child's breaker, if the breaker is enabled. Returns `false` when it is not.
+    fn trip_circuit(&mut self, child_id: &ChildId) -> bool {
+        let Some(cooldown_ms) = self.circuit_cooldown_ms else {
+            return false;
+        };
+        let until_ms = (self.clock)().saturating_add(cooldown_ms);
+        if let Some(child_info) = self.children.get_mut(child_id) {
+            child_info.circuit = CircuitState::Open { until_ms };
+        }
+        true
+    }
+
     /// Adds a child actor to supervision with a restart factory.
     ///
     /// # Arguments
@@ -258,6 +530,7 @@ where
             restart_strategy: strategy,
             restart_count: 0,
             start_sequence: self.next_start_sequence,
+            circuit: CircuitState::Closed,
 
             #[cfg(feature = "std")]
             window_start: std::time::Instant::now(),
@@ -268,6 +541,9 @@ where
             #[cfg(feature = "async-tokio")]
             join_handle: None,
 
+            #[cfg(feature = "async-tokio")]
+            links: None,
+
             #[cfg(not(feature = "async-tokio"))]
             is_running: true,
 
@@ -329,6 +605,30 @@ where
         self.children.remove(child_id).is_some()
     }
 
+    /// Changes the restart strategy of a supervised child, returning the previous one.
+    ///
+    /// Takes effect on the child's next failure; e.g. a flapping child can be switched
+    /// from [`RestartStrategy::OneForOne`] to [`RestartStrategy::Temporary`] so it is not
+    /// restarted again. The child's restart count and circuit state are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SupervisorError::ChildNotFound`] if the child is not supervised.
+    pub fn set_strategy(
+        &mut self,
+        child_id: &ChildId,
+        strategy: RestartStrategy,
+    ) -> Result<RestartStrategy, SupervisorError> {
+        let child_info = self
+            .children
+            .get_mut(child_id)
+            .ok_or(SupervisorError::ChildNotFound)?;
+        Ok(core::mem::replace(
+            &mut child_info.restart_strategy,
+            strategy,
+        ))
+    }
+
     /// Records a child failure and determines the restart strategy to apply.
     ///
     /// This method implements the core supervision logic, tracking restart counts
@@ -341,8 +641,25 @@ where
     /// * `Some(RestartStrategy)` - Strategy to apply for this failure
     /// * `None` - Child not found or restart limit exceeded
     pub fn handle_child_failure(&mut self, child_id: &ChildId) -> Option<RestartStrategy> {
+        let now = (self.clock)();
+        let intensity = self.restart_intensity();
         let child_info = self.children.get_mut(child_id)?;
 
+        match child_info.circuit {
+            CircuitState::Closed => {}
+            // Cool-down over: let one restart through as a probe.
+            CircuitState::Open { until_ms } if now >= until_ms => {
+                child_info.circuit = CircuitState::HalfOpen;
+                return Some(child_info.restart_strategy);
+            }
+            CircuitState::Open { .. } => return None,
+            // The probe failed before reporting in.
+            CircuitState::HalfOpen => {
+                self.trip_circuit(child_id);
+                return None;
+            }
+        }
+
         // Check restart rate limiting
         #[cfg(feature = "std")]
         let window_elapsed = child_info.window_start.elapsed().as_millis() as u64;
@@ -371,7 +688,20 @@ where
 
         child_info.restart_count += 1;
 
-        if child_info.restart_count > self.max_restarts {
+        let exceeded = match self.panic_analyzer {
+            Some(analyzer) => {
+                analyzer.should_escalate(child_id, child_info.restart_count as u32, &intensity)
+            }
+            None => child_info.restart_count > self.max_restarts,
+        };
+        if exceeded {
+            if self.trip_circuit(child_id) {
+                #[cfg(feature = "debug-log")]
+                log::warn!("Child {child_id:?} exceeded restart limit, tripping its circuit");
+
+                return None;
+            }
+
             // Too many restarts - remove child from supervision
             #[cfg(feature = "debug-log")]
             log::warn!("Child {child_id:?} exceeded restart limit, removing from supervision");
@@ -392,9 +722,12 @@ where
     /// * `child_id` - Identifier of the child that panicked
     /// * `error` - The ActorError that caused the panic
     ///
+    /// On a supervisor configured with [`Self::with_panic_analyzer`], a child whose error
+    /// the analyzer refuses to restart is removed from supervision.
+    ///
     /// # Returns
     /// * `Some(RestartStrategy)` - Strategy to apply if restart is allowed
-    /// * `None` - Restart limit exceeded or escalation required
+    /// * `None` - Restart refused, restart limit exceeded or escalation required
     pub fn handle_child_panic(
         &mut self,
         child_id: &ChildId,
@@ -408,8 +741,14 @@ where
                         .as_ref()
                         .map(|m| m.as_str())
                         .unwrap_or("<no message>");
-                    let id = actor_id.as_ref().map(|id| id.as_str()).unwrap_or("<no id>");
-                    log::warn!("Child {child_id:?} panicked: message='{msg}', actor_id='{id}'");
+                    match actor_id {
+                        Some(id) => log::warn!(
+                            "Child {child_id:?} panicked: message='{msg}', actor_id='{id}'"
+                        ),
+                        None => log::warn!(
+                            "Child {child_id:?} panicked: message='{msg}', actor_id='<no id>'"
+                        ),
+                    }
                 }
                 super::ActorError::Custom(msg) => {
                     log::warn!("Child {child_id:?} custom error: {msg}");
@@ -419,11 +758,20 @@ where
                 }
             }
         }
+        if let Some(analyzer) = self.panic_analyzer
+            && !analyzer.should_restart(child_id, &error)
+        {
+            #[cfg(feature = "debug-log")]
+            log::warn!(
+                "Child {child_id:?} is not restarted after {error:?}, removing from supervision"
+            );
+
+            self.children.remove(child_id);
+            return None;
+        }
         #[cfg(not(feature = "debug-log"))]
         let _ = &error;
 
-        // In the future, this analysis could be used to select a different strategy
-        // For now, use the existing failure handling logic
         let strategy = self.handle_child_failure(child_id)?;
 
         #[cfg(feature = "debug-log")]
@@ -445,20 +793,38 @@ where
     /// # Returns
     /// Duration to wait before attempting restart
     pub fn calculate_backoff_delay(&self, child_id: &ChildId) -> core::time::Duration {
-        if let Some(child_info) = self.children.get(child_id) {
-            let retry_count = child_info.restart_count;
+        let Some(child_info) = self.children.get(child_id) else {
+            return core::time::Duration::from_millis(100); // Default minimal delay
+        };
+        let retry_count = child_info.restart_count as u32;
 
+        let delay_ms = match self.panic_analyzer {
+            Some(analyzer) => {
+                analyzer.calculate_backoff_delay(retry_count, &Self::BACKOFF_STRATEGY)
+            }
             // Simple exponential backoff: 100ms * 2^(retry_count-1), max 5 seconds
-            let base_delay_ms: u64 = 100;
-            let max_delay_ms: u64 = 5000;
+            None => {
+                let exponential_delay =
+                    100_u64.saturating_mul(2_u64.saturating_pow(retry_count.saturating_sub(1)));
+                exponential_delay.min(5000)
+            }
+        };
 
-            let exponential_delay = base_delay_ms
-                .saturating_mul(2_u64.saturating_pow(retry_count.saturating_sub(1).max(0) as u32));
-            let delay_ms = exponential_delay.min(max_delay_ms);
+        core::time::Duration::from_millis(delay_ms)
+    }
 
-            core::time::Duration::from_millis(delay_ms)
-        } else {
-            core::time::Duration::from_millis(100) // Default minimal delay
+    /// Backoff between restarts, as handed to a configured [`PanicAnalyzer`].
+    const BACKOFF_STRATEGY: BackoffStrategy = BackoffStrategy::Exponential {
+        base_delay_ms: 100,
+        max_delay_ms: 5000,
+    };
+
+    /// The restart limits as handed to a configured [`PanicAnalyzer`].
+    fn restart_intensity(&self) -> RestartIntensity {
+        RestartIntensity {
+            max_restarts: self.max_restarts as u32,
+            restart_window_ms: self.restart_window_ms,
+            backoff_strategy: Self::BACKOFF_STRATEGY,
         }
     }
 
@@ -494,19 +860,94 @@ where
     /// This is a placeholder for hierarchical supervision. In a full implementation,
     /// this would send a message to a parent supervisor or trigger system-level
     /// failure handling (e.g., device reset in embedded systems).
-    pub fn escalate_failure(&mut self, child_id: &ChildId, _error: super::ActorError) {
+    ///
+    /// With the circuit breaker enabled, the child is tripped instead of removed. Otherwise,
+    /// on a supervisor configured with [`Self::with_root_escalation`], the root escalation
+    /// action runs once the child is removed.
+    ///
+    /// # Panics
+    ///
+    /// With [`RootEscalationAction::PanicProcess`], always panics.
+    pub fn escalate_failure(&mut self, child_id: &ChildId, error: super::ActorError) {
         #[cfg(feature = "debug-log")]
         log::error!("Escalating failure for child {child_id:?} - restart limits exceeded");
 
+        if self.trip_circuit(child_id) {
+            return;
+        }
+
+        let snapshot = self.root_escalation.as_ref().map(|_| self.snapshot());
+
+        // A nested supervisor's subtree is rebuilt by its parent, so stop what still runs.
+        #[cfg(feature = "async-tokio")]
+        if self.root_escalation.is_none() {
+            self.stop_children(StopReason::Escalated);
+        }
+
         // Remove the failing child from supervision to prevent further restart attempts
         self.children.remove(child_id);
 
+        if let Some(snapshot) = snapshot {
+            self.escalate_to_root(child_id, error, snapshot);
+        }
+
         // In a production system, this might:
         // - Send escalation message to parent supervisor
         // - Trigger system-level failsafe (reset, safe mode, etc.)
         // - Log critical system event for debugging
     }
 
+    /// Dumps a failure that escalated past this supervisor, then applies the root
+    /// escalation action. Does nothing on a supervisor without one.
+    fn escalate_to_root(
+        &self,
+        child_id: &ChildId,
+        error: super::ActorError,
+        snapshot: SupervisionSnapshot<ChildId>,
+    ) {
+        let Some(action) = &self.root_escalation else {
+            return;
+        };
+        let escalation = RootEscalation {
+            child_id: child_id.clone(),
+            error,
+            snapshot,
+        };
+
+        #[cfg(feature = "debug-log")]
+        {
+            log::error!(
+                "Failure of child {child_id:?} reached the root supervisor: {:?}",
+                escalation.error
+            );
+            log::error!("Supervision tree: {:?}", escalation.snapshot);
+            log::logger().flush();
+        }
+
+        #[cfg(feature = "std")]
+        {
+            use std::io::Write;
+
+            let _ = std::io::stdout().flush();
+            eprintln!(
+                "lit-bit: failure of child {child_id:?} reached the root supervisor: {:?}",
+                escalation.error
+            );
+            eprintln!("lit-bit: supervision tree: {:#?}", escalation.snapshot);
+            let _ = std::io::stderr().flush();
+        }
+
+        match action {
+            RootEscalationAction::Log => {}
+            RootEscalationAction::PanicProcess => {
+                panic!("failure of child {child_id:?} reached the root supervisor")
+            }
+            #[cfg(feature = "std")]
+            RootEscalationAction::ExitProcess(code) => std::process::exit(*code),
+            RootEscalationAction::Custom(callback) => callback(&escalation),
+        }
+    }
+
     /// Executes the restart logic for children that need to be restarted.
     ///
     /// This method implements the actual restart mechanism by calling the restart
@@ -537,6 +978,12 @@ where
         for child_id in children_to_restart {
             // Temporarily remove the child to avoid borrow conflicts
             if let Some(mut child_info) = self.children.remove(&child_id) {
+                // Stop the old task first if it is still running (a failed child is not)
+                #[cfg(feature = "async-tokio")]
+                if let Some(links) = child_info.links.take() {
+                    links.request_stop(StopReason::SupervisorRestart);
+                }
+
                 // Call the restart factory for this child
                 let factory_result = (child_info.restart_factory)();
 
@@ -746,6 +1193,43 @@ where
         }
     }
 
+    /// Registers the address of a child's running task (Tokio-specific), so the supervisor
+    /// can stop it rather than leave it running: with
+    /// [`StopReason::SupervisorRestart`] when it is restarted along with a failed sibling,
+    /// and with [`StopReason::Escalated`] when this supervisor has no root escalation and
+    /// gives up on another child. A restart spawns a new task, so register its address
+    /// again; [`spawn_supervised_actor_tokio`](super::spawn::spawn_supervised_actor_tokio)
+    /// registers the first one.
+    ///
+    /// Only the loops that deliver link exits can be stopped this way; see the
+    /// [`link`](super::link) module.
+    ///
+    /// # Errors
+    /// Returns `SupervisorError::ChildNotFound` if `child_id` is not supervised.
+    #[cfg(feature = "async-tokio")]
+    pub fn set_child_address<Event>(
+        &mut self,
+        child_id: &ChildId,
+        address: &super::address::Address<Event>,
+    ) -> Result<(), SupervisorError> {
+        let child_info = self
+            .children
+            .get_mut(child_id)
+            .ok_or(SupervisorError::ChildNotFound)?;
+        child_info.links = Some(address.link_cell());
+        Ok(())
+    }
+
+    /// Asks every child with a registered address to stop with `reason`.
+    #[cfg(feature = "async-tokio")]
+    fn stop_children(&mut self, reason: StopReason) {
+        for child_info in self.children.values_mut() {
+            if let Some(links) = child_info.links.take() {
+                links.request_stop(reason);
+            }
+        }
+    }
+
     /// Adds a child actor to supervision with its JoinHandle and restart factory atomically (Tokio-specific).
     ///
     /// This method combines child addition and handle/factory setup into a single atomic operation,
@@ -778,6 +1262,7 @@ where
             restart_strategy: strategy,
             restart_count: 0,
             start_sequence: self.next_start_sequence,
+            circuit: CircuitState::Closed,
 
             #[cfg(feature = "std")]
             window_start: std::time::Instant::now(),
@@ -787,6 +1272,8 @@ where
 
             join_handle: Some(handle),
 
+            links: None,
+
             restart_factory,
         };
 
@@ -845,13 +1332,13 @@ where
                                     };
                                     Err(ActorError::Panic {
                                         message,
-                                        actor_id: Some(format!("{child_id:?}")),
+                                        actor_id: None,
                                     })
                                 } else {
                                     // Task was cancelled
                                     Err(ActorError::Panic {
                                         message: Some("task was cancelled".into()),
-                                        actor_id: Some(format!("{child_id:?}")),
+                                        actor_id: None,
                                     })
                                 }
                             }
@@ -912,6 +1399,34 @@ where
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<ChildId, const MAX_CHILDREN: usize> SupervisorActor<ChildId, MAX_CHILDREN>
+where
+    ChildId: Clone + PartialEq + core::fmt::Debug + core::hash::Hash + Eq + TimelineId,
+{
+    /// Puts the outcome of a failure on the defmt timeline: the strategy, the child's
+    /// restart count and the backoff before its next restart, or that it ran out of restarts.
+    fn timeline_restart(&self, child_id: &ChildId, strategy: Option<RestartStrategy>) {
+        let Some(strategy) = strategy else {
+            defmt::warn!("sup: child {} out of restarts", child_id);
+            return;
+        };
+        let restart_count = self
+            .children
+            .get(child_id)
+            .map_or(0, |child_info| child_info.restart_count);
+        let backoff_ms =
+            u64::try_from(self.calculate_backoff_delay(child_id).as_millis()).unwrap_or(u64::MAX);
+        defmt::info!(
+            "sup: restart {} {} count={=usize} backoff={=u64}ms",
+            child_id,
+            strategy,
+            restart_count,
+            backoff_ms
+        );
+    }
+}
+
 impl<ChildId, const MAX_CHILDREN: usize> Supervisor for SupervisorActor<ChildId, MAX_CHILDREN>
 where
     ChildId: Clone + PartialEq + core::fmt::Debug + core::hash::Hash + Eq,
@@ -926,7 +1441,8 @@ where
 
 impl<ChildId, const MAX_CHILDREN: usize> Actor for SupervisorActor<ChildId, MAX_CHILDREN>
 where
-    ChildId: Clone + PartialEq + core::fmt::Debug + core::hash::Hash + Eq + Send + 'static,
+    ChildId:
+        Clone + PartialEq + core::fmt::Debug + core::hash::Hash + Eq + TimelineId + Send + 'static,
 {
     type Message = SupervisorMessage<ChildId>;
     type Future<'a>
@@ -936,10 +1452,15 @@ where
 
     fn handle(&mut self, msg: Self::Message) -> Self::Future<'_> {
         match msg {
-            SupervisorMessage::ChildStarted { id: _ } => {
+            SupervisorMessage::ChildStarted { id } => {
                 #[cfg(feature = "debug-log")]
                 log::info!("Child started successfully");
 
+                #[cfg(feature = "defmt")]
+                defmt::info!("sup: child {} started", id);
+
+                self.record_child_recovered(&id);
+
                 // Update child status
                 #[cfg(not(feature = "async-tokio"))]
                 {
@@ -948,9 +1469,11 @@ where
                 }
             }
 
-            SupervisorMessage::ChildStopped { id: _ } => {
+            SupervisorMessage::ChildStopped { id: _id } => {
                 #[cfg(feature = "debug-log")]
                 log::info!("Child stopped gracefully");
+                #[cfg(feature = "defmt")]
+                defmt::info!("sup: child {} stopped", _id);
 
                 // Update child status
                 #[cfg(not(feature = "async-tokio"))]
@@ -963,30 +1486,29 @@ where
             SupervisorMessage::ChildPanicked { id, error } => {
                 #[cfg(feature = "debug-log")]
                 log::warn!("Child {id:?} panicked - determining restart strategy");
-
-                #[cfg(any(feature = "std", feature = "alloc"))]
+                #[cfg(feature = "defmt")]
                 {
-                    if let Some(strategy) = self.handle_child_panic(&id, *error) {
-                        #[cfg(feature = "debug-log")]
-                        log::info!("Executing restart strategy: {strategy:?} for child {id:?}");
-                        #[cfg(not(feature = "debug-log"))]
-                        let _ = &strategy;
-                    }
+                    let failure: &super::ActorError = core::borrow::Borrow::borrow(&error);
+                    defmt::warn!("sup: child {} failed: {}", id, failure);
                 }
+
+                #[cfg(any(feature = "std", feature = "alloc"))]
+                let _strategy = self.handle_child_panic(&id, *error);
                 #[cfg(not(any(feature = "std", feature = "alloc")))]
-                {
-                    if let Some(strategy) = self.handle_child_panic(&id, error) {
-                        #[cfg(feature = "debug-log")]
-                        log::info!("Executing restart strategy: {strategy:?} for child {id:?}");
-                        #[cfg(not(feature = "debug-log"))]
-                        let _ = &strategy;
-                    }
+                let _strategy = self.handle_child_panic(&id, error);
+                #[cfg(feature = "debug-log")]
+                if let Some(strategy) = _strategy {
+                    log::info!("Executing restart strategy: {strategy:?} for child {id:?}");
                 }
+                #[cfg(feature = "defmt")]
+                self.timeline_restart(&id, _strategy);
             }
 
             SupervisorMessage::StartChild { id } => {
                 #[cfg(feature = "debug-log")]
                 log::info!("Request to start child {id:?}");
+                #[cfg(feature = "defmt")]
+                defmt::info!("sup: start child {}", id);
 
                 // Add child to supervision with default strategy (no-op factory)
                 let _ = self.add_child(id, None);
@@ -995,6 +1517,8 @@ where
             SupervisorMessage::StopChild { id } => {
                 #[cfg(feature = "debug-log")]
                 log::info!("Request to stop child {id:?}");
+                #[cfg(feature = "defmt")]
+                defmt::info!("sup: stop child {}", id);
 
                 // Remove child from supervision
                 self.remove_child(&id);
@@ -1005,7 +1529,10 @@ where
                 log::info!("Request to restart child {id:?}");
 
                 // Treat as a failure for restart counting purposes
-                if let Some(strategy) = self.handle_child_failure(&id) {
+                let strategy = self.handle_child_failure(&id);
+                #[cfg(feature = "defmt")]
+                self.timeline_restart(&id, strategy);
+                if let Some(strategy) = strategy {
                     #[cfg(feature = "debug-log")]
                     log::info!(
                         "Executing restart strategy: {strategy:?} for manual restart of child {id:?}"
@@ -1033,14 +1560,29 @@ where
             SupervisorMessage::ChildEscalated {
                 supervisor_id,
                 failed_child_id: _,
-                error: _,
+                error,
             } => {
                 #[cfg(feature = "debug-log")]
                 log::warn!("Child supervisor {supervisor_id:?} escalated a failure");
+                #[cfg(feature = "defmt")]
+                {
+                    let failure: &super::ActorError = core::borrow::Borrow::borrow(&error);
+                    defmt::warn!("sup: supervisor {} escalated: {}", supervisor_id, failure);
+                }
+
+                // Taken before the restart bookkeeping may drop the escalating supervisor.
+                let root_snapshot = self
+                    .root_escalation
+                    .as_ref()
+                    .filter(|_| self.children.contains_key(&supervisor_id))
+                    .map(|_| self.snapshot());
 
                 // For now, treat escalation as a supervisor failure requiring restart
                 // In a more sophisticated implementation, this could trigger different escalation policies
-                if let Some(strategy) = self.handle_child_failure(&supervisor_id) {
+                let strategy = self.handle_child_failure(&supervisor_id);
+                #[cfg(feature = "defmt")]
+                self.timeline_restart(&supervisor_id, strategy);
+                if let Some(strategy) = strategy {
                     #[cfg(feature = "debug-log")]
                     log::info!(
                         "Executing restart strategy: {strategy:?} for escalated supervisor {supervisor_id:?}"
@@ -1062,6 +1604,15 @@ where
                     log::warn!(
                         "Escalated supervisor {supervisor_id:?} exceeded restart limit or was not found"
                     );
+
+                    // Out of restarts with nowhere further up to escalate to.
+                    if let Some(snapshot) = root_snapshot
+                        && !self.children.contains_key(&supervisor_id)
+                    {
+                        #[cfg(any(feature = "std", feature = "alloc"))]
+                        let error = *error;
+                        self.escalate_to_root(&supervisor_id, error, snapshot);
+                    }
                 }
             }
         }
@@ -1151,7 +1702,7 @@ mod tests {
                 {
                     Box::new(ActorError::Panic {
                         message: Some("test panic".to_string()),
-                        actor_id: Some("test_actor".to_string()),
+                        actor_id: Some(crate::actor::ActorId::new(1)),
                     })
                 }
                 #[cfg(not(any(feature = "std", feature = "alloc")))]
@@ -1161,12 +1712,9 @@ mod tests {
                     let mut panic_msg = HeaplessString::<128>::new();
                     let _ = panic_msg.push_str("test panic");
 
-                    let mut actor_id_str = HeaplessString::<128>::new();
-                    let _ = actor_id_str.push_str("test_actor");
-
                     ActorError::Panic {
                         message: Some(panic_msg),
-                        actor_id: Some(actor_id_str),
+                        actor_id: Some(crate::actor::ActorId::new(1)),
                     }
                 }
             },
@@ -1305,6 +1853,81 @@ mod tests {
         assert_eq!(final_count, 1);
     }
 
+    #[test]
+    fn set_strategy_applies_to_the_next_failure() {
+        let mut supervisor = SupervisorActor::<u32, 8>::new();
+        assert!(supervisor.add_child(1, None).is_ok());
+        assert_eq!(
+            supervisor.handle_child_failure(&1),
+            Some(RestartStrategy::OneForOne)
+        );
+
+        assert_eq!(
+            supervisor.set_strategy(&1, RestartStrategy::Temporary),
+            Ok(RestartStrategy::OneForOne)
+        );
+        assert_eq!(
+            supervisor.handle_child_failure(&1),
+            Some(RestartStrategy::Temporary)
+        );
+        assert_eq!(
+            supervisor.snapshot().children[0].restart_strategy,
+            RestartStrategy::Temporary
+        );
+
+        assert_eq!(
+            supervisor.set_strategy(&2, RestartStrategy::Never),
+            Err(SupervisorError::ChildNotFound)
+        );
+    }
+
+    #[cfg(all(test, feature = "async-tokio", feature = "std"))]
+    #[tokio::test]
+    async fn strategy_changed_mid_run_stops_restarts() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut supervisor = SupervisorActor::<u32, 8>::new();
+        let restarts = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&restarts);
+        let restart_factory: RestartFactory = Box::new(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+            tokio::spawn(async { Ok(()) })
+        });
+        assert!(
+            supervisor
+                .add_child_with_factory(1, restart_factory, Some(RestartStrategy::OneForOne))
+                .is_ok()
+        );
+
+        supervisor
+            .handle(SupervisorMessage::RestartChild { id: 1 })
+            .await;
+        assert_eq!(restarts.load(Ordering::Relaxed), 1);
+
+        // An operator gives up on the flapping child.
+        assert!(
+            supervisor
+                .set_strategy(&1, RestartStrategy::Temporary)
+                .is_ok()
+        );
+        supervisor
+            .handle(SupervisorMessage::RestartChild { id: 1 })
+            .await;
+        assert_eq!(restarts.load(Ordering::Relaxed), 1);
+
+        // ... and later puts it back under supervision.
+        assert!(
+            supervisor
+                .set_strategy(&1, RestartStrategy::Permanent)
+                .is_ok()
+        );
+        supervisor
+            .handle(SupervisorMessage::RestartChild { id: 1 })
+            .await;
+        assert_eq!(restarts.load(Ordering::Relaxed), 2);
+    }
+
     #[cfg(all(test, feature = "async-tokio", feature = "std"))]
     #[tokio::test]
     async fn test_restart_with_one_for_all_strategy() {
@@ -1468,18 +2091,16 @@ mod tests {
         #[cfg(any(feature = "std", feature = "alloc"))]
         let panic_error = ActorError::Panic {
             message: Some("Test panic in actor".to_string()),
-            actor_id: Some("test_actor_1".to_string()),
+            actor_id: Some(crate::actor::ActorId::new(1)),
         };
 
         #[cfg(not(any(feature = "std", feature = "alloc")))]
         let panic_error = {
             let mut panic_msg = crate::actor::ActorString::new();
             let _ = panic_msg.push_str("Test panic in actor");
-            let mut actor_id_str = crate::actor::ActorString::new();
-            let _ = actor_id_str.push_str("test_actor_1");
             ActorError::Panic {
                 message: Some(panic_msg),
-                actor_id: Some(actor_id_str),
+                actor_id: Some(crate::actor::ActorId::new(1)),
             }
         };
 
@@ -1501,6 +2122,65 @@ mod tests {
         assert!(!supervisor.children.contains_key(&1));
     }
 
+    /// Refuses to restart after a `Custom("fatal")` error and backs off a flat 50ms.
+    struct FatalErrorAnalyzer;
+
+    impl PanicAnalyzer for FatalErrorAnalyzer {
+        fn should_restart(&self, _child_id: &dyn core::fmt::Debug, error: &ActorError) -> bool {
+            !matches!(error, ActorError::Custom("fatal"))
+        }
+
+        fn calculate_backoff_delay(&self, _failure_count: u32, _strategy: &BackoffStrategy) -> u64 {
+            50
+        }
+
+        fn should_escalate(
+            &self,
+            _child_id: &dyn core::fmt::Debug,
+            failure_count: u32,
+            intensity: &RestartIntensity,
+        ) -> bool {
+            failure_count > intensity.max_restarts
+        }
+    }
+
+    #[test]
+    fn a_custom_panic_analyzer_decides_which_failures_restart() {
+        let mut supervisor =
+            SupervisorActor::<u32, 8>::with_config(RestartStrategy::OneForOne, 3, 60_000)
+                .with_panic_analyzer(&FatalErrorAnalyzer);
+        assert!(supervisor.add_child(1, None).is_ok());
+        assert!(supervisor.add_child(2, None).is_ok());
+
+        let strategy = supervisor.handle_child_panic(&1, ActorError::Custom("transient"));
+        assert_eq!(strategy, Some(RestartStrategy::OneForOne));
+        assert_eq!(
+            supervisor.calculate_backoff_delay(&1),
+            core::time::Duration::from_millis(50)
+        );
+
+        // The analyzer refuses the fatal variant: the child is dropped, not restarted.
+        assert_eq!(
+            supervisor.handle_child_panic(&2, ActorError::Custom("fatal")),
+            None
+        );
+        assert!(!supervisor.children.contains_key(&2));
+
+        // Its escalation rule still bounds the restarts of the other child.
+        for _ in 0..2 {
+            assert!(
+                supervisor
+                    .handle_child_panic(&1, ActorError::Custom("transient"))
+                    .is_some()
+            );
+        }
+        assert_eq!(
+            supervisor.handle_child_panic(&1, ActorError::Custom("transient")),
+            None
+        );
+        assert!(!supervisor.children.contains_key(&1));
+    }
+
     #[test]
     fn test_calculate_backoff_delay_exponential() {
         let mut supervisor = SupervisorActor::<u32, 8>::new();
@@ -1561,6 +2241,34 @@ mod tests {
         assert!(!supervisor.children.contains_key(&1));
     }
 
+    #[test]
+    fn snapshot_lists_children_in_the_order_they_were_added() {
+        let mut supervisor = SupervisorActor::<u32, 8>::new();
+        for id in [7, 3, 5] {
+            assert!(supervisor.add_child(id, None).is_ok());
+        }
+        supervisor.handle_child_failure(&3);
+
+        let snapshot = supervisor.snapshot();
+        let ids: Vec<u32> = snapshot.children.iter().map(|child| child.id).collect();
+        assert_eq!(ids, [7, 3, 5]);
+        assert_eq!(snapshot.children[1].restart_count, 1);
+        assert_eq!(snapshot.children[1].circuit, CircuitState::Closed);
+    }
+
+    #[test]
+    fn log_root_escalation_only_drops_the_child() {
+        let mut supervisor =
+            SupervisorActor::<u32, 8>::new().with_root_escalation(RootEscalationAction::Log);
+        assert!(supervisor.add_child(1, None).is_ok());
+        assert!(supervisor.add_child(2, None).is_ok());
+
+        supervisor.escalate_failure(&1, ActorError::Timeout);
+
+        assert!(!supervisor.children.contains_key(&1));
+        assert!(supervisor.children.contains_key(&2));
+    }
+
     #[test]
     fn test_apply_restart_intensity_rate_limiting() {
         let mut supervisor = SupervisorActor::<u32, 8>::with_config(
@@ -1780,4 +2488,119 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn circuit_breaker_trips_instead_of_removing_child() {
+        use core::sync::atomic::{AtomicU64, Ordering};
+
+        static NOW: AtomicU64 = AtomicU64::new(1_000);
+        struct Clock;
+        impl SupervisorTimer for Clock {
+            fn current_time_ms() -> u64 {
+                NOW.load(Ordering::Relaxed)
+            }
+        }
+
+        let mut supervisor =
+            SupervisorActor::<u32, 8>::with_config(RestartStrategy::OneForOne, 1, 60_000)
+                .with_circuit_breaker(500)
+                .with_clock::<Clock>();
+        assert!(supervisor.add_child(1, None).is_ok());
+        assert_eq!(supervisor.circuit_state(&1), Some(CircuitState::Closed));
+        assert_eq!(supervisor.check_child(&1), Ok(()));
+
+        // Within the restart intensity the child restarts as usual.
+        assert_eq!(
+            supervisor.handle_child_failure(&1),
+            Some(RestartStrategy::OneForOne)
+        );
+
+        // Exceeding it trips the breaker but keeps the child supervised.
+        assert_eq!(supervisor.handle_child_failure(&1), None);
+        assert_eq!(
+            supervisor.circuit_state(&1),
+            Some(CircuitState::Open { until_ms: 1_500 })
+        );
+        assert!(matches!(
+            supervisor.check_child(&1),
+            Err(ActorError::SupervisionFailure(_))
+        ));
+
+        // Restart attempts during the cool-down are refused.
+        NOW.store(1_499, Ordering::Relaxed);
+        assert_eq!(supervisor.handle_child_failure(&1), None);
+        assert!(supervisor.check_child(&1).is_err());
+    }
+
+    #[test]
+    fn circuit_breaker_half_open_probe_closes_on_recovery() {
+        use core::sync::atomic::{AtomicU64, Ordering};
+
+        static NOW: AtomicU64 = AtomicU64::new(0);
+        struct Clock;
+        impl SupervisorTimer for Clock {
+            fn current_time_ms() -> u64 {
+                NOW.load(Ordering::Relaxed)
+            }
+        }
+
+        let mut supervisor = SupervisorActor::<u32, 8>::new()
+            .with_circuit_breaker(100)
+            .with_clock::<Clock>();
+        assert!(supervisor.add_child(1, None).is_ok());
+        supervisor.escalate_failure(&1, ActorError::Timeout);
+        assert_eq!(
+            supervisor.circuit_state(&1),
+            Some(CircuitState::Open { until_ms: 100 })
+        );
+
+        // Once the cool-down ends the breaker is ready for a probe...
+        NOW.store(100, Ordering::Relaxed);
+        assert_eq!(supervisor.circuit_state(&1), Some(CircuitState::HalfOpen));
+        assert_eq!(supervisor.check_child(&1), Ok(()));
+
+        // ...which is let through as a single restart.
+        assert_eq!(
+            supervisor.handle_child_failure(&1),
+            Some(RestartStrategy::OneForOne)
+        );
+        assert_eq!(supervisor.circuit_state(&1), Some(CircuitState::HalfOpen));
+
+        // The restarted child reporting in closes the breaker.
+        let _future = supervisor.handle(SupervisorMessage::ChildStarted { id: 1 });
+        assert_eq!(supervisor.circuit_state(&1), Some(CircuitState::Closed));
+        assert_eq!(
+            supervisor.handle_child_failure(&1),
+            Some(RestartStrategy::OneForOne)
+        );
+    }
+
+    #[test]
+    fn circuit_breaker_half_open_failure_trips_again() {
+        use core::sync::atomic::{AtomicU64, Ordering};
+
+        static NOW: AtomicU64 = AtomicU64::new(0);
+        struct Clock;
+        impl SupervisorTimer for Clock {
+            fn current_time_ms() -> u64 {
+                NOW.load(Ordering::Relaxed)
+            }
+        }
+
+        let mut supervisor = SupervisorActor::<u32, 8>::new()
+            .with_circuit_breaker(100)
+            .with_clock::<Clock>();
+        assert!(supervisor.add_child(1, None).is_ok());
+        supervisor.escalate_failure(&1, ActorError::Timeout);
+
+        NOW.store(250, Ordering::Relaxed);
+        assert!(supervisor.handle_child_failure(&1).is_some());
+
+        // The probe fails before reporting in: the cool-down starts over.
+        assert_eq!(supervisor.handle_child_failure(&1), None);
+        assert_eq!(
+            supervisor.circuit_state(&1),
+            Some(CircuitState::Open { until_ms: 350 })
+        );
+    }
 }
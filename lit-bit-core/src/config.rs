@@ -0,0 +1,151 @@
+//! Config-driven machine instantiation.
+//!
+//! A [`MachineConfig`] lets the initial state and the initial context of a machine be
+//! chosen at runtime, typically from a JSON or TOML file, instead of being fixed by the
+//! `statechart!` definition. Machines generated by the macro expose
+//! `from_config(&cfg, &initial_event)` (for contexts that implement `Deserialize`) and
+//! `from_config_with_context(&cfg, context, &initial_event)` (for everything else).
+//!
+//! The initial state override follows the same rule the macro enforces at compile time:
+//! only top-level states may be used as the initial state.
+
+use crate::runtime::{MachineDefinition, ProcessingError};
+
+// Re-exported so that generated code and users do not need their own serde dependencies.
+pub use serde;
+pub use serde_json;
+
+/// Runtime overrides for a machine's initial state and context.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MachineConfig {
+    /// Dot-separated name of a top-level state (e.g. `"Maintenance"`) to start in instead
+    /// of the declared initial state.
+    #[serde(default)]
+    pub initial: Option<String>,
+    /// Context seed. `null` (the default) deserializes as an empty object, so contexts with
+    /// `#[serde(default)]` fields can be seeded partially.
+    #[serde(default)]
+    pub context: serde_json::Value,
+}
+
+/// Errors returned when instantiating a machine from a [`MachineConfig`].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The requested initial state does not exist.
+    UnknownState {
+        requested: String,
+        valid: Vec<&'static str>,
+    },
+    /// The requested initial state exists but is nested inside another state.
+    NotTopLevel {
+        requested: String,
+        valid: Vec<&'static str>,
+    },
+    /// A restored snapshot names a state that is not a leaf of the machine.
+    UnknownSnapshotState { requested: String },
+    /// The context seed could not be deserialized into the machine's context type.
+    Context(serde_json::Error),
+    /// The runtime failed to enter the initial state.
+    Processing(ProcessingError),
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigError::UnknownState { requested, valid } => write!(
+                f,
+                "Unknown initial state '{requested}'; valid top-level states: {}",
+                valid.join(", ")
+            ),
+            ConfigError::NotTopLevel { requested, valid } => write!(
+                f,
+                "Initial state '{requested}' is not a top-level state; valid top-level states: {}",
+                valid.join(", ")
+            ),
+            ConfigError::UnknownSnapshotState { requested } => {
+                write!(
+                    f,
+                    "Snapshot state '{requested}' is not a leaf state of this machine"
+                )
+            }
+            ConfigError::Context(err) => write!(f, "Invalid context configuration: {err}"),
+            ConfigError::Processing(err) => write!(f, "Failed to enter initial state: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Context(err) => Some(err),
+            ConfigError::Processing(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl MachineConfig {
+    /// Resolves the configured initial state against `machine_def`.
+    ///
+    /// Returns `Ok(None)` if no override is configured. `parse` maps a user path to a state
+    /// id and `name_of` maps it back; the macro passes the generated `from_user_path` and
+    /// `user_path` functions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::UnknownState`] or [`ConfigError::NotTopLevel`], both listing
+    /// the valid top-level states.
+    pub fn resolve_initial<S, E, C>(
+        &self,
+        machine_def: &MachineDefinition<S, E, C>,
+        parse: impl Fn(&str) -> Option<S>,
+        name_of: impl Fn(S) -> &'static str,
+    ) -> Result<Option<S>, ConfigError>
+    where
+        S: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+        E: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+        C: Clone + 'static,
+    {
+        let Some(requested) = self.initial.as_deref() else {
+            return Ok(None);
+        };
+        let top_level = |state: S| machine_def.get_parent_of(state).is_none();
+        let valid = || {
+            machine_def
+                .states
+                .iter()
+                .filter(|node| node.parent.is_none())
+                .map(|node| name_of(node.id))
+                .collect()
+        };
+
+        match parse(requested) {
+            Some(state) if top_level(state) => Ok(Some(state)),
+            Some(_) => Err(ConfigError::NotTopLevel {
+                requested: requested.to_string(),
+                valid: valid(),
+            }),
+            None => Err(ConfigError::UnknownState {
+                requested: requested.to_string(),
+                valid: valid(),
+            }),
+        }
+    }
+
+    /// Deserializes the configured context seed into `C`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::Context`] if the seed does not match `C`.
+    pub fn deserialize_context<C>(&self) -> Result<C, ConfigError>
+    where
+        C: serde::de::DeserializeOwned,
+    {
+        let seed = if self.context.is_null() {
+            serde_json::Value::Object(serde_json::Map::new())
+        } else {
+            self.context.clone()
+        };
+        serde_json::from_value(seed).map_err(ConfigError::Context)
+    }
+}
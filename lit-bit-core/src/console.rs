@@ -0,0 +1,211 @@
+//! Text commands for driving a machine from a serial shell or diagnostic console.
+//!
+//! A [`MachineConsole`] borrows a machine and answers one command line at a time, writing
+//! its reply to any `core::fmt::Write` (a UART writer, a `heapless::String`, ...). It needs
+//! no allocator; the reply is streamed, and the only state it keeps is a fixed-size trace
+//! of the last `TRACE` events sent through it.
+//!
+//! | Command            | Reply                                                        |
+//! |--------------------|--------------------------------------------------------------|
+//! | `fsm state`        | each active state's path, one per line                       |
+//! | `fsm trace`        | the recent sends, oldest first: result, states left, entered |
+//! | `fsm send <event>` | the `SendResult`, after parsing `<event>` with the app's hook |
+//!
+//! Payload syntax is up to the application, so events are parsed by a hook given to
+//! [`MachineConsole::new`], as are state names (the generated `<Name>StateId::user_path`
+//! fits):
+//!
+//! ```rust,ignore
+//! fn parse_event(text: &str) -> Option<PumpEvent> {
+//!     match text {
+//!         "fault" => Some(PumpEvent::Fault),
+//!         _ => text.strip_prefix("set ")?.parse().ok().map(PumpEvent::SetSpeed),
+//!     }
+//! }
+//!
+//! let mut console = MachineConsole::<_, 8>::new(&mut pump, PumpStateId::user_path, parse_event);
+//! console.handle_line("fsm send set 40", &mut uart)?;
+//! ```
+
+use core::fmt::Write;
+
+use crate::StateMachine;
+use crate::runtime::{ConfigDiff, MAX_ACTIVE_REGIONS, SendResult};
+
+/// Line ending written after every line of a reply; serial terminals expect CRLF.
+pub const NEWLINE: &str = "\r\n";
+
+/// Why a command line could not be handled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConsoleError {
+    /// The line is not one of the `fsm` commands.
+    UnknownCommand,
+    /// `fsm send` was given no event.
+    MissingEvent,
+    /// The event hook did not recognize the text after `fsm send`.
+    UnknownEvent,
+    /// Writing the reply failed.
+    Output,
+}
+
+impl core::fmt::Display for ConsoleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConsoleError::UnknownCommand => {
+                write!(
+                    f,
+                    "Unknown command; expected `fsm state|trace|send <event>`."
+                )
+            }
+            ConsoleError::MissingEvent => write!(f, "`fsm send` needs an event."),
+            ConsoleError::UnknownEvent => write!(f, "Event not recognized."),
+            ConsoleError::Output => write!(f, "Writing the console output failed."),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConsoleError {}
+
+impl From<core::fmt::Error> for ConsoleError {
+    fn from(_: core::fmt::Error) -> Self {
+        ConsoleError::Output
+    }
+}
+
+/// One event sent through the console, as listed by `fsm trace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry<StateType, const N_ACTIVE: usize> {
+    /// Position of the send among all sends made through the console, from `1`.
+    pub seq: u32,
+    pub result: SendResult,
+    /// Active states that changed, comparing the configuration before and after the send.
+    pub diff: ConfigDiff<StateType, N_ACTIVE>,
+}
+
+/// Command interpreter for one machine; see the [module docs](self).
+///
+/// `TRACE` is the number of sends `fsm trace` remembers; older ones are dropped.
+pub struct MachineConsole<'a, M, const TRACE: usize, const N_ACTIVE: usize = MAX_ACTIVE_REGIONS>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    machine: &'a mut M,
+    state_path: fn(M::State) -> &'static str,
+    parse_event: fn(&str) -> Option<M::Event>,
+    trace: heapless::Deque<TraceEntry<M::State, N_ACTIVE>, TRACE>,
+    sends: u32,
+}
+
+impl<'a, M, const TRACE: usize, const N_ACTIVE: usize> MachineConsole<'a, M, TRACE, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    /// Creates a console for `machine`. `state_path` names states in replies;
+    /// `parse_event` turns the text after `fsm send ` into an event, or `None` if it names
+    /// no event.
+    pub fn new(
+        machine: &'a mut M,
+        state_path: fn(M::State) -> &'static str,
+        parse_event: fn(&str) -> Option<M::Event>,
+    ) -> Self {
+        Self {
+            machine,
+            state_path,
+            parse_event,
+            trace: heapless::Deque::new(),
+            sends: 0,
+        }
+    }
+
+    /// Runs one command line, writing the reply to `out`. Surrounding whitespace and runs
+    /// of spaces between words are ignored; a blank line does nothing.
+    ///
+    /// # Errors
+    /// Returns [`ConsoleError`] if the line is not a valid command or `out` fails. Nothing
+    /// is written for invalid commands, so the caller can print its own usage message.
+    pub fn handle_line(&mut self, line: &str, out: &mut impl Write) -> Result<(), ConsoleError> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(());
+        }
+        let mut words = line.split_whitespace();
+        if words.next() != Some("fsm") {
+            return Err(ConsoleError::UnknownCommand);
+        }
+        match words.next() {
+            Some("state") if words.next().is_none() => self.write_state(out),
+            Some("trace") if words.next().is_none() => self.write_trace(out),
+            Some("send") => {
+                // Pass the rest of the line on untouched: payloads may contain spaces.
+                let event_text = line.split_once("send").map_or("", |(_, rest)| rest).trim();
+                if event_text.is_empty() {
+                    return Err(ConsoleError::MissingEvent);
+                }
+                let event = (self.parse_event)(event_text).ok_or(ConsoleError::UnknownEvent)?;
+                let result = self.send(&event);
+                write!(out, "{result:?}{NEWLINE}")?;
+                Ok(())
+            }
+            _ => Err(ConsoleError::UnknownCommand),
+        }
+    }
+
+    /// Returns the sends `fsm trace` would list, oldest first.
+    pub fn trace(&self) -> impl Iterator<Item = &TraceEntry<M::State, N_ACTIVE>> {
+        self.trace.iter()
+    }
+
+    /// Returns the machine the console drives.
+    pub fn machine(&self) -> &M {
+        self.machine
+    }
+
+    fn send(&mut self, event: &M::Event) -> SendResult {
+        let before = self.machine.state();
+        let result = self.machine.send(event);
+        let after = self.machine.state();
+        self.sends = self.sends.wrapping_add(1);
+        if self.trace.is_full() {
+            self.trace.pop_front();
+        }
+        // TRACE == 0 keeps no trace at all; the push then fails and is dropped.
+        let _ = self.trace.push_back(TraceEntry {
+            seq: self.sends,
+            result,
+            diff: ConfigDiff::between(&before, &after),
+        });
+        result
+    }
+
+    fn write_state(&self, out: &mut impl Write) -> Result<(), ConsoleError> {
+        for state in self.machine.state() {
+            write!(out, "{}{NEWLINE}", (self.state_path)(state))?;
+        }
+        Ok(())
+    }
+
+    fn write_trace(&self, out: &mut impl Write) -> Result<(), ConsoleError> {
+        if self.trace.is_empty() {
+            write!(out, "(no events sent){NEWLINE}")?;
+        }
+        for entry in &self.trace {
+            write!(out, "#{} {:?}:", entry.seq, entry.result)?;
+            self.write_paths(&entry.diff.exited, out)?;
+            out.write_str(" ->")?;
+            self.write_paths(&entry.diff.entered, out)?;
+            out.write_str(NEWLINE)?;
+        }
+        Ok(())
+    }
+
+    fn write_paths(&self, states: &[M::State], out: &mut impl Write) -> Result<(), ConsoleError> {
+        if states.is_empty() {
+            out.write_str(" -")?;
+        }
+        for &state in states {
+            write!(out, " {}", (self.state_path)(state))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+//! State and transition coverage recording for test suites.
+//!
+//! A [`CoverageRecorder`] is a pair of fixed-size bitsets: one bit per state (indexed by
+//! the state's position in the machine definition) and one bit per transition (indexed by
+//! its ordinal in the transition table). It is injected into a runtime with
+//! `Runtime::set_coverage_recorder` and updated on every state entry and transition firing.
+//!
+//! The recorder is `no_std` and allocation free, so the same instrumentation works in
+//! embedded hardware-in-the-loop test runs. Bits are stored in `AtomicU32` words so a
+//! recorder can live in a `static`.
+//!
+//! ```rust,ignore
+//! static COVERAGE: CoverageRecorder = CoverageRecorder::new();
+//!
+//! let mut machine = MyMachine::new(ctx, &MyEvent::Start)?;
+//! machine.set_coverage_recorder(&COVERAGE);
+//! // ... drive the machine ...
+//! lit_bit_core::assert_full_coverage!(MyMachine, COVERAGE);
+//! ```
+
+#[cfg(not(feature = "portable-atomic"))]
+use core::sync::atomic::{AtomicU32, Ordering};
+#[cfg(feature = "portable-atomic")]
+use portable_atomic::{AtomicU32, Ordering};
+
+use crate::runtime::MachineDefinition;
+
+const WORD_BITS: usize = 32;
+const WORDS: usize = 8;
+
+/// Maximum number of states (and, separately, transitions) a recorder can track.
+pub const COVERAGE_CAPACITY: usize = WORDS * WORD_BITS;
+
+/// Line prefix used by [`CoverageReport::dump`]; `cargo xtask test --coverage` picks out
+/// the lines carrying it.
+pub const COVERAGE_DUMP_PREFIX: &str = "[lit-bit coverage]";
+
+struct AtomicBitSet {
+    words: [AtomicU32; WORDS],
+}
+
+impl AtomicBitSet {
+    const fn new() -> Self {
+        Self {
+            words: [const { AtomicU32::new(0) }; WORDS],
+        }
+    }
+
+    fn set(&self, index: usize) {
+        let Some(word) = self.words.get(index / WORD_BITS) else {
+            return;
+        };
+        let mask = 1 << (index % WORD_BITS);
+        #[cfg(any(target_has_atomic = "32", feature = "portable-atomic"))]
+        word.fetch_or(mask, Ordering::Relaxed);
+        // Targets without atomic read-modify-write (e.g. thumbv6m) run single-threaded tests
+        // unless `portable-atomic` is enabled.
+        #[cfg(not(any(target_has_atomic = "32", feature = "portable-atomic")))]
+        word.store(word.load(Ordering::Relaxed) | mask, Ordering::Relaxed);
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.words
+            .get(index / WORD_BITS)
+            .is_some_and(|word| word.load(Ordering::Relaxed) & (1 << (index % WORD_BITS)) != 0)
+    }
+
+    fn clear(&self) {
+        for word in &self.words {
+            word.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Records which states have been entered and which transitions have fired.
+pub struct CoverageRecorder {
+    states: AtomicBitSet,
+    transitions: AtomicBitSet,
+}
+
+impl CoverageRecorder {
+    /// Creates an empty recorder; usable in `static` items.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            states: AtomicBitSet::new(),
+            transitions: AtomicBitSet::new(),
+        }
+    }
+
+    /// Marks the state at `index` (its position in `MachineDefinition::states`) as entered.
+    pub fn record_state(&self, index: usize) {
+        self.states.set(index);
+    }
+
+    /// Marks the transition at `ordinal` (its position in `MachineDefinition::transitions`)
+    /// as fired.
+    pub fn record_transition(&self, ordinal: usize) {
+        self.transitions.set(ordinal);
+    }
+
+    /// Returns whether the state at `index` has been entered.
+    #[must_use]
+    pub fn state_entered(&self, index: usize) -> bool {
+        self.states.get(index)
+    }
+
+    /// Returns whether the transition at `ordinal` has fired.
+    #[must_use]
+    pub fn transition_fired(&self, ordinal: usize) -> bool {
+        self.transitions.get(ordinal)
+    }
+
+    /// Forgets everything recorded so far.
+    pub fn reset(&self) {
+        self.states.clear();
+        self.transitions.clear();
+    }
+
+    /// Builds a report of the states and transitions of `machine_def` that were never hit.
+    ///
+    /// `name_of` renders state ids; machines generated by `statechart!` pass their
+    /// `StateId::user_path` (see the generated `coverage_report`).
+    pub fn report<S, E, C>(
+        &self,
+        machine_def: &MachineDefinition<S, E, C>,
+        name_of: fn(S) -> &'static str,
+    ) -> CoverageReport<S>
+    where
+        S: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+        E: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+        C: Clone + 'static,
+    {
+        let mut report = CoverageReport {
+            total_states: machine_def.states.len(),
+            total_transitions: machine_def.transitions.len(),
+            never_entered: heapless::Vec::new(),
+            never_fired: heapless::Vec::new(),
+            name_of,
+        };
+        for (index, node) in machine_def.states.iter().enumerate() {
+            if !self.state_entered(index) {
+                // Capacity matches the bitset, so this can only drop states the
+                // recorder could not track anyway.
+                let _ = report.never_entered.push(node.id);
+            }
+        }
+        for (ordinal, transition) in machine_def.transitions.iter().enumerate() {
+            if !self.transition_fired(ordinal) {
+                let _ = report.never_fired.push(UncoveredTransition {
+                    ordinal,
+                    from: transition.from_state,
+                    to: transition.to_state,
+                });
+            }
+        }
+        report
+    }
+}
+
+impl Default for CoverageRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for CoverageRecorder {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let states = (0..COVERAGE_CAPACITY)
+            .filter(|&i| self.state_entered(i))
+            .count();
+        let transitions = (0..COVERAGE_CAPACITY)
+            .filter(|&i| self.transition_fired(i))
+            .count();
+        f.debug_struct("CoverageRecorder")
+            .field("states_entered", &states)
+            .field("transitions_fired", &transitions)
+            .finish()
+    }
+}
+
+/// A transition that never fired, identified by its ordinal and endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UncoveredTransition<S> {
+    pub ordinal: usize,
+    pub from: S,
+    pub to: S,
+}
+
+/// States and transitions a test run never exercised.
+#[derive(Debug, Clone)]
+pub struct CoverageReport<S> {
+    pub total_states: usize,
+    pub total_transitions: usize,
+    pub never_entered: heapless::Vec<S, COVERAGE_CAPACITY>,
+    pub never_fired: heapless::Vec<UncoveredTransition<S>, COVERAGE_CAPACITY>,
+    name_of: fn(S) -> &'static str,
+}
+
+impl<S: Copy> CoverageReport<S> {
+    /// Returns `true` if every state was entered and every transition fired.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.never_entered.is_empty() && self.never_fired.is_empty()
+    }
+
+    /// Iterates over the names of states that were never entered.
+    pub fn never_entered_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.never_entered
+            .iter()
+            .map(|&state| (self.name_of)(state))
+    }
+
+    /// Prints the report to stdout, one line per entry, prefixed with
+    /// [`COVERAGE_DUMP_PREFIX`] so `cargo xtask test --coverage` can collect it.
+    #[cfg(feature = "std")]
+    pub fn dump(&self, machine_name: &str) {
+        for line in self.to_string().lines() {
+            println!("{COVERAGE_DUMP_PREFIX} {machine_name}: {line}");
+        }
+    }
+}
+
+impl<S: Copy> core::fmt::Display for CoverageReport<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "states {}/{} entered, transitions {}/{} fired",
+            self.total_states - self.never_entered.len(),
+            self.total_states,
+            self.total_transitions - self.never_fired.len(),
+            self.total_transitions
+        )?;
+        for name in self.never_entered_names() {
+            write!(f, "\n  never entered: {name}")?;
+        }
+        for transition in &self.never_fired {
+            write!(
+                f,
+                "\n  never fired: #{} {} -> {}",
+                transition.ordinal,
+                (self.name_of)(transition.from),
+                (self.name_of)(transition.to)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Asserts that a recorder saw every state and transition of a `statechart!` machine,
+/// printing the uncovered ones on failure.
+///
+/// `$recorder` is a [`CoverageRecorder`] (or a reference to one).
+#[macro_export]
+macro_rules! assert_full_coverage {
+    ($machine:ty, $recorder:expr) => {{
+        let report = <$machine>::coverage_report(&$recorder);
+        assert!(
+            report.is_complete(),
+            "incomplete coverage for {}: {}",
+            stringify!($machine),
+            report
+        );
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits_are_independent_and_bounded() {
+        let recorder = CoverageRecorder::new();
+        recorder.record_state(0);
+        recorder.record_state(33);
+        recorder.record_transition(5);
+        // Out-of-range indices are ignored rather than panicking.
+        recorder.record_state(COVERAGE_CAPACITY + 1);
+
+        assert!(recorder.state_entered(0));
+        assert!(recorder.state_entered(33));
+        assert!(!recorder.state_entered(1));
+        assert!(!recorder.state_entered(COVERAGE_CAPACITY + 1));
+        assert!(recorder.transition_fired(5));
+        assert!(!recorder.transition_fired(0));
+
+        recorder.reset();
+        assert!(!recorder.state_entered(0));
+        assert!(!recorder.transition_fired(5));
+    }
+}
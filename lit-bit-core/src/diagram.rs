@@ -0,0 +1,150 @@
+//! Machine-readable export of a chart's structure, for web visualizers and other tooling.
+//!
+//! [`MachineDefinition::to_json`] renders the state tree and transitions as JSON:
+//!
+//! ```json
+//! {
+//!   "initial": "Idle",
+//!   "states": [
+//!     { "id": "Idle", "name": "Idle", "parallel": false, "initial": null, "children": [] },
+//!     { "id": "Active", "name": "Active", "parallel": false, "initial": "Active.Running",
+//!       "children": [ { "id": "Active.Running", ... } ] }
+//!   ],
+//!   "transitions": [
+//!     { "source": "Idle", "target": "Active", "events": ["Start"], "any_event": false,
+//!       "guarded": false }
+//!   ],
+//!   "constants": [ { "name": "MAX_VOLUME", "type": "u8", "value": "90" } ]
+//! }
+//! ```
+//!
+//! States are identified by their dotted paths. Machines generated by `statechart!` expose
+//! `definition_json()`, which passes `StateId::user_path` as the naming function. The
+//! graph itself only needs an allocator; rendering it as JSON also needs `std`.
+
+use alloc::vec::Vec;
+
+use serde::Serialize;
+
+use crate::runtime::MachineDefinition;
+
+/// A chart's states (as a tree) and transitions; serializes to the JSON shown in the
+/// module docs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChartGraph {
+    /// Path of the top-level initial state.
+    pub initial: &'static str,
+    /// Top-level states, in definition order.
+    pub states: Vec<GraphState>,
+    /// Every transition, in definition order.
+    pub transitions: Vec<GraphTransition>,
+    /// The chart's `const` items, in definition order.
+    pub constants: Vec<GraphConstant>,
+}
+
+/// A state and its children.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphState {
+    /// Full dotted path, unique within the chart.
+    pub id: &'static str,
+    /// Last segment of the path.
+    pub name: &'static str,
+    pub parallel: bool,
+    /// Path of the initial child of a compound state.
+    pub initial: Option<&'static str>,
+    pub children: Vec<GraphState>,
+}
+
+/// One transition, labelled with the event variants its pattern matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphTransition {
+    pub source: &'static str,
+    pub target: &'static str,
+    /// Event variant names, empty if the definition carries no event-kind table.
+    pub events: Vec<&'static str>,
+    /// The pattern matches every event (`_` or a binding).
+    pub any_event: bool,
+    pub guarded: bool,
+}
+
+/// A `const` item of the chart, with its type and value as written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphConstant {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub value: &'static str,
+}
+
+impl<S, E, C> MachineDefinition<S, E, C>
+where
+    S: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    E: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    C: Clone + 'static,
+{
+    /// Describes the chart as a [`ChartGraph`]; `name_of` renders state ids as dotted
+    /// paths.
+    pub fn graph(&self, name_of: fn(S) -> &'static str) -> ChartGraph {
+        let mut initial = self.initial_leaf_state;
+        while let Some(parent) = self.get_parent_of(initial) {
+            initial = parent;
+        }
+
+        let transitions = self
+            .transitions
+            .iter()
+            .enumerate()
+            .map(|(index, transition)| {
+                let kinds = self.event_kinds.get(index);
+                GraphTransition {
+                    source: name_of(transition.from_state),
+                    target: name_of(transition.to_state),
+                    events: kinds
+                        .map(|kinds| kinds.variants.iter().map(|&(name, _)| name).collect())
+                        .unwrap_or_default(),
+                    any_event: kinds.is_some_and(|kinds| kinds.any),
+                    guarded: transition.is_guarded(),
+                }
+            })
+            .collect();
+
+        ChartGraph {
+            initial: name_of(initial),
+            states: self.graph_children(None, name_of),
+            transitions,
+            constants: self
+                .constants
+                .iter()
+                .map(|constant| GraphConstant {
+                    name: constant.name,
+                    ty: constant.ty,
+                    value: constant.value,
+                })
+                .collect(),
+        }
+    }
+
+    /// Renders [`Self::graph`] as compact JSON.
+    #[cfg(feature = "std")]
+    pub fn to_json(&self, name_of: fn(S) -> &'static str) -> String {
+        serde_json::to_string(&self.graph(name_of))
+            .expect("a chart graph holds only strings, booleans and lists")
+    }
+
+    fn graph_children(&self, parent: Option<S>, name_of: fn(S) -> &'static str) -> Vec<GraphState> {
+        self.states
+            .iter()
+            .filter(|node| node.parent == parent)
+            .map(|node| {
+                let id = name_of(node.id);
+                GraphState {
+                    id,
+                    name: id.rsplit('.').next().unwrap_or(id),
+                    parallel: node.is_parallel,
+                    initial: node.initial_child.map(name_of),
+                    children: self.graph_children(Some(node.id), name_of),
+                }
+            })
+            .collect()
+    }
+}
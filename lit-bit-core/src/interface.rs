@@ -0,0 +1,70 @@
+//! Using a machine defined in another crate.
+//!
+//! A chart whose header contains `interface: true,` gets a `pub mod interface` next to the
+//! machine, and the machine implements [`MachineInterface`]. The module holds:
+//!
+//! - the machine struct and its `<Name>StateId` enum,
+//! - `DEFINITION_HASH`, a fingerprint of the chart's structure,
+//! - `HANDLED_KINDS` and `HANDLES_ANY_EVENT`, the event variants the chart reacts to,
+//! - `definition()`, the chart's static [`MachineDefinition`](crate::MachineDefinition).
+//!
+//! Another crate imports it with [`link_machine!`](crate::link_machine):
+//!
+//! ```rust,ignore
+//! lit_bit_core::link_machine!(motor_chart::MotorMachine);
+//!
+//! let motor = MotorMachine::new(MotorContext::default(), &MotorEvent::PowerOn)?;
+//! assert!(motor_machine::HANDLED_KINDS.contains(&"Start"));
+//! ```
+//!
+//! # Stability
+//!
+//! Everything reachable through the interface module follows the providing crate's semver:
+//! the machine's constructors and methods, the state id enum's variants and their
+//! `user_path` strings, and the names and types of the items listed above. The values of
+//! `DEFINITION_HASH` and `HANDLED_KINDS` change whenever the chart does, which is what they
+//! are for.
+//!
+//! The rest of what `statechart!` generates is an implementation detail and may change in
+//! any release of `lit-bit`: the `__<name>_generated` module, the
+//! `<NAME>_MACHINE_DEFINITION` constant, the `<NAME>_STATES` and `<NAME>_TRANSITIONS`
+//! tables, the matcher functions, and the discriminants of the state id enum. Code in other
+//! crates should only name items through the interface module.
+
+/// The stable surface of a chart generated with `interface: true,`.
+///
+/// Generic helpers that route events to machines from other crates can bound on this
+/// trait instead of naming generated items.
+pub trait MachineInterface {
+    /// The machine's state id enum.
+    type StateId: Copy + Eq + ::core::hash::Hash + ::core::fmt::Debug + 'static;
+    /// The type of the machine's static definition.
+    type Definition: 'static;
+
+    /// Fingerprint of the chart's states, hierarchy, initial states and transitions.
+    ///
+    /// Two builds that agree on the hash run the same chart. It is computed when the macro
+    /// expands, so it can be compared in `const` context, but it is not guaranteed to stay
+    /// the same across `lit-bit` releases.
+    const DEFINITION_HASH: u64;
+
+    /// Names of the event variants that appear in the chart's `on` patterns, in the order
+    /// they first appear.
+    const HANDLED_KINDS: &'static [&'static str];
+
+    /// Whether some transition matches every event (e.g. `on _ => ...`).
+    const HANDLES_ANY_EVENT: bool;
+
+    /// Returns the chart's static definition.
+    fn definition() -> &'static Self::Definition;
+
+    /// Returns the path of `state` as written in the chart, e.g. `"Running.Fast"`.
+    fn state_path(state: Self::StateId) -> &'static str;
+
+    /// Returns whether some transition in the chart could react to the event variant
+    /// named `kind`.
+    #[must_use]
+    fn handles(kind: &str) -> bool {
+        Self::HANDLES_ANY_EVENT || Self::HANDLED_KINDS.contains(&kind)
+    }
+}
@@ -26,7 +26,7 @@
 #![forbid(unsafe_code)]
 #![cfg_attr(feature = "nightly", feature(error_in_core))]
 
-#[cfg(feature = "alloc")]
+#[cfg(any(feature = "alloc", feature = "diagram"))]
 extern crate alloc;
 
 // Prevent invalid feature combinations
@@ -40,28 +40,241 @@ compile_error!(
 
 pub mod runtime;
 
+pub mod interface;
+
+pub mod console;
+
+pub mod scenario;
+
+#[cfg(feature = "std")]
+pub mod config;
+
+#[cfg(feature = "coverage")]
+pub mod coverage;
+
+#[cfg(feature = "proptest")]
+pub mod property;
+
+#[cfg(feature = "std")]
+pub mod upgrade;
+
+#[cfg(feature = "std")]
+pub mod replay;
+
+#[cfg(feature = "std")]
+pub mod outbox;
+
+#[cfg(feature = "diagram")]
+pub mod diagram;
+
+/// Expands to its input only when `lit-bit-core` is built with `std`.
+///
+/// Used by `statechart!` so that generated `std`-only items follow this crate's features
+/// rather than whatever features the calling crate declares.
+#[doc(hidden)]
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! __if_std {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "std"))]
+#[macro_export]
+macro_rules! __if_std {
+    ($($tokens:tt)*) => {};
+}
+
+/// Expands to its input only when `lit-bit-core` is built with `diagram`.
+#[doc(hidden)]
+#[cfg(feature = "diagram")]
+#[macro_export]
+macro_rules! __if_diagram {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "diagram"))]
+#[macro_export]
+macro_rules! __if_diagram {
+    ($($tokens:tt)*) => {};
+}
+
+/// Expands to its input only when `lit-bit-core` is built with `coverage`.
+#[doc(hidden)]
+#[cfg(feature = "coverage")]
+#[macro_export]
+macro_rules! __if_coverage {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "coverage"))]
+#[macro_export]
+macro_rules! __if_coverage {
+    ($($tokens:tt)*) => {};
+}
+
+/// Expands to its input only when `lit-bit-core` is built with `oscillation-guard`.
+#[doc(hidden)]
+#[cfg(feature = "oscillation-guard")]
+#[macro_export]
+macro_rules! __if_oscillation_guard {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "oscillation-guard"))]
+#[macro_export]
+macro_rules! __if_oscillation_guard {
+    ($($tokens:tt)*) => {};
+}
+
+/// Expands to its input only when `lit-bit-core` is built with `async`.
+#[doc(hidden)]
+#[cfg(feature = "async")]
+#[macro_export]
+macro_rules! __if_async {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "async"))]
+#[macro_export]
+macro_rules! __if_async {
+    ($($tokens:tt)*) => {};
+}
+
+/// Expands to its input only when `lit-bit-core` is built with `async-tokio` (and not
+/// `async-embassy`), the configuration whose `Address` is backed by a Tokio channel.
+#[doc(hidden)]
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+#[macro_export]
+macro_rules! __if_tokio {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(all(feature = "async-tokio", not(feature = "async-embassy"))))]
+#[macro_export]
+macro_rules! __if_tokio {
+    ($($tokens:tt)*) => {};
+}
+
+/// Expands to its input only when `lit-bit-core` is built with `debug-log`; the input can
+/// reach the `log` crate through `lit_bit_core::__log`.
+#[doc(hidden)]
+#[cfg(feature = "debug-log")]
+#[macro_export]
+macro_rules! __if_debug_log {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "debug-log"))]
+#[macro_export]
+macro_rules! __if_debug_log {
+    ($($tokens:tt)*) => {};
+}
+
+#[doc(hidden)]
+#[cfg(feature = "debug-log")]
+pub use log as __log;
+
+/// Expands to its input only when `lit-bit-core` is built with `proptest`.
+#[doc(hidden)]
+#[cfg(feature = "proptest")]
+#[macro_export]
+macro_rules! __if_proptest {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "proptest"))]
+#[macro_export]
+macro_rules! __if_proptest {
+    ($($tokens:tt)*) => {};
+}
+
+/// Names the runtime type `statechart!` uses for a chart without parallel states:
+/// [`FlatRuntime`] when `lit-bit-core` is built with `single-region`, [`Runtime`] otherwise.
+#[doc(hidden)]
+#[cfg(feature = "single-region")]
+#[macro_export]
+macro_rules! __flat_chart_runtime {
+    ($state:ty, $event:ty, $context:ty, $m:expr, $max_nodes:expr) => {
+        $crate::FlatRuntime<$state, $event, $context, { $m }>
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "single-region"))]
+#[macro_export]
+macro_rules! __flat_chart_runtime {
+    ($state:ty, $event:ty, $context:ty, $m:expr, $max_nodes:expr) => {
+        $crate::Runtime<
+            $state,
+            $event,
+            $context,
+            { $m },
+            { $crate::MAX_ACTIVE_REGIONS },
+            { $max_nodes },
+        >
+    };
+}
+
 // Re-export macros from lit_bit_macro
-pub use lit_bit_macro::{statechart, statechart_event};
+pub use lit_bit_macro::{link_machine, statechart, statechart_event, statechart_inline};
 
 // Re-export key types/traits for easier use by consumers of the crate.
+pub use interface::MachineInterface;
 pub use runtime::ActionFn; // Re-export function types for macro use
+pub use runtime::BatchSendSummary;
+pub use runtime::ChartConstant;
+#[cfg(feature = "std")]
+pub use runtime::Completion;
+pub use runtime::ConfigDiff;
+#[doc(hidden)]
+pub use runtime::DebugStatePaths;
 pub use runtime::DefaultContext;
 pub use runtime::EntryExitActionFn;
+pub use runtime::EventFilterFn;
+#[doc(hidden)]
+pub use runtime::EventSizeBudget;
+#[cfg(feature = "single-region")]
+pub use runtime::FlatRuntime;
 pub use runtime::GuardFn;
+pub use runtime::InvariantFn;
 pub use runtime::MAX_ACTIVE_REGIONS;
 pub use runtime::MachineDefinition; // If users need to construct this manually
+pub use runtime::MonotonicClock;
 pub use runtime::ProcessingError; // Re-export ProcessingError for error handling
+pub use runtime::ReadActionFn;
 pub use runtime::Runtime; // If users need to construct this manually
 pub use runtime::SendResult; // Re-export SendResult for public use
+pub use runtime::SpeculationResult;
+pub use runtime::StateGuardFn;
 pub use runtime::StateNode; // If users need to construct this manually
-pub use runtime::Transition; // If users need to construct this manually
+pub use runtime::Transition;
+pub use runtime::UnknownStateIndex;
+pub use runtime::UserPath;
+pub use runtime::{Acceptance, EventKind, TransitionEventKinds};
+pub use runtime::{
+    ActionError, ActionOutcome, FallibleAction, FallibleActionFn, RetryObserverFn, RetryReport,
+};
+pub use runtime::{AsyncGuardVerdicts, MAX_ASYNC_GUARD_VERDICTS, PendingAsyncGuard};
+pub use runtime::{Configuration, StateSet};
+pub use runtime::{DenyTargetSet, DenyTargets, PolicyHost, TransitionPolicy};
+#[cfg(feature = "oscillation-guard")]
+pub use runtime::{OscillationGuard, OscillationResponse, OscillationWindow};
+pub use runtime::{RaisedEventFn, Ticks}; // If users need to construct this manually
 
 // Re-export key actor types for easier access
 pub use actor::address::Address;
 pub use actor::backpressure::SendError;
 
 // Re-export actor types that are always available
-pub use actor::{Actor, ActorError, RestartStrategy};
+pub use actor::{Actor, ActorError, RestartStrategy, StopReason};
 
 // Re-export supervision types for convenience (Task 5.1 & 5.4)
 pub use actor::{Supervisor, SupervisorActor, SupervisorError, SupervisorMessage};
@@ -97,7 +310,7 @@ pub use actor::spawn::CounterActor;
 
 // Re-export timer types for async support
 #[cfg(feature = "async")]
-pub use timer::{Timer, TimerService};
+pub use timer::{Timer, TimerService, TimerToken};
 
 pub trait StateMachine<const N_ACTIVE: usize = MAX_ACTIVE_REGIONS> {
     type State: Copy
@@ -113,12 +326,86 @@ pub trait StateMachine<const N_ACTIVE: usize = MAX_ACTIVE_REGIONS> {
 
     type Context: Clone + 'static;
 
+    #[must_use = "check whether the event was handled"]
     fn send(&mut self, event: &Self::Event) -> SendResult;
+
+    /// Sends `events` one after another, each with run-to-completion semantics, and
+    /// summarizes the results. Processing continues past events that fail.
+    fn send_batch(&mut self, events: &[Self::Event]) -> BatchSendSummary<Self::State, N_ACTIVE> {
+        let mut summary = BatchSendSummary::default();
+        for event in events {
+            summary.record(self.send(event));
+        }
+        summary.final_state = self.state();
+        summary
+    }
+
+    /// Exits every active state and the machine's implicit root, so the next `send` or
+    /// `start` enters the initial state again; see [`Runtime::reset`]. The default does
+    /// nothing, for machines that have nothing to tear down.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingError`] if the exit sequence cannot be computed.
+    fn reset(&mut self, event: &Self::Event) -> Result<(), ProcessingError> {
+        let _ = event;
+        Ok(())
+    }
+
+    /// Returns the active leaf states in no particular order: two machines in the same
+    /// parallel configuration may list them differently. Compare or hash
+    /// [`Configuration`]s, from the runtimes' and generated machines' `configuration()`,
+    /// instead.
     fn state(&self) -> heapless::Vec<Self::State, N_ACTIVE>;
     fn context(&self) -> &Self::Context;
     fn context_mut(&mut self) -> &mut Self::Context;
 }
 
+/// Call-site shorthands built on [`StateMachine`], implemented for every machine.
+///
+/// The name of the active state needs the chart's generated path mapping, so it is the
+/// generated machines' own `current_name()` rather than a method here.
+pub trait StateMachineExt<const N_ACTIVE: usize = MAX_ACTIVE_REGIONS>:
+    StateMachine<N_ACTIVE>
+{
+    /// Sends `event` if `predicate` holds for the machine as it is now. Returns `None`
+    /// without sending otherwise.
+    fn send_if(
+        &mut self,
+        event: &Self::Event,
+        predicate: impl FnOnce(&Self) -> bool,
+    ) -> Option<SendResult> {
+        predicate(self).then(|| self.send(event))
+    }
+
+    /// Sends `events` one after another like [`StateMachine::send_batch`], but stops after
+    /// the first event whose result `keep_going` rejects; later events are not pulled
+    /// from the iterator.
+    fn send_while<I>(
+        &mut self,
+        events: I,
+        mut keep_going: impl FnMut(&SendResult) -> bool,
+    ) -> BatchSendSummary<Self::State, N_ACTIVE>
+    where
+        I: IntoIterator,
+        I::Item: core::borrow::Borrow<Self::Event>,
+    {
+        let mut summary = BatchSendSummary::default();
+        for event in events {
+            let result = self.send(core::borrow::Borrow::borrow(&event));
+            let go_on = keep_going(&result);
+            summary.record(result);
+            if !go_on {
+                break;
+            }
+        }
+        summary.final_state = self.state();
+        summary
+    }
+}
+
+impl<M: StateMachine<N_ACTIVE> + ?Sized, const N_ACTIVE: usize> StateMachineExt<N_ACTIVE> for M {}
+
 #[cfg(test)]
 mod re_export_tests {
     //! Tests to verify that key actor types are properly re-exported at the top level
@@ -155,8 +442,8 @@ mod re_export_tests {
 #[cfg(feature = "async")]
 pub mod timer;
 
-// Test utilities module - only available with test or test-probes feature
-#[cfg(any(test, feature = "test-probes"))]
+// Test utilities module - the actor test infrastructure inside needs the test or
+// test-probes feature
 pub mod test_utils;
 
 // Re-export test utilities for convenient access (Task 5.3)
@@ -0,0 +1,780 @@
+//! Transactional outbox: effects a machine's actions emit, committed together with the
+//! machine's state and delivered at least once.
+//!
+//! An action that publishes to Kafka or NATS directly can leave the context changed and the
+//! message unsent (or the other way round) if the process dies in between. Here, actions
+//! only append to an [`Effects`] buffer in the context ([`EmitsEffects`]), and
+//! [`Outbox::send`] moves what one send emitted into an [`OutboxStore`] in a single append,
+//! together with a [`Checkpoint`] of the state and context it left behind. An
+//! [`OutboxRelay`] then takes the effects out of the store one at a time, hands them to a
+//! [`Publisher`], and acknowledges each one after it was published, which truncates it
+//! from the store:
+//!
+//! ```rust,ignore
+//! let outbox = Outbox::new(FileOutboxStore::open("orders.outbox")?);
+//! let mut orders = match outbox.checkpoint() {
+//!     Some(checkpoint) => Orders::from_snapshot(&checkpoint.state, checkpoint.context)?,
+//!     None => Orders::new(Shop::default(), &OrderEvent::Boot)?,
+//! };
+//! let relay = spawn_actor_tokio(outbox.relay(KafkaPublisher::new(producer)), 4);
+//!
+//! outbox.send(&mut orders, &OrderEvent::Place(42))?;
+//! relay.send(Drain).await?;
+//! ```
+//!
+//! Delivery is at least once. An effect the relay handed out but did not get to acknowledge,
+//! because the publisher failed or the relay was dropped, is handed out again by the next
+//! drain with [`Delivery::redelivered`] set, so the publisher can deduplicate by
+//! [`EffectId`]. Nothing that was committed is skipped.
+//!
+//! [`MemoryOutboxStore`] keeps the outbox in memory (for tests, or when losing it with the
+//! process is fine); [`FileOutboxStore`] keeps it in an append-only log of JSON lines.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::actor::Actor;
+use crate::upgrade::StateSnapshot;
+use crate::{SendResult, StateMachine, UserPath};
+
+// --- Emitting effects ---
+
+/// Effects emitted by a machine's actions that are not committed yet; see the
+/// [module docs](self).
+///
+/// The buffer is empty whenever [`Outbox::send`] takes a checkpoint, so it serializes as
+/// an empty list along with the rest of the context.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Effects<E> {
+    emitted: Vec<E>,
+}
+
+impl<E> Default for Effects<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Effects<E> {
+    /// Creates an empty buffer.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            emitted: Vec::new(),
+        }
+    }
+
+    /// Emits `effect`; it is committed when the current send finishes.
+    pub fn emit(&mut self, effect: E) {
+        self.emitted.push(effect);
+    }
+
+    /// Returns the effects emitted since the last commit, oldest first.
+    #[must_use]
+    pub fn emitted(&self) -> &[E] {
+        &self.emitted
+    }
+
+    fn take(&mut self) -> Vec<E> {
+        core::mem::take(&mut self.emitted)
+    }
+}
+
+/// A machine context whose actions emit effects into an [`Effects`] buffer.
+pub trait EmitsEffects {
+    /// The effects the actions emit, e.g. the messages to publish.
+    type Effect;
+
+    /// Returns the context's effect buffer.
+    fn effects(&mut self) -> &mut Effects<Self::Effect>;
+}
+
+// --- Stores ---
+
+/// Identifies a committed effect; ids grow in commit order and are never reused.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct EffectId(pub u64);
+
+impl core::fmt::Display for EffectId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// What one send left behind: the machine's state and context, and the effects it emitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit<C, E> {
+    pub state: StateSnapshot,
+    pub context: C,
+    pub effects: Vec<E>,
+}
+
+/// The machine state recorded by a store's last commit, to restore a machine from.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint<C> {
+    /// Number of commits the store has taken, this one included.
+    pub step: u64,
+    pub state: StateSnapshot,
+    pub context: C,
+}
+
+/// A committed effect handed to a [`Publisher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery<E> {
+    pub id: EffectId,
+    pub effect: E,
+    /// `true` if the effect was handed out before without being acknowledged, so it may
+    /// already have been published.
+    pub redelivered: bool,
+}
+
+/// Durable storage for an outbox; see the [module docs](self).
+pub trait OutboxStore {
+    /// Context of the machine whose state is checkpointed.
+    type Context;
+    /// Effects stored until they are delivered.
+    type Effect;
+    type Error: core::fmt::Debug + core::fmt::Display;
+
+    /// Appends a commit as one unit: after a crash, the store holds either the checkpoint
+    /// and all the effects of `commit`, or none of it. Returns the ids given to the effects.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error if the commit could not be persisted.
+    fn append(
+        &mut self,
+        commit: Commit<Self::Context, Self::Effect>,
+    ) -> Result<core::ops::Range<u64>, Self::Error>;
+
+    /// Returns the checkpoint of the last commit, if any.
+    fn checkpoint(&self) -> Option<Checkpoint<Self::Context>>;
+
+    /// Returns the oldest effect not acknowledged yet, and records that it was handed out.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error if the hand-out could not be recorded.
+    fn next_delivery(&mut self) -> Result<Option<Delivery<Self::Effect>>, Self::Error>;
+
+    /// Marks the effect `id` and every effect before it as delivered, and drops them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error if the acknowledgement could not be persisted.
+    fn acknowledge(&mut self, id: EffectId) -> Result<(), Self::Error>;
+
+    /// Returns how many effects are waiting to be acknowledged.
+    fn pending(&self) -> usize;
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Pending<E> {
+    id: EffectId,
+    effect: E,
+    handed_out: bool,
+}
+
+/// An [`OutboxStore`] in memory, lost with the process.
+#[derive(Debug, Clone)]
+pub struct MemoryOutboxStore<C, E> {
+    checkpoint: Option<Checkpoint<C>>,
+    pending: VecDeque<Pending<E>>,
+    next_effect: u64,
+}
+
+impl<C, E> Default for MemoryOutboxStore<C, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, E> MemoryOutboxStore<C, E> {
+    /// Creates an empty store.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            checkpoint: None,
+            pending: VecDeque::new(),
+            next_effect: 0,
+        }
+    }
+
+    fn commit(
+        &mut self,
+        state: StateSnapshot,
+        context: C,
+        effects: Vec<E>,
+    ) -> core::ops::Range<u64> {
+        let step = self
+            .checkpoint
+            .as_ref()
+            .map_or(0, |checkpoint| checkpoint.step)
+            + 1;
+        self.checkpoint = Some(Checkpoint {
+            step,
+            state,
+            context,
+        });
+        let first = self.next_effect;
+        for effect in effects {
+            self.pending.push_back(Pending {
+                id: EffectId(self.next_effect),
+                effect,
+                handed_out: false,
+            });
+            self.next_effect += 1;
+        }
+        first..self.next_effect
+    }
+
+    fn hand_out(&mut self) -> Option<&mut Pending<E>> {
+        self.pending.front_mut()
+    }
+
+    fn truncate(&mut self, id: EffectId) {
+        while self.pending.front().is_some_and(|pending| pending.id <= id) {
+            self.pending.pop_front();
+        }
+    }
+}
+
+impl<C: Clone, E: Clone> OutboxStore for MemoryOutboxStore<C, E> {
+    type Context = C;
+    type Effect = E;
+    type Error = core::convert::Infallible;
+
+    fn append(&mut self, commit: Commit<C, E>) -> Result<core::ops::Range<u64>, Self::Error> {
+        Ok(self.commit(commit.state, commit.context, commit.effects))
+    }
+
+    fn checkpoint(&self) -> Option<Checkpoint<C>> {
+        self.checkpoint.clone()
+    }
+
+    fn next_delivery(&mut self) -> Result<Option<Delivery<E>>, Self::Error> {
+        Ok(self.hand_out().map(|pending| {
+            let redelivered = core::mem::replace(&mut pending.handed_out, true);
+            Delivery {
+                id: pending.id,
+                effect: pending.effect.clone(),
+                redelivered,
+            }
+        }))
+    }
+
+    fn acknowledge(&mut self, id: EffectId) -> Result<(), Self::Error> {
+        self.truncate(id);
+        Ok(())
+    }
+
+    fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Number of log lines after which a [`FileOutboxStore`] compacts its file.
+pub const COMPACT_AFTER: usize = 1024;
+
+/// One line of a [`FileOutboxStore`] log.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LogLine<C, E> {
+    Commit {
+        state: StateSnapshot,
+        context: C,
+        effects: Vec<E>,
+    },
+    HandedOut {
+        id: EffectId,
+    },
+    Ack {
+        id: EffectId,
+    },
+    /// Written by compaction: an unacknowledged effect of a dropped commit line.
+    Pending(Pending<E>),
+    /// Written by compaction: the last checkpoint, and the id the next effect gets.
+    Checkpoint {
+        checkpoint: Checkpoint<C>,
+        next_effect: u64,
+    },
+}
+
+/// Errors returned by a [`FileOutboxStore`].
+#[derive(Debug)]
+pub enum FileStoreError {
+    /// Reading or writing the log failed.
+    Io(io::Error),
+    /// A commit could not be serialized.
+    Encode(serde_json::Error),
+    /// A complete line of the log could not be parsed; `line` counts from 1.
+    Corrupt {
+        line: usize,
+        error: serde_json::Error,
+    },
+}
+
+impl core::fmt::Display for FileStoreError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FileStoreError::Io(err) => write!(f, "Outbox log I/O failed: {err}"),
+            FileStoreError::Encode(err) => write!(f, "Could not encode an outbox entry: {err}"),
+            FileStoreError::Corrupt { line, error } => {
+                write!(f, "Outbox log line {line} is corrupt: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FileStoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileStoreError::Io(err) => Some(err),
+            FileStoreError::Encode(err) | FileStoreError::Corrupt { error: err, .. } => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for FileStoreError {
+    fn from(err: io::Error) -> Self {
+        FileStoreError::Io(err)
+    }
+}
+
+/// An [`OutboxStore`] kept in an append-only log of JSON lines.
+///
+/// Every commit, hand-out and acknowledgement is one line, written with a single write and
+/// synced before the call returns. A line cut short by a crash is dropped when the log is
+/// opened again, which undoes the commit it belonged to as a whole. Once the log has
+/// [`COMPACT_AFTER`] lines, it is rewritten with only the last checkpoint and the
+/// unacknowledged effects, and swapped in by a rename.
+#[derive(Debug)]
+pub struct FileOutboxStore<C, E> {
+    path: PathBuf,
+    file: File,
+    lines: usize,
+    memory: MemoryOutboxStore<C, E>,
+}
+
+impl<C, E> FileOutboxStore<C, E>
+where
+    C: Serialize + DeserializeOwned,
+    E: Serialize + DeserializeOwned,
+{
+    /// Opens the log at `path`, creating it if needed, and replays it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileStoreError::Io`] if the file cannot be read or repaired, and
+    /// [`FileStoreError::Corrupt`] if a complete line does not parse.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FileStoreError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+
+        let mut memory = MemoryOutboxStore::new();
+        let mut lines = 0;
+        let mut intact = 0;
+        let mut reader = BufReader::new(&file);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 || !line.ends_with('\n') {
+                break;
+            }
+            let entry = serde_json::from_str::<LogLine<C, E>>(&line).map_err(|error| {
+                FileStoreError::Corrupt {
+                    line: lines + 1,
+                    error,
+                }
+            })?;
+            replay(&mut memory, entry);
+            lines += 1;
+            intact += read as u64;
+        }
+        // Anything after the last newline is a write cut short by a crash.
+        if file.metadata()?.len() > intact {
+            file.set_len(intact)?;
+            file.sync_data()?;
+        }
+
+        Ok(Self {
+            path,
+            file,
+            lines,
+            memory,
+        })
+    }
+
+    /// Returns the path of the log.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Rewrites the log with only the last checkpoint and the unacknowledged effects.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileStoreError`] if the new log cannot be written or swapped in; the old
+    /// log is then left in place.
+    pub fn compact(&mut self) -> Result<(), FileStoreError> {
+        let mut compacted = self.path.clone().into_os_string();
+        compacted.push(".compact");
+        let compacted = PathBuf::from(compacted);
+
+        let mut buffer = Vec::new();
+        let mut lines = 0;
+        for pending in &self.memory.pending {
+            encode_into(
+                &mut buffer,
+                &LogLine::<&C, &E>::Pending(Pending {
+                    id: pending.id,
+                    effect: &pending.effect,
+                    handed_out: pending.handed_out,
+                }),
+            )?;
+            lines += 1;
+        }
+        if let Some(checkpoint) = &self.memory.checkpoint {
+            encode_into(
+                &mut buffer,
+                &LogLine::<&C, &E>::Checkpoint {
+                    checkpoint: Checkpoint {
+                        step: checkpoint.step,
+                        state: checkpoint.state.clone(),
+                        context: &checkpoint.context,
+                    },
+                    next_effect: self.memory.next_effect,
+                },
+            )?;
+            lines += 1;
+        }
+
+        let mut file = File::create(&compacted)?;
+        file.write_all(&buffer)?;
+        file.sync_all()?;
+        fs::rename(&compacted, &self.path)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.lines = lines;
+        Ok(())
+    }
+
+    fn write(&mut self, entry: &LogLine<&C, &E>) -> Result<(), FileStoreError> {
+        let mut buffer = Vec::new();
+        encode_into(&mut buffer, entry)?;
+        self.file.write_all(&buffer)?;
+        self.file.sync_data()?;
+        self.lines += 1;
+        if self.lines >= COMPACT_AFTER {
+            self.compact()?;
+        }
+        Ok(())
+    }
+}
+
+fn encode_into<T: Serialize>(buffer: &mut Vec<u8>, entry: &T) -> Result<(), FileStoreError> {
+    serde_json::to_writer(&mut *buffer, entry).map_err(FileStoreError::Encode)?;
+    buffer.push(b'\n');
+    Ok(())
+}
+
+fn replay<C, E>(memory: &mut MemoryOutboxStore<C, E>, entry: LogLine<C, E>) {
+    match entry {
+        LogLine::Commit {
+            state,
+            context,
+            effects,
+        } => {
+            memory.commit(state, context, effects);
+        }
+        LogLine::HandedOut { id } => {
+            if let Some(pending) = memory.pending.iter_mut().find(|pending| pending.id == id) {
+                pending.handed_out = true;
+            }
+        }
+        LogLine::Ack { id } => memory.truncate(id),
+        LogLine::Pending(pending) => {
+            memory.next_effect = memory.next_effect.max(pending.id.0 + 1);
+            memory.pending.push_back(pending);
+        }
+        LogLine::Checkpoint {
+            checkpoint,
+            next_effect,
+        } => {
+            memory.checkpoint = Some(checkpoint);
+            memory.next_effect = memory.next_effect.max(next_effect);
+        }
+    }
+}
+
+impl<C, E> OutboxStore for FileOutboxStore<C, E>
+where
+    C: Clone + Serialize + DeserializeOwned,
+    E: Clone + Serialize + DeserializeOwned,
+{
+    type Context = C;
+    type Effect = E;
+    type Error = FileStoreError;
+
+    fn append(&mut self, commit: Commit<C, E>) -> Result<core::ops::Range<u64>, Self::Error> {
+        self.write(&LogLine::Commit {
+            state: commit.state.clone(),
+            context: &commit.context,
+            effects: commit.effects.iter().collect(),
+        })?;
+        Ok(self
+            .memory
+            .commit(commit.state, commit.context, commit.effects))
+    }
+
+    fn checkpoint(&self) -> Option<Checkpoint<C>> {
+        self.memory.checkpoint()
+    }
+
+    fn next_delivery(&mut self) -> Result<Option<Delivery<E>>, Self::Error> {
+        let Some(pending) = self.memory.hand_out() else {
+            return Ok(None);
+        };
+        let delivery = Delivery {
+            id: pending.id,
+            effect: pending.effect.clone(),
+            redelivered: pending.handed_out,
+        };
+        if !delivery.redelivered {
+            self.write(&LogLine::HandedOut { id: delivery.id })?;
+            if let Some(pending) = self.memory.hand_out() {
+                pending.handed_out = true;
+            }
+        }
+        Ok(Some(delivery))
+    }
+
+    fn acknowledge(&mut self, id: EffectId) -> Result<(), Self::Error> {
+        self.write(&LogLine::Ack { id })?;
+        self.memory.truncate(id);
+        Ok(())
+    }
+
+    fn pending(&self) -> usize {
+        self.memory.pending.len()
+    }
+}
+
+// --- Committing sends ---
+
+fn lock<S>(store: &Mutex<S>) -> MutexGuard<'_, S> {
+    // A panic while the store was locked left it between two whole operations.
+    store.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sends events to machines and commits what each send emitted; see the
+/// [module docs](self).
+#[derive(Debug)]
+pub struct Outbox<S> {
+    store: Arc<Mutex<S>>,
+}
+
+impl<S> Clone for Outbox<S> {
+    fn clone(&self) -> Self {
+        Self {
+            store: Arc::clone(&self.store),
+        }
+    }
+}
+
+impl<S: OutboxStore> Outbox<S> {
+    /// Creates an outbox committing to `store`.
+    #[must_use]
+    pub fn new(store: S) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+        }
+    }
+
+    /// Returns the store, shared with the relays of this outbox.
+    #[must_use]
+    pub fn store(&self) -> &Arc<Mutex<S>> {
+        &self.store
+    }
+
+    /// Returns the checkpoint of the last commit, to restore the machine from after a
+    /// restart.
+    #[must_use]
+    pub fn checkpoint(&self) -> Option<Checkpoint<S::Context>> {
+        lock(&self.store).checkpoint()
+    }
+
+    /// Creates a relay delivering this outbox's effects to `publisher`.
+    #[must_use]
+    pub fn relay<P: Publisher<S::Effect>>(&self, publisher: P) -> OutboxRelay<S, P> {
+        OutboxRelay::new(Arc::clone(&self.store), publisher)
+    }
+
+    /// Sends `event` to `machine`, then commits its new state and context with the effects
+    /// the send emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error if the commit failed. The machine has taken the step
+    /// anyway and is ahead of the store; restore it from [`checkpoint`](Self::checkpoint)
+    /// before sending it anything else.
+    pub fn send<M, const N: usize>(
+        &self,
+        machine: &mut M,
+        event: &M::Event,
+    ) -> Result<SendResult, S::Error>
+    where
+        M: StateMachine<N>,
+        M::State: UserPath,
+        M::Context: EmitsEffects<Effect = S::Effect>,
+        S: OutboxStore<Context = M::Context>,
+    {
+        let result = machine.send(event);
+        let effects = machine.context_mut().effects().take();
+        let commit = Commit {
+            state: StateSnapshot::new(machine.state().iter().map(|state| state.user_path())),
+            context: machine.context().clone(),
+            effects,
+        };
+        lock(&self.store).append(commit)?;
+        Ok(result)
+    }
+}
+
+// --- Delivering effects ---
+
+/// Publishes committed effects, e.g. to a message broker.
+pub trait Publisher<E> {
+    type Error: core::fmt::Debug + core::fmt::Display;
+
+    /// Publishes one effect. The effect is acknowledged once the future returns `Ok`;
+    /// until then, it will be handed out again.
+    fn publish(
+        &mut self,
+        delivery: &Delivery<E>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// Errors that stop an [`OutboxRelay::drain`].
+#[derive(Debug)]
+pub enum RelayError<S, P> {
+    /// The store failed to hand out or acknowledge an effect.
+    Store(S),
+    /// The publisher failed to publish the effect `id`; it will be redelivered.
+    Publish { id: EffectId, error: P },
+}
+
+impl<S: core::fmt::Display, P: core::fmt::Display> core::fmt::Display for RelayError<S, P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RelayError::Store(err) => write!(f, "Outbox store failed: {err}"),
+            RelayError::Publish { id, error } => {
+                write!(f, "Publishing effect {id} failed: {error}")
+            }
+        }
+    }
+}
+
+impl<S, P> std::error::Error for RelayError<S, P>
+where
+    S: core::fmt::Debug + core::fmt::Display,
+    P: core::fmt::Debug + core::fmt::Display,
+{
+}
+
+/// Message asking an [`OutboxRelay`] actor to deliver every pending effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Drain;
+
+/// Delivers an outbox's effects to a [`Publisher`] and acknowledges them; see the
+/// [module docs](self).
+///
+/// Run it as an actor (each [`Drain`] message delivers what is pending, and failures are
+/// logged and retried on the next one) or call [`drain`](Self::drain) directly.
+#[derive(Debug)]
+pub struct OutboxRelay<S, P> {
+    store: Arc<Mutex<S>>,
+    publisher: P,
+}
+
+impl<S, P> OutboxRelay<S, P>
+where
+    S: OutboxStore,
+    P: Publisher<S::Effect>,
+{
+    /// Creates a relay delivering the effects in `store` to `publisher`.
+    #[must_use]
+    pub fn new(store: Arc<Mutex<S>>, publisher: P) -> Self {
+        Self { store, publisher }
+    }
+
+    /// Returns the publisher.
+    #[must_use]
+    pub fn publisher(&self) -> &P {
+        &self.publisher
+    }
+
+    /// Publishes pending effects in commit order until none are left, and returns how many
+    /// were acknowledged.
+    ///
+    /// Dropping the future part-way is safe: an effect handed to the publisher but not
+    /// acknowledged is redelivered by the next drain.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first [`RelayError`]; the effect it stopped at stays pending.
+    pub async fn drain(&mut self) -> Result<usize, RelayError<S::Error, P::Error>> {
+        let mut delivered = 0;
+        loop {
+            let next = lock(&self.store).next_delivery();
+            let Some(delivery) = next.map_err(RelayError::Store)? else {
+                return Ok(delivered);
+            };
+            self.publisher
+                .publish(&delivery)
+                .await
+                .map_err(|error| RelayError::Publish {
+                    id: delivery.id,
+                    error,
+                })?;
+            lock(&self.store)
+                .acknowledge(delivery.id)
+                .map_err(RelayError::Store)?;
+            delivered += 1;
+        }
+    }
+}
+
+impl<S, P> Actor for OutboxRelay<S, P>
+where
+    S: OutboxStore + Send,
+    S::Effect: Send,
+    P: Publisher<S::Effect> + Send,
+{
+    type Message = Drain;
+    type Future<'a>
+        = futures::future::BoxFuture<'a, ()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, _: Drain) -> Self::Future<'_> {
+        Box::pin(async move {
+            if let Err(error) = self.drain().await {
+                tracing::warn!(%error, "outbox relay stopped; the next drain retries");
+            }
+        })
+    }
+}
@@ -0,0 +1,134 @@
+//! Property-testing helpers for statechart events.
+//!
+//! [`EventStrategy`] is a `proptest` strategy that picks events with per-variant weights,
+//! so rare events (faults, resets, timeouts) are exercised as often as a test needs rather
+//! than as often as a uniform distribution happens to produce them. Unit-only events
+//! annotated with `#[statechart_event]` can be built straight from their `Kind::ALL`
+//! enumeration with [`EventStrategy::from_kinds`].
+//!
+//! For reproducible failures, [`seeded_runner`] returns a `TestRunner` whose RNG is derived
+//! from a fixed seed, and [`EventStrategy::sample_sequence`] draws a whole event sequence
+//! from it.
+//!
+//! With this feature enabled, `statechart!` also implements `Arbitrary` for every generated
+//! state-id enum, so `any::<MyMachineStateId>()` picks a random state of the chart.
+
+// Re-exported so that generated code and users do not need their own proptest dependency.
+pub use proptest;
+
+use proptest::strategy::{BoxedStrategy, Just, NewTree, Strategy, Union, ValueTree};
+use proptest::test_runner::{Config, RngAlgorithm, TestRng, TestRunner};
+
+/// Weighted choice over event variants.
+///
+/// Variants with a weight of `0` are accepted and simply never generated, which makes it
+/// easy to switch an event off in a particular test.
+#[derive(Debug, Clone)]
+pub struct EventStrategy<E> {
+    variants: Vec<(u32, BoxedStrategy<E>)>,
+}
+
+impl<E: core::fmt::Debug + 'static> EventStrategy<E> {
+    /// Creates a strategy with no variants; add some with [`Self::variant`] or [`Self::event`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            variants: Vec::new(),
+        }
+    }
+
+    /// Adds a variant whose events are produced by `strategy` (use this for events that
+    /// carry data).
+    #[must_use]
+    pub fn variant(mut self, weight: u32, strategy: impl Strategy<Value = E> + 'static) -> Self {
+        self.variants.push((weight, strategy.boxed()));
+        self
+    }
+
+    /// Adds a fixed event value.
+    #[must_use]
+    pub fn event(self, weight: u32, event: E) -> Self
+    where
+        E: Clone,
+    {
+        self.variant(weight, Just(event))
+    }
+
+    /// Builds a strategy from a unit-event kind enumeration, typically
+    /// `MyEventKind::ALL` generated by `#[statechart_event]`.
+    #[must_use]
+    pub fn from_kinds<K>(kinds: &[K], weight_of: impl Fn(K) -> u32) -> Self
+    where
+        K: Copy + Into<E>,
+        E: Clone,
+    {
+        kinds.iter().fold(Self::new(), |strategy, &kind| {
+            strategy.event(weight_of(kind), kind.into())
+        })
+    }
+
+    /// Generates sequences of events whose length lies in `len`.
+    pub fn sequence(
+        self,
+        len: impl Into<proptest::collection::SizeRange>,
+    ) -> impl Strategy<Value = Vec<E>> {
+        proptest::collection::vec(self, len)
+    }
+
+    /// Draws `len` events from `runner`; with a [`seeded_runner`] the result is reproducible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no variant has a non-zero weight.
+    pub fn sample_sequence(&self, runner: &mut TestRunner, len: usize) -> Vec<E> {
+        (0..len)
+            .map(|_| {
+                self.new_tree(runner)
+                    .expect("EventStrategy never rejects values")
+                    .current()
+            })
+            .collect()
+    }
+
+    fn union(&self) -> Union<BoxedStrategy<E>> {
+        let weighted: Vec<_> = self
+            .variants
+            .iter()
+            .filter(|(weight, _)| *weight > 0)
+            .cloned()
+            .collect();
+        assert!(
+            !weighted.is_empty(),
+            "EventStrategy needs at least one variant with a non-zero weight"
+        );
+        Union::new_weighted(weighted)
+    }
+}
+
+impl<E: core::fmt::Debug + 'static> Default for EventStrategy<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: core::fmt::Debug + 'static> Strategy for EventStrategy<E> {
+    type Tree = <Union<BoxedStrategy<E>> as Strategy>::Tree;
+    type Value = E;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.union().new_tree(runner)
+    }
+}
+
+/// Returns a `TestRunner` whose random source is fully determined by `seed`.
+///
+/// Use it to replay a failing run: the same seed always yields the same events.
+#[must_use]
+pub fn seeded_runner(seed: u64) -> TestRunner {
+    let mut seed_bytes = [0u8; 32];
+    for chunk in seed_bytes.chunks_mut(8) {
+        chunk.copy_from_slice(&seed.to_le_bytes());
+    }
+    let rng = TestRng::from_seed(RngAlgorithm::ChaCha, &seed_bytes);
+    TestRunner::new_with_rng(Config::default(), rng)
+}
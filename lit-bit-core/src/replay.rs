@@ -0,0 +1,231 @@
+//! Recording the events a machine receives and replaying them to reproduce a run.
+//!
+//! An [`EventRecorder`] stamps every event with the time from a [`MonotonicClock`] and
+//! collects it in an [`EventLog`], which serializes with serde so it can be shipped back
+//! from the field. An [`EventReplayer`] sends the logged events, in order, to a fresh
+//! machine:
+//!
+//! ```rust,ignore
+//! // In the field:
+//! let mut recorder = EventRecorder::from_snapshot(&CLOCK, pump.snapshot());
+//! recorder.send(&mut pump, &PumpEvent::Start);
+//! let json = serde_json::to_string(recorder.log())?;
+//!
+//! // On the bench:
+//! let replayer = EventReplayer::new(serde_json::from_str(&json)?);
+//! let mut pump = match replayer.start() {
+//!     Some(snapshot) => Pump::from_snapshot(snapshot, context)?,
+//!     None => Pump::new(context, &PumpEvent::Boot)?,
+//! };
+//! replayer.replay(&mut pump);
+//! ```
+//!
+//! A log starts either from the machine's initial configuration or from a
+//! [`StateSnapshot`] taken when recording began. Snapshots hold the active states only, so
+//! the context to resume with has to be captured separately. Replays are exact as long as
+//! the machine's guards and actions depend on nothing but the context and the events; a
+//! [`ReplayClock`] attached with [`EventReplayer::with_clock`] also reproduces the recorded
+//! timestamps for code that reads the time.
+
+use core::ops::ControlFlow;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::StateMachine;
+use crate::runtime::{BatchSendSummary, MonotonicClock, SendResult, Ticks};
+use crate::upgrade::StateSnapshot;
+
+/// One event of an [`EventLog`] and when it was sent.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RecordedEvent<EventType> {
+    /// Clock reading just before the event was sent.
+    pub at: Ticks,
+    pub event: EventType,
+}
+
+/// The events a machine received, in order, and the configuration it started from.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct EventLog<EventType> {
+    /// Configuration the machine was in when recording began; `None` if it started from
+    /// its initial state.
+    #[serde(default)]
+    pub start: Option<StateSnapshot>,
+    pub events: Vec<RecordedEvent<EventType>>,
+}
+
+impl<EventType> Default for EventLog<EventType> {
+    fn default() -> Self {
+        Self {
+            start: None,
+            events: Vec::new(),
+        }
+    }
+}
+
+/// Collects the events sent to a machine into an [`EventLog`]; see the [module docs](self).
+pub struct EventRecorder<EventType> {
+    clock: &'static (dyn MonotonicClock + Sync),
+    log: EventLog<EventType>,
+}
+
+impl<EventType> core::fmt::Debug for EventRecorder<EventType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EventRecorder")
+            .field("start", &self.log.start)
+            .field("events", &self.log.events.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<EventType: Clone> EventRecorder<EventType> {
+    /// Starts a log for a machine in its initial configuration.
+    #[must_use]
+    pub fn new(clock: &'static (dyn MonotonicClock + Sync)) -> Self {
+        Self {
+            clock,
+            log: EventLog::default(),
+        }
+    }
+
+    /// Starts a log for a machine already running, in the configuration `snapshot`
+    /// captured.
+    #[must_use]
+    pub fn from_snapshot(
+        clock: &'static (dyn MonotonicClock + Sync),
+        snapshot: StateSnapshot,
+    ) -> Self {
+        Self {
+            clock,
+            log: EventLog {
+                start: Some(snapshot),
+                events: Vec::new(),
+            },
+        }
+    }
+
+    /// Appends `event`, stamped with the current time.
+    pub fn record(&mut self, event: &EventType) {
+        self.log.events.push(RecordedEvent {
+            at: self.clock.now(),
+            event: event.clone(),
+        });
+    }
+
+    /// Records `event` and sends it to `machine`.
+    pub fn send<M, const N_ACTIVE: usize>(
+        &mut self,
+        machine: &mut M,
+        event: &EventType,
+    ) -> SendResult
+    where
+        M: StateMachine<N_ACTIVE, Event = EventType>,
+    {
+        self.record(event);
+        machine.send(event)
+    }
+
+    /// Returns the events recorded so far.
+    #[must_use]
+    pub fn log(&self) -> &EventLog<EventType> {
+        &self.log
+    }
+
+    /// Stops recording and returns the log.
+    #[must_use]
+    pub fn into_log(self) -> EventLog<EventType> {
+        self.log
+    }
+}
+
+/// A [`MonotonicClock`] that an [`EventReplayer`] sets to each recorded timestamp before
+/// sending the event.
+#[derive(Debug, Default)]
+pub struct ReplayClock(AtomicU64);
+
+impl ReplayClock {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    /// Moves the clock to `now`.
+    pub fn set(&self, now: Ticks) {
+        self.0.store(now, Ordering::Relaxed);
+    }
+}
+
+impl MonotonicClock for ReplayClock {
+    fn now(&self) -> Ticks {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Sends the events of an [`EventLog`] to a machine again; see the [module docs](self).
+#[derive(Debug)]
+pub struct EventReplayer<EventType> {
+    log: EventLog<EventType>,
+    clock: Option<&'static ReplayClock>,
+}
+
+impl<EventType> EventReplayer<EventType> {
+    #[must_use]
+    pub fn new(log: EventLog<EventType>) -> Self {
+        Self { log, clock: None }
+    }
+
+    /// Sets `clock` to each event's recorded time before the event is sent.
+    #[must_use]
+    pub fn with_clock(mut self, clock: &'static ReplayClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Returns the configuration the replayed machine must start in, or `None` for its
+    /// initial configuration.
+    #[must_use]
+    pub fn start(&self) -> Option<&StateSnapshot> {
+        self.log.start.as_ref()
+    }
+
+    /// Returns the recorded events.
+    #[must_use]
+    pub fn events(&self) -> &[RecordedEvent<EventType>] {
+        &self.log.events
+    }
+
+    /// Sends every recorded event to `machine`, in order.
+    pub fn replay<M, const N_ACTIVE: usize>(
+        &self,
+        machine: &mut M,
+    ) -> BatchSendSummary<M::State, N_ACTIVE>
+    where
+        M: StateMachine<N_ACTIVE, Event = EventType>,
+    {
+        self.replay_with(machine, |_, _, _| ControlFlow::Continue(()))
+    }
+
+    /// Sends the recorded events to `machine` like [`Self::replay`], handing `step` each
+    /// event's index, the event and its result. Stops after the event for which `step`
+    /// breaks, for example to inspect the machine at the first failing step.
+    pub fn replay_with<M, const N_ACTIVE: usize>(
+        &self,
+        machine: &mut M,
+        mut step: impl FnMut(usize, &RecordedEvent<EventType>, SendResult) -> ControlFlow<()>,
+    ) -> BatchSendSummary<M::State, N_ACTIVE>
+    where
+        M: StateMachine<N_ACTIVE, Event = EventType>,
+    {
+        let mut summary = BatchSendSummary::default();
+        for (index, recorded) in self.log.events.iter().enumerate() {
+            if let Some(clock) = self.clock {
+                clock.set(recorded.at);
+            }
+            let result = machine.send(&recorded.event);
+            summary.record(result);
+            if step(index, recorded, result).is_break() {
+                break;
+            }
+        }
+        summary.final_state = machine.state();
+        summary
+    }
+}
@@ -0,0 +1,179 @@
+//! Verdicts for `[guard async ...]` conditions, decided before an event is dispatched.
+//!
+//! A chart guard written `[guard async self.config_allows(*id)]` awaits its condition, so
+//! the synchronous `send` cannot evaluate it. A generated machine with such guards has
+//! `async_send` instead of `send`. It asks the runtime for the next async guard the event
+//! would reach ([`Runtime::next_async_guard`]), awaits that guard, records the verdict in an
+//! [`AsyncGuardVerdicts`] and asks again. It stops when no undecided guard is left in the
+//! way. [`Runtime::send_with_async_verdicts`] then dispatches the event, using the recorded
+//! verdicts in place of the conditions.
+//!
+//! Guards are reached in the order `send` checks transitions: the active leaf first, then
+//! its ancestors, in declaration order within a state, with sync guards checked as well.
+//! The search stops at the first transition that is enabled. So guards short-circuit like
+//! sync guards do: once a transition is enabled, no guard after it is awaited.
+//!
+//! Async guards must be free of side effects. A condition may be awaited and its verdict
+//! never used, for example when the `async_send` future is dropped before the dispatch,
+//! and the sync guards of the same event run again during the dispatch. The machine is
+//! borrowed mutably until `async_send` returns, so nothing else can change the context or
+//! the active states between the verdicts and the dispatch. Awaiting a guard only holds up the machine that
+//! awaits it: an actor running the machine takes no new message until the guard resolves,
+//! but other actors keep running.
+
+use super::{ProcessingError, Runtime, SendResult};
+
+/// Most async-guard verdicts one event can collect.
+pub const MAX_ASYNC_GUARD_VERDICTS: usize = 16;
+
+/// An async guard that the runtime reached without a verdict.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PendingAsyncGuard {
+    /// Index of the guarded transition in the definition's `transitions`.
+    pub transition: usize,
+    /// The guard's slot, from the transition's `async_guard`.
+    pub slot: u16,
+}
+
+/// The async guards decided for one event, keyed by transition index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AsyncGuardVerdicts {
+    decided: heapless::Vec<(usize, bool), MAX_ASYNC_GUARD_VERDICTS>,
+}
+
+impl AsyncGuardVerdicts {
+    /// Returns an empty set, with no guard decided.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            decided: heapless::Vec::new(),
+        }
+    }
+
+    /// Returns the verdict recorded for the async guard of transition `transition`.
+    #[must_use]
+    pub fn get(&self, transition: usize) -> Option<bool> {
+        self.decided
+            .iter()
+            .find(|(decided, _)| *decided == transition)
+            .map(|&(_, passed)| passed)
+    }
+
+    /// Records whether the async guard of transition `transition` passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::CapacityExceeded`] once [`MAX_ASYNC_GUARD_VERDICTS`]
+    /// guards have been decided.
+    pub fn record(&mut self, transition: usize, passed: bool) -> Result<(), ProcessingError> {
+        self.decided
+            .push((transition, passed))
+            .map_err(|_| ProcessingError::CapacityExceeded)
+    }
+
+    /// Returns `true` if no guard has been decided.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.decided.is_empty()
+    }
+
+    /// Returns whether the async guard in `slot` of transition `transition` lets it
+    /// through. An undecided guard does not; the first one is noted in `pending`.
+    pub(super) fn allows(
+        &self,
+        transition: usize,
+        slot: u16,
+        pending: &mut Option<PendingAsyncGuard>,
+    ) -> bool {
+        self.get(transition).unwrap_or_else(|| {
+            pending.get_or_insert(PendingAsyncGuard { transition, slot });
+            false
+        })
+    }
+}
+
+impl<StateType, EventType, ContextType, const M: usize, const N: usize, const MN: usize>
+    Runtime<StateType, EventType, ContextType, M, N, MN>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Returns the first async guard that sending `event` would reach without a verdict in
+    /// `verdicts`, or `None` once the send no longer depends on an undecided guard.
+    ///
+    /// Returns `None` for an event the event filter rejects and before the runtime has
+    /// started.
+    #[must_use]
+    pub fn next_async_guard(
+        &self,
+        event: &EventType,
+        verdicts: &AsyncGuardVerdicts,
+    ) -> Option<PendingAsyncGuard> {
+        if self.needs_initial_entry || !(self.event_filter)(event) {
+            return None;
+        }
+        let mut pending = None;
+        let _ = self.collect_potential_transitions(
+            event,
+            &self.active_leaf_states,
+            verdicts,
+            &mut pending,
+        );
+        pending
+    }
+
+    /// Sends `event` like `send`, with `verdicts` deciding its async guards.
+    ///
+    /// A guard without a verdict rejects its transition. The verdicts only apply to the
+    /// first step of the send, so async guards reached by internal events raised during the
+    /// send reject their transitions too.
+    #[must_use = "check whether the event was handled"]
+    pub fn send_with_async_verdicts(
+        &mut self,
+        event: &EventType,
+        verdicts: AsyncGuardVerdicts,
+    ) -> SendResult {
+        self.async_verdicts = verdicts;
+        self.send_to_completion(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_first_undecided_guard_is_pending() {
+        let mut verdicts = AsyncGuardVerdicts::new();
+        verdicts.record(2, false).unwrap();
+        let mut pending = None;
+
+        assert!(!verdicts.allows(2, 0, &mut pending));
+        assert_eq!(pending, None);
+        assert!(!verdicts.allows(5, 1, &mut pending));
+        assert!(!verdicts.allows(7, 2, &mut pending));
+        assert_eq!(
+            pending,
+            Some(PendingAsyncGuard {
+                transition: 5,
+                slot: 1
+            })
+        );
+
+        verdicts.record(5, true).unwrap();
+        assert!(verdicts.allows(5, 1, &mut pending));
+    }
+
+    #[test]
+    fn recording_past_the_capacity_fails() {
+        let mut verdicts = AsyncGuardVerdicts::new();
+        for transition in 0..MAX_ASYNC_GUARD_VERDICTS {
+            verdicts.record(transition, true).unwrap();
+        }
+        assert_eq!(
+            verdicts.record(MAX_ASYNC_GUARD_VERDICTS, true),
+            Err(ProcessingError::CapacityExceeded)
+        );
+    }
+}
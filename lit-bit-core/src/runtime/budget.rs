@@ -0,0 +1,147 @@
+//! Cascades of internal events and deadline-aware sending.
+//!
+//! Actions can raise follow-up events by queueing them in the context; a runtime with a
+//! [`RaisedEventFn`] attached pulls them out after every step and processes them, in order,
+//! before the next external event. [`Runtime::send_with_budget`] does the same but stops
+//! between steps once a time budget is spent, leaving the rest of the cascade queued.
+
+use super::{ProcessingError, Runtime, SendResult};
+
+/// Capacity of the runtime's internal-event queue and of its queue of external events
+/// deferred behind an unfinished cascade.
+pub const MAX_INTERNAL_EVENTS: usize = 8;
+
+/// A point in time or a duration, in the units of a [`MonotonicClock`].
+pub type Ticks = u64;
+
+/// A clock that never goes backwards, such as a hardware timer counter.
+pub trait MonotonicClock {
+    /// Returns the current time.
+    fn now(&self) -> Ticks;
+}
+
+/// Takes the next event an action raised from the context, oldest first.
+pub type RaisedEventFn<ContextType, EventType> = fn(&mut ContextType) -> Option<EventType>;
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Sets where the runtime looks for events raised by actions.
+    ///
+    /// After each processed event, `source` is called until it returns `None` (or the
+    /// internal queue is full; the remaining events are picked up after the next step).
+    /// Raised events are processed before any later external event, so an external event
+    /// and its whole cascade still run to completion as one unit.
+    pub fn set_raised_event_source(&mut self, source: RaisedEventFn<ContextType, EventType>) {
+        self.raised_events = Some(source);
+    }
+
+    /// Returns the number of internal events queued but not yet processed.
+    #[must_use]
+    pub fn pending_internal_events(&self) -> usize {
+        self.internal_events.len()
+    }
+
+    /// Sends `event`, stopping between steps of its internal-event cascade once `budget`
+    /// ticks of `clock` have passed.
+    ///
+    /// Internal events left over from an earlier budgeted call are processed first, and
+    /// `event` is queued behind them, so events are never reordered or dropped. The step
+    /// that is running when the budget runs out is always finished; the budget is only
+    /// checked between steps. Returns [`SendResult::BudgetExhausted`] if work is left
+    /// queued, otherwise the result of `event` itself.
+    ///
+    /// If a step fails or sets off the oscillation guard, its result is returned straight
+    /// away and the rest of the queue is kept for the next call.
+    pub fn send_with_budget(
+        &mut self,
+        event: &EventType,
+        budget: Ticks,
+        clock: &impl MonotonicClock,
+    ) -> SendResult {
+        let start = clock.now();
+        self.entered_states.clear();
+        let result = self.send_queued(event, || clock.now().saturating_sub(start) >= budget);
+        self.notify_completion();
+        self.check_invariant(result)
+    }
+
+    /// Sends `event` and everything queued ahead of it or raised by it, with no budget.
+    pub(super) fn send_to_completion(&mut self, event: &EventType) -> SendResult {
+        self.entered_states.clear();
+        let result = if self.raised_events.is_none() && self.deferred_events.is_empty() {
+            self.send_step(event)
+        } else {
+            self.send_queued(event, || false)
+        };
+        self.notify_completion();
+        self.check_invariant(result)
+    }
+
+    fn send_queued(
+        &mut self,
+        event: &EventType,
+        mut out_of_budget: impl FnMut() -> bool,
+    ) -> SendResult {
+        if self.deferred_events.push_back(event.clone()).is_err() {
+            return SendResult::Error(ProcessingError::CapacityExceeded);
+        }
+
+        let mut result = SendResult::NoMatch;
+        loop {
+            // Internal events belong to the external event that raised them, so they are
+            // always taken before the next deferred external event.
+            let (next, is_this_event) = if let Some(internal) = self.internal_events.pop_front() {
+                (internal, false)
+            } else if let Some(external) = self.deferred_events.pop_front() {
+                let is_this_event = self.deferred_events.is_empty();
+                (external, is_this_event)
+            } else {
+                return result;
+            };
+
+            let step = self.send_step(&next);
+            if let SendResult::Error(_) | SendResult::OscillationDetected = step {
+                return step;
+            }
+            if is_this_event {
+                result = step;
+            }
+            self.collect_raised_events();
+
+            let work_left = !self.internal_events.is_empty() || !self.deferred_events.is_empty();
+            if work_left && out_of_budget() {
+                trace!(
+                    "[TRACE] send_with_budget: budget exhausted with {} internal events queued",
+                    self.internal_events.len()
+                );
+                return SendResult::BudgetExhausted {
+                    remaining_internal: self.internal_events.len(),
+                };
+            }
+        }
+    }
+
+    fn collect_raised_events(&mut self) {
+        let Some(source) = self.raised_events else {
+            return;
+        };
+        while !self.internal_events.is_full() {
+            let Some(raised) = source(&mut self.context) else {
+                break;
+            };
+            // Cannot fail: the queue was checked for room above.
+            let _ = self.internal_events.push_back(raised);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Detecting that a machine has finished.
+//!
+//! A machine is finished once every active leaf is a `[final]` state; in a parallel chart
+//! that means every region has reached one. [`Runtime::is_final`] answers that on any target.
+//! With `std`, [`Runtime::completed`] also returns a [`Completion`] future that resolves
+//! when a send leaves the machine finished, so async code can write
+//! `machine.completed().await` instead of checking `is_final` after every send:
+//!
+//! ```rust,ignore
+//! let done = machine.completed();
+//! let address = spawn_actor_tokio(StatechartActor::new(machine), 8);
+//! // ... events arrive from elsewhere ...
+//! done.await;
+//! ```
+//!
+//! Only sends resolve the future. A machine forced into a final configuration with
+//! `force_transition` resolves it on its next send, or when `completed` is called again.
+
+use super::{MachineDefinition, Runtime};
+
+#[cfg(feature = "std")]
+pub use signal::Completion;
+#[cfg(feature = "std")]
+pub(super) use signal::CompletionSignal;
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Returns `true` once every active leaf is a `[final]` state. An unstarted runtime
+    /// is not final.
+    #[must_use]
+    pub fn is_final(&self) -> bool {
+        all_final(self.machine_def, &self.active_leaf_states)
+    }
+
+    /// Returns a future that resolves once a send leaves the machine final (see
+    /// [`Runtime::is_final`]), or right away if it already is.
+    ///
+    /// The future does not borrow the runtime, so it can be awaited while another task
+    /// drives the machine. Every future handed out before the machine finishes resolves
+    /// when it does. Clones of the runtime do not resolve futures taken from the original.
+    #[cfg(feature = "std")]
+    pub fn completed(&mut self) -> Completion {
+        let is_final = self.is_final();
+        subscribe(&mut self.completion, is_final)
+    }
+
+    /// Resolves the pending [`Completion`]s if the last send finished the machine.
+    #[inline]
+    pub(super) fn notify_completion(&mut self) {
+        #[cfg(feature = "std")]
+        if self.completion.is_some() {
+            let is_final = self.is_final();
+            notify(&mut self.completion, is_final);
+        }
+    }
+}
+
+/// Returns `true` if `leaves` is non-empty and each of them is a `[final]` state.
+pub(super) fn all_final<StateType, EventType, ContextType>(
+    machine_def: &MachineDefinition<StateType, EventType, ContextType>,
+    leaves: &[StateType],
+) -> bool
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    ContextType: Clone + 'static,
+{
+    !leaves.is_empty()
+        && leaves.iter().all(|&leaf| {
+            machine_def
+                .get_state_node(leaf)
+                .is_some_and(|node| node.is_final)
+        })
+}
+
+/// Hands out a future tied to the runtime's signal in `slot`, creating the signal if no
+/// future is pending.
+#[cfg(feature = "std")]
+pub(super) fn subscribe(
+    slot: &mut Option<std::sync::Arc<CompletionSignal>>,
+    is_final: bool,
+) -> Completion {
+    let signal = slot.get_or_insert_with(Default::default).clone();
+    notify(slot, is_final);
+    Completion::new(signal)
+}
+
+/// Resolves and forgets the signal in `slot` when the machine `is_final`.
+#[cfg(feature = "std")]
+pub(super) fn notify(slot: &mut Option<std::sync::Arc<CompletionSignal>>, is_final: bool) {
+    if is_final && let Some(signal) = slot.take() {
+        signal.complete();
+    }
+}
+
+#[cfg(feature = "std")]
+mod signal {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    /// Shared by a runtime and the [`Completion`] futures it handed out.
+    #[derive(Debug, Default)]
+    pub struct CompletionSignal {
+        state: Mutex<SignalState>,
+    }
+
+    #[derive(Debug, Default)]
+    struct SignalState {
+        done: bool,
+        wakers: Vec<Waker>,
+    }
+
+    impl CompletionSignal {
+        pub(in crate::runtime) fn complete(&self) {
+            let wakers = {
+                let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+                state.done = true;
+                core::mem::take(&mut state.wakers)
+            };
+            for waker in wakers {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Future returned by [`Runtime::completed`](super::Runtime::completed); resolves once
+    /// the machine is final.
+    #[derive(Debug, Clone)]
+    #[must_use = "futures do nothing unless awaited"]
+    pub struct Completion {
+        signal: Arc<CompletionSignal>,
+    }
+
+    impl Completion {
+        pub(in crate::runtime) fn new(signal: Arc<CompletionSignal>) -> Self {
+            Self { signal }
+        }
+
+        /// Returns `true` once the machine has finished, without waiting.
+        #[must_use]
+        pub fn is_complete(&self) -> bool {
+            self.signal
+                .state
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .done
+        }
+    }
+
+    impl Future for Completion {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let mut state = self
+                .signal
+                .state
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if state.done {
+                return Poll::Ready(());
+            }
+            if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Comparing and hashing active configurations.
+//!
+//! [`StateMachine::state`](crate::StateMachine::state) lists the active leaves in the order
+//! the runtime happened to enter them, so two machines in the same parallel configuration
+//! can return them in different orders. [`Configuration`] is the canonical form: the leaves
+//! sorted and without duplicates, so equality, hashing and ordering only depend on which
+//! states are active. That makes it usable as a memoization key or in test assertions:
+//!
+//! ```rust,ignore
+//! let mut seen = HashSet::new();
+//! seen.insert(machine.configuration());
+//! ```
+//!
+//! For membership tests in constant time, convert a configuration to a [`StateSet`], a
+//! bitset keyed by the states' `index()`.
+
+#[cfg(feature = "single-region")]
+use super::FlatRuntime;
+use super::{ProcessingError, Runtime};
+
+/// The active leaves of a machine, sorted and deduplicated.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Configuration<StateType, const N: usize> {
+    leaves: heapless::Vec<StateType, N>,
+}
+
+impl<StateType, const N: usize> Configuration<StateType, N>
+where
+    StateType: Copy + Ord,
+{
+    /// Returns an empty configuration, the one of an unstarted machine.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            leaves: heapless::Vec::new(),
+        }
+    }
+
+    /// Returns `true` if `state` is one of the active leaves.
+    #[must_use]
+    pub fn contains(&self, state: StateType) -> bool {
+        self.leaves.binary_search(&state).is_ok()
+    }
+
+    /// Returns the active leaves in their canonical order.
+    #[must_use]
+    pub fn as_slice(&self) -> &[StateType] {
+        &self.leaves
+    }
+
+    /// Returns how many leaves are active.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns `true` for the configuration of an unstarted machine.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Returns the leaves as a bitset of their indices, or `None` if one of them does not
+    /// fit in `WORDS` words.
+    #[must_use]
+    pub fn to_state_set<const WORDS: usize>(&self) -> Option<StateSet<WORDS>>
+    where
+        StateType: Into<u32>,
+    {
+        let mut set = StateSet::new();
+        for &leaf in &self.leaves {
+            if !set.insert(leaf.into()) {
+                return None;
+            }
+        }
+        Some(set)
+    }
+
+    /// Builds the configuration whose leaves are the states in `set`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `set` holds an index no state has, and
+    /// [`ProcessingError::CapacityExceeded`] if it holds more than `N` states.
+    pub fn from_state_set<const WORDS: usize>(
+        set: &StateSet<WORDS>,
+    ) -> Result<Self, ProcessingError>
+    where
+        StateType: TryFrom<u32>,
+    {
+        let mut leaves = heapless::Vec::new();
+        for index in set.iter() {
+            let state = StateType::try_from(index).map_err(|_| ProcessingError::UnknownState)?;
+            leaves
+                .push(state)
+                .map_err(|_| ProcessingError::CapacityExceeded)?;
+        }
+        Ok(Self::from(leaves))
+    }
+}
+
+impl<StateType, const N: usize> Default for Configuration<StateType, N>
+where
+    StateType: Copy + Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<StateType, const N: usize> From<heapless::Vec<StateType, N>> for Configuration<StateType, N>
+where
+    StateType: Copy + Ord,
+{
+    fn from(mut leaves: heapless::Vec<StateType, N>) -> Self {
+        leaves.sort_unstable();
+        let mut unique: heapless::Vec<StateType, N> = heapless::Vec::new();
+        for leaf in leaves {
+            if unique.last() != Some(&leaf) {
+                // `unique` never holds more than `leaves` did.
+                let _ = unique.push(leaf);
+            }
+        }
+        Self { leaves: unique }
+    }
+}
+
+impl<'a, StateType, const N: usize> IntoIterator for &'a Configuration<StateType, N> {
+    type Item = &'a StateType;
+    type IntoIter = core::slice::Iter<'a, StateType>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.leaves.iter()
+    }
+}
+
+/// A set of states as a bitset of their `u32` indices, holding indices below `WORDS * 64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StateSet<const WORDS: usize = 1> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> StateSet<WORDS> {
+    /// One past the largest index the set can hold.
+    pub const CAPACITY: u32 = (WORDS * 64) as u32;
+
+    /// Returns an empty set.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { words: [0; WORDS] }
+    }
+
+    /// Returns the set with `index` added; `const`, so a set can be built in a `static`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is beyond [`Self::CAPACITY`], which fails the build when the set
+    /// is built in a constant.
+    #[must_use]
+    pub const fn with(mut self, index: u32) -> Self {
+        assert!(
+            index < Self::CAPACITY,
+            "state index beyond the set's capacity"
+        );
+        self.words[(index / 64) as usize] |= 1 << (index % 64);
+        self
+    }
+
+    /// Adds `index`; returns `false` if it is beyond [`Self::CAPACITY`].
+    pub fn insert(&mut self, index: u32) -> bool {
+        let Some(word) = self.words.get_mut((index / 64) as usize) else {
+            return false;
+        };
+        *word |= 1 << (index % 64);
+        true
+    }
+
+    /// Removes `index`; returns `true` if it was in the set.
+    pub fn remove(&mut self, index: u32) -> bool {
+        let Some(word) = self.words.get_mut((index / 64) as usize) else {
+            return false;
+        };
+        let bit = 1 << (index % 64);
+        let present = *word & bit != 0;
+        *word &= !bit;
+        present
+    }
+
+    /// Returns `true` if `index` is in the set.
+    #[must_use]
+    pub fn contains(&self, index: u32) -> bool {
+        self.words
+            .get((index / 64) as usize)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    /// Returns `true` if `state`'s index is in the set.
+    #[must_use]
+    pub fn contains_state<StateType: Into<u32>>(&self, state: StateType) -> bool {
+        self.contains(state.into())
+    }
+
+    /// Returns how many indices the set holds.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if the set holds no index.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Iterates over the indices in the set, smallest first.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..Self::CAPACITY).filter(|&index| self.contains(index))
+    }
+}
+
+impl<const WORDS: usize> Default for StateSet<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + Ord + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Returns the active leaves in canonical form; empty for an unstarted runtime.
+    #[must_use]
+    pub fn configuration(&self) -> Configuration<StateType, N_ACTIVE> {
+        Configuration::from(self.active_leaf_states.clone())
+    }
+}
+
+#[cfg(feature = "single-region")]
+impl<StateType, EventType, ContextType, const M: usize>
+    FlatRuntime<StateType, EventType, ContextType, M>
+where
+    StateType: Copy + Clone + Ord + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Returns the active leaf as a configuration, like `Runtime::configuration`.
+    #[must_use]
+    pub fn configuration(&self) -> Configuration<StateType, 1> {
+        Configuration::from(crate::StateMachine::<1>::state(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_are_sorted_and_deduplicated() {
+        let leaves = heapless::Vec::<u8, 4>::from_slice(&[7, 2, 7, 5]).unwrap();
+        let configuration = Configuration::from(leaves);
+        assert_eq!(configuration.as_slice(), &[2, 5, 7]);
+        assert!(configuration.contains(5));
+        assert!(!configuration.contains(3));
+    }
+
+    #[test]
+    fn state_sets_round_trip_and_reject_indices_beyond_capacity() {
+        let mut set = StateSet::<2>::new();
+        assert!(set.insert(3));
+        assert!(set.insert(70));
+        assert!(!set.insert(128));
+        assert_eq!(set.iter().collect::<heapless::Vec<u32, 4>>(), [3, 70]);
+        assert_eq!(set.len(), 2);
+
+        let configuration = Configuration::<u32, 4>::from_state_set(&set).unwrap();
+        assert_eq!(configuration.as_slice(), &[3, 70]);
+        assert_eq!(configuration.to_state_set::<2>(), Some(set));
+        assert_eq!(configuration.to_state_set::<1>(), None);
+
+        assert!(set.remove(70));
+        assert!(!set.contains(70));
+
+        const BUILT: StateSet<2> = StateSet::new().with(3).with(70);
+        assert_eq!(BUILT.iter().collect::<heapless::Vec<u32, 4>>(), [3, 70]);
+    }
+}
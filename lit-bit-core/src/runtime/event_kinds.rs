@@ -0,0 +1,131 @@
+//! Which event kinds a machine can respond to in its current configuration.
+//!
+//! `statechart!` records, for every transition, which variants of the event enum its `on`
+//! pattern names ([`TransitionEventKinds`]). Combined with the `Kind` enum
+//! `#[statechart_event]` generates ([`EventKind`]), the runtime can answer "which events
+//! would be accepted right now?" without constructing any events.
+
+use super::{MachineDefinition, Runtime};
+
+/// The event variants a transition's `on` pattern can match, as recorded by `statechart!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionEventKinds {
+    /// Variant names the pattern matches, each flagged `true` when the pattern inspects the
+    /// variant's payload and so only matches some events of that variant.
+    pub variants: &'static [(&'static str, bool)],
+    /// The pattern matches every event (`_` or a plain binding).
+    pub any: bool,
+}
+
+impl TransitionEventKinds {
+    /// Returns `Some(payload_dependent)` if the pattern can match events of `variant`.
+    #[must_use]
+    pub fn matches(&self, variant: &str) -> Option<bool> {
+        if self.any {
+            return Some(false);
+        }
+        self.variants
+            .iter()
+            .find(|(name, _)| *name == variant)
+            .map(|&(_, payload_dependent)| payload_dependent)
+    }
+}
+
+/// Implemented by the `<Event>Kind` enums `#[statechart_event]` generates.
+pub trait EventKind: Copy + Eq + 'static {
+    /// The event enum this is the kind of.
+    type Event;
+
+    /// Every kind, in declaration order.
+    const ALL: &'static [Self];
+
+    /// The variant name as written in the event enum.
+    fn name(self) -> &'static str;
+}
+
+/// How certainly an event kind would be accepted, see [`Runtime::accepted_event_kinds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Acceptance {
+    /// Any event of this kind triggers a transition.
+    Accepted,
+    /// Some events of this kind may trigger a transition, depending on a guard or on the
+    /// event's payload.
+    Conditional,
+}
+
+impl<StateType, EventType, ContextType> MachineDefinition<StateType, EventType, ContextType>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Attaches the per-transition event-kind table, one entry per entry of `transitions`.
+    #[must_use]
+    pub const fn with_event_kinds(mut self, event_kinds: &'static [TransitionEventKinds]) -> Self {
+        self.event_kinds = event_kinds;
+        self
+    }
+
+    /// Returns how events of `variant` would be handled with `active_leaves` active, or
+    /// `None` if no transition can take them.
+    ///
+    /// Transitions are considered in the order the runtime tries them: from each active
+    /// leaf up through its ancestors, in declaration order within a state. A transition
+    /// that accepts the whole kind unconditionally shadows everything after it.
+    #[must_use]
+    pub fn acceptance(&self, active_leaves: &[StateType], variant: &str) -> Option<Acceptance> {
+        let mut acceptance = None;
+        for &leaf in active_leaves {
+            let mut state = Some(leaf);
+            while let Some(current) = state {
+                let from_current = self
+                    .transitions
+                    .iter()
+                    .zip(self.event_kinds)
+                    .filter(|(transition, _)| transition.from_state == current);
+                for (transition, kinds) in from_current {
+                    let Some(payload_dependent) = kinds.matches(variant) else {
+                        continue;
+                    };
+                    if payload_dependent || transition.is_guarded() {
+                        acceptance = Some(Acceptance::Conditional);
+                    } else {
+                        return Some(Acceptance::Accepted);
+                    }
+                }
+                state = self.get_parent_of(current);
+            }
+        }
+        acceptance
+    }
+}
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Returns every event kind the machine would respond to right now, with whether it
+    /// is accepted outright or only conditionally (see [`Acceptance`]).
+    ///
+    /// Relies on the event-kind table `statechart!` attaches to its definitions; a
+    /// hand-built definition without one reports no kinds.
+    pub fn accepted_event_kinds<K>(&self) -> impl Iterator<Item = (K, Acceptance)> + '_
+    where
+        K: EventKind<Event = EventType>,
+    {
+        K::ALL.iter().filter_map(|&kind| {
+            self.machine_def
+                .acceptance(&self.active_leaf_states, kind.name())
+                .map(|acceptance| (kind, acceptance))
+        })
+    }
+}
@@ -0,0 +1,1212 @@
+//! Single-region runtime for charts without `[parallel]` states.
+//!
+//! A chart without parallel states always has exactly one active leaf, so the general
+//! [`Runtime`](super::Runtime) machinery for tracking and arbitrating several regions is pure
+//! overhead: the active configuration is a `heapless::Vec` of leaves, every dispatch loops
+//! over it, and exits/entries are planned per region. [`FlatRuntime`] stores the active leaf
+//! as a single state id, dispatches by walking that leaf's ancestry once, and never clones
+//! the context: the whole exit/entry plan is computed (and can fail) before any action runs.
+//!
+//! The observable behaviour — which transition wins, the order of exit, transition and
+//! entry actions, and the resulting state — is identical to `Runtime` for flat charts.
+//! `statechart!` picks this runtime automatically when `lit-bit-core` is built with the
+//! `single-region` feature and the chart declares no parallel states; charts with parallel
+//! states keep using `Runtime`.
+
+use super::completion;
+use super::invariant;
+#[cfg(feature = "oscillation-guard")]
+use super::oscillation;
+use super::retry::{RetryObserverFn, RetrySettings};
+use super::{
+    Acceptance, AsyncGuardVerdicts, ConfigDiff, EventFilterFn, EventKind, MAX_ACTIVE_REGIONS,
+    MachineDefinition, MonotonicClock, PendingAsyncGuard, PolicyHost, ProcessingError, SendResult,
+    Transition, TransitionPolicy, accept_all_events,
+};
+use crate::StateMachine;
+
+/// Runtime for charts with a single active region. See the [module docs](self).
+///
+/// Generic Parameters:
+/// - `StateType`, `EventType`, `ContextType`: as for [`Runtime`](super::Runtime).
+/// - `M`: maximum hierarchy depth of a single state path.
+#[derive(Debug)]
+pub struct FlatRuntime<StateType, EventType, ContextType, const M: usize>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    ContextType: Clone + 'static,
+{
+    machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+    active_leaf: StateType,
+    context: ContextType,
+    #[cfg(feature = "coverage")]
+    coverage: Option<&'static crate::coverage::CoverageRecorder>,
+    #[cfg(feature = "async")]
+    timers: crate::timer::ArmedTimers<StateType, M>,
+    retry: RetrySettings<StateType>,
+    panic_on_invariant_violation: bool,
+    event_filter: EventFilterFn<EventType>,
+    policy: Option<&'static dyn TransitionPolicy<StateType, EventType>>,
+    /// Verdicts for the async guards of the next send; taken by that send.
+    async_verdicts: AsyncGuardVerdicts,
+    /// Set by [`FlatRuntime::unstarted`] until the initial state has been entered.
+    needs_initial_entry: bool,
+    /// States entered by the most recent send, in entry order.
+    entered_states: heapless::Vec<StateType, M>,
+    /// Resolves the futures handed out by [`FlatRuntime::completed`].
+    #[cfg(feature = "std")]
+    completion: Option<std::sync::Arc<completion::CompletionSignal>>,
+    #[cfg(feature = "oscillation-guard")]
+    oscillation: oscillation::OscillationTracker<StateType, M>,
+}
+
+/// Duplicates everything but the coverage recorder and pending completion futures, like
+/// `Runtime`'s `Clone`.
+impl<StateType, EventType, ContextType, const M: usize> Clone
+    for FlatRuntime<StateType, EventType, ContextType, M>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    ContextType: Clone + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            machine_def: self.machine_def,
+            active_leaf: self.active_leaf,
+            context: self.context.clone(),
+            #[cfg(feature = "coverage")]
+            coverage: None,
+            #[cfg(feature = "async")]
+            timers: self.timers.clone(),
+            retry: self.retry,
+            panic_on_invariant_violation: self.panic_on_invariant_violation,
+            event_filter: self.event_filter,
+            policy: self.policy,
+            async_verdicts: self.async_verdicts.clone(),
+            needs_initial_entry: self.needs_initial_entry,
+            entered_states: self.entered_states.clone(),
+            #[cfg(feature = "std")]
+            completion: None,
+            #[cfg(feature = "oscillation-guard")]
+            oscillation: self.oscillation.clone(),
+        }
+    }
+}
+
+/// Exit and entry sequence for one step, computed before any action runs.
+struct StepPlan<StateType, const M: usize> {
+    exits: heapless::Vec<StateType, M>,
+    entries: heapless::Vec<StateType, M>,
+    next_leaf: StateType,
+}
+
+impl<StateType, EventType, ContextType, const M: usize>
+    FlatRuntime<StateType, EventType, ContextType, M>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Creates a runtime in the definition's initial state; see `Runtime::new`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingError`] if the initial state cannot be entered.
+    pub fn new(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        initial_context: ContextType,
+        initial_event: &EventType,
+    ) -> Result<Self, ProcessingError> {
+        Self::with_initial_state(
+            machine_def,
+            initial_context,
+            machine_def.initial_leaf_state,
+            initial_event,
+        )
+    }
+
+    /// Creates a runtime that starts in `initial_state`; see `Runtime::with_initial_state`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `initial_state` is not part of the
+    /// machine definition, or an entry error if its initial children cannot be resolved.
+    pub fn with_initial_state(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        initial_context: ContextType,
+        initial_state: StateType,
+        initial_event: &EventType,
+    ) -> Result<Self, ProcessingError> {
+        if machine_def.get_state_node(initial_state).is_none() {
+            return Err(ProcessingError::UnknownState);
+        }
+        let mut runtime = Self::unstarted(machine_def, initial_context);
+        runtime.enter_initial_state(initial_state, initial_event)?;
+        Ok(runtime)
+    }
+
+    /// Rebuilds a runtime in the given configuration without running any entry action;
+    /// see `Runtime::restore`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `leaves` is empty or names a state that
+    /// is not a leaf, and [`ProcessingError::CapacityExceeded`] if it holds more than one.
+    pub fn restore(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        context: ContextType,
+        leaves: &[StateType],
+    ) -> Result<Self, ProcessingError> {
+        let (&leaf, rest) = leaves.split_first().ok_or(ProcessingError::UnknownState)?;
+        if !machine_def.is_leaf(leaf) {
+            return Err(ProcessingError::UnknownState);
+        }
+        if !rest.is_empty() {
+            return Err(ProcessingError::CapacityExceeded);
+        }
+        let mut runtime = Self::unstarted(machine_def, context);
+        runtime.active_leaf = leaf;
+        runtime.needs_initial_entry = false;
+        Ok(runtime)
+    }
+
+    /// Creates a runtime that only handles events `event_filter` accepts; see
+    /// `Runtime::with_event_filter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FlatRuntime::new`].
+    pub fn with_event_filter(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        initial_context: ContextType,
+        initial_event: &EventType,
+        event_filter: EventFilterFn<EventType>,
+    ) -> Result<Self, ProcessingError> {
+        let mut runtime = Self::unstarted(machine_def, initial_context);
+        runtime.event_filter = event_filter;
+        runtime.enter_initial_state(machine_def.initial_leaf_state, initial_event)?;
+        Ok(runtime)
+    }
+
+    /// Creates a runtime that has not entered its initial state yet; see
+    /// `Runtime::unstarted`. Until it starts, `state` is empty and `active_state` returns
+    /// the definition's initial leaf.
+    #[must_use]
+    pub const fn unstarted(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        initial_context: ContextType,
+    ) -> Self {
+        Self {
+            machine_def,
+            active_leaf: machine_def.initial_leaf_state,
+            context: initial_context,
+            #[cfg(feature = "coverage")]
+            coverage: None,
+            #[cfg(feature = "async")]
+            timers: crate::timer::ArmedTimers::new(),
+            retry: RetrySettings::new(),
+            panic_on_invariant_violation: false,
+            event_filter: accept_all_events,
+            policy: None,
+            async_verdicts: AsyncGuardVerdicts::new(),
+            needs_initial_entry: true,
+            entered_states: heapless::Vec::new(),
+            #[cfg(feature = "std")]
+            completion: None,
+            #[cfg(feature = "oscillation-guard")]
+            oscillation: oscillation::OscillationTracker::new(),
+        }
+    }
+
+    /// Enters the initial state of an unstarted runtime; see `Runtime::start`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProcessingError`] if the initial state cannot be entered.
+    pub fn start(&mut self, initial_event: &EventType) -> Result<(), ProcessingError> {
+        if !self.needs_initial_entry {
+            return Ok(());
+        }
+        self.enter_initial_state(self.machine_def.initial_leaf_state, initial_event)
+    }
+
+    /// Returns `false` until an unstarted runtime has entered its initial state.
+    #[must_use]
+    pub const fn is_started(&self) -> bool {
+        !self.needs_initial_entry
+    }
+
+    fn enter_initial_state(
+        &mut self,
+        initial_state: StateType,
+        initial_event: &EventType,
+    ) -> Result<(), ProcessingError> {
+        // The initial state's ancestors are entered first, outermost first.
+        let mut entries: heapless::Vec<StateType, M> =
+            self.machine_def.active_configuration(&[initial_state]);
+        let leaf = self
+            .push_initial_descent(initial_state, &mut entries)
+            .map_err(|_| ProcessingError::EntryLogicFailure)?;
+        if let Some(entry_fn) = self.machine_def.entry_action {
+            entry_fn(&mut self.context, initial_event);
+        }
+        self.run_entries(&entries, initial_event);
+        self.active_leaf = leaf;
+        self.needs_initial_entry = false;
+        self.entered_states = entries;
+        #[cfg(feature = "async")]
+        self.timers
+            .arm_entered(self.machine_def.after_timers, &self.entered_states);
+        Ok(())
+    }
+
+    /// Returns the active leaf state; before the runtime starts, the initial leaf it will
+    /// enter.
+    #[inline]
+    pub fn active_state(&self) -> StateType {
+        self.active_leaf
+    }
+
+    /// Returns the active configuration as a one-element view, like `Runtime::state`.
+    #[inline]
+    pub fn state(&self) -> heapless::Vec<StateType, MAX_ACTIVE_REGIONS> {
+        StateMachine::<MAX_ACTIVE_REGIONS>::state(self)
+    }
+
+    /// Returns the active leaf and its ancestors, root-most first; see
+    /// `Runtime::active_configuration`.
+    pub fn active_configuration<const N: usize>(&self) -> heapless::Vec<StateType, N> {
+        self.machine_def.active_configuration(&[self.active_leaf])
+    }
+
+    /// Returns the states entered and exited between this configuration and `other`'s; see
+    /// `Runtime::diff`.
+    pub fn diff<const N: usize>(&self, other: &Self) -> ConfigDiff<StateType, N> {
+        let before: heapless::Vec<StateType, N> = self.active_configuration();
+        let after: heapless::Vec<StateType, N> = other.active_configuration();
+        ConfigDiff::between(&before, &after)
+    }
+
+    /// Returns the states entered by the most recent send, parents first; see
+    /// `Runtime::entered_states`.
+    #[must_use]
+    pub fn entered_states(&self) -> &[StateType] {
+        &self.entered_states
+    }
+
+    /// Returns every event kind the machine would respond to right now; see
+    /// `Runtime::accepted_event_kinds`.
+    pub fn accepted_event_kinds<K>(&self) -> impl Iterator<Item = (K, Acceptance)> + '_
+    where
+        K: EventKind<Event = EventType>,
+    {
+        K::ALL.iter().filter_map(|&kind| {
+            self.machine_def
+                .acceptance(&[self.active_leaf], kind.name())
+                .map(|acceptance| (kind, acceptance))
+        })
+    }
+
+    /// Returns `true` once the active leaf is a `[final]` state; see `Runtime::is_final`.
+    #[must_use]
+    pub fn is_final(&self) -> bool {
+        !self.needs_initial_entry && completion::all_final(self.machine_def, &[self.active_leaf])
+    }
+
+    /// Returns a future that resolves once a send leaves the machine final; see
+    /// `Runtime::completed`.
+    #[cfg(feature = "std")]
+    pub fn completed(&mut self) -> super::Completion {
+        let is_final = self.is_final();
+        completion::subscribe(&mut self.completion, is_final)
+    }
+
+    /// Processes `event` and checks the definition's invariant; see `Runtime::send`.
+    #[inline]
+    #[must_use = "check whether the event was handled"]
+    pub fn send(&mut self, event: &EventType) -> SendResult {
+        let result = self.send_internal(event);
+        #[cfg(feature = "std")]
+        if self.completion.is_some() {
+            let is_final = self.is_final();
+            completion::notify(&mut self.completion, is_final);
+        }
+        invariant::check(
+            self.machine_def.invariant,
+            &self.context,
+            self.panic_on_invariant_violation,
+            result,
+        )
+    }
+
+    #[inline]
+    pub fn context(&self) -> &ContextType {
+        &self.context
+    }
+
+    #[inline]
+    pub fn context_mut(&mut self) -> &mut ContextType {
+        &mut self.context
+    }
+
+    /// Swaps in `context` and returns the previous one; see `Runtime::set_context`.
+    #[inline]
+    pub fn set_context(&mut self, context: ContextType) -> ContextType {
+        core::mem::replace(&mut self.context, context)
+    }
+
+    /// Sets the clock fallible actions wait on between retries; see
+    /// `Runtime::set_retry_clock`.
+    pub fn set_retry_clock(&mut self, clock: &'static (dyn MonotonicClock + Sync)) {
+        self.retry.clock = Some(clock);
+    }
+
+    /// Sets the observer told about every run of a fallible action; see
+    /// `Runtime::set_retry_observer`.
+    pub fn set_retry_observer(&mut self, observer: RetryObserverFn<StateType>) {
+        self.retry.observer = Some(observer);
+    }
+
+    /// Makes a violated invariant panic; see `Runtime::set_panic_on_invariant_violation`.
+    pub fn set_panic_on_invariant_violation(&mut self, panic: bool) {
+        self.panic_on_invariant_violation = panic;
+    }
+
+    /// Attaches a policy that can veto transitions; see `Runtime::set_policy`.
+    pub fn set_policy(
+        &mut self,
+        policy: Option<&'static dyn TransitionPolicy<StateType, EventType>>,
+    ) {
+        self.policy = policy;
+    }
+
+    /// Attaches an oscillation guard; see `Runtime::set_oscillation_guard`.
+    #[cfg(feature = "oscillation-guard")]
+    pub fn set_oscillation_guard(&mut self, guard: Option<super::OscillationGuard<StateType>>) {
+        self.oscillation.set_guard(guard);
+    }
+
+    /// Returns how often `state` was entered in the current window; see
+    /// `Runtime::entry_count`.
+    #[cfg(feature = "oscillation-guard")]
+    #[must_use]
+    pub fn entry_count(&self, state: StateType) -> u16 {
+        self.oscillation.count(state)
+    }
+
+    /// Resets the entry counters; see `Runtime::reset_entry_counts`.
+    #[cfg(feature = "oscillation-guard")]
+    pub fn reset_entry_counts(&mut self) {
+        self.oscillation.reset();
+    }
+
+    /// Returns the first async guard that sending `event` would reach without a verdict in
+    /// `verdicts`; see `Runtime::next_async_guard`.
+    #[must_use]
+    pub fn next_async_guard(
+        &self,
+        event: &EventType,
+        verdicts: &AsyncGuardVerdicts,
+    ) -> Option<PendingAsyncGuard> {
+        if self.needs_initial_entry || !(self.event_filter)(event) {
+            return None;
+        }
+        let mut pending = None;
+        let _ = self.select_transition(event, verdicts, &mut pending);
+        pending
+    }
+
+    /// Sends `event` with `verdicts` deciding its async guards; see
+    /// `Runtime::send_with_async_verdicts`.
+    #[must_use = "check whether the event was handled"]
+    pub fn send_with_async_verdicts(
+        &mut self,
+        event: &EventType,
+        verdicts: AsyncGuardVerdicts,
+    ) -> SendResult {
+        self.async_verdicts = verdicts;
+        self.send(event)
+    }
+
+    /// Processes `event`; see `Runtime::send_internal`.
+    #[must_use = "check whether the event was handled"]
+    pub fn send_internal(&mut self, event: &EventType) -> SendResult {
+        let async_verdicts = core::mem::take(&mut self.async_verdicts);
+        self.entered_states.clear();
+        if self.needs_initial_entry
+            && let Err(error) = self.start(event)
+        {
+            return SendResult::Error(error);
+        }
+        if !(self.event_filter)(event) {
+            return SendResult::NoMatch;
+        }
+        #[cfg(feature = "oscillation-guard")]
+        self.oscillation.on_event();
+        let Some((source, transition)) = self.select_transition(event, &async_verdicts, &mut None)
+        else {
+            return SendResult::NoMatch;
+        };
+        if let Some(policy) = self.policy
+            && !policy.allow(&[self.active_leaf], transition.to_state, event)
+        {
+            return SendResult::PolicyRejected;
+        }
+        let plan = match self.plan_transition(source, transition.to_state) {
+            Ok(plan) => plan,
+            Err(e) => return SendResult::Error(e),
+        };
+
+        // The context is changed in place, so a fallible action needs a copy to roll back to.
+        let rollback = transition
+            .fallible_action
+            .is_some()
+            .then(|| self.context.clone());
+        self.run_exits(&plan.exits, event);
+        if let Some(action_fn) = transition.action {
+            action_fn(&mut self.context, event);
+        }
+        if let Some(read_fn) = transition.read_action {
+            read_fn(&self.context, event);
+        }
+        if let Some(fallible) = &transition.fallible_action
+            && let Err(error) = self.retry.run(
+                fallible,
+                source,
+                transition.to_state,
+                &mut self.context,
+                event,
+            )
+        {
+            if let Some(context) = rollback {
+                self.context = context;
+            }
+            return SendResult::Error(error);
+        }
+        self.run_entries(&plan.entries, event);
+        self.active_leaf = plan.next_leaf;
+        self.entered_states = plan.entries;
+
+        #[cfg(feature = "coverage")]
+        self.record_transition_coverage(transition);
+        #[cfg(feature = "async")]
+        {
+            self.cancel_exited_timers(&plan.exits);
+            self.timers
+                .arm_entered(self.machine_def.after_timers, &self.entered_states);
+        }
+
+        #[cfg(feature = "oscillation-guard")]
+        if let Some(response) = self.oscillation.record(&self.entered_states)
+            && let Some(result) =
+                oscillation::respond(response, |target| self.force_transition(target, event))
+        {
+            return result;
+        }
+        SendResult::Transitioned
+    }
+
+    /// Forcibly moves the machine to `target`; see `Runtime::force_transition`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `target` is not part of the machine
+    /// definition, or a capacity error if the hierarchy is deeper than `M`.
+    pub fn force_transition(
+        &mut self,
+        target: StateType,
+        event: &EventType,
+    ) -> Result<(), ProcessingError> {
+        if self.machine_def.get_state_node(target).is_none() {
+            return Err(ProcessingError::UnknownState);
+        }
+        // The target is always exited and re-entered, so the scope is strictly above it.
+        let mut lca = self.find_lca(self.active_leaf, target)?;
+        if lca == Some(target) {
+            lca = self.machine_def.get_parent_of(target);
+        }
+        let mut plan = StepPlan {
+            exits: heapless::Vec::new(),
+            entries: heapless::Vec::new(),
+            next_leaf: target,
+        };
+        self.push_exit_path(lca, &mut plan.exits)?;
+        plan.next_leaf = self.push_entry_plan(target, lca, target, &mut plan.entries)?;
+
+        self.run_exits(&plan.exits, event);
+        self.run_entries(&plan.entries, event);
+        self.active_leaf = plan.next_leaf;
+
+        #[cfg(feature = "async")]
+        self.cancel_exited_timers(&plan.exits);
+        self.entered_states = plan.entries;
+        #[cfg(feature = "async")]
+        self.timers
+            .arm_entered(self.machine_def.after_timers, &self.entered_states);
+        Ok(())
+    }
+
+    /// Exits the active path and runs the definition's exit action, leaving the runtime
+    /// unstarted; see `Runtime::reset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::PathTooLong`] if the hierarchy is deeper than `M`; nothing
+    /// has run then.
+    pub fn reset(&mut self, event: &EventType) -> Result<(), ProcessingError> {
+        if self.needs_initial_entry {
+            return Ok(());
+        }
+        let mut exits: heapless::Vec<StateType, M> = heapless::Vec::new();
+        self.push_exit_path(None, &mut exits)?;
+        self.run_exits(&exits, event);
+        if let Some(exit_fn) = self.machine_def.exit_action {
+            exit_fn(&mut self.context, event);
+        }
+
+        #[cfg(feature = "async")]
+        self.cancel_exited_timers(&exits);
+        self.active_leaf = self.machine_def.initial_leaf_state;
+        self.entered_states.clear();
+        self.needs_initial_entry = true;
+        Ok(())
+    }
+
+    /// Finds the winning transition: the first matching transition of the deepest state on
+    /// the active path, mirroring `Runtime`'s arbitration for a single region. Async guards
+    /// are decided as in `Runtime::collect_potential_transitions`.
+    fn select_transition(
+        &self,
+        event: &EventType,
+        verdicts: &AsyncGuardVerdicts,
+        pending_async_guard: &mut Option<PendingAsyncGuard>,
+    ) -> Option<(
+        StateType,
+        &'static Transition<StateType, EventType, ContextType>,
+    )> {
+        let mut check_state = Some(self.active_leaf);
+        while let Some(state) = check_state {
+            for (index, transition) in self.machine_def.transitions.iter().enumerate() {
+                if transition.from_state != state {
+                    continue;
+                }
+                if transition.match_fn.is_some_and(|matches| !matches(event)) {
+                    continue;
+                }
+                if transition
+                    .guard
+                    .is_some_and(|guard| !guard(&self.context, event))
+                {
+                    continue;
+                }
+                if transition.state_guard.is_some_and(|guard| {
+                    !guard(&self.context, event, &|state| self.is_state_active(state))
+                }) {
+                    continue;
+                }
+                if transition
+                    .async_guard
+                    .is_some_and(|slot| !verdicts.allows(index, slot, pending_async_guard))
+                {
+                    continue;
+                }
+                return Some((state, transition));
+            }
+            check_state = self.machine_def.get_parent_of(state);
+        }
+        None
+    }
+
+    /// Returns `true` if `state` is the active leaf or one of its ancestors.
+    fn is_state_active(&self, state: StateType) -> bool {
+        let mut current = Some(self.active_leaf);
+        while let Some(active) = current {
+            if active == state {
+                return true;
+            }
+            current = self.machine_def.get_parent_of(active);
+        }
+        false
+    }
+
+    /// Computes exits and entries for a transition from `source` to `target`.
+    ///
+    /// Self-transitions exit and re-enter the source; all other transitions are scoped by
+    /// the least common ancestor of the active leaf and the target.
+    fn plan_transition(
+        &self,
+        source: StateType,
+        target: StateType,
+    ) -> Result<StepPlan<StateType, M>, ProcessingError> {
+        let (exit_scope, entry_scope) = if source == target {
+            (self.machine_def.get_parent_of(source), Some(source))
+        } else {
+            // A target on the active path is exited and re-entered, like a self-transition.
+            let mut lca = self.find_lca(self.active_leaf, target)?;
+            if lca == Some(target) {
+                lca = self.machine_def.get_parent_of(target);
+            }
+            (lca, lca)
+        };
+        let mut plan = StepPlan {
+            exits: heapless::Vec::new(),
+            entries: heapless::Vec::new(),
+            next_leaf: target,
+        };
+        self.push_exit_path(exit_scope, &mut plan.exits)?;
+        plan.next_leaf =
+            self.push_entry_plan(target, entry_scope, self.active_leaf, &mut plan.entries)?;
+        Ok(plan)
+    }
+
+    /// Pushes the active path from the leaf up to, but excluding, `scope`.
+    fn push_exit_path(
+        &self,
+        scope: Option<StateType>,
+        exits: &mut heapless::Vec<StateType, M>,
+    ) -> Result<(), ProcessingError> {
+        let mut current = Some(self.active_leaf);
+        while let Some(state) = current {
+            if scope == Some(state) {
+                break;
+            }
+            exits
+                .push(state)
+                .map_err(|_| ProcessingError::PathTooLong)?;
+            current = self.machine_def.get_parent_of(state);
+        }
+        Ok(())
+    }
+
+    /// Pushes the states entered when moving into `target` from within `scope`, and returns
+    /// the leaf the machine ends up in.
+    fn push_entry_plan(
+        &self,
+        target: StateType,
+        scope: Option<StateType>,
+        source_leaf: StateType,
+        entries: &mut heapless::Vec<StateType, M>,
+    ) -> Result<StateType, ProcessingError> {
+        // States strictly below the scope down to the target, root-most first.
+        let mut path: heapless::Vec<StateType, M> = heapless::Vec::new();
+        let mut current = Some(target);
+        while let Some(state) = current {
+            if scope == Some(state) {
+                break;
+            }
+            path.push(state).map_err(|_| ProcessingError::PathTooLong)?;
+            current = self.machine_def.get_parent_of(state);
+        }
+        for &state in path.iter().rev() {
+            entries
+                .push(state)
+                .map_err(|_| ProcessingError::PathTooLong)?;
+        }
+
+        // A leaf transitioning to itself is re-entered even though it is its own scope.
+        if scope == Some(target) && target == source_leaf {
+            entries
+                .push(target)
+                .map_err(|_| ProcessingError::CapacityExceeded)?;
+        }
+
+        let node = self
+            .machine_def
+            .get_state_node(target)
+            .ok_or(ProcessingError::EntryLogicFailure)?;
+        if node.initial_child.is_some() {
+            self.push_initial_descent(target, entries)
+                .map_err(|_| ProcessingError::EntryLogicFailure)
+        } else {
+            Ok(target)
+        }
+    }
+
+    /// Pushes `state` (unless already entered this step) and its chain of initial children,
+    /// returning the resulting leaf.
+    fn push_initial_descent(
+        &self,
+        state: StateType,
+        entries: &mut heapless::Vec<StateType, M>,
+    ) -> Result<StateType, ProcessingError> {
+        let mut current = state;
+        loop {
+            if !entries.contains(&current) {
+                entries
+                    .push(current)
+                    .map_err(|_| ProcessingError::CapacityExceeded)?;
+            }
+            match self
+                .machine_def
+                .get_state_node(current)
+                .ok_or(ProcessingError::EntryLogicFailure)?
+                .initial_child
+            {
+                Some(child) => current = child,
+                None => return Ok(current),
+            }
+        }
+    }
+
+    fn find_lca(
+        &self,
+        state1: StateType,
+        state2: StateType,
+    ) -> Result<Option<StateType>, ProcessingError> {
+        let mut path2: heapless::Vec<StateType, M> = heapless::Vec::new();
+        let mut current = Some(state2);
+        while let Some(state) = current {
+            path2
+                .push(state)
+                .map_err(|_| ProcessingError::PathTooLong)?;
+            current = self.machine_def.get_parent_of(state);
+        }
+        let mut current = Some(state1);
+        while let Some(state) = current {
+            if path2.contains(&state) {
+                return Ok(Some(state));
+            }
+            current = self.machine_def.get_parent_of(state);
+        }
+        Ok(None)
+    }
+
+    fn run_exits(&mut self, exits: &[StateType], event: &EventType) {
+        for &state in exits {
+            if let Some(exit_fn) = self
+                .machine_def
+                .get_state_node(state)
+                .and_then(|n| n.exit_action)
+            {
+                exit_fn(&mut self.context, event);
+            }
+        }
+    }
+
+    fn run_entries(&mut self, entries: &[StateType], event: &EventType) {
+        for &state in entries {
+            if let Some(entry_fn) = self
+                .machine_def
+                .get_state_node(state)
+                .and_then(|n| n.entry_action)
+            {
+                entry_fn(&mut self.context, event);
+            }
+            #[cfg(feature = "coverage")]
+            self.record_state_coverage(state);
+        }
+    }
+
+    /// Attaches a coverage recorder; see `Runtime::set_coverage_recorder`.
+    #[cfg(feature = "coverage")]
+    pub fn set_coverage_recorder(&mut self, recorder: &'static crate::coverage::CoverageRecorder) {
+        self.coverage = Some(recorder);
+        let mut current = Some(self.active_leaf);
+        while let Some(state) = current {
+            self.record_state_coverage(state);
+            current = self.machine_def.get_parent_of(state);
+        }
+    }
+
+    #[cfg(feature = "coverage")]
+    fn record_state_coverage(&self, state: StateType) {
+        if let Some(recorder) = self.coverage
+            && let Some(index) = self.machine_def.states.iter().position(|n| n.id == state)
+        {
+            recorder.record_state(index);
+        }
+    }
+
+    #[cfg(feature = "coverage")]
+    fn record_transition_coverage(
+        &self,
+        transition: &'static Transition<StateType, EventType, ContextType>,
+    ) {
+        if let Some(recorder) = self.coverage
+            && let Some(ordinal) = self
+                .machine_def
+                .transitions
+                .iter()
+                .position(|t| core::ptr::eq(t, transition))
+        {
+            recorder.record_transition(ordinal);
+        }
+    }
+
+    /// See `Runtime::arm_timer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `state` is not currently active, and
+    /// [`ProcessingError::CapacityExceeded`] if the timer table is full.
+    #[cfg(feature = "async")]
+    pub fn arm_timer(
+        &mut self,
+        state: StateType,
+        timer_id: usize,
+        token: crate::timer::TimerToken,
+    ) -> Result<(), ProcessingError> {
+        let mut current = Some(self.active_leaf);
+        while let Some(active) = current {
+            if active == state {
+                return self
+                    .timers
+                    .track(state, timer_id, token)
+                    .map(|_superseded| ())
+                    .map_err(|_| ProcessingError::CapacityExceeded);
+            }
+            current = self.machine_def.get_parent_of(active);
+        }
+        Err(ProcessingError::UnknownState)
+    }
+
+    /// See `Runtime::cancel_timer`.
+    #[cfg(feature = "async")]
+    pub fn cancel_timer(&mut self, token: crate::timer::TimerToken) -> bool {
+        self.timers.cancel(token)
+    }
+
+    /// See `Runtime::timer_fired`.
+    #[cfg(feature = "async")]
+    pub fn timer_fired(&mut self, token: crate::timer::TimerToken) -> Option<(StateType, usize)> {
+        self.timers
+            .fire(token)
+            .map(|armed| (armed.state, armed.timer_id))
+    }
+
+    /// See `Runtime::set_timer_clock`.
+    #[cfg(feature = "async")]
+    pub fn set_timer_clock(
+        &mut self,
+        clock: &'static (dyn crate::MonotonicClock + Sync),
+        tick: core::time::Duration,
+    ) {
+        self.timers.set_clock(clock, tick);
+    }
+
+    /// See `Runtime::armed_timers`.
+    #[cfg(feature = "async")]
+    pub fn armed_timers(&self) -> impl Iterator<Item = (StateType, core::time::Duration)> + '_ {
+        self.timers
+            .iter()
+            .map(|timer| (timer.state, self.timers.remaining(timer)))
+    }
+
+    /// See `Runtime::entered_timers`.
+    #[cfg(feature = "async")]
+    pub fn entered_timers(&self) -> impl Iterator<Item = crate::timer::TimerToken> + '_ {
+        self.timers
+            .iter()
+            .filter(|timer| self.entered_states.contains(&timer.state))
+            .map(|timer| timer.token)
+    }
+
+    #[cfg(feature = "async")]
+    fn cancel_exited_timers(&mut self, exited: &[StateType]) {
+        for &state in exited {
+            self.timers.cancel_state(
+                state,
+                <crate::timer::Timer as crate::timer::TimerService>::cancel,
+            );
+        }
+    }
+}
+
+impl<StateType, EventType, ContextType, const M: usize> PolicyHost<StateType, EventType>
+    for FlatRuntime<StateType, EventType, ContextType, M>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    fn set_policy(&mut self, policy: Option<&'static dyn TransitionPolicy<StateType, EventType>>) {
+        FlatRuntime::set_policy(self, policy);
+    }
+}
+
+impl<StateType, EventType, ContextType, const M: usize, const N_ACTIVE: usize>
+    StateMachine<N_ACTIVE> for FlatRuntime<StateType, EventType, ContextType, M>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    type State = StateType;
+    type Event = EventType;
+    type Context = ContextType;
+
+    #[inline]
+    fn send(&mut self, event: &EventType) -> SendResult {
+        FlatRuntime::send(self, event)
+    }
+
+    #[inline]
+    fn reset(&mut self, event: &EventType) -> Result<(), ProcessingError> {
+        FlatRuntime::reset(self, event)
+    }
+
+    #[inline]
+    fn state(&self) -> heapless::Vec<Self::State, N_ACTIVE> {
+        let mut active = heapless::Vec::new();
+        if self.needs_initial_entry {
+            return active;
+        }
+        // N_ACTIVE is at least one for every caller; a zero-capacity view stays empty.
+        let _ = active.push(self.active_leaf);
+        active
+    }
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+
+    #[inline]
+    fn context_mut(&mut self) -> &mut Self::Context {
+        &mut self.context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{Runtime, StateNode};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    enum S {
+        Idle,
+        Active,
+        ActiveA,
+        ActiveB,
+        Done,
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    enum E {
+        Go,
+        Next,
+        Reset,
+        Again,
+        Leaf,
+        Finish,
+    }
+
+    type Log = heapless::Vec<(&'static str, S), 64>;
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    struct Ctx {
+        log: Log,
+        blocked: bool,
+    }
+
+    macro_rules! log_fn {
+        ($name:ident, $kind:literal, $state:expr) => {
+            fn $name(ctx: &mut Ctx, _event: &E) {
+                let _ = ctx.log.push(($kind, $state));
+            }
+        };
+    }
+    log_fn!(enter_idle, "entry", S::Idle);
+    log_fn!(exit_idle, "exit", S::Idle);
+    log_fn!(enter_active, "entry", S::Active);
+    log_fn!(exit_active, "exit", S::Active);
+    log_fn!(enter_a, "entry", S::ActiveA);
+    log_fn!(exit_a, "exit", S::ActiveA);
+    log_fn!(enter_b, "entry", S::ActiveB);
+    log_fn!(exit_b, "exit", S::ActiveB);
+    log_fn!(enter_done, "entry", S::Done);
+    log_fn!(action_log, "action", S::Done);
+
+    fn not_blocked(ctx: &Ctx, _event: &E) -> bool {
+        !ctx.blocked
+    }
+
+    const STATES: &[StateNode<S, Ctx, E>] = &[
+        StateNode {
+            id: S::Idle,
+            parent: None,
+            initial_child: None,
+            entry_action: Some(enter_idle),
+            exit_action: Some(exit_idle),
+            is_parallel: false,
+            is_final: false,
+        },
+        StateNode {
+            id: S::Active,
+            parent: None,
+            initial_child: Some(S::ActiveA),
+            entry_action: Some(enter_active),
+            exit_action: Some(exit_active),
+            is_parallel: false,
+            is_final: false,
+        },
+        StateNode {
+            id: S::ActiveA,
+            parent: Some(S::Active),
+            initial_child: None,
+            entry_action: Some(enter_a),
+            exit_action: Some(exit_a),
+            is_parallel: false,
+            is_final: false,
+        },
+        StateNode {
+            id: S::ActiveB,
+            parent: Some(S::Active),
+            initial_child: None,
+            entry_action: Some(enter_b),
+            exit_action: Some(exit_b),
+            is_parallel: false,
+            is_final: false,
+        },
+        StateNode {
+            id: S::Done,
+            parent: None,
+            initial_child: None,
+            entry_action: Some(enter_done),
+            exit_action: None,
+            is_parallel: false,
+            is_final: true,
+        },
+    ];
+
+    macro_rules! on {
+        ($from:expr => $to:expr, $event:pat) => {
+            on!($from => $to, $event, None, None)
+        };
+        ($from:expr => $to:expr, $event:pat, $guard:expr, $action:expr) => {
+            Transition {
+                from_state: $from,
+                to_state: $to,
+                action: $action,
+                guard: $guard,
+                match_fn: Some(|e: &E| matches!(e, $event)),
+                state_guard: None,
+                read_action: None,
+                fallible_action: None,
+                async_guard: None,
+            }
+        };
+    }
+
+    static TRANSITION_TABLE: &[Transition<S, E, Ctx>] = &[
+        on!(S::Idle => S::Active, E::Go, Some(not_blocked), Some(action_log)),
+        on!(S::Idle => S::Idle, E::Again),
+        on!(S::ActiveA => S::ActiveB, E::Next),
+        on!(S::ActiveB => S::ActiveA, E::Next),
+        on!(S::ActiveB => S::ActiveB, E::Leaf),
+        on!(S::Active => S::Active, E::Again),
+        on!(S::Active => S::Idle, E::Reset),
+        on!(S::ActiveA => S::Active, E::Reset),
+        on!(S::Active => S::ActiveB, E::Leaf),
+        on!(S::Active => S::Done, E::Finish, None, Some(action_log)),
+        on!(S::Done => S::Idle, E::Go),
+    ];
+    static DEF: MachineDefinition<S, E, Ctx> =
+        MachineDefinition::new(STATES, TRANSITION_TABLE, S::Idle);
+
+    const DEPTH: usize = 5;
+
+    fn both() -> (
+        FlatRuntime<S, E, Ctx, DEPTH>,
+        Runtime<S, E, Ctx, DEPTH, 4, 20>,
+    ) {
+        let flat = FlatRuntime::new(&DEF, Ctx::default(), &E::Go).expect("flat runtime");
+        let full = Runtime::new(&DEF, Ctx::default(), &E::Go).expect("runtime");
+        (flat, full)
+    }
+
+    #[test]
+    fn matches_runtime_on_every_event_sequence_of_length_four() {
+        let events = [E::Go, E::Next, E::Reset, E::Again, E::Leaf, E::Finish];
+        let mut sequence = [0usize; 4];
+        loop {
+            let (mut flat, mut full) = both();
+            for (step, &index) in sequence.iter().enumerate() {
+                let event = &events[index];
+                if step == 2 {
+                    flat.context_mut().blocked = true;
+                    full.context_mut().blocked = true;
+                }
+                assert_eq!(
+                    flat.send(event),
+                    full.send(event),
+                    "result differs for {sequence:?} at step {step}"
+                );
+                assert_eq!(
+                    flat.state(),
+                    full.state(),
+                    "state differs for {sequence:?} at step {step}"
+                );
+                assert_eq!(
+                    flat.context(),
+                    full.context(),
+                    "actions differ for {sequence:?} at step {step}"
+                );
+                assert_eq!(flat.is_final(), full.is_final());
+            }
+
+            // Advance the odometer over all 6^4 sequences.
+            let mut digit = 0;
+            loop {
+                if digit == sequence.len() {
+                    return;
+                }
+                sequence[digit] += 1;
+                if sequence[digit] < events.len() {
+                    break;
+                }
+                sequence[digit] = 0;
+                digit += 1;
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn completed_resolves_once_the_leaf_is_final() {
+        let (mut flat, _) = both();
+        let done = flat.completed();
+        assert_eq!(flat.send(&E::Go), SendResult::Transitioned);
+        assert!(!done.is_complete());
+        assert_eq!(flat.send(&E::Finish), SendResult::Transitioned);
+        assert!(flat.is_final() && done.is_complete());
+    }
+
+    #[test]
+    fn force_transition_matches_runtime() {
+        for target in [S::Idle, S::Active, S::ActiveA, S::ActiveB, S::Done] {
+            let (mut flat, mut full) = both();
+            assert_eq!(flat.send(&E::Go), SendResult::Transitioned);
+            assert_eq!(full.send(&E::Go), SendResult::Transitioned);
+            assert_eq!(
+                flat.force_transition(target, &E::Reset),
+                full.force_transition(target, &E::Reset)
+            );
+            assert_eq!(flat.state(), full.state());
+            assert_eq!(flat.context(), full.context(), "target {target:?}");
+        }
+    }
+
+    #[test]
+    fn is_smaller_than_the_multi_region_runtime() {
+        assert!(
+            core::mem::size_of::<FlatRuntime<S, E, Ctx, DEPTH>>()
+                < core::mem::size_of::<Runtime<S, E, Ctx, DEPTH, 4, 20>>()
+        );
+    }
+
+    #[test]
+    fn state_is_a_single_leaf_and_initial_state_can_be_overridden() {
+        let flat: FlatRuntime<S, E, Ctx, DEPTH> =
+            FlatRuntime::with_initial_state(&DEF, Ctx::default(), S::Active, &E::Go)
+                .expect("flat runtime");
+
+        assert_eq!(flat.active_state(), S::ActiveA);
+        assert_eq!(flat.state().as_slice(), &[S::ActiveA]);
+        assert_eq!(
+            flat.context().log.as_slice(),
+            &[("entry", S::Active), ("entry", S::ActiveA)]
+        );
+    }
+}
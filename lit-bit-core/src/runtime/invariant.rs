@@ -0,0 +1,87 @@
+//! Context invariants checked after every external event.
+//!
+//! `invariant: self.check_invariants,` in a chart header attaches an [`InvariantFn`] to the
+//! machine definition. In debug builds, or with the `validate-context` feature, the runtime
+//! calls it once an external event and every internal event it raised have been processed,
+//! and reports a violation as [`SendResult::InvariantViolated`]. Actions may break an
+//! invariant temporarily, as long as the cascade has restored it by the time it completes.
+//!
+//! A violation does not roll anything back: the machine keeps the configuration and context
+//! the event led to, just as in a build without the check, and keeps accepting events.
+//! [`Runtime::set_panic_on_invariant_violation`] turns violations into panics instead, which
+//! is handy in tests.
+
+use super::{Runtime, SendResult};
+
+/// Checks a context for consistency, returning a short description of the first broken
+/// invariant, e.g. `"credits never negative"`.
+pub type InvariantFn<ContextType> = fn(context: &ContextType) -> Result<(), &'static str>;
+
+/// Whether this build checks invariants: always in debug builds, and in release builds
+/// with the `validate-context` feature.
+pub const INVARIANT_CHECKS_ENABLED: bool =
+    cfg!(any(debug_assertions, feature = "validate-context"));
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Makes a violated invariant panic instead of being reported as
+    /// [`SendResult::InvariantViolated`]. Has no effect in builds that do not check
+    /// invariants (see [`INVARIANT_CHECKS_ENABLED`]).
+    pub fn set_panic_on_invariant_violation(&mut self, panic: bool) {
+        self.panic_on_invariant_violation = panic;
+    }
+
+    /// Runs the definition's invariant on the context once `result`'s event has run to
+    /// completion.
+    pub(super) fn check_invariant(&self, result: SendResult) -> SendResult {
+        check(
+            self.machine_def.invariant,
+            &self.context,
+            self.panic_on_invariant_violation,
+            result,
+        )
+    }
+}
+
+/// Replaces `result` with [`SendResult::InvariantViolated`] if `invariant` rejects
+/// `context`, or panics if `panic` is set.
+pub(super) fn check<ContextType>(
+    invariant: Option<InvariantFn<ContextType>>,
+    context: &ContextType,
+    panic: bool,
+    result: SendResult,
+) -> SendResult {
+    if !INVARIANT_CHECKS_ENABLED {
+        return result;
+    }
+    // An error or an oscillation is reported as is, and a cascade cut short by its budget
+    // or by the oscillation guard has not completed.
+    if matches!(
+        result,
+        SendResult::Error(_) | SendResult::BudgetExhausted { .. } | SendResult::OscillationDetected
+    ) {
+        return result;
+    }
+    let Some(invariant) = invariant else {
+        return result;
+    };
+    match invariant(context) {
+        Ok(()) => result,
+        Err(violation) => {
+            assert!(!panic, "context invariant violated: {violation}");
+            trace!("[TRACE] context invariant violated: {}", violation);
+            SendResult::InvariantViolated(violation)
+        }
+    }
+}
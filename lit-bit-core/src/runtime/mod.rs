@@ -1,5 +1,42 @@
-// src/core/mod.rs
-// This module will house core state machine logic and types.
+//! # Statechart Runtime
+//!
+//! [`Runtime`] executes the [`MachineDefinition`] generated by `statechart!`; with the
+//! `single-region` feature, charts without parallel or history states use `FlatRuntime`
+//! instead.
+//!
+//! ## Transition Ordering
+//!
+//! Both runtimes run the actions of a transition from `source` to `target` in this order:
+//!
+//! 1. **Exits, innermost first.** Every active state below the transition's *scope* is
+//!    exited, starting from the active leaf and walking up to (but not including) the scope.
+//! 2. **The transition action**, after every exit and before any entry.
+//! 3. **Entries, outermost first.** The states from just below the scope down to `target`
+//!    are entered, followed by `target`'s chain of initial children.
+//!
+//! The scope is the deepest state that contains both the active leaf and `target`. If that
+//! is `target` itself — a self-transition, or a transition to an ancestor of the active leaf
+//! — the scope is `target`'s parent instead, so `target` is always exited and re-entered.
+//! The source state plays no part: a state handling an event for an active descendant
+//! behaves as if the descendant had the transition, so a transition from a state to one of
+//! its own descendants keeps the source (and any ancestor of the target that stays active)
+//! without exiting it.
+//!
+//! For example, with `A { B { C { D, E } } }` active in `D`:
+//!
+//! | Transition | Actions                                                   |
+//! |------------|-----------------------------------------------------------|
+//! | `D => E`   | exit D, action, enter E                                   |
+//! | `D => B`   | exit D, exit C, exit B, action, enter B, enter C, enter D |
+//! | `B => E`   | exit D, action, enter E                                   |
+//! | `B => B`   | exit D, exit C, exit B, action, enter B, enter C, enter D |
+//! | `D => D`   | exit D, action, enter D                                   |
+//!
+//! Starting a machine enters its initial configuration the same way, outermost first from
+//! the top-level state down to the initial leaf. When a parallel state is entered, it is
+//! entered before its regions, and the regions are entered one after the other in
+//! declaration order, each down to its initial leaf. These guarantees are pinned by the
+//! conformance tests in `lit-bit-tests`.
 
 #[macro_use]
 mod tracing {
@@ -19,6 +56,45 @@ mod tracing {
 #[allow(unused_imports)]
 use heapless::Vec;
 
+mod completion;
+#[cfg(feature = "std")]
+pub use completion::Completion;
+
+mod configuration;
+pub use configuration::{Configuration, StateSet};
+
+mod event_kinds;
+pub use event_kinds::{Acceptance, EventKind, TransitionEventKinds};
+
+mod async_guard;
+pub use async_guard::{AsyncGuardVerdicts, MAX_ASYNC_GUARD_VERDICTS, PendingAsyncGuard};
+
+mod budget;
+pub use budget::{MAX_INTERNAL_EVENTS, MonotonicClock, RaisedEventFn, Ticks};
+
+mod invariant;
+pub use invariant::{INVARIANT_CHECKS_ENABLED, InvariantFn};
+
+#[cfg(feature = "oscillation-guard")]
+mod oscillation;
+#[cfg(feature = "oscillation-guard")]
+pub use oscillation::{OscillationGuard, OscillationResponse, OscillationWindow};
+
+mod policy;
+pub use policy::{DenyTargetSet, DenyTargets, PolicyHost, TransitionPolicy};
+
+mod retry;
+use retry::RetrySettings;
+pub use retry::{
+    ActionError, ActionOutcome, FallibleAction, FallibleActionFn, RetryObserverFn, RetryReport,
+    is_fallible_action,
+};
+
+#[cfg(feature = "single-region")]
+mod flat;
+#[cfg(feature = "single-region")]
+pub use flat::FlatRuntime;
+
 // Re-export the StateMachine trait for easier use if core types implement it.
 // Potentially, the macro-generated machine would be in a submodule of `core` or a user module.
 pub use crate::StateMachine;
@@ -39,16 +115,35 @@ pub struct DefaultContext {/* ... fields ... */}
 // Define function pointer types for actions and guards
 pub type ActionFn<ContextType, EventType> = fn(context: &mut ContextType, event: &EventType);
 pub type GuardFn<ContextType, EventType> = fn(context: &ContextType, event: &EventType) -> bool;
+/// A guard that can also ask whether a state is active (`in(State)` in a chart's guard);
+/// `is_active` sees the configuration before the transition, ancestors included.
+pub type StateGuardFn<StateType, ContextType, EventType> =
+    fn(context: &ContextType, event: &EventType, is_active: &dyn Fn(StateType) -> bool) -> bool;
 pub type EntryExitActionFn<ContextType, EventType> =
     fn(context: &mut ContextType, event: &EventType);
+/// A transition action that only reads the context (`[action(read) f]` in a chart), such
+/// as a notification through interior mutability. Read actions of transitions in different
+/// parallel regions may be reordered, or run concurrently, by later runtimes; only their
+/// order relative to the mutating actions of the same step is kept.
+pub type ReadActionFn<ContextType, EventType> = fn(context: &ContextType, event: &EventType);
 
 // Add near ActionFn / GuardFn
 type MatchFn<EventType> = fn(&EventType) -> bool;
 
+/// Decides whether a runtime handles an event at all; events it rejects are reported as
+/// [`SendResult::NoMatch`] without any transition being considered.
+pub type EventFilterFn<EventType> = fn(event: &EventType) -> bool;
+
+/// The filter of a runtime built without one.
+const fn accept_all_events<EventType>(_event: &EventType) -> bool {
+    true
+}
+
 // --- Flat State Machine Definition ---
 
 /// Represents a simple transition for a flat state machine.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[allow(unpredictable_function_pointer_comparisons)]
 pub struct Transition<StateType, EventType, ContextType> {
     pub from_state: StateType,
     pub to_state: StateType,
@@ -56,11 +151,29 @@ pub struct Transition<StateType, EventType, ContextType> {
     pub guard: Option<GuardFn<ContextType, EventType>>,
     /// Pattern matching function that determines if an event matches this transition
     pub match_fn: Option<MatchFn<EventType>>,
+    /// Guard that can test the active states too; checked after `guard`.
+    pub state_guard: Option<StateGuardFn<StateType, ContextType, EventType>>,
+    /// Action that only reads the context (`[action(read) f]`); runs after `action`.
+    pub read_action: Option<ReadActionFn<ContextType, EventType>>,
+    /// Action that may fail and be retried (`[action retry(n) f]`); runs after `action`.
+    pub fallible_action: Option<FallibleAction<ContextType, EventType>>,
+    /// Slot of an awaited condition (`[guard async cond]`), checked after the other guards.
+    /// Only a send with [`AsyncGuardVerdicts`] can pass it; see the `async_guard` module.
+    pub async_guard: Option<u16>,
+}
+
+impl<StateType, EventType, ContextType> Transition<StateType, EventType, ContextType> {
+    /// Returns `true` if any kind of guard can reject the transition.
+    #[must_use]
+    pub const fn is_guarded(&self) -> bool {
+        self.guard.is_some() || self.state_guard.is_some() || self.async_guard.is_some()
+    }
 }
 
 /// Defines the structure of a simple, flat state machine.
 /// This would be largely generated by the `statechart!` macro.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[allow(unpredictable_function_pointer_comparisons)]
 pub struct StateNode<StateType, ContextType, EventType>
 where
     StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
@@ -81,6 +194,19 @@ where
     /// This field is set automatically by the `statechart!` macro when the `[parallel]`
     /// attribute is used in the state definition.
     pub is_parallel: bool,
+    /// Whether this is a `[final]` state: the chart, or the parallel region containing it,
+    /// is done once it gets here. See [`Runtime::is_final`].
+    pub is_final: bool,
+}
+
+/// A `const` item declared in a `statechart!` body, recorded as written for exporters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChartConstant {
+    pub name: &'static str,
+    /// The declared type, as source text.
+    pub ty: &'static str,
+    /// The initializer expression, as source text.
+    pub value: &'static str,
 }
 
 #[derive(Clone)]
@@ -93,6 +219,25 @@ where
     pub states: &'static [StateNode<StateType, ContextType, EventType>],
     pub transitions: &'static [Transition<StateType, EventType, ContextType>],
     pub initial_leaf_state: StateType,
+    /// Event kinds each transition's pattern matches, parallel to `transitions`; empty
+    /// unless set with [`Self::with_event_kinds`].
+    pub event_kinds: &'static [TransitionEventKinds],
+    /// Checked after every external event in debug builds; see [`Self::with_invariant`].
+    pub invariant: Option<InvariantFn<ContextType>>,
+    /// Runs once when the machine starts, before any state's entry action; see
+    /// [`Self::with_entry_action`].
+    pub entry_action: Option<EntryExitActionFn<ContextType, EventType>>,
+    /// Runs when the machine is reset, after every state's exit action; see
+    /// [`Self::with_exit_action`].
+    pub exit_action: Option<EntryExitActionFn<ContextType, EventType>>,
+    /// The chart's `const` items; empty unless set with [`Self::with_constants`].
+    pub constants: &'static [ChartConstant],
+    /// Compound states re-entered at the leaf they were last left from; empty unless set
+    /// with [`Self::with_history_states`].
+    pub history_states: &'static [StateType],
+    /// The duration of every `after` transition, by owning state; empty unless set with
+    /// [`Self::with_after_timers`].
+    pub after_timers: &'static [(StateType, core::time::Duration)],
 }
 
 // Manual Debug impl to avoid requiring StateType, EventType, ContextType to be Debug for MachineDefinition itself to be Debug
@@ -108,6 +253,13 @@ where
             .field("states", &self.states) // StateNode needs Debug for this to be useful
             .field("transitions", &self.transitions)
             .field("initial_leaf_state", &self.initial_leaf_state)
+            .field("event_kinds", &self.event_kinds)
+            .field("invariant", &self.invariant.is_some())
+            .field("entry_action", &self.entry_action.is_some())
+            .field("exit_action", &self.exit_action.is_some())
+            .field("constants", &self.constants)
+            .field("history_states", &self.history_states)
+            .field("after_timers", &self.after_timers)
             .finish()
     }
 }
@@ -127,9 +279,79 @@ where
             states,
             transitions,
             initial_leaf_state,
+            event_kinds: &[],
+            invariant: None,
+            entry_action: None,
+            exit_action: None,
+            constants: &[],
+            history_states: &[],
+            after_timers: &[],
         }
     }
 
+    /// Attaches the context invariant the runtime checks after every external event and its
+    /// cascade of internal events, reporting violations as [`SendResult::InvariantViolated`].
+    /// Only checked in debug builds or with the `validate-context` feature.
+    #[must_use]
+    pub const fn with_invariant(mut self, invariant: InvariantFn<ContextType>) -> Self {
+        self.invariant = Some(invariant);
+        self
+    }
+
+    /// Attaches the action of the machine's implicit root state: it runs with the initial
+    /// event when the runtime starts, before the entry action of any state.
+    #[must_use]
+    pub const fn with_entry_action(
+        mut self,
+        action: EntryExitActionFn<ContextType, EventType>,
+    ) -> Self {
+        self.entry_action = Some(action);
+        self
+    }
+
+    /// Attaches the exit action of the machine's implicit root state: it runs when the
+    /// runtime is reset (see [`Runtime::reset`]), after the exit action of every state.
+    #[must_use]
+    pub const fn with_exit_action(
+        mut self,
+        action: EntryExitActionFn<ContextType, EventType>,
+    ) -> Self {
+        self.exit_action = Some(action);
+        self
+    }
+
+    /// Attaches the table of the chart's `const` items, for exporters to list.
+    #[must_use]
+    pub const fn with_constants(mut self, constants: &'static [ChartConstant]) -> Self {
+        self.constants = constants;
+        self
+    }
+
+    /// Marks the compound states declared with `initial: history`: once such a state has
+    /// been exited, entering it again without naming one of its descendants restores the
+    /// leaf it was left from instead of its initial child. Each region of a parallel state
+    /// keeps its own record, so the regions are restored independently. The states must
+    /// not contain a parallel state. Only [`Runtime`] restores history; `statechart!` never
+    /// picks [`FlatRuntime`] for a chart that declares it.
+    #[must_use]
+    pub const fn with_history_states(mut self, history_states: &'static [StateType]) -> Self {
+        self.history_states = history_states;
+        self
+    }
+
+    /// Attaches the durations of the chart's `after` transitions. A state's timers are
+    /// numbered in the order they are listed, which is the `timer_id` of its `TimerFired`
+    /// events. With the `async` feature, the runtime arms a token for each one whenever
+    /// its state is entered; see `Runtime::entered_timers`.
+    #[must_use]
+    pub const fn with_after_timers(
+        mut self,
+        after_timers: &'static [(StateType, core::time::Duration)],
+    ) -> Self {
+        self.after_timers = after_timers;
+        self
+    }
+
     // Helper to find a state node by its ID
     pub fn get_state_node(
         &self,
@@ -138,6 +360,35 @@ where
         self.states.iter().find(|s_node| s_node.id == state_id)
     }
 
+    /// Collects every state that is active when `leaves` are the active leaves: each leaf
+    /// and all of its ancestors, root-most first, without duplicates. States beyond the
+    /// capacity `N` are dropped.
+    pub fn active_configuration<const N: usize>(
+        &self,
+        leaves: &[StateType],
+    ) -> heapless::Vec<StateType, N> {
+        let mut configuration = heapless::Vec::new();
+        for &leaf in leaves {
+            let mark = configuration.len();
+            let mut current = Some(leaf);
+            while let Some(state) = current {
+                if !configuration.contains(&state) && configuration.push(state).is_err() {
+                    break;
+                }
+                current = self.get_parent_of(state);
+            }
+            // Ancestors were pushed after their descendants; flip this leaf's segment.
+            configuration[mark..].reverse();
+        }
+        configuration
+    }
+
+    /// Returns `true` if `state_id` is part of the definition and has no children.
+    pub fn is_leaf(&self, state_id: StateType) -> bool {
+        self.get_state_node(state_id).is_some()
+            && !self.states.iter().any(|node| node.parent == Some(state_id))
+    }
+
     // Helper to get the parent of a state, if it exists
     pub fn get_parent_of(&self, state_id: StateType) -> Option<StateType> {
         self.get_state_node(state_id)
@@ -187,6 +438,9 @@ pub enum ProcessingError {
     CapacityExceeded,   // For various vector overflows during processing
     ArbitrationFailure, // If arbitration logic fails unexpectedly
     EntryLogicFailure, // If entry logic (execute_entry_actions_from_lca or enter_state_recursive_logic) has issues
+    UnknownState,      // If a caller-supplied state ID is not part of the MachineDefinition
+    ActionFailed,      // If a fallible transition action still failed after its retries
+    NotStarted,        // If an event is sent to a `Runtime::new_deferred` runtime before `start`
 }
 
 impl core::fmt::Display for ProcessingError {
@@ -202,6 +456,15 @@ impl core::fmt::Display for ProcessingError {
             ProcessingError::EntryLogicFailure => {
                 write!(f, "State entry logic failed after transition.")
             }
+            ProcessingError::UnknownState => {
+                write!(f, "State is not part of the machine definition.")
+            }
+            ProcessingError::ActionFailed => {
+                write!(f, "Transition action failed after all retries.")
+            }
+            ProcessingError::NotStarted => {
+                write!(f, "Runtime has not been started.")
+            }
         }
     }
 }
@@ -209,6 +472,31 @@ impl core::fmt::Display for ProcessingError {
 #[cfg(feature = "std")]
 impl std::error::Error for ProcessingError {}
 
+/// Error from converting a `u32` that no state of the machine uses into a generated
+/// state-ID enum (`TryFrom<u32>`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownStateIndex(pub u32);
+
+impl core::fmt::Display for UnknownStateIndex {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "No state has index {}.", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnknownStateIndex {}
+
+/// Dot-separated state paths as written in the chart, for generic code; generated state-ID
+/// enums implement it with their inherent `from_user_path` and `user_path`.
+pub trait UserPath: Copy + Eq + core::fmt::Debug + 'static {
+    /// Returns the state at `user_path` (e.g. `"Parent.Child"`), or `None` if the chart
+    /// has no state there.
+    fn from_user_path(user_path: &str) -> Option<Self>;
+
+    /// Returns the dot-separated path of this state.
+    fn user_path(self) -> &'static str;
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum EntryErrorKind {
     CycleDetected,
@@ -251,6 +539,7 @@ impl std::error::Error for EntryError {
 
 /// Result type for the `send_internal` method.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[must_use = "check whether the event was handled"]
 pub enum SendResult {
     /// A transition was successfully processed.
     Transitioned,
@@ -258,6 +547,208 @@ pub enum SendResult {
     NoMatch,
     /// An error occurred during event processing.
     Error(ProcessingError),
+    /// `Runtime::send_with_budget` ran out of time before the event's cascade of internal
+    /// events finished; the rest stays queued and is processed first by the next send.
+    BudgetExhausted {
+        /// Internal events still waiting to be processed.
+        remaining_internal: usize,
+    },
+    /// The event ran to completion, but the chart's `invariant` rejected the resulting
+    /// context. The step is kept; only checked in debug builds or with `validate-context`.
+    InvariantViolated(&'static str),
+    /// Every transition selected for the event was vetoed by the runtime's
+    /// [`TransitionPolicy`]; nothing changed.
+    PolicyRejected,
+    /// The event was processed, but it entered a state more often than the runtime's
+    /// oscillation guard allows; only returned with the `oscillation-guard` feature.
+    OscillationDetected,
+}
+
+/// Outcome of feeding a slice of events through a machine with `send_batch`.
+///
+/// Each event is still processed with run-to-completion semantics; the summary only
+/// aggregates the individual [`SendResult`]s and records the configuration the batch left
+/// the machine in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchSendSummary<StateType, const N_ACTIVE: usize> {
+    /// Events that caused a transition.
+    pub transitioned: usize,
+    /// Events no transition matched.
+    pub ignored: usize,
+    /// Events whose processing failed; the machine was left as it was before each of them.
+    pub failed: usize,
+    /// The error of the first failed event, if any.
+    pub first_error: Option<ProcessingError>,
+    /// Events after which the chart's invariant was violated; they were still processed.
+    pub invariant_violations: usize,
+    /// The first invariant violation reported, if any.
+    pub first_invariant_violation: Option<&'static str>,
+    /// Events whose transitions were all vetoed by the runtime's [`TransitionPolicy`].
+    pub policy_rejected: usize,
+    /// Events that set off the runtime's oscillation guard; they were still processed.
+    pub oscillations: usize,
+    /// Active leaf states after the last event.
+    pub final_state: heapless::Vec<StateType, N_ACTIVE>,
+}
+
+impl<StateType, const N_ACTIVE: usize> BatchSendSummary<StateType, N_ACTIVE> {
+    /// Counts the result of one event.
+    pub fn record(&mut self, result: SendResult) {
+        match result {
+            SendResult::Transitioned => self.transitioned += 1,
+            SendResult::NoMatch => self.ignored += 1,
+            SendResult::Error(error) => {
+                self.failed += 1;
+                self.first_error.get_or_insert(error);
+            }
+            // Only `send_with_budget` reports this; batches always run each event to
+            // completion.
+            SendResult::BudgetExhausted { .. } => {}
+            SendResult::InvariantViolated(violation) => {
+                self.invariant_violations += 1;
+                self.first_invariant_violation.get_or_insert(violation);
+            }
+            SendResult::PolicyRejected => self.policy_rejected += 1,
+            SendResult::OscillationDetected => self.oscillations += 1,
+        }
+    }
+
+    /// Returns the number of events processed.
+    #[must_use]
+    pub fn processed(&self) -> usize {
+        self.transitioned
+            + self.ignored
+            + self.failed
+            + self.invariant_violations
+            + self.policy_rejected
+            + self.oscillations
+    }
+}
+
+impl<StateType, const N_ACTIVE: usize> Default for BatchSendSummary<StateType, N_ACTIVE> {
+    fn default() -> Self {
+        Self {
+            transitioned: 0,
+            ignored: 0,
+            failed: 0,
+            first_error: None,
+            invariant_violations: 0,
+            first_invariant_violation: None,
+            policy_rejected: 0,
+            oscillations: 0,
+            final_state: heapless::Vec::new(),
+        }
+    }
+}
+
+/// Outcome of a generated machine's `speculate`: what sending an event to a clone of the
+/// machine returned, and the clone as the event left it.
+///
+/// The original machine is untouched; compare it with [`Self::machine`] (e.g. with the
+/// generated `diff`) to see what the event would change, or keep the clone to commit.
+#[derive(Debug, Clone)]
+pub struct SpeculationResult<Machine> {
+    /// What `send` returned on the clone.
+    pub result: SendResult,
+    /// The clone after the event.
+    pub machine: Machine,
+}
+
+impl<Machine> SpeculationResult<Machine> {
+    /// Returns `true` if the event caused a transition.
+    #[must_use]
+    pub fn transitioned(&self) -> bool {
+        self.result == SendResult::Transitioned
+    }
+
+    /// Returns the speculated machine, e.g. to adopt it in place of the original.
+    #[must_use]
+    pub fn into_machine(self) -> Machine {
+        self.machine
+    }
+}
+
+/// Formats dot-separated state paths (as returned by a generated `user_path`) as a list of
+/// `::`-joined paths; used by the `Debug` impl `statechart!` generates.
+#[doc(hidden)]
+pub struct DebugStatePaths<I>(pub I);
+
+impl<I> core::fmt::Debug for DebugStatePaths<I>
+where
+    I: Iterator<Item = &'static str> + Clone,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        struct Path(&'static str);
+
+        impl core::fmt::Debug for Path {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                for (index, segment) in self.0.split('.').enumerate() {
+                    if index > 0 {
+                        f.write_str("::")?;
+                    }
+                    f.write_str(segment)?;
+                }
+                Ok(())
+            }
+        }
+
+        f.debug_list().entries(self.0.clone().map(Path)).finish()
+    }
+}
+
+/// Compile-time check behind a chart's `max_event_size` header: `statechart!` evaluates
+/// `EventSizeBudget::<{ size_of::<Event>() }, BUDGET>::FITS`, so a failing build names the
+/// event's actual size next to the budget.
+#[doc(hidden)]
+pub struct EventSizeBudget<const SIZE: usize, const BUDGET: usize>;
+
+impl<const SIZE: usize, const BUDGET: usize> EventSizeBudget<SIZE, BUDGET> {
+    pub const FITS: () = assert!(
+        SIZE <= BUDGET,
+        "the event type is larger than the chart's `max_event_size` budget (SIZE in the failing `EventSizeBudget::<SIZE, BUDGET>` is its size in bytes); box or shrink the largest variants, or build with `LIT_BIT_SIZE_HINTS=1` for a test that prints the size of each"
+    );
+}
+
+/// States entered and exited between two active configurations.
+///
+/// Built by comparing configuration snapshots (see [`Runtime::diff`]), so a state that was
+/// exited and re-entered in between — e.g. by a self-transition — appears in neither list.
+/// `N` bounds both lists; a chart never has more than its number of states active.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDiff<StateType, const N: usize> {
+    /// States active in the newer configuration only, in entry order (parents first).
+    pub entered: heapless::Vec<StateType, N>,
+    /// States active in the older configuration only, in exit order (children first).
+    pub exited: heapless::Vec<StateType, N>,
+}
+
+impl<StateType: Copy + PartialEq, const N: usize> ConfigDiff<StateType, N> {
+    /// Compares two configurations given root-most first, as returned by
+    /// `active_configuration`. States beyond the capacity `N` are dropped.
+    #[must_use]
+    pub fn between(before: &[StateType], after: &[StateType]) -> Self {
+        let mut diff = Self {
+            entered: heapless::Vec::new(),
+            exited: heapless::Vec::new(),
+        };
+        for &state in before.iter().rev() {
+            if !after.contains(&state) {
+                let _ = diff.exited.push(state);
+            }
+        }
+        for &state in after {
+            if !before.contains(&state) {
+                let _ = diff.entered.push(state);
+            }
+        }
+        diff
+    }
+
+    /// Returns `true` if both configurations contain the same states.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entered.is_empty() && self.exited.is_empty()
+    }
 }
 
 /// Runtime instance of a state machine.
@@ -271,6 +762,22 @@ pub enum SendResult {
 /// - `MAX_NODES_FOR_COMPUTATION`: Const generic for buffer sizes needed for computations involving
 ///   multiple hierarchy branches, typically `M * N_ACTIVE`. This is used for temporary
 ///   collections during transition processing (e.g. exit sets).
+///
+/// Cloning a runtime duplicates its whole state: the active configuration, the context,
+/// queued internal and deferred events, armed timers and every setting. Only the coverage
+/// recorder and pending [`Runtime::completed`] futures are left behind, so sends on a clone
+/// (e.g. a speculative one) are not counted and do not resolve them.
+///
+/// # Sending from inside an action
+///
+/// A send cannot start while another one on the same runtime is running: `send` takes
+/// `&mut self` for the whole step, and actions only get the context. A context that
+/// reaches its own machine through a shared handle (`Rc<RefCell<_>>`, a mutex) finds it
+/// already borrowed or locked. An action that wants its machine to see another event
+/// either raises it (see [`Runtime::set_raised_event_source`]), to have it processed right
+/// after the current step and before the next external event, or, when the machine runs
+/// as an actor, `try_send`s it to the actor's own `Address`, which queues it in the mailbox
+/// behind the messages already there.
 #[derive(Debug)]
 pub struct Runtime<
     StateType,
@@ -287,6 +794,69 @@ pub struct Runtime<
     machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
     pub active_leaf_states: heapless::Vec<StateType, N_ACTIVE>,
     context: ContextType,
+    #[cfg(feature = "coverage")]
+    coverage: Option<&'static crate::coverage::CoverageRecorder>,
+    #[cfg(feature = "async")]
+    timers: crate::timer::ArmedTimers<StateType, MAX_NODES_FOR_COMPUTATION>,
+    raised_events: Option<RaisedEventFn<ContextType, EventType>>,
+    internal_events: heapless::Deque<EventType, MAX_INTERNAL_EVENTS>,
+    deferred_events: heapless::Deque<EventType, MAX_INTERNAL_EVENTS>,
+    retry: RetrySettings<StateType>,
+    panic_on_invariant_violation: bool,
+    event_filter: EventFilterFn<EventType>,
+    policy: Option<&'static dyn TransitionPolicy<StateType, EventType>>,
+    /// Verdicts for the async guards of the next step; taken by that step.
+    async_verdicts: AsyncGuardVerdicts,
+    /// Set by [`Runtime::unstarted`] until the initial state has been entered.
+    needs_initial_entry: bool,
+    /// Set by [`Runtime::new_deferred`]: sends fail until [`Runtime::start`] succeeds.
+    deferred_start: bool,
+    /// States entered by the most recent send, in entry order.
+    entered_states: heapless::Vec<StateType, MAX_NODES_FOR_COMPUTATION>,
+    /// The leaf each history state (see [`MachineDefinition::with_history_states`]) was last
+    /// left from, as `(history_state, leaf)`.
+    history: heapless::Vec<(StateType, StateType), M>,
+    /// Resolves the futures handed out by [`Runtime::completed`]; `None` while none wait.
+    #[cfg(feature = "std")]
+    completion: Option<std::sync::Arc<completion::CompletionSignal>>,
+    #[cfg(feature = "oscillation-guard")]
+    oscillation: oscillation::OscillationTracker<StateType, MAX_NODES_FOR_COMPUTATION>,
+}
+
+impl<StateType, EventType, ContextType, const M: usize, const N: usize, const MN: usize> Clone
+    for Runtime<StateType, EventType, ContextType, M, N, MN>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    ContextType: Clone + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            machine_def: self.machine_def,
+            active_leaf_states: self.active_leaf_states.clone(),
+            context: self.context.clone(),
+            #[cfg(feature = "coverage")]
+            coverage: None,
+            #[cfg(feature = "async")]
+            timers: self.timers.clone(),
+            raised_events: self.raised_events,
+            internal_events: self.internal_events.clone(),
+            deferred_events: self.deferred_events.clone(),
+            retry: self.retry,
+            panic_on_invariant_violation: self.panic_on_invariant_violation,
+            event_filter: self.event_filter,
+            policy: self.policy,
+            async_verdicts: self.async_verdicts.clone(),
+            needs_initial_entry: self.needs_initial_entry,
+            deferred_start: self.deferred_start,
+            entered_states: self.entered_states.clone(),
+            history: self.history.clone(),
+            #[cfg(feature = "std")]
+            completion: None,
+            #[cfg(feature = "oscillation-guard")]
+            oscillation: self.oscillation.clone(),
+        }
+    }
 }
 
 // --- ⛳ 1. Helper scratch struct (place just after Runtime<T> definition) ---------
@@ -297,6 +867,21 @@ where
     /// Tracks which states *already* had their entry action executed during the
     /// current `send_internal` cycle.  This is cleared at the end of the call.
     entry_actions_run: &'a mut heapless::Vec<StateType, M>,
+    /// The runtime's history records, consulted when a history state is entered.
+    history: &'a [(StateType, StateType)],
+}
+
+impl<StateType, const M: usize> Scratch<'_, StateType, M>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+{
+    /// The leaf `state` was last left from, if it is a history state that has been exited.
+    fn recorded_leaf(&self, state: StateType) -> Option<StateType> {
+        self.history
+            .iter()
+            .find(|&&(history_state, _)| history_state == state)
+            .map(|&(_, leaf)| leaf)
+    }
 }
 
 // Helper function, can be outside impl Runtime or a static method if preferred.
@@ -335,8 +920,8 @@ where
     }
     let result: Result<(), EntryError> = (|| {
         let Some(node) = machine_def.get_state_node(state_id_to_enter) else {
-            assert!(
-                !cfg!(debug_assertions),
+            debug_assert!(
+                false,
                 "State ID ({state_id_to_enter:?}) not found in MachineDefinition."
             );
             return Err(EntryError {
@@ -370,6 +955,18 @@ where
                                 })?;
                         }
                     }
+                    if let Some(leaf) = scratch.recorded_leaf(s_node_in_def.id) {
+                        enter_history_path::<_, _, _, M, N_ACTIVE>(
+                            machine_def,
+                            context,
+                            s_node_in_def.id,
+                            leaf,
+                            accumulator,
+                            scratch,
+                            event,
+                        )?;
+                        continue;
+                    }
                     let initial_child_of_region = s_node_in_def.initial_child.ok_or_else(|| {
                         debug_assert!(
                             false,
@@ -391,6 +988,19 @@ where
                     )?;
                 }
             }
+        } else if let Some(leaf) = node
+            .initial_child
+            .and_then(|_| scratch.recorded_leaf(state_id_to_enter))
+        {
+            enter_history_path::<_, _, _, M, N_ACTIVE>(
+                machine_def,
+                context,
+                state_id_to_enter,
+                leaf,
+                accumulator,
+                scratch,
+                event,
+            )?;
         } else if let Some(initial_child_id) = node.initial_child {
             enter_state_recursive_logic::<_, _, _, M, N_ACTIVE>(
                 machine_def,
@@ -415,6 +1025,121 @@ where
     result
 }
 
+/// Enters the states below `history_state` down to `leaf`, the leaf it was last left from,
+/// outermost first. A history state holds no parallel state, so this is a single branch.
+fn enter_history_path<StateType, EventType, ContextType, const M: usize, const N_ACTIVE: usize>(
+    machine_def: &MachineDefinition<StateType, EventType, ContextType>,
+    context: &mut ContextType,
+    history_state: StateType,
+    leaf: StateType,
+    accumulator: &mut heapless::Vec<StateType, N_ACTIVE>,
+    scratch: &mut Scratch<'_, StateType, M>,
+    event: &EventType,
+) -> Result<(), EntryError>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    ContextType: Clone + 'static,
+{
+    let capacity_exceeded = || EntryError {
+        kind: EntryErrorKind::CapacityExceeded,
+    };
+    let mut path: heapless::Vec<StateType, M> = heapless::Vec::new();
+    let mut current = Some(leaf);
+    while let Some(state) = current {
+        if state == history_state {
+            break;
+        }
+        path.push(state).map_err(|_| capacity_exceeded())?;
+        current = machine_def.get_parent_of(state);
+    }
+    if current.is_none() {
+        debug_assert!(
+            false,
+            "Recorded leaf {leaf:?} is not below history state {history_state:?}"
+        );
+        return Err(EntryError {
+            kind: EntryErrorKind::StateNotFound,
+        });
+    }
+    for &state in path.iter().rev() {
+        if scratch.entry_actions_run.contains(&state) {
+            continue;
+        }
+        if let Some(entry_fn) = machine_def
+            .get_state_node(state)
+            .and_then(|node| node.entry_action)
+        {
+            entry_fn(context, event);
+        }
+        scratch
+            .entry_actions_run
+            .push(state)
+            .map_err(|_| capacity_exceeded())?;
+    }
+    if !accumulator.contains(&leaf) {
+        accumulator.push(leaf).map_err(|_| capacity_exceeded())?;
+    }
+    Ok(())
+}
+
+/// Collects the leaves entering `state` activates, following initial children and every
+/// region of a parallel state the way `enter_state_recursive_logic` does, without running
+/// any entry action.
+fn collect_initial_leaves<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+>(
+    machine_def: &MachineDefinition<StateType, EventType, ContextType>,
+    state: StateType,
+    depth: usize,
+    leaves: &mut heapless::Vec<StateType, N_ACTIVE>,
+) -> Result<(), ProcessingError>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+    ContextType: Clone + 'static,
+{
+    if depth > M {
+        return Err(ProcessingError::EntryLogicFailure);
+    }
+    let node = machine_def
+        .get_state_node(state)
+        .ok_or(ProcessingError::UnknownState)?;
+    if node.is_parallel {
+        for region in machine_def
+            .states
+            .iter()
+            .filter(|s| s.parent == Some(state))
+        {
+            let initial_child = region
+                .initial_child
+                .ok_or(ProcessingError::EntryLogicFailure)?;
+            collect_initial_leaves::<_, _, _, M, N_ACTIVE>(
+                machine_def,
+                initial_child,
+                depth + 1,
+                leaves,
+            )?;
+        }
+    } else if let Some(initial_child) = node.initial_child {
+        collect_initial_leaves::<_, _, _, M, N_ACTIVE>(
+            machine_def,
+            initial_child,
+            depth + 1,
+            leaves,
+        )?;
+    } else if !leaves.contains(&state) {
+        leaves
+            .push(state)
+            .map_err(|_| ProcessingError::CapacityExceeded)?;
+    }
+    Ok(())
+}
+
 // Define PotentialTransition struct at the module level
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct PotentialTransition<StateType, EventType, ContextType>
@@ -459,22 +1184,213 @@ where
         initial_context: ContextType,
         initial_event: &EventType,
     ) -> Result<Self, ProcessingError> {
-        let mut mutable_context = initial_context;
+        Self::with_initial_state(
+            machine_def,
+            initial_context,
+            machine_def.initial_leaf_state,
+            initial_event,
+        )
+    }
+
+    /// Creates a new runtime that starts in `initial_state` instead of the definition's
+    /// declared initial state.
+    ///
+    /// The state is entered exactly as the declared initial state would be: its entry action
+    /// runs and, for compound or parallel states, the initial children are resolved down to
+    /// the leaves. Callers are expected to pass a top-level state; see
+    /// `config::MachineConfig` for the validated, config-driven entry point.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `initial_state` is not part of the
+    /// machine definition, otherwise the same errors as [`Runtime::new`].
+    pub fn with_initial_state(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        initial_context: ContextType,
+        initial_state: StateType,
+        initial_event: &EventType,
+    ) -> Result<Self, ProcessingError> {
+        if machine_def.get_state_node(initial_state).is_none() {
+            return Err(ProcessingError::UnknownState);
+        }
+
+        let mut runtime = Self::unstarted(machine_def, initial_context);
+        runtime.enter_initial_state(initial_state, initial_event)?;
+        Ok(runtime)
+    }
+
+    /// Rebuilds a runtime whose active leaves are `leaves`, without running any entry
+    /// action, for example to resume from a persisted configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `leaves` is empty or names a state that
+    /// is not a leaf of the machine definition, and [`ProcessingError::CapacityExceeded`] if
+    /// it holds more than `N_ACTIVE` states.
+    pub fn restore(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        context: ContextType,
+        leaves: &[StateType],
+    ) -> Result<Self, ProcessingError> {
+        if leaves.is_empty() || !leaves.iter().all(|&leaf| machine_def.is_leaf(leaf)) {
+            return Err(ProcessingError::UnknownState);
+        }
+        let mut runtime = Self::unstarted(machine_def, context);
+        runtime.active_leaf_states =
+            heapless::Vec::from_slice(leaves).map_err(|()| ProcessingError::CapacityExceeded)?;
+        runtime.needs_initial_entry = false;
+        Ok(runtime)
+    }
+
+    /// Creates a new runtime, like [`Runtime::new`], that only handles events for which
+    /// `event_filter` returns `true`.
+    ///
+    /// Every other event, external or raised, is reported as [`SendResult::NoMatch`]
+    /// without scanning any transition. The initial event is not filtered.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Runtime::new`].
+    pub fn with_event_filter(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        initial_context: ContextType,
+        initial_event: &EventType,
+        event_filter: EventFilterFn<EventType>,
+    ) -> Result<Self, ProcessingError> {
+        let mut runtime = Self::unstarted(machine_def, initial_context);
+        runtime.event_filter = event_filter;
+        runtime.enter_initial_state(machine_def.initial_leaf_state, initial_event)?;
+        Ok(runtime)
+    }
+
+    /// Creates a runtime in its initial configuration without running any entry action,
+    /// for charts whose entry actions need resources acquired after construction.
+    ///
+    /// [`Runtime::state`] already reports the initial leaves, but the runtime is not
+    /// started: [`Runtime::start`] runs the entry actions, and every event sent before it
+    /// fails with [`ProcessingError::NotStarted`] instead of starting the runtime the way
+    /// a first send starts one built with [`Runtime::unstarted`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::CapacityExceeded`] if the initial configuration holds
+    /// more than `N_ACTIVE` leaves, and [`ProcessingError::EntryLogicFailure`] if the
+    /// definition's initial children do not lead to a leaf within `M` levels.
+    pub fn new_deferred(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        initial_context: ContextType,
+    ) -> Result<Self, ProcessingError> {
+        let mut runtime = Self::unstarted(machine_def, initial_context);
+        collect_initial_leaves::<_, _, _, M, N_ACTIVE>(
+            machine_def,
+            machine_def.initial_leaf_state,
+            0,
+            &mut runtime.active_leaf_states,
+        )?;
+        runtime.deferred_start = true;
+        Ok(runtime)
+    }
+
+    /// Creates a runtime that has not entered its initial state yet.
+    ///
+    /// Nothing runs here, so a runtime (and a generated machine's `const_new`) can be built
+    /// in a `const` context such as a `static` initializer. The configuration stays empty
+    /// until [`Runtime::start`] enters the initial state; if `start` is never called, the
+    /// first event sent is used as the initial event instead.
+    #[must_use]
+    pub const fn unstarted(
+        machine_def: &'static MachineDefinition<StateType, EventType, ContextType>,
+        initial_context: ContextType,
+    ) -> Self {
+        Runtime {
+            machine_def,
+            active_leaf_states: heapless::Vec::new(),
+            context: initial_context,
+            #[cfg(feature = "coverage")]
+            coverage: None,
+            #[cfg(feature = "async")]
+            timers: crate::timer::ArmedTimers::new(),
+            raised_events: None,
+            internal_events: heapless::Deque::new(),
+            deferred_events: heapless::Deque::new(),
+            retry: RetrySettings::new(),
+            panic_on_invariant_violation: false,
+            event_filter: accept_all_events,
+            policy: None,
+            async_verdicts: AsyncGuardVerdicts::new(),
+            needs_initial_entry: true,
+            deferred_start: false,
+            entered_states: heapless::Vec::new(),
+            history: heapless::Vec::new(),
+            #[cfg(feature = "std")]
+            completion: None,
+            #[cfg(feature = "oscillation-guard")]
+            oscillation: oscillation::OscillationTracker::new(),
+        }
+    }
+
+    /// Enters the initial state of a runtime built with [`Runtime::unstarted`] or
+    /// [`Runtime::new_deferred`], running its entry actions with `initial_event`. Does
+    /// nothing once the runtime has started.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Runtime::new`]; the runtime then stays unstarted.
+    pub fn start(&mut self, initial_event: &EventType) -> Result<(), ProcessingError> {
+        if !self.needs_initial_entry {
+            return Ok(());
+        }
+        self.enter_initial_state(self.machine_def.initial_leaf_state, initial_event)?;
+        self.deferred_start = false;
+        Ok(())
+    }
+
+    /// Returns `false` for a runtime built with [`Runtime::unstarted`] or
+    /// [`Runtime::new_deferred`] until its initial state has been entered.
+    #[must_use]
+    pub const fn is_started(&self) -> bool {
+        !self.needs_initial_entry
+    }
+
+    fn enter_initial_state(
+        &mut self,
+        initial_state: StateType,
+        initial_event: &EventType,
+    ) -> Result<(), ProcessingError> {
         let mut active_states_vec = heapless::Vec::new();
         let mut visited_for_initial_entry: heapless::Vec<StateType, M> = heapless::Vec::new();
         let mut entry_actions_run_vec: heapless::Vec<StateType, M> = heapless::Vec::new();
 
-        let top_level_initial_state_id = machine_def.initial_leaf_state;
+        if let Some(entry_fn) = self.machine_def.entry_action {
+            entry_fn(&mut self.context, initial_event);
+        }
+
+        // The initial state's ancestors are entered first, outermost first.
+        let ancestors: heapless::Vec<StateType, M> =
+            self.machine_def.active_configuration(&[initial_state]);
+        for &ancestor in ancestors.iter().filter(|&&state| state != initial_state) {
+            if let Some(entry_fn) = self
+                .machine_def
+                .get_state_node(ancestor)
+                .and_then(|node| node.entry_action)
+            {
+                entry_fn(&mut self.context, initial_event);
+            }
+            entry_actions_run_vec
+                .push(ancestor)
+                .map_err(|_| ProcessingError::CapacityExceeded)?;
+        }
 
         // Pass M explicitly if needed, or let it be inferred from the type of visited_for_initial_entry
         enter_state_recursive_logic::<_, _, _, M, N_ACTIVE>(
-            machine_def,
-            &mut mutable_context,
-            top_level_initial_state_id,
+            self.machine_def,
+            &mut self.context,
+            initial_state,
             &mut active_states_vec,
             &mut visited_for_initial_entry,
             &mut Scratch::<StateType, M> {
                 entry_actions_run: &mut entry_actions_run_vec,
+                history: &self.history,
             },
             initial_event,
         )
@@ -489,23 +1405,261 @@ where
             return Err(ProcessingError::EntryLogicFailure);
         }
 
-        Ok(Runtime {
-            machine_def, // Assign the reference
-            active_leaf_states: active_states_vec,
-            context: mutable_context,
-        })
+        self.active_leaf_states = active_states_vec;
+        self.needs_initial_entry = false;
+        self.set_entered_states(&entry_actions_run_vec);
+        Ok(())
     }
 
+    /// Records, for every history state among `states_exited` or below one of them, the leaf
+    /// of `previous_leaves` it was left from.
+    fn record_history(&mut self, previous_leaves: &[StateType], states_exited: &[StateType]) {
+        if self.machine_def.history_states.is_empty() {
+            return;
+        }
+        for &leaf in previous_leaves {
+            let mut current = self.machine_def.get_parent_of(leaf);
+            while let Some(state) = current {
+                let exited = states_exited
+                    .iter()
+                    .any(|&exited| self.is_descendant_or_self(state, exited).unwrap_or(false));
+                if !exited {
+                    break;
+                }
+                if self.machine_def.history_states.contains(&state) {
+                    if let Some(record) = self.history.iter_mut().find(|(h, _)| *h == state) {
+                        record.1 = leaf;
+                    } else if self.history.push((state, leaf)).is_err() {
+                        debug_assert!(false, "More history states than states: {state:?}");
+                    }
+                }
+                current = self.machine_def.get_parent_of(state);
+            }
+        }
+    }
+
+    /// Replaces the states reported by [`Runtime::entered_states`].
+    fn set_entered_states(&mut self, entered: &[StateType]) {
+        self.entered_states.clear();
+        self.record_entered_states(entered);
+    }
+
+    /// Appends to the states reported by [`Runtime::entered_states`], dropping any beyond
+    /// its capacity.
+    fn record_entered_states(&mut self, entered: &[StateType]) {
+        for &state in entered {
+            if self.entered_states.push(state).is_err() {
+                break;
+            }
+        }
+        #[cfg(feature = "async")]
+        self.timers
+            .arm_entered(self.machine_def.after_timers, entered);
+    }
+
+    #[inline]
     pub fn state(&self) -> heapless::Vec<StateType, N_ACTIVE> {
         self.active_leaf_states.clone()
     }
+
+    /// Returns the first active leaf — the only one in a chart without parallel states —
+    /// like `FlatRuntime::active_state`. Before the runtime starts, this is the initial
+    /// leaf it will enter.
+    #[must_use]
+    pub fn active_state(&self) -> StateType {
+        self.active_leaf_states
+            .first()
+            .copied()
+            .unwrap_or(self.machine_def.initial_leaf_state)
+    }
+
+    /// Returns every active state — the active leaves and all of their ancestors —
+    /// root-most first. States beyond the capacity `N` are dropped.
+    pub fn active_configuration<const N: usize>(&self) -> heapless::Vec<StateType, N> {
+        self.machine_def
+            .active_configuration(&self.active_leaf_states)
+    }
+
+    /// Returns the states entered and exited when moving from this runtime's configuration
+    /// to `other`'s, e.g. a snapshot taken before a `send` compared with the runtime after.
+    pub fn diff<const N: usize>(&self, other: &Self) -> ConfigDiff<StateType, N> {
+        let before: heapless::Vec<StateType, N> = self.active_configuration();
+        let after: heapless::Vec<StateType, N> = other.active_configuration();
+        ConfigDiff::between(&before, &after)
+    }
+
+    /// Returns the states entered by the most recent send, in entry order: parents before
+    /// their children, and each region of a parallel state in turn.
+    ///
+    /// Unlike [`Runtime::diff`], this lists exactly the states whose entry ran, so a state
+    /// exited and re-entered by a self-transition is included. Events raised while handling
+    /// the send add their entries too. The list is empty if the send did not transition and
+    /// is kept until the next send; after construction it holds the initial configuration,
+    /// and after [`Runtime::force_transition`] the states it entered. States beyond
+    /// `MAX_NODES_FOR_COMPUTATION` are dropped.
+    #[must_use]
+    pub fn entered_states(&self) -> &[StateType] {
+        &self.entered_states
+    }
+
+    /// Attaches a coverage recorder that is updated on every state entry and transition.
+    ///
+    /// The states that are active when the recorder is attached (including their
+    /// ancestors) are recorded as entered, so attaching right after construction also
+    /// covers the initial configuration.
+    #[cfg(feature = "coverage")]
+    pub fn set_coverage_recorder(&mut self, recorder: &'static crate::coverage::CoverageRecorder) {
+        self.coverage = Some(recorder);
+        for &leaf in &self.active_leaf_states {
+            let mut current = Some(leaf);
+            while let Some(state) = current {
+                self.record_state_coverage(state);
+                current = self.machine_def.get_parent_of(state);
+            }
+        }
+    }
+
+    #[cfg(feature = "coverage")]
+    fn record_state_coverage(&self, state: StateType) {
+        if let Some(recorder) = self.coverage
+            && let Some(index) = self.machine_def.states.iter().position(|n| n.id == state)
+        {
+            recorder.record_state(index);
+        }
+    }
+
+    #[cfg(feature = "coverage")]
+    fn record_transition_coverage(
+        &self,
+        transition: &'static Transition<StateType, EventType, ContextType>,
+    ) {
+        if let Some(recorder) = self.coverage
+            && let Some(ordinal) = self
+                .machine_def
+                .transitions
+                .iter()
+                .position(|t| core::ptr::eq(t, transition))
+        {
+            recorder.record_transition(ordinal);
+        }
+    }
+
+    /// Stores `token` as the armed timer for the `after` transition `timer_id` of `state`.
+    ///
+    /// Re-arming the same `(state, timer_id)` replaces (and thereby cancels) the previous
+    /// token. The token is dropped automatically when `state` is exited, so an expiry that
+    /// arrives afterwards is reported as stale by [`Runtime::timer_fired`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `state` is not currently active, and
+    /// [`ProcessingError::CapacityExceeded`] if the timer table is full.
+    #[cfg(feature = "async")]
+    pub fn arm_timer(
+        &mut self,
+        state: StateType,
+        timer_id: usize,
+        token: crate::timer::TimerToken,
+    ) -> Result<(), ProcessingError> {
+        if !self.is_state_active(state) {
+            return Err(ProcessingError::UnknownState);
+        }
+        self.timers
+            .track(state, timer_id, token)
+            .map(|_superseded| ())
+            .map_err(|_| ProcessingError::CapacityExceeded)
+    }
+
+    /// Cancels the timer identified by `token`. Returns `true` only if it was still armed;
+    /// cancelling twice, or after the timer fired, is a no-op.
+    #[cfg(feature = "async")]
+    pub fn cancel_timer(&mut self, token: crate::timer::TimerToken) -> bool {
+        self.timers.cancel(token)
+    }
+
+    /// Reports the expiry of `token`.
+    ///
+    /// Returns the owning state and `timer_id` if the timer is still armed, so the caller
+    /// can deliver the corresponding `TimerFired` event. Returns `None` for tokens that
+    /// were cancelled, already fired, or belong to a state that has since been exited;
+    /// such expiries must be ignored.
+    #[cfg(feature = "async")]
+    pub fn timer_fired(&mut self, token: crate::timer::TimerToken) -> Option<(StateType, usize)> {
+        self.timers
+            .fire(token)
+            .map(|armed| (armed.state, armed.timer_id))
+    }
+
+    /// Sets the clock armed timers are timed with, one tick of which lasts `tick`, so
+    /// [`Runtime::armed_timers`] can tell how long each has left. Only timers armed
+    /// afterwards are timed.
+    #[cfg(feature = "async")]
+    pub fn set_timer_clock(
+        &mut self,
+        clock: &'static (dyn MonotonicClock + Sync),
+        tick: core::time::Duration,
+    ) {
+        self.timers.set_clock(clock, tick);
+    }
+
+    /// Lists the timers armed for active states with the time each has left: zero once
+    /// overdue, and the full duration for timers armed before a clock was set with
+    /// [`Runtime::set_timer_clock`].
+    ///
+    /// This is a snapshot for debugging: the next send, expiry or re-arming can change it.
+    #[cfg(feature = "async")]
+    pub fn armed_timers(&self) -> impl Iterator<Item = (StateType, core::time::Duration)> + '_ {
+        self.timers
+            .iter()
+            .map(|timer| (timer.state, self.timers.remaining(timer)))
+    }
+
+    /// Returns the tokens the most recent send armed for the `after` timers of the states
+    /// it entered (see [`MachineDefinition::with_after_timers`]). A driver sleeps for each
+    /// token's duration and then reports it with [`Runtime::timer_fired`].
+    #[cfg(feature = "async")]
+    pub fn entered_timers(&self) -> impl Iterator<Item = crate::timer::TimerToken> + '_ {
+        self.timers
+            .iter()
+            .filter(|timer| self.entered_states.contains(&timer.state))
+            .map(|timer| timer.token)
+    }
+
+    /// Returns `true` if `state` is an active leaf or an ancestor of one.
+    fn is_state_active(&self, state: StateType) -> bool {
+        self.active_leaf_states
+            .iter()
+            .any(|&leaf| self.is_descendant_or_self(leaf, state).unwrap_or(false))
+    }
+
+    #[cfg(feature = "async")]
+    fn cancel_exited_timers(&mut self, exited: &[StateType]) {
+        for &state in exited {
+            self.timers.cancel_state(
+                state,
+                <crate::timer::Timer as crate::timer::TimerService>::cancel,
+            );
+        }
+    }
+
+    #[inline]
     pub fn context(&self) -> &ContextType {
         &self.context
     }
+    #[inline]
     pub fn context_mut(&mut self) -> &mut ContextType {
         &mut self.context
     }
 
+    /// Swaps in `context` and returns the previous one, without running any action.
+    ///
+    /// The swap bypasses the chart's invariant and any other check: keeping the new context
+    /// consistent with the active states is the caller's responsibility.
+    #[inline]
+    pub fn set_context(&mut self, context: ContextType) -> ContextType {
+        core::mem::replace(&mut self.context, context)
+    }
+
     // --- Helper methods for hierarchical transitions ---
 
     /// Collects the path from a leaf state up to the root, including the leaf itself.
@@ -693,15 +1847,9 @@ where
                 // Check if region is active (has a descendant in active_leaf_states)
                 let mut region_active_leaf: Option<StateType> = None;
                 for &leaf in &self.active_leaf_states {
-                    match self.is_descendant_or_self(leaf, region_node.id) {
-                        // Handle Result
-                        Ok(is_desc) => {
-                            if is_desc {
-                                region_active_leaf = Some(leaf);
-                                break;
-                            }
-                        }
-                        Err(e) => return Err(e), // Propagate ProcessingError
+                    if self.is_descendant_or_self(leaf, region_node.id)? {
+                        region_active_leaf = Some(leaf);
+                        break;
                     }
                 }
                 if let Some(active_leaf_in_region) = region_active_leaf {
@@ -785,12 +1933,17 @@ where
         None
     }
 
-    /// Collects potential transitions for the given event from all active leaf states
+    /// Collects potential transitions for the given event from all active leaf states.
+    ///
+    /// Async guards are decided by `verdicts`; the first one without a verdict is noted in
+    /// `pending_async_guard` and rejects its transition.
     #[allow(dead_code)]
     fn collect_potential_transitions(
         &self,
         event: &EventType,
         current_active_leaves_snapshot: &heapless::Vec<StateType, N_ACTIVE>,
+        verdicts: &AsyncGuardVerdicts,
+        pending_async_guard: &mut Option<PendingAsyncGuard>,
     ) -> Result<
         heapless::Vec<
             PotentialTransition<StateType, EventType, ContextType>,
@@ -807,7 +1960,7 @@ where
             let mut check_state_id_opt = Some(active_leaf_id);
             'hierarchy_search: while let Some(check_state_id) = check_state_id_opt {
                 if self.machine_def.get_state_node(check_state_id).is_some() {
-                    for t_def in self.machine_def.transitions {
+                    for (t_index, t_def) in self.machine_def.transitions.iter().enumerate() {
                         if t_def.from_state == check_state_id {
                             // Check if event matches using match_fn if available
                             #[allow(clippy::collapsible_if)]
@@ -816,10 +1969,22 @@ where
                                     continue; // Skip this transition if event doesn't match
                                 }
                             }
-                            // Now check the guard if any
+                            // Now check the guard if any
+                            #[allow(clippy::collapsible_if)]
+                            if let Some(guard_fn) = t_def.guard {
+                                if !guard_fn(&self.context, event) {
+                                    trace!(
+                                        "[GUARD FAILED] From {:?} on {:?} → {:?}",
+                                        t_def.from_state, event, t_def.to_state
+                                    );
+                                    continue;
+                                }
+                            }
                             #[allow(clippy::collapsible_if)]
-                            if let Some(guard_fn) = t_def.guard {
-                                if !guard_fn(&self.context, event) {
+                            if let Some(state_guard) = t_def.state_guard {
+                                if !state_guard(&self.context, event, &|state| {
+                                    self.is_state_active(state)
+                                }) {
                                     trace!(
                                         "[GUARD FAILED] From {:?} on {:?} → {:?}",
                                         t_def.from_state, event, t_def.to_state
@@ -827,6 +1992,15 @@ where
                                     continue;
                                 }
                             }
+                            if let Some(slot) = t_def.async_guard
+                                && !verdicts.allows(t_index, slot, pending_async_guard)
+                            {
+                                trace!(
+                                    "[ASYNC GUARD FAILED] From {:?} on {:?} → {:?}",
+                                    t_def.from_state, event, t_def.to_state
+                                );
+                                continue;
+                            }
                             trace!(
                                 "[MATCH] From {:?} on {:?} → {:?}",
                                 t_def.from_state, event, t_def.to_state
@@ -880,13 +2054,7 @@ where
                 }
             }
         }
-        if let Some(action_fn) = trans_info.transition_ref.action {
-            trace!(
-                "[ACTION] Running action for {:?} → {:?} on {:?}",
-                source_state_id, trans_info.target_state_id, event
-            );
-            action_fn(temp_context, event);
-        }
+        self.run_transition_action(trans_info, event, temp_context)?;
         if entry_execution_list
             .push((
                 source_state_id,
@@ -900,6 +2068,36 @@ where
         Ok(())
     }
 
+    /// Runs a transition's action, then its fallible action with retries.
+    fn run_transition_action(
+        &self,
+        trans_info: &PotentialTransition<StateType, EventType, ContextType>,
+        event: &EventType,
+        temp_context: &mut ContextType,
+    ) -> Result<(), ProcessingError> {
+        let transition = trans_info.transition_ref;
+        if let Some(action_fn) = transition.action {
+            trace!(
+                "[ACTION] Running action for {:?} → {:?} on {:?}",
+                trans_info.transition_from_state_id, trans_info.target_state_id, event
+            );
+            action_fn(temp_context, event);
+        }
+        if let Some(read_fn) = transition.read_action {
+            read_fn(temp_context, event);
+        }
+        if let Some(fallible) = &transition.fallible_action {
+            self.retry.run(
+                fallible,
+                trans_info.transition_from_state_id,
+                trans_info.target_state_id,
+                temp_context,
+                event,
+            )?;
+        }
+        Ok(())
+    }
+
     /// Processes a self-transition (not simple leaf - handles hierarchical exits/re-entry).
     pub(crate) fn process_self_transition(
         &self,
@@ -945,13 +2143,7 @@ where
                 }
             }
         }
-        if let Some(action_fn) = trans_info.transition_ref.action {
-            trace!(
-                "[ACTION] Running action for {:?} → {:?} on {:?}",
-                source_state_id, trans_info.target_state_id, event
-            );
-            action_fn(temp_context, event);
-        }
+        self.run_transition_action(trans_info, event, temp_context)?;
         if entry_execution_list
             .push((
                 source_state_id,
@@ -981,7 +2173,12 @@ where
         let target_state_id = trans_info.target_state_id;
         let active_leaf_for_this_trans = trans_info.source_leaf_id;
 
-        let lca_id = self.find_lca(active_leaf_for_this_trans, target_state_id)?;
+        // A target that is the active leaf or one of its ancestors is exited and re-entered,
+        // so the transition is scoped strictly above it.
+        let mut lca_id = self.find_lca(active_leaf_for_this_trans, target_state_id)?;
+        if lca_id == Some(target_state_id) {
+            lca_id = self.machine_def.get_parent_of(target_state_id);
+        }
         let states_to_exit_for_branch =
             self.compute_ordered_exit_set(active_leaf_for_this_trans, lca_id)?;
 
@@ -1016,13 +2213,7 @@ where
             }
         }
 
-        if let Some(action_fn) = trans_info.transition_ref.action {
-            trace!(
-                "[ACTION] Running action for {:?} → {:?} on {:?}",
-                source_state_id, target_state_id, event
-            );
-            action_fn(temp_context, event);
-        }
+        self.run_transition_action(trans_info, event, temp_context)?;
 
         let lca_for_entry = lca_id; // always use real LCA so ancestors stay suppressed
         if entry_execution_list
@@ -1149,24 +2340,21 @@ where
                 lca_id,
                 self.get_ancestry(target_id)
             );
-            match self.execute_entry_actions_from_lca_with_context(
+            let leaves_from_entry = self.execute_entry_actions_from_lca_with_context(
                 target_id,
                 lca_id,
                 source_id, // Use source_id from the tuple
                 event,
                 &mut Scratch::<StateType, M> {
                     entry_actions_run: entry_actions_run_vec,
+                    history: &self.history,
                 },
                 temp_context,
-            ) {
-                Ok(leaves_from_entry) => {
-                    for new_leaf in leaves_from_entry {
-                        if new_leaves.push(new_leaf).is_err() {
-                            return Err(ProcessingError::CapacityExceeded);
-                        }
-                    }
+            )?;
+            for new_leaf in leaves_from_entry {
+                if new_leaves.push(new_leaf).is_err() {
+                    return Err(ProcessingError::CapacityExceeded);
                 }
-                Err(processing_error) => return Err(processing_error),
             }
         }
 
@@ -1271,7 +2459,16 @@ where
     ///
     /// This function may panic if:
     /// - Output stream operations fail when `std` feature is enabled (due to `unwrap()` calls)
+    #[must_use = "check whether the event was handled"]
     pub fn send_internal(&mut self, event: &EventType) -> SendResult {
+        self.entered_states.clear();
+        self.send_step(event)
+    }
+
+    /// Processes one event like [`Runtime::send_internal`], adding its entries to those
+    /// already recorded for the current send.
+    pub(super) fn send_step(&mut self, event: &EventType) -> SendResult {
+        let async_verdicts = core::mem::take(&mut self.async_verdicts);
         #[cfg(all(feature = "debug-log", feature = "std"))]
         {
             println!("COMPILE-TIME DEBUG-LOG FEATURE IS ACTIVE");
@@ -1286,33 +2483,75 @@ where
             // io::stdout().flush().unwrap();
         }
 
+        // A runtime built with `unstarted` enters its initial state on the first event;
+        // one built with `new_deferred` waits for an explicit `start`.
+        if self.deferred_start {
+            return SendResult::Error(ProcessingError::NotStarted);
+        }
+        if self.needs_initial_entry
+            && let Err(error) = self.start(event)
+        {
+            return SendResult::Error(error);
+        }
+        if !(self.event_filter)(event) {
+            trace!("[TRACE] send_internal: event {:?} filtered out", event);
+            return SendResult::NoMatch;
+        }
+        #[cfg(feature = "oscillation-guard")]
+        self.oscillation.on_event();
+
         // Create a single entry_actions_run Vec to be reused throughout send_internal
         let mut entry_actions_run_vec: heapless::Vec<StateType, M> = heapless::Vec::new();
 
         // Phase 0: Collect potential transitions (read-only on context for guards)
         let current_active_leaves_snapshot = self.active_leaf_states.clone();
 
-        let potential_transitions =
-            match self.collect_potential_transitions(event, &current_active_leaves_snapshot) {
-                Ok(transitions) => transitions,
-                Err(e) => return SendResult::Error(e),
-            };
+        let potential_transitions = match self.collect_potential_transitions(
+            event,
+            &current_active_leaves_snapshot,
+            &async_verdicts,
+            &mut None,
+        ) {
+            Ok(transitions) => transitions,
+            Err(e) => return SendResult::Error(e),
+        };
 
         if potential_transitions.is_empty() {
             return SendResult::NoMatch;
         }
 
         // Phase 0.5: Arbitrate and de-duplicate transitions (still read-only on context)
-        let final_transitions_to_execute = match self.arbitrate_transitions(&potential_transitions)
-        {
-            Ok(transitions) => transitions,
-            Err(e) => return SendResult::Error(e),
-        };
+        let mut final_transitions_to_execute =
+            match self.arbitrate_transitions(&potential_transitions) {
+                Ok(transitions) => transitions,
+                Err(e) => return SendResult::Error(e),
+            };
 
         if final_transitions_to_execute.is_empty() {
             return SendResult::NoMatch;
         }
 
+        // Phase 0.75: Let the policy veto transitions before any exit runs
+        if let Some(policy) = self.policy {
+            final_transitions_to_execute.retain(|transition| {
+                let allowed = policy.allow(
+                    &current_active_leaves_snapshot,
+                    transition.target_state_id,
+                    event,
+                );
+                if !allowed {
+                    trace!(
+                        "[POLICY] Vetoed {:?} on {:?} → {:?}",
+                        transition.transition_from_state_id, event, transition.target_state_id
+                    );
+                }
+                allowed
+            });
+            if final_transitions_to_execute.is_empty() {
+                return SendResult::PolicyRejected;
+            }
+        }
+
         // --- Context and State Commit Logic ---
         // Phase 1: Apply transitions (exits and actions)
         // Clone context only when we're about to mutate it
@@ -1375,6 +2614,7 @@ where
             "[TRACE] FINAL next_active_leaves to assign: {:?}",
             next_active_leaves
         );
+        self.record_history(&current_active_leaves_snapshot, &states_exited_this_step);
         self.active_leaf_states.clear();
         self.active_leaf_states
             .extend(next_active_leaves.iter().copied());
@@ -1383,11 +2623,224 @@ where
             self.active_leaf_states
         );
 
+        #[cfg(feature = "async")]
+        self.cancel_exited_timers(&states_exited_this_step);
+
+        #[cfg(feature = "coverage")]
+        {
+            for transition in &final_transitions_to_execute {
+                self.record_transition_coverage(transition.transition_ref);
+            }
+            for &state in &entry_actions_run_vec {
+                self.record_state_coverage(state);
+            }
+        }
+
+        self.record_entered_states(&entry_actions_run_vec);
+
         // Commit the mutated context since we know transitions occurred
         self.context = temp_context;
+
+        #[cfg(feature = "oscillation-guard")]
+        if let Some(result) = self.check_oscillation(&entry_actions_run_vec, event) {
+            return result;
+        }
         SendResult::Transitioned
     }
 
+    /// Forcibly moves the machine to `target`, bypassing event-driven semantics.
+    ///
+    /// **Escape hatch for recovery tooling.** No transition table lookup, guard, or
+    /// transition action is involved: the current configuration is exited (exit actions
+    /// run leaf-first up to the least common ancestor of all active leaves and `target`),
+    /// then entry actions run from that ancestor down to `target`, descending through
+    /// initial children if `target` is compound or parallel. `event` is handed to the
+    /// entry/exit actions, exactly as `initial_event` is for [`Runtime::new`].
+    ///
+    /// Forcing a state inside one region of a parallel state only touches that region;
+    /// active leaves in sibling regions are left as they are.
+    ///
+    /// The context and active states are only committed if the whole operation succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::UnknownState`] if `target` is not part of the machine
+    /// definition, or the usual capacity/entry errors if the exit or entry phases fail.
+    pub fn force_transition(
+        &mut self,
+        target: StateType,
+        event: &EventType,
+    ) -> Result<(), ProcessingError> {
+        if self.machine_def.get_state_node(target).is_none() {
+            return Err(ProcessingError::UnknownState);
+        }
+        trace!(
+            "[FORCE] {:?} → {:?} via {:?}",
+            self.active_leaf_states, target, event
+        );
+
+        // Leaves in a sibling region of a parallel ancestor are orthogonal to the target and
+        // stay untouched. The transition scope is the deepest ancestor shared by the target
+        // and every other active leaf; it must be a proper ancestor so the target is re-entered.
+        let mut leaves_to_exit: heapless::Vec<StateType, N_ACTIVE> = heapless::Vec::new();
+        let mut lca = Some(target);
+        for &leaf in &self.active_leaf_states {
+            let leaf_lca = self.find_lca(leaf, target)?;
+            let is_orthogonal = leaf_lca != Some(target)
+                && leaf_lca
+                    .and_then(|id| self.machine_def.get_state_node(id))
+                    .is_some_and(|n| n.is_parallel);
+            if is_orthogonal {
+                continue;
+            }
+            leaves_to_exit
+                .push(leaf)
+                .map_err(|_| ProcessingError::CapacityExceeded)?;
+            lca = match lca {
+                Some(current) => self.find_lca(current, leaf)?,
+                None => None,
+            };
+        }
+        if lca == Some(target) {
+            lca = self.machine_def.get_parent_of(target);
+        }
+
+        let mut temp_context = self.context.clone();
+        let mut states_exited: heapless::Vec<StateType, MAX_NODES_FOR_COMPUTATION> =
+            heapless::Vec::new();
+        for &leaf in &leaves_to_exit {
+            for state_to_exit_id in self.compute_ordered_exit_set(leaf, lca)? {
+                if states_exited.contains(&state_to_exit_id) {
+                    continue;
+                }
+                if let Some(exit_fn) = self
+                    .machine_def
+                    .get_state_node(state_to_exit_id)
+                    .and_then(|n| n.exit_action)
+                {
+                    trace!("[EXIT] {:?} (exit_fn = true)", state_to_exit_id);
+                    exit_fn(&mut temp_context, event);
+                }
+                states_exited
+                    .push(state_to_exit_id)
+                    .map_err(|_| ProcessingError::CapacityExceeded)?;
+            }
+        }
+
+        let mut entry_actions_run_vec: heapless::Vec<StateType, M> = heapless::Vec::new();
+        let entered_leaves = self.execute_entry_actions_from_lca_with_context(
+            target,
+            lca,
+            target,
+            event,
+            &mut Scratch::<StateType, M> {
+                entry_actions_run: &mut entry_actions_run_vec,
+                history: &self.history,
+            },
+            &mut temp_context,
+        )?;
+
+        let mut next_active_leaves: heapless::Vec<StateType, N_ACTIVE> = heapless::Vec::new();
+        for &leaf in &self.active_leaf_states {
+            let was_exited = states_exited
+                .iter()
+                .any(|&exited| self.is_descendant_or_self(leaf, exited).unwrap_or(false));
+            if !was_exited && !next_active_leaves.contains(&leaf) {
+                next_active_leaves
+                    .push(leaf)
+                    .map_err(|_| ProcessingError::CapacityExceeded)?;
+            }
+        }
+        for leaf in entered_leaves {
+            let resolved_leaf = self.resolve_to_leaf(leaf)?;
+            if !next_active_leaves.contains(&resolved_leaf) {
+                next_active_leaves
+                    .push(resolved_leaf)
+                    .map_err(|_| ProcessingError::CapacityExceeded)?;
+            }
+        }
+        if next_active_leaves.is_empty() {
+            return Err(ProcessingError::EntryLogicFailure);
+        }
+
+        #[cfg(feature = "coverage")]
+        for &state in &entry_actions_run_vec {
+            self.record_state_coverage(state);
+        }
+
+        #[cfg(feature = "async")]
+        self.cancel_exited_timers(&states_exited);
+
+        let previous_leaves = core::mem::replace(&mut self.active_leaf_states, next_active_leaves);
+        self.record_history(&previous_leaves, &states_exited);
+        self.context = temp_context;
+        self.set_entered_states(&entry_actions_run_vec);
+        Ok(())
+    }
+
+    /// Stops the machine: exits every active state, innermost first, then runs the
+    /// definition's exit action (see [`MachineDefinition::with_exit_action`]), all with
+    /// `event`.
+    ///
+    /// The runtime is left as [`Runtime::unstarted`] leaves it, with the context as the exit
+    /// actions left it: queued internal and deferred events and the history records are
+    /// dropped, and the next [`Runtime::start`] or `send` enters the initial state again,
+    /// running the definition's entry action first. Does nothing if the runtime has not started.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingError::CapacityExceeded`] if the active configuration holds more
+    /// than `MAX_NODES_FOR_COMPUTATION` states; nothing has run then.
+    pub fn reset(&mut self, event: &EventType) -> Result<(), ProcessingError> {
+        if self.needs_initial_entry {
+            return Ok(());
+        }
+        let configuration: heapless::Vec<StateType, MAX_NODES_FOR_COMPUTATION> = self
+            .machine_def
+            .active_configuration(&self.active_leaf_states);
+        // `active_configuration` drops what does not fit; exiting only part of it would
+        // leave the context half torn down.
+        let complete = self.active_leaf_states.iter().all(|&leaf| {
+            let mut current = Some(leaf);
+            while let Some(state) = current {
+                if !configuration.contains(&state) {
+                    return false;
+                }
+                current = self.machine_def.get_parent_of(state);
+            }
+            true
+        });
+        if !complete {
+            return Err(ProcessingError::CapacityExceeded);
+        }
+        trace!("[RESET] {:?} via {:?}", self.active_leaf_states, event);
+
+        // Ancestors come before their descendants in the configuration.
+        for &state in configuration.iter().rev() {
+            if let Some(exit_fn) = self
+                .machine_def
+                .get_state_node(state)
+                .and_then(|node| node.exit_action)
+            {
+                exit_fn(&mut self.context, event);
+            }
+        }
+        if let Some(exit_fn) = self.machine_def.exit_action {
+            exit_fn(&mut self.context, event);
+        }
+
+        #[cfg(feature = "async")]
+        self.cancel_exited_timers(&configuration);
+
+        self.active_leaf_states.clear();
+        self.internal_events.clear();
+        self.deferred_events.clear();
+        self.entered_states.clear();
+        self.history.clear();
+        self.needs_initial_entry = true;
+        Ok(())
+    }
+
     // Cloned and modified version of execute_entry_actions_from_lca to accept context
     // This is a temporary measure; ideally, the original would be refactored.
     #[allow(clippy::too_many_lines)]
@@ -1607,16 +3060,11 @@ where
                 if core::ptr::eq(pt_candidate, other_pt) {
                     continue;
                 }
-                match self.is_proper_ancestor(
+                if self.is_proper_ancestor(
                     pt_candidate.transition_from_state_id,
                     other_pt.transition_from_state_id,
-                ) {
-                    Ok(is_ancestor) => {
-                        if is_ancestor {
-                            continue 'candidate_loop;
-                        }
-                    }
-                    Err(e) => return Err(e),
+                )? {
+                    continue 'candidate_loop;
                 }
             }
             if arbitrated_transitions.push(pt_candidate.clone()).is_err() {
@@ -1675,18 +3123,41 @@ where
     type Event = EventType;
     type Context = ContextType;
 
+    #[inline]
     fn send(&mut self, event: &EventType) -> SendResult {
-        self.send_internal(event)
+        self.send_to_completion(event)
+    }
+
+    #[inline]
+    fn reset(&mut self, event: &EventType) -> Result<(), ProcessingError> {
+        Runtime::reset(self, event)
+    }
+
+    fn send_batch(&mut self, events: &[EventType]) -> BatchSendSummary<StateType, N_ACTIVE> {
+        trace!("[TRACE] send_batch START for {} events", events.len());
+        let mut summary = BatchSendSummary::default();
+        for event in events {
+            summary.record(self.send_to_completion(event));
+        }
+        summary.final_state = self.active_leaf_states.clone();
+        trace!(
+            "[TRACE] send_batch END: {} transitioned, {} ignored, {} failed",
+            summary.transitioned, summary.ignored, summary.failed
+        );
+        summary
     }
 
+    #[inline]
     fn state(&self) -> heapless::Vec<Self::State, N_ACTIVE> {
         self.active_leaf_states.clone()
     }
 
+    #[inline]
     fn context(&self) -> &Self::Context {
         &self.context
     }
 
+    #[inline]
     fn context_mut(&mut self) -> &mut Self::Context {
         &mut self.context
     }
@@ -1763,6 +3234,7 @@ mod tests {
             entry_action: None,
             exit_action: None,
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: TestState::S1,
@@ -1771,6 +3243,7 @@ mod tests {
             entry_action: None,
             exit_action: None,
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: TestState::S2,
@@ -1779,6 +3252,7 @@ mod tests {
             entry_action: None,
             exit_action: None,
             is_parallel: false,
+            is_final: false,
         },
     ];
 
@@ -1792,6 +3266,7 @@ mod tests {
                 entry_action: None,
                 exit_action: None,
                 is_parallel: false,
+                is_final: false,
             },
             StateNode {
                 id: TestState::S1,
@@ -1800,6 +3275,7 @@ mod tests {
                 entry_action: None,
                 exit_action: None,
                 is_parallel: false,
+                is_final: false,
             },
             StateNode {
                 id: TestState::S2,
@@ -1808,6 +3284,7 @@ mod tests {
                 entry_action: None,
                 exit_action: None,
                 is_parallel: false,
+                is_final: false,
             },
         ];
 
@@ -2119,6 +3596,7 @@ mod tests {
             entry_action: Some(pt_log_enter_parallel),
             exit_action: Some(pt_log_exit_parallel),
             is_parallel: true,
+            is_final: false,
         },
         StateNode {
             id: ParallelTestState::R1,
@@ -2127,6 +3605,7 @@ mod tests {
             entry_action: Some(pt_log_enter_region1),
             exit_action: Some(pt_log_exit_region1),
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: ParallelTestState::R1A,
@@ -2135,6 +3614,7 @@ mod tests {
             entry_action: Some(pt_log_enter_region1_state_a),
             exit_action: Some(pt_log_exit_region1_state_a),
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: ParallelTestState::R1B,
@@ -2143,6 +3623,7 @@ mod tests {
             entry_action: Some(pt_log_enter_region1_state_b),
             exit_action: Some(pt_log_exit_region1_state_b),
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: ParallelTestState::R2,
@@ -2151,6 +3632,7 @@ mod tests {
             entry_action: Some(pt_log_enter_region2),
             exit_action: Some(pt_log_exit_region2),
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: ParallelTestState::R2X,
@@ -2159,6 +3641,7 @@ mod tests {
             entry_action: Some(pt_log_enter_region2_state_x),
             exit_action: Some(pt_log_exit_region2_state_x),
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: ParallelTestState::R2Y,
@@ -2167,6 +3650,7 @@ mod tests {
             entry_action: Some(pt_log_enter_region2_state_y),
             exit_action: Some(pt_log_exit_region2_state_y),
             is_parallel: false,
+            is_final: false,
         },
         StateNode {
             id: ParallelTestState::SOuter,
@@ -2175,6 +3659,7 @@ mod tests {
             entry_action: Some(pt_log_enter_state_outer),
             exit_action: Some(pt_log_exit_state_outer),
             is_parallel: false,
+            is_final: false,
         },
     ];
 
@@ -2189,6 +3674,10 @@ mod tests {
             action: Some(pt_log_event_parallel_self_action),
             guard: None,
             match_fn: Some(matches_parallel_self),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::P,
@@ -2196,6 +3685,10 @@ mod tests {
             action: Some(pt_log_event_parallel_to_outer_action),
             guard: None,
             match_fn: Some(matches_parallel_to_outer),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::R1A,
@@ -2203,6 +3696,10 @@ mod tests {
             action: Some(pt_log_region1_state_a_event_e1_action),
             guard: None,
             match_fn: Some(matches_parallel_e1),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::R1A,
@@ -2210,6 +3707,10 @@ mod tests {
             action: Some(pt_log_region1_state_a_event_region1_self_action),
             guard: None,
             match_fn: Some(matches_parallel_region1_self),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::R1A,
@@ -2217,6 +3718,10 @@ mod tests {
             action: Some(pt_log_region1_state_a_event_region1_only_action),
             guard: None,
             match_fn: Some(matches_region1_only),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::R1B,
@@ -2224,6 +3729,10 @@ mod tests {
             action: Some(pt_log_region1_state_b_event_e2_action),
             guard: None,
             match_fn: Some(matches_parallel_e2),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::R2X,
@@ -2231,6 +3740,10 @@ mod tests {
             action: Some(pt_log_region2_state_x_event_e1_action),
             guard: None,
             match_fn: Some(matches_parallel_e1),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::R2X,
@@ -2238,6 +3751,10 @@ mod tests {
             action: Some(pt_log_region2_state_x_event_region2_self_action),
             guard: None,
             match_fn: Some(matches_parallel_region2_self),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::R2Y,
@@ -2245,6 +3762,10 @@ mod tests {
             action: Some(pt_log_region2_state_y_event_e2_action),
             guard: None,
             match_fn: Some(matches_parallel_e2),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
         Transition {
             from_state: ParallelTestState::SOuter,
@@ -2252,6 +3773,10 @@ mod tests {
             action: Some(pt_log_event_outer_to_parallel_action),
             guard: None,
             match_fn: Some(matches_outer_to_parallel),
+            state_guard: None,
+            read_action: None,
+            fallible_action: None,
+            async_guard: None,
         },
     ];
 
@@ -2568,6 +4093,232 @@ mod tests {
         matches!(event, ParallelTestEvent::E1)
     }
 
+    #[test]
+    fn test_force_transition_into_parallel_region() {
+        let mut runtime = Runtime::<
+            _,
+            _,
+            _,
+            TEST_HIERARCHY_DEPTH_M,
+            MAX_ACTIVE_REGIONS,
+            TEST_MAX_NODES_FOR_COMPUTATION,
+        >::new(
+            &PARALLEL_MACHINE_DEF,
+            ParallelActionLogContext::default(),
+            &ParallelTestEvent::E1,
+        )
+        .expect("Failed to create runtime for test");
+
+        runtime
+            .force_transition(ParallelTestState::R1B, &ParallelTestEvent::E1)
+            .expect("force_transition should succeed");
+
+        let mut active = runtime.state();
+        active.sort_unstable();
+        assert_eq!(
+            active.as_slice(),
+            &[ParallelTestState::R1B, ParallelTestState::R2X]
+        );
+    }
+
+    #[test]
+    fn test_config_diff_orders_exits_children_first_and_entries_parents_first() {
+        let diff: ConfigDiff<u8, 4> = ConfigDiff::between(&[1, 2, 3], &[1, 4, 5]);
+        assert_eq!(diff.exited.as_slice(), &[3, 2]);
+        assert_eq!(diff.entered.as_slice(), &[4, 5]);
+        assert!(ConfigDiff::<u8, 4>::between(&[1, 2], &[1, 2]).is_empty());
+    }
+
+    #[test]
+    fn test_force_transition_rejects_unknown_state() {
+        const SINGLE_STATE: &[StateNode<TestState, TestContextForEmpty, TestEvent>] =
+            &[StateNode {
+                id: TestState::S0,
+                parent: None,
+                initial_child: None,
+                entry_action: None,
+                exit_action: None,
+                is_parallel: false,
+                is_final: false,
+            }];
+        static SINGLE_STATE_DEF: MachineDefinition<TestState, TestEvent, TestContextForEmpty> =
+            MachineDefinition::new(SINGLE_STATE, &[], TestState::S0);
+
+        let mut runtime =
+            Runtime::<
+                _,
+                _,
+                _,
+                TEST_HIERARCHY_DEPTH_M,
+                MAX_ACTIVE_REGIONS,
+                TEST_MAX_NODES_FOR_COMPUTATION,
+            >::new(&SINGLE_STATE_DEF, DefaultContext::default(), &TestEvent::E0)
+            .expect("Failed to create runtime for test");
+
+        assert_eq!(
+            runtime.force_transition(TestState::S2, &TestEvent::E0),
+            Err(ProcessingError::UnknownState)
+        );
+        assert_eq!(runtime.state().as_slice(), &[TestState::S0]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_exiting_a_state_cancels_its_timers() {
+        use crate::timer::{TimerService, TimerToken};
+        use core::time::Duration;
+
+        const TOGGLE_TRANSITIONS: &[Transition<TestState, TestEvent, TestContextForEmpty>] = &[
+            Transition {
+                from_state: TestState::S0,
+                to_state: TestState::S1,
+                action: None,
+                guard: None,
+                match_fn: Some(matches_test_event_e0),
+                state_guard: None,
+                read_action: None,
+                fallible_action: None,
+                async_guard: None,
+            },
+            Transition {
+                from_state: TestState::S1,
+                to_state: TestState::S0,
+                action: None,
+                guard: None,
+                match_fn: Some(matches_test_event_e0),
+                state_guard: None,
+                read_action: None,
+                fallible_action: None,
+                async_guard: None,
+            },
+        ];
+        static TOGGLE_DEF: MachineDefinition<TestState, TestEvent, TestContextForEmpty> =
+            MachineDefinition::new(
+                TEST_STATENODES_EMPTY_CTX_POPULATED,
+                TOGGLE_TRANSITIONS,
+                TestState::S0,
+            );
+
+        let mut runtime = Runtime::<
+            _,
+            _,
+            _,
+            TEST_HIERARCHY_DEPTH_M,
+            MAX_ACTIVE_REGIONS,
+            TEST_MAX_NODES_FOR_COMPUTATION,
+        >::new(&TOGGLE_DEF, DefaultContext::default(), &TestEvent::E0)
+        .expect("Failed to create runtime for test");
+
+        let first_visit: TimerToken = crate::timer::Timer::arm(Duration::from_millis(50));
+        runtime.arm_timer(TestState::S0, 0, first_visit).unwrap();
+        assert_eq!(
+            runtime.arm_timer(TestState::S1, 0, first_visit),
+            Err(ProcessingError::UnknownState)
+        );
+
+        // Leave and re-enter S0: the first visit's timer must not fire against the new visit.
+        assert_eq!(runtime.send(&TestEvent::E0), SendResult::Transitioned);
+        assert_eq!(runtime.armed_timers().next(), None);
+        assert_eq!(runtime.send(&TestEvent::E0), SendResult::Transitioned);
+        let second_visit = crate::timer::Timer::arm(Duration::from_millis(50));
+        runtime.arm_timer(TestState::S0, 0, second_visit).unwrap();
+
+        assert_eq!(runtime.timer_fired(first_visit), None);
+        assert!(!runtime.cancel_timer(first_visit));
+        assert_eq!(runtime.timer_fired(second_visit), Some((TestState::S0, 0)));
+        assert_eq!(runtime.timer_fired(second_visit), None);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_cancel_timer_is_idempotent() {
+        use crate::timer::TimerService;
+        use core::time::Duration;
+
+        static SINGLE_DEF: MachineDefinition<TestState, TestEvent, TestContextForEmpty> =
+            MachineDefinition::new(TEST_STATENODES_EMPTY_CTX_POPULATED, &[], TestState::S0);
+        let mut runtime = Runtime::<
+            _,
+            _,
+            _,
+            TEST_HIERARCHY_DEPTH_M,
+            MAX_ACTIVE_REGIONS,
+            TEST_MAX_NODES_FOR_COMPUTATION,
+        >::new(&SINGLE_DEF, DefaultContext::default(), &TestEvent::E0)
+        .expect("Failed to create runtime for test");
+
+        let token = crate::timer::Timer::arm(Duration::from_secs(1));
+        runtime.arm_timer(TestState::S0, 0, token).unwrap();
+        assert!(runtime.cancel_timer(token));
+        assert!(!runtime.cancel_timer(token));
+        assert_eq!(runtime.timer_fired(token), None);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_entering_a_state_arms_its_after_timers() {
+        use core::time::Duration;
+
+        const TOGGLE_TRANSITIONS: &[Transition<TestState, TestEvent, TestContextForEmpty>] = &[
+            Transition {
+                from_state: TestState::S0,
+                to_state: TestState::S1,
+                action: None,
+                guard: None,
+                match_fn: Some(matches_test_event_e0),
+                state_guard: None,
+                read_action: None,
+                fallible_action: None,
+                async_guard: None,
+            },
+            Transition {
+                from_state: TestState::S1,
+                to_state: TestState::S0,
+                action: None,
+                guard: None,
+                match_fn: Some(matches_test_event_e0),
+                state_guard: None,
+                read_action: None,
+                fallible_action: None,
+                async_guard: None,
+            },
+        ];
+        static TIMED_DEF: MachineDefinition<TestState, TestEvent, TestContextForEmpty> =
+            MachineDefinition::new(
+                TEST_STATENODES_EMPTY_CTX_POPULATED,
+                TOGGLE_TRANSITIONS,
+                TestState::S0,
+            )
+            .with_after_timers(&[(TestState::S0, Duration::from_millis(50))]);
+
+        let mut runtime = Runtime::<
+            _,
+            _,
+            _,
+            TEST_HIERARCHY_DEPTH_M,
+            MAX_ACTIVE_REGIONS,
+            TEST_MAX_NODES_FOR_COMPUTATION,
+        >::new(&TIMED_DEF, DefaultContext::default(), &TestEvent::E0)
+        .expect("Failed to create runtime for test");
+
+        assert_eq!(runtime.entered_timers().count(), 1);
+        let first_visit = runtime
+            .entered_timers()
+            .next()
+            .expect("starting in S0 arms its timer");
+        assert_eq!(first_visit.duration(), Duration::from_millis(50));
+
+        // Entering S1 arms nothing; re-entering S0 arms a new token and the old one is stale.
+        assert_eq!(runtime.send(&TestEvent::E0), SendResult::Transitioned);
+        assert_eq!(runtime.entered_timers().next(), None);
+        assert_eq!(runtime.send(&TestEvent::E0), SendResult::Transitioned);
+        let second_visit = runtime.entered_timers().next().expect("S0 re-armed");
+        assert_ne!(second_visit, first_visit);
+
+        assert_eq!(runtime.timer_fired(first_visit), None);
+        assert_eq!(runtime.timer_fired(second_visit), Some((TestState::S0, 0)));
+    }
+
     fn matches_parallel_e2(event: &ParallelTestEvent) -> bool {
         matches!(event, ParallelTestEvent::E2)
     }
@@ -0,0 +1,278 @@
+//! Detecting a machine that keeps bouncing between states.
+//!
+//! A pair of guards that each send the machine back where the other came from can make it
+//! cycle between two states as fast as events arrive, or forever within one cascade of
+//! raised events, while every single step looks fine in a log. With the `oscillation-guard`
+//! feature, a runtime given an [`OscillationGuard`] counts how often each state is entered
+//! within a window, measured in processed events or in ticks of a [`MonotonicClock`], and
+//! reacts once a state is entered more than `threshold` times before the window closes:
+//!
+//! ```rust,ignore
+//! // Log (with `debug-log`) and report any state entered more than 50 times in 100 events.
+//! pump.set_oscillation_guard(Some(
+//!     OscillationGuard::new(50, OscillationWindow::Events(100))
+//!         .with_response(OscillationResponse::Report),
+//! ));
+//! ```
+//!
+//! Counters are per state, saturate at `u16::MAX`, and all start over when a window closes,
+//! when the guard fires, and on [`Runtime::reset_entry_counts`]. Windows are consecutive
+//! rather than sliding, so a burst split across two windows can go unnoticed until it
+//! fills one of them. Only the first `MAX_NODES_FOR_COMPUTATION` distinct states entered in
+//! a window are counted.
+
+use super::{MonotonicClock, ProcessingError, Runtime, SendResult, Ticks};
+
+/// How long entries are counted before the counters start over.
+#[derive(Clone, Copy)]
+pub enum OscillationWindow {
+    /// A window of this many processed events, internal ones included.
+    Events(u32),
+    /// A window of this many ticks of the clock.
+    Ticks(Ticks, &'static (dyn MonotonicClock + Sync)),
+}
+
+impl core::fmt::Debug for OscillationWindow {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Events(events) => f.debug_tuple("Events").field(events).finish(),
+            Self::Ticks(ticks, _) => f.debug_tuple("Ticks").field(ticks).finish(),
+        }
+    }
+}
+
+/// What the runtime does when a state is entered too often.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OscillationResponse<StateType> {
+    /// Log the oscillating state (with `debug-log`) and carry on.
+    Log,
+    /// Also return [`SendResult::OscillationDetected`] from the send, which stops the
+    /// cascade of raised events it belongs to.
+    Report,
+    /// Force the machine into this state, e.g. an error state declared for the purpose,
+    /// and return [`SendResult::OscillationDetected`].
+    EnterState(StateType),
+}
+
+/// When and how a runtime reacts to a state being entered too often; see the
+/// [module docs](self).
+#[derive(Debug, Clone, Copy)]
+pub struct OscillationGuard<StateType> {
+    /// Entries of one state a window may hold before the guard fires.
+    pub threshold: u16,
+    pub window: OscillationWindow,
+    pub response: OscillationResponse<StateType>,
+}
+
+impl<StateType: Copy> OscillationGuard<StateType> {
+    /// A guard that fires once a state is entered more than `threshold` times within
+    /// `window`, and only logs it.
+    #[must_use]
+    pub const fn new(threshold: u16, window: OscillationWindow) -> Self {
+        Self {
+            threshold,
+            window,
+            response: OscillationResponse::Log,
+        }
+    }
+
+    /// Sets what happens when the guard fires.
+    #[must_use]
+    pub const fn with_response(mut self, response: OscillationResponse<StateType>) -> Self {
+        self.response = response;
+        self
+    }
+}
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Attaches an oscillation guard, replacing any earlier one and resetting the entry
+    /// counters; `None` removes it and stops counting.
+    pub fn set_oscillation_guard(&mut self, guard: Option<OscillationGuard<StateType>>) {
+        self.oscillation.set_guard(guard);
+    }
+
+    /// Returns how often `state` has been entered in the current window. Always 0 without
+    /// an oscillation guard.
+    #[must_use]
+    pub fn entry_count(&self, state: StateType) -> u16 {
+        self.oscillation.count(state)
+    }
+
+    /// Starts a new window with every entry counter at 0.
+    pub fn reset_entry_counts(&mut self) {
+        self.oscillation.reset();
+    }
+
+    /// Counts the states one step entered and carries out the guard's response if one of
+    /// them was entered too often. Returns the result to report instead of the step's own.
+    pub(super) fn check_oscillation(
+        &mut self,
+        entered: &[StateType],
+        event: &EventType,
+    ) -> Option<SendResult> {
+        let response = self.oscillation.record(entered)?;
+        respond(response, |target| self.force_transition(target, event))
+    }
+}
+
+/// Turns a fired guard's `response` into the result of the send, forcing the machine into
+/// the target state through `force` for [`OscillationResponse::EnterState`].
+pub(super) fn respond<StateType: Copy>(
+    response: OscillationResponse<StateType>,
+    force: impl FnOnce(StateType) -> Result<(), ProcessingError>,
+) -> Option<SendResult> {
+    match response {
+        OscillationResponse::Log => None,
+        OscillationResponse::Report => Some(SendResult::OscillationDetected),
+        OscillationResponse::EnterState(target) => Some(match force(target) {
+            Ok(()) => SendResult::OscillationDetected,
+            Err(error) => SendResult::Error(error),
+        }),
+    }
+}
+
+/// A runtime's guard and the entry counters of its current window.
+#[derive(Debug, Clone)]
+pub(super) struct OscillationTracker<StateType: 'static, const CAP: usize> {
+    guard: Option<OscillationGuard<StateType>>,
+    /// Events processed, for [`OscillationWindow::Events`].
+    events: Ticks,
+    /// Event count or clock reading when the current window opened.
+    window_start: Ticks,
+    counts: heapless::Vec<(StateType, u16), CAP>,
+}
+
+impl<StateType, const CAP: usize> OscillationTracker<StateType, CAP>
+where
+    StateType: Copy + PartialEq + core::fmt::Debug,
+{
+    pub(super) const fn new() -> Self {
+        Self {
+            guard: None,
+            events: 0,
+            window_start: 0,
+            counts: heapless::Vec::new(),
+        }
+    }
+
+    pub(super) fn set_guard(&mut self, guard: Option<OscillationGuard<StateType>>) {
+        self.guard = guard;
+        self.reset();
+    }
+
+    pub(super) fn count(&self, state: StateType) -> u16 {
+        self.counts
+            .iter()
+            .find(|(counted, _)| *counted == state)
+            .map_or(0, |&(_, count)| count)
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.counts.clear();
+        self.window_start = self.now();
+    }
+
+    /// Notes that an event is about to be processed, closing the window if it is over.
+    pub(super) fn on_event(&mut self) {
+        let Some(guard) = self.guard else {
+            return;
+        };
+        let length = match guard.window {
+            OscillationWindow::Events(events) => Ticks::from(events),
+            OscillationWindow::Ticks(ticks, _) => ticks,
+        };
+        if self.now().wrapping_sub(self.window_start) >= length {
+            self.reset();
+        }
+        self.events = self.events.wrapping_add(1);
+    }
+
+    /// Counts `entered` and returns the guard's response if a state went over the
+    /// threshold; the counters then start over.
+    pub(super) fn record(
+        &mut self,
+        entered: &[StateType],
+    ) -> Option<OscillationResponse<StateType>> {
+        let guard = self.guard?;
+        let mut oscillating = None;
+        for &state in entered {
+            let count = if let Some((_, count)) = self
+                .counts
+                .iter_mut()
+                .find(|(counted, _)| *counted == state)
+            {
+                *count = count.saturating_add(1);
+                *count
+            } else if self.counts.push((state, 1)).is_ok() {
+                1
+            } else {
+                continue;
+            };
+            if count > guard.threshold && oscillating.is_none() {
+                oscillating = Some(state);
+            }
+        }
+
+        let _state = oscillating?;
+        trace!(
+            "[OSCILLATION] {:?} entered more than {} times within {:?}",
+            _state, guard.threshold, guard.window
+        );
+        self.reset();
+        Some(guard.response)
+    }
+
+    fn now(&self) -> Ticks {
+        match self.guard.map(|guard| guard.window) {
+            Some(OscillationWindow::Ticks(_, clock)) => clock.now(),
+            _ => self.events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_saturate_instead_of_wrapping() {
+        let mut tracker = OscillationTracker::<u8, 4>::new();
+        tracker.set_guard(Some(OscillationGuard::new(
+            u16::MAX,
+            OscillationWindow::Events(u32::MAX),
+        )));
+        let entries = [7u8; 1024];
+        for _ in 0..70 {
+            assert_eq!(tracker.record(&entries), None);
+        }
+        assert_eq!(tracker.count(7), u16::MAX);
+    }
+
+    #[test]
+    fn only_the_first_states_of_a_window_are_counted() {
+        let mut tracker = OscillationTracker::<u8, 2>::new();
+        tracker.set_guard(Some(OscillationGuard::new(
+            1,
+            OscillationWindow::Events(10),
+        )));
+        assert_eq!(tracker.record(&[1, 2, 3]), None);
+        assert_eq!(tracker.count(3), 0);
+        assert_eq!(
+            tracker.record(&[1]),
+            Some(OscillationResponse::Log),
+            "a counted state still fires"
+        );
+    }
+}
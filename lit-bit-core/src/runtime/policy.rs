@@ -0,0 +1,142 @@
+//! Vetoing transitions from outside the chart.
+//!
+//! A [`TransitionPolicy`] attached with [`Runtime::set_policy`] is asked about every
+//! transition the runtime has selected for an event, after its guard passed and before any
+//! exit action runs. A vetoed transition is dropped as if its guard had failed, except that
+//! no other transition takes its place. If every selected transition is vetoed, `send`
+//! returns [`SendResult::PolicyRejected`](super::SendResult::PolicyRejected) and nothing
+//! changes; in a parallel state, regions whose transitions are allowed still move.
+//!
+//! Policies are meant for rules that hold across the whole chart, such as a safe mode that
+//! keeps certain states out of reach whatever the guards say:
+//!
+//! ```rust,ignore
+//! static SAFE_MODE: DenyTargets<PumpStateId> =
+//!     DenyTargets(&[PumpStateId::Boost, PumpStateId::Purge]);
+//!
+//! pump.set_policy(Some(&SAFE_MODE)); // entering safe mode
+//! pump.set_policy(None);             // leaving it
+//! ```
+//!
+//! [`DenyTargets`] scans its list for every transition. [`DenyTargetSet`] takes the states
+//! as a [`StateSet`] of their indices instead and tests one bit, which suits long lists or
+//! sets computed at run time:
+//!
+//! ```rust,ignore
+//! static SAFE_MODE: DenyTargetSet = DenyTargetSet(
+//!     StateSet::new()
+//!         .with(PumpStateId::Boost.index())
+//!         .with(PumpStateId::Purge.index()),
+//! );
+//! ```
+//!
+//! Policies compose as tuples: `(A, B)` allows a transition only if both `A` and `B` do.
+
+use super::{Runtime, StateSet};
+
+/// Decides whether a transition the chart selected may run.
+///
+/// `from` is the active leaf configuration before the event and `to` the transition's
+/// target as written in the chart.
+pub trait TransitionPolicy<StateType, EventType>: Sync {
+    /// Returns `false` to veto the transition to `to`.
+    fn allow(&self, from: &[StateType], to: StateType, event: &EventType) -> bool;
+}
+
+impl<StateType, EventType> core::fmt::Debug for dyn TransitionPolicy<StateType, EventType> + '_ {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("TransitionPolicy")
+    }
+}
+
+impl<StateType, EventType, A, B> TransitionPolicy<StateType, EventType> for (A, B)
+where
+    StateType: Copy,
+    A: TransitionPolicy<StateType, EventType>,
+    B: TransitionPolicy<StateType, EventType>,
+{
+    fn allow(&self, from: &[StateType], to: StateType, event: &EventType) -> bool {
+        self.0.allow(from, to, event) && self.1.allow(from, to, event)
+    }
+}
+
+/// Vetoes every transition that targets one of the listed states.
+///
+/// Only the target as written in the chart is compared: a transition to a compound state
+/// that is not listed is allowed even if it settles in a listed child.
+#[derive(Debug, Clone, Copy)]
+pub struct DenyTargets<StateType: 'static>(pub &'static [StateType]);
+
+impl<StateType, EventType> TransitionPolicy<StateType, EventType> for DenyTargets<StateType>
+where
+    StateType: PartialEq + Sync,
+{
+    fn allow(&self, _from: &[StateType], to: StateType, _event: &EventType) -> bool {
+        !self.0.contains(&to)
+    }
+}
+
+/// Vetoes every transition that targets a state whose index is in the set.
+///
+/// Like [`DenyTargets`], only the target as written in the chart is compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DenyTargetSet<const WORDS: usize = 1>(pub StateSet<WORDS>);
+
+impl<StateType, EventType, const WORDS: usize> TransitionPolicy<StateType, EventType>
+    for DenyTargetSet<WORDS>
+where
+    StateType: Into<u32>,
+{
+    fn allow(&self, _from: &[StateType], to: StateType, _event: &EventType) -> bool {
+        !self.0.contains_state(to)
+    }
+}
+
+/// A machine that accepts a [`TransitionPolicy`]: the runtimes, every machine `statechart!`
+/// generates, and a [`StatechartActor`](crate::actor::StatechartActor) wrapping one.
+pub trait PolicyHost<StateType, EventType> {
+    /// Attaches `policy`, replacing any earlier one; `None` removes it.
+    fn set_policy(&mut self, policy: Option<&'static dyn TransitionPolicy<StateType, EventType>>);
+}
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Attaches a policy that can veto transitions after their guards pass; see the
+    /// [module docs](self). `None` removes it.
+    pub fn set_policy(
+        &mut self,
+        policy: Option<&'static dyn TransitionPolicy<StateType, EventType>>,
+    ) {
+        self.policy = policy;
+    }
+}
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> PolicyHost<StateType, EventType>
+    for Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    fn set_policy(&mut self, policy: Option<&'static dyn TransitionPolicy<StateType, EventType>>) {
+        Runtime::set_policy(self, policy);
+    }
+}
@@ -0,0 +1,234 @@
+//! Transition actions that can fail and are retried before the transition is given up.
+//!
+//! `[action retry(3) write_config]` in a chart turns `write_config` into a
+//! [`FallibleActionFn`]: when it returns an [`ActionError`], the runtime calls it again, up to
+//! three more times, before treating the step as failed. A failed step is rolled back like
+//! any other processing error: the context and the active states are left as they were
+//! before the event, and `send` returns [`ProcessingError::ActionFailed`].
+//!
+//! `retry(3, delay = 10)` waits 10 ticks of the runtime's retry clock between attempts. The
+//! wait is a busy loop, since actions run synchronously; without a clock (see
+//! [`Runtime::set_retry_clock`](super::Runtime::set_retry_clock)) retries are immediate.
+//!
+//! A plain `[action try_apply]` whose function returns `Result<(), ActionError>` (see
+//! [`ActionOutcome`]) is a fallible action with no retries: the first error aborts the
+//! transition the same way.
+
+use super::{MonotonicClock, ProcessingError, Runtime, Ticks};
+
+/// Error returned by a fallible transition action.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ActionError {
+    /// Short description for diagnostics, e.g. `"i2c nack"`.
+    pub reason: &'static str,
+}
+
+impl ActionError {
+    #[must_use]
+    pub const fn new(reason: &'static str) -> Self {
+        Self { reason }
+    }
+}
+
+impl core::fmt::Display for ActionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "action failed: {}", self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ActionError {}
+
+/// A transition action that can fail.
+pub type FallibleActionFn<ContextType, EventType> =
+    fn(context: &mut ContextType, event: &EventType) -> Result<(), ActionError>;
+
+/// What a plain transition action returns: `()`, or `Result<(), ActionError>` for an action
+/// that can abort its transition. `statechart!` files `[action f]` under
+/// [`Transition::action`](super::Transition::action) or, with no retries,
+/// [`Transition::fallible_action`](super::Transition::fallible_action) accordingly.
+#[diagnostic::on_unimplemented(
+    message = "a transition action returns `()` or `Result<(), ActionError>`, not `{Self}`"
+)]
+pub trait ActionOutcome {
+    /// Whether an action returning `Self` can fail.
+    const FALLIBLE: bool;
+
+    /// Returns the action's verdict; `()` always succeeds.
+    ///
+    /// # Errors
+    ///
+    /// The action's error, if it failed.
+    fn into_result(self) -> Result<(), ActionError>;
+}
+
+impl ActionOutcome for () {
+    const FALLIBLE: bool = false;
+
+    fn into_result(self) -> Result<(), ActionError> {
+        Ok(())
+    }
+}
+
+impl ActionOutcome for Result<(), ActionError> {
+    const FALLIBLE: bool = true;
+
+    fn into_result(self) -> Result<(), ActionError> {
+        self
+    }
+}
+
+/// Picks the slot of `[action f]` at compile time; used by the code `statechart!` generates.
+#[doc(hidden)]
+pub const fn is_fallible_action<ContextType, EventType, Outcome: ActionOutcome>(
+    _action: &impl Fn(&mut ContextType, &EventType) -> Outcome,
+) -> bool {
+    Outcome::FALLIBLE
+}
+
+/// A fallible transition action and how often to retry it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[allow(unpredictable_function_pointer_comparisons)]
+pub struct FallibleAction<ContextType, EventType> {
+    pub action: FallibleActionFn<ContextType, EventType>,
+    /// Attempts after the first one; the action runs at most `retries + 1` times.
+    pub retries: u8,
+    /// Ticks of the retry clock to wait before each retry.
+    pub delay: Ticks,
+}
+
+/// What happened to a fallible action during one step, as reported to a
+/// [`RetryObserverFn`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RetryReport<StateType> {
+    pub from_state: StateType,
+    pub to_state: StateType,
+    /// How many times the action ran, including the first attempt.
+    pub attempts: u16,
+    /// The error of the last attempt, or `None` if the action eventually succeeded.
+    pub error: Option<ActionError>,
+}
+
+impl<StateType> RetryReport<StateType> {
+    /// Returns whether the action eventually succeeded.
+    #[must_use]
+    pub const fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Called after every run of a fallible action, whether it succeeded or not.
+pub type RetryObserverFn<StateType> = fn(&RetryReport<StateType>);
+
+impl<
+    StateType,
+    EventType,
+    ContextType,
+    const M: usize,
+    const N_ACTIVE: usize,
+    const MAX_NODES_FOR_COMPUTATION: usize,
+> Runtime<StateType, EventType, ContextType, M, N_ACTIVE, MAX_NODES_FOR_COMPUTATION>
+where
+    StateType: Copy + Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    EventType: Clone + PartialEq + Eq + core::hash::Hash + core::fmt::Debug + 'static,
+    ContextType: Clone + 'static,
+{
+    /// Sets the clock that `retry(n, delay = ticks)` waits on between attempts. Without
+    /// one, retries happen immediately.
+    pub fn set_retry_clock(&mut self, clock: &'static (dyn MonotonicClock + Sync)) {
+        self.retry.clock = Some(clock);
+    }
+
+    /// Sets the observer told about every run of a fallible action: how many attempts it
+    /// took and whether it succeeded.
+    pub fn set_retry_observer(&mut self, observer: RetryObserverFn<StateType>) {
+        self.retry.observer = Some(observer);
+    }
+}
+
+/// The clock and observer a runtime uses for fallible actions.
+pub(super) struct RetrySettings<StateType: 'static> {
+    pub(super) clock: Option<&'static (dyn MonotonicClock + Sync)>,
+    pub(super) observer: Option<RetryObserverFn<StateType>>,
+}
+
+impl<StateType> RetrySettings<StateType> {
+    pub(super) const fn new() -> Self {
+        Self {
+            clock: None,
+            observer: None,
+        }
+    }
+
+    /// Runs `fallible` until it succeeds or runs out of retries, then reports the outcome.
+    pub(super) fn run<ContextType, EventType>(
+        &self,
+        fallible: &FallibleAction<ContextType, EventType>,
+        from_state: StateType,
+        to_state: StateType,
+        context: &mut ContextType,
+        event: &EventType,
+    ) -> Result<(), ProcessingError>
+    where
+        StateType: Copy + core::fmt::Debug,
+    {
+        let mut attempts: u16 = 0;
+        let error = loop {
+            attempts += 1;
+            match (fallible.action)(context, event) {
+                Ok(()) => break None,
+                Err(error) if attempts > u16::from(fallible.retries) => break Some(error),
+                Err(_error) => {
+                    trace!(
+                        "[ACTION] {:?} → {:?} failed on attempt {}: {}",
+                        from_state, to_state, attempts, _error
+                    );
+                    self.wait(fallible.delay);
+                }
+            }
+        };
+
+        if let Some(observer) = self.observer {
+            observer(&RetryReport {
+                from_state,
+                to_state,
+                attempts,
+                error,
+            });
+        }
+        match error {
+            None => Ok(()),
+            Some(_) => Err(ProcessingError::ActionFailed),
+        }
+    }
+
+    fn wait(&self, delay: Ticks) {
+        let Some(clock) = self.clock else {
+            return;
+        };
+        if delay == 0 {
+            return;
+        }
+        let start = clock.now();
+        while clock.now().saturating_sub(start) < delay {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl<StateType> Clone for RetrySettings<StateType> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<StateType> Copy for RetrySettings<StateType> {}
+
+impl<StateType: core::fmt::Debug> core::fmt::Debug for RetrySettings<StateType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RetrySettings")
+            .field("clock", &self.clock.is_some())
+            .field("observer", &self.observer.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,196 @@
+//! Fluent behavioural tests for statecharts.
+//!
+//! [`statechart_test!`](crate::statechart_test) writes the boilerplate of a machine's test
+//! module: a `scenario` function that builds the machine, checks that it started in the
+//! chart's declared initial state and wraps it in a [`Scenario`]. Each step of a test is
+//! then one line:
+//!
+//! ```rust,ignore
+//! mod airlock {
+//!     lit_bit_core::statechart_test!(super::Airlock);
+//!
+//!     #[test]
+//!     fn locking_seals_the_hatch() {
+//!         scenario(&Dock::Boot)
+//!             .expect_state(AirlockStateId::CycleHatchOpen)
+//!             .send(Dock::Lock)
+//!             .expect_result(SendResult::Transitioned)
+//!             .expect_state(AirlockStateId::CycleHatchSealed);
+//!     }
+//! }
+//! ```
+//!
+//! Failed expectations panic at the line of the expectation, listing the active states and
+//! the result of the last send.
+
+use crate::{MAX_ACTIVE_REGIONS, SendResult, StateMachine};
+
+/// A machine under test, with the result of the last event sent to it.
+#[derive(Debug)]
+pub struct Scenario<M, const N_ACTIVE: usize = MAX_ACTIVE_REGIONS>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    machine: M,
+    last_result: Option<SendResult>,
+}
+
+impl<M, const N_ACTIVE: usize> Scenario<M, N_ACTIVE>
+where
+    M: StateMachine<N_ACTIVE>,
+{
+    /// Starts a scenario from `machine` as it is.
+    #[must_use]
+    pub fn new(machine: M) -> Self {
+        Self {
+            machine,
+            last_result: None,
+        }
+    }
+
+    /// Sends `event` to the machine.
+    pub fn send(mut self, event: M::Event) -> Self {
+        self.last_result = Some(self.machine.send(&event));
+        self
+    }
+
+    /// Sends every event in `events`, in order.
+    pub fn send_all(mut self, events: impl IntoIterator<Item = M::Event>) -> Self {
+        for event in events {
+            self.last_result = Some(self.machine.send(&event));
+        }
+        self
+    }
+
+    /// Asserts that `state` is one of the active leaf states.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it is not.
+    #[track_caller]
+    pub fn expect_state(self, state: M::State) -> Self {
+        let active = self.machine.state();
+        assert!(
+            active.contains(&state),
+            "expected {state:?} to be active, but the active states are {active:?} (last send: {:?})",
+            self.last_result
+        );
+        self
+    }
+
+    /// Asserts that the active leaf states are exactly `states`, in any order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a state in `states` is not active or another state is.
+    #[track_caller]
+    pub fn expect_states(self, states: &[M::State]) -> Self {
+        let active = self.machine.state();
+        let matches = active.len() == states.len()
+            && states.iter().all(|state| active.contains(state))
+            && active.iter().all(|state| states.contains(state));
+        assert!(
+            matches,
+            "expected the active states {states:?}, found {active:?} (last send: {:?})",
+            self.last_result
+        );
+        self
+    }
+
+    /// Asserts that the last send returned `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it returned something else or nothing has been sent yet.
+    #[track_caller]
+    pub fn expect_result(self, expected: SendResult) -> Self {
+        assert_eq!(
+            self.last_result,
+            Some(expected),
+            "unexpected result of the last send (active states: {:?})",
+            self.machine.state()
+        );
+        self
+    }
+
+    /// Asserts that `check` holds for the machine's context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it does not.
+    #[track_caller]
+    pub fn expect_context(self, check: impl FnOnce(&M::Context) -> bool) -> Self {
+        assert!(
+            check(self.machine.context()),
+            "context check failed (active states: {:?}, last send: {:?})",
+            self.machine.state(),
+            self.last_result
+        );
+        self
+    }
+
+    /// Returns the result of the last send, or `None` before the first one.
+    #[must_use]
+    pub fn last_result(&self) -> Option<SendResult> {
+        self.last_result
+    }
+
+    /// Returns the machine under test.
+    #[must_use]
+    pub fn machine(&self) -> &M {
+        &self.machine
+    }
+
+    /// Returns the machine under test mutably, for steps the DSL has no words for.
+    pub fn machine_mut(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    /// Ends the scenario, returning the machine.
+    #[must_use]
+    pub fn into_machine(self) -> M {
+        self.machine
+    }
+}
+
+/// Defines `scenario(initial_event)` for a `statechart!` machine in the invoking module: it
+/// builds the machine with `new`, asserts that it started in the chart's declared initial
+/// state, and returns a [`Scenario`](crate::scenario::Scenario) over it.
+///
+/// The context is `Default::default()` unless given as `context = <expr>`; the expression
+/// is evaluated for every scenario. Invoke the macro once per module.
+///
+/// ```rust,ignore
+/// lit_bit_core::statechart_test!(Airlock);
+/// lit_bit_core::statechart_test!(Airlock, context = Bay::with_pressure(3));
+/// ```
+#[macro_export]
+macro_rules! statechart_test {
+    ($machine:ty) => {
+        $crate::statechart_test!($machine, context = ::core::default::Default::default());
+    };
+    ($machine:ty, context = $context:expr) => {
+        /// Builds the machine under test, checks its initial state and starts a scenario.
+        #[allow(dead_code)]
+        #[track_caller]
+        fn scenario(
+            initial_event: &<$machine as $crate::StateMachine>::Event,
+        ) -> $crate::scenario::Scenario<$machine> {
+            let machine = match <$machine>::new($context, initial_event) {
+                Ok(machine) => machine,
+                Err(error) => panic!("{} failed to start: {error:?}", stringify!($machine)),
+            };
+            let definition = <$machine>::definition();
+            let active = $crate::StateMachine::state(&machine);
+            let configuration =
+                definition.active_configuration::<{ <$machine>::COMPUTE_BUFFER }>(&active);
+            assert!(
+                configuration.contains(&definition.initial_leaf_state),
+                "{} started in {active:?}, outside its initial state {:?}",
+                stringify!($machine),
+                definition.initial_leaf_state
+            );
+            $crate::scenario::Scenario::new(machine)
+        }
+    };
+}
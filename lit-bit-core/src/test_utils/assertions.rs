@@ -0,0 +1,315 @@
+//! Assertions on a machine's active states, written as chart paths.
+//!
+//! [`assert_in_state!`](crate::assert_in_state) and
+//! [`assert_configuration!`](crate::assert_configuration) resolve paths such as
+//! `"Operational::Active"` (or `"Operational.Active"`) with the state-ID enum's
+//! `from_user_path`, and compare them with the active leaf states in any order. A failure
+//! lists both sides as `::`-joined paths instead of state-ID variants:
+//!
+//! ```text
+//! active states differ from the expected configuration
+//!   expected: Audio::Playing, Video::Paused
+//!     actual: Audio::Playing, Video::Stopped
+//!    missing: Video::Paused
+//!      extra: Video::Stopped
+//! ```
+//!
+//! Given the trace of a [`MachineConsole`](crate::console::MachineConsole), the message also
+//! lists its last [`TRACE_SHOWN`] sends:
+//!
+//! ```rust,ignore
+//! assert_configuration!(player, ["Audio::Playing", "Video::Paused"]);
+//! assert_in_state!(console.machine(), "Video::Paused", trace = console.trace());
+//! ```
+//!
+//! The messages are written with `core::fmt` only, so the assertions work in `no_std` test
+//! harnesses too.
+
+use core::fmt;
+
+use crate::console::TraceEntry;
+use crate::{MAX_ACTIVE_REGIONS, UserPath};
+
+/// Most sends a failure message lists from a trace, the most recent ones.
+pub const TRACE_SHOWN: usize = 8;
+
+/// Longest path, in bytes, the assertions can resolve.
+const MAX_PATH_LEN: usize = 128;
+
+/// Asserts that the machine is in the state at a path: an active leaf is that state or one
+/// of its descendants. See the [module docs](crate::test_utils::assertions).
+///
+/// ```rust,ignore
+/// assert_in_state!(machine, "Operational::Active");
+/// assert_in_state!(console.machine(), "Operational", trace = console.trace());
+/// ```
+#[macro_export]
+macro_rules! assert_in_state {
+    ($machine:expr, $path:expr $(,)?) => {
+        $crate::test_utils::assertions::assert_in_state(
+            &$crate::__active_states!($machine),
+            $path,
+            $crate::__no_trace!(),
+        )
+    };
+    ($machine:expr, $path:expr, trace = $trace:expr $(,)?) => {
+        $crate::test_utils::assertions::assert_in_state(
+            &$crate::__active_states!($machine),
+            $path,
+            ::core::option::Option::Some($trace),
+        )
+    };
+}
+
+/// Asserts that the machine's active leaf states are exactly the states at the given
+/// paths, in any order. See the [module docs](crate::test_utils::assertions).
+///
+/// ```rust,ignore
+/// assert_configuration!(player, ["Audio::Playing", "Video::Paused"]);
+/// ```
+#[macro_export]
+macro_rules! assert_configuration {
+    ($machine:expr, $paths:expr $(,)?) => {
+        $crate::test_utils::assertions::assert_configuration(
+            &$crate::__active_states!($machine),
+            &$paths,
+            $crate::__no_trace!(),
+        )
+    };
+    ($machine:expr, $paths:expr, trace = $trace:expr $(,)?) => {
+        $crate::test_utils::assertions::assert_configuration(
+            &$crate::__active_states!($machine),
+            &$paths,
+            ::core::option::Option::Some($trace),
+        )
+    };
+}
+
+/// The active leaf states of a generated machine or a runtime.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __active_states {
+    ($machine:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::StateMachine as _;
+        ($machine).state()
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __no_trace {
+    () => {
+        ::core::option::Option::None::<[&$crate::console::TraceEntry<_, 0>; 0]>
+    };
+}
+
+/// Implementation of [`assert_in_state!`](crate::assert_in_state).
+///
+/// # Panics
+///
+/// Panics if `path` names no state of the chart or no active leaf is within it.
+#[track_caller]
+pub fn assert_in_state<'t, S, I, const N: usize>(actual: &[S], path: &str, trace: Option<I>)
+where
+    S: UserPath,
+    I: IntoIterator<Item = &'t TraceEntry<S, N>>,
+{
+    let failure = match resolve::<S>(path) {
+        None => Failure::UnknownPath(path),
+        Some(state) if actual.iter().any(|&leaf| within(leaf, state)) => return,
+        Some(_) => Failure::NotInState(path),
+    };
+    fail(failure, actual, trace);
+}
+
+/// Implementation of [`assert_configuration!`](crate::assert_configuration).
+///
+/// # Panics
+///
+/// Panics if a path names no state of the chart or the active leaves differ from the
+/// states at `paths`.
+#[track_caller]
+pub fn assert_configuration<'t, S, I, const N: usize>(
+    actual: &[S],
+    paths: &[&str],
+    trace: Option<I>,
+) where
+    S: UserPath,
+    I: IntoIterator<Item = &'t TraceEntry<S, N>>,
+{
+    let mut missing = false;
+    for &path in paths {
+        match resolve::<S>(path) {
+            None => fail(Failure::UnknownPath(path), actual, trace),
+            Some(state) => missing |= !actual.contains(&state),
+        }
+    }
+    let extra = actual
+        .iter()
+        .any(|&leaf| !paths.iter().any(|&path| resolve(path) == Some(leaf)));
+    if missing || extra {
+        fail(Failure::Configuration(paths), actual, trace);
+    }
+}
+
+/// Looks up `path`, with its states separated by `::` or `.`.
+fn resolve<S: UserPath>(path: &str) -> Option<S> {
+    let mut dotted = heapless::String::<MAX_PATH_LEN>::new();
+    for (position, segment) in segments(path).enumerate() {
+        if position > 0 {
+            dotted.push('.').ok()?;
+        }
+        dotted.push_str(segment).ok()?;
+    }
+    S::from_user_path(&dotted)
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split("::").flat_map(|part| part.split('.'))
+}
+
+/// Returns `true` if `leaf` is `state` or one of its descendants.
+fn within<S: UserPath>(leaf: S, state: S) -> bool {
+    leaf.user_path()
+        .strip_prefix(state.user_path())
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+enum Failure<'a> {
+    UnknownPath(&'a str),
+    NotInState(&'a str),
+    Configuration(&'a [&'a str]),
+}
+
+#[track_caller]
+fn fail<'t, S, I, const N: usize>(failure: Failure<'_>, actual: &[S], trace: Option<I>) -> !
+where
+    S: UserPath,
+    I: IntoIterator<Item = &'t TraceEntry<S, N>>,
+{
+    let mut recent = heapless::Deque::<&TraceEntry<S, N>, TRACE_SHOWN>::new();
+    let traced = trace.map(|entries| {
+        for entry in entries {
+            if recent.is_full() {
+                recent.pop_front();
+            }
+            let _ = recent.push_back(entry);
+        }
+        &recent
+    });
+    panic!(
+        "{}",
+        Report {
+            failure,
+            actual,
+            trace: traced,
+        }
+    )
+}
+
+/// A failure message; see the [module docs](self).
+struct Report<'a, 't, S, const N: usize> {
+    failure: Failure<'a>,
+    actual: &'a [S],
+    trace: Option<&'a heapless::Deque<&'t TraceEntry<S, N>, TRACE_SHOWN>>,
+}
+
+impl<S: UserPath, const N: usize> fmt::Display for Report<'_, '_, S, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.failure {
+            Failure::UnknownPath(path) => {
+                write!(f, "`{}` names no state of the chart", Breadcrumbs(path))?;
+            }
+            Failure::NotInState(path) => {
+                write!(f, "machine is not in the expected state")?;
+                write!(f, "\n  expected: {}", Breadcrumbs(path))?;
+            }
+            Failure::Configuration(paths) => {
+                write!(f, "active states differ from the expected configuration")?;
+                write!(f, "\n  expected:")?;
+                write_list(f, paths.iter().copied())?;
+            }
+        }
+
+        // Sorted, so that the message does not depend on the order of the leaves.
+        let mut actual = heapless::Vec::<&'static str, MAX_ACTIVE_REGIONS>::new();
+        for &leaf in self.actual {
+            let _ = actual.push(leaf.user_path());
+        }
+        actual.sort_unstable();
+        write!(f, "\n    actual:")?;
+        write_list(f, actual.iter().copied())?;
+
+        if let Failure::Configuration(paths) = self.failure {
+            let expected = |leaf: &str| {
+                paths
+                    .iter()
+                    .any(|&path| resolve::<S>(path).is_some_and(|state| state.user_path() == leaf))
+            };
+            let mut missing = paths
+                .iter()
+                .copied()
+                .filter(|&path| {
+                    resolve::<S>(path).is_none_or(|state| !self.actual.contains(&state))
+                })
+                .peekable();
+            if missing.peek().is_some() {
+                write!(f, "\n   missing:")?;
+                write_list(f, missing)?;
+            }
+            let mut extra = actual
+                .iter()
+                .copied()
+                .filter(|leaf| !expected(leaf))
+                .peekable();
+            if extra.peek().is_some() {
+                write!(f, "\n     extra:")?;
+                write_list(f, extra)?;
+            }
+        }
+
+        if let Some(trace) = self.trace {
+            if trace.is_empty() {
+                write!(f, "\nno sends traced")?;
+            } else {
+                write!(f, "\nlast sends, oldest first:")?;
+            }
+            for entry in trace {
+                write!(f, "\n  #{} {:?}:", entry.seq, entry.result)?;
+                write_list(f, entry.diff.exited.iter().map(|state| state.user_path()))?;
+                write!(f, " ->")?;
+                write_list(f, entry.diff.entered.iter().map(|state| state.user_path()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes ` a, b, c`, each path as breadcrumbs, or ` -` when there are none.
+fn write_list<'p>(f: &mut fmt::Formatter<'_>, paths: impl Iterator<Item = &'p str>) -> fmt::Result {
+    let mut paths = paths.peekable();
+    if paths.peek().is_none() {
+        return f.write_str(" -");
+    }
+    for (position, path) in paths.enumerate() {
+        let separator = if position == 0 { " " } else { ", " };
+        write!(f, "{separator}{}", Breadcrumbs(path))?;
+    }
+    Ok(())
+}
+
+/// A path written with `::` between its states, whichever separator it was given with.
+struct Breadcrumbs<'a>(&'a str);
+
+impl fmt::Display for Breadcrumbs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, segment) in segments(self.0).enumerate() {
+            if position > 0 {
+                f.write_str("::")?;
+            }
+            f.write_str(segment)?;
+        }
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@
 //! forwards all calls to the inner actor while emitting probe events.
 
 use super::probes::{ProbeEvent, create_probe_string};
-use crate::actor::{Actor, ActorError};
+use crate::actor::{Actor, ActorError, StopReason};
 use core::marker::PhantomData;
 
 #[cfg(any(feature = "std", feature = "alloc"))]
@@ -199,11 +199,17 @@ impl<A: Actor> Actor for InstrumentedActor<A> {
     }
 
     fn on_stop(self) -> Result<(), ActorError> {
+        // Called directly rather than by a loop, which is how a closed mailbox ended one
+        // before loops passed a reason.
+        self.on_stop_with_reason(StopReason::SendersDropped)
+    }
+
+    fn on_stop_with_reason(self, reason: StopReason) -> Result<(), ActorError> {
         // Emit stop event before forwarding (since we consume self)
-        self.emit_event(ProbeEvent::ActorStopped);
+        self.emit_event(ProbeEvent::ActorStopped { reason });
 
         // Forward to inner actor
-        self.inner.on_stop()
+        self.inner.on_stop_with_reason(reason)
     }
 
     fn on_panic(&self, info: &core::panic::PanicInfo) -> crate::actor::RestartStrategy {
@@ -1,9 +1,14 @@
 //! Test utilities for async actors across Tokio and Embassy runtimes
 //!
 //! This module provides cross-runtime test infrastructure with deterministic scheduling
-//! and zero-overhead probes for testing async actor systems. Only available with
-//! `test` or `test-probes` feature to ensure zero cost in production builds.
+//! and zero-overhead probes for testing async actor systems. The actor utilities are only
+//! available with the `test` or `test-probes` feature to ensure zero cost in production
+//! builds.
+//!
+//! The state assertions in [`assertions`] are always available: they cost nothing unless
+//! a test uses them, and work without async or `std`.
 
+pub mod assertions;
 #[cfg(any(test, feature = "test-probes"))]
 pub mod instrumented_actor;
 #[cfg(any(test, feature = "test-probes"))]
@@ -8,6 +8,8 @@ use core::marker::PhantomData;
 #[cfg(feature = "async-tokio")]
 use core::time::Duration;
 
+use crate::actor::StopReason;
+
 // Use appropriate string types depending on the environment
 #[cfg(any(feature = "std", feature = "alloc"))]
 type ProbeString = alloc::string::String;
@@ -32,8 +34,8 @@ pub enum ProbeEvent {
     },
     /// Actor started successfully
     ActorStarted,
-    /// Actor stopped (gracefully or due to error)
-    ActorStopped,
+    /// Actor stopped (gracefully or due to error), and why its loop ended
+    ActorStopped { reason: StopReason },
     /// Actor panicked with error details
     PanicOccurred { error: ProbeString },
 }
@@ -162,18 +164,44 @@ impl<A> ActorProbe<A> {
         self.expect_event(ProbeEvent::ActorStarted).await
     }
 
-    /// Wait for the actor to stop with timeout
+    /// Wait for the actor to stop with timeout, and return why it stopped
     ///
     /// # Examples
     /// ```rust,no_run
     /// # use lit_bit_core::test_utils::ActorProbe;
+    /// # use lit_bit_core::actor::StopReason;
     /// # async fn example(mut probe: ActorProbe<()>) -> Result<(), Box<dyn std::error::Error>> {
-    /// probe.expect_actor_stopped().await?;
+    /// assert_eq!(probe.expect_actor_stopped().await?, StopReason::SendersDropped);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn expect_actor_stopped(&mut self) -> Result<(), TestError> {
-        self.expect_event(ProbeEvent::ActorStopped).await
+    pub async fn expect_actor_stopped(&mut self) -> Result<StopReason, TestError> {
+        let stopped = async {
+            loop {
+                if let ProbeEvent::ActorStopped { reason } = self.next_event().await? {
+                    return Ok(reason);
+                }
+            }
+        };
+
+        #[cfg(feature = "async-tokio")]
+        {
+            tokio::time::timeout(Duration::from_secs(5), stopped)
+                .await
+                .map_err(|_| TestError::Timeout)?
+        }
+
+        #[cfg(feature = "async-embassy")]
+        {
+            embassy_time::with_timeout(embassy_time::Duration::from_secs(5), stopped)
+                .await
+                .map_err(|_| TestError::Timeout)?
+        }
+
+        #[cfg(not(any(feature = "async-tokio", feature = "async-embassy")))]
+        {
+            stopped.await
+        }
     }
 
     /// Wait for a panic event and return the error details
@@ -514,7 +542,9 @@ mod tests {
     fn probe_event_equality_works() {
         let event1 = ProbeEvent::ActorStarted;
         let event2 = ProbeEvent::ActorStarted;
-        let event3 = ProbeEvent::ActorStopped;
+        let event3 = ProbeEvent::ActorStopped {
+            reason: StopReason::SendersDropped,
+        };
 
         assert_eq!(event1, event2);
         assert_ne!(event1, event3);
@@ -597,7 +627,9 @@ mod tests {
         // Test the core event preservation logic that applies to all runtime variants
         // This doesn't require unsafe code or specific runtime setup
         {
-            let lifecycle_event = ProbeEvent::ActorStopped;
+            let lifecycle_event = ProbeEvent::ActorStopped {
+                reason: StopReason::StopRequested,
+            };
             let mut peeked_event: Option<ProbeEvent> = Some(lifecycle_event.clone());
 
             // Test the same behavior that's used in ActorProbe
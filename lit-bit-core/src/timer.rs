@@ -4,8 +4,14 @@
 //! This module implements the research findings for supporting `after(Duration)`
 //! transitions across different async runtimes.
 
+#[cfg(not(feature = "portable-atomic"))]
+use core::sync::atomic::{AtomicU32, Ordering};
 use core::time::Duration;
 
+use crate::runtime::{MonotonicClock, Ticks};
+#[cfg(feature = "portable-atomic")]
+use portable_atomic::{AtomicU32, Ordering};
+
 /// Converts a Duration to u64 microseconds with overflow protection.
 ///
 /// This helper function ensures consistent behavior when converting Duration
@@ -57,6 +63,243 @@ pub trait TimerService {
     /// Returns a future that resolves after the given duration has elapsed.
     /// The implementation is runtime-specific but the API remains consistent.
     fn sleep(duration: Duration) -> Self::SleepFuture;
+
+    /// Arms a timer for `duration` and returns the token that identifies this arming.
+    ///
+    /// The token is what the runtime stores for the owning state; it arms one on every
+    /// entry of a state with an `after` transition (see `Runtime::entered_timers`). The
+    /// driver then awaits [`TimerService::sleep`] with [`TimerToken::duration`] and reports
+    /// the expiry with `Runtime::timer_fired`.
+    fn arm(duration: Duration) -> TimerToken {
+        TimerToken::new(duration)
+    }
+
+    /// Cancels the timer identified by `token`.
+    ///
+    /// Cancelling is idempotent: cancelling an already cancelled or already fired token is
+    /// a no-op. The default implementation does nothing: the task sleeping on the token
+    /// (see `spawn_expiry`) is detached and still wakes at its deadline, but the runtime
+    /// no longer tracks the token and ignores its expiry. Services backed by hardware
+    /// timers can override it to stop the timer early.
+    fn cancel(_token: TimerToken) {}
+}
+
+static NEXT_TIMER_TOKEN: AtomicU32 = AtomicU32::new(0);
+
+fn next_timer_token_id() -> u32 {
+    #[cfg(any(target_has_atomic = "32", feature = "portable-atomic"))]
+    {
+        NEXT_TIMER_TOKEN.fetch_add(1, Ordering::Relaxed)
+    }
+    // Targets without atomic read-modify-write (e.g. thumbv6m) arm timers from one context
+    // unless `portable-atomic` is enabled.
+    #[cfg(not(any(target_has_atomic = "32", feature = "portable-atomic")))]
+    {
+        let id = NEXT_TIMER_TOKEN.load(Ordering::Relaxed);
+        NEXT_TIMER_TOKEN.store(id.wrapping_add(1), Ordering::Relaxed);
+        id
+    }
+}
+
+/// Identifies one arming of a timer.
+///
+/// Every call to [`TimerService::arm`] yields a distinct token, so an expiry can be matched
+/// against exactly the arming that produced it: re-entering a state arms a new token, and
+/// the expiry of the token from the previous visit is recognised as stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerToken {
+    id: u32,
+    duration: Duration,
+}
+
+impl TimerToken {
+    /// Creates a fresh token for a timer of `duration`.
+    #[must_use]
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            id: next_timer_token_id(),
+            duration,
+        }
+    }
+
+    /// Returns the unique id of this arming.
+    #[must_use]
+    pub const fn id(self) -> u32 {
+        self.id
+    }
+
+    /// Returns the duration the timer was armed with.
+    #[must_use]
+    pub const fn duration(self) -> Duration {
+        self.duration
+    }
+}
+
+/// A timer armed on behalf of a state, as tracked by the runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArmedTimer<S> {
+    /// The state whose `after` transition the timer drives.
+    pub state: S,
+    /// Index of the `after` transition within the state (the `timer_id` of `TimerFired`).
+    pub timer_id: usize,
+    pub token: TimerToken,
+    /// When the timer was tracked, on the table's clock; `None` if it had none then.
+    pub armed_at: Option<Ticks>,
+}
+
+/// Fixed-capacity table of the timers armed for the currently active states.
+///
+/// There is at most one entry per `(state, timer_id)` pair. Entries are removed when the
+/// timer is cancelled, when it fires, or when its state is exited, so a token that is no
+/// longer in the table is stale and its expiry must be ignored.
+#[derive(Clone)]
+pub struct ArmedTimers<S, const N: usize> {
+    entries: heapless::Vec<ArmedTimer<S>, N>,
+    /// Times the entries for [`ArmedTimers::remaining`], with the length of one tick.
+    clock: Option<(&'static (dyn MonotonicClock + Sync), Duration)>,
+}
+
+impl<S: Copy + PartialEq, const N: usize> ArmedTimers<S, N> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: heapless::Vec::new(),
+            clock: None,
+        }
+    }
+
+    /// Times timers tracked from now on with `clock`, one tick of which lasts `tick`.
+    pub fn set_clock(&mut self, clock: &'static (dyn MonotonicClock + Sync), tick: Duration) {
+        self.clock = Some((clock, tick));
+    }
+
+    /// Returns how long `timer` has left before it is due: its full duration if it was
+    /// tracked without a clock, and zero once it is overdue.
+    #[must_use]
+    pub fn remaining(&self, timer: &ArmedTimer<S>) -> Duration {
+        let (Some((clock, tick)), Some(armed_at)) = (self.clock, timer.armed_at) else {
+            return timer.token.duration();
+        };
+        let elapsed_ticks = clock.now().saturating_sub(armed_at);
+        let elapsed = tick.saturating_mul(u32::try_from(elapsed_ticks).unwrap_or(u32::MAX));
+        timer.token.duration().saturating_sub(elapsed)
+    }
+
+    /// Tracks `token` for `(state, timer_id)`.
+    ///
+    /// Returns the token this one supersedes, if the pair was already armed; the old token
+    /// is no longer tracked and therefore effectively cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns the new token back if the table is full.
+    pub fn track(
+        &mut self,
+        state: S,
+        timer_id: usize,
+        token: TimerToken,
+    ) -> Result<Option<TimerToken>, TimerToken> {
+        let armed_at = self.clock.map(|(clock, _)| clock.now());
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.state == state && entry.timer_id == timer_id)
+        {
+            entry.armed_at = armed_at;
+            return Ok(Some(core::mem::replace(&mut entry.token, token)));
+        }
+        self.entries
+            .push(ArmedTimer {
+                state,
+                timer_id,
+                token,
+                armed_at,
+            })
+            .map(|()| None)
+            .map_err(|entry| entry.token)
+    }
+
+    /// Arms a fresh token with [`TimerService::arm`] for every timer `after_timers` lists for
+    /// a state in `entered`, numbering each state's timers in the order they are listed.
+    pub fn arm_entered(&mut self, after_timers: &[(S, Duration)], entered: &[S]) {
+        for &state in entered {
+            let durations = after_timers
+                .iter()
+                .filter(|(owner, _)| *owner == state)
+                .map(|&(_, duration)| duration);
+            for (timer_id, duration) in durations.enumerate() {
+                let token = <Timer as TimerService>::arm(duration);
+                if self.track(state, timer_id, token).is_err() {
+                    debug_assert!(false, "More armed timers than the table holds");
+                }
+            }
+        }
+    }
+
+    /// Stops tracking `token`. Returns `true` only for the call that actually cancelled it,
+    /// so repeated cancellation is harmless.
+    pub fn cancel(&mut self, token: TimerToken) -> bool {
+        self.take(token).is_some()
+    }
+
+    /// Stops tracking every timer owned by `state`, passing each cancelled token to
+    /// `on_cancel`.
+    pub fn cancel_state(&mut self, state: S, mut on_cancel: impl FnMut(TimerToken)) {
+        self.entries.retain(|entry| {
+            let owned = entry.state == state;
+            if owned {
+                on_cancel(entry.token);
+            }
+            !owned
+        });
+    }
+
+    /// Consumes the expiry of `token`, returning the timer it belongs to if it is still
+    /// armed and `None` if it was cancelled, already fired, or never tracked.
+    pub fn fire(&mut self, token: TimerToken) -> Option<ArmedTimer<S>> {
+        self.take(token)
+    }
+
+    /// Returns `true` if `token` is still armed.
+    #[must_use]
+    pub fn is_armed(&self, token: TimerToken) -> bool {
+        self.entries.iter().any(|entry| entry.token == token)
+    }
+
+    /// Iterates over the armed timers.
+    pub fn iter(&self) -> impl Iterator<Item = &ArmedTimer<S>> {
+        self.entries.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn take(&mut self, token: TimerToken) -> Option<ArmedTimer<S>> {
+        let index = self.entries.iter().position(|entry| entry.token == token)?;
+        Some(self.entries.swap_remove(index))
+    }
+}
+
+impl<S: core::fmt::Debug, const N: usize> core::fmt::Debug for ArmedTimers<S, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ArmedTimers")
+            .field("entries", &self.entries)
+            .field("clock", &self.clock.is_some())
+            .finish()
+    }
+}
+
+impl<S: Copy + PartialEq, const N: usize> Default for ArmedTimers<S, N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // Tokio implementation - only available when async-tokio feature is enabled
@@ -72,6 +315,20 @@ impl TimerService for TokioTimer {
     }
 }
 
+/// Spawns a detached Tokio task that sleeps for `token`'s duration and then passes the token
+/// to `expired`, which hands it back to the machine (for example through its actor's
+/// mailbox) to be reported with `Runtime::timer_fired`.
+#[cfg(feature = "async-tokio")]
+pub fn spawn_expiry<F>(token: TimerToken, expired: F)
+where
+    F: FnOnce(TimerToken) + Send + 'static,
+{
+    tokio::spawn(async move {
+        TokioTimer::sleep(token.duration()).await;
+        expired(token);
+    });
+}
+
 // Embassy implementation - only available when async-embassy feature is enabled
 #[cfg(feature = "async-embassy")]
 pub struct EmbassyTimer;
@@ -253,6 +510,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn timer_tokens_are_unique_per_arming() {
+        let first = TestTimer::arm(Duration::from_millis(10));
+        let second = TestTimer::arm(Duration::from_millis(10));
+
+        assert_ne!(first, second);
+        assert_eq!(first.duration(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn armed_timers_cancel_is_idempotent_and_stale_expiry_is_ignored() {
+        let mut timers: ArmedTimers<u8, 4> = ArmedTimers::new();
+        let token = TestTimer::arm(Duration::from_secs(1));
+        assert_eq!(timers.track(1, 0, token), Ok(None));
+
+        assert!(timers.cancel(token));
+        assert!(!timers.cancel(token));
+        TestTimer::cancel(token);
+        assert_eq!(timers.fire(token), None);
+        assert!(timers.is_empty());
+    }
+
+    #[test]
+    fn arm_entered_arms_each_listed_timer_of_the_entered_states() {
+        let mut timers: ArmedTimers<u8, 4> = ArmedTimers::new();
+        let after_timers = [
+            (1, Duration::from_millis(10)),
+            (2, Duration::from_millis(20)),
+            (1, Duration::from_millis(30)),
+        ];
+        timers.arm_entered(&after_timers, &[1, 3]);
+
+        let armed = timers
+            .iter()
+            .map(|timer| (timer.state, timer.timer_id, timer.token.duration()));
+        assert!(armed.eq([
+            (1, 0, Duration::from_millis(10)),
+            (1, 1, Duration::from_millis(30)),
+        ]));
+    }
+
+    #[test]
+    fn rearming_supersedes_previous_token() {
+        let mut timers: ArmedTimers<u8, 4> = ArmedTimers::new();
+        let old = TestTimer::arm(Duration::from_secs(1));
+        let new = TestTimer::arm(Duration::from_secs(1));
+        timers.track(1, 0, old).unwrap();
+
+        assert_eq!(timers.track(1, 0, new), Ok(Some(old)));
+        assert_eq!(timers.fire(old), None);
+        assert_eq!(
+            timers.fire(new),
+            Some(ArmedTimer {
+                state: 1,
+                timer_id: 0,
+                token: new,
+                armed_at: None,
+            })
+        );
+        // A timer fires at most once.
+        assert_eq!(timers.fire(new), None);
+    }
+
+    #[test]
+    fn remaining_time_counts_down_on_the_table_clock() {
+        use core::sync::atomic::AtomicU64;
+
+        struct Millis(AtomicU64);
+        impl MonotonicClock for Millis {
+            fn now(&self) -> Ticks {
+                self.0.load(core::sync::atomic::Ordering::Relaxed)
+            }
+        }
+        static CLOCK: Millis = Millis(AtomicU64::new(1_000));
+
+        let mut timers: ArmedTimers<u8, 4> = ArmedTimers::new();
+        let untimed = TestTimer::arm(Duration::from_millis(300));
+        timers.track(1, 0, untimed).unwrap();
+        timers.set_clock(&CLOCK, Duration::from_millis(1));
+        let timed = TestTimer::arm(Duration::from_millis(300));
+        timers.track(2, 0, timed).unwrap();
+
+        CLOCK.0.store(1_120, core::sync::atomic::Ordering::Relaxed);
+        let remaining = |state| {
+            let timer = timers.iter().find(|timer| timer.state == state).unwrap();
+            timers.remaining(timer)
+        };
+        assert_eq!(remaining(2), Duration::from_millis(180));
+        // Tracked before the clock was set, so it cannot be timed.
+        assert_eq!(remaining(1), Duration::from_millis(300));
+
+        CLOCK.0.store(5_000, core::sync::atomic::Ordering::Relaxed);
+        assert_eq!(remaining(2), Duration::ZERO);
+    }
+
+    #[test]
+    fn cancel_state_only_drops_that_states_timers() {
+        let mut timers: ArmedTimers<u8, 1> = ArmedTimers::new();
+        let kept = TestTimer::arm(Duration::from_secs(1));
+        timers.track(2, 0, kept).unwrap();
+        let overflow = TestTimer::arm(Duration::from_secs(1));
+        assert_eq!(timers.track(1, 0, overflow), Err(overflow));
+
+        let mut cancelled = 0;
+        timers.cancel_state(1, |_| cancelled += 1);
+        assert_eq!(cancelled, 0);
+        assert!(timers.is_armed(kept));
+
+        timers.cancel_state(2, |token| assert_eq!(token, kept));
+        assert!(!timers.is_armed(kept));
+    }
+
     #[test]
     fn duration_conversion_works() {
         let duration = Duration::from_secs(5);
@@ -0,0 +1,761 @@
+//! Structural chart diffing and snapshot migration for live chart upgrades.
+//!
+//! When a new firmware or service version ships a modified chart, a persisted
+//! [`StateSnapshot`] taken under the old definition may name states that no longer exist.
+//! This module compares two type-erased chart definitions and rewrites snapshots so they
+//! can be restored under the new one:
+//!
+//! 1. Describe both charts as [`ErasedDefinition`]s. Machines generated by `statechart!`
+//!    provide `erased_definition()`; tooling that only has source code (such as the
+//!    `lit-bit-cli diff` subcommand) can build one with [`ErasedDefinition::new`].
+//! 2. [`diff_definitions`] reports added, removed and renamed states (by full dotted path)
+//!    and changed transitions.
+//! 3. [`migrate_snapshot`] maps the snapshot's active states through the diff, resolving
+//!    removed states with a [`MigrationPolicy`].
+//!
+//! ```rust,ignore
+//! let diff = diff_definitions(&OldMachine::erased_definition(), &NewMachine::erased_definition());
+//! let migrated = migrate_snapshot(&persisted, &diff, &MigrationPolicy::Parent)?;
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use crate::runtime::MachineDefinition;
+
+/// One state of an [`ErasedDefinition`], identified by its dotted path (`"Parent.Child"`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErasedState {
+    pub path: String,
+    pub parent: Option<String>,
+    pub initial_child: Option<String>,
+    pub is_parallel: bool,
+}
+
+impl ErasedState {
+    /// Returns the last segment of the state's path.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        leaf_name(&self.path)
+    }
+}
+
+/// One transition of an [`ErasedDefinition`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErasedTransition {
+    pub from: String,
+    pub to: String,
+    /// Source text of the event pattern (e.g. `"Event::Start"`), if known. Definitions
+    /// built from a compiled [`MachineDefinition`] do not carry patterns; their transitions
+    /// are told apart by their position among the transitions of the same source state.
+    pub event: Option<String>,
+    pub guarded: bool,
+}
+
+/// A chart definition with state ids and function pointers erased to strings, so that
+/// charts of different machine types (or charts read from source) can be compared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErasedDefinition {
+    states: Vec<ErasedState>,
+    transitions: Vec<ErasedTransition>,
+    initial: String,
+}
+
+impl ErasedDefinition {
+    /// Builds a definition from its parts. `initial` is the path of the top-level initial
+    /// state.
+    #[must_use]
+    pub fn new(
+        states: Vec<ErasedState>,
+        transitions: Vec<ErasedTransition>,
+        initial: impl Into<String>,
+    ) -> Self {
+        Self {
+            states,
+            transitions,
+            initial: initial.into(),
+        }
+    }
+
+    /// Erases a compiled machine definition; `name_of` renders state ids as dotted paths
+    /// (machines generated by `statechart!` pass `StateId::user_path`).
+    pub fn from_definition<S, E, C>(
+        machine_def: &MachineDefinition<S, E, C>,
+        name_of: fn(S) -> &'static str,
+    ) -> Self
+    where
+        S: Copy + Clone + PartialEq + Eq + core::hash::Hash + 'static,
+        E: Clone + PartialEq + Eq + core::hash::Hash + 'static,
+        C: Clone + 'static,
+    {
+        let states = machine_def
+            .states
+            .iter()
+            .map(|node| ErasedState {
+                path: name_of(node.id).to_string(),
+                parent: node.parent.map(|parent| name_of(parent).to_string()),
+                initial_child: node.initial_child.map(|child| name_of(child).to_string()),
+                is_parallel: node.is_parallel,
+            })
+            .collect();
+        let transitions = machine_def
+            .transitions
+            .iter()
+            .map(|transition| ErasedTransition {
+                from: name_of(transition.from_state).to_string(),
+                to: name_of(transition.to_state).to_string(),
+                event: None,
+                guarded: transition.is_guarded(),
+            })
+            .collect();
+        let mut initial = machine_def.initial_leaf_state;
+        while let Some(parent) = machine_def.get_parent_of(initial) {
+            initial = parent;
+        }
+        Self::new(states, transitions, name_of(initial))
+    }
+
+    /// Iterates over the states in definition order.
+    pub fn states(&self) -> impl Iterator<Item = &ErasedState> {
+        self.states.iter()
+    }
+
+    /// Iterates over the transitions in definition order.
+    pub fn transitions(&self) -> impl Iterator<Item = &ErasedTransition> {
+        self.transitions.iter()
+    }
+
+    /// Returns the path of the top-level initial state.
+    #[must_use]
+    pub fn initial(&self) -> &str {
+        &self.initial
+    }
+
+    /// Looks up a state by its dotted path.
+    #[must_use]
+    pub fn state(&self, path: &str) -> Option<&ErasedState> {
+        self.states.iter().find(|state| state.path == path)
+    }
+
+    /// Iterates over the direct children of `path` in definition order.
+    pub fn children<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a ErasedState> + 'a {
+        self.states
+            .iter()
+            .filter(move |state| state.parent.as_deref() == Some(path))
+    }
+
+    /// Returns a hash of the chart's structure: states, hierarchy, initial states and
+    /// transitions. Two definitions with the same hash have nothing for
+    /// [`diff_definitions`] to report.
+    #[must_use]
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::default();
+        let mut states: Vec<_> = self.states.iter().collect();
+        states.sort_by(|a, b| a.path.cmp(&b.path));
+        states.hash(&mut hasher);
+        self.transitions.hash(&mut hasher);
+        self.initial.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the leaves entered when `path` is entered: its initial descendants, and for
+    /// parallel states the initial descendants of every region.
+    fn initial_leaves(&self, path: &str) -> Vec<String> {
+        let Some(state) = self.state(path) else {
+            return Vec::new();
+        };
+        let mut children = self.children(path).peekable();
+        if children.peek().is_none() {
+            return vec![state.path.clone()];
+        }
+        if state.is_parallel {
+            return children
+                .flat_map(|region| self.initial_leaves(&region.path))
+                .collect();
+        }
+        match &state.initial_child {
+            Some(initial) => self.initial_leaves(initial),
+            None => children
+                .next()
+                .map(|first| self.initial_leaves(&first.path))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Shape used by the rename heuristics: the parallel flag, the number of children and
+    /// the outgoing events, none of which change when states are renamed.
+    fn shape_hash(&self, path: &str) -> u64 {
+        let mut hasher = Fnv1a::default();
+        self.state(path)
+            .map(|state| state.is_parallel)
+            .hash(&mut hasher);
+        self.children(path).count().hash(&mut hasher);
+        let mut events: Vec<_> = self
+            .transitions
+            .iter()
+            .filter(|transition| transition.from == path)
+            .map(|transition| (transition.event.as_deref(), transition.guarded))
+            .collect();
+        events.sort_unstable();
+        events.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// 64-bit FNV-1a; unlike `DefaultHasher` its output is stable across Rust releases.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+fn leaf_name(path: &str) -> &str {
+    path.rsplit('.').next().unwrap_or(path)
+}
+
+fn parent_path(path: &str) -> Option<&str> {
+    path.rsplit_once('.').map(|(parent, _)| parent)
+}
+
+/// A state whose path changed between two definitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// A transition that differs between two definitions. Transitions of the old definition
+/// are reported with renamed states already mapped to their new paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransitionChange {
+    Added(ErasedTransition),
+    Removed(ErasedTransition),
+    /// Same source state and event, but a different target or guard.
+    Changed {
+        old: ErasedTransition,
+        new: ErasedTransition,
+    },
+}
+
+/// Differences between two chart definitions, as computed by [`diff_definitions`].
+#[derive(Debug, Clone)]
+pub struct DefinitionDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub renamed: Vec<StateRename>,
+    pub transitions: Vec<TransitionChange>,
+    target: ErasedDefinition,
+}
+
+impl DefinitionDiff {
+    /// Returns `true` if the two definitions are structurally identical.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.transitions.is_empty()
+    }
+
+    /// Returns the new path of `path`, following renames; `None` if it was removed.
+    #[must_use]
+    pub fn map_state(&self, path: &str) -> Option<&str> {
+        if let Some(rename) = self.renamed.iter().find(|rename| rename.from == path) {
+            return Some(&rename.to);
+        }
+        if self.removed.iter().any(|removed| removed == path) {
+            return None;
+        }
+        self.target.state(path).map(|state| state.path.as_str())
+    }
+}
+
+impl core::fmt::Display for DefinitionDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no structural changes");
+        }
+        let describe = |transition: &ErasedTransition| {
+            let event = transition.event.as_deref().unwrap_or("<event>");
+            let guard = if transition.guarded { " [guard]" } else { "" };
+            format!(
+                "{}: on {event}{guard} => {}",
+                transition.from, transition.to
+            )
+        };
+        let mut lines = Vec::new();
+        lines.extend(self.removed.iter().map(|path| format!("- state {path}")));
+        lines.extend(self.added.iter().map(|path| format!("+ state {path}")));
+        lines.extend(
+            self.renamed
+                .iter()
+                .map(|rename| format!("~ state {} -> {}", rename.from, rename.to)),
+        );
+        for change in &self.transitions {
+            lines.push(match change {
+                TransitionChange::Removed(transition) => format!("- {}", describe(transition)),
+                TransitionChange::Added(transition) => format!("+ {}", describe(transition)),
+                TransitionChange::Changed { old, new } => {
+                    format!("~ {} (was {})", describe(new), describe(old))
+                }
+            });
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Compares two chart definitions.
+///
+/// States are matched by full dotted path. A removed state is reported as renamed to an
+/// added one when the match is unambiguous, trying in order:
+///
+/// - the same name under the (possibly renamed) same parent, which carries a parent rename
+///   over to its children;
+/// - a different name under the same parent, with the same shape (parallel flag, number
+///   of children, outgoing events);
+/// - the same name and shape under a different parent (a moved state).
+///
+/// Transitions are matched by source state and event after applying the renames.
+#[must_use]
+pub fn diff_definitions(old: &ErasedDefinition, new: &ErasedDefinition) -> DefinitionDiff {
+    let mut diff = DefinitionDiff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        renamed: Vec::new(),
+        transitions: Vec::new(),
+        target: new.clone(),
+    };
+    if old.structural_hash() == new.structural_hash() {
+        return diff;
+    }
+
+    let mut removed: Vec<&ErasedState> = old
+        .states()
+        .filter(|state| new.state(&state.path).is_none())
+        .collect();
+    // Parents first, so their renames are known when their children are matched.
+    removed.sort_by_key(|state| state.path.matches('.').count());
+    let mut unclaimed: Vec<&ErasedState> = new
+        .states()
+        .filter(|state| old.state(&state.path).is_none())
+        .collect();
+
+    let mut renames: HashMap<String, String> = HashMap::new();
+    for state in removed {
+        let mapped_parent = state
+            .parent
+            .as_deref()
+            .map(|parent| renames.get(parent).map_or(parent, String::as_str));
+        let shape = old.shape_hash(&state.path);
+        let same_parent = |candidate: &&ErasedState| candidate.parent.as_deref() == mapped_parent;
+        let same_shape = |candidate: &&ErasedState| new.shape_hash(&candidate.path) == shape;
+        let same_name = |candidate: &&ErasedState| candidate.name() == state.name();
+
+        let matched = unique_match(&unclaimed, |c| same_parent(c) && same_name(c))
+            .or_else(|| unique_match(&unclaimed, |c| same_parent(c) && same_shape(c)))
+            .or_else(|| unique_match(&unclaimed, |c| same_name(c) && same_shape(c)));
+        match matched {
+            Some(index) => {
+                let to = unclaimed.remove(index).path.clone();
+                renames.insert(state.path.clone(), to.clone());
+                diff.renamed.push(StateRename {
+                    from: state.path.clone(),
+                    to,
+                });
+            }
+            None => diff.removed.push(state.path.clone()),
+        }
+    }
+    let unclaimed: HashSet<&str> = unclaimed.iter().map(|state| state.path.as_str()).collect();
+    diff.added = new
+        .states()
+        .filter(|state| unclaimed.contains(state.path.as_str()))
+        .map(|state| state.path.clone())
+        .collect();
+    // Report removals in definition order rather than depth order.
+    let removed_paths: HashSet<String> = diff.removed.drain(..).collect();
+    diff.removed = old
+        .states()
+        .filter(|state| removed_paths.contains(&state.path))
+        .map(|state| state.path.clone())
+        .collect();
+
+    let rename = |path: &str| {
+        renames
+            .get(path)
+            .cloned()
+            .unwrap_or_else(|| path.to_string())
+    };
+    let old_transitions: Vec<ErasedTransition> = old
+        .transitions()
+        .map(|transition| ErasedTransition {
+            from: rename(&transition.from),
+            to: rename(&transition.to),
+            ..transition.clone()
+        })
+        .collect();
+    let old_keyed = key_transitions(&old_transitions);
+    let new_transitions: Vec<ErasedTransition> = new.transitions().cloned().collect();
+    let new_keyed = key_transitions(&new_transitions);
+    for (key, old_transition) in &old_keyed {
+        match new_keyed.iter().find(|(new_key, _)| new_key == key) {
+            None => diff
+                .transitions
+                .push(TransitionChange::Removed((*old_transition).clone())),
+            Some((_, new_transition)) if new_transition != old_transition => {
+                diff.transitions.push(TransitionChange::Changed {
+                    old: (*old_transition).clone(),
+                    new: (*new_transition).clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, new_transition) in &new_keyed {
+        if !old_keyed.iter().any(|(old_key, _)| old_key == key) {
+            diff.transitions
+                .push(TransitionChange::Added((*new_transition).clone()));
+        }
+    }
+    diff
+}
+
+fn unique_match<'a>(
+    candidates: &[&'a ErasedState],
+    predicate: impl Fn(&&'a ErasedState) -> bool,
+) -> Option<usize> {
+    let mut matches = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| predicate(candidate));
+    let (index, _) = matches.next()?;
+    matches.next().is_none().then_some(index)
+}
+
+/// Source state, event and occurrence of a transition.
+type TransitionKey<'a> = (&'a str, Option<&'a str>, usize);
+
+/// Keys transitions by source state, event and occurrence, so guarded alternatives on the
+/// same event (and pattern-less transitions) are matched up by their order.
+fn key_transitions(
+    transitions: &[ErasedTransition],
+) -> Vec<(TransitionKey<'_>, &ErasedTransition)> {
+    let mut seen: HashMap<(&str, Option<&str>), usize> = HashMap::new();
+    transitions
+        .iter()
+        .map(|transition| {
+            let slot = (transition.from.as_str(), transition.event.as_deref());
+            let occurrence = seen.entry(slot).or_default();
+            let key = (slot.0, slot.1, *occurrence);
+            *occurrence += 1;
+            (key, transition)
+        })
+        .collect()
+}
+
+/// A persisted machine configuration: the dotted paths of its active leaf states.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StateSnapshot {
+    pub active: Vec<String>,
+}
+
+impl StateSnapshot {
+    /// Creates a snapshot from active leaf paths.
+    pub fn new<I, P>(active: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        Self {
+            active: active.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// What [`migrate_snapshot`] does with an active state that the new definition removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationPolicy {
+    /// Fall back to the nearest surviving ancestor and enter its initial descendants.
+    Parent,
+    /// Discard the snapshot and start in the new definition's initial configuration.
+    Initial,
+    /// Look the removed path up in an explicit table of old path to new path.
+    Map(BTreeMap<String, String>),
+}
+
+/// Errors returned by [`migrate_snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The removed state has no ancestor in the new definition.
+    NoSurvivingAncestor { state: String },
+    /// The mapping table has no entry for a removed state.
+    Unmapped { state: String },
+    /// The mapping table sends a removed state to a path the new definition lacks.
+    UnknownTarget { state: String, target: String },
+}
+
+impl core::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MigrationError::NoSurvivingAncestor { state } => {
+                write!(f, "Removed state '{state}' has no surviving ancestor")
+            }
+            MigrationError::Unmapped { state } => {
+                write!(f, "No migration mapping for removed state '{state}'")
+            }
+            MigrationError::UnknownTarget { state, target } => write!(
+                f,
+                "Removed state '{state}' is mapped to '{target}', which does not exist"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Rewrites `snapshot`, taken under the old definition of `diff`, for the new definition.
+///
+/// Surviving and renamed states carry over; removed states are resolved with `policy`.
+/// A fallback that lands on a compound state enters its initial descendants.
+///
+/// # Errors
+///
+/// Returns a [`MigrationError`] if `policy` cannot place a removed active state.
+pub fn migrate_snapshot(
+    snapshot: &StateSnapshot,
+    diff: &DefinitionDiff,
+    policy: &MigrationPolicy,
+) -> Result<StateSnapshot, MigrationError> {
+    let new = &diff.target;
+    let mut active: Vec<String> = Vec::new();
+    for path in &snapshot.active {
+        let target = match diff.map_state(path) {
+            Some(mapped) => mapped.to_string(),
+            None => match policy {
+                MigrationPolicy::Initial => {
+                    return Ok(StateSnapshot::new(new.initial_leaves(new.initial())));
+                }
+                MigrationPolicy::Parent => {
+                    let mut ancestor = parent_path(path);
+                    loop {
+                        let Some(candidate) = ancestor else {
+                            return Err(MigrationError::NoSurvivingAncestor {
+                                state: path.clone(),
+                            });
+                        };
+                        if let Some(mapped) = diff.map_state(candidate) {
+                            break mapped.to_string();
+                        }
+                        ancestor = parent_path(candidate);
+                    }
+                }
+                MigrationPolicy::Map(table) => {
+                    let target = table.get(path).ok_or_else(|| MigrationError::Unmapped {
+                        state: path.clone(),
+                    })?;
+                    if new.state(target).is_none() {
+                        return Err(MigrationError::UnknownTarget {
+                            state: path.clone(),
+                            target: target.clone(),
+                        });
+                    }
+                    target.clone()
+                }
+            },
+        };
+        for leaf in new.initial_leaves(&target) {
+            if !active.contains(&leaf) {
+                active.push(leaf);
+            }
+        }
+    }
+    Ok(StateSnapshot { active })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(path: &str, initial_child: Option<&str>) -> ErasedState {
+        ErasedState {
+            path: path.to_string(),
+            parent: parent_path(path).map(str::to_string),
+            initial_child: initial_child.map(str::to_string),
+            is_parallel: false,
+        }
+    }
+
+    fn on(from: &str, event: &str, to: &str) -> ErasedTransition {
+        ErasedTransition {
+            from: from.to_string(),
+            to: to.to_string(),
+            event: Some(event.to_string()),
+            guarded: false,
+        }
+    }
+
+    fn player_v1() -> ErasedDefinition {
+        ErasedDefinition::new(
+            vec![
+                state("Stopped", None),
+                state("Playing", Some("Playing.Normal")),
+                state("Playing.Normal", None),
+                state("Playing.Fast", None),
+            ],
+            vec![
+                on("Stopped", "Play", "Playing"),
+                on("Playing", "Stop", "Stopped"),
+                on("Playing.Normal", "Faster", "Playing.Fast"),
+                on("Playing.Fast", "Slower", "Playing.Normal"),
+            ],
+            "Stopped",
+        )
+    }
+
+    #[test]
+    fn identical_definitions_have_an_empty_diff() {
+        let diff = diff_definitions(&player_v1(), &player_v1());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "no structural changes");
+    }
+
+    #[test]
+    fn renames_are_detected_by_parent_name_and_shape() {
+        // `Playing` becomes `Running` (children follow by name), `Fast` becomes `Turbo`
+        // (same parent and shape), and `Stopped` moves under a new `Power` parent.
+        let v2 = ErasedDefinition::new(
+            vec![
+                state("Power", Some("Power.Stopped")),
+                state("Power.Stopped", None),
+                state("Running", Some("Running.Normal")),
+                state("Running.Normal", None),
+                state("Running.Turbo", None),
+            ],
+            vec![
+                on("Power.Stopped", "Play", "Running"),
+                on("Running", "Stop", "Power.Stopped"),
+                on("Running.Normal", "Faster", "Running.Turbo"),
+                on("Running.Turbo", "Slower", "Running.Normal"),
+            ],
+            "Power",
+        );
+        let diff = diff_definitions(&player_v1(), &v2);
+
+        let renamed: Vec<_> = diff
+            .renamed
+            .iter()
+            .map(|rename| (rename.from.as_str(), rename.to.as_str()))
+            .collect();
+        assert_eq!(
+            renamed,
+            [
+                ("Stopped", "Power.Stopped"),
+                ("Playing", "Running"),
+                ("Playing.Normal", "Running.Normal"),
+                ("Playing.Fast", "Running.Turbo"),
+            ]
+        );
+        assert_eq!(diff.added, ["Power"]);
+        assert!(diff.removed.is_empty());
+        // After renaming, every transition lines up again.
+        assert!(diff.transitions.is_empty());
+    }
+
+    #[test]
+    fn ambiguous_candidates_are_not_treated_as_renames() {
+        let v2 = ErasedDefinition::new(
+            vec![
+                state("Stopped", None),
+                state("Playing", Some("Playing.Normal")),
+                state("Playing.Normal", None),
+                state("Playing.Slow", None),
+                state("Playing.Turbo", None),
+            ],
+            vec![
+                on("Stopped", "Play", "Playing"),
+                on("Playing", "Stop", "Stopped"),
+                on("Playing.Normal", "Faster", "Playing.Normal"),
+                on("Playing.Slow", "Slower", "Playing.Normal"),
+                on("Playing.Turbo", "Slower", "Playing.Normal"),
+            ],
+            "Stopped",
+        );
+        let diff = diff_definitions(&player_v1(), &v2);
+
+        assert!(diff.renamed.is_empty());
+        assert_eq!(diff.removed, ["Playing.Fast"]);
+        assert_eq!(diff.added, ["Playing.Slow", "Playing.Turbo"]);
+        assert!(diff.transitions.contains(&TransitionChange::Changed {
+            old: on("Playing.Normal", "Faster", "Playing.Fast"),
+            new: on("Playing.Normal", "Faster", "Playing.Normal"),
+        }));
+        assert!(diff.transitions.contains(&TransitionChange::Removed(on(
+            "Playing.Fast",
+            "Slower",
+            "Playing.Normal"
+        ))));
+    }
+
+    #[test]
+    fn snapshot_migrates_across_a_removed_leaf() {
+        let v2 = ErasedDefinition::new(
+            vec![
+                state("Stopped", None),
+                state("Playing", Some("Playing.Normal")),
+                state("Playing.Normal", None),
+            ],
+            vec![
+                on("Stopped", "Play", "Playing"),
+                on("Playing", "Stop", "Stopped"),
+            ],
+            "Stopped",
+        );
+        let diff = diff_definitions(&player_v1(), &v2);
+        assert_eq!(diff.removed, ["Playing.Fast"]);
+
+        let snapshot = StateSnapshot::new(["Playing.Fast"]);
+        assert_eq!(
+            migrate_snapshot(&snapshot, &diff, &MigrationPolicy::Parent),
+            Ok(StateSnapshot::new(["Playing.Normal"]))
+        );
+        assert_eq!(
+            migrate_snapshot(&snapshot, &diff, &MigrationPolicy::Initial),
+            Ok(StateSnapshot::new(["Stopped"]))
+        );
+        let table = BTreeMap::from([("Playing.Fast".to_string(), "Stopped".to_string())]);
+        assert_eq!(
+            migrate_snapshot(&snapshot, &diff, &MigrationPolicy::Map(table)),
+            Ok(StateSnapshot::new(["Stopped"]))
+        );
+        assert_eq!(
+            migrate_snapshot(&snapshot, &diff, &MigrationPolicy::Map(BTreeMap::new())),
+            Err(MigrationError::Unmapped {
+                state: "Playing.Fast".to_string()
+            })
+        );
+        // Surviving states are left alone whatever the policy.
+        assert_eq!(
+            migrate_snapshot(
+                &StateSnapshot::new(["Stopped"]),
+                &diff,
+                &MigrationPolicy::Initial
+            ),
+            Ok(StateSnapshot::new(["Stopped"]))
+        );
+    }
+}
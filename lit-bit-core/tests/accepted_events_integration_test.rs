@@ -0,0 +1,101 @@
+// lit-bit-core/tests/accepted_events_integration_test.rs
+//
+// `accepted_event_kinds` reports which `#[statechart_event]` kinds the active configuration
+// responds to, following the runtime's leaf-to-root, first-match transition order.
+
+use lit_bit_core::{Acceptance, statechart, statechart_event};
+
+#[statechart_event]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlayerEvent {
+    Play,
+    Pause,
+    Stop,
+    Seek(u32),
+    Eject,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayerContext {
+    pub has_disc: bool,
+}
+
+pub fn has_disc(ctx: &PlayerContext, _event: &PlayerEvent) -> bool {
+    ctx.has_disc
+}
+
+statechart! {
+    name: Player,
+    context: PlayerContext,
+    event: PlayerEvent,
+    initial: Stopped,
+    state Stopped {
+        on PlayerEvent::Play => Active;
+        on PlayerEvent::Seek(0) => Active;
+        on PlayerEvent::Eject [guard has_disc] => Stopped;
+    }
+    state Active {
+        initial: Playing;
+        on PlayerEvent::Stop => Stopped;
+        on PlayerEvent::Seek(_) => Active;
+        state Playing {
+            on PlayerEvent::Pause => Paused;
+            // Shadowed by the unguarded transition above.
+            on PlayerEvent::Pause [guard has_disc] => Playing;
+            on PlayerEvent::Seek(0) => Playing;
+        }
+        state Paused {
+            on (PlayerEvent::Play | PlayerEvent::Pause) => Playing;
+        }
+    }
+}
+
+fn accepted(player: &Player) -> Vec<(PlayerEventKind, Acceptance)> {
+    player.accepted_event_kinds::<PlayerEventKind>().collect()
+}
+
+#[test]
+fn accepted_kinds_follow_the_active_configuration() {
+    let mut player =
+        Player::new(PlayerContext::default(), &PlayerEvent::Stop).expect("player starts");
+
+    // Guards and payload patterns only make a kind conditionally acceptable.
+    assert_eq!(
+        accepted(&player),
+        [
+            (PlayerEventKind::Play, Acceptance::Accepted),
+            (PlayerEventKind::Seek, Acceptance::Conditional),
+            (PlayerEventKind::Eject, Acceptance::Conditional),
+        ]
+    );
+
+    // In a child state the parent's transitions count too; `Seek(_)` on `Active` accepts
+    // every seek even though `Playing` only matches `Seek(0)` itself.
+    let _ = player.send(&PlayerEvent::Play);
+    assert_eq!(
+        accepted(&player),
+        [
+            (PlayerEventKind::Pause, Acceptance::Accepted),
+            (PlayerEventKind::Stop, Acceptance::Accepted),
+            (PlayerEventKind::Seek, Acceptance::Accepted),
+        ]
+    );
+
+    let _ = player.send(&PlayerEvent::Pause);
+    assert_eq!(
+        accepted(&player),
+        [
+            (PlayerEventKind::Play, Acceptance::Accepted),
+            (PlayerEventKind::Pause, Acceptance::Accepted),
+            (PlayerEventKind::Stop, Acceptance::Accepted),
+            (PlayerEventKind::Seek, Acceptance::Accepted),
+        ]
+    );
+
+    let _ = player.send(&PlayerEvent::Stop);
+    assert!(
+        !accepted(&player)
+            .iter()
+            .any(|(kind, _)| *kind == PlayerEventKind::Stop)
+    );
+}
@@ -0,0 +1,246 @@
+// lit-bit-core/tests/async_guard_integration_test.rs
+//
+// `[guard async cond]`: conditions awaited by `async_send` in the order `send` checks
+// transitions, stopping at the first enabled one, on Tokio with a paused clock.
+
+#![cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lit_bit_core::SendResult;
+use lit_bit_core::actor::{Actor, spawn_actor_tokio};
+use lit_bit_macro::statechart;
+use tokio::sync::RwLock;
+use tokio::time::{Instant, sleep};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Request {
+    Boot,
+    Badge(u8),
+    Close,
+}
+
+#[derive(Debug, Default)]
+pub struct Config {
+    pub badges: Vec<u8>,
+    pub maintenance: bool,
+}
+
+/// Shared configuration behind an async lock, and a log of the guards asked.
+#[derive(Debug, Clone)]
+pub struct Gate {
+    pub config: Arc<RwLock<Config>>,
+    pub asked: Arc<Mutex<Vec<&'static str>>>,
+    pub latency: Duration,
+}
+
+impl Gate {
+    fn new(config: Config, latency: Duration) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            asked: Arc::default(),
+            latency,
+        }
+    }
+
+    async fn badge_allowed(&self, badge: u8) -> bool {
+        self.ask("badge").await;
+        self.config.read().await.badges.contains(&badge)
+    }
+
+    async fn in_maintenance(&self) -> bool {
+        self.ask("maintenance").await;
+        self.config.read().await.maintenance
+    }
+
+    async fn ask(&self, guard: &'static str) {
+        self.asked.lock().unwrap().push(guard);
+        sleep(self.latency).await;
+    }
+
+    fn asked(&self) -> Vec<&'static str> {
+        std::mem::take(&mut *self.asked.lock().unwrap())
+    }
+}
+
+statechart! {
+    name: Door,
+    context: Gate,
+    event: Request,
+    initial: Locked,
+    state Locked {
+        initial: Idle;
+        on Request::Badge(_) [guard async self.in_maintenance()] => Maintenance;
+        state Idle [terminal] {
+            on Request::Badge(id) [guard *id == 0] => Alarm;
+            on Request::Badge(id) [guard async self.badge_allowed(*id)] => Unlocked;
+        }
+    }
+    state Unlocked {
+        on Request::Close => Locked;
+    }
+    state Maintenance {}
+    state Alarm {}
+}
+
+const LATENCY: Duration = Duration::from_millis(50);
+
+fn door(config: Config) -> Door {
+    Door::new(Gate::new(config, LATENCY), &Request::Boot).expect("door starts")
+}
+
+fn allowing(badges: &[u8]) -> Config {
+    Config {
+        badges: badges.to_vec(),
+        ..Config::default()
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_passing_guard_stops_the_search() {
+    let mut door = door(allowing(&[5]));
+    let start = Instant::now();
+
+    assert_eq!(
+        door.async_send(&Request::Badge(5)).await,
+        SendResult::Transitioned
+    );
+    assert_eq!(door.current(), DoorStateId::Unlocked);
+    // The parent's maintenance guard comes after the leaf's and is never awaited.
+    assert_eq!(door.context().asked(), ["badge"]);
+    assert_eq!(start.elapsed(), LATENCY);
+}
+
+#[tokio::test(start_paused = true)]
+async fn guards_are_awaited_leaf_first_one_at_a_time() {
+    let mut door = door(Config {
+        maintenance: true,
+        ..Config::default()
+    });
+    let start = Instant::now();
+
+    assert_eq!(
+        door.async_send(&Request::Badge(7)).await,
+        SendResult::Transitioned
+    );
+    assert_eq!(door.current(), DoorStateId::Maintenance);
+    assert_eq!(door.context().asked(), ["badge", "maintenance"]);
+    assert_eq!(start.elapsed(), LATENCY * 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_passing_sync_guard_ahead_of_them_skips_the_async_guards() {
+    let mut door = door(Config::default());
+
+    assert_eq!(
+        door.async_send(&Request::Badge(0)).await,
+        SendResult::Transitioned
+    );
+    assert_eq!(door.current(), DoorStateId::Alarm);
+    assert!(door.context().asked().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn every_guard_failing_is_no_match() {
+    let mut door = door(Config::default());
+
+    assert_eq!(
+        door.async_send(&Request::Badge(7)).await,
+        SendResult::NoMatch
+    );
+    assert_eq!(door.current(), DoorStateId::LockedIdle);
+    assert_eq!(door.context().asked(), ["badge", "maintenance"]);
+
+    // Events no async guard stands in the way of are dispatched straight away.
+    assert_eq!(door.async_send(&Request::Close).await, SendResult::NoMatch);
+    assert!(door.context().asked().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn guards_see_the_shared_config_when_they_run() {
+    let mut door = door(Config::default());
+    let config = Arc::clone(&door.context().config);
+
+    assert_eq!(
+        door.async_send(&Request::Badge(3)).await,
+        SendResult::NoMatch
+    );
+    config.write().await.badges.push(3);
+    assert_eq!(
+        door.async_send(&Request::Badge(3)).await,
+        SendResult::Transitioned
+    );
+    assert_eq!(door.current(), DoorStateId::Unlocked);
+}
+
+#[test]
+fn async_guards_are_reported_as_guarded() {
+    let guarded = Door::definition()
+        .transitions
+        .iter()
+        .filter(|transition| transition.async_guard.is_some())
+        .inspect(|transition| assert!(transition.is_guarded()))
+        .count();
+    assert_eq!(guarded, 2);
+}
+
+/// Runs a door as an actor, logging when each message has been handled.
+struct DoorActor {
+    name: &'static str,
+    door: Door,
+    start: Instant,
+    handled: Arc<Mutex<Vec<(&'static str, Duration)>>>,
+}
+
+impl Actor for DoorActor {
+    type Message = Request;
+    type Future<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+    fn handle(&mut self, request: Request) -> Self::Future<'_> {
+        Box::pin(async move {
+            let _ = self.door.async_send(&request).await;
+            let elapsed = self.start.elapsed();
+            self.handled.lock().unwrap().push((self.name, elapsed));
+        })
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_slow_guard_only_holds_up_its_own_actor() {
+    let start = Instant::now();
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let spawn = |name, latency| {
+        let door =
+            Door::new(Gate::new(allowing(&[1]), latency), &Request::Boot).expect("door starts");
+        spawn_actor_tokio(
+            DoorActor {
+                name,
+                door,
+                start,
+                handled: Arc::clone(&handled),
+            },
+            4,
+        )
+    };
+    let slow = spawn("slow", Duration::from_secs(1));
+    let fast = spawn("fast", Duration::from_millis(10));
+
+    slow.send(Request::Badge(1)).await.unwrap();
+    slow.send(Request::Close).await.unwrap();
+    fast.send(Request::Badge(1)).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    // The fast door is done long before the slow guard resolves; the slow door's next
+    // message waits for its guard.
+    assert_eq!(
+        *handled.lock().unwrap(),
+        [
+            ("fast", Duration::from_millis(10)),
+            ("slow", Duration::from_secs(1)),
+            ("slow", Duration::from_secs(1)),
+        ]
+    );
+}
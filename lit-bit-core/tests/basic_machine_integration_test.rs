@@ -388,7 +388,10 @@ mod wildcard_pattern_test {
         assert_eq!(machine.context().log[0].as_str(), "specific_match");
 
         // Go back to State1
-        machine.send(&WildcardEvent::EventB);
+        assert_eq!(
+            machine.send(&WildcardEvent::EventB),
+            SendResult::Transitioned
+        );
         assert_eq!(
             machine.state().as_slice(),
             &[WildcardMachineStateId::State1]
@@ -406,7 +409,10 @@ mod wildcard_pattern_test {
         assert_eq!(machine.context().log[0].as_str(), "wildcard_match");
 
         // Go back to State1
-        machine.send(&WildcardEvent::EventA);
+        assert_eq!(
+            machine.send(&WildcardEvent::EventA),
+            SendResult::Transitioned
+        );
         assert_eq!(
             machine.state().as_slice(),
             &[WildcardMachineStateId::State1]
@@ -423,11 +429,42 @@ mod wildcard_pattern_test {
         assert_eq!(machine.context().log.len(), 1);
         assert_eq!(machine.context().log[0].as_str(), "wildcard_match");
     }
+
+    #[test]
+    fn test_send_batch_matches_sending_one_by_one() {
+        let events = [
+            WildcardEvent::EventA, // State1 -> State2
+            WildcardEvent::EventC, // State2 -> State1
+            WildcardEvent::EventB, // State1 -> State3 (wildcard)
+            WildcardEvent::EventB, // no transition from State3
+            WildcardEvent::EventA, // State3 -> State1
+        ];
+
+        let mut one_by_one =
+            WildcardMachine::new(WildcardContext::default(), &WildcardEvent::EventA)
+                .expect("Failed to create wildcard machine");
+        for event in &events {
+            let _ = one_by_one.send(event);
+        }
+
+        let mut batched = WildcardMachine::new(WildcardContext::default(), &WildcardEvent::EventA)
+            .expect("Failed to create wildcard machine");
+        let summary = batched.send_batch(&events);
+
+        assert_eq!(
+            (summary.transitioned, summary.ignored, summary.failed),
+            (4, 1, 0)
+        );
+        assert_eq!(summary.final_state, one_by_one.state());
+        assert_eq!(batched.state(), one_by_one.state());
+        assert_eq!(batched.context(), one_by_one.context());
+    }
 }
 
 // --- Test for Multiple State Machines Without Name Collisions ---
 #[cfg(test)]
 mod multiple_machines_test {
+    use lit_bit_core::SendResult;
     use lit_bit_core::StateMachine;
     use lit_bit_macro::statechart;
 
@@ -505,17 +542,17 @@ mod multiple_machines_test {
         assert_eq!(machine_b.state().as_slice(), &[MachineBStateId::StateB1]);
 
         // Test transitions on both machines
-        machine_a.send(&EventA::Go);
+        assert_eq!(machine_a.send(&EventA::Go), SendResult::Transitioned);
         assert_eq!(machine_a.state().as_slice(), &[MachineAStateId::StateA2]);
 
-        machine_b.send(&EventB::Start);
+        assert_eq!(machine_b.send(&EventB::Start), SendResult::Transitioned);
         assert_eq!(machine_b.state().as_slice(), &[MachineBStateId::StateB2]);
 
         // Ensure they don't interfere with each other
-        machine_a.send(&EventA::Stop);
+        assert_eq!(machine_a.send(&EventA::Stop), SendResult::Transitioned);
         assert_eq!(machine_a.state().as_slice(), &[MachineAStateId::StateA1]);
 
-        machine_b.send(&EventB::End);
+        assert_eq!(machine_b.send(&EventB::End), SendResult::Transitioned);
         assert_eq!(machine_b.state().as_slice(), &[MachineBStateId::StateB1]);
     }
 }
@@ -560,4 +597,160 @@ mod public_api_test {
         let result2 = <PublicApiMachine as StateMachine>::send(&mut machine, &PublicApiEvent::Test);
         assert_eq!(result2, lit_bit_core::SendResult::NoMatch);
     }
+
+    #[test]
+    fn test_definition_accessor_matches_the_generated_const() {
+        const DEFINITION: &lit_bit_core::MachineDefinition<
+            PublicApiMachineStateId,
+            PublicApiEvent,
+            PublicApiContext,
+        > = PublicApiMachine::definition();
+
+        assert_eq!(
+            DEFINITION.initial_leaf_state,
+            PUBLICAPIMACHINE_MACHINE_DEFINITION.initial_leaf_state
+        );
+        assert_eq!(DEFINITION.states.len(), 2);
+        assert_eq!(
+            DEFINITION.transitions.len(),
+            PUBLICAPIMACHINE_MACHINE_DEFINITION.transitions.len()
+        );
+    }
+}
+
+// --- Test for the force_transition recovery escape hatch ---
+#[cfg(test)]
+#[allow(clippy::trivially_copy_pass_by_ref)]
+mod force_transition_test {
+    use lit_bit_core::SendResult;
+    use lit_bit_core::StateMachine;
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct ForceContext {
+        log: heapless::Vec<heapless::String<16>, 16>,
+    }
+
+    impl ForceContext {
+        fn record(&mut self, entry: &str) {
+            let s = heapless::String::try_from(entry).expect("Failed to create heapless string");
+            self.log.push(s).expect("ForceContext log overflow");
+        }
+
+        fn entries(&self) -> Vec<&str> {
+            self.log.iter().map(heapless::String::as_str).collect()
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum ForceEvent {
+        Go,
+    }
+
+    fn enter_op(ctx: &mut ForceContext, _e: &ForceEvent) {
+        ctx.record("enter_op");
+    }
+    fn exit_op(ctx: &mut ForceContext, _e: &ForceEvent) {
+        ctx.record("exit_op");
+    }
+    fn enter_idle(ctx: &mut ForceContext, _e: &ForceEvent) {
+        ctx.record("enter_idle");
+    }
+    fn exit_idle(ctx: &mut ForceContext, _e: &ForceEvent) {
+        ctx.record("exit_idle");
+    }
+    fn enter_busy(ctx: &mut ForceContext, _e: &ForceEvent) {
+        ctx.record("enter_busy");
+    }
+    fn enter_maint(ctx: &mut ForceContext, _e: &ForceEvent) {
+        ctx.record("enter_maint");
+    }
+    fn enter_diag(ctx: &mut ForceContext, _e: &ForceEvent) {
+        ctx.record("enter_diag");
+    }
+
+    statechart! {
+        name: ForceMachine,
+        context: ForceContext,
+        event: ForceEvent,
+        initial: Operational,
+        state Operational {
+            initial: Idle;
+            entry: enter_op;
+            exit: exit_op;
+            state Idle {
+                entry: enter_idle;
+                exit: exit_idle;
+                on ForceEvent::Go => Busy;
+            }
+            state Busy {
+                entry: enter_busy;
+            }
+        }
+        state Maintenance {
+            initial: Diagnostics;
+            entry: enter_maint;
+            state Diagnostics {
+                entry: enter_diag;
+            }
+        }
+    }
+
+    fn machine() -> ForceMachine {
+        let mut machine = ForceMachine::new(ForceContext::default(), &ForceEvent::Go)
+            .expect("Failed to create force machine");
+        machine.context_mut().log.clear();
+        machine
+    }
+
+    #[test]
+    fn force_into_compound_state_exits_and_descends_via_initial_children() {
+        let mut machine = machine();
+        machine
+            .force_transition(ForceMachineStateId::Maintenance, &ForceEvent::Go)
+            .expect("force_transition should succeed");
+
+        assert_eq!(
+            machine.state().as_slice(),
+            &[ForceMachineStateId::MaintenanceDiagnostics]
+        );
+        assert_eq!(
+            machine.context().entries(),
+            vec!["exit_idle", "exit_op", "enter_maint", "enter_diag"]
+        );
+    }
+
+    #[test]
+    fn force_to_sibling_keeps_shared_ancestor_active() {
+        let mut machine = machine();
+        machine
+            .force_transition(ForceMachineStateId::OperationalBusy, &ForceEvent::Go)
+            .expect("force_transition should succeed");
+
+        assert_eq!(
+            machine.state().as_slice(),
+            &[ForceMachineStateId::OperationalBusy]
+        );
+        assert_eq!(machine.context().entries(), vec!["exit_idle", "enter_busy"]);
+    }
+
+    #[test]
+    fn force_to_active_compound_state_reenters_it() {
+        let mut machine = machine();
+        assert_eq!(machine.send(&ForceEvent::Go), SendResult::Transitioned);
+        machine.context_mut().log.clear();
+
+        machine
+            .force_transition(ForceMachineStateId::Operational, &ForceEvent::Go)
+            .expect("force_transition should succeed");
+
+        assert_eq!(
+            machine.state().as_slice(),
+            &[ForceMachineStateId::OperationalIdle]
+        );
+        assert_eq!(
+            machine.context().entries(),
+            vec!["exit_op", "enter_op", "enter_idle"]
+        );
+    }
 }
@@ -0,0 +1,169 @@
+// lit-bit-core/tests/budgeted_send_integration_test.rs
+//
+// `Runtime::send_with_budget`: cascades of raised events split across budgeted calls.
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use lit_bit_core::{MAX_ACTIVE_REGIONS, MonotonicClock, Runtime, SendResult, StateMachine, Ticks};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Job {
+    Start,
+    Step(u8),
+    Done,
+    Reset,
+}
+
+/// A clock that only moves when an action spends time.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock(Rc<Cell<Ticks>>);
+
+impl MonotonicClock for MockClock {
+    fn now(&self) -> Ticks {
+        self.0.get()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    pub clock: MockClock,
+    pub raised: VecDeque<Job>,
+    pub log: Vec<Job>,
+}
+
+impl Pipeline {
+    fn work(&mut self, event: &Job) {
+        self.log.push(*event);
+        self.clock.0.set(self.clock.0.get() + 1);
+    }
+}
+
+pub fn start_job(ctx: &mut Pipeline, event: &Job) {
+    ctx.work(event);
+    ctx.raised
+        .extend([Job::Step(1), Job::Step(2), Job::Step(3), Job::Done]);
+}
+
+pub fn record(ctx: &mut Pipeline, event: &Job) {
+    ctx.work(event);
+}
+
+fn next_raised(ctx: &mut Pipeline) -> Option<Job> {
+    ctx.raised.pop_front()
+}
+
+statechart! {
+    name: JobMachine,
+    context: Pipeline,
+    event: Job,
+    initial: Idle,
+    state Idle {
+        on Job::Start => Running [action start_job];
+    }
+    state Running {
+        on Job::Step(_) => Running [action record];
+        on Job::Done => Finished [action record];
+    }
+    state Finished {
+        on Job::Reset => Idle [action record];
+    }
+}
+
+type JobRuntime = Runtime<JobMachineStateId, Job, Pipeline, 3, MAX_ACTIVE_REGIONS, 12>;
+
+fn runtime() -> (JobRuntime, MockClock) {
+    let pipeline = Pipeline::default();
+    let clock = pipeline.clock.clone();
+    let mut runtime =
+        JobRuntime::new(JobMachine::definition(), pipeline, &Job::Reset).expect("runtime starts");
+    runtime.set_raised_event_source(next_raised);
+    (runtime, clock)
+}
+
+#[test]
+fn cascade_spanning_two_budgeted_calls_loses_and_reorders_nothing() {
+    let (mut runtime, clock) = runtime();
+
+    assert_eq!(
+        runtime.send_with_budget(&Job::Start, 2, &clock),
+        SendResult::BudgetExhausted {
+            remaining_internal: 3
+        }
+    );
+    assert_eq!(runtime.context().log, [Job::Start, Job::Step(1)]);
+    assert_eq!(runtime.state()[0], JobMachineStateId::Running);
+    assert_eq!(runtime.pending_internal_events(), 3);
+
+    // The leftover cascade runs before the new external event.
+    assert_eq!(
+        runtime.send_with_budget(&Job::Reset, 10, &clock),
+        SendResult::Transitioned
+    );
+    assert_eq!(
+        runtime.context().log,
+        [
+            Job::Start,
+            Job::Step(1),
+            Job::Step(2),
+            Job::Step(3),
+            Job::Done,
+            Job::Reset
+        ]
+    );
+    assert_eq!(runtime.state()[0], JobMachineStateId::Idle);
+    assert_eq!(runtime.pending_internal_events(), 0);
+}
+
+#[test]
+fn external_events_are_deferred_while_a_cascade_is_unfinished() {
+    let (mut runtime, clock) = runtime();
+
+    assert!(matches!(
+        runtime.send_with_budget(&Job::Start, 1, &clock),
+        SendResult::BudgetExhausted { .. }
+    ));
+    // Still mid-cascade after this call, so `Reset` waits behind it.
+    assert_eq!(
+        runtime.send_with_budget(&Job::Reset, 2, &clock),
+        SendResult::BudgetExhausted {
+            remaining_internal: 2
+        }
+    );
+    assert_eq!(runtime.state()[0], JobMachineStateId::Running);
+
+    // An unbudgeted send finishes everything queued, then handles its own event.
+    assert_eq!(runtime.send(&Job::Start), SendResult::Transitioned);
+    assert_eq!(
+        runtime.context().log,
+        [
+            Job::Start,
+            Job::Step(1),
+            Job::Step(2),
+            Job::Step(3),
+            Job::Done,
+            Job::Reset,
+            Job::Start,
+            Job::Step(1),
+            Job::Step(2),
+            Job::Step(3),
+            Job::Done
+        ]
+    );
+    assert_eq!(runtime.state()[0], JobMachineStateId::Finished);
+}
+
+#[test]
+fn cascade_within_budget_completes_in_one_call() {
+    let (mut runtime, clock) = runtime();
+
+    assert_eq!(
+        runtime.send_with_budget(&Job::Start, 100, &clock),
+        SendResult::Transitioned
+    );
+    assert_eq!(runtime.state()[0], JobMachineStateId::Finished);
+    assert_eq!(runtime.context().log.len(), 5);
+    assert_eq!(clock.now(), 5);
+}
@@ -0,0 +1,76 @@
+// lit-bit-core/tests/chart_constants_integration_test.rs
+//
+// `const` items at the top of a chart body: usable by the chart's guard expressions and
+// recorded in its definition for exporters.
+
+use lit_bit_core::{ChartConstant, SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Knob {
+    Boot,
+    Up(u8),
+    Mute,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Amp {
+    pub volume: u8,
+}
+
+fn raise(ctx: &mut Amp, event: &Knob) {
+    if let Knob::Up(step) = event {
+        ctx.volume += step;
+    }
+}
+
+statechart! {
+    name: Stereo,
+    context: Amp,
+    event: Knob,
+    initial: Playing,
+
+    const MAX_VOLUME: u8 = 90;
+    pub const MUTE_LEVEL: u8 = MAX_VOLUME / 10;
+
+    state Playing [terminal] {
+        on Knob::Up(step) [guard self.volume + *step <= MAX_VOLUME] => Playing [action raise];
+        on Knob::Mute [guard self.volume > MUTE_LEVEL] => Muted;
+    }
+    state Muted {}
+}
+
+#[test]
+fn guards_read_the_chart_constants() {
+    let mut stereo = Stereo::new(Amp { volume: 80 }, &Knob::Boot).expect("stereo starts");
+    assert_eq!(stereo.send(&Knob::Up(10)), SendResult::Transitioned);
+    assert_eq!(stereo.context().volume, 90);
+    assert_eq!(stereo.send(&Knob::Up(1)), SendResult::NoMatch);
+
+    assert_eq!(stereo.send(&Knob::Mute), SendResult::Transitioned);
+    assert_eq!(stereo.state()[0], StereoStateId::Muted);
+}
+
+#[test]
+fn public_constants_are_exported_with_the_machine() {
+    assert_eq!(MUTE_LEVEL, 9);
+}
+
+#[test]
+fn the_definition_records_each_constant_as_written() {
+    assert_eq!(
+        Stereo::definition().constants,
+        [
+            ChartConstant {
+                name: "MAX_VOLUME",
+                ty: "u8",
+                value: "90"
+            },
+            ChartConstant {
+                name: "MUTE_LEVEL",
+                ty: "u8",
+                value: "MAX_VOLUME / 10"
+            },
+        ]
+    );
+}
@@ -0,0 +1,138 @@
+// lit-bit-core/tests/completion_integration_test.rs
+//
+// `Runtime::is_final` and the `completed()` future: a machine is done once every active
+// leaf, in every region, is a `[final]` state.
+
+use lit_bit_core::{MAX_ACTIVE_REGIONS, Runtime, SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Job {
+    Start,
+    UploadDone,
+    ScanDone,
+    Retry,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Batch;
+
+statechart! {
+    name: Pipeline,
+    context: Batch,
+    event: Job,
+    initial: Working,
+    state Working [parallel] {
+        state Upload {
+            initial: Sending;
+            state Sending {
+                on Job::UploadDone => Uploaded;
+            }
+            state Uploaded [final] {}
+        }
+        state Scan {
+            initial: Scanning;
+            state Scanning {
+                on Job::ScanDone => Scanned;
+            }
+            state Scanned [final] {}
+        }
+        on Job::Retry => Working;
+    }
+}
+
+type PipelineRuntime = Runtime<PipelineStateId, Job, Batch, 8, MAX_ACTIVE_REGIONS, 32>;
+
+fn runtime() -> PipelineRuntime {
+    PipelineRuntime::new(Pipeline::definition(), Batch, &Job::Start).expect("pipeline starts")
+}
+
+#[test]
+fn a_parallel_machine_is_final_only_once_every_region_is() {
+    let mut pipeline = runtime();
+    assert!(!pipeline.is_final());
+
+    assert_eq!(pipeline.send(&Job::UploadDone), SendResult::Transitioned);
+    assert!(!pipeline.is_final(), "the scan region is still working");
+
+    assert_eq!(pipeline.send(&Job::ScanDone), SendResult::Transitioned);
+    assert!(pipeline.is_final());
+}
+
+#[test]
+fn an_unstarted_runtime_is_not_final() {
+    let pipeline = PipelineRuntime::unstarted(Pipeline::definition(), Batch);
+    assert!(!pipeline.is_final());
+}
+
+#[test]
+fn the_generated_machine_agrees_with_the_runtime() {
+    let mut machine = Pipeline::new(Batch, &Job::Start).expect("pipeline starts");
+    let _ = machine.send(&Job::UploadDone);
+    let _ = machine.send(&Job::ScanDone);
+    assert!(machine.is_final());
+}
+
+#[cfg(feature = "std")]
+mod completed {
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::{Context, Poll, Wake, Waker};
+
+    use super::{Job, runtime};
+    use lit_bit_core::StateMachine;
+
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn resolves_on_the_send_that_finishes_the_machine() {
+        let mut pipeline = runtime();
+        let wakes = Arc::new(CountingWaker::default());
+        let waker = Waker::from(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut done = pin!(pipeline.completed());
+
+        assert_eq!(done.as_mut().poll(&mut cx), Poll::Pending);
+        let _ = pipeline.send(&Job::UploadDone);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+        assert!(!done.is_complete());
+
+        let _ = pipeline.send(&Job::ScanDone);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(done.as_mut().poll(&mut cx), Poll::Ready(()));
+    }
+
+    #[test]
+    fn resolves_at_once_when_the_machine_is_already_final() {
+        let mut pipeline = runtime();
+        let _ = pipeline.send(&Job::UploadDone);
+        let _ = pipeline.send(&Job::ScanDone);
+
+        assert!(pipeline.completed().is_complete());
+    }
+
+    #[test]
+    fn every_pending_future_resolves_and_clones_do_not_resolve_them() {
+        let mut pipeline = runtime();
+        let first = pipeline.completed();
+        let second = pipeline.completed();
+
+        let mut fork = pipeline.clone();
+        let _ = fork.send(&Job::UploadDone);
+        let _ = fork.send(&Job::ScanDone);
+        assert!(fork.is_final());
+        assert!(!first.is_complete());
+
+        let _ = pipeline.send(&Job::UploadDone);
+        let _ = pipeline.send(&Job::ScanDone);
+        assert!(first.is_complete() && second.is_complete());
+    }
+}
@@ -0,0 +1,77 @@
+// lit-bit-core/tests/compute_buffer_integration_test.rs
+//
+// `compute_buffer:` in the chart header: the declared scratch buffer size and the default.
+
+use defaulted::Defaulted;
+use lit_bit_core::{SendResult, StateMachine};
+use tight::{Tight, TightStateId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Step {
+    Boot,
+    Across,
+    Back,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Steps;
+
+// Five states and a buffer of exactly five: the tightest size the build accepts.
+mod tight {
+    use super::{Step, Steps};
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Tight,
+        context: Steps,
+        event: Step,
+        compute_buffer: 5,
+        initial: Root,
+        state Root {
+            initial: Left;
+            state Left {
+                initial: Inner;
+                on Step::Across => Root::Right;
+                state Inner {}
+                state Other {}
+            }
+            state Right {
+                on Step::Back => Root::Left::Other;
+            }
+        }
+    }
+}
+
+mod defaulted {
+    use super::{Step, Steps};
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Defaulted,
+        context: Steps,
+        event: Step,
+        initial: Only,
+        state Only {}
+    }
+}
+
+#[test]
+fn declared_buffer_is_exposed_as_a_const() {
+    assert_eq!(Tight::COMPUTE_BUFFER, 5);
+}
+
+#[test]
+fn default_buffer_is_four_slots_per_state() {
+    assert_eq!(Defaulted::COMPUTE_BUFFER, 4);
+}
+
+#[test]
+fn buffer_the_size_of_the_chart_handles_transitions_across_branches() {
+    let mut machine = Tight::new(Steps, &Step::Boot).expect("machine starts");
+    assert_eq!(machine.send(&Step::Across), SendResult::Transitioned);
+    assert_eq!(machine.send(&Step::Back), SendResult::Transitioned);
+    assert_eq!(
+        machine.state().as_slice(),
+        [TightStateId::from_str_path("Root_Left_Other").expect("state exists")]
+    );
+}
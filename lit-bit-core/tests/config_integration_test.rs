@@ -0,0 +1,162 @@
+// lit-bit-core/tests/config_integration_test.rs
+#![cfg(feature = "std")]
+
+use lit_bit_core::config::{ConfigError, MachineConfig, serde, serde_json};
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, PartialEq, Default, serde::Deserialize)]
+#[serde(crate = "lit_bit_core::config::serde", default)]
+pub struct PumpContext {
+    target_rpm: u32,
+    label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PumpEvent {
+    Start,
+    Stop,
+}
+
+statechart! {
+    name: PumpMachine,
+    context: PumpContext,
+    event: PumpEvent,
+    initial: Idle,
+    state Idle {
+        on PumpEvent::Start => Running;
+    }
+    state Running {
+        initial: Ramping;
+        on PumpEvent::Stop => Idle;
+        state Ramping {}
+        state Steady {}
+    }
+}
+
+fn config(json: serde_json::Value) -> MachineConfig {
+    serde_json::from_value(json).expect("valid MachineConfig JSON")
+}
+
+#[test]
+fn user_paths_round_trip() {
+    assert_eq!(
+        PumpMachineStateId::from_user_path("Running.Steady"),
+        Some(PumpMachineStateId::RunningSteady)
+    );
+    assert_eq!(
+        PumpMachineStateId::RunningSteady.user_path(),
+        "Running.Steady"
+    );
+    assert_eq!(PumpMachineStateId::from_user_path("Steady"), None);
+}
+
+#[test]
+fn depth_follows_nesting() {
+    const STEADY_DEPTH: u8 = PumpMachineStateId::RunningSteady.depth();
+    assert_eq!(STEADY_DEPTH, 1);
+    assert_eq!(PumpMachineStateId::Running.depth(), 0);
+    assert_eq!(PumpMachineStateId::Idle.depth(), 0);
+
+    let tree: Vec<String> = [
+        PumpMachineStateId::Running,
+        PumpMachineStateId::RunningRamping,
+    ]
+    .iter()
+    .map(|state| {
+        format!(
+            "{}{}",
+            "  ".repeat(usize::from(state.depth())),
+            state.user_path()
+        )
+    })
+    .collect();
+    assert_eq!(tree, ["Running", "  Running.Ramping"]);
+}
+
+#[test]
+fn empty_config_uses_declared_initial_state_and_default_context() {
+    let machine = PumpMachine::from_config(&MachineConfig::default(), &PumpEvent::Start)
+        .expect("default config should be valid");
+
+    assert_eq!(machine.state().as_slice(), &[PumpMachineStateId::Idle]);
+    assert_eq!(machine.context(), &PumpContext::default());
+}
+
+#[test]
+fn valid_override_sets_initial_state_and_seeds_context() {
+    let cfg = config(serde_json::json!({
+        "initial": "Running",
+        "context": { "target_rpm": 1200 }
+    }));
+    let mut machine =
+        PumpMachine::from_config(&cfg, &PumpEvent::Start).expect("config should be valid");
+
+    assert_eq!(
+        machine.state().as_slice(),
+        &[PumpMachineStateId::RunningRamping]
+    );
+    assert_eq!(machine.context().target_rpm, 1200);
+    assert_eq!(machine.context().label, "");
+
+    assert_eq!(machine.send(&PumpEvent::Stop), SendResult::Transitioned);
+    assert_eq!(machine.state().as_slice(), &[PumpMachineStateId::Idle]);
+}
+
+#[test]
+fn nested_initial_state_is_rejected_with_top_level_options() {
+    let cfg = config(serde_json::json!({ "initial": "Running.Steady" }));
+    let err = PumpMachine::from_config(&cfg, &PumpEvent::Start).unwrap_err();
+
+    match &err {
+        ConfigError::NotTopLevel { requested, valid } => {
+            assert_eq!(requested, "Running.Steady");
+            assert_eq!(valid, &["Idle", "Running"]);
+        }
+        other => panic!("expected NotTopLevel, got {other:?}"),
+    }
+    assert!(
+        err.to_string()
+            .contains("valid top-level states: Idle, Running")
+    );
+}
+
+#[test]
+fn unknown_initial_state_lists_top_level_options() {
+    let cfg = config(serde_json::json!({ "initial": "Flooded" }));
+    let err = PumpMachine::from_config(&cfg, &PumpEvent::Start).unwrap_err();
+
+    assert!(matches!(err, ConfigError::UnknownState { .. }));
+    assert_eq!(
+        err.to_string(),
+        "Unknown initial state 'Flooded'; valid top-level states: Idle, Running"
+    );
+}
+
+#[test]
+fn context_field_errors_are_reported() {
+    let cfg = config(serde_json::json!({ "context": { "target_rpm": "fast" } }));
+    let err = PumpMachine::from_config(&cfg, &PumpEvent::Start).unwrap_err();
+
+    let ConfigError::Context(inner) = &err else {
+        panic!("expected Context error, got {err:?}");
+    };
+    assert!(inner.to_string().contains("invalid type"));
+}
+
+#[test]
+fn explicit_context_skips_deserialization() {
+    let cfg = config(serde_json::json!({ "initial": "Running", "context": "ignored" }));
+    let context = PumpContext {
+        target_rpm: 900,
+        label: "manual".into(),
+    };
+    let machine = PumpMachine::from_config_with_context(&cfg, context.clone(), &PumpEvent::Start)
+        .expect("config should be valid");
+
+    assert_eq!(
+        machine.state().as_slice(),
+        &[PumpMachineStateId::RunningRamping]
+    );
+    assert_eq!(machine.context(), &context);
+}
@@ -0,0 +1,115 @@
+// lit-bit-core/tests/configuration_integration_test.rs
+//
+// `configuration()`: the active leaves in canonical form, so machines that reach the same
+// parallel configuration compare and hash equal whatever order they got there in.
+
+use std::collections::HashSet;
+
+use lit_bit_core::{Configuration, MAX_ACTIVE_REGIONS, Runtime, SendResult, StateSet};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dock {
+    Boot,
+    Lock,
+    Pump,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Bay;
+
+statechart! {
+    name: Airlock,
+    context: Bay,
+    event: Dock,
+    initial: Cycle,
+    state Cycle [parallel] {
+        state Hatch {
+            initial: Open;
+            state Open {
+                on Dock::Lock => Sealed;
+            }
+            state Sealed {}
+        }
+        state Air {
+            initial: Vented;
+            state Vented {
+                on Dock::Pump => Pressurized;
+            }
+            state Pressurized {}
+        }
+    }
+}
+
+type Config = Configuration<AirlockStateId, MAX_ACTIVE_REGIONS>;
+
+fn airlock_after(events: &[Dock]) -> Airlock {
+    let mut airlock = Airlock::new(Bay, &Dock::Boot).expect("airlock starts");
+    for event in events {
+        assert_eq!(airlock.send(event), SendResult::Transitioned);
+    }
+    airlock
+}
+
+#[test]
+fn the_same_parallel_configuration_compares_equal_whatever_the_event_order() {
+    let lock_first = airlock_after(&[Dock::Lock, Dock::Pump]);
+    let pump_first = airlock_after(&[Dock::Pump, Dock::Lock]);
+
+    assert_eq!(lock_first.configuration(), pump_first.configuration());
+    assert_eq!(
+        lock_first.configuration().as_slice(),
+        pump_first.configuration().as_slice()
+    );
+
+    let mut seen: HashSet<Config> = HashSet::new();
+    seen.insert(lock_first.configuration());
+    assert!(!seen.insert(pump_first.configuration()));
+    assert!(seen.insert(airlock_after(&[Dock::Lock]).configuration()));
+}
+
+#[test]
+fn ordering_depends_only_on_the_active_leaves() {
+    let start = airlock_after(&[]).configuration();
+    let sealed = airlock_after(&[Dock::Lock]).configuration();
+    let both = airlock_after(&[Dock::Pump, Dock::Lock]).configuration();
+
+    assert_ne!(start, sealed);
+    let mut sorted = [both.clone(), start.clone(), sealed.clone()];
+    sorted.sort();
+    let mut again = [sealed, both, start];
+    again.sort();
+    assert_eq!(sorted, again);
+}
+
+#[test]
+fn membership_through_the_configuration_and_its_state_set() {
+    let configuration = airlock_after(&[Dock::Pump]).configuration();
+    assert!(configuration.contains(AirlockStateId::CycleAirPressurized));
+    assert!(configuration.contains(AirlockStateId::CycleHatchOpen));
+    assert!(!configuration.contains(AirlockStateId::CycleAirVented));
+
+    let set: StateSet = configuration.to_state_set().expect("indices fit one word");
+    assert!(set.contains_state(AirlockStateId::CycleAirPressurized));
+    assert!(!set.contains_state(AirlockStateId::CycleHatchSealed));
+    assert_eq!(set.len(), 2);
+    assert_eq!(Config::from_state_set(&set), Ok(configuration));
+}
+
+#[test]
+fn runtimes_report_the_same_configuration_as_the_machine() {
+    type AirlockRuntime = Runtime<AirlockStateId, Dock, Bay, 4, MAX_ACTIVE_REGIONS, 32>;
+    let mut runtime =
+        AirlockRuntime::new(Airlock::definition(), Bay, &Dock::Boot).expect("airlock starts");
+    let _ = lit_bit_core::StateMachine::send(&mut runtime, &Dock::Lock);
+
+    assert_eq!(
+        runtime.configuration(),
+        airlock_after(&[Dock::Lock]).configuration()
+    );
+    assert!(
+        AirlockRuntime::unstarted(Airlock::definition(), Bay)
+            .configuration()
+            .is_empty()
+    );
+}
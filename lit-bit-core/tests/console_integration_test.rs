@@ -0,0 +1,145 @@
+// lit-bit-core/tests/console_integration_test.rs
+//
+// `MachineConsole`: driving a machine with `fsm state`, `fsm trace` and `fsm send` lines.
+
+use lit_bit_core::console::{ConsoleError, MachineConsole};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cmd {
+    Start,
+    SetSpeed(u8),
+    Stop,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Motor {
+    pub speed: u8,
+}
+
+fn set_speed(ctx: &mut Motor, event: &Cmd) {
+    if let Cmd::SetSpeed(speed) = event {
+        ctx.speed = *speed;
+    }
+}
+
+statechart! {
+    name: MotorMachine,
+    context: Motor,
+    event: Cmd,
+    initial: Idle,
+    state Idle {
+        on Start => Running;
+    }
+    state Running {
+        initial: Slow;
+        on Stop => Idle;
+        state Slow {
+            on SetSpeed(51..) => Running::Fast [action set_speed];
+        }
+        state Fast {
+            on SetSpeed(..=50) => Running::Slow [action set_speed];
+        }
+    }
+}
+
+fn parse_event(text: &str) -> Option<Cmd> {
+    match text {
+        "start" => Some(Cmd::Start),
+        "stop" => Some(Cmd::Stop),
+        _ => text.strip_prefix("speed ")?.parse().ok().map(Cmd::SetSpeed),
+    }
+}
+
+fn motor() -> MotorMachine {
+    MotorMachine::new(Motor::default(), &Cmd::Start).expect("machine starts")
+}
+
+fn run(console: &mut MachineConsole<'_, MotorMachine, 2>, line: &str) -> String {
+    let mut out = String::new();
+    console
+        .handle_line(line, &mut out)
+        .expect("command succeeds");
+    out
+}
+
+#[test]
+fn state_lists_the_active_state_paths() {
+    let mut machine = motor();
+    let mut console =
+        MachineConsole::new(&mut machine, MotorMachineStateId::user_path, parse_event);
+    assert_eq!(run(&mut console, "fsm state"), "Idle\r\n");
+    run(&mut console, "fsm send start");
+    assert_eq!(run(&mut console, "  fsm   state "), "Running.Slow\r\n");
+}
+
+#[test]
+fn send_parses_the_event_with_the_hook_and_reports_the_result() {
+    let mut machine = motor();
+    let mut console =
+        MachineConsole::new(&mut machine, MotorMachineStateId::user_path, parse_event);
+    assert_eq!(run(&mut console, "fsm send start"), "Transitioned\r\n");
+    assert_eq!(run(&mut console, "fsm send speed 80"), "Transitioned\r\n");
+    assert_eq!(run(&mut console, "fsm send start"), "NoMatch\r\n");
+    assert_eq!(console.machine().context().speed, 80);
+}
+
+#[test]
+fn trace_keeps_the_most_recent_sends() {
+    let mut machine = motor();
+    let mut console =
+        MachineConsole::new(&mut machine, MotorMachineStateId::user_path, parse_event);
+    assert_eq!(run(&mut console, "fsm trace"), "(no events sent)\r\n");
+
+    run(&mut console, "fsm send start");
+    run(&mut console, "fsm send speed 80");
+    run(&mut console, "fsm send stop");
+    assert_eq!(
+        run(&mut console, "fsm trace"),
+        "#2 Transitioned: Running.Slow -> Running.Fast\r\n\
+         #3 Transitioned: Running.Fast -> Idle\r\n"
+    );
+    run(&mut console, "fsm send stop");
+    assert!(run(&mut console, "fsm trace").ends_with("#4 NoMatch: - -> -\r\n"));
+}
+
+#[test]
+fn invalid_lines_are_rejected_without_output() {
+    let mut machine = motor();
+    let mut console =
+        MachineConsole::<_, 2>::new(&mut machine, MotorMachineStateId::user_path, parse_event);
+    let mut out = String::new();
+    assert_eq!(console.handle_line("", &mut out), Ok(()));
+    assert_eq!(
+        console.handle_line("reboot", &mut out),
+        Err(ConsoleError::UnknownCommand)
+    );
+    assert_eq!(
+        console.handle_line("fsm state now", &mut out),
+        Err(ConsoleError::UnknownCommand)
+    );
+    assert_eq!(
+        console.handle_line("fsm send", &mut out),
+        Err(ConsoleError::MissingEvent)
+    );
+    assert_eq!(
+        console.handle_line("fsm send warp 9", &mut out),
+        Err(ConsoleError::UnknownEvent)
+    );
+    assert!(out.is_empty());
+    assert_eq!(console.trace().count(), 0);
+}
+
+#[test]
+fn output_is_bounded_by_the_writer() {
+    let mut machine = motor();
+    let mut console =
+        MachineConsole::<_, 2>::new(&mut machine, MotorMachineStateId::user_path, parse_event);
+    let mut small: heapless::String<4> = heapless::String::new();
+    assert_eq!(
+        console.handle_line("fsm send start", &mut small),
+        Err(ConsoleError::Output)
+    );
+    // The event was still sent; only the reply did not fit.
+    assert_eq!(console.trace().count(), 1);
+}
@@ -0,0 +1,83 @@
+// lit-bit-core/tests/const_machine_integration_test.rs
+//
+// `const_new` machines in statics: entry actions are deferred until `start` or the first
+// `send`, and run exactly once.
+
+use std::sync::Mutex;
+
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    Power,
+    Toggle,
+}
+
+#[derive(Debug, Clone)]
+pub struct Counts {
+    pub off_entries: u32,
+    pub on_entries: u32,
+}
+
+impl Counts {
+    pub const ZERO: Self = Self {
+        off_entries: 0,
+        on_entries: 0,
+    };
+}
+
+pub fn enter_off(ctx: &mut Counts, _event: &Signal) {
+    ctx.off_entries += 1;
+}
+
+pub fn enter_on(ctx: &mut Counts, _event: &Signal) {
+    ctx.on_entries += 1;
+}
+
+statechart! {
+    name: LampMachine,
+    context: Counts,
+    event: Signal,
+    initial: Off,
+    state Off {
+        entry: enter_off;
+        on Signal::Toggle => On;
+    }
+    state On {
+        entry: enter_on;
+        on Signal::Toggle => Off;
+    }
+}
+
+static STARTED_LAMP: Mutex<LampMachine> = Mutex::new(LampMachine::const_new(Counts::ZERO));
+static LAZY_LAMP: Mutex<LampMachine> = Mutex::new(LampMachine::const_new(Counts::ZERO));
+
+#[test]
+fn entry_actions_fire_exactly_once_on_start() {
+    let mut lamp = STARTED_LAMP.lock().unwrap();
+    assert!(!lamp.is_started());
+    assert_eq!(lamp.context().off_entries, 0);
+    assert!(lamp.state().is_empty());
+
+    lamp.start(&Signal::Power).expect("lamp starts");
+    lamp.start(&Signal::Power).expect("second start is a no-op");
+    assert!(lamp.is_started());
+    assert_eq!(lamp.context().off_entries, 1);
+    assert_eq!(lamp.state()[0], LampMachineStateId::Off);
+
+    assert_eq!(lamp.send(&Signal::Toggle), SendResult::Transitioned);
+    assert_eq!(lamp.context().off_entries, 1);
+    assert_eq!(lamp.context().on_entries, 1);
+}
+
+#[test]
+fn first_send_enters_the_initial_state_before_handling_the_event() {
+    let mut lamp = LAZY_LAMP.lock().unwrap();
+
+    assert_eq!(lamp.send(&Signal::Toggle), SendResult::Transitioned);
+    assert!(lamp.is_started());
+    assert_eq!(lamp.context().off_entries, 1);
+    assert_eq!(lamp.context().on_entries, 1);
+    assert_eq!(lamp.state()[0], LampMachineStateId::On);
+}
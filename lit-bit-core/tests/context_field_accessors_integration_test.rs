@@ -0,0 +1,68 @@
+// lit-bit-core/tests/context_field_accessors_integration_test.rs
+//
+// `context_fields: [...]` in the chart header generates `ctx_<field>()` accessors.
+
+use lit_bit_core::SendResult;
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tally {
+    Add(u32),
+    Close,
+    Reopen,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    pub total: u32,
+    pub entries: Vec<u32>,
+    pub label: &'static str,
+    // Not listed below, so it gets no accessor.
+    pub closures: u8,
+}
+
+pub fn add(ctx: &mut Ledger, event: &Tally) {
+    if let Tally::Add(amount) = event {
+        ctx.total += amount;
+        ctx.entries.push(*amount);
+    }
+}
+
+pub fn close(ctx: &mut Ledger, _event: &Tally) {
+    ctx.closures += 1;
+}
+
+statechart! {
+    name: LedgerMachine,
+    context: Ledger,
+    event: Tally,
+    context_fields: [total: u32, entries: Vec<u32>, label: &'static str],
+    initial: Open,
+    state Open {
+        on Tally::Add(_) => Open [action add];
+        on Tally::Close => Closed [action close];
+    }
+    state Closed {
+        on Tally::Reopen => Open;
+    }
+}
+
+#[test]
+fn accessors_track_the_context() {
+    let ledger = Ledger {
+        label: "petty cash",
+        ..Ledger::default()
+    };
+    let mut machine = LedgerMachine::new(ledger, &Tally::Reopen).expect("machine starts");
+    assert_eq!(*machine.ctx_total(), 0);
+    assert_eq!(*machine.ctx_label(), "petty cash");
+
+    assert_eq!(machine.send(&Tally::Add(5)), SendResult::Transitioned);
+    assert_eq!(machine.send(&Tally::Add(7)), SendResult::Transitioned);
+    assert_eq!(*machine.ctx_total(), 12);
+    assert_eq!(machine.ctx_entries(), &[5, 7]);
+    assert_eq!(machine.ctx_total(), &machine.context().total);
+
+    machine.context_mut().total = 0;
+    assert_eq!(*machine.ctx_total(), 0);
+}
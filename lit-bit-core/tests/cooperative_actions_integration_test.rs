@@ -0,0 +1,142 @@
+// lit-bit-core/tests/cooperative_actions_integration_test.rs
+//
+// Time-sliced actions on Embassy: a flash erase written as `Cooperative` slices lets
+// another task run between sectors, measured on a mock Embassy time driver.
+#![cfg(feature = "async-embassy")]
+
+use core::cell::Cell;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::task::Waker;
+
+use embassy_time::{Duration, Instant};
+use lit_bit_core::actor::{Actor, Cooperative, CooperativeActor, StatechartActor, Yielder};
+use lit_bit_macro::statechart;
+
+/// Time only moves when the test advances it.
+struct MockDriver {
+    now: AtomicU64,
+}
+
+impl embassy_time_driver::Driver for MockDriver {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+
+    fn schedule_wake(&self, _at: u64, waker: &Waker) {
+        waker.wake_by_ref();
+    }
+}
+
+embassy_time_driver::time_driver_impl!(static DRIVER: MockDriver = MockDriver {
+    now: AtomicU64::new(0)
+});
+
+const SECTORS: u8 = 8;
+const SECTOR_ERASE: Duration = Duration::from_millis(10);
+
+/// Stands in for erasing one sector: the flash controller keeps the core busy for
+/// `SECTOR_ERASE`.
+fn erase_sector() {
+    DRIVER
+        .now
+        .fetch_add(SECTOR_ERASE.as_ticks(), Ordering::SeqCst);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    Boot,
+    Erase,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Storage {
+    yielder: Yielder,
+    sliced: bool,
+    erased: u8,
+}
+
+impl Cooperative for Storage {
+    fn yielder(&mut self) -> &mut Yielder {
+        &mut self.yielder
+    }
+
+    fn resume(&mut self) {
+        erase_sector();
+        self.erased += 1;
+        if self.erased < SECTORS {
+            self.yielder.checkpoint();
+        }
+    }
+}
+
+fn start_erase(storage: &mut Storage, _event: &Command) {
+    storage.erased = 0;
+    if storage.sliced {
+        storage.resume();
+    } else {
+        while storage.erased < SECTORS {
+            erase_sector();
+            storage.erased += 1;
+        }
+    }
+}
+
+statechart! {
+    name: Flasher,
+    context: Storage,
+    event: Command,
+    initial: Idle,
+    state Idle {
+        on Command::Erase => Erasing;
+    }
+    state Erasing {
+        entry: start_erase;
+    }
+}
+
+/// Sends `Erase` while another task records the longest gap between its own turns, and
+/// returns that gap.
+fn worst_latency_during_erase(sliced: bool) -> Duration {
+    let storage = Storage {
+        sliced,
+        ..Storage::default()
+    };
+    let machine = Flasher::new(storage, &Command::Boot).expect("flasher starts");
+    let mut actor = CooperativeActor::new(StatechartActor::new(machine));
+    let done = Cell::new(false);
+
+    let erase = async {
+        // The command arrives once the other task is running.
+        embassy_futures::yield_now().await;
+        actor.handle(Command::Erase).await;
+        done.set(true);
+    };
+    let other_task = async {
+        let mut last = Instant::now();
+        let mut worst = Duration::from_ticks(0);
+        loop {
+            let now = Instant::now();
+            worst = worst.max(now - last);
+            last = now;
+            if done.get() {
+                break worst;
+            }
+            embassy_futures::yield_now().await;
+        }
+    };
+    let ((), worst) = embassy_futures::block_on(embassy_futures::join::join(erase, other_task));
+
+    let machine = actor.into_inner().into_inner();
+    assert_eq!(machine.context().erased, SECTORS);
+    assert!(!machine.context().yielder.is_pending());
+    worst
+}
+
+#[test]
+fn other_tasks_run_between_the_slices_of_a_long_action() {
+    assert_eq!(
+        worst_latency_during_erase(false),
+        SECTOR_ERASE * u32::from(SECTORS)
+    );
+    assert_eq!(worst_latency_during_erase(true), SECTOR_ERASE);
+}
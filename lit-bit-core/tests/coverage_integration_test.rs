@@ -0,0 +1,93 @@
+// lit-bit-core/tests/coverage_integration_test.rs
+#![cfg(feature = "coverage")]
+
+use lit_bit_core::SendResult;
+use lit_bit_core::coverage::CoverageRecorder;
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Default)]
+pub struct DoorContext;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DoorEvent {
+    Open,
+    Close,
+    Lock,
+}
+
+statechart! {
+    name: DoorMachine,
+    context: DoorContext,
+    event: DoorEvent,
+    initial: Closed,
+    state Closed {
+        initial: Unlocked;
+        on DoorEvent::Open => Opened;
+        state Unlocked {
+            on DoorEvent::Lock => Locked;
+        }
+        state Locked {}
+    }
+    state Opened {
+        on DoorEvent::Close => Closed;
+    }
+}
+
+fn machine(recorder: &'static CoverageRecorder) -> DoorMachine {
+    let mut machine =
+        DoorMachine::new(DoorContext, &DoorEvent::Close).expect("Failed to create door machine");
+    machine.set_coverage_recorder(recorder);
+    machine
+}
+
+#[test]
+fn report_lists_unvisited_states_and_transitions_by_name() {
+    static COVERAGE: CoverageRecorder = CoverageRecorder::new();
+    let mut door = machine(&COVERAGE);
+    assert_eq!(door.send(&DoorEvent::Open), SendResult::Transitioned);
+
+    let report = DoorMachine::coverage_report(&COVERAGE);
+    assert!(!report.is_complete());
+    assert_eq!(
+        report.never_entered_names().collect::<Vec<_>>(),
+        vec!["Closed.Locked"]
+    );
+    let rendered = report.to_string();
+    assert!(rendered.starts_with("states 3/4 entered, transitions 1/3 fired"));
+    assert!(rendered.contains("never fired: #1 Closed.Unlocked -> Closed.Locked"));
+    assert!(rendered.contains("never fired: #2 Opened -> Closed"));
+}
+
+#[test]
+fn full_walk_reaches_full_coverage() {
+    static COVERAGE: CoverageRecorder = CoverageRecorder::new();
+    let mut door = machine(&COVERAGE);
+    assert_eq!(door.send(&DoorEvent::Open), SendResult::Transitioned);
+    assert_eq!(door.send(&DoorEvent::Close), SendResult::Transitioned);
+    assert_eq!(door.send(&DoorEvent::Lock), SendResult::Transitioned);
+
+    lit_bit_core::assert_full_coverage!(DoorMachine, COVERAGE);
+}
+
+#[test]
+#[should_panic(expected = "incomplete coverage for DoorMachine")]
+fn assert_full_coverage_fails_on_gaps() {
+    static COVERAGE: CoverageRecorder = CoverageRecorder::new();
+    let _door = machine(&COVERAGE);
+
+    lit_bit_core::assert_full_coverage!(DoorMachine, COVERAGE);
+}
+
+#[test]
+fn speculative_sends_are_not_recorded() {
+    static COVERAGE: CoverageRecorder = CoverageRecorder::new();
+    let door = machine(&COVERAGE);
+    let speculation = door.speculate(&DoorEvent::Lock);
+    assert!(speculation.transitioned());
+
+    let report = DoorMachine::coverage_report(&COVERAGE);
+    assert_eq!(
+        report.never_entered_names().collect::<Vec<_>>(),
+        vec!["Closed.Locked", "Opened"]
+    );
+}
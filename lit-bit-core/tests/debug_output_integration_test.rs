@@ -0,0 +1,89 @@
+// lit-bit-core/tests/debug_output_integration_test.rs
+//
+// The `Debug` output of generated machines: active leaf paths, plus the context when the
+// chart opts in with `debug_context: true`.
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaEvent {
+    Play,
+    Mute,
+}
+
+mod parallel {
+    use super::MediaEvent;
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Volume {
+        pub level: u8,
+    }
+
+    statechart! {
+        name: Player,
+        context: Volume,
+        event: MediaEvent,
+        debug_context: true,
+        initial: Media,
+        state Media [parallel] {
+            state Playback {
+                initial: Stopped;
+                state Stopped { on MediaEvent::Play => Playing; }
+                state Playing {}
+            }
+            state Audio {
+                initial: Loud;
+                state Loud { on MediaEvent::Mute => Muted; }
+                state Muted {}
+            }
+        }
+    }
+}
+
+// Not `Debug`: without `debug_context` the context is never formatted.
+#[derive(Clone, Default)]
+pub struct Credentials {
+    pub token: &'static str,
+}
+
+statechart! {
+    name: Session,
+    context: Credentials,
+    event: MediaEvent,
+    initial: Idle,
+    state Idle { on MediaEvent::Play => Active; }
+    state Active {}
+}
+
+#[test]
+fn parallel_machine_debug_lists_every_region_and_the_context() {
+    let mut player = parallel::Player::new(parallel::Volume { level: 7 }, &MediaEvent::Play)
+        .expect("machine starts");
+    assert_eq!(
+        format!("{player:?}"),
+        "Player { active: [Media::Playback::Stopped, Media::Audio::Loud], context: Volume { level: 7 } }"
+    );
+
+    let _ = player.send(&MediaEvent::Mute);
+    assert_eq!(
+        format!("{player:?}"),
+        "Player { active: [Media::Playback::Stopped, Media::Audio::Muted], context: Volume { level: 7 } }"
+    );
+    assert_eq!(player.context().level, 7);
+}
+
+#[test]
+fn context_is_left_out_unless_the_chart_opts_in() {
+    let mut session =
+        Session::new(Credentials { token: "secret" }, &MediaEvent::Play).expect("machine starts");
+    assert_eq!(format!("{session:?}"), "Session { active: [Idle], .. }");
+
+    let _ = session.send(&MediaEvent::Play);
+    let debug = format!("{session:#?}");
+    assert_eq!(
+        debug,
+        "Session {\n    active: [\n        Active,\n    ],\n    ..\n}"
+    );
+    assert!(!debug.contains(session.context().token));
+}
@@ -0,0 +1,128 @@
+// lit-bit-core/tests/deferred_start_integration_test.rs
+//
+// `Runtime::new_deferred`: the initial configuration is set at construction, but entry
+// actions wait for `start`, and events sent before it are rejected.
+
+use lit_bit_core::{MAX_ACTIVE_REGIONS, ProcessingError, Runtime, SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cmd {
+    Boot,
+    Dim,
+}
+
+/// Entry actions log to `bus`, which only exists once it has been opened.
+#[derive(Debug, Clone, Default)]
+pub struct Panel {
+    pub bus: Option<Vec<&'static str>>,
+}
+
+fn log(ctx: &mut Panel, line: &'static str) {
+    ctx.bus
+        .as_mut()
+        .expect("bus opened before entry")
+        .push(line);
+}
+
+pub fn enter_panel(ctx: &mut Panel, _event: &Cmd) {
+    log(ctx, "Panel");
+}
+
+pub fn enter_bright(ctx: &mut Panel, _event: &Cmd) {
+    log(ctx, "Bright");
+}
+
+pub fn enter_dimmed(ctx: &mut Panel, _event: &Cmd) {
+    log(ctx, "Dimmed");
+}
+
+pub fn enter_idle(ctx: &mut Panel, _event: &Cmd) {
+    log(ctx, "Idle");
+}
+
+statechart! {
+    name: Display,
+    context: Panel,
+    event: Cmd,
+    initial: Active,
+    state Active [parallel] {
+        entry: enter_panel;
+        state Backlight {
+            initial: Bright;
+            state Bright {
+                entry: enter_bright;
+                on Cmd::Dim => Dimmed;
+            }
+            state Dimmed {
+                entry: enter_dimmed;
+            }
+        }
+        state Touch {
+            initial: Idle;
+            state Idle {
+                entry: enter_idle;
+            }
+        }
+    }
+}
+
+type DisplayRuntime = Runtime<DisplayStateId, Cmd, Panel, 4, MAX_ACTIVE_REGIONS, 16>;
+
+fn opened() -> Panel {
+    Panel {
+        bus: Some(Vec::new()),
+    }
+}
+
+#[test]
+fn the_initial_configuration_is_set_without_running_entry_actions() {
+    let runtime =
+        DisplayRuntime::new_deferred(Display::definition(), Panel::default()).expect("deferred");
+    let started = DisplayRuntime::new(Display::definition(), opened(), &Cmd::Boot).expect("new");
+
+    assert!(!runtime.is_started());
+    assert_eq!(runtime.state(), started.state());
+    assert!(runtime.context().bus.is_none());
+}
+
+#[test]
+fn events_sent_before_start_are_rejected() {
+    let mut runtime =
+        DisplayRuntime::new_deferred(Display::definition(), Panel::default()).expect("deferred");
+
+    assert_eq!(
+        runtime.send(&Cmd::Dim),
+        SendResult::Error(ProcessingError::NotStarted)
+    );
+    assert!(!runtime.is_started());
+    assert_eq!(
+        runtime.state().as_slice(),
+        [
+            DisplayStateId::ActiveBacklightBright,
+            DisplayStateId::ActiveTouchIdle
+        ]
+    );
+}
+
+#[test]
+fn start_runs_the_entry_actions_new_would_have_run() {
+    let mut runtime =
+        DisplayRuntime::new_deferred(Display::definition(), Panel::default()).expect("deferred");
+    let started = DisplayRuntime::new(Display::definition(), opened(), &Cmd::Boot).expect("new");
+
+    runtime.context_mut().bus = Some(Vec::new());
+    runtime.start(&Cmd::Boot).expect("entry actions run");
+
+    assert!(runtime.is_started());
+    assert_eq!(runtime.context().bus, started.context().bus);
+    assert_eq!(runtime.state(), started.state());
+
+    // Starting again does nothing, and events are handled from now on.
+    runtime.start(&Cmd::Boot).expect("already started");
+    assert_eq!(runtime.send(&Cmd::Dim), SendResult::Transitioned);
+    assert_eq!(
+        runtime.context().bus.as_deref(),
+        Some(["Panel", "Bright", "Idle", "Dimmed"].as_slice())
+    );
+}
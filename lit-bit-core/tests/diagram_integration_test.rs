@@ -0,0 +1,120 @@
+// lit-bit-core/tests/diagram_integration_test.rs
+#![cfg(all(feature = "diagram", feature = "std"))]
+
+use lit_bit_core::config::serde_json::{self, Value, json};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cmd {
+    Start,
+    Stop,
+    Tick(u8),
+    Reset,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Ctx;
+
+pub fn is_fast(_ctx: &Ctx, event: &Cmd) -> bool {
+    matches!(event, Cmd::Tick(n) if *n > 5)
+}
+
+statechart! {
+    name: Player,
+    context: Ctx,
+    event: Cmd,
+    initial: Idle,
+    state Idle {
+        on Cmd::Start => Active;
+    }
+    state Active [parallel] {
+        on (Cmd::Stop | Cmd::Reset) => Idle;
+        state Motor {
+            initial: Slow;
+            state Slow {
+                on Cmd::Tick(_) [guard is_fast] => Fast;
+            }
+            state Fast {
+                on _ => Slow;
+            }
+        }
+        state Lights {
+            initial: Off;
+            state Off {}
+        }
+    }
+}
+
+#[test]
+fn json_describes_the_state_tree_and_labelled_transitions() {
+    let graph: Value = serde_json::from_str(&Player::definition_json()).expect("valid JSON");
+
+    assert_eq!(graph["initial"], "Idle");
+    let states = graph["states"].as_array().expect("states");
+    assert_eq!(states.len(), 2);
+    let active = &states[1];
+    assert_eq!(active["id"], "Active");
+    assert_eq!(active["parallel"], true);
+    let motor = &active["children"][0];
+    assert_eq!(
+        *motor,
+        json!({
+            "id": "Active.Motor",
+            "name": "Motor",
+            "parallel": false,
+            "initial": "Active.Motor.Slow",
+            "children": [
+                { "id": "Active.Motor.Slow", "name": "Slow", "parallel": false, "initial": null, "children": [] },
+                { "id": "Active.Motor.Fast", "name": "Fast", "parallel": false, "initial": null, "children": [] }
+            ]
+        })
+    );
+
+    let transitions = graph["transitions"].as_array().expect("transitions");
+    let from = |source: &str| {
+        transitions
+            .iter()
+            .find(|transition| transition["source"] == source)
+            .unwrap_or_else(|| panic!("transition from {source}"))
+    };
+    assert_eq!(from("Active")["events"], json!(["Stop", "Reset"]));
+    assert_eq!(from("Active.Motor.Slow")["guarded"], true);
+    assert_eq!(from("Active.Motor.Slow")["target"], "Active.Motor.Fast");
+    assert_eq!(from("Active.Motor.Fast")["any_event"], true);
+    assert_eq!(graph["constants"], json!([]));
+}
+
+mod constants {
+    use lit_bit_core::config::serde_json::{self, Value, json};
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Knob {
+        Up,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Amp;
+
+    statechart! {
+        name: Stereo,
+        context: Amp,
+        event: Knob,
+        initial: Playing,
+        pub const MAX_VOLUME: u8 = 90;
+        state Playing {
+            on Knob::Up => Playing;
+        }
+    }
+
+    #[test]
+    fn json_lists_the_chart_constants() {
+        let graph: Value = serde_json::from_str(&Stereo::definition_json()).expect("valid JSON");
+        assert_eq!(
+            graph["constants"],
+            json!([{ "name": "MAX_VOLUME", "type": "u8", "value": "90" }])
+        );
+        assert_eq!(MAX_VOLUME, 90);
+        assert!(Stereo::new(Amp, &Knob::Up).is_ok());
+    }
+}
@@ -0,0 +1,112 @@
+// lit-bit-core/tests/entered_states_integration_test.rs
+//
+// `entered_states` lists the states entered by the most recent send, in entry order.
+
+use lit_bit_core::SendResult;
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Nav {
+    Open,
+    Next,
+    Refresh,
+    Close,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Screens {
+    pub refreshed: u32,
+}
+
+fn refresh(ctx: &mut Screens, _event: &Nav) {
+    ctx.refreshed += 1;
+}
+
+statechart! {
+    name: Wizard,
+    context: Screens,
+    event: Nav,
+    initial: Closed,
+    state Closed {
+        on Nav::Open => Dialog;
+    }
+    state Dialog {
+        initial: Steps;
+        on Nav::Close => Closed;
+        state Steps {
+            initial: First;
+            state First {
+                on Nav::Next => Second;
+            }
+            state Second {
+                on Nav::Refresh => Second [action refresh];
+            }
+        }
+    }
+}
+
+fn wizard() -> Wizard {
+    Wizard::new(Screens::default(), &Nav::Close).expect("machine starts")
+}
+
+#[test]
+fn lists_the_initial_configuration_after_construction() {
+    assert_eq!(wizard().entered_states(), [WizardStateId::Closed]);
+}
+
+#[test]
+fn lists_newly_entered_states_parents_first() {
+    let mut machine = wizard();
+
+    assert_eq!(machine.send(&Nav::Open), SendResult::Transitioned);
+    assert_eq!(
+        machine.entered_states(),
+        [
+            WizardStateId::Dialog,
+            WizardStateId::DialogSteps,
+            WizardStateId::DialogStepsFirst,
+        ]
+    );
+
+    // Ancestors that stay active are not listed.
+    assert_eq!(machine.send(&Nav::Next), SendResult::Transitioned);
+    assert_eq!(machine.entered_states(), [WizardStateId::DialogStepsSecond]);
+}
+
+#[test]
+fn includes_states_re_entered_by_a_self_transition() {
+    let mut machine = wizard();
+    assert_eq!(machine.send(&Nav::Open), SendResult::Transitioned);
+    assert_eq!(machine.send(&Nav::Next), SendResult::Transitioned);
+
+    let before = machine.clone();
+    assert_eq!(machine.send(&Nav::Refresh), SendResult::Transitioned);
+    assert_eq!(machine.entered_states(), [WizardStateId::DialogStepsSecond]);
+    assert!(before.diff(&machine).is_empty());
+}
+
+#[test]
+fn is_cleared_by_a_send_that_does_not_transition() {
+    let mut machine = wizard();
+    assert_eq!(machine.send(&Nav::Open), SendResult::Transitioned);
+
+    assert_eq!(machine.send(&Nav::Open), SendResult::NoMatch);
+    assert!(machine.entered_states().is_empty());
+}
+
+#[test]
+fn lists_the_states_entered_by_a_forced_transition() {
+    let mut machine = wizard();
+
+    machine
+        .force_transition(WizardStateId::DialogStepsSecond, &Nav::Open)
+        .expect("target exists");
+    assert_eq!(
+        machine.entered_states(),
+        [
+            WizardStateId::Dialog,
+            WizardStateId::DialogSteps,
+            WizardStateId::DialogStepsSecond,
+        ]
+    );
+}
@@ -0,0 +1,93 @@
+// lit-bit-core/tests/event_filter_integration_test.rs
+//
+// Event filters given at construction: rejected events are unhandled without transitioning.
+
+use lit_bit_core::{MAX_ACTIVE_REGIONS, Runtime, SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cmd {
+    Boot,
+    Start,
+    Stop,
+    Calibrate,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Log {
+    pub handled: Vec<Cmd>,
+    pub raised: Vec<Cmd>,
+}
+
+pub fn record(ctx: &mut Log, event: &Cmd) {
+    ctx.handled.push(*event);
+}
+
+pub fn start_and_calibrate(ctx: &mut Log, event: &Cmd) {
+    ctx.handled.push(*event);
+    ctx.raised.push(Cmd::Calibrate);
+}
+
+statechart! {
+    name: Motor,
+    context: Log,
+    event: Cmd,
+    initial: Stopped,
+    state Stopped {
+        on Cmd::Start => Running [action start_and_calibrate];
+        on Cmd::Calibrate => Calibrating [action record];
+    }
+    state Running {
+        on Cmd::Stop => Stopped [action record];
+        on Cmd::Calibrate => Calibrating [action record];
+    }
+    state Calibrating {
+        on Cmd::Stop => Stopped [action record];
+    }
+}
+
+/// Calibration is not supported in this build.
+fn without_calibration(event: &Cmd) -> bool {
+    *event != Cmd::Calibrate
+}
+
+#[test]
+fn filtered_events_do_not_transition() {
+    let mut motor = Motor::with_event_filter(Log::default(), &Cmd::Boot, without_calibration)
+        .expect("machine starts");
+
+    assert_eq!(motor.send(&Cmd::Calibrate), SendResult::NoMatch);
+    assert_eq!(motor.state()[0], MotorStateId::Stopped);
+    assert!(motor.context().handled.is_empty());
+
+    assert_eq!(motor.send(&Cmd::Start), SendResult::Transitioned);
+    assert_eq!(motor.send(&Cmd::Calibrate), SendResult::NoMatch);
+    assert_eq!(motor.state()[0], MotorStateId::Running);
+    assert_eq!(motor.context().handled, [Cmd::Start]);
+}
+
+#[test]
+fn machines_without_a_filter_handle_every_event() {
+    let mut motor = Motor::new(Log::default(), &Cmd::Boot).expect("machine starts");
+
+    assert_eq!(motor.send(&Cmd::Calibrate), SendResult::Transitioned);
+    assert_eq!(motor.state()[0], MotorStateId::Calibrating);
+}
+
+#[test]
+fn raised_events_are_filtered_too() {
+    type MotorRuntime = Runtime<MotorStateId, Cmd, Log, 3, MAX_ACTIVE_REGIONS, 12>;
+    let mut runtime = MotorRuntime::with_event_filter(
+        Motor::definition(),
+        Log::default(),
+        &Cmd::Boot,
+        without_calibration,
+    )
+    .expect("runtime starts");
+    runtime.set_raised_event_source(|ctx| ctx.raised.pop());
+
+    assert_eq!(runtime.send(&Cmd::Start), SendResult::Transitioned);
+    assert_eq!(runtime.state()[0], MotorStateId::Running);
+    assert_eq!(runtime.context().handled, [Cmd::Start]);
+    assert!(runtime.context().raised.is_empty());
+}
@@ -0,0 +1,57 @@
+// lit-bit-core/tests/event_size_budget_integration_test.rs
+//
+// `max_event_size:` in the chart header: the declared budget, checked against the event
+// type at compile time, and the RAM a mailbox of those events needs.
+
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Frame {
+    Boot,
+    Byte(u8),
+    Word(u32, u16),
+    Window { start: u16, len: u16 },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Link;
+
+statechart! {
+    name: Uart,
+    context: Link,
+    event: Frame,
+    mailbox_capacity: 8,
+    max_event_size: 8,
+    initial: Idle,
+    state Idle {
+        on Frame::Byte(_) | Frame::Word(_, _) => Receiving;
+    }
+    state Receiving {
+        on Frame::Window { len, .. } [guard *len > 0] => Idle;
+        on Frame::Boot => Idle;
+    }
+}
+
+#[test]
+fn declared_budget_is_exposed_as_a_const() {
+    assert_eq!(Uart::MAX_EVENT_SIZE, 8);
+    assert!(core::mem::size_of::<Frame>() <= Uart::MAX_EVENT_SIZE);
+}
+
+#[test]
+fn the_budget_bounds_the_mailbox_ram() {
+    const MAILBOX_BYTES: usize = Uart::MAILBOX_CAPACITY * Uart::MAX_EVENT_SIZE;
+    assert_eq!(MAILBOX_BYTES, 64);
+}
+
+#[test]
+fn a_budgeted_machine_runs_as_usual() {
+    let mut uart = Uart::new(Link, &Frame::Boot).expect("machine starts");
+    assert_eq!(uart.send(&Frame::Word(1, 2)), SendResult::Transitioned);
+    assert_eq!(
+        uart.send(&Frame::Window { start: 0, len: 4 }),
+        SendResult::Transitioned
+    );
+    assert_eq!(uart.state()[0], UartStateId::Idle);
+}
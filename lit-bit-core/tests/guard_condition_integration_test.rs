@@ -0,0 +1,212 @@
+// lit-bit-core/tests/guard_condition_integration_test.rs
+//
+// Guard conditions: `[guard <expr>]` where the expression reads the event's bindings,
+// `self` for the context and `in(State)` for the active configuration, alone or combined.
+
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ev {
+    Boot,
+    Arm,
+    Check,
+    Go,
+    Set(u8),
+    Fire(u8),
+    Reset,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Panel {
+    pub enabled: bool,
+    pub limit: u8,
+}
+
+impl Panel {
+    fn ok(&self, power: u8) -> bool {
+        power <= self.limit
+    }
+}
+
+statechart! {
+    name: Console,
+    context: Panel,
+    event: Ev,
+    initial: Board,
+    state Board [parallel] {
+        state Safety {
+            initial: Locked;
+            state Locked {
+                on Ev::Arm => Ready;
+            }
+            state Ready {
+                on Ev::Arm => Locked;
+            }
+        }
+        state Launcher {
+            initial: Idle;
+            on Ev::Reset => Idle;
+            state Idle {
+                on Ev::Fire(x) [guard self.ok(*x) && in(Board::Safety::Ready)] => Launched else => Refused;
+                on Ev::Set(x) [guard *x > 3] => Primed;
+                on Ev::Go [guard in(Board::Safety::Ready) || self.enabled] => Primed;
+                on Ev::Check [guard self.enabled] => Checked;
+            }
+            state Launched {}
+            state Refused {}
+            state Primed {}
+            state Checked {}
+        }
+    }
+}
+
+fn console(panel: Panel) -> Console {
+    Console::new(panel, &Ev::Boot).expect("console starts")
+}
+
+fn launcher(console: &Console) -> ConsoleStateId {
+    *console
+        .state()
+        .iter()
+        .find(|leaf| {
+            !matches!(
+                leaf,
+                ConsoleStateId::BoardSafetyLocked | ConsoleStateId::BoardSafetyReady
+            )
+        })
+        .expect("the launcher region is active")
+}
+
+fn armed(panel: Panel) -> Console {
+    let mut console = console(panel);
+    assert_eq!(console.send(&Ev::Arm), SendResult::Transitioned);
+    console
+}
+
+#[test]
+fn a_binding_only_guard_reads_the_event_payload() {
+    let mut console = console(Panel::default());
+    assert_eq!(console.send(&Ev::Set(2)), SendResult::NoMatch);
+    assert_eq!(launcher(&console), ConsoleStateId::BoardLauncherIdle);
+
+    assert_eq!(console.send(&Ev::Set(7)), SendResult::Transitioned);
+    assert_eq!(launcher(&console), ConsoleStateId::BoardLauncherPrimed);
+}
+
+#[test]
+fn a_context_only_guard_reads_self() {
+    let mut console = console(Panel::default());
+    assert_eq!(console.send(&Ev::Check), SendResult::NoMatch);
+
+    console.context_mut().enabled = true;
+    assert_eq!(console.send(&Ev::Check), SendResult::Transitioned);
+    assert_eq!(launcher(&console), ConsoleStateId::BoardLauncherChecked);
+}
+
+#[test]
+fn an_in_state_guard_reads_the_other_region() {
+    let mut console = console(Panel::default());
+    assert_eq!(console.send(&Ev::Go), SendResult::NoMatch);
+
+    let mut console_armed = armed(Panel::default());
+    assert_eq!(console_armed.send(&Ev::Go), SendResult::Transitioned);
+    assert_eq!(
+        launcher(&console_armed),
+        ConsoleStateId::BoardLauncherPrimed
+    );
+
+    // The other side of the `||` lets an enabled panel through while locked.
+    console.context_mut().enabled = true;
+    assert_eq!(console.send(&Ev::Go), SendResult::Transitioned);
+}
+
+#[test]
+fn a_combined_guard_needs_the_binding_the_context_and_the_state() {
+    let limited = Panel {
+        limit: 5,
+        ..Panel::default()
+    };
+
+    // Armed, within the limit: launches.
+    let mut pad = armed(limited.clone());
+    assert_eq!(pad.send(&Ev::Fire(5)), SendResult::Transitioned);
+    assert_eq!(launcher(&pad), ConsoleStateId::BoardLauncherLaunched);
+
+    // Armed, over the limit: the `else` branch.
+    let mut pad = armed(limited.clone());
+    assert_eq!(pad.send(&Ev::Fire(6)), SendResult::Transitioned);
+    assert_eq!(launcher(&pad), ConsoleStateId::BoardLauncherRefused);
+
+    // Within the limit but locked: the `else` branch too.
+    let mut pad = console(limited);
+    assert_eq!(pad.send(&Ev::Fire(1)), SendResult::Transitioned);
+    assert_eq!(launcher(&pad), ConsoleStateId::BoardLauncherRefused);
+
+    // Arming and disarming again is seen by the next evaluation.
+    assert_eq!(pad.send(&Ev::Reset), SendResult::Transitioned);
+    assert_eq!(pad.send(&Ev::Arm), SendResult::Transitioned);
+    assert_eq!(pad.send(&Ev::Arm), SendResult::Transitioned);
+    assert_eq!(pad.send(&Ev::Fire(1)), SendResult::Transitioned);
+    assert_eq!(launcher(&pad), ConsoleStateId::BoardLauncherRefused);
+}
+
+#[test]
+fn condition_guards_are_reported_as_guarded() {
+    let definition = Console::definition();
+    let guarded = definition
+        .transitions
+        .iter()
+        .filter(|transition| transition.from_state == ConsoleStateId::BoardLauncherIdle)
+        .filter(|transition| transition.is_guarded())
+        .count();
+    assert_eq!(guarded, 4);
+}
+
+#[cfg(feature = "single-region")]
+mod flat {
+    use lit_bit_core::SendResult;
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Knob {
+        Boot,
+        Turn(i8),
+        Press,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Dial {
+        pub floor: i8,
+    }
+
+    statechart! {
+        name: Radio,
+        context: Dial,
+        event: Knob,
+        initial: Tuner,
+        state Tuner {
+            initial: Scanning;
+            state Scanning [terminal] {
+                on Knob::Turn(step) [guard *step >= self.floor && in(Tuner)] => Locked;
+                on Knob::Press [guard in(Tuner::Locked)] => Muted;
+            }
+            state Locked {
+                on Knob::Press [guard in(Tuner::Scanning)] => Muted else => Scanning;
+            }
+        }
+        state Muted {}
+    }
+
+    #[test]
+    fn the_flat_runtime_evaluates_condition_guards() {
+        let mut radio = Radio::new(Dial { floor: 2 }, &Knob::Boot).expect("radio starts");
+        assert_eq!(radio.send(&Knob::Press), SendResult::NoMatch);
+        assert_eq!(radio.send(&Knob::Turn(1)), SendResult::NoMatch);
+        assert_eq!(radio.send(&Knob::Turn(3)), SendResult::Transitioned);
+        assert_eq!(radio.current(), RadioStateId::TunerLocked);
+
+        assert_eq!(radio.send(&Knob::Press), SendResult::Transitioned);
+        assert_eq!(radio.current(), RadioStateId::TunerScanning);
+    }
+}
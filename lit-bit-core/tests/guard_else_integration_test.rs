@@ -0,0 +1,86 @@
+// lit-bit-core/tests/guard_else_integration_test.rs
+//
+// `on E [guard g] => A else => B;` fallbacks for guarded transitions.
+
+use lit_bit_core::{Acceptance, SendResult, StateMachine};
+use lit_bit_macro::{statechart, statechart_event};
+
+#[statechart_event]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DoorEvent {
+    Open,
+    Lock,
+    Unlock,
+    Reset,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Door {
+    pub locked: bool,
+    pub rejected_attempts: u32,
+    pub resets: u32,
+}
+
+pub fn is_unlocked(ctx: &Door, _event: &DoorEvent) -> bool {
+    !ctx.locked
+}
+
+pub fn lock(ctx: &mut Door, _event: &DoorEvent) {
+    ctx.locked = true;
+}
+
+pub fn unlock(ctx: &mut Door, _event: &DoorEvent) {
+    ctx.locked = false;
+}
+
+pub fn count_rejection(ctx: &mut Door, _event: &DoorEvent) {
+    ctx.rejected_attempts += 1;
+}
+
+pub fn count_reset(ctx: &mut Door, _event: &DoorEvent) {
+    ctx.resets += 1;
+}
+
+statechart! {
+    name: DoorMachine,
+    context: Door,
+    event: DoorEvent,
+    initial: Closed,
+    state Closed {
+        on DoorEvent::Open [guard is_unlocked] => Opened else => Alarm [action count_rejection];
+        on DoorEvent::Lock => Closed [action lock];
+        on DoorEvent::Unlock => Closed [action unlock];
+    }
+    state Opened {
+        on DoorEvent::Reset => Closed;
+    }
+    state Alarm {
+        on DoorEvent::Reset => Closed [action count_reset];
+    }
+}
+
+#[test]
+fn else_branch_is_taken_only_when_the_guard_rejects() {
+    let mut door = DoorMachine::new(Door::default(), &DoorEvent::Reset).expect("machine starts");
+
+    assert_eq!(door.send(&DoorEvent::Open), SendResult::Transitioned);
+    assert_eq!(door.state()[0], DoorMachineStateId::Opened);
+    assert_eq!(door.context().rejected_attempts, 0);
+
+    assert_eq!(door.send(&DoorEvent::Reset), SendResult::Transitioned);
+    assert_eq!(door.send(&DoorEvent::Lock), SendResult::Transitioned);
+    assert_eq!(door.send(&DoorEvent::Open), SendResult::Transitioned);
+    assert_eq!(door.state()[0], DoorMachineStateId::Alarm);
+    assert_eq!(door.context().rejected_attempts, 1);
+
+    assert_eq!(door.send(&DoorEvent::Reset), SendResult::Transitioned);
+    assert_eq!(door.context().resets, 1);
+}
+
+#[test]
+fn guarded_transition_with_else_always_accepts_its_event() {
+    let door = DoorMachine::new(Door::default(), &DoorEvent::Reset).expect("machine starts");
+
+    let accepted: Vec<_> = door.accepted_event_kinds::<DoorEventKind>().collect();
+    assert!(accepted.contains(&(DoorEventKind::Open, Acceptance::Accepted)));
+}
@@ -0,0 +1,103 @@
+// lit-bit-core/tests/guarded_hook_integration_test.rs
+//
+// `entry [guard cond]: action;` and `exit [guard cond]: action;`: hooks that only run when
+// their guard holds.
+
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ev {
+    Boot,
+    Open { quiet: bool },
+    Close { quiet: bool },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Logger {
+    pub verbose: bool,
+    pub lines: Vec<&'static str>,
+}
+
+fn is_verbose(ctx: &Logger, _event: &Ev) -> bool {
+    ctx.verbose
+}
+
+fn log_open(ctx: &mut Logger, _event: &Ev) {
+    ctx.lines.push("opened");
+}
+
+fn log_close(ctx: &mut Logger, _event: &Ev) {
+    ctx.lines.push("closing");
+}
+
+fn log_closed(ctx: &mut Logger, _event: &Ev) {
+    ctx.lines.push("closed");
+}
+
+statechart! {
+    name: Valve,
+    context: Logger,
+    event: Ev,
+    initial: Closed,
+    state Closed {
+        entry: log_closed;
+        on Ev::Open { .. } => Open;
+    }
+    state Open {
+        // The guard sees the context and the triggering event, like a transition guard.
+        entry [guard is_verbose]: log_open;
+        exit [guard |_ctx: &Logger, event: &Ev| !matches!(event, Ev::Close { quiet: true })]: log_close;
+        on Ev::Close { .. } => Closed;
+    }
+}
+
+fn valve(verbose: bool) -> Valve {
+    let mut valve = Valve::new(
+        Logger {
+            verbose,
+            lines: Vec::new(),
+        },
+        &Ev::Boot,
+    )
+    .expect("valve starts");
+    valve.context_mut().lines.clear();
+    valve
+}
+
+#[test]
+fn guarded_entry_runs_only_when_the_guard_holds() {
+    let mut verbose = valve(true);
+    assert_eq!(
+        verbose.send(&Ev::Open { quiet: false }),
+        SendResult::Transitioned
+    );
+    assert_eq!(verbose.context().lines, ["opened"]);
+
+    let mut quiet = valve(false);
+    assert_eq!(
+        quiet.send(&Ev::Open { quiet: false }),
+        SendResult::Transitioned
+    );
+    assert!(quiet.context().lines.is_empty());
+    assert_eq!(quiet.state()[0], ValveStateId::Open);
+}
+
+#[test]
+fn guarded_exit_sees_the_triggering_event() {
+    let mut valve = valve(false);
+    let _ = valve.send(&Ev::Open { quiet: false });
+    assert_eq!(
+        valve.send(&Ev::Close { quiet: false }),
+        SendResult::Transitioned
+    );
+    assert_eq!(valve.context().lines, ["closing", "closed"]);
+
+    valve.context_mut().lines.clear();
+    let _ = valve.send(&Ev::Open { quiet: false });
+    assert_eq!(
+        valve.send(&Ev::Close { quiet: true }),
+        SendResult::Transitioned
+    );
+    assert_eq!(valve.context().lines, ["closed"]);
+}
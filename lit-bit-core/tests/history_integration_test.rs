@@ -0,0 +1,196 @@
+// lit-bit-core/tests/history_integration_test.rs
+//
+// `initial: history Child;` on the regions of a parallel state: each region re-enters the
+// leaf it was last left from, independently of the others, when the parallel state is
+// entered again.
+
+use lit_bit_core::SendResult;
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Remote {
+    Power,
+    Volume,
+    Picture,
+    Subtitles,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Screen {
+    picture_entries: u32,
+}
+
+fn count_picture_entry(screen: &mut Screen, _event: &Remote) {
+    screen.picture_entries += 1;
+}
+
+statechart! {
+    name: Television,
+    context: Screen,
+    event: Remote,
+    initial: Standby,
+    state Standby {
+        on Remote::Power => Watching;
+    }
+    state Watching [parallel] {
+        on Remote::Power => Standby;
+        state Sound {
+            initial: history Muted;
+            state Muted {
+                on Remote::Volume => Quiet;
+            }
+            state Quiet {
+                on Remote::Volume => Loud;
+            }
+            state Loud {}
+        }
+        state Video {
+            initial: history Dark;
+            state Dark {
+                on Remote::Picture => Lit;
+            }
+            state Lit {
+                initial: Standard;
+                entry: count_picture_entry;
+                state Standard {
+                    on Remote::Picture => Vivid;
+                }
+                state Vivid {}
+            }
+        }
+        state Captions {
+            initial: Hidden;
+            state Hidden {
+                on Remote::Subtitles => Shown;
+            }
+            state Shown {}
+        }
+    }
+}
+
+fn leaves(tv: &Television) -> Vec<TelevisionStateId> {
+    let mut leaves = Vec::new();
+    tv.visit_active(|leaf| leaves.push(leaf));
+    leaves.sort();
+    leaves
+}
+
+fn sorted(mut states: Vec<TelevisionStateId>) -> Vec<TelevisionStateId> {
+    states.sort();
+    states
+}
+
+#[test]
+fn each_region_restores_its_own_last_leaf() {
+    let mut tv = Television::new(Screen::default(), &Remote::Power).expect("tv starts");
+    assert_eq!(tv.send(&Remote::Power), SendResult::Transitioned);
+    assert_eq!(
+        leaves(&tv),
+        sorted(vec![
+            TelevisionStateId::WatchingSoundMuted,
+            TelevisionStateId::WatchingVideoDark,
+            TelevisionStateId::WatchingCaptionsHidden,
+        ])
+    );
+
+    // Each region moves on its own: two steps in `Sound`, two levels deep in `Video`.
+    assert_eq!(tv.send(&Remote::Volume), SendResult::Transitioned);
+    assert_eq!(tv.send(&Remote::Volume), SendResult::Transitioned);
+    assert_eq!(tv.send(&Remote::Picture), SendResult::Transitioned);
+    assert_eq!(tv.send(&Remote::Picture), SendResult::Transitioned);
+    assert_eq!(
+        leaves(&tv),
+        sorted(vec![
+            TelevisionStateId::WatchingSoundLoud,
+            TelevisionStateId::WatchingVideoLitVivid,
+            TelevisionStateId::WatchingCaptionsHidden,
+        ])
+    );
+
+    assert_eq!(tv.send(&Remote::Power), SendResult::Transitioned);
+    assert_eq!(leaves(&tv), vec![TelevisionStateId::Standby]);
+    assert_eq!(tv.send(&Remote::Power), SendResult::Transitioned);
+    assert_eq!(
+        leaves(&tv),
+        sorted(vec![
+            TelevisionStateId::WatchingSoundLoud,
+            TelevisionStateId::WatchingVideoLitVivid,
+            TelevisionStateId::WatchingCaptionsHidden,
+        ])
+    );
+}
+
+#[test]
+fn restoring_enters_the_states_above_the_leaf_and_other_regions_start_over() {
+    let mut tv = Television::new(Screen::default(), &Remote::Power).expect("tv starts");
+    let _ = tv.send(&Remote::Power);
+    let _ = tv.send(&Remote::Picture);
+    let _ = tv.send(&Remote::Subtitles);
+    assert_eq!(tv.context().picture_entries, 1);
+
+    let _ = tv.send(&Remote::Power);
+    let _ = tv.send(&Remote::Power);
+    // `Lit` is entered again on the way to `Standard`; `Captions` has no history.
+    assert_eq!(tv.context().picture_entries, 2);
+    assert_eq!(
+        leaves(&tv),
+        sorted(vec![
+            TelevisionStateId::WatchingSoundMuted,
+            TelevisionStateId::WatchingVideoLitStandard,
+            TelevisionStateId::WatchingCaptionsHidden,
+        ])
+    );
+}
+
+#[test]
+fn reset_forgets_history() {
+    let mut tv = Television::new(Screen::default(), &Remote::Power).expect("tv starts");
+    let _ = tv.send(&Remote::Power);
+    let _ = tv.send(&Remote::Volume);
+    let _ = tv.send(&Remote::Power);
+
+    tv.reset(&Remote::Power).expect("tv resets");
+    tv.start(&Remote::Power).expect("tv starts again");
+    let _ = tv.send(&Remote::Power);
+    assert_eq!(
+        leaves(&tv),
+        sorted(vec![
+            TelevisionStateId::WatchingSoundMuted,
+            TelevisionStateId::WatchingVideoDark,
+            TelevisionStateId::WatchingCaptionsHidden,
+        ])
+    );
+}
+
+// Without parallel states: the chart uses `Runtime` even with `single-region`, since the
+// single-leaf runtime keeps no history.
+statechart! {
+    name: Menu,
+    context: Screen,
+    event: Remote,
+    initial: Closed,
+    state Closed {
+        on Remote::Power => Open;
+    }
+    state Open {
+        initial: history Picture;
+        on Remote::Power => Closed;
+        state Picture {
+            on Remote::Volume => Sound;
+        }
+        state Sound {}
+    }
+}
+
+#[test]
+fn a_compound_state_restores_its_last_leaf() {
+    let mut menu = Menu::new(Screen::default(), &Remote::Power).expect("menu starts");
+    let _ = menu.send(&Remote::Power);
+    let _ = menu.send(&Remote::Volume);
+    assert_eq!(menu.current(), MenuStateId::OpenSound);
+
+    let _ = menu.send(&Remote::Power);
+    assert_eq!(menu.current(), MenuStateId::Closed);
+    let _ = menu.send(&Remote::Power);
+    assert_eq!(menu.current(), MenuStateId::OpenSound);
+}
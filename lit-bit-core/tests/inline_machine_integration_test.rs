@@ -0,0 +1,108 @@
+// lit-bit-core/tests/inline_machine_integration_test.rs
+
+use lit_bit_core::{SendResult, StateMachine, statechart_inline};
+
+/// Counts how deeply nested a bracket sequence is, using a machine whose types and
+/// actions are all local to the function.
+fn max_depth(input: &str) -> u32 {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Token {
+        Start,
+        Open,
+        Close,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct Depth {
+        current: u32,
+        max: u32,
+    }
+
+    fn descend(depth: &mut Depth, _event: &Token) {
+        depth.current += 1;
+        depth.max = depth.max.max(depth.current);
+    }
+
+    fn ascend(depth: &mut Depth, _event: &Token) {
+        depth.current = depth.current.saturating_sub(1);
+    }
+
+    fn is_nested(depth: &Depth, _event: &Token) -> bool {
+        depth.current > 1
+    }
+
+    let seed = Depth::default();
+    let mut machine = statechart_inline! {
+        context: Depth = seed,
+        event: Token = Token::Start,
+        initial: Outside,
+        state Outside {
+            on Token::Open => Inside [action descend];
+        }
+        state Inside {
+            on Token::Open => Inside [action descend];
+            on Token::Close [guard is_nested] => Inside [action ascend];
+            on Token::Close => Outside [action ascend];
+        }
+    }
+    .expect("inline machine starts");
+
+    for c in input.chars() {
+        let token = if c == '(' { Token::Open } else { Token::Close };
+        let _ = machine.send(&token);
+    }
+    machine.context().max
+}
+
+#[test]
+fn inline_machine_runs_inside_a_function_body() {
+    assert_eq!(max_depth(""), 0);
+    assert_eq!(max_depth("()()"), 1);
+    assert_eq!(max_depth("(()(()))"), 3);
+}
+
+#[test]
+fn inline_machine_without_context_implements_state_machine() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Switch {
+        Toggle,
+    }
+
+    let mut machine = statechart_inline! {
+        event: Switch = Switch::Toggle,
+        initial: Off,
+        state Off { on Switch::Toggle => On; }
+        state On { on Switch::Toggle => Off; }
+    }
+    .expect("inline machine starts");
+
+    fn toggle<M: StateMachine<Event = Switch>>(machine: &mut M) -> SendResult {
+        machine.send(&Switch::Toggle)
+    }
+    assert_eq!(toggle(&mut machine), SendResult::Transitioned);
+    assert_eq!(machine.state()[0].user_path(), "On");
+    assert_eq!(toggle(&mut machine), SendResult::Transitioned);
+    assert_eq!(machine.state()[0].user_path(), "Off");
+}
+
+#[test]
+fn inline_machines_in_one_scope_do_not_collide() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Ping {
+        Go,
+    }
+
+    let first = statechart_inline! {
+        event: Ping = Ping::Go,
+        initial: A,
+        state A { on Ping::Go => B; }
+        state B {}
+    };
+    let second = statechart_inline! {
+        event: Ping = Ping::Go,
+        initial: X,
+        state X {}
+    };
+    assert_eq!(first.expect("first").state()[0].user_path(), "A");
+    assert_eq!(second.expect("second").state()[0].user_path(), "X");
+}
@@ -0,0 +1,203 @@
+// lit-bit-core/tests/invariant_integration_test.rs
+//
+// `invariant:` charts: the context check run after every external event and its cascade.
+// Integration tests build in debug mode, so the check is always enabled here.
+
+use std::collections::VecDeque;
+
+use lit_bit_core::{MAX_ACTIVE_REGIONS, Runtime, SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Txn {
+    Order(i32),
+    Debit(i32),
+    Refund(i32),
+    Confirm,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Account {
+    pub credits: i32,
+    /// Refunded after every order's debit, within the same cascade.
+    pub coupon: i32,
+    pub raised: VecDeque<Txn>,
+}
+
+impl Account {
+    fn check_invariants(&self) -> Result<(), &'static str> {
+        if self.credits < 0 {
+            return Err("credits never negative");
+        }
+        Ok(())
+    }
+}
+
+pub fn place_order(ctx: &mut Account, event: &Txn) {
+    if let Txn::Order(amount) = event {
+        ctx.raised.push_back(Txn::Debit(*amount));
+        if ctx.coupon > 0 {
+            ctx.raised.push_back(Txn::Refund(ctx.coupon));
+        }
+        ctx.raised.push_back(Txn::Confirm);
+    }
+}
+
+pub fn debit(ctx: &mut Account, event: &Txn) {
+    if let Txn::Debit(amount) = event {
+        ctx.credits -= amount;
+    }
+}
+
+pub fn refund(ctx: &mut Account, event: &Txn) {
+    if let Txn::Refund(amount) = event {
+        ctx.credits += amount;
+    }
+}
+
+fn next_raised(ctx: &mut Account) -> Option<Txn> {
+    ctx.raised.pop_front()
+}
+
+statechart! {
+    name: Shop,
+    context: Account,
+    event: Txn,
+    invariant: self.check_invariants,
+    initial: Idle,
+    state Idle {
+        on Txn::Order(_) => Charging [action place_order];
+        on Txn::Refund(_) => Idle [action refund];
+    }
+    state Charging {
+        on Txn::Debit(_) => Charging [action debit];
+        on Txn::Refund(_) => Charging [action refund];
+        on Txn::Confirm => Idle;
+    }
+}
+
+type ShopRuntime = Runtime<ShopStateId, Txn, Account, 2, MAX_ACTIVE_REGIONS, 8>;
+
+fn runtime(credits: i32) -> ShopRuntime {
+    let account = Account {
+        credits,
+        ..Account::default()
+    };
+    let mut runtime =
+        ShopRuntime::new(Shop::definition(), account, &Txn::Confirm).expect("runtime starts");
+    runtime.set_raised_event_source(next_raised);
+    runtime
+}
+
+#[test]
+fn violation_raised_mid_cascade_is_reported_after_it_completes() {
+    let mut runtime = runtime(10);
+
+    assert_eq!(
+        runtime.send(&Txn::Order(15)),
+        SendResult::InvariantViolated("credits never negative")
+    );
+    // The whole cascade ran and was kept.
+    assert_eq!(runtime.state()[0], ShopStateId::Idle);
+    assert_eq!(runtime.context().credits, -5);
+    assert_eq!(runtime.pending_internal_events(), 0);
+
+    // Still usable: the next event is checked against the repaired context.
+    assert_eq!(runtime.send(&Txn::Refund(10)), SendResult::Transitioned);
+    assert_eq!(runtime.context().credits, 5);
+    assert_eq!(runtime.send(&Txn::Order(5)), SendResult::Transitioned);
+    assert_eq!(runtime.context().credits, 0);
+}
+
+#[test]
+fn violation_repaired_before_the_cascade_ends_is_not_reported() {
+    let mut runtime = runtime(10);
+    runtime.context_mut().coupon = 20;
+
+    // Credits drop to -15 after the debit and are back at 5 once the coupon is refunded.
+    assert_eq!(runtime.send(&Txn::Order(25)), SendResult::Transitioned);
+    assert_eq!(runtime.context().credits, 5);
+}
+
+#[test]
+fn budgeted_send_checks_only_once_the_cascade_has_completed() {
+    struct Step(std::cell::Cell<u64>);
+    impl lit_bit_core::MonotonicClock for Step {
+        fn now(&self) -> u64 {
+            let now = self.0.get();
+            self.0.set(now + 1);
+            now
+        }
+    }
+    let clock = Step(std::cell::Cell::new(0));
+    let mut runtime = runtime(10);
+
+    assert!(matches!(
+        runtime.send_with_budget(&Txn::Order(15), 1, &clock),
+        SendResult::BudgetExhausted { .. }
+    ));
+    assert_eq!(
+        runtime.send_with_budget(&Txn::Refund(1), 100, &clock),
+        SendResult::InvariantViolated("credits never negative")
+    );
+    assert_eq!(runtime.context().credits, -4);
+}
+
+#[test]
+fn generated_machine_reports_violations_in_send_and_send_batch() {
+    let mut shop = Shop::new(Account::default(), &Txn::Confirm).expect("machine starts");
+
+    // Without a raised-event source, `Order` only moves to `Charging`.
+    assert_eq!(shop.send(&Txn::Order(3)), SendResult::Transitioned);
+    assert_eq!(
+        shop.send(&Txn::Debit(3)),
+        SendResult::InvariantViolated("credits never negative")
+    );
+
+    let summary = shop.send_batch(&[Txn::Debit(1), Txn::Refund(10), Txn::Confirm]);
+    assert_eq!(summary.invariant_violations, 1);
+    assert_eq!(
+        summary.first_invariant_violation,
+        Some("credits never negative")
+    );
+    assert_eq!(summary.transitioned, 2);
+    assert_eq!(summary.processed(), 3);
+    assert_eq!(shop.context().credits, 6);
+    assert_eq!(shop.state()[0], ShopStateId::Idle);
+}
+
+#[test]
+#[should_panic(expected = "context invariant violated: credits never negative")]
+fn violations_can_panic_instead() {
+    let mut runtime = runtime(0);
+    runtime.set_panic_on_invariant_violation(true);
+    let _ = runtime.send(&Txn::Order(1));
+}
+
+#[test]
+fn set_context_swaps_without_running_actions_or_checks() {
+    let mut runtime = runtime(10);
+    runtime.set_panic_on_invariant_violation(true);
+    assert_eq!(runtime.send(&Txn::Order(4)), SendResult::Transitioned);
+
+    let broken = Account {
+        credits: -1,
+        ..Account::default()
+    };
+    let previous = runtime.set_context(broken);
+    assert_eq!(previous.credits, 6);
+    assert_eq!(runtime.context().credits, -1);
+    assert_eq!(runtime.state()[0], ShopStateId::Idle);
+    assert_eq!(runtime.pending_internal_events(), 0);
+
+    // The generated machine swaps the same way.
+    let mut shop = Shop::new(Account::default(), &Txn::Confirm).expect("machine starts");
+    let fresh = shop.set_context(Account {
+        credits: 3,
+        ..Account::default()
+    });
+    assert_eq!(fresh.credits, 0);
+    assert_eq!(shop.send(&Txn::Order(3)), SendResult::Transitioned);
+    assert_eq!(shop.send(&Txn::Debit(3)), SendResult::Transitioned);
+    assert_eq!(shop.context().credits, 0);
+}
@@ -0,0 +1,141 @@
+// lit-bit-core/tests/leaf_accessor_integration_test.rs
+//
+// `current()` on charts without parallel states and `visit_active` on parallel ones: the
+// active leaves in a shape `match` and loops can use without unwrapping a `Vec`.
+
+mod flat {
+    use lit_bit_core::StateMachine;
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Tick {
+        Next,
+        Hold,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Lamp;
+
+    statechart! {
+        name: Signal,
+        context: Lamp,
+        event: Tick,
+        initial: Red,
+        state Red {
+            on Tick::Next => Green;
+        }
+        state Green {
+            on Tick::Next => Amber;
+        }
+        state Amber {
+            initial: Steady;
+            on Tick::Next => Red;
+            state Steady {
+                on Tick::Hold => Blinking;
+            }
+            state Blinking {}
+        }
+    }
+
+    fn label(signal: &Signal) -> &'static str {
+        match signal.current() {
+            SignalStateId::Red => "stop",
+            SignalStateId::Green => "go",
+            SignalStateId::Amber | SignalStateId::AmberSteady => "slow",
+            SignalStateId::AmberBlinking => "caution",
+        }
+    }
+
+    #[test]
+    fn current_is_the_single_active_leaf() {
+        let mut signal = Signal::new(Lamp, &Tick::Next).expect("signal starts");
+        assert_eq!(signal.current(), SignalStateId::Red);
+        assert_eq!(label(&signal), "stop");
+
+        let _ = signal.send(&Tick::Next);
+        let _ = signal.send(&Tick::Next);
+        assert_eq!(signal.current(), SignalStateId::AmberSteady);
+        let _ = signal.send(&Tick::Hold);
+        assert_eq!(label(&signal), "caution");
+        assert_eq!(signal.state().as_slice(), [signal.current()]);
+    }
+
+    #[test]
+    fn an_unstarted_machine_reports_its_initial_leaf() {
+        let mut signal = Signal::const_new(Lamp);
+        assert!(signal.state().is_empty());
+        assert_eq!(signal.current(), SignalStateId::Red);
+
+        signal.start(&Tick::Next).expect("signal starts");
+        assert_eq!(signal.current(), SignalStateId::Red);
+    }
+}
+
+mod parallel {
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Knob {
+        Boot,
+        Heat,
+        Fan,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Dash;
+
+    statechart! {
+        name: Climate,
+        context: Dash,
+        event: Knob,
+        initial: Cabin,
+        state Cabin [parallel] {
+            state Heater {
+                initial: Off;
+                state Off {
+                    on Knob::Heat => On;
+                }
+                state On {}
+            }
+            state Blower {
+                initial: Low;
+                state Low {
+                    on Knob::Fan => High;
+                }
+                state High {}
+            }
+        }
+    }
+
+    fn visited(climate: &Climate) -> Vec<ClimateStateId> {
+        let mut leaves = Vec::new();
+        climate.visit_active(|leaf| leaves.push(leaf));
+        leaves.sort();
+        leaves
+    }
+
+    #[test]
+    fn visit_active_sees_every_region_leaf_once() {
+        let mut climate = Climate::new(Dash, &Knob::Boot).expect("climate starts");
+        let start = visited(&climate);
+        assert_eq!(start.len(), 2);
+        assert!(start.contains(&ClimateStateId::CabinHeaterOff));
+        assert!(start.contains(&ClimateStateId::CabinBlowerLow));
+
+        let _ = climate.send(&Knob::Fan);
+        let _ = climate.send(&Knob::Heat);
+        assert_eq!(
+            visited(&climate),
+            climate.configuration().as_slice(),
+            "visits the same leaves as the configuration"
+        );
+        assert!(visited(&climate).contains(&ClimateStateId::CabinBlowerHigh));
+        assert!(visited(&climate).contains(&ClimateStateId::CabinHeaterOn));
+    }
+
+    #[test]
+    fn an_unstarted_machine_visits_nothing() {
+        let climate = Climate::const_new(Dash);
+        assert!(visited(&climate).is_empty());
+    }
+}
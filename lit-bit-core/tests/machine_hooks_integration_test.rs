@@ -0,0 +1,204 @@
+// lit-bit-core/tests/machine_hooks_integration_test.rs
+//
+// `entry:` and `exit:` in the chart header: hooks on the machine's implicit root, run once
+// on startup before any state is entered and on reset after every state has been exited.
+
+use lit_bit_core::{MAX_ACTIVE_REGIONS, Runtime, SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Power {
+    On,
+    Off,
+    Next,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    pub log: Vec<&'static str>,
+}
+
+impl Journal {
+    fn on_machine_start(&mut self, _event: &Power) {
+        self.log.push("machine entry");
+    }
+
+    fn on_machine_stop(&mut self, _event: &Power) {
+        self.log.push("machine exit");
+    }
+}
+
+fn enter_running(ctx: &mut Journal, _event: &Power) {
+    ctx.log.push("enter Running");
+}
+
+fn exit_running(ctx: &mut Journal, _event: &Power) {
+    ctx.log.push("exit Running");
+}
+
+fn enter_warmup(ctx: &mut Journal, _event: &Power) {
+    ctx.log.push("enter Warmup");
+}
+
+fn exit_warmup(ctx: &mut Journal, _event: &Power) {
+    ctx.log.push("exit Warmup");
+}
+
+fn enter_steady(ctx: &mut Journal, _event: &Power) {
+    ctx.log.push("enter Steady");
+}
+
+fn exit_steady(ctx: &mut Journal, _event: &Power) {
+    ctx.log.push("exit Steady");
+}
+
+statechart! {
+    name: Heater,
+    context: Journal,
+    event: Power,
+    entry: self.on_machine_start,
+    exit: self.on_machine_stop,
+    initial: Running,
+    state Running {
+        initial: Warmup;
+        entry: enter_running;
+        exit: exit_running;
+        on Power::Off => Idle;
+        state Warmup {
+            entry: enter_warmup;
+            exit: exit_warmup;
+            on Power::Next => Steady;
+        }
+        state Steady {
+            entry: enter_steady;
+            exit: exit_steady;
+        }
+    }
+    state Idle {
+        on Power::On => Running;
+    }
+}
+
+fn started() -> Heater {
+    Heater::new(Journal::default(), &Power::On).expect("heater starts")
+}
+
+#[test]
+fn the_entry_hook_runs_once_before_any_state_is_entered() {
+    let mut heater = started();
+    assert_eq!(
+        heater.context().log,
+        ["machine entry", "enter Running", "enter Warmup"]
+    );
+
+    assert_eq!(heater.send(&Power::Off), SendResult::Transitioned);
+    assert_eq!(heater.send(&Power::On), SendResult::Transitioned);
+    let entries = heater
+        .context()
+        .log
+        .iter()
+        .filter(|line| **line == "machine entry")
+        .count();
+    assert_eq!(
+        entries, 1,
+        "transitions never re-run the machine entry hook"
+    );
+}
+
+#[test]
+fn a_const_machine_runs_the_entry_hook_when_it_starts() {
+    let mut heater = Heater::const_new(Journal::default());
+    assert!(heater.context().log.is_empty());
+
+    heater.start(&Power::On).expect("heater starts");
+    assert_eq!(heater.context().log[0], "machine entry");
+}
+
+#[test]
+fn reset_runs_the_exit_hook_after_every_state_exit() {
+    let mut heater = started();
+    assert_eq!(heater.send(&Power::Next), SendResult::Transitioned);
+    heater.context_mut().log.clear();
+
+    heater.reset(&Power::Off).expect("heater resets");
+    assert_eq!(
+        heater.context().log,
+        ["exit Steady", "exit Running", "machine exit"]
+    );
+    assert!(!heater.is_started());
+    assert!(heater.state().is_empty());
+
+    // Resetting a stopped machine runs nothing.
+    heater.reset(&Power::Off).expect("nothing to reset");
+    assert_eq!(heater.context().log.len(), 3);
+}
+
+#[test]
+fn the_machine_starts_over_after_a_reset() {
+    let mut heater = started();
+    heater.reset(&Power::Off).expect("heater resets");
+    heater.context_mut().log.clear();
+
+    heater.start(&Power::On).expect("heater restarts");
+    assert_eq!(
+        heater.context().log,
+        ["machine entry", "enter Running", "enter Warmup"]
+    );
+    assert_eq!(heater.state()[0], HeaterStateId::RunningWarmup);
+}
+
+#[test]
+fn runtime_reset_exits_like_the_generated_machine() {
+    type HeaterRuntime = Runtime<HeaterStateId, Power, Journal, 4, MAX_ACTIVE_REGIONS, 16>;
+    let mut runtime =
+        HeaterRuntime::new(Heater::definition(), Journal::default(), &Power::On).expect("starts");
+    runtime.context_mut().log.clear();
+
+    StateMachine::reset(&mut runtime, &Power::Off).expect("runtime resets");
+    assert_eq!(
+        runtime.context().log,
+        ["exit Warmup", "exit Running", "machine exit"]
+    );
+    assert!(!runtime.is_started());
+}
+
+mod actor {
+    use super::{Journal, Power};
+    use lit_bit_core::actor::{Actor, StatechartActor};
+    use lit_bit_macro::statechart;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    // `on_stop` consumes the actor, so this chart reports its exit hook through a static.
+    static LAMP_STOPS: AtomicU32 = AtomicU32::new(0);
+
+    fn count_stop(_ctx: &mut Journal, _event: &Power) {
+        LAMP_STOPS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    statechart! {
+        name: Lamp,
+        context: Journal,
+        event: Power,
+        exit: count_stop,
+        initial: Lit,
+        state Lit {
+            on Power::Off => Dark;
+        }
+        state Dark {
+            on Power::On => Lit;
+        }
+    }
+
+    #[test]
+    fn the_actor_adapter_resets_the_machine_when_it_stops() {
+        let lamp = Lamp::new(Journal::default(), &Power::On).expect("lamp starts");
+        let actor = StatechartActor::new(lamp).with_stop_event(Power::Off);
+        assert_eq!(actor.on_stop(), Ok(()));
+        assert_eq!(LAMP_STOPS.load(Ordering::SeqCst), 1);
+
+        // Without a stop event the machine is dropped as it is.
+        let lamp = Lamp::new(Journal::default(), &Power::On).expect("lamp starts");
+        assert_eq!(StatechartActor::new(lamp).on_stop(), Ok(()));
+        assert_eq!(LAMP_STOPS.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,95 @@
+// lit-bit-core/tests/mailbox_capacity_integration_test.rs
+//
+// `mailbox_capacity:` in the chart header: the declared bound and the generated spawn helpers.
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reading {
+    Boot,
+    Sample(u16),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Samples {
+    pub count: u32,
+}
+
+pub fn count(ctx: &mut Samples, _event: &Reading) {
+    ctx.count += 1;
+}
+
+statechart! {
+    name: Sensor,
+    context: Samples,
+    event: Reading,
+    mailbox_capacity: 2,
+    initial: Sampling,
+    state Sampling {
+        on Reading::Sample(_) => Sampling [action count];
+    }
+}
+
+#[test]
+fn declared_capacity_is_exposed_as_a_const() {
+    assert_eq!(Sensor::MAILBOX_CAPACITY, 2);
+}
+
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+mod tokio_spawn {
+    use super::{Reading, Samples, Sensor};
+    use lit_bit_core::actor::backpressure::SendError;
+
+    #[tokio::test]
+    async fn spawn_tokio_applies_backpressure_at_the_declared_bound() {
+        let (address, handle) =
+            Sensor::spawn_tokio(Samples::default(), &Reading::Boot).expect("machine starts");
+
+        // The actor task has not run yet on this single-threaded runtime, so nothing drains.
+        for value in 0..2 {
+            address
+                .try_send(Reading::Sample(value))
+                .expect("fits the declared capacity");
+        }
+        assert!(matches!(
+            address.try_send(Reading::Sample(2)),
+            Err(SendError::Full(Reading::Sample(2)))
+        ));
+
+        drop(address);
+        let result = handle.await.expect("actor task completes");
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn spawn_tokio_with_capacity_overrides_the_declared_bound() {
+        let (address, handle) =
+            Sensor::spawn_tokio_with_capacity(Samples::default(), &Reading::Boot, 1)
+                .expect("machine starts");
+
+        address
+            .try_send(Reading::Sample(0))
+            .expect("fits the overridden capacity");
+        assert!(matches!(
+            address.try_send(Reading::Sample(1)),
+            Err(SendError::Full(_))
+        ));
+
+        // Once the actor drains its mailbox, the address accepts events again.
+        address
+            .send(Reading::Sample(1))
+            .await
+            .expect("actor is still running");
+        drop(address);
+        assert!(handle.await.expect("actor task completes").is_ok());
+    }
+}
+
+#[cfg(feature = "async-embassy")]
+#[allow(dead_code)]
+fn spawn_on_embassy(
+    spawner: embassy_executor::Spawner,
+) -> Result<lit_bit_core::Address<Reading, 2>, embassy_executor::SpawnError> {
+    let machine = Sensor::new(Samples::default(), &Reading::Boot).expect("machine starts");
+    lit_bit_core::spawn_statechart_embassy!(spawner, Sensor, machine)
+}
@@ -0,0 +1,173 @@
+// lit-bit-core/tests/metrics_export_integration_test.rs
+//
+// `MetricsExporter`: an adaptive batch actor's `ActorMetrics` published as gauges and
+// counters labelled with the actor's id, read back from an in-memory recorder.
+
+#![cfg(feature = "metrics-integration")]
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use lit_bit_core::actor::metrics_export::{self, MetricsExporter};
+use lit_bit_core::actor::spawn::spawn_batch_actor_tokio_adaptive;
+use lit_bit_core::actor::{ActorId, AdaptiveBatching, BatchActor};
+use metrics::{
+    Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    with_local_recorder,
+};
+
+/// Keeps every registered metric as `name{label=value,...}`, plus the descriptions.
+#[derive(Default)]
+struct MemoryRecorder {
+    values: Mutex<BTreeMap<String, Arc<AtomicU64>>>,
+    descriptions: Mutex<Vec<String>>,
+}
+
+impl MemoryRecorder {
+    fn cell(&self, key: &Key) -> Arc<AtomicU64> {
+        let labels: Vec<_> = key
+            .labels()
+            .map(|label| format!("{}={}", label.key(), label.value()))
+            .collect();
+        let name = format!("{}{{{}}}", key.name(), labels.join(","));
+        Arc::clone(self.values.lock().unwrap().entry(name).or_default())
+    }
+
+    fn counter(&self, name: &str) -> u64 {
+        self.values.lock().unwrap()[name].load(Ordering::Relaxed)
+    }
+
+    fn gauge(&self, name: &str) -> f64 {
+        f64::from_bits(self.counter(name))
+    }
+
+    fn describe(&self, key: &KeyName, description: &SharedString) {
+        let line = format!("{}: {description}", key.as_str());
+        self.descriptions.lock().unwrap().push(line);
+    }
+}
+
+impl Recorder for MemoryRecorder {
+    fn describe_counter(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.describe(&key, &description);
+    }
+
+    fn describe_gauge(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.describe(&key, &description);
+    }
+
+    fn describe_histogram(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.describe(&key, &description);
+    }
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.cell(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.cell(key))
+    }
+
+    fn register_histogram(&self, _key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+struct Sink {
+    handled: Arc<AtomicU64>,
+}
+
+impl BatchActor for Sink {
+    type Message = u32;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle_batch(&mut self, messages: &[u32]) -> Self::Future<'_> {
+        self.handled
+            .fetch_add(messages.len() as u64, Ordering::Relaxed);
+        core::future::ready(())
+    }
+}
+
+#[tokio::test]
+async fn the_actor_figures_are_published_under_its_id() {
+    let recorder = MemoryRecorder::default();
+    let handled = Arc::new(AtomicU64::new(0));
+    let (address, metrics) = spawn_batch_actor_tokio_adaptive(
+        Sink {
+            handled: Arc::clone(&handled),
+        },
+        64,
+        AdaptiveBatching::new(1, 16),
+    );
+    let exporter = with_local_recorder(&recorder, || {
+        MetricsExporter::new(ActorId::new(7), Arc::clone(&metrics))
+    });
+
+    exporter.publish();
+    assert_eq!(recorder.gauge("lit_bit_actor_batch_limit{actor_id=7}"), 1.0);
+    assert_eq!(
+        recorder.counter("lit_bit_actor_messages_total{actor_id=7}"),
+        0
+    );
+
+    for msg in 0..40 {
+        address.send(msg).await.unwrap();
+    }
+    while handled.load(Ordering::Relaxed) < 40 {
+        tokio::task::yield_now().await;
+    }
+    exporter.publish();
+
+    // Batches of 1, 2, 4, 8, 16 and 9, as in the adaptive batching tests.
+    assert_eq!(
+        recorder.gauge("lit_bit_actor_batch_limit{actor_id=7}"),
+        16.0
+    );
+    assert_eq!(
+        recorder.gauge("lit_bit_actor_last_batch_size{actor_id=7}"),
+        9.0
+    );
+    assert_eq!(
+        recorder.counter("lit_bit_actor_batches_total{actor_id=7}"),
+        6
+    );
+    assert_eq!(
+        recorder.counter("lit_bit_actor_messages_total{actor_id=7}"),
+        40
+    );
+}
+
+#[test]
+fn named_actors_carry_their_name_and_the_metrics_are_described() {
+    let recorder = MemoryRecorder::default();
+    let id = ActorId::named("ingest");
+    with_local_recorder(&recorder, || {
+        metrics_export::describe();
+        MetricsExporter::new(id, Arc::default()).publish();
+    });
+
+    let names: Vec<_> = recorder.values.lock().unwrap().keys().cloned().collect();
+    let labels = format!("{{actor_id={},actor_name=ingest}}", id.get());
+    assert_eq!(
+        names,
+        [
+            format!("lit_bit_actor_batch_limit{labels}"),
+            format!("lit_bit_actor_batches_total{labels}"),
+            format!("lit_bit_actor_last_batch_size{labels}"),
+            format!("lit_bit_actor_messages_total{labels}"),
+        ]
+    );
+    assert_eq!(
+        *recorder.descriptions.lock().unwrap(),
+        [
+            "lit_bit_actor_batch_limit: Drain limit of the actor's next batch.",
+            "lit_bit_actor_last_batch_size: Messages in the actor's last batch.",
+            "lit_bit_actor_batches_total: Batches the actor has handled.",
+            "lit_bit_actor_messages_total: Messages the actor has handled.",
+        ]
+    );
+}
@@ -0,0 +1,188 @@
+// lit-bit-core/tests/oscillation_guard_integration_test.rs
+//
+// The oscillation guard: a pair of guards that always pass sends the machine back and forth
+// between two states, and the guard fires once one of them is entered too often.
+#![cfg(feature = "oscillation-guard")]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use lit_bit_core::{
+    MAX_ACTIVE_REGIONS, MonotonicClock, OscillationGuard, OscillationResponse, OscillationWindow,
+    Runtime, SendResult, StateMachine, Ticks,
+};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Valve {
+    Boot,
+    Settle,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Loop {
+    /// Re-raise `Settle` on every entry, so one external event never settles.
+    pub echo: bool,
+    pub raised: Vec<Valve>,
+    pub opened: u32,
+}
+
+// Both guards were meant to check opposite pressure thresholds, but each one passes.
+fn too_low(_ctx: &Loop, _event: &Valve) -> bool {
+    true
+}
+
+fn too_high(_ctx: &Loop, _event: &Valve) -> bool {
+    true
+}
+
+fn enter_open(ctx: &mut Loop, _event: &Valve) {
+    ctx.opened += 1;
+    if ctx.echo {
+        ctx.raised.push(Valve::Settle);
+    }
+}
+
+fn enter_closed(ctx: &mut Loop, _event: &Valve) {
+    if ctx.echo {
+        ctx.raised.push(Valve::Settle);
+    }
+}
+
+statechart! {
+    name: ValveMachine,
+    context: Loop,
+    event: Valve,
+    initial: Closed,
+    state Closed [terminal] {
+        entry: enter_closed;
+        on Valve::Settle [guard too_low] => Open;
+    }
+    state Open [terminal] {
+        entry: enter_open;
+        on Valve::Settle [guard too_high] => Closed;
+    }
+    state Fault {}
+}
+
+type ValveRuntime = Runtime<ValveMachineStateId, Valve, Loop, 4, MAX_ACTIVE_REGIONS, 16>;
+
+fn echoing(guard: OscillationGuard<ValveMachineStateId>) -> ValveRuntime {
+    let context = Loop {
+        echo: true,
+        ..Loop::default()
+    };
+    let mut runtime =
+        ValveRuntime::new(ValveMachine::definition(), context, &Valve::Boot).expect("valve starts");
+    runtime.context_mut().raised.clear();
+    runtime.set_raised_event_source(|ctx| ctx.raised.pop());
+    runtime.set_oscillation_guard(Some(guard));
+    runtime
+}
+
+fn machine(guard: OscillationGuard<ValveMachineStateId>) -> ValveMachine {
+    let mut machine = ValveMachine::new(Loop::default(), &Valve::Boot).expect("valve starts");
+    machine.set_oscillation_guard(Some(guard));
+    machine
+}
+
+#[test]
+fn report_stops_a_ping_pong_cascade_once_a_state_passes_the_threshold() {
+    let mut valve = echoing(
+        OscillationGuard::new(5, OscillationWindow::Events(100))
+            .with_response(OscillationResponse::Report),
+    );
+
+    assert_eq!(valve.send(&Valve::Settle), SendResult::OscillationDetected);
+    assert_eq!(
+        valve.context().opened,
+        6,
+        "fires on the sixth entry of Open"
+    );
+    assert_eq!(valve.entry_count(ValveMachineStateId::Open), 0);
+}
+
+#[test]
+fn enter_state_moves_the_machine_into_the_declared_error_state() {
+    let mut valve = echoing(
+        OscillationGuard::new(3, OscillationWindow::Events(100))
+            .with_response(OscillationResponse::EnterState(ValveMachineStateId::Fault)),
+    );
+
+    assert_eq!(valve.send(&Valve::Settle), SendResult::OscillationDetected);
+    assert_eq!(valve.state()[0], ValveMachineStateId::Fault);
+    assert_eq!(valve.context().opened, 4);
+}
+
+#[test]
+fn log_counts_entries_and_starts_over_once_the_guard_fires() {
+    let mut valve = machine(OscillationGuard::new(2, OscillationWindow::Events(100)));
+
+    let results: Vec<SendResult> = (0..6).map(|_| valve.send(&Valve::Settle)).collect();
+    assert!(
+        results
+            .iter()
+            .all(|result| *result == SendResult::Transitioned)
+    );
+    // Open was entered on sends 1, 3 and 5; the third entry fired the guard.
+    assert_eq!(valve.entry_count(ValveMachineStateId::Open), 0);
+    assert_eq!(valve.entry_count(ValveMachineStateId::Closed), 1);
+
+    valve.reset_entry_counts();
+    assert_eq!(valve.entry_count(ValveMachineStateId::Closed), 0);
+}
+
+#[test]
+fn entries_spread_over_several_windows_do_not_fire() {
+    let mut valve = machine(
+        OscillationGuard::new(2, OscillationWindow::Events(4))
+            .with_response(OscillationResponse::Report),
+    );
+
+    // Open is entered twice in every window of four events, never three times.
+    for _ in 0..20 {
+        assert_eq!(valve.send(&Valve::Settle), SendResult::Transitioned);
+    }
+    assert!(valve.entry_count(ValveMachineStateId::Open) <= 2);
+}
+
+static NOW: AtomicU64 = AtomicU64::new(0);
+
+struct TestClock;
+
+impl MonotonicClock for TestClock {
+    fn now(&self) -> Ticks {
+        NOW.load(Ordering::SeqCst)
+    }
+}
+
+static CLOCK: TestClock = TestClock;
+
+#[test]
+fn a_clock_window_fires_only_for_entries_close_together() {
+    let mut valve = machine(
+        OscillationGuard::new(2, OscillationWindow::Ticks(10, &CLOCK))
+            .with_response(OscillationResponse::Report),
+    );
+
+    // One entry of Open every 12 ticks: each lands in a fresh window.
+    for _ in 0..6 {
+        NOW.fetch_add(6, Ordering::SeqCst);
+        assert_eq!(valve.send(&Valve::Settle), SendResult::Transitioned);
+    }
+
+    // Entries with no time passing in between pile up in one window.
+    let results: Vec<SendResult> = (0..6).map(|_| valve.send(&Valve::Settle)).collect();
+    assert!(results.contains(&SendResult::OscillationDetected));
+}
+
+#[test]
+fn batches_count_the_events_that_set_off_the_guard() {
+    let mut valve = machine(
+        OscillationGuard::new(1, OscillationWindow::Events(100))
+            .with_response(OscillationResponse::Report),
+    );
+
+    let summary = valve.send_batch(&[Valve::Settle; 8]);
+    assert_eq!(summary.oscillations, 2);
+    assert_eq!(summary.processed(), 8);
+}
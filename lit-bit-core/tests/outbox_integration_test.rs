@@ -0,0 +1,368 @@
+// lit-bit-core/tests/outbox_integration_test.rs
+//
+// Transactional outbox: effects committed with the machine's checkpoint, delivered at
+// least once by relays that are dropped mid-stream and restarted against the same store,
+// in memory and in a file log.
+
+#![cfg(feature = "std")]
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lit_bit_core::SendResult;
+use lit_bit_core::outbox::{
+    Delivery, EffectId, Effects, EmitsEffects, FileOutboxStore, MemoryOutboxStore, Outbox,
+    OutboxRelay, OutboxStore, Publisher, RelayError,
+};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    Boot,
+    Place(u32),
+    Ship,
+}
+
+/// A message for the broker.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Message {
+    Placed(u32),
+    Shipped { orders: u32 },
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Shop {
+    pub orders: u32,
+    pub effects: Effects<Message>,
+}
+
+impl EmitsEffects for Shop {
+    type Effect = Message;
+
+    fn effects(&mut self) -> &mut Effects<Message> {
+        &mut self.effects
+    }
+}
+
+fn place(shop: &mut Shop, event: &Order) {
+    if let Order::Place(id) = event {
+        shop.orders += 1;
+        shop.effects.emit(Message::Placed(*id));
+    }
+}
+
+fn ship(shop: &mut Shop, _event: &Order) {
+    shop.effects.emit(Message::Shipped {
+        orders: shop.orders,
+    });
+}
+
+statechart! {
+    name: Orders,
+    context: Shop,
+    event: Order,
+    initial: Open,
+    state Open {
+        on Order::Place(_) => Open [action place];
+        on Order::Ship => Shipped [action ship];
+    }
+    state Shipped {}
+}
+
+fn orders() -> Orders {
+    Orders::new(Shop::default(), &Order::Boot).expect("orders start")
+}
+
+/// Records what it publishes; stalls forever on the effect `stall_at`, as if the broker
+/// hung, and fails on `fail_at`.
+#[derive(Debug, Clone, Default)]
+struct Broker {
+    published: Arc<Mutex<Vec<(EffectId, Message, bool)>>>,
+    stall_at: Option<EffectId>,
+    fail_at: Option<EffectId>,
+}
+
+impl Broker {
+    fn published(&self) -> Vec<(EffectId, Message, bool)> {
+        self.published.lock().unwrap().clone()
+    }
+}
+
+impl Publisher<Message> for Broker {
+    type Error = &'static str;
+
+    async fn publish(&mut self, delivery: &Delivery<Message>) -> Result<(), Self::Error> {
+        self.published.lock().unwrap().push((
+            delivery.id,
+            delivery.effect.clone(),
+            delivery.redelivered,
+        ));
+        if self.stall_at == Some(delivery.id) {
+            std::future::pending::<()>().await;
+        }
+        if self.fail_at == Some(delivery.id) {
+            self.fail_at = None;
+            return Err("broker unavailable");
+        }
+        Ok(())
+    }
+}
+
+/// Runs `relay` until it stalls, then drops it mid-stream.
+async fn kill_while_draining<S, P>(mut relay: OutboxRelay<S, P>)
+where
+    S: OutboxStore,
+    P: Publisher<S::Effect>,
+{
+    let drain = tokio::time::timeout(Duration::from_secs(1), relay.drain());
+    assert!(drain.await.is_err(), "the relay stalls");
+}
+
+fn place_three_and_ship<S>(outbox: &Outbox<S>, machine: &mut Orders)
+where
+    S: OutboxStore<Context = Shop, Effect = Message>,
+{
+    for id in [10, 11, 12] {
+        assert_eq!(
+            outbox.send(machine, &Order::Place(id)).unwrap(),
+            SendResult::Transitioned
+        );
+    }
+    assert_eq!(
+        outbox.send(machine, &Order::Ship).unwrap(),
+        SendResult::Transitioned
+    );
+}
+
+fn log_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("lit-bit-outbox-{}-{name}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn a_send_commits_its_effects_with_the_checkpoint() {
+    let outbox = Outbox::new(MemoryOutboxStore::new());
+    let mut machine = orders();
+    assert!(outbox.checkpoint().is_none());
+
+    assert_eq!(
+        outbox.send(&mut machine, &Order::Place(7)).unwrap(),
+        SendResult::Transitioned
+    );
+    let checkpoint = outbox.checkpoint().expect("a checkpoint");
+    assert_eq!(checkpoint.step, 1);
+    assert_eq!(checkpoint.state.active, ["Open"]);
+    assert_eq!(checkpoint.context.orders, 1);
+    // The effect moved to the store; neither the machine nor the checkpoint holds it.
+    assert!(checkpoint.context.effects.emitted().is_empty());
+    assert!(machine.context().effects.emitted().is_empty());
+    assert_eq!(outbox.store().lock().unwrap().pending(), 1);
+
+    // A send that matches nothing still commits, without effects.
+    assert_eq!(
+        outbox.send(&mut machine, &Order::Boot).unwrap(),
+        SendResult::NoMatch
+    );
+    assert_eq!(outbox.checkpoint().unwrap().step, 2);
+    assert_eq!(outbox.store().lock().unwrap().pending(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_relay_dropped_mid_stream_loses_nothing_and_flags_the_redelivery() {
+    let outbox = Outbox::new(MemoryOutboxStore::new());
+    let mut machine = orders();
+    place_three_and_ship(&outbox, &mut machine);
+
+    let stalling = Broker {
+        stall_at: Some(EffectId(2)),
+        ..Broker::default()
+    };
+    kill_while_draining(outbox.relay(stalling.clone())).await;
+    assert_eq!(outbox.store().lock().unwrap().pending(), 2);
+
+    let broker = Broker::default();
+    assert_eq!(outbox.relay(broker.clone()).drain().await.unwrap(), 2);
+    assert_eq!(
+        stalling.published(),
+        [
+            (EffectId(0), Message::Placed(10), false),
+            (EffectId(1), Message::Placed(11), false),
+            (EffectId(2), Message::Placed(12), false),
+        ]
+    );
+    assert_eq!(
+        broker.published(),
+        [
+            (EffectId(2), Message::Placed(12), true),
+            (EffectId(3), Message::Shipped { orders: 3 }, false),
+        ]
+    );
+    assert_eq!(outbox.store().lock().unwrap().pending(), 0);
+}
+
+#[tokio::test]
+async fn a_failed_publish_stops_the_drain_and_is_retried() {
+    let outbox = Outbox::new(MemoryOutboxStore::new());
+    let mut machine = orders();
+    place_three_and_ship(&outbox, &mut machine);
+
+    let mut relay = outbox.relay(Broker {
+        fail_at: Some(EffectId(1)),
+        ..Broker::default()
+    });
+    let error = relay.drain().await.unwrap_err();
+    assert!(matches!(
+        error,
+        RelayError::Publish {
+            id: EffectId(1),
+            error: "broker unavailable"
+        }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Publishing effect #1 failed: broker unavailable"
+    );
+
+    assert_eq!(relay.drain().await.unwrap(), 3);
+    let flags: Vec<_> = relay
+        .publisher()
+        .published()
+        .into_iter()
+        .map(|(id, _, redelivered)| (id.0, redelivered))
+        .collect();
+    assert_eq!(
+        flags,
+        [(0, false), (1, false), (1, true), (2, false), (3, false)]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_file_log_restores_the_machine_and_its_pending_effects_after_a_restart() {
+    let path = log_path("restart");
+    {
+        let outbox = Outbox::new(FileOutboxStore::open(&path).unwrap());
+        let mut machine = orders();
+        place_three_and_ship(&outbox, &mut machine);
+        kill_while_draining(outbox.relay(Broker {
+            stall_at: Some(EffectId(1)),
+            ..Broker::default()
+        }))
+        .await;
+        // The process dies with the store and the machine.
+    }
+
+    let outbox = Outbox::new(FileOutboxStore::open(&path).unwrap());
+    let checkpoint = outbox.checkpoint().expect("the checkpoint survives");
+    assert_eq!(checkpoint.step, 4);
+    let machine = Orders::from_snapshot(&checkpoint.state, checkpoint.context).unwrap();
+    assert_eq!(machine.current(), OrdersStateId::Shipped);
+    assert_eq!(machine.context().orders, 3);
+
+    let broker = Broker::default();
+    assert_eq!(outbox.relay(broker.clone()).drain().await.unwrap(), 3);
+    assert_eq!(
+        broker.published(),
+        [
+            (EffectId(1), Message::Placed(11), true),
+            (EffectId(2), Message::Placed(12), false),
+            (EffectId(3), Message::Shipped { orders: 3 }, false),
+        ]
+    );
+
+    // Acknowledged effects stay acknowledged across the next restart too.
+    drop(outbox);
+    let store = FileOutboxStore::<Shop, Message>::open(&path).unwrap();
+    assert_eq!(store.pending(), 0);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn a_commit_cut_short_by_a_crash_is_undone_as_a_whole() {
+    let path = log_path("torn");
+    let outbox = Outbox::new(FileOutboxStore::open(&path).unwrap());
+    let mut machine = orders();
+    assert_eq!(
+        outbox.send(&mut machine, &Order::Place(1)).unwrap(),
+        SendResult::Transitioned
+    );
+    drop(outbox);
+
+    // Half of the next commit's line made it to disk.
+    let intact = std::fs::read(&path).unwrap();
+    let mut torn = intact.clone();
+    torn.extend_from_slice(br#"{"commit":{"state":{"active":["Open"]},"context":{"ord"#);
+    std::fs::write(&path, torn).unwrap();
+
+    let mut store = FileOutboxStore::<Shop, Message>::open(&path).unwrap();
+    assert_eq!(store.checkpoint().unwrap().step, 1);
+    assert_eq!(store.pending(), 1);
+    assert_eq!(std::fs::read(&path).unwrap(), intact);
+
+    // Ids carry on from the log.
+    let outbox = Outbox::new(store);
+    assert_eq!(
+        outbox.send(&mut machine, &Order::Place(2)).unwrap(),
+        SendResult::Transitioned
+    );
+    store = FileOutboxStore::open(&path).unwrap();
+    assert_eq!(
+        store.next_delivery().unwrap().map(|delivery| delivery.id),
+        Some(EffectId(0))
+    );
+    store.acknowledge(EffectId(0)).unwrap();
+    assert_eq!(
+        store.next_delivery().unwrap(),
+        Some(Delivery {
+            id: EffectId(1),
+            effect: Message::Placed(2),
+            redelivered: false,
+        })
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn compaction_keeps_the_checkpoint_and_the_unacknowledged_effects() {
+    let path = log_path("compact");
+    let outbox = Outbox::new(FileOutboxStore::open(&path).unwrap());
+    let mut machine = orders();
+    place_three_and_ship(&outbox, &mut machine);
+    {
+        let mut store = outbox.store().lock().unwrap();
+        assert_eq!(store.next_delivery().unwrap().unwrap().id, EffectId(0));
+        store.acknowledge(EffectId(0)).unwrap();
+        assert_eq!(store.next_delivery().unwrap().unwrap().id, EffectId(1));
+        store.compact().unwrap();
+    }
+    drop(outbox);
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 4);
+
+    let mut store = FileOutboxStore::<Shop, Message>::open(&path).unwrap();
+    assert_eq!(store.checkpoint().unwrap().step, 4);
+    assert_eq!(store.pending(), 3);
+    let delivery = store.next_delivery().unwrap().unwrap();
+    assert_eq!((delivery.id, delivery.redelivered), (EffectId(1), true));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(all(feature = "async-tokio", not(feature = "async-embassy")))]
+#[tokio::test]
+async fn the_relay_runs_as_an_actor() {
+    use lit_bit_core::actor::spawn_actor_tokio;
+    use lit_bit_core::outbox::Drain;
+
+    let outbox = Outbox::new(MemoryOutboxStore::new());
+    let broker = Broker::default();
+    let relay = spawn_actor_tokio(outbox.relay(broker.clone()), 4);
+
+    let mut machine = orders();
+    place_three_and_ship(&outbox, &mut machine);
+    relay.send(Drain).await.unwrap();
+    while outbox.store().lock().unwrap().pending() > 0 {
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(broker.published().len(), 4);
+}
@@ -11,7 +11,7 @@ mod parallel_integration_tests {
     use lit_bit_core::StateMachine; // Trait needed for machine.state(), machine.send(), etc.
     use lit_bit_macro::statechart; // The proc macro // For string manipulation in context/log // ADDED IMPORT
     // Use the StateId enum generated by the statechart! macro directly
-    // It will be in this scope due to `pub use generated_state_machine::*;`
+    // It will be in this scope due to the `pub use` of the generated module
     // No explicit `use` needed here if the macro is invoked in this module.
 
     // Define a context for logging actions, similar to unit tests
@@ -516,5 +516,87 @@ mod parallel_integration_tests {
         );
     }
 
+    #[test]
+    fn test_integration_diff_between_parallel_and_outer_configurations() {
+        use IntegrationParallelMachineStateId as Id;
+
+        let in_parallel =
+            IntegrationParallelMachine::new(TestLogContext::default(), &TestEvent::default())
+                .expect("Failed to create parallel machine");
+        let mut in_outer =
+            IntegrationParallelMachine::new(TestLogContext::default(), &TestEvent::default())
+                .expect("Failed to create parallel machine");
+        assert_eq!(
+            in_outer.send(&TestEvent::EvToOuter),
+            SendResult::Transitioned
+        );
+
+        assert_eq!(
+            in_parallel.active_configuration().as_slice(),
+            &[
+                Id::ParallelState,
+                Id::ParallelStateRegion1,
+                Id::ParallelStateRegion1Region1StateA,
+                Id::ParallelStateRegion2,
+                Id::ParallelStateRegion2Region2StateX,
+            ]
+        );
+
+        let diff = in_parallel.diff(&in_outer);
+        assert_eq!(diff.entered.as_slice(), &[Id::OuterState]);
+        assert_eq!(
+            diff.exited.as_slice(),
+            &[
+                Id::ParallelStateRegion2Region2StateX,
+                Id::ParallelStateRegion2,
+                Id::ParallelStateRegion1Region1StateA,
+                Id::ParallelStateRegion1,
+                Id::ParallelState,
+            ]
+        );
+
+        // A region-local self-transition leaves the configuration unchanged.
+        let mut after_self_transition =
+            IntegrationParallelMachine::new(TestLogContext::default(), &TestEvent::default())
+                .expect("Failed to create parallel machine");
+        assert_eq!(
+            after_self_transition.send(&TestEvent::EvR1),
+            SendResult::Transitioned
+        );
+        assert!(in_parallel.diff(&after_self_transition).is_empty());
+    }
+
+    #[test]
+    fn test_integration_entered_states_visit_each_region_in_turn() {
+        use IntegrationParallelMachineStateId as Id;
+        const PARALLEL_ENTRY: [Id; 5] = [
+            Id::ParallelState,
+            Id::ParallelStateRegion1,
+            Id::ParallelStateRegion1Region1StateA,
+            Id::ParallelStateRegion2,
+            Id::ParallelStateRegion2Region2StateX,
+        ];
+
+        let mut machine =
+            IntegrationParallelMachine::new(TestLogContext::default(), &TestEvent::default())
+                .expect("Failed to create parallel machine");
+        assert_eq!(machine.entered_states(), PARALLEL_ENTRY);
+
+        assert_eq!(machine.send(&TestEvent::EvR1), SendResult::Transitioned);
+        assert_eq!(
+            machine.entered_states(),
+            [Id::ParallelStateRegion1Region1StateA]
+        );
+
+        assert_eq!(
+            machine.send(&TestEvent::EvToOuter),
+            SendResult::Transitioned
+        );
+        assert_eq!(machine.entered_states(), [Id::OuterState]);
+
+        assert_eq!(machine.send(&TestEvent::EvToP), SendResult::Transitioned);
+        assert_eq!(machine.entered_states(), PARALLEL_ENTRY);
+    }
+
     // Further tests for parallel event handling will be added here.
 }
@@ -0,0 +1,310 @@
+// lit-bit-core/tests/pattern_integration_test.rs
+//
+// `on` patterns that match literals and ranges rather than enum variants.
+
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+mod integer_events {
+    use super::*;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Dial {
+        pub digits: u32,
+    }
+
+    pub fn count_digit(ctx: &mut Dial, _event: &u8) {
+        ctx.digits += 1;
+    }
+
+    statechart! {
+        name: Dialer,
+        context: Dial,
+        event: u8,
+        initial: Idle,
+        state Idle {
+            on 0..=9 => Dialing [action count_digit];
+        }
+        state Dialing {
+            on 0..=9 => Dialing [action count_digit];
+            on (10 | 11) => Idle;
+        }
+    }
+
+    #[test]
+    fn integer_ranges_and_literals_select_transitions() {
+        let mut dialer = Dialer::new(Dial::default(), &0).expect("machine starts");
+
+        assert_eq!(dialer.send(&42), SendResult::NoMatch);
+        assert_eq!(dialer.send(&7), SendResult::Transitioned);
+        assert_eq!(dialer.state()[0], DialerStateId::Dialing);
+        assert_eq!(dialer.send(&9), SendResult::Transitioned);
+        assert_eq!(dialer.send(&12), SendResult::NoMatch);
+        assert_eq!(dialer.send(&11), SendResult::Transitioned);
+        assert_eq!(dialer.state()[0], DialerStateId::Idle);
+        assert_eq!(dialer.context().digits, 2);
+    }
+}
+
+mod char_events {
+    use super::*;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Word {
+        pub text: String,
+    }
+
+    pub fn push_char(ctx: &mut Word, event: &char) {
+        ctx.text.push(*event);
+    }
+
+    statechart! {
+        name: Lexer,
+        context: Word,
+        event: char,
+        initial: Space,
+        state Space {
+            on ('a'..='z' | 'A'..='Z') => InWord [action push_char];
+        }
+        state InWord {
+            on ('a'..='z' | 'A'..='Z' | '0'..='9') => InWord [action push_char];
+            on ' ' => Space;
+        }
+    }
+
+    #[test]
+    fn char_literals_and_ranges_select_transitions() {
+        let mut lexer = Lexer::new(Word::default(), &' ').expect("machine starts");
+
+        assert_eq!(lexer.send(&'1'), SendResult::NoMatch);
+        for c in "ab1".chars() {
+            assert_eq!(lexer.send(&c), SendResult::Transitioned);
+        }
+        assert_eq!(lexer.send(&'!'), SendResult::NoMatch);
+        assert_eq!(lexer.send(&' '), SendResult::Transitioned);
+        assert_eq!(lexer.state()[0], LexerStateId::Space);
+        assert_eq!(lexer.context().text, "ab1");
+    }
+}
+
+mod ranges_in_variants {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Key {
+        Press(u8),
+        Enter,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Entry {
+        pub value: u32,
+    }
+
+    pub fn append_digit(ctx: &mut Entry, event: &Key) {
+        if let Key::Press(digit) = event {
+            ctx.value = ctx.value * 10 + u32::from(*digit);
+        }
+    }
+
+    // The guard receives the whole event, not just the part the pattern matched.
+    pub fn not_leading_zero(ctx: &Entry, event: &Key) -> bool {
+        ctx.value != 0 || *event != Key::Press(0)
+    }
+
+    statechart! {
+        name: Keypad,
+        context: Entry,
+        event: Key,
+        initial: Ready,
+        state Ready {
+            on Press(0..=9) [guard not_leading_zero] => Ready [action append_digit];
+            on Enter => Done;
+        }
+        state Done {}
+    }
+
+    #[test]
+    fn ranges_nested_in_variants_match_and_guards_see_the_full_event() {
+        let mut keypad = Keypad::new(Entry::default(), &Key::Enter).expect("machine starts");
+
+        assert_eq!(keypad.send(&Key::Press(0)), SendResult::NoMatch);
+        assert_eq!(keypad.send(&Key::Press(4)), SendResult::Transitioned);
+        assert_eq!(keypad.send(&Key::Press(0)), SendResult::Transitioned);
+        assert_eq!(keypad.send(&Key::Press(10)), SendResult::NoMatch);
+        assert_eq!(keypad.context().value, 40);
+        assert_eq!(keypad.send(&Key::Enter), SendResult::Transitioned);
+        assert_eq!(keypad.state()[0], KeypadStateId::Done);
+    }
+}
+
+mod alternatives {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum PlayerEvent {
+        Play,
+        Pause,
+        Stop,
+        Error(u8),
+        Seek { position: u32 },
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Halts {
+        pub count: u32,
+    }
+
+    pub fn halt(ctx: &mut Halts, _event: &PlayerEvent) {
+        ctx.count += 1;
+    }
+
+    statechart! {
+        name: Player,
+        context: Halts,
+        event: PlayerEvent,
+        initial: Paused,
+        state Paused {
+            on Play => Playing;
+        }
+        state Playing {
+            on Pause | PlayerEvent::Stop | Error(_) => Paused [action halt];
+            on PlayerEvent::Seek { position: 0 } | Seek { position: 100.. } => Playing;
+        }
+    }
+
+    #[test]
+    fn any_alternative_takes_the_one_transition() {
+        // One table entry per line, however many alternatives it lists.
+        assert_eq!(Player::definition().transitions.len(), 3);
+
+        let mut player = Player::new(Halts::default(), &PlayerEvent::Play).expect("machine starts");
+        for event in [PlayerEvent::Pause, PlayerEvent::Stop, PlayerEvent::Error(3)] {
+            assert_eq!(player.send(&PlayerEvent::Play), SendResult::Transitioned);
+            assert_eq!(player.send(&event), SendResult::Transitioned);
+            assert_eq!(player.state()[0], PlayerStateId::Paused);
+        }
+        assert_eq!(player.context().count, 3);
+
+        assert_eq!(player.send(&PlayerEvent::Play), SendResult::Transitioned);
+        let seek = |position| PlayerEvent::Seek { position };
+        assert_eq!(player.send(&seek(0)), SendResult::Transitioned);
+        assert_eq!(player.send(&seek(150)), SendResult::Transitioned);
+        assert_eq!(player.send(&seek(50)), SendResult::NoMatch);
+    }
+}
+
+mod payload_constants {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Mode {
+        Eco,
+        Sport,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Level {
+        Low,
+        High,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Preset {
+        Factory,
+        Custom(Level),
+    }
+
+    pub struct Volume;
+
+    impl Volume {
+        pub const MAX: u8 = 100;
+    }
+
+    pub const MUTE: u8 = 0;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Command {
+        KeyPress(char),
+        SetMode(Mode),
+        SetPreset(Preset),
+        SetVolume(u8),
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Console;
+
+    statechart! {
+        name: Amp,
+        context: Console,
+        event: Command,
+        initial: Normal,
+        state Normal {
+            on KeyPress('q') => Quitting;
+            on SetMode(Mode::Eco) => EcoMode;
+            on SetPreset(Preset::Custom(Level::High)) => Boosted;
+            on SetVolume(MUTE) => Muted;
+            on SetVolume(Volume::MAX) => Loud;
+            on SetVolume(1..=10) => Quiet;
+        }
+        state EcoMode { on Command::SetMode(Mode::Sport) => Normal; }
+        state Boosted {}
+        state Muted {}
+        state Loud {}
+        state Quiet {}
+        state Quitting {}
+    }
+
+    fn amp() -> Amp {
+        Amp::new(Console, &Command::KeyPress(' ')).expect("machine starts")
+    }
+
+    fn after(command: Command) -> AmpStateId {
+        let mut amp = amp();
+        assert_eq!(amp.send(&command), SendResult::Transitioned, "{command:?}");
+        amp.state()[0]
+    }
+
+    #[test]
+    fn payload_literals_and_constants_select_transitions() {
+        assert_eq!(after(Command::KeyPress('q')), AmpStateId::Quitting);
+        assert_eq!(after(Command::SetMode(Mode::Eco)), AmpStateId::EcoMode);
+        assert_eq!(
+            after(Command::SetPreset(Preset::Custom(Level::High))),
+            AmpStateId::Boosted
+        );
+        assert_eq!(after(Command::SetVolume(0)), AmpStateId::Muted);
+        assert_eq!(after(Command::SetVolume(100)), AmpStateId::Loud);
+        assert_eq!(after(Command::SetVolume(7)), AmpStateId::Quiet);
+    }
+
+    #[test]
+    fn other_payloads_do_not_match() {
+        let mut amp = amp();
+        for command in [
+            Command::KeyPress('w'),
+            Command::SetMode(Mode::Sport),
+            Command::SetPreset(Preset::Factory),
+            Command::SetPreset(Preset::Custom(Level::Low)),
+            Command::SetVolume(50),
+        ] {
+            assert_eq!(amp.send(&command), SendResult::NoMatch, "{command:?}");
+        }
+        assert_eq!(amp.state()[0], AmpStateId::Normal);
+    }
+
+    #[test]
+    fn qualified_patterns_keep_their_payload_constants() {
+        let mut amp = amp();
+        assert_eq!(
+            amp.send(&Command::SetMode(Mode::Eco)),
+            SendResult::Transitioned
+        );
+        assert_eq!(amp.send(&Command::SetMode(Mode::Eco)), SendResult::NoMatch);
+        assert_eq!(
+            amp.send(&Command::SetMode(Mode::Sport)),
+            SendResult::Transitioned
+        );
+        assert_eq!(amp.state()[0], AmpStateId::Normal);
+    }
+}
@@ -0,0 +1,224 @@
+// lit-bit-core/tests/policy_integration_test.rs
+//
+// Transition policies: vetoes applied after a transition's guard has passed.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use lit_bit_core::actor::StatechartActor;
+use lit_bit_core::{
+    DenyTargetSet, DenyTargets, SendResult, StateMachine, StateSet, TransitionPolicy,
+};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cmd {
+    Boot,
+    Heat,
+    Purge,
+    Cool,
+    Toggle,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Plant {
+    pub armed: bool,
+    pub exits: u32,
+    pub actions: u32,
+}
+
+fn armed(ctx: &Plant, _event: &Cmd) -> bool {
+    ctx.armed
+}
+
+fn count_exit(ctx: &mut Plant, _event: &Cmd) {
+    ctx.exits += 1;
+}
+
+fn count_action(ctx: &mut Plant, _event: &Cmd) {
+    ctx.actions += 1;
+}
+
+statechart! {
+    name: Reactor,
+    context: Plant,
+    event: Cmd,
+    initial: Idle,
+    state Idle [terminal] {
+        exit: count_exit;
+        on Cmd::Heat [guard armed] => Heating [action count_action];
+        on Cmd::Purge [guard armed] => Purging [action count_action];
+    }
+    state Heating {
+        on Cmd::Cool => Idle;
+    }
+    state Purging {
+        on Cmd::Cool => Idle;
+    }
+}
+
+static NO_PURGE: DenyTargets<ReactorStateId> = DenyTargets(&[ReactorStateId::Purging]);
+static NO_PURGE_SET: DenyTargetSet =
+    DenyTargetSet(StateSet::new().with(ReactorStateId::Purging.index()));
+
+/// Blocks every transition out of `Idle` while safe mode is on.
+struct SafeMode(AtomicBool);
+
+impl TransitionPolicy<ReactorStateId, Cmd> for SafeMode {
+    fn allow(&self, from: &[ReactorStateId], _to: ReactorStateId, _event: &Cmd) -> bool {
+        !(self.0.load(Ordering::Relaxed) && from.contains(&ReactorStateId::Idle))
+    }
+}
+
+fn armed_reactor() -> Reactor {
+    Reactor::new(
+        Plant {
+            armed: true,
+            ..Plant::default()
+        },
+        &Cmd::Boot,
+    )
+    .expect("machine starts")
+}
+
+#[test]
+fn vetoed_transition_runs_no_exit_or_action() {
+    let mut reactor = armed_reactor();
+    reactor.set_policy(Some(&NO_PURGE));
+
+    assert_eq!(reactor.send(&Cmd::Purge), SendResult::PolicyRejected);
+    assert_eq!(reactor.state()[0], ReactorStateId::Idle);
+    assert_eq!(reactor.context().exits, 0);
+    assert_eq!(reactor.context().actions, 0);
+
+    // Other targets are unaffected, and a failing guard still reads as no match.
+    assert_eq!(reactor.send(&Cmd::Heat), SendResult::Transitioned);
+    assert_eq!(reactor.send(&Cmd::Cool), SendResult::Transitioned);
+    reactor.context_mut().armed = false;
+    assert_eq!(reactor.send(&Cmd::Purge), SendResult::NoMatch);
+
+    reactor.context_mut().armed = true;
+    reactor.set_policy(None);
+    assert_eq!(reactor.send(&Cmd::Purge), SendResult::Transitioned);
+    assert_eq!(reactor.state()[0], ReactorStateId::Purging);
+}
+
+#[test]
+fn a_state_set_vetoes_the_targets_it_holds() {
+    let mut reactor = armed_reactor();
+    reactor.set_policy(Some(&NO_PURGE_SET));
+
+    assert_eq!(reactor.send(&Cmd::Purge), SendResult::PolicyRejected);
+    assert_eq!(reactor.state()[0], ReactorStateId::Idle);
+    assert_eq!(reactor.send(&Cmd::Heat), SendResult::Transitioned);
+}
+
+#[test]
+fn composed_policies_must_all_allow() {
+    static SAFE_MODE: SafeMode = SafeMode(AtomicBool::new(false));
+    static POLICY: (SafeMode, DenyTargets<ReactorStateId>) = (
+        SafeMode(AtomicBool::new(true)),
+        DenyTargets(&[ReactorStateId::Purging]),
+    );
+
+    let mut reactor = armed_reactor();
+    reactor.set_policy(Some(&POLICY));
+    assert_eq!(reactor.send(&Cmd::Heat), SendResult::PolicyRejected);
+
+    POLICY.0.0.store(false, Ordering::Relaxed);
+    assert_eq!(reactor.send(&Cmd::Purge), SendResult::PolicyRejected);
+    assert_eq!(reactor.send(&Cmd::Heat), SendResult::Transitioned);
+
+    // A policy can be switched at run time without re-attaching it.
+    reactor.set_policy(Some(&SAFE_MODE));
+    assert_eq!(reactor.send(&Cmd::Cool), SendResult::Transitioned);
+    SAFE_MODE.0.store(true, Ordering::Relaxed);
+    assert_eq!(reactor.send(&Cmd::Heat), SendResult::PolicyRejected);
+}
+
+#[test]
+fn batches_count_rejected_events() {
+    let mut reactor = armed_reactor();
+    reactor.set_policy(Some(&NO_PURGE));
+
+    let summary = reactor.send_batch(&[Cmd::Purge, Cmd::Heat, Cmd::Purge, Cmd::Cool]);
+    assert_eq!(summary.policy_rejected, 1);
+    assert_eq!(summary.transitioned, 2);
+    assert_eq!(summary.ignored, 1);
+    assert_eq!(summary.processed(), 4);
+}
+
+#[test]
+fn statechart_actor_forwards_the_policy() {
+    let mut actor = StatechartActor::new(armed_reactor());
+    actor.set_policy(Some(&NO_PURGE));
+
+    assert_eq!(
+        actor.machine_mut().send(&Cmd::Purge),
+        SendResult::PolicyRejected
+    );
+    assert_eq!(actor.machine().state()[0], ReactorStateId::Idle);
+}
+
+mod parallel {
+    use super::{Cmd, Plant, armed};
+    use lit_bit_core::{DenyTargets, SendResult, StateMachine};
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Console,
+        context: Plant,
+        event: Cmd,
+        initial: Panel,
+        state Panel [parallel] {
+            state Lamp {
+                initial: Off;
+                state Off [terminal] { on Cmd::Toggle [guard armed] => On; }
+                state On { on Cmd::Toggle => Off; }
+            }
+            state Fan {
+                initial: Still;
+                state Still [terminal] { on Cmd::Toggle [guard armed] => Spinning; }
+                state Spinning { on Cmd::Toggle => Still; }
+            }
+        }
+    }
+
+    static NO_SPIN: DenyTargets<ConsoleStateId> = DenyTargets(&[ConsoleStateId::PanelFanSpinning]);
+    static NOTHING_ON: DenyTargets<ConsoleStateId> = DenyTargets(&[
+        ConsoleStateId::PanelLampOn,
+        ConsoleStateId::PanelFanSpinning,
+    ]);
+
+    fn console() -> Console {
+        Console::new(
+            Plant {
+                armed: true,
+                ..Plant::default()
+            },
+            &Cmd::Boot,
+        )
+        .expect("machine starts")
+    }
+
+    #[test]
+    fn allowed_regions_move_while_vetoed_ones_stay() {
+        let mut console = console();
+        console.set_policy(Some(&NO_SPIN));
+
+        assert_eq!(console.send(&Cmd::Toggle), SendResult::Transitioned);
+        let state = console.state();
+        assert!(state.contains(&ConsoleStateId::PanelLampOn));
+        assert!(state.contains(&ConsoleStateId::PanelFanStill));
+    }
+
+    #[test]
+    fn vetoing_every_region_rejects_the_event() {
+        let mut console = console();
+        console.set_policy(Some(&NOTHING_ON));
+
+        assert_eq!(console.send(&Cmd::Toggle), SendResult::PolicyRejected);
+        let state = console.state();
+        assert!(state.contains(&ConsoleStateId::PanelLampOff));
+        assert!(state.contains(&ConsoleStateId::PanelFanStill));
+    }
+}
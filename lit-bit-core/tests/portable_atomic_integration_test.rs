@@ -0,0 +1,92 @@
+// lit-bit-core/tests/portable_atomic_integration_test.rs
+//
+// The `portable-atomic` build of the pieces thumbv6m needs it for: the ISR senders over a
+// `static_mailbox!`, and the counters that hand out unique ids.
+#![cfg(feature = "portable-atomic")]
+
+use std::cell::RefCell;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+// The critical sections the shared senders lock, as a board's HAL would provide them: a
+// process-wide lock the thread holding it may take again.
+struct HostCriticalSection;
+critical_section::set_impl!(HostCriticalSection);
+
+static CRITICAL: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    static HELD: RefCell<(usize, Option<MutexGuard<'static, ()>>)> =
+        const { RefCell::new((0, None)) };
+}
+
+// SAFETY: `acquire` blocks until no other thread is in a critical section, and the lock
+// stays held until the outermost `release` on this thread.
+unsafe impl critical_section::Impl for HostCriticalSection {
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        HELD.with_borrow_mut(|(depth, lock)| {
+            if *depth == 0 {
+                *lock = Some(CRITICAL.lock().unwrap_or_else(PoisonError::into_inner));
+            }
+            *depth += 1;
+        });
+    }
+
+    unsafe fn release(_: critical_section::RawRestoreState) {
+        HELD.with_borrow_mut(|(depth, lock)| {
+            *depth -= 1;
+            if *depth == 0 {
+                *lock = None;
+            }
+        });
+    }
+}
+
+#[cfg(not(feature = "async-tokio"))]
+#[test]
+fn shared_isr_senders_feed_a_static_mailbox() {
+    let (tx, mut inbox) = lit_bit_core::static_mailbox!(PORTABLE_TICKS: u32, 8; shared_isr);
+    let other = tx;
+
+    let producers: Vec<_> = [(tx, 0), (other, 100)]
+        .into_iter()
+        .map(|(sender, base)| {
+            thread::spawn(move || {
+                for n in 0..3 {
+                    sender.send_from_isr(base + n).expect("mailbox has room");
+                }
+            })
+        })
+        .collect();
+    for producer in producers {
+        producer.join().expect("producer finishes");
+    }
+
+    let mut received: Vec<u32> = core::iter::from_fn(|| inbox.dequeue()).collect();
+    received.sort_unstable();
+    assert_eq!(received, [0, 1, 2, 100, 101, 102]);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn timer_tokens_stay_unique_across_threads() {
+    use std::collections::HashSet;
+
+    use lit_bit_core::timer::TimerToken;
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            thread::spawn(|| {
+                (0..250)
+                    .map(|_| TimerToken::new(core::time::Duration::from_millis(1)).id())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    let mut ids = HashSet::new();
+    for worker in workers {
+        ids.extend(worker.join().expect("worker finishes"));
+    }
+    assert_eq!(ids.len(), 1000);
+}
@@ -0,0 +1,125 @@
+// lit-bit-core/tests/reachability_integration_test.rs
+//
+// `reachable_from`: the states a chart can enter from a given state, computed when the
+// chart is expanded.
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    Boot,
+    Work,
+    Done,
+    Fault,
+    Recover,
+    Panel,
+    Toggle,
+    Leave,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Plant;
+
+fn overheated(_: &Plant, _: &Signal) -> bool {
+    false
+}
+
+statechart! {
+    name: Reactor,
+    context: Plant,
+    event: Signal,
+    initial: Running,
+    state Running {
+        initial: Idle;
+        on Signal::Fault => Tripped;
+        on Signal::Panel => Controls::Light::On;
+        state Idle {
+            on Signal::Work => Busy;
+        }
+        state Busy {
+            on Signal::Done [guard overheated] => Idle;
+        }
+    }
+    state Tripped {
+        on Signal::Recover => Safe;
+    }
+    state Safe {}
+    state Controls [parallel] {
+        on Signal::Leave => Safe;
+        state Light {
+            initial: Off;
+            state Off {
+                on Signal::Toggle => On;
+            }
+            state On {}
+        }
+        state Fan {
+            initial: Low;
+            state Low {}
+        }
+    }
+    state Spare {}
+}
+
+use ReactorStateId as S;
+
+fn all_states() -> impl Iterator<Item = ReactorStateId> {
+    (0..).map_while(ReactorStateId::from_index)
+}
+
+#[test]
+fn a_terminal_state_reaches_nothing() {
+    assert!(Reactor::reachable_from(S::Safe).is_empty());
+    assert_eq!(Reactor::reachable_from(S::Tripped), [S::Safe]);
+}
+
+#[test]
+fn the_tripped_state_is_reachable_from_every_running_state() {
+    for state in [S::Running, S::RunningIdle, S::RunningBusy] {
+        assert!(
+            Reactor::reachable_from(state).contains(&S::Tripped),
+            "{state:?} cannot reach Tripped"
+        );
+    }
+}
+
+#[test]
+fn the_safe_state_is_reachable_from_anywhere_but_an_unconnected_state() {
+    for state in all_states().filter(|state| !matches!(state, S::Safe | S::Spare)) {
+        assert!(
+            Reactor::reachable_from(state).contains(&S::Safe),
+            "{state:?} cannot reach Safe"
+        );
+    }
+    assert!(all_states().all(|state| !Reactor::reachable_from(state).contains(&S::Spare)));
+}
+
+#[test]
+fn guards_are_ignored_and_cycles_reach_their_start() {
+    // `Busy => Idle` is guarded by a check that never holds; it still counts.
+    assert!(Reactor::reachable_from(S::RunningIdle).contains(&S::RunningIdle));
+    assert!(Reactor::reachable_from(S::RunningBusy).contains(&S::RunningIdle));
+}
+
+#[test]
+fn entering_a_parallel_state_enters_all_of_its_regions() {
+    let from_idle = Reactor::reachable_from(S::RunningIdle);
+    for state in [
+        S::Controls,
+        S::ControlsLight,
+        S::ControlsLightOn,
+        S::ControlsFan,
+        S::ControlsFanLow,
+    ] {
+        assert!(from_idle.contains(&state), "{state:?} is not reachable");
+    }
+    // The light is switched on directly, and nothing switches it off.
+    assert!(!from_idle.contains(&S::ControlsLightOff));
+    assert!(Reactor::reachable_from(S::ControlsFanLow).contains(&S::ControlsLightOn));
+}
+
+#[test]
+fn the_lists_are_in_state_id_order_and_usable_in_consts() {
+    const FROM_BUSY: &[ReactorStateId] = Reactor::reachable_from(ReactorStateId::RunningBusy);
+    assert!(FROM_BUSY.windows(2).all(|pair| pair[0] < pair[1]));
+}
@@ -0,0 +1,102 @@
+// lit-bit-core/tests/read_action_integration_test.rs
+//
+// `[action(read) f]`: transition actions that only get `&Context`, next to the usual
+// `&mut Context` actions on the same machine.
+
+use std::cell::RefCell;
+
+use lit_bit_core::SendResult;
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Meter {
+    Pulse,
+    Report,
+    Reset,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Counts {
+    pub pulses: u32,
+}
+
+thread_local! {
+    static REPORTED: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+}
+
+fn take_reported() -> Vec<u32> {
+    REPORTED.with(|reported| reported.borrow_mut().drain(..).collect())
+}
+
+pub fn count(ctx: &mut Counts, _event: &Meter) {
+    ctx.pulses += 1;
+}
+
+pub fn clear(ctx: &mut Counts, _event: &Meter) {
+    ctx.pulses = 0;
+}
+
+pub fn report(ctx: &Counts, _event: &Meter) {
+    REPORTED.with(|reported| reported.borrow_mut().push(ctx.pulses));
+}
+
+statechart! {
+    name: PulseMeter,
+    context: Counts,
+    event: Meter,
+    initial: Counting,
+    state Counting {
+        on Meter::Pulse => Counting [action count];
+        on Meter::Report => Counting [action(read) report];
+        on Meter::Reset => Counting [action clear];
+    }
+}
+
+#[test]
+fn read_and_mutating_actions_run_on_the_same_machine() {
+    let mut meter = PulseMeter::new(Counts::default(), &Meter::Reset).expect("meter starts");
+    for event in [
+        Meter::Pulse,
+        Meter::Pulse,
+        Meter::Report,
+        Meter::Reset,
+        Meter::Report,
+    ] {
+        assert_eq!(meter.send(&event), SendResult::Transitioned);
+    }
+
+    assert_eq!(take_reported(), [2, 0]);
+    assert_eq!(meter.context().pulses, 0);
+}
+
+statechart! {
+    name: Panel,
+    context: Counts,
+    event: Meter,
+    initial: Running,
+    state Running [parallel] {
+        state Counter {
+            initial: Live;
+            state Live {
+                on Meter::Pulse => Live [action count];
+            }
+        }
+        state Display {
+            initial: Shown;
+            state Shown {
+                on Meter::Report => Shown [action(read) report];
+            }
+        }
+    }
+}
+
+#[test]
+fn read_actions_run_in_parallel_regions() {
+    let mut panel = Panel::new(Counts::default(), &Meter::Reset).expect("panel starts");
+    assert_eq!(panel.send(&Meter::Pulse), SendResult::Transitioned);
+    assert_eq!(panel.send(&Meter::Pulse), SendResult::Transitioned);
+    assert_eq!(panel.send(&Meter::Report), SendResult::Transitioned);
+
+    assert_eq!(take_reported(), [2]);
+    assert_eq!(panel.context().pulses, 2);
+}
@@ -0,0 +1,194 @@
+// lit-bit-core/tests/replay_integration_test.rs
+#![cfg(feature = "std")]
+
+use core::ops::ControlFlow;
+
+use lit_bit_core::config::{ConfigError, serde, serde_json};
+use lit_bit_core::replay::{EventLog, EventRecorder, EventReplayer, ReplayClock};
+use lit_bit_core::upgrade::StateSnapshot;
+use lit_bit_core::{MonotonicClock, SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Till {
+    pub credit: u32,
+    pub vended: u32,
+    pub entries: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(crate = "lit_bit_core::config::serde")]
+pub enum Coin {
+    Insert(u32),
+    Vend,
+    Refund,
+    Service,
+}
+
+fn add_credit(ctx: &mut Till, event: &Coin) {
+    if let Coin::Insert(amount) = event {
+        ctx.credit += amount;
+    }
+}
+
+fn can_vend(ctx: &Till, _event: &Coin) -> bool {
+    ctx.credit >= 3
+}
+
+fn vend(ctx: &mut Till, _event: &Coin) {
+    ctx.credit -= 3;
+    ctx.vended += 1;
+}
+
+fn refund(ctx: &mut Till, _event: &Coin) {
+    ctx.credit = 0;
+}
+
+fn count_entry(ctx: &mut Till, _event: &Coin) {
+    ctx.entries += 1;
+}
+
+statechart! {
+    name: Vending,
+    context: Till,
+    event: Coin,
+    initial: Selling,
+    state Selling {
+        initial: Idle;
+        on Coin::Service => Maintenance;
+        state Idle {
+            entry: count_entry;
+            on Coin::Insert(_) => Paid [action add_credit];
+        }
+        state Paid {
+            on Coin::Insert(_) => Paid [action add_credit];
+            on Coin::Vend [guard can_vend] => Idle [action vend];
+            on Coin::Refund => Idle [action refund];
+        }
+    }
+    state Maintenance {
+        on Coin::Service => Selling;
+    }
+}
+
+/// Test clock the recording side advances by hand.
+static FIELD_CLOCK: ReplayClock = ReplayClock::new();
+
+fn vending() -> Vending {
+    Vending::new(Till::default(), &Coin::Refund).expect("machine starts")
+}
+
+#[test]
+fn replay_from_a_snapshot_reproduces_state_and_context() {
+    let mut field = vending();
+    assert_eq!(field.send(&Coin::Insert(2)), SendResult::Transitioned);
+    let resume_context = field.context().clone();
+
+    let mut recorder = EventRecorder::from_snapshot(&FIELD_CLOCK, field.snapshot());
+    for event in [Coin::Insert(2), Coin::Vend, Coin::Insert(1), Coin::Vend] {
+        let _ = recorder.send(&mut field, &event);
+    }
+    let json = serde_json::to_string(recorder.log()).expect("log serializes");
+
+    let log: EventLog<Coin> = serde_json::from_str(&json).expect("log deserializes");
+    let replayer = EventReplayer::new(log);
+    let snapshot = replayer.start().expect("recording started from a snapshot");
+    let mut bench = Vending::from_snapshot(snapshot, resume_context).expect("snapshot restores");
+    // Restoring runs no entry actions.
+    assert_eq!(bench.context().entries, field.context().entries - 1);
+
+    let summary = replayer.replay(&mut bench);
+    assert_eq!(summary.transitioned, 3);
+    assert_eq!(summary.ignored, 1);
+    assert_eq!(bench.state(), field.state());
+    assert_eq!(bench.context(), field.context());
+}
+
+#[test]
+fn replay_reproduces_recorded_timestamps() {
+    static BENCH_CLOCK: ReplayClock = ReplayClock::new();
+
+    let mut recorder = EventRecorder::new(&FIELD_CLOCK);
+    let mut field = vending();
+    for (at, event) in [(10, Coin::Insert(5)), (25, Coin::Vend), (40, Coin::Service)] {
+        FIELD_CLOCK.set(at);
+        assert_eq!(recorder.send(&mut field, &event), SendResult::Transitioned);
+    }
+
+    let replayer = EventReplayer::new(recorder.into_log()).with_clock(&BENCH_CLOCK);
+    assert!(replayer.start().is_none());
+    let mut seen = Vec::new();
+    replayer.replay_with(&mut vending(), |index, recorded, _| {
+        seen.push((index, BENCH_CLOCK.now()));
+        assert_eq!(BENCH_CLOCK.now(), recorded.at);
+        ControlFlow::Continue(())
+    });
+    assert_eq!(seen, vec![(0, 10), (1, 25), (2, 40)]);
+}
+
+#[test]
+fn replay_with_stops_where_the_step_breaks() {
+    let mut recorder = EventRecorder::new(&FIELD_CLOCK);
+    for event in [Coin::Insert(1), Coin::Vend, Coin::Refund] {
+        recorder.record(&event);
+    }
+
+    let mut bench = vending();
+    let summary =
+        EventReplayer::new(recorder.into_log()).replay_with(&mut bench, |_, _, result| {
+            if result == SendResult::NoMatch {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+    // The guard rejected `Vend` with only one coin inserted; `Refund` never ran.
+    assert_eq!(summary.processed(), 2);
+    assert_eq!(bench.state()[0], VendingStateId::SellingPaid);
+    assert_eq!(bench.context().credit, 1);
+}
+
+#[test]
+fn snapshots_must_name_leaf_states() {
+    for path in ["Selling", "Selling.Gone"] {
+        let err = Vending::from_snapshot(&StateSnapshot::new([path]), Till::default())
+            .expect_err("not a leaf");
+        assert!(
+            matches!(&err, ConfigError::UnknownSnapshotState { requested } if requested == path),
+            "unexpected error: {err}"
+        );
+    }
+}
+
+mod parallel {
+    use super::{Coin, Till, count_entry};
+    use lit_bit_core::upgrade::StateSnapshot;
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Panel,
+        context: Till,
+        event: Coin,
+        initial: Running,
+        state Running [parallel] {
+            state Display {
+                initial: Dark;
+                state Dark { entry: count_entry; on Coin::Vend => Lit; }
+                state Lit {}
+            }
+            state Hopper {
+                initial: Empty;
+                state Empty { entry: count_entry; on Coin::Insert(_) => Full; }
+                state Full {}
+            }
+        }
+    }
+
+    #[test]
+    fn every_region_is_restored() {
+        let snapshot = StateSnapshot::new(["Running.Display.Lit", "Running.Hopper.Full"]);
+        let panel = Panel::from_snapshot(&snapshot, Till::default()).expect("restores");
+        assert_eq!(panel.snapshot(), snapshot);
+        assert_eq!(panel.context().entries, 0);
+    }
+}
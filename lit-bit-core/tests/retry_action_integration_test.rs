@@ -0,0 +1,159 @@
+// lit-bit-core/tests/retry_action_integration_test.rs
+//
+// `[action retry(n) f]`: fallible transition actions retried before the step fails.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use lit_bit_core::{
+    ActionError, MonotonicClock, ProcessingError, RetryReport, SendResult, StateMachine, Ticks,
+};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bus {
+    Configure,
+    Calibrate,
+    Reset,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Sensor {
+    /// How many more writes the scripted bus NACKs.
+    pub nacks_left: u8,
+    pub writes: u32,
+    pub configured: bool,
+}
+
+pub fn write_config(ctx: &mut Sensor, _event: &Bus) -> Result<(), ActionError> {
+    ctx.writes += 1;
+    if ctx.nacks_left > 0 {
+        ctx.nacks_left -= 1;
+        return Err(ActionError::new("i2c nack"));
+    }
+    ctx.configured = true;
+    Ok(())
+}
+
+pub fn calibrate(ctx: &mut Sensor, _event: &Bus) -> Result<(), ActionError> {
+    ctx.writes += 1;
+    Err(ActionError::new("no reference"))
+}
+
+pub fn reset(ctx: &mut Sensor, _event: &Bus) {
+    ctx.configured = false;
+}
+
+statechart! {
+    name: SensorMachine,
+    context: Sensor,
+    event: Bus,
+    initial: Idle,
+    state Idle {
+        on Bus::Configure => Ready [action retry(3) write_config];
+    }
+    state Ready {
+        on Bus::Calibrate => Calibrated [action retry(1, delay = 5) calibrate];
+        on Bus::Reset => Idle [action reset];
+    }
+    state Calibrated {}
+}
+
+thread_local! {
+    static REPORTS: RefCell<Vec<RetryReport<SensorMachineStateId>>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(report: &RetryReport<SensorMachineStateId>) {
+    REPORTS.with(|reports| reports.borrow_mut().push(*report));
+}
+
+fn take_reports() -> Vec<RetryReport<SensorMachineStateId>> {
+    REPORTS.with(|reports| reports.borrow_mut().drain(..).collect())
+}
+
+/// Advances by one tick every time it is read.
+struct SteppingClock(AtomicU64);
+
+impl MonotonicClock for SteppingClock {
+    fn now(&self) -> Ticks {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+fn machine(nacks: u8) -> SensorMachine {
+    let sensor = Sensor {
+        nacks_left: nacks,
+        ..Sensor::default()
+    };
+    let mut machine = SensorMachine::new(sensor, &Bus::Reset).expect("machine starts");
+    machine.set_retry_observer(record);
+    machine
+}
+
+#[test]
+fn action_failing_twice_succeeds_on_the_third_attempt() {
+    let mut machine = machine(2);
+
+    assert_eq!(machine.send(&Bus::Configure), SendResult::Transitioned);
+    assert_eq!(machine.state().as_slice(), &[SensorMachineStateId::Ready]);
+    assert_eq!(machine.context().writes, 3);
+    assert!(machine.context().configured);
+
+    let reports = take_reports();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].from_state, SensorMachineStateId::Idle);
+    assert_eq!(reports[0].to_state, SensorMachineStateId::Ready);
+    assert_eq!(reports[0].attempts, 3);
+    assert!(reports[0].succeeded());
+}
+
+#[test]
+fn exhausted_retries_roll_the_step_back() {
+    let mut machine = machine(4);
+
+    assert_eq!(
+        machine.send(&Bus::Configure),
+        SendResult::Error(ProcessingError::ActionFailed)
+    );
+    assert_eq!(machine.state().as_slice(), &[SensorMachineStateId::Idle]);
+    assert_eq!(machine.context().writes, 0);
+    assert_eq!(machine.context().nacks_left, 4);
+
+    let reports = take_reports();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].attempts, 4);
+    assert_eq!(reports[0].error, Some(ActionError::new("i2c nack")));
+}
+
+#[test]
+fn delays_wait_on_the_retry_clock() {
+    static CLOCK: SteppingClock = SteppingClock(AtomicU64::new(0));
+
+    let mut machine = machine(0);
+    machine.set_retry_clock(&CLOCK);
+    assert_eq!(machine.send(&Bus::Configure), SendResult::Transitioned);
+
+    let before = CLOCK.0.load(Ordering::Relaxed);
+    assert_eq!(
+        machine.send(&Bus::Calibrate),
+        SendResult::Error(ProcessingError::ActionFailed)
+    );
+    assert!(CLOCK.0.load(Ordering::Relaxed) - before >= 5);
+    assert_eq!(machine.state().as_slice(), &[SensorMachineStateId::Ready]);
+
+    let reports = take_reports();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[1].attempts, 2);
+    assert!(!reports[1].succeeded());
+}
+
+#[test]
+fn retries_are_immediate_without_a_clock() {
+    let mut machine = machine(0);
+    assert_eq!(machine.send(&Bus::Configure), SendResult::Transitioned);
+    assert_eq!(
+        machine.send(&Bus::Calibrate),
+        SendResult::Error(ProcessingError::ActionFailed)
+    );
+    assert_eq!(take_reports()[1].attempts, 2);
+}
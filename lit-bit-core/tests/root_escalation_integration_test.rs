@@ -0,0 +1,107 @@
+// lit-bit-core/tests/root_escalation_integration_test.rs
+//
+// `RootEscalationAction` on the top-level supervisor. The process-ending actions are run
+// in a child process: the test re-runs its own binary, filtered to itself, with
+// `ROOT_ESCALATION_CHILD` set.
+#![cfg(feature = "std")]
+
+use std::process::{Command, Output};
+use std::sync::Mutex;
+
+use lit_bit_core::actor::{
+    Actor, ActorError, RestartStrategy, RootEscalation, RootEscalationAction, SupervisorActor,
+    SupervisorMessage,
+};
+
+const CHILD_ENV: &str = "ROOT_ESCALATION_CHILD";
+
+fn root_supervisor(action: RootEscalationAction<u32>) -> SupervisorActor<u32, 8> {
+    // No restarts allowed, so the first escalation from a child supervisor goes to the root.
+    let mut supervisor = SupervisorActor::with_config(RestartStrategy::OneForOne, 0, 60_000)
+        .with_root_escalation(action);
+    for id in [1, 2] {
+        supervisor.add_child(id, None).expect("child added");
+    }
+    supervisor
+}
+
+fn escalate_from_child_supervisor(supervisor: &mut SupervisorActor<u32, 8>) {
+    let _future = supervisor.handle(SupervisorMessage::ChildEscalated {
+        supervisor_id: 2,
+        failed_child_id: 20,
+        error: Box::new(ActorError::Custom("database unreachable")),
+    });
+}
+
+fn run_in_child_process(test_name: &str) -> Output {
+    Command::new(std::env::current_exe().expect("test binary path"))
+        .args([test_name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD_ENV, "1")
+        .output()
+        .expect("test binary runs")
+}
+
+#[test]
+fn exit_process_action_exits_with_its_code_after_dumping_the_tree() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        let mut supervisor = root_supervisor(RootEscalationAction::ExitProcess(42));
+        escalate_from_child_supervisor(&mut supervisor);
+        unreachable!("ExitProcess returned");
+    }
+
+    let output =
+        run_in_child_process("exit_process_action_exits_with_its_code_after_dumping_the_tree");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(42), "stderr: {stderr}");
+    assert!(stderr.contains("failure of child 2 reached the root supervisor"));
+    assert!(stderr.contains("database unreachable"));
+    assert!(stderr.contains("supervision tree"));
+}
+
+#[test]
+fn panic_process_action_panics_after_dumping_the_tree() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        let mut supervisor = root_supervisor(RootEscalationAction::PanicProcess);
+        supervisor.escalate_failure(&1, ActorError::Timeout);
+        unreachable!("PanicProcess returned");
+    }
+
+    let output = run_in_child_process("panic_process_action_panics_after_dumping_the_tree");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    let dump = stderr.find("supervision tree").expect("tree dumped");
+    let panic = stderr.find("panicked").expect("process panicked");
+    assert!(
+        dump < panic,
+        "tree must be dumped before the panic: {stderr}"
+    );
+}
+
+static ESCALATIONS: Mutex<Vec<(u32, Vec<u32>)>> = Mutex::new(Vec::new());
+
+fn record_escalation(escalation: &RootEscalation<u32>) {
+    let children = escalation.snapshot.children.iter().map(|child| child.id);
+    ESCALATIONS
+        .lock()
+        .unwrap()
+        .push((escalation.child_id, children.collect()));
+}
+
+#[test]
+fn custom_action_receives_the_escalation_and_snapshot() {
+    let mut supervisor = root_supervisor(RootEscalationAction::Custom(record_escalation));
+
+    escalate_from_child_supervisor(&mut supervisor);
+
+    // The snapshot is taken before the escalating supervisor is dropped.
+    assert_eq!(*ESCALATIONS.lock().unwrap(), [(2, vec![1, 2])]);
+    assert_eq!(
+        supervisor
+            .snapshot()
+            .children
+            .iter()
+            .map(|child| child.id)
+            .collect::<Vec<_>>(),
+        [1]
+    );
+}
@@ -0,0 +1,91 @@
+// lit-bit-core/tests/same_shape_machines_integration_test.rs
+//
+// Two `statechart!` machines with the same states and transitions, expanded side by side
+// in one module: every generated symbol is prefixed with the machine name, so they build
+// without warnings and run independently.
+#![deny(warnings)]
+
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Media {
+    Play,
+    Stop,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Deck {
+    pub loaded: bool,
+    pub plays: u8,
+}
+
+fn count_play(deck: &mut Deck, _event: &Media) {
+    deck.plays += 1;
+}
+
+statechart! {
+    name: MediaPlayer,
+    context: Deck,
+    event: Media,
+    initial: Idle,
+    state Idle [terminal] {
+        on Media::Play [guard self.loaded] => Active [action count_play];
+    }
+    state Active {
+        on Media::Stop => Idle;
+    }
+}
+
+statechart! {
+    name: Recorder,
+    context: Deck,
+    event: Media,
+    initial: Idle,
+    state Idle [terminal] {
+        on Media::Play [guard self.loaded] => Active [action count_play];
+    }
+    state Active {
+        on Media::Stop => Idle;
+    }
+}
+
+#[test]
+fn same_shaped_machines_in_one_module_run_independently() {
+    let mut player = MediaPlayer::new(Deck::default(), &Media::Stop).expect("player starts");
+    let loaded = Deck {
+        loaded: true,
+        ..Deck::default()
+    };
+    let mut recorder = Recorder::new(loaded, &Media::Stop).expect("recorder starts");
+
+    assert_eq!(player.send(&Media::Play), SendResult::NoMatch);
+    assert_eq!(recorder.send(&Media::Play), SendResult::Transitioned);
+    assert_eq!(player.state().as_slice(), [MediaPlayerStateId::Idle]);
+    assert_eq!(recorder.state().as_slice(), [RecorderStateId::Active]);
+    assert_eq!((player.context().plays, recorder.context().plays), (0, 1));
+
+    player.context_mut().loaded = true;
+    assert_eq!(player.send(&Media::Play), SendResult::Transitioned);
+    assert_eq!(recorder.send(&Media::Stop), SendResult::Transitioned);
+    assert_eq!(player.state().as_slice(), [MediaPlayerStateId::Active]);
+    assert_eq!(recorder.state().as_slice(), [RecorderStateId::Idle]);
+}
+
+#[test]
+fn each_machine_keeps_its_own_definition() {
+    let player = MediaPlayer::definition();
+    let recorder = Recorder::definition();
+    assert_eq!(player.transitions.len(), 2);
+    assert_eq!(recorder.transitions.len(), 2);
+    assert_eq!(player.transitions[0].from_state, MediaPlayerStateId::Idle);
+    assert_eq!(recorder.transitions[0].from_state, RecorderStateId::Idle);
+    assert_eq!(
+        MEDIAPLAYER_MACHINE_DEFINITION.initial_leaf_state,
+        MediaPlayerStateId::Idle
+    );
+    assert_eq!(
+        RECORDER_MACHINE_DEFINITION.initial_leaf_state,
+        RecorderStateId::Idle
+    );
+}
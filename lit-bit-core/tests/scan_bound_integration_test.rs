@@ -0,0 +1,110 @@
+// lit-bit-core/tests/scan_bound_integration_test.rs
+//
+// `MAX_TRANSITIONS_PER_STATE` and `MAX_TRANSITION_CHECKS_PER_SEND`: macro-time bounds on
+// the transitions one `send` checks.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    A,
+    B,
+    C,
+    D,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Ctx;
+
+mod nested {
+    use super::*;
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Nested,
+        context: Ctx,
+        event: Signal,
+        initial: Outer,
+        state Outer {
+            initial: Inner;
+            on Signal::D => Other;
+            state Inner {
+                initial: Leaf;
+                on Signal::C => Outer;
+                state Leaf {
+                    on Signal::A => Sibling;
+                    on Signal::B => Sibling;
+                }
+                state Sibling {}
+            }
+        }
+        // Three transitions of its own, but shallower than Outer.Inner.Leaf (1 + 1 + 2).
+        state Other {
+            on Signal::A => Outer;
+            on Signal::B => Outer;
+            on Signal::C => Outer;
+        }
+    }
+}
+
+mod parallel {
+    use super::*;
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Split,
+        context: Ctx,
+        event: Signal,
+        initial: Both,
+        state Both [parallel] {
+            on Signal::D => Both;
+            state Left {
+                initial: L1;
+                state L1 {
+                    on Signal::A => L2;
+                    on Signal::B => L2;
+                }
+                state L2 {}
+            }
+            state Right {
+                initial: R1;
+                state R1 { on Signal::C => R1; }
+            }
+        }
+    }
+}
+
+use lit_bit_core::StateMachine;
+use nested::{Nested, NestedStateId};
+use parallel::Split;
+
+// Usable in `const` context, e.g. to size a latency budget.
+const _: () = assert!(Nested::MAX_TRANSITIONS_PER_STATE <= 8);
+
+#[test]
+fn hierarchical_bound_sums_the_deepest_ancestor_chain() {
+    assert_eq!(Nested::MAX_TRANSITIONS_PER_STATE, 4);
+    assert_eq!(Nested::MAX_TRANSITION_CHECKS_PER_SEND, 4);
+}
+
+#[test]
+fn parallel_bound_adds_up_every_region() {
+    // Left leaf walks L1 (2) + Left (0) + Both (1); Right walks R1 (1) + Right (0) + Both (1).
+    assert_eq!(Split::MAX_TRANSITIONS_PER_STATE, 3);
+    assert_eq!(Split::MAX_TRANSITION_CHECKS_PER_SEND, 5);
+}
+
+#[test]
+fn bound_matches_the_transitions_on_the_deepest_path() {
+    let machine = Nested::new(Ctx, &Signal::A).expect("machine starts");
+    assert_eq!(machine.state().as_slice(), &[NestedStateId::OuterInnerLeaf]);
+    let checked = Nested::definition()
+        .transitions
+        .iter()
+        .filter(|t| {
+            matches!(
+                t.from_state,
+                NestedStateId::OuterInnerLeaf | NestedStateId::OuterInner | NestedStateId::Outer
+            )
+        })
+        .count();
+    assert_eq!(checked, Nested::MAX_TRANSITIONS_PER_STATE);
+}
@@ -0,0 +1,86 @@
+// lit-bit-core/tests/scenario_integration_test.rs
+//
+// `statechart_test!` and the `Scenario` DSL: behavioural tests written one step per line.
+
+use lit_bit_core::SendResult;
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Door {
+    Boot,
+    Open,
+    Close,
+    Lock,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Latch {
+    pub locks: u32,
+}
+
+fn count_lock(ctx: &mut Latch, _event: &Door) {
+    ctx.locks += 1;
+}
+
+statechart! {
+    name: Entry,
+    context: Latch,
+    event: Door,
+    initial: Shut,
+    state Shut {
+        initial: Unlocked;
+        on Door::Open => Ajar;
+        state Unlocked {
+            on Door::Lock => Locked [action count_lock];
+        }
+        state Locked {}
+    }
+    state Ajar {
+        on Door::Close => Shut;
+    }
+}
+
+mod defaults {
+    use super::{Door, EntryStateId, SendResult};
+
+    lit_bit_core::statechart_test!(super::Entry);
+
+    #[test]
+    fn the_harness_starts_in_the_initial_state() {
+        scenario(&Door::Boot).expect_states(&[EntryStateId::ShutUnlocked]);
+    }
+
+    #[test]
+    fn a_session_reads_one_step_per_line() {
+        scenario(&Door::Boot)
+            .send(Door::Open)
+            .expect_result(SendResult::Transitioned)
+            .expect_state(EntryStateId::Ajar)
+            .send(Door::Lock)
+            .expect_result(SendResult::NoMatch)
+            .send_all([Door::Close, Door::Lock])
+            .expect_state(EntryStateId::ShutLocked)
+            .expect_context(|latch| latch.locks == 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected Ajar to be active")]
+    fn a_wrong_state_fails_the_test() {
+        scenario(&Door::Boot).expect_state(EntryStateId::Ajar);
+    }
+}
+
+mod custom_context {
+    use super::{Door, Latch};
+
+    lit_bit_core::statechart_test!(super::Entry, context = Latch { locks: 7 });
+
+    #[test]
+    fn the_context_expression_is_used() {
+        let machine = scenario(&Door::Boot)
+            .send(Door::Lock)
+            .expect_context(|latch| latch.locks == 8)
+            .into_machine();
+        assert!(machine.is_started());
+    }
+}
@@ -0,0 +1,113 @@
+// lit-bit-core/tests/speculation_integration_test.rs
+//
+// Cloning generated machines and speculating on events without touching the original.
+
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    Pay,
+    Ship,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    pub charged: u32,
+    pub entries: Vec<&'static str>,
+}
+
+fn charge(ctx: &mut Ledger, _event: &Order) {
+    ctx.charged += 1;
+    ctx.entries.push("charge");
+}
+
+fn record_shipping(ctx: &mut Ledger, _event: &Order) {
+    ctx.entries.push("ship");
+}
+
+statechart! {
+    name: Checkout,
+    context: Ledger,
+    event: Order,
+    initial: Open,
+    state Open {
+        initial: Cart;
+        on Order::Cancel => Cancelled;
+        state Cart {
+            on Order::Pay => Paid [action charge];
+        }
+        state Paid {
+            entry: record_shipping;
+            on Order::Ship => Shipped;
+        }
+    }
+    state Shipped {}
+    state Cancelled {}
+}
+
+fn checkout() -> Checkout {
+    Checkout::new(Ledger::default(), &Order::Pay).expect("machine starts")
+}
+
+#[test]
+fn speculating_leaves_the_original_untouched() {
+    let machine = checkout();
+
+    let speculation = machine.speculate(&Order::Pay);
+    assert!(speculation.transitioned());
+    assert_eq!(
+        speculation.machine.state().as_slice(),
+        [CheckoutStateId::OpenPaid]
+    );
+    assert_eq!(speculation.machine.context().charged, 1);
+    assert_eq!(speculation.machine.context().entries, ["charge", "ship"]);
+
+    assert_eq!(machine.state().as_slice(), [CheckoutStateId::OpenCart]);
+    assert_eq!(machine.context().charged, 0);
+    assert!(machine.context().entries.is_empty());
+}
+
+#[test]
+fn speculation_reports_what_would_change() {
+    let machine = checkout();
+
+    let speculation = machine.speculate(&Order::Cancel);
+    let diff = machine.diff(&speculation.machine);
+    assert!(diff.exited.contains(&CheckoutStateId::Open));
+    assert!(diff.exited.contains(&CheckoutStateId::OpenCart));
+    assert_eq!(diff.entered.as_slice(), [CheckoutStateId::Cancelled]);
+
+    let ignored = machine.speculate(&Order::Ship);
+    assert_eq!(ignored.result, SendResult::NoMatch);
+    assert!(!ignored.transitioned());
+    assert!(machine.diff(&ignored.machine).entered.is_empty());
+}
+
+#[test]
+fn a_speculation_can_be_adopted() {
+    let mut machine = checkout();
+
+    let speculation = machine.speculate(&Order::Pay);
+    if speculation.transitioned() {
+        machine = speculation.into_machine();
+    }
+    assert_eq!(machine.send(&Order::Ship), SendResult::Transitioned);
+    assert_eq!(machine.state().as_slice(), [CheckoutStateId::Shipped]);
+    assert_eq!(machine.context().charged, 1);
+}
+
+#[test]
+fn clones_evolve_independently() {
+    let mut original = checkout();
+    let mut copy = original.clone();
+
+    assert_eq!(copy.send(&Order::Cancel), SendResult::Transitioned);
+    assert_eq!(original.send(&Order::Pay), SendResult::Transitioned);
+
+    assert_eq!(copy.state().as_slice(), [CheckoutStateId::Cancelled]);
+    assert_eq!(original.state().as_slice(), [CheckoutStateId::OpenPaid]);
+    assert_eq!(copy.context().charged, 0);
+    assert_eq!(original.context().charged, 1);
+}
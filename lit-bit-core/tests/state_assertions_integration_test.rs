@@ -0,0 +1,175 @@
+// lit-bit-core/tests/state_assertions_integration_test.rs
+//
+// `assert_in_state!` and `assert_configuration!`: passing on paths as written in the chart,
+// and the failure messages they render otherwise.
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use lit_bit_core::console::MachineConsole;
+use lit_bit_core::{assert_configuration, assert_in_state};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Boot,
+    Play,
+    Pause,
+    Stop,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Deck;
+
+statechart! {
+    name: Player,
+    context: Deck,
+    event: Key,
+    initial: Media,
+    state Media [parallel] {
+        state Audio {
+            initial: Muted;
+            state Muted {
+                on Key::Play => Playing;
+            }
+            state Playing {
+                on Key::Stop => Muted;
+            }
+        }
+        state Video {
+            initial: Stopped;
+            state Stopped {
+                on Key::Play => Paused;
+            }
+            state Paused {
+                on Key::Pause => Stopped;
+            }
+        }
+    }
+}
+
+fn player() -> Player {
+    Player::new(Deck, &Key::Boot).expect("player starts")
+}
+
+fn parse_event(text: &str) -> Option<Key> {
+    match text {
+        "play" => Some(Key::Play),
+        "pause" => Some(Key::Pause),
+        "stop" => Some(Key::Stop),
+        _ => None,
+    }
+}
+
+/// Runs `assertion`, which must fail, and returns its message.
+fn failure(assertion: impl FnOnce()) -> String {
+    let payload = catch_unwind(AssertUnwindSafe(assertion)).expect_err("the assertion fails");
+    *payload.downcast::<String>().expect("a formatted message")
+}
+
+#[test]
+fn paths_resolve_with_either_separator_and_ancestors_count() {
+    let player = player();
+    assert_in_state!(player, "Media::Audio::Muted");
+    assert_in_state!(player, "Media.Video.Stopped");
+    assert_in_state!(player, "Media::Video");
+    assert_in_state!(&player, "Media");
+    assert_configuration!(player, ["Media::Video::Stopped", "Media::Audio::Muted"]);
+}
+
+#[test]
+fn a_state_that_is_not_active_lists_the_active_leaves() {
+    let player = player();
+    assert_eq!(
+        failure(|| assert_in_state!(player, "Media::Video::Paused")),
+        "machine is not in the expected state
+  expected: Media::Video::Paused
+    actual: Media::Audio::Muted, Media::Video::Stopped"
+    );
+}
+
+#[test]
+fn a_different_configuration_lists_the_missing_and_extra_leaves() {
+    let player = player();
+    assert_eq!(
+        failure(|| assert_configuration!(
+            player,
+            ["Media::Audio::Playing", "Media::Video::Stopped"]
+        )),
+        "active states differ from the expected configuration
+  expected: Media::Audio::Playing, Media::Video::Stopped
+    actual: Media::Audio::Muted, Media::Video::Stopped
+   missing: Media::Audio::Playing
+     extra: Media::Audio::Muted"
+    );
+
+    // A configuration with a leaf left out only has extra leaves.
+    assert_eq!(
+        failure(|| assert_configuration!(player, ["Media.Audio.Muted"])),
+        "active states differ from the expected configuration
+  expected: Media::Audio::Muted
+    actual: Media::Audio::Muted, Media::Video::Stopped
+     extra: Media::Video::Stopped"
+    );
+}
+
+#[test]
+fn a_path_naming_no_state_is_reported_as_such() {
+    let player = player();
+    assert_eq!(
+        failure(|| assert_in_state!(player, "Media::Audio::Paused")),
+        "`Media::Audio::Paused` names no state of the chart
+    actual: Media::Audio::Muted, Media::Video::Stopped"
+    );
+    assert_eq!(
+        failure(|| assert_configuration!(player, ["Media::Audio::Muted", "Video::Stopped"])),
+        "`Video::Stopped` names no state of the chart
+    actual: Media::Audio::Muted, Media::Video::Stopped"
+    );
+}
+
+#[test]
+fn a_console_trace_lists_the_last_sends() {
+    let mut player = player();
+    let mut console =
+        MachineConsole::<_, 4>::new(&mut player, PlayerStateId::user_path, parse_event);
+    assert_eq!(
+        failure(|| assert_in_state!(
+            console.machine(),
+            "Media::Audio::Playing",
+            trace = console.trace()
+        )),
+        "machine is not in the expected state
+  expected: Media::Audio::Playing
+    actual: Media::Audio::Muted, Media::Video::Stopped
+no sends traced"
+    );
+
+    let mut out = String::new();
+    for line in [
+        "fsm send play",
+        "fsm send stop",
+        "fsm send pause",
+        "fsm send pause",
+    ] {
+        console
+            .handle_line(line, &mut out)
+            .expect("command succeeds");
+    }
+    assert_eq!(
+        failure(|| assert_configuration!(
+            console.machine(),
+            ["Media::Audio::Playing", "Media::Video::Paused"],
+            trace = console.trace(),
+        )),
+        "active states differ from the expected configuration
+  expected: Media::Audio::Playing, Media::Video::Paused
+    actual: Media::Audio::Muted, Media::Video::Stopped
+   missing: Media::Audio::Playing, Media::Video::Paused
+     extra: Media::Audio::Muted, Media::Video::Stopped
+last sends, oldest first:
+  #1 Transitioned: Media::Video::Stopped, Media::Audio::Muted -> Media::Audio::Playing, Media::Video::Paused
+  #2 Transitioned: Media::Audio::Playing -> Media::Audio::Muted
+  #3 Transitioned: Media::Video::Paused -> Media::Video::Stopped
+  #4 NoMatch: - -> -"
+    );
+}
@@ -0,0 +1,90 @@
+// lit-bit-core/tests/state_index_integration_test.rs
+//
+// `u32` conversions of the state-ID enum: sorted indices by default, `[id = N]` to pin them.
+
+use lit_bit_core::UnknownStateIndex;
+use pinned::PinnedStateId;
+use sorted::SortedStateId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    Go,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Link;
+
+mod sorted {
+    use super::{Link, Signal};
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Sorted,
+        context: Link,
+        event: Signal,
+        initial: Idle,
+        state Idle {
+            on Go => Busy;
+        }
+        state Busy {
+            initial: Sending;
+            state Sending {}
+            state Acking {}
+        }
+    }
+}
+
+mod pinned {
+    use super::{Link, Signal};
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Pinned,
+        context: Link,
+        event: Signal,
+        initial: Idle,
+        state Idle [id = 10] {
+            on Go => Busy;
+        }
+        state Busy [id = 20] {
+            initial: Sending;
+            state Sending [id = 21] {}
+            state Acking [id = 22] {}
+        }
+    }
+}
+
+#[test]
+fn states_are_numbered_in_sorted_path_order_by_default() {
+    let by_index: Vec<&str> = (0..4)
+        .map(|index| {
+            SortedStateId::try_from(index)
+                .expect("index is in range")
+                .user_path()
+        })
+        .collect();
+    assert_eq!(by_index, ["Busy", "Busy.Acking", "Busy.Sending", "Idle"]);
+}
+
+#[test]
+fn pinned_states_keep_their_declared_index() {
+    assert_eq!(u32::from(PinnedStateId::Idle), 10);
+    assert_eq!(u32::from(PinnedStateId::BusyAcking), 22);
+    assert_eq!(PinnedStateId::try_from(21), Ok(PinnedStateId::BusySending));
+}
+
+#[test]
+fn every_state_round_trips_through_its_index() {
+    for path in ["Idle", "Busy", "Busy.Sending", "Busy.Acking"] {
+        let sorted = SortedStateId::from_user_path(path).expect("state exists");
+        assert_eq!(SortedStateId::from_index(sorted.index()), Some(sorted));
+        let pinned = PinnedStateId::from_user_path(path).expect("state exists");
+        assert_eq!(PinnedStateId::try_from(u32::from(pinned)), Ok(pinned));
+    }
+}
+
+#[test]
+fn unused_indices_are_rejected() {
+    assert_eq!(SortedStateId::try_from(4), Err(UnknownStateIndex(4)));
+    assert_eq!(PinnedStateId::try_from(0), Err(UnknownStateIndex(0)));
+}
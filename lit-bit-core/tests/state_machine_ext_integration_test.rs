@@ -0,0 +1,91 @@
+// lit-bit-core/tests/state_machine_ext_integration_test.rs
+//
+// `StateMachineExt` on a generated machine: `send_if` gated on the machine, `send_while`
+// stopping a stream of events early, and the machine's own `current_name()`.
+
+use lit_bit_core::{SendResult, StateMachine, StateMachineExt};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Press,
+    Hold,
+    Release,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Pad {
+    locked: bool,
+}
+
+statechart! {
+    name: Button,
+    context: Pad,
+    event: Key,
+    initial: Up,
+    state Up {
+        on Key::Press => Down;
+    }
+    state Down {
+        initial: Pressed;
+        on Key::Release => Up;
+        state Pressed {
+            on Key::Hold => Held;
+        }
+        state Held {}
+    }
+}
+
+fn button() -> Button {
+    Button::new(Pad::default(), &Key::Release).expect("button starts")
+}
+
+#[test]
+fn send_if_only_sends_when_the_predicate_holds() {
+    let mut button = button();
+    button.context_mut().locked = true;
+    assert_eq!(button.send_if(&Key::Press, |b| !b.context().locked), None);
+    assert_eq!(button.current(), ButtonStateId::Up);
+
+    button.context_mut().locked = false;
+    assert_eq!(
+        button.send_if(&Key::Press, |b| !b.context().locked),
+        Some(SendResult::Transitioned)
+    );
+    assert_eq!(button.current(), ButtonStateId::DownPressed);
+}
+
+#[test]
+fn send_while_stops_after_the_first_rejected_result() {
+    let mut button = button();
+    let mut pulled = 0;
+    let events = [Key::Press, Key::Press, Key::Hold, Key::Release]
+        .into_iter()
+        .inspect(|_| pulled += 1);
+
+    let summary = button.send_while(events, |result| *result == SendResult::Transitioned);
+    assert_eq!(summary.transitioned, 1);
+    assert_eq!(summary.ignored, 1);
+    assert_eq!(summary.final_state.as_slice(), [ButtonStateId::DownPressed]);
+    // The second `Press` matched nothing, so `Hold` and `Release` were never pulled.
+    assert_eq!(pulled, 2);
+}
+
+#[test]
+fn send_while_takes_borrowed_events_and_runs_to_the_end() {
+    let mut button = button();
+    let events = vec![Key::Press, Key::Hold, Key::Release];
+    let summary = button.send_while(&events, |_| true);
+    assert_eq!(summary.transitioned, 3);
+    assert_eq!(summary.final_state, button.state());
+}
+
+#[test]
+fn current_name_is_the_dotted_path_of_the_active_leaf() {
+    let mut button = button();
+    assert_eq!(button.current_name(), "Up");
+    let _ = button.send(&Key::Press);
+    assert_eq!(button.current_name(), "Down.Pressed");
+    let _ = button.send(&Key::Hold);
+    assert_eq!(button.current_name(), "Down.Held");
+}
@@ -0,0 +1,159 @@
+// lit-bit-core/tests/strict_machine_integration_test.rs
+//
+// `strict: true,` charts: `send` returns `Result<SendResult, <Name>Error>`, naming the
+// active state and the event's kind for every result but a finished step.
+
+use lit_bit_core::{DenyTargets, ProcessingError, SendResult, StateMachine};
+use lit_bit_macro::{statechart, statechart_event};
+
+#[statechart_event]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Cmd {
+    Boot,
+    Open,
+    Close,
+    Fill(u8),
+    Drain,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Tank {
+    pub level: u8,
+}
+
+impl Tank {
+    fn check_level(&self) -> Result<(), &'static str> {
+        if self.level > 100 {
+            Err("the tank overflows")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn fill(tank: &mut Tank, event: &Cmd) {
+    if let Cmd::Fill(amount) = event {
+        tank.level = tank.level.saturating_add(*amount);
+    }
+}
+
+statechart! {
+    name: Valve,
+    context: Tank,
+    event: Cmd,
+    strict: true,
+    invariant: self.check_level,
+    initial: Closed,
+    state Closed {
+        on Cmd::Open => Open;
+    }
+    state Open {
+        initial: Flowing;
+        on Cmd::Close => Closed;
+        state Flowing {
+            on Cmd::Fill(_) => Flowing [action fill];
+            on Cmd::Drain => Draining;
+        }
+        state Draining {}
+    }
+}
+
+fn valve() -> Valve {
+    Valve::new(Tank::default(), &Cmd::Boot).expect("valve starts")
+}
+
+#[test]
+fn a_finished_step_is_ok() {
+    let mut valve = valve();
+    assert_eq!(valve.send(&Cmd::Open), Ok(SendResult::Transitioned));
+    assert_eq!(valve.current(), ValveStateId::OpenFlowing);
+}
+
+#[test]
+fn an_unhandled_event_names_the_state_and_the_event_kind() {
+    let mut valve = valve();
+    let error = valve.send(&Cmd::Fill(3)).unwrap_err();
+    assert_eq!(
+        error,
+        ValveError::Unhandled {
+            state: ValveStateId::Closed,
+            event_kind: CmdKind::Fill,
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "No transition handles `Fill` in `Closed`."
+    );
+}
+
+#[test]
+fn a_vetoed_event_is_reported_as_rejected() {
+    static NO_DRAIN: DenyTargets<ValveStateId> = DenyTargets(&[ValveStateId::OpenDraining]);
+    let mut valve = valve();
+    valve.set_policy(Some(&NO_DRAIN));
+    assert_eq!(valve.send(&Cmd::Open), Ok(SendResult::Transitioned));
+
+    let error = valve.send(&Cmd::Drain).unwrap_err();
+    assert_eq!(
+        error,
+        ValveError::PolicyRejected {
+            state: ValveStateId::OpenFlowing,
+            event_kind: CmdKind::Drain,
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "The transition policy rejected `Drain` in `Open.Flowing`."
+    );
+}
+
+#[test]
+fn a_violated_invariant_names_the_state_the_event_left() {
+    let mut valve = valve();
+    assert_eq!(valve.send(&Cmd::Open), Ok(SendResult::Transitioned));
+    assert_eq!(valve.send(&Cmd::Fill(90)), Ok(SendResult::Transitioned));
+
+    let error = valve.send(&Cmd::Fill(20)).unwrap_err();
+    assert_eq!(
+        error,
+        ValveError::InvariantViolated {
+            state: ValveStateId::OpenFlowing,
+            event_kind: CmdKind::Fill,
+            violation: "the tank overflows",
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "`Fill` left `Open.Flowing` with a violated invariant: the tank overflows"
+    );
+}
+
+#[test]
+fn processing_errors_are_wrapped_with_their_cause() {
+    let error = ValveError::Processing {
+        state: ValveStateId::Closed,
+        event_kind: CmdKind::Open,
+        error: ProcessingError::CapacityExceeded,
+    };
+    assert_eq!(
+        error.to_string(),
+        "Processing `Open` in `Closed` failed: Internal capacity exceeded during event processing."
+    );
+    #[cfg(feature = "std")]
+    let _: &dyn std::error::Error = &error;
+}
+
+#[test]
+fn the_unchecked_and_trait_sends_keep_the_plain_result() {
+    let mut valve = valve();
+    assert_eq!(valve.send_unchecked(&Cmd::Close), SendResult::NoMatch);
+    assert_eq!(
+        StateMachine::send(&mut valve, &Cmd::Close),
+        SendResult::NoMatch
+    );
+    assert_eq!(valve.speculate(&Cmd::Drain).result, SendResult::NoMatch);
+    assert_eq!(
+        StateMachine::send(&mut valve, &Cmd::Open),
+        SendResult::Transitioned
+    );
+}
@@ -0,0 +1,165 @@
+// lit-bit-core/tests/sub_machine_integration_test.rs
+//
+// `state Name = OtherMachine;` states that delegate events to an embedded machine.
+
+use lit_bit_core::{SendResult, StateMachine};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShopEvent {
+    Open,
+    Buy,
+    Card,
+    Approved,
+    Declined,
+    Cancel,
+}
+
+mod checkout {
+    use super::ShopEvent;
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Payment {
+        pub attempts: u32,
+    }
+
+    fn count_attempt(ctx: &mut Payment, _event: &ShopEvent) {
+        ctx.attempts += 1;
+    }
+
+    statechart! {
+        name: Checkout,
+        context: Payment,
+        event: ShopEvent,
+        initial: EnteringCard,
+        state EnteringCard {
+            on ShopEvent::Card => Authorizing [action count_attempt];
+        }
+        state Authorizing {
+            on ShopEvent::Approved => Paid;
+            on ShopEvent::Declined => EnteringCard;
+        }
+        state Paid [final] {}
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Cart;
+
+mod shop {
+    use super::{Cart, ShopEvent};
+    use crate::checkout::Checkout;
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Shop,
+        context: Cart,
+        event: ShopEvent,
+        initial: Order,
+        state Order {
+            initial: Browsing;
+            on ShopEvent::Cancel => Cancelled;
+            state Browsing {
+                on ShopEvent::Buy => Paying;
+            }
+            state Paying = Checkout => Shipped;
+            state Shipped [final] {}
+        }
+        state Cancelled [final] {}
+    }
+}
+
+mod kiosk {
+    use super::{Cart, ShopEvent};
+    use crate::checkout::Checkout;
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Kiosk,
+        context: Cart,
+        event: ShopEvent,
+        initial: Paying,
+        state Paying = Checkout;
+        state Closed {
+            on ShopEvent::Open => Paying;
+        }
+    }
+}
+
+use checkout::CheckoutStateId;
+use kiosk::{Kiosk, KioskStateId};
+use shop::{Shop, ShopStateId};
+
+#[test]
+fn final_states_are_marked_on_state_ids() {
+    assert!(CheckoutStateId::Paid.is_final());
+    assert!(!CheckoutStateId::Authorizing.is_final());
+    assert!(ShopStateId::OrderShipped.is_final());
+    assert!(!ShopStateId::OrderPaying.is_final());
+}
+
+#[test]
+fn events_go_to_the_sub_machine_until_it_is_done() {
+    let mut shop = Shop::new(Cart, &ShopEvent::Open).expect("shop starts");
+    assert_eq!(shop.send(&ShopEvent::Buy), SendResult::Transitioned);
+    assert_eq!(shop.state()[0], ShopStateId::OrderPaying);
+
+    // Handled inside the checkout; the shop itself stays where it is.
+    assert_eq!(shop.send(&ShopEvent::Card), SendResult::Transitioned);
+    assert_eq!(shop.send(&ShopEvent::Declined), SendResult::Transitioned);
+    assert_eq!(shop.send(&ShopEvent::Card), SendResult::Transitioned);
+    assert_eq!(shop.state()[0], ShopStateId::OrderPaying);
+    assert!(!shop.is_final());
+
+    // The checkout reaching `Paid` moves the shop on.
+    assert_eq!(shop.send(&ShopEvent::Approved), SendResult::Transitioned);
+    assert_eq!(shop.state()[0], ShopStateId::OrderShipped);
+    assert!(shop.is_final());
+}
+
+#[test]
+fn events_the_sub_machine_ignores_reach_the_parent() {
+    let mut shop = Shop::new(Cart, &ShopEvent::Open).expect("shop starts");
+    let summary = shop.send_batch(&[ShopEvent::Buy, ShopEvent::Card, ShopEvent::Buy]);
+    assert_eq!(summary.transitioned, 2);
+    assert_eq!(summary.ignored, 1);
+
+    assert_eq!(shop.send(&ShopEvent::Cancel), SendResult::Transitioned);
+    assert_eq!(shop.state()[0], ShopStateId::Cancelled);
+    assert!(shop.is_final());
+}
+
+#[test]
+fn re_entering_the_state_starts_a_fresh_sub_machine() {
+    let mut kiosk = Kiosk::new(Cart, &ShopEvent::Open).expect("kiosk starts");
+    assert_eq!(kiosk.send(&ShopEvent::Card), SendResult::Transitioned);
+
+    // Without a `=> Target`, a finished checkout leaves the kiosk in `Paying` and stops
+    // taking events; the kiosk's own transitions still apply.
+    assert_eq!(kiosk.send(&ShopEvent::Approved), SendResult::Transitioned);
+    assert_eq!(kiosk.send(&ShopEvent::Card), SendResult::NoMatch);
+    kiosk
+        .force_transition(KioskStateId::Closed, &ShopEvent::Cancel)
+        .expect("closes");
+
+    assert_eq!(kiosk.send(&ShopEvent::Open), SendResult::Transitioned);
+    assert_eq!(kiosk.state()[0], KioskStateId::Paying);
+    assert_eq!(kiosk.send(&ShopEvent::Card), SendResult::Transitioned);
+}
+
+#[test]
+fn speculating_clones_the_running_sub_machine() {
+    let mut shop = Shop::new(Cart, &ShopEvent::Open).expect("shop starts");
+    shop.send_batch(&[ShopEvent::Buy, ShopEvent::Card]);
+
+    // Approving would finish the checkout and ship, but only on the clone.
+    let speculation = shop.speculate(&ShopEvent::Approved);
+    assert!(speculation.transitioned());
+    assert_eq!(speculation.machine.state()[0], ShopStateId::OrderShipped);
+
+    assert_eq!(shop.state()[0], ShopStateId::OrderPaying);
+    assert_eq!(shop.send(&ShopEvent::Declined), SendResult::Transitioned);
+    assert_eq!(shop.send(&ShopEvent::Card), SendResult::Transitioned);
+    assert_eq!(shop.send(&ShopEvent::Approved), SendResult::Transitioned);
+    assert!(shop.is_final());
+}
@@ -0,0 +1,83 @@
+// lit-bit-core/tests/transition_order_integration_test.rs
+//
+// `[order = N]` on transitions: a state tries its ranked transitions first, lowest rank
+// first, whatever order the chart (or the macros composing it) declared them in.
+
+use lit_bit_core::{SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Press {
+    Button,
+    Lever,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Panel {
+    pub armed: bool,
+}
+
+fn is_armed(ctx: &Panel, _event: &Press) -> bool {
+    ctx.armed
+}
+
+mod declared {
+    use super::{Panel, Press};
+    use lit_bit_macro::statechart;
+
+    // Without ranks the first declared transition wins.
+    statechart! {
+        name: Declared,
+        context: Panel,
+        event: Press,
+        initial: Idle,
+        state Idle {
+            on Press::Button => Left;
+            on Press::Button => Right;
+        }
+        state Left {}
+        state Right {}
+    }
+}
+
+statechart! {
+    name: Ranked,
+    context: Panel,
+    event: Press,
+    initial: Idle,
+    state Idle {
+        on Press::Button => Left;
+        on Press::Button [order = 1] => Right;
+        on Press::Lever => Left;
+        on Press::Lever [guard is_armed] [order = 3] => Armed else => Right;
+        on Press::Lever [order = 2] [guard is_armed] => Left;
+    }
+    state Left {}
+    state Right {}
+    state Armed {}
+}
+
+#[test]
+fn a_ranked_transition_beats_an_earlier_declared_one() {
+    let mut declared = declared::Declared::new(Panel::default(), &Press::Button).expect("starts");
+    assert_eq!(declared.send(&Press::Button), SendResult::Transitioned);
+    assert_eq!(declared.state()[0], declared::DeclaredStateId::Left);
+
+    let mut ranked = Ranked::new(Panel::default(), &Press::Button).expect("starts");
+    assert_eq!(ranked.send(&Press::Button), SendResult::Transitioned);
+    assert_eq!(ranked.state()[0], RankedStateId::Right);
+}
+
+#[test]
+fn ranks_order_guarded_transitions_and_keep_else_behind_its_guard() {
+    // Rank 2 is tried first and passes its guard.
+    let mut ranked = Ranked::new(Panel { armed: true }, &Press::Button).expect("starts");
+    assert_eq!(ranked.send(&Press::Lever), SendResult::Transitioned);
+    assert_eq!(ranked.state()[0], RankedStateId::Left);
+
+    // Unarmed, rank 2 is rejected and rank 3's `else` is taken before the unranked
+    // transition declared first.
+    let mut ranked = Ranked::new(Panel::default(), &Press::Button).expect("starts");
+    assert_eq!(ranked.send(&Press::Lever), SendResult::Transitioned);
+    assert_eq!(ranked.state()[0], RankedStateId::Right);
+}
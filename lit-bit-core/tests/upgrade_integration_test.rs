@@ -0,0 +1,78 @@
+// lit-bit-core/tests/upgrade_integration_test.rs
+#![cfg(feature = "std")]
+
+use lit_bit_core::upgrade::{
+    MigrationPolicy, StateSnapshot, TransitionChange, diff_definitions, migrate_snapshot,
+};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Default)]
+pub struct DoorContext;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DoorEvent {
+    Open,
+    Close,
+    Lock,
+}
+
+mod v1 {
+    use super::{DoorContext, DoorEvent};
+    use lit_bit_macro::statechart;
+
+    statechart! {
+        name: Door,
+        context: DoorContext,
+        event: DoorEvent,
+        initial: Closed,
+        state Closed {
+            initial: Unlocked;
+            on DoorEvent::Open => Opened;
+            state Unlocked {
+                on DoorEvent::Lock => Locked;
+            }
+            state Locked {}
+        }
+        state Opened {
+            on DoorEvent::Close => Closed;
+        }
+    }
+}
+
+// Version 2 drops the `Locked` leaf and its transition.
+statechart! {
+    name: DoorV2,
+    context: DoorContext,
+    event: DoorEvent,
+    initial: Closed,
+    state Closed {
+        initial: Unlocked;
+        on DoorEvent::Open => Opened;
+        state Unlocked {}
+    }
+    state Opened {
+        on DoorEvent::Close => Closed;
+    }
+}
+
+#[test]
+fn persisted_snapshot_migrates_to_the_new_chart() {
+    let mut door = v1::Door::new(DoorContext, &DoorEvent::Open).expect("valid machine");
+    let _ = door.send(&DoorEvent::Lock);
+    let persisted = door.snapshot();
+    assert_eq!(persisted, StateSnapshot::new(["Closed.Locked"]));
+
+    let diff = diff_definitions(&v1::Door::erased_definition(), &DoorV2::erased_definition());
+    assert_eq!(diff.removed, ["Closed.Locked"]);
+    assert!(diff.added.is_empty() && diff.renamed.is_empty());
+    assert!(matches!(
+        diff.transitions.as_slice(),
+        [TransitionChange::Removed(transition)] if transition.to == "Closed.Locked"
+    ));
+
+    let migrated = migrate_snapshot(&persisted, &diff, &MigrationPolicy::Parent)
+        .expect("Closed survives the upgrade");
+    assert_eq!(migrated, StateSnapshot::new(["Closed.Unlocked"]));
+    let restored = DoorV2StateId::from_user_path(&migrated.active[0]).expect("known state");
+    assert_eq!(restored, DoorV2StateId::ClosedUnlocked);
+}
@@ -0,0 +1,134 @@
+// lit-bit-core/tests/validating_action_integration_test.rs
+//
+// `[action f]` with `f` returning `Result<(), ActionError>`: an `Err` aborts the transition
+// and rolls the step back, exit actions included, without any `retry(...)` marker.
+
+use std::cell::RefCell;
+
+use lit_bit_core::{ActionError, ProcessingError, SendResult, StateMachine};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Form {
+    Submit(u8),
+    Edit,
+    Start,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Draft {
+    pub age: u8,
+    pub exits: u32,
+    pub edits: u32,
+}
+
+thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn take_log() -> Vec<&'static str> {
+    LOG.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+pub fn leave_editing(ctx: &mut Draft, _event: &Form) {
+    ctx.exits += 1;
+    LOG.with(|log| log.borrow_mut().push("exit Editing"));
+}
+
+pub fn try_submit(ctx: &mut Draft, event: &Form) -> Result<(), ActionError> {
+    let Form::Submit(age) = *event else {
+        return Ok(());
+    };
+    ctx.age = age;
+    if age > 150 {
+        return Err(ActionError::new("implausible age"));
+    }
+    Ok(())
+}
+
+pub fn edit(ctx: &mut Draft, _event: &Form) {
+    ctx.edits += 1;
+}
+
+statechart! {
+    name: FormMachine,
+    context: Draft,
+    event: Form,
+    initial: Editing,
+    state Editing {
+        exit: leave_editing;
+        on Form::Submit(_) => Submitted [action try_submit];
+        on Form::Edit => Editing [action edit];
+    }
+    state Submitted {
+        on Form::Edit => Editing [action edit];
+    }
+}
+
+#[test]
+fn an_ok_result_lets_the_transition_through() {
+    let mut form = FormMachine::new(Draft::default(), &Form::Start).expect("form starts");
+
+    assert_eq!(form.send(&Form::Submit(42)), SendResult::Transitioned);
+    assert_eq!(form.state().as_slice(), &[FormMachineStateId::Submitted]);
+    assert_eq!(form.context().age, 42);
+    assert_eq!(form.context().exits, 1);
+    assert_eq!(take_log(), ["exit Editing"]);
+}
+
+#[test]
+fn an_err_result_aborts_the_transition_and_rolls_back_the_exit_actions() {
+    let mut form = FormMachine::new(Draft::default(), &Form::Start).expect("form starts");
+
+    assert_eq!(
+        form.send(&Form::Submit(200)),
+        SendResult::Error(ProcessingError::ActionFailed)
+    );
+    assert_eq!(form.state().as_slice(), &[FormMachineStateId::Editing]);
+    // The exit action ran, but its changes to the context are rolled back with the rest of
+    // the step; effects outside the context are not.
+    assert_eq!(form.context().exits, 0);
+    assert_eq!(form.context().age, 0);
+    assert_eq!(take_log(), ["exit Editing"]);
+
+    // The machine carries on from the source state.
+    assert_eq!(form.send(&Form::Edit), SendResult::Transitioned);
+    assert_eq!(form.context().edits, 1);
+    assert_eq!(form.send(&Form::Submit(30)), SendResult::Transitioned);
+    assert_eq!(form.state().as_slice(), &[FormMachineStateId::Submitted]);
+}
+
+statechart! {
+    name: Wizard,
+    context: Draft,
+    event: Form,
+    initial: Steps,
+    state Steps {
+        initial: Personal;
+        exit: leave_editing;
+        on Form::Submit(_) => Done [action try_submit];
+        state Personal {
+            on Form::Edit => Personal [action edit];
+        }
+    }
+    state Done {}
+}
+
+#[test]
+fn nested_exits_are_rolled_back_too() {
+    let mut wizard = Wizard::new(Draft::default(), &Form::Start).expect("wizard starts");
+
+    assert_eq!(wizard.send(&Form::Edit), SendResult::Transitioned);
+    assert_eq!(
+        wizard.send(&Form::Submit(151)),
+        SendResult::Error(ProcessingError::ActionFailed)
+    );
+    assert_eq!(wizard.state().as_slice(), &[WizardStateId::StepsPersonal]);
+    assert_eq!(wizard.context().exits, 0);
+    assert_eq!(wizard.context().edits, 1);
+    take_log();
+
+    assert_eq!(wizard.send(&Form::Submit(40)), SendResult::Transitioned);
+    assert_eq!(wizard.state().as_slice(), &[WizardStateId::Done]);
+    assert_eq!(wizard.context().exits, 1);
+}
@@ -121,6 +121,7 @@ pub(crate) fn generate_states_array<'ast>(
         );
 
         let is_parallel_literal = tmp_state.is_parallel;
+        let is_final_literal = tmp_state.is_final;
 
         state_node_initializers.push(quote! {
             lit_bit_core::StateNode {
@@ -130,6 +131,7 @@ pub(crate) fn generate_states_array<'ast>(
                 entry_action: #entry_action_expr,
                 exit_action: #exit_action_expr,
                 is_parallel: #is_parallel_literal,
+                is_final: #is_final_literal,
             }
         });
     }
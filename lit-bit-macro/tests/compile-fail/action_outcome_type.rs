@@ -0,0 +1,33 @@
+// The generated code is gated on features of the crate using the macro.
+#![allow(unexpected_cfgs)]
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Default)]
+pub struct Ctx {
+    pub name: &'static str,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Ev {
+    Save,
+}
+
+// Actions return `()` or `Result<(), ActionError>`; this one returns a `bool`.
+fn validate(ctx: &mut Ctx, _event: &Ev) -> bool {
+    !ctx.name.is_empty()
+}
+
+statechart! {
+    name: Editor,
+    context: Ctx,
+    event: Ev,
+    initial: Editing,
+
+    state Editing {
+        on Ev::Save => Saved [action validate];
+    }
+    state Saved {}
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use lit_bit_macro::statechart;
+
+#[derive(Clone, Debug, Default)]
+struct TestContext;
+
+impl TestContext {
+    async fn allows(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TestEvent {
+    Go,
+}
+
+statechart! {
+    name: MyMachine,
+    context: TestContext,
+    event: TestEvent,
+    initial: StateA,
+
+    state StateA {
+        on TestEvent::Go [guard async self.allows()] => StateB;
+    }
+    state StateB {}
+}
+
+fn main() {
+    let mut machine = MyMachine::new(TestContext, &TestEvent::Go).unwrap();
+    let _ = machine.send(&TestEvent::Go); // Error: async guards need `async_send`
+}
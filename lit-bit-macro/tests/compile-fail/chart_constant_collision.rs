@@ -0,0 +1,25 @@
+use lit_bit_macro::statechart;
+
+#[derive(Clone, Debug, Default)]
+struct TestContext;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TestEvent {
+    Go,
+}
+
+statechart! {
+    name: MyMachine,
+    context: TestContext,
+    event: TestEvent,
+    initial: StateA,
+
+    const MYMACHINE_TRANSITIONS: u8 = 2; // Error: the transition table has this name
+
+    state StateA {
+        on TestEvent::Go => StateB;
+    }
+    state StateB {}
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use lit_bit_macro::statechart;
+
+#[derive(Clone, Debug, Default)]
+struct TestContext;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TestEvent {
+    Go,
+    Stop,
+}
+
+statechart! {
+    name: MyMachine,
+    context: TestContext,
+    event: TestEvent,
+    initial: StateA,
+
+    state StateA {
+        on TestEvent::Go [order = 1] => StateB;
+        on TestEvent::Stop [order = 1] => StateB; // Error: rank 1 is taken
+    }
+    state StateB {}
+}
+
+fn main() {}
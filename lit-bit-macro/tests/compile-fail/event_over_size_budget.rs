@@ -0,0 +1,27 @@
+use lit_bit_macro::statechart;
+
+#[derive(Clone, Debug, Default)]
+struct TestContext;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TestEvent {
+    Go,
+    Frame([u8; 20]), // Error: 21 bytes against a budget of 16
+}
+
+statechart! {
+    name: MyMachine,
+    context: TestContext,
+    event: TestEvent,
+    mailbox_capacity: 8,
+    max_event_size: 16,
+    initial: StateA,
+    state StateA {
+        on TestEvent::Go => StateB;
+    }
+    state StateB {
+        on TestEvent::Frame(_) => StateA;
+    }
+}
+
+fn main() {}
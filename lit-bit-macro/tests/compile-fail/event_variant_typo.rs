@@ -0,0 +1,29 @@
+// The generated code is gated on features of the crate using the macro.
+#![allow(unexpected_cfgs)]
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Default)]
+pub struct Ctx;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Ev {
+    Play,
+    Pause,
+}
+
+statechart! {
+    name: Player,
+    context: Ctx,
+    event: Ev,
+    initial: Stopped,
+
+    state Stopped {
+        on Play => Playing;
+    }
+    state Playing {
+        on Pasue => Stopped;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+// The generated code is gated on features of the crate using the macro.
+#![allow(unexpected_cfgs)]
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Default)]
+pub struct Ctx {
+    pub verbose: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Ev {
+    Go,
+}
+
+// Guards get `&Ctx`; this one asks for `&mut Ctx`.
+fn is_verbose(ctx: &mut Ctx, _event: &Ev) -> bool {
+    ctx.verbose
+}
+
+fn log(_ctx: &mut Ctx, _event: &Ev) {}
+
+statechart! {
+    name: Logger,
+    context: Ctx,
+    event: Ev,
+    initial: Idle,
+
+    state Idle {
+        entry [guard is_verbose]: log;
+        on Go => Idle;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+use lit_bit_macro::statechart;
+
+#[derive(Clone, Debug, Default)]
+struct TestContext;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TestEvent {
+    Go,
+}
+
+statechart! {
+    name: MyMachine,
+    context: TestContext,
+    event: TestEvent,
+    initial: Outer,
+
+    state Outer {
+        initial: history Both; // Error: history records one leaf, `Both` has two
+        state Both [parallel] {
+            state Left {
+                initial: A;
+                state A {
+                    on TestEvent::Go => B;
+                }
+                state B {}
+            }
+            state Right {
+                initial: C;
+                state C {}
+            }
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,52 @@
+// The generated code is gated on features of the crate using the macro.
+#![allow(unexpected_cfgs)]
+// The dead-end check is a warning; deny it so this case fails and its output is checked.
+#![deny(deprecated)]
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Default)]
+pub struct Ctx {
+    pub armed: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Ev {
+    Arm,
+    Fire,
+    Abort,
+}
+
+statechart! {
+    name: Launcher,
+    context: Ctx,
+    event: Ev,
+    initial: Idle,
+
+    state Idle {
+        on Arm => Armed;
+    }
+    // Only left if `armed` is ever set: reported.
+    state Armed {
+        on Fire [guard self.armed] => Flight;
+    }
+    state Flight {
+        initial: Climbing;
+        // The enclosing `Flight` can always be aborted: not reported.
+        on Abort => Idle;
+        state Climbing {
+            on Fire [guard self.armed] => Coasting;
+        }
+        state Coasting {
+            on Fire [guard self.armed] => Climbing;
+        }
+    }
+    // Meant to be an end: not reported.
+    state Locked [terminal] {
+        on Arm [guard self.armed] => Idle;
+    }
+    // Final, and without transitions: not reported.
+    state Landed [final] {}
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+// The generated code is gated on features of the crate using the macro.
+#![allow(unexpected_cfgs)]
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Default)]
+pub struct Ctx {
+    pub ticks: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Ev {
+    Tick,
+    Report,
+}
+
+// `action(read)` gets `&Ctx`; this one asks for `&mut Ctx`.
+fn count(ctx: &mut Ctx, _event: &Ev) {
+    ctx.ticks += 1;
+}
+
+// Plain actions get `&mut Ctx`; this one only takes `&Ctx`.
+fn report(ctx: &Ctx, _event: &Ev) {
+    let _ = ctx.ticks;
+}
+
+statechart! {
+    name: Counter,
+    context: Ctx,
+    event: Ev,
+    initial: Idle,
+
+    state Idle {
+        on Ev::Tick => Idle [action(read) count];
+        on Ev::Report => Idle [action report];
+    }
+}
+
+fn main() {}
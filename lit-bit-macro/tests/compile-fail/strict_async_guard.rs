@@ -0,0 +1,31 @@
+use lit_bit_macro::{statechart, statechart_event};
+
+#[derive(Clone, Debug, Default)]
+struct TestContext;
+
+impl TestContext {
+    async fn allows(&self) -> bool {
+        true
+    }
+}
+
+#[statechart_event]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TestEvent {
+    Go,
+}
+
+statechart! {
+    name: MyMachine,
+    context: TestContext,
+    event: TestEvent,
+    strict: true, // Error: `async_send` has no strict form
+    initial: StateA,
+
+    state StateA {
+        on TestEvent::Go [guard async self.allows()] => StateB;
+    }
+    state StateB {}
+}
+
+fn main() {}
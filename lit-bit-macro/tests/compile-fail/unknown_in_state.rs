@@ -0,0 +1,23 @@
+use lit_bit_macro::statechart;
+
+#[derive(Clone, Debug, Default)]
+struct TestContext;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum TestEvent {
+    Go,
+}
+
+statechart! {
+    name: MyMachine,
+    context: TestContext,
+    event: TestEvent,
+    initial: StateA,
+
+    state StateA {
+        on TestEvent::Go [guard in(StateC)] => StateB; // Error: no state `StateC`
+    }
+    state StateB {}
+}
+
+fn main() {}
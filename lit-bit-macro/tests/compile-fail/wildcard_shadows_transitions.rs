@@ -0,0 +1,44 @@
+// The generated code is gated on features of the crate using the macro.
+#![allow(unexpected_cfgs)]
+// The shadowing lint is a warning; deny it so this case fails and its output is checked.
+#![deny(deprecated)]
+
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Default)]
+pub struct Ctx;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Ev {
+    Play,
+    Pause,
+    Stop,
+}
+
+fn never(_ctx: &Ctx, _event: &Ev) -> bool {
+    false
+}
+
+statechart! {
+    name: Player,
+    context: Ctx,
+    event: Ev,
+    initial: Stopped,
+
+    state Stopped {
+        // Guarded, so later transitions can still fire: not reported.
+        on _ [guard never] => Playing;
+        on Play => Playing;
+    }
+    state Playing {
+        on _ => Stopped;
+        on Pause => Paused;
+        on Ev::Stop => Stopped;
+    }
+    state Paused {
+        on Play => Playing;
+        on _ => Stopped;
+    }
+}
+
+fn main() {}
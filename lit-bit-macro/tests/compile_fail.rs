@@ -0,0 +1,22 @@
+//! Compile-fail tests: charts the macro must reject, with the diagnostics it reports.
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile-fail/unknown_target_state.rs");
+    t.compile_fail("tests/compile-fail/event_variant_typo.rs");
+    t.compile_fail("tests/compile-fail/event_variant_typo_did_you_mean.rs");
+    t.compile_fail("tests/compile-fail/events_list_out_of_date.rs");
+    t.compile_fail("tests/compile-fail/guarded_hook_wrong_signature.rs");
+    t.compile_fail("tests/compile-fail/wildcard_shadows_transitions.rs");
+    t.compile_fail("tests/compile-fail/potential_dead_end_state.rs");
+    t.compile_fail("tests/compile-fail/duplicate_transition_order.rs");
+    t.compile_fail("tests/compile-fail/unknown_in_state.rs");
+    t.compile_fail("tests/compile-fail/chart_constant_collision.rs");
+    t.compile_fail("tests/compile-fail/event_over_size_budget.rs");
+    t.compile_fail("tests/compile-fail/async_guard_sync_send.rs");
+    t.compile_fail("tests/compile-fail/strict_async_guard.rs");
+    t.compile_fail("tests/compile-fail/read_action_signature.rs");
+    t.compile_fail("tests/compile-fail/action_outcome_type.rs");
+    t.compile_fail("tests/compile-fail/history_around_parallel_state.rs");
+}
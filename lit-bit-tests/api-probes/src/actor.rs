@@ -0,0 +1,294 @@
+//! The `actor` module and its submodules, in both feature sets; the Tokio-only spawn
+//! helpers are probed in `hosted`.
+
+use lit_bit_core::SendResult;
+use lit_bit_core::actor::supervision::{
+    ChildSnapshot, CircuitState, RestartFactory, RootEscalation, RootEscalationAction,
+    SupervisionSnapshot, SupervisorActor, SupervisorError, SupervisorTimer, TimelineId,
+};
+use lit_bit_core::actor::{
+    BackoffStrategy, Cooperative, CooperativeActor, DefaultPanicAnalyzer, EscalationPolicy,
+    HandleScope, PanicAnalyzer, ReentrancyGuard, RestartIntensity, StatechartActor, Yielder,
+};
+
+use crate::charts::Signal;
+use crate::charts::door::Door;
+use crate::root::{Echo, Root, Tally};
+
+/// The `actor` re-exports name the items of the submodules and of the crate root.
+#[allow(clippy::type_complexity)]
+fn actor_paths_agree(
+    errors: (
+        lit_bit_core::actor::ActorError,
+        lit_bit_core::actor::RestartStrategy,
+        lit_bit_core::actor::SupervisorError,
+        lit_bit_core::actor::SupervisorMessage<u32>,
+        lit_bit_core::actor::backpressure::SendError<Signal>,
+    ),
+    ids: (
+        lit_bit_core::actor::ActorId,
+        lit_bit_core::actor::id::ActorId,
+    ),
+    snapshots: (
+        lit_bit_core::actor::ChildSnapshot<u32>,
+        lit_bit_core::actor::SupervisionSnapshot<u32>,
+        lit_bit_core::actor::CircuitState,
+        lit_bit_core::actor::RootEscalation<u32>,
+        lit_bit_core::actor::RootEscalationAction<u32>,
+    ),
+    supervisor: lit_bit_core::actor::SupervisorActor<u32, 4>,
+    door: lit_bit_core::actor::integration::StatechartActor<Door>,
+) -> (
+    (
+        lit_bit_core::ActorError,
+        lit_bit_core::RestartStrategy,
+        SupervisorError,
+        lit_bit_core::SupervisorMessage<u32>,
+        lit_bit_core::SendError<Signal>,
+    ),
+    (lit_bit_core::actor::ActorId, lit_bit_core::actor::ActorId),
+    (
+        ChildSnapshot<u32>,
+        SupervisionSnapshot<u32>,
+        CircuitState,
+        RootEscalation<u32>,
+        RootEscalationAction<u32>,
+    ),
+    lit_bit_core::SupervisorActor<u32, 4>,
+    StatechartActor<Door>,
+) {
+    (errors, ids, snapshots, supervisor, door)
+}
+
+fn actor_traits_agree<A, B, S>(actor: A, batch: B, supervisor: S) -> (A, B, S)
+where
+    A: lit_bit_core::actor::Actor + lit_bit_core::Actor,
+    B: lit_bit_core::actor::BatchActor + lit_bit_core::BatchActor,
+    S: lit_bit_core::actor::Supervisor + lit_bit_core::Supervisor,
+{
+    (actor, batch, supervisor)
+}
+
+fn actor_traits_are_implemented() -> (Echo, Tally, Root) {
+    actor_traits_agree(Echo, Tally(0), Root)
+}
+
+fn loops_agree<A, Mode>(actor: A) -> A
+where
+    A: lit_bit_core::actor::ActorLoop<Mode> + lit_bit_core::actor::spawn::ActorLoop<Mode>,
+{
+    actor
+}
+
+fn loop_modes() -> (Echo, Tally) {
+    (
+        loops_agree::<_, lit_bit_core::actor::SingleMessage>(Echo),
+        loops_agree::<_, lit_bit_core::actor::Coalesced>(Tally(0)),
+    )
+}
+
+fn loop_modes_agree(
+    modes: (
+        lit_bit_core::actor::spawn::SingleMessage,
+        lit_bit_core::actor::spawn::Coalesced,
+    ),
+) -> (
+    lit_bit_core::actor::SingleMessage,
+    lit_bit_core::actor::Coalesced,
+) {
+    modes
+}
+
+#[cfg(not(feature = "std"))]
+fn address_paths_agree(
+    address: lit_bit_core::actor::address::Address<Signal, 4>,
+) -> lit_bit_core::Address<Signal, 4> {
+    address
+}
+
+#[cfg(feature = "std")]
+fn address_paths_agree(
+    address: lit_bit_core::actor::address::Address<Signal>,
+) -> lit_bit_core::Address<Signal> {
+    address
+}
+
+// --- Supervision ---
+
+struct Uptime;
+
+impl SupervisorTimer for Uptime {
+    fn current_time_ms() -> u64 {
+        0
+    }
+}
+
+fn log_escalation(escalation: &RootEscalation<u32>) {
+    let _ = escalation.snapshot.children.len();
+}
+
+fn supervisor() -> SupervisorActor<u32, 4> {
+    SupervisorActor::<u32, 4>::with_config(lit_bit_core::RestartStrategy::OneForAll, 3, 1_000)
+        .with_circuit_breaker(500)
+        .with_clock::<Uptime>()
+        .with_root_escalation(RootEscalationAction::Custom(log_escalation))
+        .with_panic_analyzer(&DefaultPanicAnalyzer)
+}
+
+fn circuit(supervisor: &SupervisorActor<u32, 4>) -> Option<CircuitState> {
+    supervisor.circuit_state(&1).map(|state| state.at(10))
+}
+
+fn retire(
+    supervisor: &mut SupervisorActor<u32, 4>,
+) -> Result<lit_bit_core::RestartStrategy, SupervisorError> {
+    supervisor.set_strategy(&1, lit_bit_core::RestartStrategy::Temporary)
+}
+
+fn restart_factory(factory: RestartFactory) -> Option<RestartFactory> {
+    Some(factory)
+}
+
+fn timeline_id<C>(id: C) -> C
+where
+    C: TimelineId + lit_bit_core::actor::TimelineId,
+{
+    id
+}
+
+fn clocks_agree<T: SupervisorTimer + lit_bit_core::actor::SupervisorTimer>() -> u64 {
+    <T as lit_bit_core::actor::SupervisorTimer>::current_time_ms()
+}
+
+fn backoff() -> u64 {
+    let intensity = RestartIntensity {
+        backoff_strategy: BackoffStrategy::Linear { base_delay_ms: 10 },
+        ..RestartIntensity::default()
+    };
+    let analyzer = DefaultPanicAnalyzer;
+    let escalate = PanicAnalyzer::should_escalate(&analyzer, &1u32, 2, &intensity);
+    let _ = EscalationPolicy::NotifyParent;
+    analyzer.calculate_backoff_delay(u32::from(escalate), &intensity.backoff_strategy)
+}
+
+fn reentrancy(guard: &'static ReentrancyGuard) -> bool {
+    let scope: Result<HandleScope<'_>, _> = guard.enter();
+    scope.is_ok() && guard.is_handling()
+}
+
+static GUARD: ReentrancyGuard = ReentrancyGuard::new();
+
+fn ids() -> u32 {
+    let id = lit_bit_core::actor::ActorId::new(7);
+    let failure = lit_bit_core::actor::create_controlled_failure("probe", Some(id));
+    let _ = lit_bit_core::actor::panic_handling::create_controlled_failure("probe", None);
+    let _ = failure;
+    id.get()
+}
+
+fn spawn_error(error: lit_bit_core::actor::spawn::SpawnError) -> bool {
+    matches!(error, lit_bit_core::actor::spawn::SpawnError::SpawnFailed)
+}
+
+// --- Statecharts as actors ---
+
+fn report(_door: &Door, _result: SendResult) {}
+
+fn batch_started(_door: &Door, _events: usize) {}
+
+fn batch_ended(
+    _door: &Door,
+    summary: &lit_bit_core::BatchSendSummary<crate::charts::door::DoorStateId, 4>,
+) {
+    let _ = summary.processed();
+}
+
+const OUTPUT: lit_bit_core::actor::integration::OutputFn<Door> = report;
+const BATCH_START: lit_bit_core::actor::integration::BatchStartFn<Door> = batch_started;
+const BATCH_END: lit_bit_core::actor::integration::BatchEndFn<Door, 4> = batch_ended;
+
+fn statechart_actor(door: Door) -> Door {
+    StatechartActor::new(door)
+        .with_output(OUTPUT)
+        .with_batch_observers(BATCH_START, BATCH_END)
+        .into_inner()
+}
+
+// --- Time-sliced actions ---
+
+#[derive(Debug, Clone, Default)]
+struct Eraser {
+    yielder: Yielder,
+    left: u8,
+}
+
+impl Cooperative for Eraser {
+    fn yielder(&mut self) -> &mut Yielder {
+        &mut self.yielder
+    }
+
+    fn resume(&mut self) {
+        self.left = self.left.saturating_sub(1);
+        if self.left > 0 {
+            self.yielder.checkpoint();
+        }
+    }
+}
+
+fn sliced<M>(actor: StatechartActor<M>) -> lit_bit_core::actor::cooperative::CooperativeActor<M>
+where
+    M: lit_bit_core::StateMachine,
+    M::Context: Cooperative,
+{
+    CooperativeActor::new(actor)
+}
+
+fn erase_rest(eraser: &mut Eraser) -> (usize, bool) {
+    let slices = lit_bit_core::actor::cooperative::finish(eraser);
+    (slices, eraser.yielder.is_pending())
+}
+
+// --- `no_std` mailboxes and actor loops ---
+
+#[cfg(not(feature = "std"))]
+mod embedded {
+    use lit_bit_core::actor::backpressure::embedded::{capacity, is_empty, is_full, len};
+    use lit_bit_core::actor::{Inbox, Outbox, create_mailbox};
+
+    use crate::charts::Signal;
+    use crate::root::{Echo, Tally};
+
+    lit_bit_core::define_static_mailbox!(QUEUE, Signal, 4);
+
+    fn mailbox() -> (Outbox<Signal, 4>, Inbox<Signal, 4>) {
+        create_mailbox(&QUEUE)
+    }
+
+    fn backpressure(outbox: &mut Outbox<Signal, 4>, inbox: &mut Inbox<Signal, 4>) -> usize {
+        let _ = lit_bit_core::actor::backpressure::embedded::try_send(outbox, Signal::Go);
+        let queued = len(outbox) + capacity(outbox);
+        let _ = (is_full(outbox), is_empty(inbox));
+        let _ = lit_bit_core::actor::backpressure::embedded::try_recv(inbox);
+        queued + lit_bit_core::actor::backpressure::embedded::inbox_len(inbox)
+    }
+
+    fn isr(outbox: Outbox<Signal, 4>) -> lit_bit_core::actor::isr::IsrSender<Signal, 4> {
+        let sender: lit_bit_core::actor::IsrSender<Signal, 4> =
+            lit_bit_core::actor::isr::IsrSender::new(outbox);
+        sender
+    }
+
+    async fn loops(inbox: Inbox<Signal, 4>, batch_inbox: Inbox<Signal, 4>) {
+        let _ = lit_bit_core::actor::actor_task(Echo, inbox).await;
+        let _ = lit_bit_core::actor::batch_actor_task(Tally(0), batch_inbox).await;
+    }
+
+    fn picked_loops(
+        mailbox: (Outbox<Signal, 4>, Inbox<Signal, 4>),
+        batch_mailbox: (Outbox<Signal, 4>, Inbox<Signal, 4>),
+    ) {
+        let (_echo, _task) = lit_bit_core::actor::spawn_actor(Echo, mailbox.0, mailbox.1);
+        let (_tally, _task) =
+            lit_bit_core::actor::spawn::spawn_actor(Tally(0), batch_mailbox.0, batch_mailbox.1);
+    }
+}
@@ -0,0 +1,219 @@
+//! `statechart!` with each documented header option, and the macros that go with it.
+//!
+//! Every chart lives in its own module, so each header option is probed on its own.
+
+use lit_bit_core::statechart_event;
+
+#[statechart_event]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    Boot,
+    Go,
+    Stop,
+    Fail,
+}
+
+/// The bare header, with guards, actions, entry/exit hooks and retried and fallible actions.
+pub mod door {
+    use super::Signal;
+    use lit_bit_core::{ActionError, statechart};
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Latch {
+        pub opens: u32,
+    }
+
+    fn count(ctx: &mut Latch, _event: &Signal) {
+        ctx.opens += 1;
+    }
+
+    fn unlocked(ctx: &Latch, _event: &Signal) -> bool {
+        ctx.opens < 10
+    }
+
+    fn release(_ctx: &mut Latch, _event: &Signal) -> Result<(), ActionError> {
+        Ok(())
+    }
+
+    statechart! {
+        name: Door,
+        context: Latch,
+        event: Signal,
+        initial: Closed,
+        state Closed [terminal] {
+            entry: count;
+            on Signal::Go [guard unlocked] => Open [action retry(2, delay = 5) release];
+        }
+        state Open {
+            exit: count;
+            on Signal::Stop => Closed [action release];
+        }
+    }
+}
+
+/// `debug_context`, `context_fields` and `invariant`, with parallel and final states.
+pub mod panel {
+    use super::Signal;
+    use lit_bit_core::statechart;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Gauge {
+        pub level: u8,
+    }
+
+    impl Gauge {
+        fn check(&self) -> Result<(), &'static str> {
+            if self.level > 100 {
+                return Err("level above 100");
+            }
+            Ok(())
+        }
+    }
+
+    statechart! {
+        name: Panel,
+        context: Gauge,
+        event: Signal,
+        debug_context: true,
+        context_fields: [level: u8],
+        invariant: self.check,
+        initial: Running,
+        state Running [parallel] {
+            on Signal::Fail => Halted;
+            state Lamp {
+                initial: Off;
+                state Off { on Signal::Go => On; }
+                state On { on Signal::Stop => Off; }
+            }
+            state Pump {
+                initial: Idle;
+                state Idle { on Signal::Go => Busy; }
+                state Busy { on Signal::Stop => Idle; }
+            }
+        }
+        state Halted [final] {}
+    }
+}
+
+/// `interface: true`, linked back in with `link_machine!` below.
+pub mod motor {
+    use super::Signal;
+    use lit_bit_core::statechart;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Drive;
+
+    statechart! {
+        name: Motor,
+        context: Drive,
+        event: Signal,
+        interface: true,
+        initial: Off,
+        state Off { on Signal::Go => Running; }
+        state Running { on Signal::Stop => Off; }
+    }
+}
+
+pub mod linked {
+    lit_bit_core::link_machine!(pub(crate) crate::charts::motor::Motor);
+}
+
+/// A sub-machine state running the `Door` chart.
+pub mod lobby {
+    use super::Signal;
+    use crate::charts::door::Door;
+    use lit_bit_core::statechart;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Visitors;
+
+    statechart! {
+        name: Lobby,
+        context: Visitors,
+        event: Signal,
+        initial: Entering,
+        state Entering = Door => Inside;
+        state Inside [final] {}
+    }
+}
+
+/// `[guard async cond]`, which gives the machine `async_send` instead of `send`.
+pub mod turnstile {
+    use super::Signal;
+    use lit_bit_core::statechart;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Fare;
+
+    impl Fare {
+        async fn paid(&self) -> bool {
+            true
+        }
+    }
+
+    statechart! {
+        name: Turnstile,
+        context: Fare,
+        event: Signal,
+        initial: Closed,
+        state Closed [terminal] { on Signal::Go [guard async self.paid()] => Open; }
+        state Open { on Signal::Stop => Closed; }
+    }
+
+    pub fn pass(
+        turnstile: &mut Turnstile,
+    ) -> impl core::future::Future<Output = lit_bit_core::SendResult> + '_ {
+        turnstile.async_send(&Signal::Go)
+    }
+}
+
+/// `strict: true,`: `send` returns the chart's own error enum.
+pub mod valve {
+    use super::{Signal, SignalKind};
+    use lit_bit_core::statechart;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Pipe;
+
+    statechart! {
+        name: Valve,
+        context: Pipe,
+        event: Signal,
+        strict: true,
+        initial: Shut,
+        state Shut { on Signal::Go => Flowing; }
+        state Flowing { on Signal::Stop => Shut; }
+    }
+
+    pub fn open(valve: &mut Valve) -> Option<SignalKind> {
+        match valve.send(&Signal::Go) {
+            Ok(_) => None,
+            Err(ValveError::Unhandled { event_kind, .. }) => Some(event_kind),
+            Err(_) => Some(SignalKind::Fail),
+        }
+    }
+}
+
+fn inline_machine() -> Result<impl lit_bit_core::StateMachine, lit_bit_core::ProcessingError> {
+    lit_bit_core::statechart_inline! {
+        event: Signal = Signal::Boot,
+        initial: Waiting,
+        state Waiting { on Signal::Go => Done; }
+        state Done {}
+    }
+}
+
+/// `statechart_test!` over the `Door` chart.
+mod door_scenario {
+    use super::Signal;
+    use crate::charts::door::{Door, DoorStateId};
+
+    lit_bit_core::statechart_test!(Door);
+
+    fn walk() -> lit_bit_core::scenario::Scenario<Door> {
+        scenario(&Signal::Boot)
+            .send(Signal::Go)
+            .expect_result(lit_bit_core::SendResult::Transitioned)
+            .expect_state(DoorStateId::Open)
+    }
+}
@@ -0,0 +1,383 @@
+//! Items only built with `std` and `async-tokio`: the Tokio actor runtime, timers,
+//! config-driven instantiation, snapshot migration, replay and the outbox.
+//!
+//! Functions are probed by naming them (instantiated where generic) rather than calling
+//! them, so the probes need no Tokio or serde dependency of their own.
+
+use core::convert::Infallible;
+use core::ops::ControlFlow;
+use core::pin::Pin;
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+use lit_bit_core::actor::address::{ActorCell, SpawnChildError};
+use lit_bit_core::actor::spawn_context::{ParentActor, SpawnContext};
+use lit_bit_core::actor::{
+    ActorId, AsyncActor, AsyncBatchActor, StatechartActor, StatechartMessage, StatechartQuery,
+    WithQueries,
+};
+use lit_bit_core::config::{ConfigError, MachineConfig};
+use lit_bit_core::outbox::{
+    Checkpoint, Commit, Delivery, Drain, EffectId, Effects, EmitsEffects, FileOutboxStore,
+    FileStoreError, MemoryOutboxStore, Outbox, OutboxRelay, OutboxStore, Publisher, RelayError,
+};
+use lit_bit_core::replay::{EventLog, EventRecorder, EventReplayer, RecordedEvent, ReplayClock};
+use lit_bit_core::timer::{ArmedTimer, ArmedTimers, TokioTimer};
+use lit_bit_core::upgrade::{
+    DefinitionDiff, ErasedDefinition, ErasedState, ErasedTransition, MigrationError,
+    MigrationPolicy, StateRename, StateSnapshot, TransitionChange,
+};
+use lit_bit_core::{
+    Actor, ActorError, Address, Inbox, MAX_ACTIVE_REGIONS, SendError, SendResult, SupervisorActor,
+    TimerToken,
+};
+
+use crate::charts::Signal;
+use crate::charts::door::Door;
+use crate::root::{Echo, LIGHT, Light, LightRuntime, Tally};
+
+// --- Spawning on Tokio ---
+
+fn spawn_helpers() {
+    let _ = lit_bit_core::actor::spawn_actor::<Echo, _>;
+    let _ = lit_bit_core::actor::spawn::spawn_actor::<Tally, _>;
+    let _ = lit_bit_core::actor::spawn_actor_tokio::<Echo>;
+    let _ = lit_bit_core::actor::spawn::spawn_actor_tokio::<Echo>;
+    let _ = lit_bit_core::actor::spawn_batch_actor_tokio::<Tally>;
+    let _ = lit_bit_core::actor::spawn::spawn_batch_actor_tokio::<Tally>;
+    let _ = lit_bit_core::actor::spawn_batch_actor_tokio_adaptive::<Tally>;
+    let _ = lit_bit_core::actor::spawn::spawn_batch_actor_tokio_adaptive::<Tally>;
+    let _ = lit_bit_core::actor::spawn_statechart_tokio::<Door, 4>;
+    let _ = lit_bit_core::actor::spawn::spawn_statechart_tokio::<Door, 4>;
+    let _ = lit_bit_core::actor::spawn_supervised_actor_tokio::<Echo, u32, 4>;
+    let _ = lit_bit_core::actor::spawn::spawn_supervised_actor_tokio::<Echo, u32, 4>;
+    let _ = lit_bit_core::actor::spawn_supervised_batch_actor_tokio::<Tally, u32, 4>;
+    let _ = lit_bit_core::actor::spawn::spawn_supervised_batch_actor_tokio::<Tally, u32, 4>;
+    let _ =
+        lit_bit_core::actor::spawn::spawn_supervised_actor_with_panic_handling::<Echo, ActorId, 4>;
+    let _ = lit_bit_core::actor::spawn::panic_safe_actor_task::<Echo>;
+}
+
+fn adaptive_batching(metrics: &lit_bit_core::actor::ActorMetrics) -> usize {
+    let _: u64 = metrics.batches() + metrics.messages();
+    let bounds: lit_bit_core::actor::AdaptiveBatching =
+        lit_bit_core::actor::adaptive_batch::AdaptiveBatching::new(1, 64);
+    let _: &lit_bit_core::actor::adaptive_batch::ActorMetrics = metrics;
+    bounds.min() + bounds.max() + metrics.batch_limit() + metrics.last_batch_size()
+}
+
+fn actor_tasks() {
+    let _ = lit_bit_core::actor_task::<Echo>;
+    let _ = lit_bit_core::actor::actor_task::<Echo>;
+    let _ = lit_bit_core::actor::batch_actor_task::<Tally>;
+}
+
+fn panic_capture() {
+    let _ = lit_bit_core::actor::capture_panic_info;
+    let _ = lit_bit_core::actor::capture_panic_info_with_id;
+    let _ = lit_bit_core::actor::capture_panic_info_from_payload;
+    let _ = lit_bit_core::actor::capture_panic_info_from_payload_with_id;
+    let _ = lit_bit_core::actor::panic_handling::capture_panic_info;
+    let _ = lit_bit_core::actor::panic_handling::capture_panic_info_with_id;
+    let _ = lit_bit_core::actor::panic_handling::capture_panic_info_from_payload;
+    let _ = lit_bit_core::actor::panic_handling::capture_panic_info_from_payload_with_id;
+}
+
+fn actor_names(id: ActorId) -> Option<&'static str> {
+    lit_bit_core::actor::id::registry::register(id, "probe");
+    let name = lit_bit_core::actor::id::registry::lookup(id);
+    let _ = lit_bit_core::actor::id::registry::unregister(id);
+    name
+}
+
+async fn backpressure(
+    outbox: &lit_bit_core::Outbox<Signal>,
+    inbox: &mut Inbox<Signal>,
+) -> Option<Signal> {
+    let _ = lit_bit_core::actor::backpressure::std_async::send(outbox, Signal::Go).await;
+    let _ = lit_bit_core::actor::backpressure::std_async::try_send(outbox, Signal::Stop);
+    let _ = lit_bit_core::actor::backpressure::std_async::capacity(outbox);
+    let _ = lit_bit_core::actor::backpressure::std_async::try_recv(inbox);
+    lit_bit_core::actor::backpressure::std_async::recv(inbox).await
+}
+
+fn children(address: &Address<Signal>) -> Result<usize, SpawnChildError> {
+    let (child, _inbox) = address.spawn_child(4)?;
+    let cell: std::sync::Arc<ActorCell<Signal>> = child.cell();
+    Ok(std::sync::Arc::strong_count(&cell))
+}
+
+fn stop(
+    address: &Address<Signal>,
+    supervisor: &mut SupervisorActor<u32, 4>,
+) -> Result<(), lit_bit_core::SupervisorError> {
+    address.stop();
+    supervisor.set_child_address(&1, address)
+}
+
+fn send_blocking(address: &Address<Signal>) -> Result<(), SendError<Signal>> {
+    address.send_blocking(Signal::Go)
+}
+
+fn sink(address: Address<Signal>) -> lit_bit_core::actor::sink::AddressSink<Signal> {
+    address.into_sink()
+}
+
+fn statechart_actor(door: Door) -> StatechartActor<Door> {
+    StatechartActor::new(door)
+}
+
+fn queryable(door: Door) -> StatechartActor<Door, MAX_ACTIVE_REGIONS, WithQueries> {
+    StatechartActor::new(door).with_queries()
+}
+
+async fn query(address: &Address<StatechartMessage<Door>>) -> Option<usize> {
+    let _ = address.context_snapshot().await;
+    let _ = address
+        .ask(|reply| StatechartMessage::Query(StatechartQuery::Context(reply)))
+        .await;
+    address.configuration().await.map(|active| active.len())
+}
+
+// --- Parent actors ---
+
+struct Lobby;
+
+impl Actor for Lobby {
+    type Message = Signal;
+    type Future<'a> = core::future::Ready<()>;
+
+    fn handle(&mut self, _msg: Signal) -> Self::Future<'_> {
+        core::future::ready(())
+    }
+}
+
+impl ParentActor for Lobby {
+    fn handle_in(&mut self, _msg: Signal, spawn: &mut SpawnContext) -> Self::Future<'_> {
+        let _ = spawn.spawn_child(Echo, 4);
+        core::future::ready(())
+    }
+
+    fn on_child_exit(
+        &mut self,
+        _child: ActorId,
+        _result: Result<(), ActorError>,
+        spawn: &mut SpawnContext,
+    ) {
+        let _ = spawn.child_count();
+    }
+}
+
+fn parent_paths_agree<P>(parent: P, spawn: lit_bit_core::actor::SpawnContext) -> (P, SpawnContext)
+where
+    P: lit_bit_core::actor::ParentActor + lit_bit_core::actor::spawn_context::ParentActor,
+{
+    (parent, spawn)
+}
+
+fn parents() -> SpawnContext {
+    let _ = lit_bit_core::actor::parent_actor_task::<Lobby>;
+    let _ = lit_bit_core::actor::spawn_context::parent_actor_task::<Lobby>;
+    let _ = lit_bit_core::actor::spawn_parent_actor_tokio::<Lobby>;
+    let _ = lit_bit_core::actor::spawn_context::spawn_parent_actor_tokio::<Lobby>;
+    let _ = lit_bit_core::actor::spawn_context::spawn_parent_actor_tokio_with::<Lobby>;
+    parent_paths_agree(Lobby, SpawnContext::with_supervisor(SupervisorActor::new())).1
+}
+
+struct Boxed;
+
+impl AsyncActor for Boxed {
+    type Message = Signal;
+
+    fn handle(
+        &mut self,
+        _msg: Signal,
+    ) -> Pin<Box<dyn core::future::Future<Output = ()> + Send + '_>> {
+        Box::pin(core::future::ready(()))
+    }
+}
+
+struct BoxedBatch;
+
+impl AsyncBatchActor for BoxedBatch {
+    type Message = Signal;
+
+    fn handle_batch(
+        &mut self,
+        _messages: &[Signal],
+    ) -> Pin<Box<dyn core::future::Future<Output = ()> + Send + '_>> {
+        Box::pin(core::future::ready(()))
+    }
+}
+
+// --- Timers ---
+
+fn timers_agree<T>() -> (
+    lit_bit_core::timer::TimerToken,
+    <T as lit_bit_core::TimerService>::SleepFuture,
+)
+where
+    T: lit_bit_core::TimerService + lit_bit_core::timer::TimerService,
+{
+    let token: TimerToken = <T as lit_bit_core::timer::TimerService>::arm(Duration::from_millis(5));
+    (
+        token,
+        <T as lit_bit_core::TimerService>::sleep(token.duration()),
+    )
+}
+
+fn active_timers() -> (lit_bit_core::Timer, lit_bit_core::timer::Timer) {
+    let _ = timers_agree::<TokioTimer>;
+    (TokioTimer, TokioTimer)
+}
+
+fn armed(token: TimerToken) -> Option<ArmedTimer<Light>> {
+    let mut timers = ArmedTimers::<Light, 4>::new();
+    let _ = timers.track(Light::On, 0, token);
+    timers.fire(token)
+}
+
+struct StoppedClock;
+
+impl lit_bit_core::MonotonicClock for StoppedClock {
+    fn now(&self) -> lit_bit_core::Ticks {
+        0
+    }
+}
+
+fn time_left(light: &mut LightRuntime, timers: &mut ArmedTimers<Light, 4>) -> Duration {
+    static CLOCK: StoppedClock = StoppedClock;
+    light.set_timer_clock(&CLOCK, Duration::from_millis(1));
+    timers.set_clock(&CLOCK, Duration::from_micros(10));
+    let from_table = timers
+        .iter()
+        .map(|timer: &ArmedTimer<Light>| (timer.armed_at, timers.remaining(timer)))
+        .map(|(_armed_at, remaining): (Option<lit_bit_core::Ticks>, Duration)| remaining);
+    light
+        .armed_timers()
+        .map(|(_state, remaining): (Light, Duration)| remaining)
+        .chain(from_table)
+        .sum()
+}
+
+// --- Config-driven instantiation ---
+
+fn round_trips<T>(value: &T) -> bool
+where
+    T: lit_bit_core::config::serde::Serialize + lit_bit_core::config::serde::de::DeserializeOwned,
+{
+    lit_bit_core::config::serde_json::to_value(value).is_ok()
+}
+
+fn configured(config: &MachineConfig) -> Result<Option<Light>, ConfigError> {
+    let _: lit_bit_core::config::serde_json::Value = config.context.clone();
+    let _ = lit_bit_core::config::serde_json::to_string(config);
+    let _ = round_trips::<MachineConfig>;
+    config.resolve_initial(
+        &LIGHT,
+        |name| (name == "On").then_some(Light::On),
+        |_| "Off",
+    )
+}
+
+// --- Upgrades ---
+
+fn light_name(state: Light) -> &'static str {
+    match state {
+        Light::Off => "Off",
+        Light::On => "On",
+    }
+}
+
+fn migrate() -> Result<StateSnapshot, MigrationError> {
+    let old = ErasedDefinition::from_definition(&LIGHT, light_name);
+    let state: Option<&ErasedState> = old.state("Off");
+    let _: Option<&ErasedTransition> = old.transitions().next();
+    let _ = state.map(ErasedState::name);
+    let new = ErasedDefinition::new(Vec::new(), Vec::new(), "Off");
+
+    let diff: DefinitionDiff = lit_bit_core::upgrade::diff_definitions(&old, &new);
+    let _: Option<&StateRename> = diff.renamed.first();
+    if let Some(TransitionChange::Changed { old, new }) = diff.transitions.first() {
+        let _ = (old, new);
+    }
+    lit_bit_core::upgrade::migrate_snapshot(
+        &StateSnapshot::new(["On"]),
+        &diff,
+        &MigrationPolicy::Parent,
+    )
+}
+
+// --- Recording and replay ---
+
+static REPLAY_CLOCK: ReplayClock = ReplayClock::new();
+
+fn record_and_replay(light: &mut LightRuntime) -> usize {
+    let mut recorder = EventRecorder::<Signal>::new(&REPLAY_CLOCK);
+    let _ = recorder.send(light, &Signal::Go);
+    let log: EventLog<Signal> = recorder.into_log();
+    let _: Option<&RecordedEvent<Signal>> = log.events.first();
+
+    let replayer = EventReplayer::new(log).with_clock(&REPLAY_CLOCK);
+    let summary = replayer.replay_with(light, |_, _, _| ControlFlow::Break(()));
+    summary.processed()
+}
+
+// --- Completion ---
+
+fn completion(light: &mut LightRuntime, door: &mut Door) -> bool {
+    let runtime: lit_bit_core::runtime::Completion = light.completed();
+    let machine: lit_bit_core::Completion = door.completed();
+    runtime.is_complete() && machine.is_complete()
+}
+
+// --- Outbox ---
+
+struct Wire;
+
+impl Publisher<u32> for Wire {
+    type Error = core::convert::Infallible;
+
+    async fn publish(&mut self, delivery: &Delivery<u32>) -> Result<(), Self::Error> {
+        let _: (EffectId, u32, bool) = (delivery.id, delivery.effect, delivery.redelivered);
+        Ok(())
+    }
+}
+
+fn effects(effects: &mut Effects<u32>) -> usize {
+    effects.emit(1);
+    Effects::<u32>::new().emitted().len() + effects.emitted().len()
+}
+
+fn outbox_send<M>(machine: &mut M, event: &M::Event) -> Result<SendResult, Infallible>
+where
+    M: lit_bit_core::StateMachine,
+    M::State: lit_bit_core::UserPath,
+    M::Context: EmitsEffects<Effect = u32>,
+{
+    let outbox = Outbox::new(MemoryOutboxStore::<M::Context, u32>::new());
+    let _: Option<Checkpoint<M::Context>> = outbox.checkpoint();
+    let _: &Arc<Mutex<MemoryOutboxStore<M::Context, u32>>> = outbox.store();
+    let _: OutboxRelay<_, Wire> = outbox.relay(Wire);
+    let _ = OutboxRelay::new(Arc::clone(outbox.store()), Wire).publisher();
+    outbox.send(machine, event)
+}
+
+fn outbox_stores(commit: Commit<u32, u32>) -> Result<usize, FileStoreError> {
+    let mut store = FileOutboxStore::<u32, u32>::open("outbox.log")?;
+    let _: &std::path::Path = store.path();
+    store.compact()?;
+    let _: core::ops::Range<u64> = store.append(commit)?;
+    if let Some(delivery) = store.next_delivery()? {
+        store.acknowledge(delivery.id)?;
+    }
+    let _ = MemoryOutboxStore::<u32, u32>::default().checkpoint();
+    let _ = lit_bit_core::outbox::COMPACT_AFTER;
+    let _: fn(FileStoreError) -> RelayError<FileStoreError, Infallible> = RelayError::Store;
+    let _ = RelayError::<FileStoreError, EffectId>::Publish {
+        id: EffectId(0),
+        error: EffectId(1),
+    };
+    let _ = (Drain, FileStoreError::Io(std::io::ErrorKind::Other.into()));
+    Ok(store.pending())
+}
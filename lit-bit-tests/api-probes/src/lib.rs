@@ -0,0 +1,25 @@
+//! Compile-only probes of `lit-bit-core`'s public API.
+//!
+//! Nothing in this crate is meant to run. Each probe names a public item the way a user
+//! would, so building the crate with the toolchain pinned in `rust-version` proves the
+//! whole surface still compiles there. `cargo xtask msrv` builds it for both feature sets:
+//!
+//! - without features, as a `no_std` crate on top of `lit-bit-core` without features;
+//! - with `std`, on top of `lit-bit-core` with `std` and `async-tokio`.
+//!
+//! The same command compares the output of `cargo public-api` with the paths spelled out
+//! here and fails for any public item no probe mentions. Probes therefore write every path
+//! in full, starting at `lit_bit_core::`, either in a `use` or inline; an item reachable
+//! through several paths (a module item and its crate-root re-export) needs a probe for
+//! each.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// Probes are compiled, never called.
+#![allow(dead_code)]
+
+mod actor;
+mod charts;
+#[cfg(feature = "std")]
+mod hosted;
+mod root;
+mod runtime;
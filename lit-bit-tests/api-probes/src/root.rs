@@ -0,0 +1,299 @@
+//! Everything `lit-bit-core` re-exports from its crate root.
+
+use lit_bit_core::{
+    Acceptance, ActionError, ActionFn, ActionOutcome, AsyncGuardVerdicts, BatchSendSummary,
+    ChartConstant, ConfigDiff, DefaultContext, DenyTargets, EntryExitActionFn, EventFilterFn,
+    EventKind, FallibleAction, FallibleActionFn, GuardFn, InvariantFn, MAX_ACTIVE_REGIONS,
+    MAX_ASYNC_GUARD_VERDICTS, MachineDefinition, MachineInterface, MonotonicClock,
+    PendingAsyncGuard, PolicyHost, ProcessingError, RaisedEventFn, ReadActionFn, RetryObserverFn,
+    RetryReport, Runtime, SendResult, StateGuardFn, StateMachine, StateMachineExt, StateNode,
+    Ticks, Transition, TransitionEventKinds, TransitionPolicy,
+};
+use lit_bit_core::{
+    Actor, ActorError, Address, BatchActor, RestartStrategy, SendError, StopReason, Supervisor,
+    SupervisorActor, SupervisorError, SupervisorMessage,
+};
+
+use crate::charts::linked::{Motor, MotorStateId, motor};
+use crate::charts::{Signal, SignalKind};
+
+// --- A hand-built machine definition ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Light {
+    Off,
+    On,
+}
+
+fn switch(_ctx: &mut DefaultContext, _event: &Signal) {}
+
+fn look(_ctx: &DefaultContext, _event: &Signal) {}
+
+fn always(_ctx: &DefaultContext, _event: &Signal) -> bool {
+    true
+}
+
+fn while_off(_ctx: &DefaultContext, _event: &Signal, is_active: &dyn Fn(Light) -> bool) -> bool {
+    is_active(Light::Off)
+}
+
+fn is_go(event: &Signal) -> bool {
+    matches!(event, Signal::Go)
+}
+
+fn try_switch(_ctx: &mut DefaultContext, _event: &Signal) -> Result<(), ActionError> {
+    Err(ActionError::new("relay stuck"))
+}
+
+fn nothing_raised(_ctx: &mut DefaultContext) -> Option<Signal> {
+    None
+}
+
+fn always_valid(_ctx: &DefaultContext) -> Result<(), &'static str> {
+    Ok(())
+}
+
+fn observe_retry(report: &RetryReport<Light>) {
+    let _ = report.succeeded();
+}
+
+const ENTER: EntryExitActionFn<DefaultContext, Signal> = switch;
+const ACTION: ActionFn<DefaultContext, Signal> = switch;
+const GUARD: GuardFn<DefaultContext, Signal> = always;
+const STATE_GUARD: StateGuardFn<Light, DefaultContext, Signal> = while_off;
+const FILTER: EventFilterFn<Signal> = is_go;
+const FALLIBLE: FallibleActionFn<DefaultContext, Signal> = try_switch;
+const READ: ReadActionFn<DefaultContext, Signal> = look;
+const RAISED: RaisedEventFn<DefaultContext, Signal> = nothing_raised;
+const INVARIANT: InvariantFn<DefaultContext> = always_valid;
+const OBSERVER: RetryObserverFn<Light> = observe_retry;
+
+static STATES: [StateNode<Light, DefaultContext, Signal>; 2] = [
+    StateNode {
+        id: Light::Off,
+        parent: None,
+        initial_child: None,
+        entry_action: None,
+        exit_action: None,
+        is_parallel: false,
+        is_final: false,
+    },
+    StateNode {
+        id: Light::On,
+        parent: None,
+        initial_child: None,
+        entry_action: Some(ENTER),
+        exit_action: Some(ENTER),
+        is_parallel: false,
+        is_final: false,
+    },
+];
+
+static TRANSITIONS: [Transition<Light, Signal, DefaultContext>; 1] = [Transition {
+    from_state: Light::Off,
+    to_state: Light::On,
+    action: Some(ACTION),
+    guard: Some(GUARD),
+    match_fn: Some(is_go),
+    state_guard: Some(STATE_GUARD),
+    read_action: Some(READ),
+    fallible_action: Some(FallibleAction {
+        action: FALLIBLE,
+        retries: 1,
+        delay: 0,
+    }),
+    async_guard: Some(0),
+}];
+
+static CONSTANTS: [ChartConstant; 1] = [ChartConstant {
+    name: "BLINK_MS",
+    ty: "u32",
+    value: "500",
+}];
+
+static EVENT_KINDS: [TransitionEventKinds; 1] = [TransitionEventKinds {
+    variants: &[("Go", false)],
+    any: false,
+}];
+
+pub static LIGHT: MachineDefinition<Light, Signal, DefaultContext> =
+    MachineDefinition::new(&STATES, &TRANSITIONS, Light::Off)
+        .with_event_kinds(&EVENT_KINDS)
+        .with_invariant(INVARIANT)
+        .with_entry_action(ACTION)
+        .with_exit_action(ACTION)
+        .with_constants(&CONSTANTS);
+
+pub type LightRuntime = Runtime<Light, Signal, DefaultContext, 2, MAX_ACTIVE_REGIONS, 8>;
+
+struct FixedClock(Ticks);
+
+impl MonotonicClock for FixedClock {
+    fn now(&self) -> Ticks {
+        self.0
+    }
+}
+
+static CLOCK: FixedClock = FixedClock(0);
+
+struct Curfew;
+
+impl TransitionPolicy<Light, Signal> for Curfew {
+    fn allow(&self, _from: &[Light], to: Light, _event: &Signal) -> bool {
+        to != Light::On
+    }
+}
+
+static NO_LIGHT: DenyTargets<Light> = DenyTargets(&[Light::On]);
+static CURFEW: Curfew = Curfew;
+
+fn drive_runtime() -> Result<usize, ProcessingError> {
+    let mut light =
+        LightRuntime::with_event_filter(&LIGHT, DefaultContext::default(), &Signal::Boot, FILTER)?;
+    light.set_retry_clock(&CLOCK);
+    light.set_retry_observer(OBSERVER);
+    light.set_raised_event_source(RAISED);
+    light.set_policy(Some(&NO_LIGHT));
+    PolicyHost::set_policy(&mut light, Some(&CURFEW));
+
+    let mut verdicts = AsyncGuardVerdicts::new();
+    while let Some(PendingAsyncGuard { transition, slot }) =
+        light.next_async_guard(&Signal::Go, &verdicts)
+    {
+        verdicts.record(transition, slot == 0)?;
+    }
+    let decided = verdicts.get(0).is_some() && !verdicts.is_empty();
+    let _ = light.send_with_async_verdicts(&Signal::Go, verdicts);
+
+    match light.send(&Signal::Go) {
+        SendResult::Error(error) => return Err(error),
+        SendResult::Transitioned
+        | SendResult::NoMatch
+        | SendResult::BudgetExhausted { .. }
+        | SendResult::InvariantViolated(_)
+        | SendResult::PolicyRejected
+        | SendResult::OscillationDetected => {}
+    }
+    let _ = light.send_with_budget(&Signal::Stop, 10, &CLOCK);
+    let conditional = light
+        .accepted_event_kinds::<SignalKind>()
+        .filter(|(_, acceptance)| *acceptance == Acceptance::Conditional)
+        .count();
+
+    let summary: BatchSendSummary<Light, MAX_ACTIVE_REGIONS> = light.send_batch(&[Signal::Stop]);
+    let _ = light.send_if(&Signal::Stop, |_| conditional > 0);
+    let _ = light.send_while([Signal::Stop], |result| *result == SendResult::Transitioned);
+    let diff = ConfigDiff::<Light, 2>::between(&[light.active_state()], &summary.final_state);
+    Ok(conditional
+        + diff.entered.len()
+        + SignalKind::ALL.len()
+        + usize::from(decided) * MAX_ASYNC_GUARD_VERDICTS)
+}
+
+fn start_deferred() -> Result<bool, ProcessingError> {
+    let mut light = LightRuntime::new_deferred(&LIGHT, DefaultContext::default())?;
+    if light.send(&Signal::Go) != SendResult::Error(ProcessingError::NotStarted) {
+        return Ok(false);
+    }
+    light.start(&Signal::Boot)?;
+    Ok(light.is_started())
+}
+
+fn interface_hash() -> (u64, usize) {
+    (
+        <Motor as MachineInterface>::DEFINITION_HASH,
+        motor::HANDLED_KINDS.len() + usize::from(MotorStateId::Off.is_final()),
+    )
+}
+
+fn kind_names<K: EventKind>() -> impl Iterator<Item = &'static str> {
+    K::ALL.iter().map(|kind| kind.name())
+}
+
+fn verdict<O: ActionOutcome>(outcome: O) -> (bool, Result<(), ActionError>) {
+    (O::FALLIBLE, outcome.into_result())
+}
+
+// --- Actors and supervision ---
+
+pub struct Echo;
+
+impl Actor for Echo {
+    type Message = Signal;
+    type Future<'a> = core::future::Ready<()>;
+
+    fn handle(&mut self, _msg: Signal) -> Self::Future<'_> {
+        core::future::ready(())
+    }
+
+    fn on_start(&mut self) -> Result<(), ActorError> {
+        Err(ActorError::StartupFailure)
+    }
+
+    fn on_stop_with_reason(self, reason: StopReason) -> Result<(), ActorError> {
+        match reason {
+            StopReason::SendersDropped | StopReason::StopRequested => self.on_stop(),
+            StopReason::SupervisorRestart | StopReason::Escalated => Ok(()),
+        }
+    }
+}
+
+pub struct Tally(pub usize);
+
+impl BatchActor for Tally {
+    type Message = Signal;
+    type Future<'a> = core::future::Ready<()>;
+
+    fn handle_batch(&mut self, messages: &[Signal]) -> Self::Future<'_> {
+        self.0 += messages.len();
+        core::future::ready(())
+    }
+}
+
+pub struct Root;
+
+impl Supervisor for Root {
+    type ChildId = u32;
+
+    fn on_child_failure(&mut self, _child_id: u32) -> RestartStrategy {
+        RestartStrategy::OneForOne
+    }
+}
+
+fn supervise() -> Result<(), SupervisorError> {
+    let mut supervisor = SupervisorActor::<u32, 4>::new();
+    supervisor.add_child(1, Some(RestartStrategy::RestForOne))?;
+    let _ = SupervisorMessage::<u32>::StartChild { id: 2 };
+    Ok(())
+}
+
+fn full_mailbox(error: SendError<Signal>) -> Option<Signal> {
+    match error {
+        SendError::Full(signal) | SendError::WouldBlockInAsyncContext(signal) => Some(signal),
+        SendError::Closed(_) => None,
+    }
+}
+
+#[cfg(not(feature = "std"))]
+fn no_std_mailboxes() -> Address<Signal, 4> {
+    use lit_bit_core::{Inbox, Outbox, create_mailbox};
+
+    lit_bit_core::define_static_mailbox!(QUEUE, Signal, 4);
+    let (outbox, _inbox): (Outbox<Signal, 4>, Inbox<Signal, 4>) = create_mailbox(&QUEUE);
+    let _ = lit_bit_core::static_mailbox!(ISR_MAILBOX: Signal, 4; isr);
+    Address::from_producer(outbox)
+}
+
+#[cfg(feature = "std")]
+async fn tokio_mailboxes() -> Result<(), ActorError> {
+    use lit_bit_core::{Inbox, Outbox, create_mailbox};
+
+    let (outbox, inbox): (Outbox<Signal>, Inbox<Signal>) = create_mailbox(8);
+    let address: Address<Signal> = Address::from_tokio_sender(outbox);
+    let _ = address.try_send(Signal::Go);
+    lit_bit_core::actor_task(Echo, inbox).await
+}
+
+fn trace_probe() {
+    lit_bit_core::trace!("light probe");
+}
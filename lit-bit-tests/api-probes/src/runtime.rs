@@ -0,0 +1,191 @@
+//! The `runtime` and `interface` modules: the paths behind the crate-root re-exports, and
+//! the items only reachable through a module.
+
+use lit_bit_core::DefaultContext;
+
+use crate::charts::Signal;
+use crate::root::Light;
+
+type Summary = lit_bit_core::runtime::BatchSendSummary<Light, 4>;
+type Diff = lit_bit_core::runtime::ConfigDiff<Light, 2>;
+type Definition = lit_bit_core::runtime::MachineDefinition<Light, Signal, DefaultContext>;
+type Node = lit_bit_core::runtime::StateNode<Light, DefaultContext, Signal>;
+type Edge = lit_bit_core::runtime::Transition<Light, Signal, DefaultContext>;
+type Retried = lit_bit_core::runtime::FallibleAction<DefaultContext, Signal>;
+type Report = lit_bit_core::runtime::RetryReport<Light>;
+type Deny = lit_bit_core::runtime::DenyTargets<Light>;
+type Full = lit_bit_core::runtime::Runtime<Light, Signal, DefaultContext, 2, 4, 8>;
+type Short = lit_bit_core::runtime::DefaultRuntime<Light, Signal, DefaultContext, 2, 8>;
+
+/// Each module path names the same type as its crate-root re-export.
+#[allow(clippy::type_complexity)]
+fn types_are_re_exported(
+    values: (
+        lit_bit_core::runtime::SendResult,
+        lit_bit_core::runtime::ProcessingError,
+        lit_bit_core::runtime::Acceptance,
+        lit_bit_core::runtime::ActionError,
+        lit_bit_core::runtime::DefaultContext,
+        lit_bit_core::runtime::TransitionEventKinds,
+        lit_bit_core::runtime::ChartConstant,
+        lit_bit_core::runtime::Ticks,
+    ),
+    tables: (
+        Summary,
+        Diff,
+        &'static Definition,
+        Node,
+        Edge,
+        Retried,
+        Report,
+        Deny,
+        lit_bit_core::runtime::Configuration<Light, 4>,
+        lit_bit_core::runtime::StateSet<2>,
+        lit_bit_core::runtime::DenyTargetSet<2>,
+    ),
+) -> (
+    (
+        lit_bit_core::SendResult,
+        lit_bit_core::ProcessingError,
+        lit_bit_core::Acceptance,
+        lit_bit_core::ActionError,
+        lit_bit_core::DefaultContext,
+        lit_bit_core::TransitionEventKinds,
+        lit_bit_core::ChartConstant,
+        lit_bit_core::Ticks,
+    ),
+    (
+        lit_bit_core::BatchSendSummary<Light, 4>,
+        lit_bit_core::ConfigDiff<Light, 2>,
+        &'static lit_bit_core::MachineDefinition<Light, Signal, DefaultContext>,
+        lit_bit_core::StateNode<Light, DefaultContext, Signal>,
+        lit_bit_core::Transition<Light, Signal, DefaultContext>,
+        lit_bit_core::FallibleAction<DefaultContext, Signal>,
+        lit_bit_core::RetryReport<Light>,
+        lit_bit_core::DenyTargets<Light>,
+        lit_bit_core::Configuration<Light, 4>,
+        lit_bit_core::StateSet<2>,
+        lit_bit_core::DenyTargetSet<2>,
+    ),
+) {
+    (values, tables)
+}
+
+fn runtimes_are_re_exported(
+    full: Full,
+) -> lit_bit_core::Runtime<Light, Signal, DefaultContext, 2, 4, 8> {
+    let short: Short = full;
+    short
+}
+
+/// The function-pointer aliases agree as well.
+#[allow(clippy::type_complexity)]
+fn callbacks_are_re_exported(
+    callbacks: (
+        lit_bit_core::runtime::ActionFn<DefaultContext, Signal>,
+        lit_bit_core::runtime::GuardFn<DefaultContext, Signal>,
+        lit_bit_core::runtime::EntryExitActionFn<DefaultContext, Signal>,
+        lit_bit_core::runtime::EventFilterFn<Signal>,
+        lit_bit_core::runtime::FallibleActionFn<DefaultContext, Signal>,
+        lit_bit_core::runtime::ReadActionFn<DefaultContext, Signal>,
+        lit_bit_core::runtime::RaisedEventFn<DefaultContext, Signal>,
+        lit_bit_core::runtime::InvariantFn<DefaultContext>,
+        lit_bit_core::runtime::RetryObserverFn<Light>,
+        lit_bit_core::runtime::StateGuardFn<Light, DefaultContext, Signal>,
+    ),
+) -> (
+    lit_bit_core::ActionFn<DefaultContext, Signal>,
+    lit_bit_core::GuardFn<DefaultContext, Signal>,
+    lit_bit_core::EntryExitActionFn<DefaultContext, Signal>,
+    lit_bit_core::EventFilterFn<Signal>,
+    lit_bit_core::FallibleActionFn<DefaultContext, Signal>,
+    lit_bit_core::ReadActionFn<DefaultContext, Signal>,
+    lit_bit_core::RaisedEventFn<DefaultContext, Signal>,
+    lit_bit_core::InvariantFn<DefaultContext>,
+    lit_bit_core::RetryObserverFn<Light>,
+    lit_bit_core::StateGuardFn<Light, DefaultContext, Signal>,
+) {
+    callbacks
+}
+
+/// ... and so do the traits.
+fn traits_are_re_exported<M, K>(
+    machine: &mut M,
+    event: &M::Event,
+    clock: &dyn lit_bit_core::runtime::MonotonicClock,
+    policy: &dyn lit_bit_core::runtime::TransitionPolicy<M::State, M::Event>,
+) -> (lit_bit_core::Ticks, bool, &'static [K])
+where
+    M: lit_bit_core::runtime::StateMachine + lit_bit_core::runtime::PolicyHost<M::State, M::Event>,
+    K: lit_bit_core::runtime::EventKind<Event = M::Event>,
+{
+    lit_bit_core::PolicyHost::set_policy(machine, None);
+    let state = lit_bit_core::StateMachine::state(machine);
+    let allowed = match state.first() {
+        Some(&to) => policy.allow(&state, to, event),
+        None => true,
+    };
+    (
+        lit_bit_core::MonotonicClock::now(clock),
+        allowed,
+        <K as lit_bit_core::EventKind>::ALL,
+    )
+}
+
+fn interface_is_re_exported<M: lit_bit_core::interface::MachineInterface>() -> u64 {
+    <M as lit_bit_core::MachineInterface>::DEFINITION_HASH
+}
+
+const REGIONS: usize = lit_bit_core::runtime::MAX_ACTIVE_REGIONS + lit_bit_core::MAX_ACTIVE_REGIONS;
+
+// --- Only reachable through `runtime` ---
+
+const INTERNAL_EVENTS: usize = lit_bit_core::runtime::MAX_INTERNAL_EVENTS;
+const CHECKS_INVARIANTS: bool = lit_bit_core::runtime::INVARIANT_CHECKS_ENABLED;
+
+fn placeholder_ids() -> (
+    lit_bit_core::runtime::StateId,
+    lit_bit_core::runtime::EventId,
+) {
+    (
+        lit_bit_core::runtime::StateId(0),
+        lit_bit_core::runtime::EventId(0),
+    )
+}
+
+fn entry_error() -> lit_bit_core::runtime::EntryError {
+    lit_bit_core::runtime::EntryError {
+        kind: lit_bit_core::runtime::EntryErrorKind::CycleDetected,
+    }
+}
+
+// --- Speculation on generated machines ---
+
+fn speculate(
+    door: &crate::charts::door::Door,
+) -> lit_bit_core::runtime::SpeculationResult<crate::charts::door::Door> {
+    let speculation: lit_bit_core::SpeculationResult<_> = door.clone().speculate(&Signal::Go);
+    if speculation.transitioned() {
+        return speculation;
+    }
+    door.speculate(&Signal::Stop)
+        .into_machine()
+        .speculate(&Signal::Go)
+}
+
+fn entered_states(door: &crate::charts::door::Door) -> usize {
+    door.entered_states().len() + door.current_name().len()
+}
+
+fn active_leaves(
+    door: &crate::charts::door::Door,
+    panel: &crate::charts::panel::Panel,
+) -> (crate::charts::door::DoorStateId, usize) {
+    let mut leaves = 0;
+    panel.visit_active(|_| leaves += 1);
+    (door.current(), leaves)
+}
+
+fn is_final(light: &Full, door: &crate::charts::door::Door) -> bool {
+    light.is_final() && door.is_final()
+}
@@ -0,0 +1,2 @@
+//! Integration tests of lit-bit on the Embassy executor, kept apart from `lit-bit-tests`,
+//! which builds on Tokio. The tests are under `tests/`.
@@ -0,0 +1,68 @@
+// lit-bit-tests/embassy/tests/thermostat.rs
+//
+// The `embassy_thermostat` example run end to end on virtual time: sensor readings drive
+// the chart, its `after` timer cuts Heating off, and the supervised sensor survives its
+// injected failures.
+
+#[path = "../../../lit-bit-core/examples/embassy_thermostat/thermostat.rs"]
+mod thermostat;
+
+/// The log lines without their timestamps.
+fn events(log: &[String]) -> Vec<&str> {
+    log.iter()
+        .map(|line| line.split_once(" ms  ").expect("timestamped").1)
+        .collect()
+}
+
+/// The virtual time at which `event` was logged.
+fn at(log: &[String], event: &str) -> u64 {
+    let line = log
+        .iter()
+        .find(|line| line.ends_with(event))
+        .unwrap_or_else(|| panic!("{event:?} not logged"));
+    line.split_once(" ms").unwrap().0.trim().parse().unwrap()
+}
+
+// One test only: the executor, channels and time driver are process-wide statics.
+#[test]
+fn the_thermostat_follows_the_room_and_survives_sensor_failures() {
+    let log = thermostat::run();
+    let events = events(&log);
+
+    let transitions: Vec<_> = events.iter().filter(|e| e.contains(" -> ")).collect();
+    assert_eq!(
+        transitions,
+        [
+            &"Idle -> Heating on Reading(185)",
+            &"Heating -> Idle after 6000 ms",
+            &"Idle -> Cooling on Reading(256)",
+            &"Cooling -> Idle on Reading(229)",
+            &"Idle -> Off on Shutdown",
+        ]
+    );
+
+    // Heating never reached 21 °C and was cut off after six seconds.
+    let heating = at(&log, "Idle -> Heating on Reading(185)");
+    let cut_off = at(&log, "Heating -> Idle after 6000 ms");
+    assert_eq!(cut_off - heating, 6000);
+    // Cooling was left on a reading, so its timer ran out in Idle and was ignored.
+    assert!(events.contains(&"stale timer ignored"));
+
+    // Both ADC timeouts were survived, and every scripted reading still arrived.
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| e.starts_with("sensor failed"))
+            .count(),
+        2
+    );
+    assert!(!events.contains(&"supervisor gives up on the sensor"));
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| e.starts_with("sensor reads"))
+            .count(),
+        15
+    );
+    assert!(events.contains(&"sensor done"));
+}
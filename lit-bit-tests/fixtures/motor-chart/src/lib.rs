@@ -0,0 +1,54 @@
+//! A small chart published through its `interface` module, so that `lit-bit-tests` can
+//! link it from another crate.
+
+use lit_bit_core::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MotorEvent {
+    PowerOn,
+    Start { rpm: u16 },
+    Stop,
+    Fault,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MotorContext {
+    pub rpm: u16,
+    pub faults: u8,
+}
+
+fn set_rpm(ctx: &mut MotorContext, event: &MotorEvent) {
+    if let MotorEvent::Start { rpm } = event {
+        ctx.rpm = *rpm;
+    }
+}
+
+fn stop(ctx: &mut MotorContext, _event: &MotorEvent) {
+    ctx.rpm = 0;
+}
+
+fn record_fault(ctx: &mut MotorContext, _event: &MotorEvent) {
+    ctx.rpm = 0;
+    ctx.faults += 1;
+}
+
+statechart! {
+    name: MotorMachine,
+    context: MotorContext,
+    event: MotorEvent,
+    interface: true,
+    initial: Off,
+    state Off {
+        on MotorEvent::PowerOn => Powered;
+    }
+    state Powered {
+        initial: Idle;
+        on MotorEvent::Fault => Off [action record_fault];
+        state Idle {
+            on MotorEvent::Start { .. } => Running [action set_rpm];
+        }
+        state Running {
+            on MotorEvent::Stop => Idle [action stop];
+        }
+    }
+}
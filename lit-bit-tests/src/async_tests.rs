@@ -69,3 +69,218 @@ async fn test_many_concurrent_actors() {
     let results = join_all(tasks).await;
     assert_eq!(results.len(), 10);
 }
+
+/// `after` timers on Tokio's paused clock: the chart arms a token whenever a state with a
+/// timer is entered, `spawn_timers` sleeps on it, and `fire_timer` delivers the expiry
+/// unless the runtime reports it as stale.
+#[cfg(feature = "async-tokio")]
+mod after_timers {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use lit_bit_core::{MonotonicClock, SendResult, StateMachine, Ticks, TimerToken};
+    use lit_bit_macro::statechart;
+    use tokio::sync::mpsc;
+    use tokio::time::{self, Duration, Instant};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Oven {
+        Start,
+        Cancel,
+        Door,
+        TimerFired {
+            state_id: OvenMachineStateId,
+            timer_id: usize,
+        },
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Kitchen;
+
+    statechart! {
+        name: OvenMachine,
+        context: Kitchen,
+        event: Oven,
+        initial: Idle,
+        state Idle {
+            on Oven::Start => Heating;
+        }
+        state Heating {
+            initial: Preheat;
+            after(Duration::from_millis(500)) => Idle;
+            on Oven::Cancel => Idle;
+            state Preheat {
+                after(Duration::from_millis(100)) => Baking;
+                on Oven::Door => Paused;
+            }
+            state Baking {}
+            state Paused {
+                on Oven::Door => Preheat;
+            }
+        }
+    }
+
+    /// Runs an oven the way its actor would: every step spawns the timers it armed, and the
+    /// expired tokens come back through a channel standing in for the mailbox.
+    struct Driver {
+        oven: OvenMachine,
+        started: Instant,
+        expired_tx: mpsc::UnboundedSender<TimerToken>,
+        expired: mpsc::UnboundedReceiver<TimerToken>,
+    }
+
+    impl Driver {
+        async fn new() -> Self {
+            let (expired_tx, expired) = mpsc::unbounded_channel();
+            let driver = Self {
+                oven: OvenMachine::new(Kitchen, &Oven::Start).expect("oven starts"),
+                started: Instant::now(),
+                expired_tx,
+                expired,
+            };
+            driver.spawn_timers().await;
+            driver
+        }
+
+        /// Spawns the timers of the last step and lets them start sleeping, so their
+        /// deadlines count from now.
+        async fn spawn_timers(&self) {
+            let expired_tx = self.expired_tx.clone();
+            self.oven.spawn_timers(move |token| {
+                let _ = expired_tx.send(token);
+            });
+            tokio::task::yield_now().await;
+        }
+
+        /// Sends `event`, which every step of these tests expects to transition.
+        async fn send(&mut self, event: Oven) {
+            assert_eq!(self.oven.send(&event), SendResult::Transitioned);
+            self.spawn_timers().await;
+        }
+
+        /// Moves the clock forward by `millis`, one millisecond at a time, delivering every
+        /// expiry as it arrives. Returns how many of them fired a transition.
+        async fn advance(&mut self, millis: u64) -> usize {
+            let mut fired = 0;
+            for _ in 0..millis {
+                time::advance(Duration::from_millis(1)).await;
+                tokio::task::yield_now().await;
+                while let Ok(token) = self.expired.try_recv() {
+                    if self.oven.fire_timer(token) == SendResult::Transitioned {
+                        fired += 1;
+                        self.spawn_timers().await;
+                    }
+                }
+            }
+            fired
+        }
+
+        fn state(&self) -> OvenMachineStateId {
+            self.oven.state()[0]
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_timer_fires_at_its_deadline_and_not_before() {
+        let mut oven = Driver::new().await;
+        oven.send(Oven::Start).await;
+        assert_eq!(oven.state(), OvenMachineStateId::HeatingPreheat);
+
+        assert_eq!(oven.advance(99).await, 0);
+        assert_eq!(oven.state(), OvenMachineStateId::HeatingPreheat);
+
+        assert_eq!(oven.advance(1).await, 1);
+        assert_eq!(oven.state(), OvenMachineStateId::HeatingBaking);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn leaving_a_state_early_cancels_its_timer() {
+        let mut oven = Driver::new().await;
+        oven.send(Oven::Start).await;
+        oven.advance(50).await;
+        oven.send(Oven::Door).await;
+
+        // Preheat's expiry at 100 ms is stale and fires nothing.
+        assert_eq!(oven.advance(100).await, 0);
+        assert_eq!(oven.state(), OvenMachineStateId::HeatingPaused);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn re_entering_a_state_re_arms_its_timer_from_the_new_entry() {
+        let mut oven = Driver::new().await;
+        oven.send(Oven::Start).await;
+        oven.advance(50).await;
+        oven.send(Oven::Door).await;
+        oven.advance(30).await;
+        oven.send(Oven::Door).await;
+        assert_eq!(oven.state(), OvenMachineStateId::HeatingPreheat);
+
+        // The first visit's deadline (100 ms) passes without effect; the second visit's
+        // timer runs its full duration from the re-entry at 80 ms.
+        assert_eq!(oven.advance(99).await, 0);
+        assert_eq!(oven.state(), OvenMachineStateId::HeatingPreheat);
+        assert_eq!(oven.advance(1).await, 1);
+        assert_eq!(oven.started.elapsed(), Duration::from_millis(180));
+        assert_eq!(oven.state(), OvenMachineStateId::HeatingBaking);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_parent_timer_outlives_child_transitions_and_exiting_the_parent_cancels_both() {
+        let mut oven = Driver::new().await;
+        oven.send(Oven::Start).await;
+        oven.send(Oven::Door).await;
+        oven.send(Oven::Door).await;
+
+        // The parent's timer keeps running while its children change and fires once, after
+        // Preheat's own timer has moved on to Baking.
+        assert_eq!(oven.advance(500).await, 2);
+        assert_eq!(oven.state(), OvenMachineStateId::Idle);
+
+        // Exiting the parent cancels its own timer and the active child's.
+        oven.send(Oven::Start).await;
+        oven.advance(10).await;
+        oven.send(Oven::Cancel).await;
+        assert_eq!(oven.advance(1_000).await, 0);
+        assert_eq!(oven.state(), OvenMachineStateId::Idle);
+    }
+
+    /// A millisecond clock `set_timer_clock` can hold on to.
+    struct SharedClock(AtomicU64);
+
+    impl MonotonicClock for SharedClock {
+        fn now(&self) -> Ticks {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn armed_timers_report_the_time_left_on_the_timer_clock() {
+        static CLOCK: SharedClock = SharedClock(AtomicU64::new(0));
+        let mut oven = OvenMachine::new(Kitchen, &Oven::Start).expect("oven starts");
+        oven.set_timer_clock(&CLOCK, Duration::from_millis(1));
+        assert!(oven.armed_timers().next().is_none());
+
+        // Entering Heating and Preheat arms their `after` timers.
+        assert_eq!(oven.send(&Oven::Start), SendResult::Transitioned);
+        CLOCK.0.store(40, Ordering::Relaxed);
+        let mut armed: Vec<_> = oven.armed_timers().collect();
+        armed.sort();
+        assert_eq!(
+            armed,
+            [
+                (OvenMachineStateId::Heating, Duration::from_millis(460)),
+                (
+                    OvenMachineStateId::HeatingPreheat,
+                    Duration::from_millis(60)
+                ),
+            ]
+        );
+
+        // Leaving Preheat drops its timer from the snapshot; an overdue timer reads zero.
+        assert_eq!(oven.send(&Oven::Door), SendResult::Transitioned);
+        CLOCK.0.store(600, Ordering::Relaxed);
+        assert_eq!(
+            oven.armed_timers().collect::<Vec<_>>(),
+            [(OvenMachineStateId::Heating, Duration::ZERO)]
+        );
+    }
+}
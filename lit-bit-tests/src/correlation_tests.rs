@@ -0,0 +1,223 @@
+//! End-to-end tests for correlation IDs across a chain of traced actors
+//!
+//! The chain is `Ingress -> Relay (statechart) -> Sink`, every stage wrapped in a
+//! `TracedActor`. A capturing tracing layer records the `correlation` field of each
+//! `actor.handle` span and of the statechart's processed-event report.
+
+use std::sync::{Arc, Mutex};
+
+use lit_bit_core::actor::address::Address;
+use lit_bit_core::actor::{
+    Actor, CorrelationScope, StatechartActor, Traced, TracedActor, spawn_actor_tokio,
+};
+use lit_bit_macro::statechart;
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+/// Correlation fields seen by [`CaptureLayer`], in the order they were recorded.
+#[derive(Debug, Default)]
+struct Captured {
+    spans: Vec<u64>,
+    statechart_events: Vec<Option<u64>>,
+}
+
+#[derive(Clone, Default)]
+struct CaptureLayer {
+    captured: Arc<Mutex<Captured>>,
+}
+
+#[derive(Default)]
+struct CorrelationVisitor {
+    correlation: Option<u64>,
+}
+
+impl Visit for CorrelationVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "correlation" {
+            self.correlation = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn core::fmt::Debug) {}
+}
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "actor.handle" {
+            return;
+        }
+        let mut visitor = CorrelationVisitor::default();
+        attrs.record(&mut visitor);
+        let correlation = visitor
+            .correlation
+            .expect("actor spans record a correlation");
+        self.captured.lock().unwrap().spans.push(correlation);
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != "lit_bit_core::actor::integration" {
+            return;
+        }
+        let mut visitor = CorrelationVisitor::default();
+        event.record(&mut visitor);
+        self.captured
+            .lock()
+            .unwrap()
+            .statechart_events
+            .push(visitor.correlation);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Request;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelayEvent {
+    Forward,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Delivered;
+
+/// First stage: forwards every request to the relay.
+struct Ingress {
+    relay: Address<Traced<RelayEvent>>,
+}
+
+impl Actor for Ingress {
+    type Message = Request;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, _msg: Request) -> Self::Future<'_> {
+        self.relay
+            .try_send_correlated(RelayEvent::Forward)
+            .expect("relay mailbox should have room");
+        core::future::ready(())
+    }
+}
+
+#[derive(Clone)]
+pub struct RelayContext {
+    sink: Address<Traced<Delivered>>,
+}
+
+fn forward_to_sink(ctx: &mut RelayContext, _event: &RelayEvent) {
+    ctx.sink
+        .try_send_correlated(Delivered)
+        .expect("sink mailbox should have room");
+}
+
+// Second stage: a statechart whose transition action sends downstream.
+statechart! {
+    name: RelayMachine,
+    context: RelayContext,
+    event: RelayEvent,
+    initial: Ready,
+
+    state Ready {
+        on RelayEvent::Forward => Ready [action forward_to_sink];
+    }
+}
+
+/// Last stage: reports the correlation ID it handled the delivery under.
+struct Sink {
+    seen: mpsc::UnboundedSender<Option<u64>>,
+}
+
+impl Actor for Sink {
+    type Message = Delivered;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, _msg: Delivered) -> Self::Future<'_> {
+        let correlation = CorrelationScope::current().map(CorrelationScope::correlation);
+        self.seen
+            .send(correlation)
+            .expect("test should be listening");
+        core::future::ready(())
+    }
+}
+
+struct Chain {
+    ingress: Address<Traced<Request>>,
+    seen: mpsc::UnboundedReceiver<Option<u64>>,
+}
+
+fn spawn_chain() -> Chain {
+    let (seen_tx, seen) = mpsc::unbounded_channel();
+    let sink = spawn_actor_tokio(TracedActor::new(Sink { seen: seen_tx }), 8);
+    let machine = RelayMachine::new(RelayContext { sink }, &RelayEvent::Forward)
+        .expect("Failed to create relay machine");
+    let relay = spawn_actor_tokio(TracedActor::new(StatechartActor::new(machine)), 8);
+    let ingress = spawn_actor_tokio(TracedActor::new(Ingress { relay }), 8);
+    Chain { ingress, seen }
+}
+
+#[tokio::test]
+async fn explicit_correlation_reaches_every_span_in_the_chain() {
+    let layer = CaptureLayer::default();
+    let captured = layer.captured.clone();
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+
+    let mut chain = spawn_chain();
+    chain
+        .ingress
+        .send(Traced::new(42, Request))
+        .await
+        .expect("ingress should accept the request");
+
+    assert_eq!(chain.seen.recv().await, Some(Some(42)));
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured.spans, [42, 42, 42]);
+    assert_eq!(captured.statechart_events, [Some(42)]);
+}
+
+#[tokio::test]
+async fn send_correlated_starts_a_new_trace_outside_a_handler() {
+    let layer = CaptureLayer::default();
+    let captured = layer.captured.clone();
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+
+    assert_eq!(CorrelationScope::current(), None);
+    let mut chain = spawn_chain();
+    chain
+        .ingress
+        .send_correlated(Request)
+        .await
+        .expect("ingress should accept the first request");
+    let first = chain.seen.recv().await.flatten().expect("first trace");
+    chain
+        .ingress
+        .send_correlated(Request)
+        .await
+        .expect("ingress should accept the second request");
+    let second = chain.seen.recv().await.flatten().expect("second trace");
+
+    assert_ne!(
+        first, second,
+        "each request outside a handler starts its own trace"
+    );
+    let captured = captured.lock().unwrap();
+    assert_eq!(
+        captured.spans,
+        [first, first, first, second, second, second]
+    );
+}
+
+#[test]
+fn explicit_scope_stamps_outgoing_messages() {
+    let incoming = Traced::new(7, Request);
+    let scope = CorrelationScope::of(&incoming);
+
+    assert_eq!(scope.correlation(), 7);
+    assert_eq!(scope.stamp(Delivered), Traced::new(7, Delivered));
+    assert_eq!(incoming.into_inner(), Request);
+}
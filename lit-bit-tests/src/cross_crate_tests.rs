@@ -0,0 +1,121 @@
+//! Linking a machine defined in another crate (`lit-bit-motor-chart`) through its
+//! `interface` module.
+
+use lit_bit_core::{MachineInterface, SendResult, assert_in_state, link_machine, statechart};
+use lit_bit_motor_chart::{MotorContext, MotorEvent};
+
+link_machine!(lit_bit_motor_chart::MotorMachine);
+
+// The interface is usable in `const` context.
+const _: () = assert!(!motor_machine::HANDLES_ANY_EVENT);
+
+/// Generic routing code only needs the `MachineInterface` bound.
+fn accepts<M: MachineInterface>(kind: &str) -> bool {
+    M::handles(kind)
+}
+
+// Same structure as the linked chart, with no actions.
+mod local_copy {
+    use super::*;
+
+    statechart! {
+        name: LocalMotor,
+        context: MotorContext,
+        event: MotorEvent,
+        interface: true,
+        initial: Off,
+        state Off {
+            on MotorEvent::PowerOn => Powered;
+        }
+        state Powered {
+            initial: Idle;
+            on MotorEvent::Fault => Off;
+            state Idle {
+                on MotorEvent::Start { .. } => Running;
+            }
+            state Running {
+                on MotorEvent::Stop => Idle;
+            }
+        }
+    }
+}
+
+// As above, plus a way to stop from `Idle`.
+mod extended_copy {
+    use super::*;
+
+    statechart! {
+        name: ExtendedMotor,
+        context: MotorContext,
+        event: MotorEvent,
+        interface: true,
+        initial: Off,
+        state Off {
+            on MotorEvent::PowerOn => Powered;
+        }
+        state Powered {
+            initial: Idle;
+            on MotorEvent::Fault => Off;
+            state Idle {
+                on MotorEvent::Start { .. } => Running;
+                on MotorEvent::Stop => Off;
+            }
+            state Running {
+                on MotorEvent::Stop => Idle;
+            }
+        }
+    }
+}
+
+#[test]
+fn linked_machine_runs_with_the_providers_actions() {
+    let mut motor =
+        MotorMachine::new(MotorContext::default(), &MotorEvent::PowerOn).expect("motor starts");
+    assert_eq!(motor.send(&MotorEvent::PowerOn), SendResult::Transitioned);
+    assert_eq!(
+        motor.send(&MotorEvent::Start { rpm: 1200 }),
+        SendResult::Transitioned
+    );
+    assert_in_state!(motor, "Powered::Running");
+    assert_eq!(motor.context().rpm, 1200);
+
+    assert_eq!(motor.send(&MotorEvent::Fault), SendResult::Transitioned);
+    assert_in_state!(motor, "Off");
+    assert_eq!(motor.context().faults, 1);
+}
+
+#[test]
+fn interface_module_describes_the_chart() {
+    assert_eq!(
+        motor_machine::HANDLED_KINDS,
+        &["PowerOn", "Fault", "Start", "Stop"]
+    );
+    assert_eq!(motor_machine::definition().states.len(), 4);
+    assert_eq!(
+        <MotorMachine as MachineInterface>::state_path(MotorMachineStateId::PoweredIdle),
+        "Powered.Idle"
+    );
+}
+
+#[test]
+fn routing_goes_through_the_trait() {
+    assert!(accepts::<MotorMachine>("Start"));
+    assert!(!accepts::<MotorMachine>("Brake"));
+}
+
+#[test]
+fn definition_hash_follows_the_chart_structure() {
+    assert_eq!(
+        <MotorMachine as MachineInterface>::DEFINITION_HASH,
+        motor_machine::DEFINITION_HASH
+    );
+    // Actions are not part of the structure.
+    assert_eq!(
+        motor_machine::DEFINITION_HASH,
+        local_copy::interface::DEFINITION_HASH
+    );
+    assert_ne!(
+        motor_machine::DEFINITION_HASH,
+        extended_copy::interface::DEFINITION_HASH
+    );
+}
@@ -7,8 +7,21 @@
 
 pub mod actor_tests;
 pub mod async_tests;
+#[cfg(feature = "async-tokio")]
+pub mod correlation_tests;
+pub mod cross_crate_tests;
 pub mod integration;
+#[cfg(feature = "async-tokio")]
+pub mod link_tests;
+pub mod ordering_tests;
 pub mod property_tests;
+pub mod reentrancy_tests;
+#[cfg(feature = "async-tokio")]
+pub mod send_blocking_tests;
+#[cfg(feature = "async-tokio")]
+pub mod statechart_query_tests;
+#[cfg(feature = "async-tokio")]
+pub mod stop_reason_tests;
 
 /// Common test utilities and fixtures
 pub mod common {
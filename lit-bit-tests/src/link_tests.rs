@@ -0,0 +1,203 @@
+//! Tests for links between peer actors
+//!
+//! Covers crash propagation along links (including cascades), trapping exits with
+//! `LinkAction::Ignore`, clean shutdowns that notify nobody, and `unlink`.
+
+use lit_bit_core::actor::address::Address;
+use lit_bit_core::actor::{
+    Actor, ActorError, ActorJoinHandle, LinkAction, LinkExit, spawn_actor_tokio_with_handle,
+};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Ping,
+    Crash,
+}
+
+/// Answers pings on `pongs`, panics on `Crash`, and reports every link exit it sees on
+/// `exits`, trapping them when `trap_exits` is set.
+struct Peer {
+    pongs: mpsc::UnboundedSender<()>,
+    exits: mpsc::UnboundedSender<LinkExit>,
+    trap_exits: bool,
+}
+
+impl Actor for Peer {
+    type Message = Command;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, msg: Command) -> Self::Future<'_> {
+        match msg {
+            Command::Ping => {
+                let _ = self.pongs.send(());
+            }
+            Command::Crash => panic!("peer crashed on request"),
+        }
+        core::future::ready(())
+    }
+
+    fn on_link_exit(&mut self, exit: &LinkExit) -> LinkAction {
+        let _ = self.exits.send(exit.clone());
+        if self.trap_exits {
+            LinkAction::Ignore
+        } else {
+            LinkAction::Terminate
+        }
+    }
+}
+
+struct Spawned {
+    address: Address<Command>,
+    handle: ActorJoinHandle,
+    pongs: mpsc::UnboundedReceiver<()>,
+    exits: mpsc::UnboundedReceiver<LinkExit>,
+}
+
+fn spawn_peer(trap_exits: bool) -> Spawned {
+    let (pongs_tx, pongs) = mpsc::unbounded_channel();
+    let (exits_tx, exits) = mpsc::unbounded_channel();
+    let (address, handle) = spawn_actor_tokio_with_handle(
+        Peer {
+            pongs: pongs_tx,
+            exits: exits_tx,
+            trap_exits,
+        },
+        8,
+    );
+    Spawned {
+        address,
+        handle,
+        pongs,
+        exits,
+    }
+}
+
+async fn crash(peer: Spawned) {
+    peer.address
+        .send(Command::Crash)
+        .await
+        .expect("peer should accept the crash command");
+    let join_error = peer.handle.await.expect_err("crashing peer panics");
+    assert!(join_error.is_panic());
+}
+
+#[tokio::test]
+async fn a_crash_terminates_the_linked_peer() {
+    let failing = spawn_peer(false);
+    let mut linked = spawn_peer(false);
+    failing.address.link(&linked.address);
+    let failing_id = failing.address.actor_id();
+
+    crash(failing).await;
+
+    let exit = linked.exits.recv().await.expect("linked peer is notified");
+    assert_eq!(exit.peer, failing_id);
+    assert!(matches!(exit.error, ActorError::Panic { .. }));
+    assert_eq!(
+        linked
+            .handle
+            .await
+            .expect("linked peer ends without panicking"),
+        Err(ActorError::LinkedExit { peer: failing_id })
+    );
+}
+
+#[tokio::test]
+async fn a_crash_cascades_along_a_chain_of_links() {
+    let first = spawn_peer(false);
+    let middle = spawn_peer(false);
+    let last = spawn_peer(false);
+    first.address.link(&middle.address);
+    middle.address.link(&last.address);
+    let middle_id = middle.address.actor_id();
+
+    crash(first).await;
+
+    assert!(matches!(
+        middle.handle.await,
+        Ok(Err(ActorError::LinkedExit { .. }))
+    ));
+    assert_eq!(
+        last.handle.await.expect("last peer ends without panicking"),
+        Err(ActorError::LinkedExit { peer: middle_id })
+    );
+}
+
+#[tokio::test]
+async fn a_trapping_peer_is_notified_and_keeps_running() {
+    let failing = spawn_peer(false);
+    let mut trapping = spawn_peer(true);
+    trapping.address.link(&failing.address);
+    let failing_id = failing.address.actor_id();
+
+    crash(failing).await;
+
+    let exit = trapping
+        .exits
+        .recv()
+        .await
+        .expect("trapping peer is notified");
+    assert_eq!(exit.peer, failing_id);
+    trapping
+        .address
+        .send(Command::Ping)
+        .await
+        .expect("trapping peer still accepts messages");
+    assert_eq!(trapping.pongs.recv().await, Some(()));
+}
+
+#[tokio::test]
+async fn a_clean_shutdown_notifies_nobody() {
+    let stopping = spawn_peer(false);
+    let mut linked = spawn_peer(false);
+    stopping.address.link(&linked.address);
+
+    drop(stopping.address);
+    assert_eq!(stopping.handle.await.expect("no panic"), Ok(()));
+
+    linked
+        .address
+        .send(Command::Ping)
+        .await
+        .expect("linked peer still accepts messages");
+    assert_eq!(linked.pongs.recv().await, Some(()));
+    assert!(linked.exits.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn unlinked_peers_do_not_share_failures() {
+    let failing = spawn_peer(false);
+    let mut bystander = spawn_peer(false);
+    failing.address.link(&bystander.address);
+    bystander.address.unlink(&failing.address);
+
+    crash(failing).await;
+
+    bystander
+        .address
+        .send(Command::Ping)
+        .await
+        .expect("bystander still accepts messages");
+    assert_eq!(bystander.pongs.recv().await, Some(()));
+    assert!(bystander.exits.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn linking_to_a_failed_actor_reports_the_failure_at_once() {
+    let failing = spawn_peer(false);
+    let failed_address = failing.address.clone();
+    let failing_id = failed_address.actor_id();
+    crash(failing).await;
+
+    let late = spawn_peer(false);
+    late.address.link(&failed_address);
+
+    assert_eq!(
+        late.handle.await.expect("late peer ends without panicking"),
+        Err(ActorError::LinkedExit { peer: failing_id })
+    );
+}
@@ -0,0 +1,240 @@
+//! Conformance tests for exit/entry ordering across the least common ancestor
+//!
+//! These pin the guarantees documented under "Transition Ordering" in the runtime module:
+//! exits innermost-first, then the transition action, then entries outermost-first.
+
+use lit_bit_core::{SendResult, assert_configuration};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderEvent {
+    Cousin,
+    UncleToNephew,
+    ChildToAncestor,
+    AncestorToDescendant,
+    SelfLeaf,
+    SelfMid,
+    SelfRoot,
+    Init,
+}
+
+/// Records every entry, exit and transition action in the order the runtime ran them.
+#[derive(Debug, Clone, Default)]
+pub struct CallRecorder {
+    pub calls: Vec<&'static str>,
+}
+
+impl CallRecorder {
+    fn record(&mut self, call: &'static str) {
+        self.calls.push(call);
+    }
+}
+
+macro_rules! recorders {
+    ($($name:ident => $call:literal),* $(,)?) => {
+        $(
+            fn $name(ctx: &mut CallRecorder, _event: &OrderEvent) {
+                ctx.record($call);
+            }
+        )*
+    };
+}
+
+recorders! {
+    enter_a => "enter A", exit_a => "exit A",
+    enter_b => "enter B", exit_b => "exit B",
+    enter_c => "enter C", exit_c => "exit C",
+    enter_d => "enter D", exit_d => "exit D",
+    enter_e => "enter E", exit_e => "exit E",
+    enter_f => "enter F", exit_f => "exit F",
+    enter_g => "enter G", exit_g => "exit G",
+    enter_h => "enter H", exit_h => "exit H",
+    enter_i => "enter I", exit_i => "exit I",
+    enter_j => "enter J", exit_j => "exit J",
+    transition_action => "action",
+}
+
+// Four levels deep on both sides of A:
+//
+//   A ── B ── C ── D
+//   │    │    └─── E
+//   │    └─── F ── G
+//   └─── H ── I ── J
+statechart! {
+    name: OrderingMachine,
+    context: CallRecorder,
+    event: OrderEvent,
+    initial: A,
+
+    state A {
+        initial: B;
+        entry: enter_a;
+        exit: exit_a;
+        on OrderEvent::SelfRoot => A [action transition_action];
+
+        state B {
+            initial: C;
+            entry: enter_b;
+            exit: exit_b;
+            on OrderEvent::AncestorToDescendant => A::B::C::E [action transition_action];
+            on OrderEvent::SelfMid => A::B [action transition_action];
+
+            state C {
+                initial: D;
+                entry: enter_c;
+                exit: exit_c;
+
+                state D {
+                    entry: enter_d;
+                    exit: exit_d;
+                    on OrderEvent::Cousin => A::H::I::J [action transition_action];
+                    on OrderEvent::ChildToAncestor => A::B [action transition_action];
+                    on OrderEvent::SelfLeaf => A::B::C::D [action transition_action];
+                    on OrderEvent::UncleToNephew => A::B::F [action transition_action];
+                }
+                state E {
+                    entry: enter_e;
+                    exit: exit_e;
+                }
+            }
+            state F {
+                initial: G;
+                entry: enter_f;
+                exit: exit_f;
+                on OrderEvent::UncleToNephew => A::B::C::E [action transition_action];
+
+                state G {
+                    entry: enter_g;
+                    exit: exit_g;
+                }
+            }
+        }
+        state H {
+            initial: I;
+            entry: enter_h;
+            exit: exit_h;
+
+            state I {
+                initial: J;
+                entry: enter_i;
+                exit: exit_i;
+
+                state J {
+                    entry: enter_j;
+                    exit: exit_j;
+                }
+            }
+        }
+    }
+}
+
+/// Starts the machine in `A::B::C::D` with an empty call log.
+fn machine_in_d() -> OrderingMachine {
+    let mut machine = OrderingMachine::new(CallRecorder::default(), &OrderEvent::Init)
+        .expect("Failed to create ordering machine");
+    assert_eq!(
+        machine.context().calls,
+        ["enter A", "enter B", "enter C", "enter D"],
+        "Initial entry should run outermost-first down to the initial leaf"
+    );
+    machine.context_mut().calls.clear();
+    machine
+}
+
+fn assert_transition(
+    machine: &mut OrderingMachine,
+    event: OrderEvent,
+    expected_calls: &[&str],
+    expected_leaf: &str,
+) {
+    assert_eq!(machine.send(&event), SendResult::Transitioned);
+    assert_eq!(machine.context().calls, expected_calls, "{event:?}");
+    assert_configuration!(machine, [expected_leaf]);
+}
+
+#[test]
+fn cousin_transition_exits_to_the_common_ancestor_and_enters_the_other_branch() {
+    let mut machine = machine_in_d();
+    assert_transition(
+        &mut machine,
+        OrderEvent::Cousin,
+        &[
+            "exit D", "exit C", "exit B", "action", "enter H", "enter I", "enter J",
+        ],
+        "A::H::I::J",
+    );
+}
+
+#[test]
+fn uncle_to_nephew_transition_stays_inside_the_shared_parent() {
+    let mut machine = machine_in_d();
+    assert_eq!(
+        machine.send(&OrderEvent::UncleToNephew),
+        SendResult::Transitioned
+    );
+    machine.context_mut().calls.clear();
+
+    // F is C's sibling, so B stays active while F is left for C's child E.
+    assert_transition(
+        &mut machine,
+        OrderEvent::UncleToNephew,
+        &["exit G", "exit F", "action", "enter C", "enter E"],
+        "A::B::C::E",
+    );
+}
+
+#[test]
+fn child_to_ancestor_transition_exits_and_reenters_the_ancestor() {
+    let mut machine = machine_in_d();
+    assert_transition(
+        &mut machine,
+        OrderEvent::ChildToAncestor,
+        &[
+            "exit D", "exit C", "exit B", "action", "enter B", "enter C", "enter D",
+        ],
+        "A::B::C::D",
+    );
+}
+
+#[test]
+fn ancestor_to_descendant_transition_keeps_the_source_active() {
+    let mut machine = machine_in_d();
+    assert_transition(
+        &mut machine,
+        OrderEvent::AncestorToDescendant,
+        &["exit D", "action", "enter E"],
+        "A::B::C::E",
+    );
+}
+
+#[test]
+fn self_transitions_exit_and_reenter_at_every_depth() {
+    let mut machine = machine_in_d();
+    assert_transition(
+        &mut machine,
+        OrderEvent::SelfLeaf,
+        &["exit D", "action", "enter D"],
+        "A::B::C::D",
+    );
+
+    let mut machine = machine_in_d();
+    assert_transition(
+        &mut machine,
+        OrderEvent::SelfMid,
+        &[
+            "exit D", "exit C", "exit B", "action", "enter B", "enter C", "enter D",
+        ],
+        "A::B::C::D",
+    );
+
+    let mut machine = machine_in_d();
+    assert_transition(
+        &mut machine,
+        OrderEvent::SelfRoot,
+        &[
+            "exit D", "exit C", "exit B", "exit A", "action", "enter A", "enter B", "enter C",
+            "enter D",
+        ],
+        "A::B::C::D",
+    );
+}
@@ -1,8 +1,33 @@
 //! Property-based tests for statechart behavior
 
 use crate::common::*;
+use lit_bit_core::property::{EventStrategy, seeded_runner};
+use lit_bit_macro::statechart_event;
 use proptest::prelude::*;
 
+#[statechart_event]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PumpEvent {
+    Start,
+    Stop,
+    Fault,
+}
+
+#[statechart_event]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SensorEvent {
+    Reading(u16),
+    Fault,
+}
+
+/// Mostly start/stop traffic with an occasional fault.
+fn pump_events() -> EventStrategy<PumpEvent> {
+    EventStrategy::from_kinds(&PumpEventKind::ALL, |kind| match kind {
+        PumpEventKind::Start | PumpEventKind::Stop => 10,
+        PumpEventKind::Fault => 1,
+    })
+}
+
 // Property test strategies
 prop_compose! {
     fn arb_test_event()(variant in 0..4u8) -> TestEvent {
@@ -58,4 +83,124 @@ proptest! {
         // TODO: Implement memory leak detection
         let _ = operations;
     }
+
+    #[test]
+    fn test_weighted_events_only_produce_enabled_variants(
+        events in EventStrategy::new()
+            .event(3, PumpEvent::Start)
+            .event(1, PumpEvent::Fault)
+            .event(0, PumpEvent::Stop)
+            .sequence(0..50)
+    ) {
+        prop_assert!(!events.contains(&PumpEvent::Stop));
+    }
+
+    #[test]
+    fn test_data_carrying_variants_use_their_own_strategy(
+        event in EventStrategy::new()
+            .variant(5, (100u16..200).prop_map(SensorEvent::Reading))
+            .event(1, SensorEvent::Fault)
+    ) {
+        if let SensorEvent::Reading(value) = event {
+            prop_assert!((100..200).contains(&value));
+        }
+    }
+}
+
+#[test]
+fn test_kind_enumeration_covers_every_variant() {
+    assert_eq!(
+        PumpEventKind::ALL,
+        [
+            PumpEventKind::Start,
+            PumpEventKind::Stop,
+            PumpEventKind::Fault
+        ]
+    );
+    assert_eq!(PumpEventKind::Fault.name(), "Fault");
+    assert_eq!(PumpEvent::from(PumpEventKind::Stop), PumpEvent::Stop);
+    assert_eq!(SensorEventKind::ALL.len(), 2);
+}
+
+#[test]
+fn test_weights_bias_the_distribution() {
+    let events = pump_events().sample_sequence(&mut seeded_runner(7), 2_000);
+    let faults = events.iter().filter(|e| **e == PumpEvent::Fault).count();
+    let starts = events.iter().filter(|e| **e == PumpEvent::Start).count();
+
+    // Expected ratio is 1:10; leave generous slack so the test is not seed-sensitive.
+    assert!(faults > 0, "rare events must still be generated");
+    assert!(faults * 4 < starts, "faults={faults} starts={starts}");
+}
+
+#[test]
+fn test_seeded_runner_is_reproducible() {
+    let first = pump_events().sample_sequence(&mut seeded_runner(42), 64);
+    let second = pump_events().sample_sequence(&mut seeded_runner(42), 64);
+    let other = pump_events().sample_sequence(&mut seeded_runner(43), 64);
+
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+mod arbitrary_states {
+    use super::PumpEvent;
+    use lit_bit_core::StateMachine;
+    use lit_bit_macro::statechart;
+    use proptest::prelude::*;
+    use proptest::strategy::ValueTree;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct ValveContext;
+
+    statechart! {
+        name: Valve,
+        context: ValveContext,
+        event: PumpEvent,
+        initial: Closed,
+        state Closed {
+            on PumpEvent::Start => Open;
+        }
+        state Open {
+            initial: Flowing;
+            on PumpEvent::Stop => Closed;
+            state Flowing {
+                on PumpEvent::Fault => Throttled;
+            }
+            state Throttled {}
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_stop_closes_the_valve_from_any_state(start in any::<ValveStateId>()) {
+            let mut valve = Valve::new(ValveContext, &PumpEvent::Stop).expect("valve starts");
+            valve.force_transition(start, &PumpEvent::Stop).expect("every state is reachable");
+
+            let _ = valve.send(&PumpEvent::Stop);
+            prop_assert_eq!(valve.state()[0], ValveStateId::Closed);
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_state_ids_cover_the_whole_chart() {
+        let mut runner = lit_bit_core::property::seeded_runner(1);
+        let strategy = any::<ValveStateId>();
+        let mut seen = std::collections::BTreeSet::new();
+        for _ in 0..200 {
+            let tree = strategy
+                .new_tree(&mut runner)
+                .expect("state ids are generated");
+            seen.insert(tree.current());
+        }
+        assert_eq!(
+            seen.into_iter().collect::<Vec<_>>(),
+            [
+                ValveStateId::Closed,
+                ValveStateId::Open,
+                ValveStateId::OpenFlowing,
+                ValveStateId::OpenThrottled
+            ]
+        );
+    }
 }
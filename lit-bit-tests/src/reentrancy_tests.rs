@@ -0,0 +1,212 @@
+//! Tests for actions that want their own machine to see another event
+//!
+//! Pins the three paths documented on `Runtime`: a shared handle finds the machine busy,
+//! raised events run right after the current step, and an actor's `try_send` to its own
+//! address goes through the mailbox.
+
+/// The machine reaches itself through an `Rc<RefCell<_>>`, the only way a context can hold
+/// on to its own machine without `unsafe`.
+mod shared_handle {
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
+
+    use lit_bit_core::{SendResult, assert_in_state};
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Knock {
+        Boot,
+        Once,
+        Again,
+    }
+
+    #[derive(Clone, Default)]
+    pub struct Door {
+        pub me: Weak<RefCell<DoorMachine>>,
+        pub nested_send_blocked: Option<bool>,
+    }
+
+    fn knock_again(ctx: &mut Door, _event: &Knock) {
+        let me = ctx.me.upgrade().expect("machine is alive");
+        let blocked = match me.try_borrow_mut() {
+            Ok(mut machine) => {
+                let _ = machine.send(&Knock::Again);
+                false
+            }
+            Err(_) => true,
+        };
+        ctx.nested_send_blocked = Some(blocked);
+    }
+
+    statechart! {
+        name: DoorMachine,
+        context: Door,
+        event: Knock,
+        initial: Closed,
+        state Closed {
+            on Knock::Once => Ajar [action knock_again];
+        }
+        state Ajar {
+            on Knock::Again => Open;
+        }
+        state Open {}
+    }
+
+    #[test]
+    fn a_nested_send_through_a_shared_handle_finds_the_machine_busy() {
+        let machine = Rc::new(RefCell::new(
+            DoorMachine::new(Door::default(), &Knock::Boot).expect("machine starts"),
+        ));
+        machine.borrow_mut().context_mut().me = Rc::downgrade(&machine);
+
+        let result = machine.borrow_mut().send(&Knock::Once);
+
+        assert_eq!(result, SendResult::Transitioned);
+        let machine = machine.borrow();
+        assert_eq!(machine.context().nested_send_blocked, Some(true));
+        assert_in_state!(machine, "Ajar");
+    }
+}
+
+/// The supported way for an action to feed its own machine without an actor.
+mod raised_events {
+    use lit_bit_core::{MAX_ACTIVE_REGIONS, Runtime, SendResult, StateMachine, assert_in_state};
+    use lit_bit_macro::statechart;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Knock {
+        Boot,
+        Once,
+        Again,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Door {
+        pub raised: Vec<Knock>,
+        pub log: Vec<&'static str>,
+    }
+
+    fn knock_again(ctx: &mut Door, _event: &Knock) {
+        ctx.raised.push(Knock::Again);
+        ctx.log.push("once");
+    }
+
+    fn enter_ajar(ctx: &mut Door, _event: &Knock) {
+        ctx.log.push("enter ajar");
+    }
+
+    fn enter_open(ctx: &mut Door, _event: &Knock) {
+        ctx.log.push("enter open");
+    }
+
+    statechart! {
+        name: DoorMachine,
+        context: Door,
+        event: Knock,
+        initial: Closed,
+        state Closed {
+            on Knock::Once => Ajar [action knock_again];
+        }
+        state Ajar {
+            entry: enter_ajar;
+            on Knock::Again => Open;
+        }
+        state Open {
+            entry: enter_open;
+        }
+    }
+
+    #[test]
+    fn a_raised_event_runs_after_the_step_that_raised_it() {
+        let mut runtime =
+            Runtime::<DoorMachineStateId, Knock, Door, 2, MAX_ACTIVE_REGIONS, 8>::new(
+                DoorMachine::definition(),
+                Door::default(),
+                &Knock::Boot,
+            )
+            .expect("runtime starts");
+        runtime.set_raised_event_source(|ctx| ctx.raised.pop());
+
+        assert_eq!(runtime.send(&Knock::Once), SendResult::Transitioned);
+
+        // The step that raised `Again` finished (Ajar was entered) before `Again` ran.
+        assert_eq!(runtime.context().log, ["once", "enter ajar", "enter open"]);
+        assert_in_state!(runtime, "Open");
+    }
+}
+
+/// An actor's own mailbox is the other way back in.
+#[cfg(feature = "async-tokio")]
+mod actor_mailbox {
+    use std::sync::{Arc, OnceLock};
+
+    use lit_bit_core::actor::StatechartActor;
+    use lit_bit_core::actor::address::Address;
+    use lit_bit_core::actor::spawn::spawn_actor_tokio;
+    use lit_bit_core::{SendResult, StateMachine};
+    use lit_bit_macro::statechart;
+    use tokio::sync::mpsc;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Knock {
+        Boot,
+        Once,
+        Again,
+    }
+
+    #[derive(Clone)]
+    pub struct Door {
+        pub me: Arc<OnceLock<Address<Knock>>>,
+        pub seen: mpsc::UnboundedSender<(DoorMachineStateId, SendResult)>,
+    }
+
+    fn knock_again(ctx: &mut Door, _event: &Knock) {
+        ctx.me
+            .get()
+            .expect("address is set before the first message")
+            .try_send(Knock::Again)
+            .expect("own mailbox has room");
+    }
+
+    fn report(machine: &DoorMachine, result: SendResult) {
+        let _ = machine.context().seen.send((machine.state()[0], result));
+    }
+
+    statechart! {
+        name: DoorMachine,
+        context: Door,
+        event: Knock,
+        initial: Closed,
+        state Closed {
+            on Knock::Once => Ajar [action knock_again];
+        }
+        state Ajar {
+            on Knock::Again => Open;
+        }
+        state Open {}
+    }
+
+    #[tokio::test]
+    async fn try_send_to_its_own_address_is_handled_as_the_next_message() {
+        let (seen_tx, mut seen) = mpsc::unbounded_channel();
+        let me = Arc::new(OnceLock::new());
+        let door = Door {
+            me: me.clone(),
+            seen: seen_tx,
+        };
+        let machine = DoorMachine::new(door, &Knock::Boot).expect("machine starts");
+        let address = spawn_actor_tokio(StatechartActor::new(machine).with_output(report), 4);
+        assert!(me.set(address.clone()).is_ok(), "address is set once");
+
+        address.send(Knock::Once).await.expect("door accepts");
+
+        assert_eq!(
+            seen.recv().await,
+            Some((DoorMachineStateId::Ajar, SendResult::Transitioned))
+        );
+        assert_eq!(
+            seen.recv().await,
+            Some((DoorMachineStateId::Open, SendResult::Transitioned))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Tests for `Address::send_blocking`
+//!
+//! Covers sending from a plain `std::thread` and from `spawn_blocking`, both past the
+//! mailbox capacity so the sender has to wait, and the message handed back when the call
+//! is made from async code on the runtime.
+
+use lit_bit_core::actor::address::Address;
+use lit_bit_core::actor::backpressure::SendError;
+use lit_bit_core::actor::{Actor, spawn_actor_tokio};
+use tokio::sync::mpsc;
+
+/// Forwards every message it handles to `seen`.
+struct Collector {
+    seen: mpsc::UnboundedSender<u32>,
+}
+
+impl Actor for Collector {
+    type Message = u32;
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, msg: u32) -> Self::Future<'_> {
+        let _ = self.seen.send(msg);
+        core::future::ready(())
+    }
+}
+
+/// A collector with a mailbox of two, so that sending more waits for it to drain.
+fn collector() -> (Address<u32>, mpsc::UnboundedReceiver<u32>) {
+    let (seen_tx, seen) = mpsc::unbounded_channel();
+    let address = spawn_actor_tokio(Collector { seen: seen_tx }, 2);
+    (address, seen)
+}
+
+async fn received(seen: &mut mpsc::UnboundedReceiver<u32>, count: usize) -> Vec<u32> {
+    let mut messages = Vec::new();
+    for _ in 0..count {
+        messages.push(seen.recv().await.expect("collector forwards every message"));
+    }
+    messages
+}
+
+#[tokio::test]
+async fn a_plain_thread_sends_past_the_mailbox_capacity() {
+    let (address, mut seen) = collector();
+
+    let sender = std::thread::spawn(move || {
+        (1..=5)
+            .map(|msg| address.send_blocking(msg))
+            .collect::<Vec<_>>()
+    });
+
+    assert_eq!(received(&mut seen, 5).await, [1, 2, 3, 4, 5]);
+    let results = tokio::task::spawn_blocking(move || sender.join())
+        .await
+        .expect("join task runs")
+        .expect("sender thread does not panic");
+    assert!(results.iter().all(Result::is_ok));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn a_spawn_blocking_closure_sends_past_the_mailbox_capacity() {
+    let (address, mut seen) = collector();
+
+    let sender = tokio::task::spawn_blocking(move || {
+        (1..=5)
+            .map(|msg| address.send_blocking(msg))
+            .collect::<Vec<_>>()
+    });
+
+    assert_eq!(received(&mut seen, 5).await, [1, 2, 3, 4, 5]);
+    let results = sender.await.expect("blocking task does not panic");
+    assert!(results.iter().all(Result::is_ok));
+}
+
+#[tokio::test]
+async fn sending_from_async_code_hands_the_message_back() {
+    let (address, mut seen) = collector();
+
+    assert_eq!(
+        address.send_blocking(7),
+        Err(SendError::WouldBlockInAsyncContext(7))
+    );
+    let spawned = address.clone();
+    let from_task = tokio::spawn(async move { spawned.send_blocking(8) })
+        .await
+        .expect("task does not panic");
+    assert_eq!(from_task, Err(SendError::WouldBlockInAsyncContext(8)));
+
+    // Nothing was delivered, and the address still works with `send`.
+    address.send(9).await.expect("collector should accept");
+    assert_eq!(received(&mut seen, 1).await, [9]);
+}
+
+#[test]
+fn sending_to_a_stopped_actor_fails_with_closed() {
+    let runtime = tokio::runtime::Runtime::new().expect("runtime builds");
+    let (address, seen) = runtime.block_on(async { collector() });
+    drop(seen);
+    drop(runtime);
+
+    assert_eq!(address.send_blocking(1), Err(SendError::Closed(1)));
+}
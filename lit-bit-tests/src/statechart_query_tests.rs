@@ -0,0 +1,93 @@
+//! Tests for asking a queryable `StatechartActor` about its machine
+//!
+//! A turnstile chart runs behind `StatechartActor::with_queries`; its configuration and
+//! context are read back through `Address::configuration` and `Address::context_snapshot`.
+
+use lit_bit_core::actor::{StatechartActor, StatechartMessage, spawn_actor_tokio_with_handle};
+use lit_bit_macro::statechart;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gate {
+    Coin,
+    Push,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tally {
+    pub coins: u32,
+    pub passes: u32,
+}
+
+pub fn take_coin(ctx: &mut Tally, _event: &Gate) {
+    ctx.coins += 1;
+}
+
+pub fn let_through(ctx: &mut Tally, _event: &Gate) {
+    ctx.passes += 1;
+}
+
+statechart! {
+    name: Turnstile,
+    context: Tally,
+    event: Gate,
+    initial: Locked,
+
+    state Locked {
+        on Gate::Coin => Unlocked [action take_coin];
+    }
+    state Unlocked {
+        on Gate::Push => Locked [action let_through];
+    }
+}
+
+#[tokio::test]
+async fn queries_are_answered_after_the_events_sent_before_them() {
+    let machine = Turnstile::new(Tally::default(), &Gate::Push).expect("turnstile starts");
+    let (address, _handle) =
+        spawn_actor_tokio_with_handle(StatechartActor::new(machine).with_queries(), 8);
+
+    assert_eq!(
+        address
+            .configuration()
+            .await
+            .expect("actor answers")
+            .as_slice(),
+        [TurnstileStateId::Locked]
+    );
+
+    for event in [Gate::Coin, Gate::Push, Gate::Coin] {
+        address
+            .send(StatechartMessage::Event(event))
+            .await
+            .expect("turnstile should accept");
+    }
+
+    assert_eq!(
+        address
+            .configuration()
+            .await
+            .expect("actor answers")
+            .as_slice(),
+        [TurnstileStateId::Unlocked]
+    );
+    assert_eq!(
+        address.context_snapshot().await,
+        Some(Tally {
+            coins: 2,
+            passes: 1
+        })
+    );
+}
+
+#[tokio::test]
+async fn asking_a_stopped_actor_gets_no_answer() {
+    let machine = Turnstile::new(Tally::default(), &Gate::Push).expect("turnstile starts");
+    let (address, handle) =
+        spawn_actor_tokio_with_handle(StatechartActor::new(machine).with_queries(), 8);
+
+    address.stop();
+    assert_eq!(handle.await.expect("actor ends without panicking"), Ok(()));
+
+    assert_eq!(address.configuration().await, None);
+    assert_eq!(address.context_snapshot().await, None);
+}
@@ -0,0 +1,135 @@
+//! Tests for the reason passed to `Actor::on_stop_with_reason`
+//!
+//! Covers each `StopReason` through the trigger that produces it: dropping every
+//! address, `Address::stop`, a supervisor restarting siblings together, and a nested
+//! supervisor escalating a failure.
+
+use lit_bit_core::actor::address::Address;
+use lit_bit_core::actor::spawn::spawn_supervised_actor_tokio;
+use lit_bit_core::actor::{
+    Actor, ActorError, RestartStrategy, StopReason, SupervisorActor, SupervisorMessage,
+    spawn_actor_tokio_with_handle,
+};
+use tokio::sync::mpsc;
+
+/// Answers every message on `pongs` and reports why it stopped on `stops`.
+struct Reporter {
+    pongs: mpsc::UnboundedSender<()>,
+    stops: mpsc::UnboundedSender<StopReason>,
+}
+
+impl Actor for Reporter {
+    type Message = ();
+    type Future<'a>
+        = core::future::Ready<()>
+    where
+        Self: 'a;
+
+    fn handle(&mut self, (): ()) -> Self::Future<'_> {
+        let _ = self.pongs.send(());
+        core::future::ready(())
+    }
+
+    fn on_stop_with_reason(self, reason: StopReason) -> Result<(), ActorError> {
+        let _ = self.stops.send(reason);
+        Ok(())
+    }
+}
+
+struct Channels {
+    pongs: mpsc::UnboundedReceiver<()>,
+    stops: mpsc::UnboundedReceiver<StopReason>,
+}
+
+fn reporter() -> (Reporter, Channels) {
+    let (pongs_tx, pongs) = mpsc::unbounded_channel();
+    let (stops_tx, stops) = mpsc::unbounded_channel();
+    let actor = Reporter {
+        pongs: pongs_tx,
+        stops: stops_tx,
+    };
+    (actor, Channels { pongs, stops })
+}
+
+/// Spawns a reporter under `supervisor`, which registers its address.
+fn supervised(supervisor: &mut SupervisorActor<u32, 8>, id: u32) -> (Address<()>, Channels) {
+    let (actor, channels) = reporter();
+    let address = spawn_supervised_actor_tokio(actor, supervisor, id, 8)
+        .expect("supervisor should accept the child");
+    (address, channels)
+}
+
+#[tokio::test]
+async fn dropping_every_address_stops_with_senders_dropped() {
+    let (actor, mut channels) = reporter();
+    let (address, handle) = spawn_actor_tokio_with_handle(actor, 8);
+    address.send(()).await.expect("reporter should accept");
+    channels.pongs.recv().await.expect("reporter answers");
+
+    drop(address);
+
+    assert_eq!(
+        handle.await.expect("reporter ends without panicking"),
+        Ok(())
+    );
+    assert_eq!(
+        channels.stops.recv().await,
+        Some(StopReason::SendersDropped)
+    );
+}
+
+#[tokio::test]
+async fn a_stop_request_ends_the_loop_before_the_mailbox_closes() {
+    let (actor, mut channels) = reporter();
+    let (address, handle) = spawn_actor_tokio_with_handle(actor, 8);
+    let (peer_actor, mut peer) = reporter();
+    let (peer_address, _peer_handle) = spawn_actor_tokio_with_handle(peer_actor, 8);
+    address.link(&peer_address);
+    address.send(()).await.expect("reporter should accept");
+    channels.pongs.recv().await.expect("reporter answers");
+
+    address.stop();
+
+    assert_eq!(
+        handle.await.expect("reporter ends without panicking"),
+        Ok(())
+    );
+    assert_eq!(channels.stops.recv().await, Some(StopReason::StopRequested));
+    assert!(address.send(()).await.is_err());
+
+    // Being stopped is a clean shutdown, so the linked peer keeps running.
+    peer_address.send(()).await.expect("peer should accept");
+    peer.pongs.recv().await.expect("peer still answers");
+    assert!(peer.stops.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn siblings_restarted_together_stop_with_supervisor_restart() {
+    let mut supervisor =
+        SupervisorActor::<u32, 8>::with_config(RestartStrategy::OneForAll, 5, 60_000);
+    let (_first, mut first) = supervised(&mut supervisor, 1);
+    let (_second, mut second) = supervised(&mut supervisor, 2);
+
+    Actor::handle(&mut supervisor, SupervisorMessage::RestartChild { id: 1 }).await;
+
+    assert_eq!(
+        first.stops.recv().await,
+        Some(StopReason::SupervisorRestart)
+    );
+    assert_eq!(
+        second.stops.recv().await,
+        Some(StopReason::SupervisorRestart)
+    );
+}
+
+#[tokio::test]
+async fn escalating_a_failure_stops_the_running_children_with_escalated() {
+    let mut supervisor = SupervisorActor::<u32, 8>::new();
+    let (_first, mut first) = supervised(&mut supervisor, 1);
+    let (_second, mut second) = supervised(&mut supervisor, 2);
+
+    supervisor.escalate_failure(&1, ActorError::Timeout);
+
+    assert_eq!(first.stops.recv().await, Some(StopReason::Escalated));
+    assert_eq!(second.stops.recv().await, Some(StopReason::Escalated));
+}
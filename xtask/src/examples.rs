@@ -0,0 +1,258 @@
+//! `cargo xtask examples`: builds and runs the examples listed in
+//! `lit-bit-core/examples/examples.toml` and checks their output.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::run_command;
+
+const MANIFEST: &str = "lit-bit-core/examples/examples.toml";
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Deserialize)]
+struct Manifest {
+    example: Vec<ExampleSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExampleSpec {
+    name: String,
+    /// Cross-compilation target; `None` builds and runs on the host.
+    target: Option<String>,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    timeout_secs: Option<u64>,
+    /// Expected stdout, relative to the manifest's directory.
+    expected: Option<PathBuf>,
+    /// Run an embedded example under `qemu-system-arm` when it is installed.
+    #[serde(default)]
+    qemu: bool,
+}
+
+enum Outcome {
+    Passed,
+    Blessed,
+    BuiltOnly(&'static str),
+}
+
+pub fn run_examples(bless: bool, host_only: bool) -> Result<()> {
+    let root = workspace_root();
+    let manifest_path = root.join(MANIFEST);
+    let manifest = load_manifest(&manifest_path)?;
+    let examples_dir = manifest_path
+        .parent()
+        .expect("manifest lives in a directory");
+    let qemu_available = Command::new("qemu-system-arm")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+
+    let mut failures = Vec::new();
+    for spec in &manifest.example {
+        if host_only && spec.target.is_some() {
+            continue;
+        }
+        match run_example(&root, examples_dir, spec, bless, qemu_available) {
+            Ok(Outcome::Passed) => println!("✓ {}", spec.name),
+            Ok(Outcome::Blessed) => println!("✓ {} (expected output updated)", spec.name),
+            Ok(Outcome::BuiltOnly(reason)) => println!("✓ {} (built only: {reason})", spec.name),
+            Err(err) => {
+                println!("✗ {}: {err:#}", spec.name);
+                failures.push(spec.name.as_str());
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        bail!(
+            "{} example(s) failed: {}",
+            failures.len(),
+            failures.join(", ")
+        );
+    }
+    println!("✓ All examples passed");
+    Ok(())
+}
+
+/// Lists the embedded examples that build for `target`, as `(name, features)`.
+///
+/// The manifest names one target per example; an example is also built for every other
+/// target of the same architecture family, so a `thumbv7m` Cortex-M example covers
+/// `thumbv6m` and `thumbv8m.main` as well.
+pub(crate) fn embedded_examples(target: &str) -> Result<Vec<(String, Vec<String>)>> {
+    let manifest = load_manifest(&workspace_root().join(MANIFEST))?;
+    Ok(manifest
+        .example
+        .into_iter()
+        .filter(|spec| {
+            spec.target
+                .as_deref()
+                .is_some_and(|own| arch_family(own) == arch_family(target))
+        })
+        .map(|spec| (spec.name, spec.features))
+        .collect())
+}
+
+/// `thumbv7m-none-eabi` -> `thumbv`, `riscv32imac-unknown-none-elf` -> `riscv`.
+fn arch_family(target: &str) -> &str {
+    let end = target
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(target.len());
+    &target[..end]
+}
+
+fn load_manifest(manifest_path: &Path) -> Result<Manifest> {
+    toml::from_str(
+        &std::fs::read_to_string(manifest_path)
+            .with_context(|| format!("cannot read {}", manifest_path.display()))?,
+    )
+    .with_context(|| format!("invalid manifest {}", manifest_path.display()))
+}
+
+fn run_example(
+    root: &Path,
+    examples_dir: &Path,
+    spec: &ExampleSpec,
+    bless: bool,
+    qemu_available: bool,
+) -> Result<Outcome> {
+    let features = spec.features.join(",");
+    let mut build = vec![
+        "cargo",
+        "build",
+        "-p",
+        "lit-bit-core",
+        "--example",
+        &spec.name,
+    ];
+    if !features.is_empty() {
+        build.extend(["--features", &features]);
+    }
+    if let Some(target) = &spec.target {
+        build.extend(["--target", target]);
+    }
+    run_command(&build)?;
+
+    let target_dir = root.join("target");
+    let mut command = match &spec.target {
+        None => Command::new(target_dir.join("debug/examples").join(&spec.name)),
+        Some(_) if !spec.qemu => return Ok(Outcome::BuiltOnly("not run under QEMU")),
+        Some(_) if !qemu_available => {
+            return Ok(Outcome::BuiltOnly("qemu-system-arm not found"));
+        }
+        Some(target) => {
+            let mut qemu = Command::new("qemu-system-arm");
+            qemu.args([
+                "-cpu",
+                "cortex-m3",
+                "-machine",
+                "lm3s6965evb",
+                "-nographic",
+                "-semihosting-config",
+                "enable=on,target=native",
+                "-kernel",
+            ])
+            .arg(
+                target_dir
+                    .join(target)
+                    .join("debug/examples")
+                    .join(&spec.name),
+            );
+            qemu
+        }
+    };
+    command.args(&spec.args);
+    let timeout = Duration::from_secs(spec.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let stdout = run_with_timeout(&mut command, timeout)?;
+
+    let Some(expected) = &spec.expected else {
+        return Ok(Outcome::Passed);
+    };
+    let expected_path = examples_dir.join(expected);
+    if bless {
+        if let Some(dir) = expected_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&expected_path, &stdout)?;
+        return Ok(Outcome::Blessed);
+    }
+    let wanted = std::fs::read_to_string(&expected_path).with_context(|| {
+        format!(
+            "cannot read {} (run with --bless to create it)",
+            expected_path.display()
+        )
+    })?;
+    match first_difference(&wanted, &stdout) {
+        None => Ok(Outcome::Passed),
+        Some((line, wanted_line, actual_line)) => bail!(
+            "stdout differs from {} at line {line}:\n  expected: {wanted_line}\n  actual:   {actual_line}",
+            expected_path.display()
+        ),
+    }
+}
+
+/// Runs `command`, returning its stdout, or fails if it exits unsuccessfully or is still
+/// running after `timeout`.
+fn run_with_timeout(command: &mut Command, timeout: Duration) -> Result<String> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("cannot start {:?}", command.get_program()))?;
+    let mut pipe = child.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut stdout = String::new();
+        pipe.read_to_string(&mut stdout).map(|_| stdout)
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            bail!("timed out after {}s", timeout.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    let stdout = reader.join().expect("stdout reader panicked")?;
+    if !status.success() {
+        bail!("exited with {status}");
+    }
+    Ok(stdout)
+}
+
+/// Returns the first differing line (1-based) and both versions of it, if any.
+fn first_difference(wanted: &str, actual: &str) -> Option<(usize, String, String)> {
+    let mut wanted_lines = wanted.lines();
+    let mut actual_lines = actual.lines();
+    for line in 1.. {
+        match (wanted_lines.next(), actual_lines.next()) {
+            (None, None) => return None,
+            (wanted, actual) if wanted == actual => {}
+            (wanted, actual) => {
+                let show = |text: Option<&str>| text.unwrap_or("<end of output>").to_string();
+                return Some((line, show(wanted), show(actual)));
+            }
+        }
+    }
+    unreachable!("the loop only ends by returning")
+}
+
+pub(crate) fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives inside the workspace")
+        .to_path_buf()
+}
@@ -2,6 +2,12 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::process::Command;
 
+mod examples;
+mod msrv;
+
+/// Must match `lit_bit_core::coverage::COVERAGE_DUMP_PREFIX`.
+const COVERAGE_DUMP_PREFIX: &str = "[lit-bit coverage]";
+
 #[derive(Parser)]
 #[command(name = "xtask")]
 #[command(about = "Automation tasks for lit-bit")]
@@ -19,15 +25,61 @@ enum Commands {
         target: String,
     },
     /// Run all tests
-    Test,
+    Test {
+        /// Enable the `coverage` feature and print the state/transition coverage dump
+        #[arg(long)]
+        coverage: bool,
+    },
     /// Run benchmarks in smoke mode
     Bench {
         /// Run in smoke mode (quick)
         #[arg(long)]
         smoke: bool,
     },
-    /// Check all targets
-    CheckAll,
+    /// Build and run the examples in `lit-bit-core/examples/examples.toml` and compare
+    /// their output with the checked-in expected output
+    Examples {
+        /// Overwrite the expected-output files with the actual output
+        #[arg(long)]
+        bless: bool,
+        /// Skip embedded-only examples
+        #[arg(long)]
+        host_only: bool,
+    },
+    /// Check `lit-bit-core` and build the embedded examples for the host and every
+    /// bare-metal target, then print a pass/fail matrix
+    CheckAll {
+        /// Install missing targets with `rustup target add` instead of failing
+        #[arg(long)]
+        install_targets: bool,
+    },
+    /// Compare the thumbv7m size of a flat chart with and without `single-region`, and
+    /// check what the `defmt` supervision instrumentation adds to flash
+    /// (requires `cargo-binutils`)
+    SizeReport {
+        /// Largest flash growth, in bytes, allowed for building the supervision example
+        /// with `defmt`
+        #[arg(long, default_value_t = 4096)]
+        defmt_budget: u64,
+    },
+    /// Build the API probes in `lit-bit-tests/api-probes` with the pinned MSRV toolchain
+    /// for the `no_std` and `std` feature sets, then check with `cargo public-api` that
+    /// every public item of `lit-bit-core` is probed
+    Msrv {
+        /// Only build the probes; skip the `cargo public-api` coverage check
+        #[arg(long)]
+        skip_coverage: bool,
+    },
+    /// Run the actor soak scenario from `lit-bit-bench` and fail on memory, mailbox or
+    /// latency growth; the time series is written to `target/soak/soak.csv`
+    Soak {
+        /// How long to soak, in minutes
+        #[arg(long, default_value_t = 10)]
+        minutes: u64,
+        /// Run the 30-second CI variant instead
+        #[arg(long, conflicts_with = "minutes")]
+        smoke: bool,
+    },
 }
 
 fn main() -> Result<()> {
@@ -35,34 +87,40 @@ fn main() -> Result<()> {
 
     match cli.command {
         Commands::Ci { target } => run_ci(&target),
-        Commands::Test => run_tests(),
+        Commands::Test { coverage } => run_tests(coverage),
         Commands::Bench { smoke } => run_benchmarks(smoke),
-        Commands::CheckAll => check_all_targets(),
+        Commands::Examples { bless, host_only } => examples::run_examples(bless, host_only),
+        Commands::CheckAll { install_targets } => check_all_targets(install_targets),
+        Commands::SizeReport { defmt_budget } => size_report(defmt_budget),
+        Commands::Msrv { skip_coverage } => msrv::run_msrv(skip_coverage),
+        Commands::Soak { minutes, smoke } => run_soak(minutes, smoke),
     }
 }
 
+/// Bare-metal targets checked by `check-all`, with the extra `lit-bit-core` features each
+/// needs. `thumbv6m` has no compare-and-swap, so the mailbox statics need `portable-atomic`.
+const EMBEDDED_TARGETS: &[(&str, &[&str])] = &[
+    ("thumbv6m-none-eabi", &["portable-atomic"]),
+    ("thumbv7m-none-eabi", &[]),
+    ("thumbv8m.main-none-eabi", &[]),
+    ("riscv32imac-unknown-none-elf", &[]),
+];
+
+const HOST_TARGET: &str = "x86_64-unknown-linux-gnu";
+
 fn run_ci(target: &str) -> Result<()> {
     println!("Running CI for target: {}", target);
 
-    match target {
-        "thumbv7m-none-eabi" | "riscv32imac-unknown-none-elf" => {
+    match embedded_features(target) {
+        Some(features) => {
             // Embedded targets - only check core with no-std
-            run_command(&[
-                "cargo",
-                "check",
-                "--target",
-                target,
-                "-p",
-                "lit-bit-core",
-                "--no-default-features",
-            ])?;
+            check_embedded_core(target, features)?;
             println!("✓ Embedded target {} builds successfully", target);
         }
-        _ => {
+        None => {
             // Host targets - run full CI
-            run_command(&["cargo", "check", "--workspace"])?;
-            run_command(&["cargo", "test", "-p", "lit-bit-tests"])?;
-            run_command(&["cargo", "check", "-p", "lit-bit-bench"])?;
+            check_host()?;
+            examples::run_examples(false, true)?;
             println!("✓ Host target {} passes all checks", target);
         }
     }
@@ -70,9 +128,81 @@ fn run_ci(target: &str) -> Result<()> {
     Ok(())
 }
 
-fn run_tests() -> Result<()> {
-    println!("Running all tests...");
+fn embedded_features(target: &str) -> Option<&'static [&'static str]> {
+    EMBEDDED_TARGETS
+        .iter()
+        .find(|(name, _)| *name == target)
+        .map(|(_, features)| *features)
+}
+
+fn check_host() -> Result<()> {
+    run_command(&["cargo", "check", "--workspace"])?;
     run_command(&["cargo", "test", "-p", "lit-bit-tests"])?;
+    run_command(&["cargo", "check", "-p", "lit-bit-bench"])
+}
+
+fn check_embedded_core(target: &str, features: &[&str]) -> Result<()> {
+    let features = features.join(",");
+    let mut args = vec![
+        "cargo",
+        "check",
+        "--target",
+        target,
+        "-p",
+        "lit-bit-core",
+        "--no-default-features",
+    ];
+    if !features.is_empty() {
+        args.extend(["--features", &features]);
+    }
+    run_command(&args)
+}
+
+/// Builds every embedded example of `target`'s architecture, returning how many built and
+/// how many there are.
+fn build_embedded_examples(target: &str, extra_features: &[&str]) -> Result<(usize, usize)> {
+    let examples = examples::embedded_examples(target)?;
+    let mut built = 0;
+    for (name, features) in &examples {
+        let mut features: Vec<&str> = features.iter().map(String::as_str).collect();
+        features.extend(extra_features);
+        let features = features.join(",");
+        let mut args = vec!["cargo", "build", "-p", "lit-bit-core", "--example", name];
+        if !features.is_empty() {
+            args.extend(["--features", &features]);
+        }
+        args.extend(["--target", target]);
+        match run_command(&args) {
+            Ok(()) => built += 1,
+            Err(err) => println!("✗ {name} for {target}: {err:#}"),
+        }
+    }
+    Ok((built, examples.len()))
+}
+
+fn run_tests(coverage: bool) -> Result<()> {
+    println!("Running all tests...");
+    if coverage {
+        let stdout = run_command_output(&[
+            "cargo",
+            "test",
+            "-p",
+            "lit-bit-tests",
+            "--features",
+            "coverage",
+            "--",
+            "--nocapture",
+        ])?;
+        println!("State coverage:");
+        // The test harness may print its own progress on the same line, so match anywhere.
+        for line in stdout.lines() {
+            if let Some((_, dump)) = line.split_once(COVERAGE_DUMP_PREFIX) {
+                println!("  {}", dump.trim());
+            }
+        }
+    } else {
+        run_command(&["cargo", "test", "-p", "lit-bit-tests"])?;
+    }
     println!("✓ All tests passed");
     Ok(())
 }
@@ -90,23 +220,193 @@ fn run_benchmarks(smoke: bool) -> Result<()> {
     Ok(())
 }
 
-fn check_all_targets() -> Result<()> {
-    let targets = [
-        "x86_64-unknown-linux-gnu",
-        "thumbv7m-none-eabi",
-        "riscv32imac-unknown-none-elf",
-    ];
+fn run_soak(minutes: u64, smoke: bool) -> Result<()> {
+    let minutes = minutes.to_string();
+    let scenario: &[&str] = if smoke {
+        &["--smoke"]
+    } else {
+        &["--minutes", &minutes]
+    };
+    // Streams the soak's progress instead of capturing it like `run_command`.
+    let status = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "lit-bit-bench",
+            "--release",
+            "--bin",
+            "soak",
+            "--",
+            "--csv",
+            "target/soak/soak.csv",
+        ])
+        .args(scenario)
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("soak failed ({status})");
+    }
+    Ok(())
+}
+
+fn check_all_targets(install_targets: bool) -> Result<()> {
+    ensure_targets_installed(install_targets)?;
 
-    for target in &targets {
+    let mut rows = Vec::new();
+    println!("Checking target: {}", HOST_TARGET);
+    rows.push((
+        HOST_TARGET,
+        Status::of(check_host()),
+        Status::of(examples::run_examples(false, true)),
+    ));
+
+    for &(target, features) in EMBEDDED_TARGETS {
         println!("Checking target: {}", target);
-        run_ci(target)?;
+        let core = Status::of(check_embedded_core(target, features));
+        let examples = match build_embedded_examples(target, features) {
+            Ok((_, 0)) => Status::Passed("none".to_string()),
+            Ok((built, total)) if built == total => Status::Passed(format!("{built}/{total}")),
+            Ok((built, total)) => Status::Failed(format!("FAIL {built}/{total}")),
+            Err(err) => Status::of(Err(err)),
+        };
+        rows.push((target, core, examples));
+    }
+
+    println!();
+    println!("{:<30} {:<10} {:<10}", "target", "core", "examples");
+    for (target, core, examples) in &rows {
+        println!("{target:<30} {core:<10} {examples:<10}");
     }
 
+    let failed: Vec<&str> = rows
+        .iter()
+        .filter(|(_, core, examples)| core.failed() || examples.failed())
+        .map(|(target, _, _)| *target)
+        .collect();
+    if !failed.is_empty() {
+        anyhow::bail!("{} target(s) failed: {}", failed.len(), failed.join(", "));
+    }
     println!("✓ All targets check successfully");
     Ok(())
 }
 
+/// One cell of the `check-all` matrix.
+enum Status {
+    Passed(String),
+    Failed(String),
+}
+
+impl Status {
+    /// `ok` or `FAIL`, printing the error of a failed step.
+    fn of(result: Result<()>) -> Self {
+        match result {
+            Ok(()) => Status::Passed("ok".to_string()),
+            Err(err) => {
+                println!("✗ {err:#}");
+                Status::Failed("FAIL".to_string())
+            }
+        }
+    }
+
+    fn failed(&self) -> bool {
+        matches!(self, Status::Failed(_))
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Passed(text) | Status::Failed(text) => f.pad(text),
+        }
+    }
+}
+
+fn ensure_targets_installed(install_targets: bool) -> Result<()> {
+    let installed = run_command_output(&["rustup", "target", "list", "--installed"])?;
+    let missing: Vec<&str> = EMBEDDED_TARGETS
+        .iter()
+        .map(|(target, _)| *target)
+        .filter(|target| !installed.lines().any(|line| line.trim() == *target))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    if !install_targets {
+        anyhow::bail!(
+            "missing targets: {}; install them with `rustup target add {}` or pass --install-targets",
+            missing.join(", "),
+            missing.join(" ")
+        );
+    }
+    println!("Installing targets: {}", missing.join(", "));
+    let mut args = vec!["rustup", "target", "add"];
+    args.extend(&missing);
+    run_command(&args)
+}
+
+fn size_report(defmt_budget: u64) -> Result<()> {
+    // The traffic light chart has no parallel states, so `single-region` switches it to
+    // `FlatRuntime`; everything else about the build is identical.
+    for (label, features) in [
+        ("multi-region (Runtime)", "panic-halt"),
+        ("single-region (FlatRuntime)", "panic-halt,single-region"),
+    ] {
+        let output = cargo_size("traffic_light_cortex_m", features)?;
+        println!("{label}:");
+        for line in output.lines() {
+            println!("  {line}");
+        }
+    }
+
+    // defmt's format strings live in a non-allocated section, so only the calls and their
+    // arguments count against flash.
+    let without = flash_bytes(&cargo_size("supervision_cortex_m", "panic-halt")?)?;
+    let with = flash_bytes(&cargo_size("supervision_cortex_m", "panic-halt,defmt")?)?;
+    let growth = with.saturating_sub(without);
+    println!(
+        "supervision defmt timeline: {without} -> {with} bytes of flash (+{growth}, budget {defmt_budget})"
+    );
+    if growth > defmt_budget {
+        anyhow::bail!(
+            "defmt instrumentation adds {growth} bytes of flash, over the {defmt_budget}-byte budget"
+        );
+    }
+    Ok(())
+}
+
+fn cargo_size(example: &str, features: &str) -> Result<String> {
+    run_command_output(&[
+        "cargo",
+        "size",
+        "-p",
+        "lit-bit-core",
+        "--example",
+        example,
+        "--target",
+        "thumbv7m-none-eabi",
+        "--release",
+        "--features",
+        features,
+    ])
+}
+
+/// Sums `text` and `data` from `size`'s Berkeley-format output.
+fn flash_bytes(size_output: &str) -> Result<u64> {
+    let sizes = size_output
+        .lines()
+        .nth(1)
+        .ok_or_else(|| anyhow::anyhow!("unexpected `cargo size` output:\n{size_output}"))?;
+    let mut columns = sizes.split_whitespace().map(str::parse::<u64>);
+    match (columns.next(), columns.next()) {
+        (Some(Ok(text)), Some(Ok(data))) => Ok(text + data),
+        _ => anyhow::bail!("unexpected `cargo size` output:\n{size_output}"),
+    }
+}
+
 fn run_command(args: &[&str]) -> Result<()> {
+    run_command_output(args).map(|_| ())
+}
+
+fn run_command_output(args: &[&str]) -> Result<String> {
     let mut cmd = Command::new(args[0]);
     cmd.args(&args[1..]);
 
@@ -121,5 +421,5 @@ fn run_command(args: &[&str]) -> Result<()> {
         );
     }
 
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
@@ -0,0 +1,374 @@
+//! `cargo xtask msrv`: builds the API probes in `lit-bit-tests/api-probes` with the
+//! toolchain pinned in `lit-bit-core`'s `rust-version`, and checks with
+//! `cargo public-api` that every public item of `lit-bit-core` is probed.
+
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+use crate::examples::workspace_root;
+
+const PROBES_DIR: &str = "lit-bit-tests/api-probes";
+/// The probes are not a workspace member, so they are built through their own manifest.
+const PROBES_MANIFEST: &str = "lit-bit-tests/api-probes/Cargo.toml";
+/// Manifests whose `rust-version` must match `lit-bit-core`'s.
+const PINNED_MANIFESTS: [&str; 3] = [
+    "lit-bit-core/Cargo.toml",
+    "lit-bit-macro/Cargo.toml",
+    PROBES_MANIFEST,
+];
+
+/// A feature set users build `lit-bit-core` with.
+struct FeatureSet {
+    name: &'static str,
+    probe_args: &'static [&'static str],
+    core_args: &'static [&'static str],
+}
+
+const FEATURE_SETS: [FeatureSet; 2] = [
+    FeatureSet {
+        name: "no_std",
+        probe_args: &["--no-default-features"],
+        core_args: &["--no-default-features"],
+    },
+    FeatureSet {
+        name: "std",
+        probe_args: &["--features", "std"],
+        core_args: &["--features", "std,async-tokio"],
+    },
+];
+
+pub fn run_msrv(skip_coverage: bool) -> Result<()> {
+    let root = workspace_root();
+    let msrv = pinned_msrv(&root)?;
+    println!("Pinned MSRV: {msrv}");
+
+    for set in &FEATURE_SETS {
+        println!("Building API probes ({}) with Rust {msrv}...", set.name);
+        let toolchain = format!("+{msrv}");
+        let mut args = vec![
+            toolchain.as_str(),
+            "build",
+            "--manifest-path",
+            PROBES_MANIFEST,
+        ];
+        args.extend(set.probe_args);
+        cargo(&root, &args)?;
+    }
+    println!("✓ API probes build with Rust {msrv}");
+
+    if skip_coverage {
+        return Ok(());
+    }
+    let probed = probed_paths(&root.join(PROBES_DIR).join("src"))?;
+    let mut missing = Vec::new();
+    for set in &FEATURE_SETS {
+        println!("Checking probe coverage ({})...", set.name);
+        let mut args = vec!["public-api", "-p", "lit-bit-core", "--simplified"];
+        args.extend(set.core_args);
+        let api = cargo(&root, &args).context(
+            "`cargo public-api` failed; install it with `cargo install cargo-public-api`",
+        )?;
+        for item in unprobed(&public_items(&api), &probed) {
+            missing.push(format!("{item} ({})", set.name));
+        }
+    }
+    if !missing.is_empty() {
+        bail!(
+            "{} public item(s) of lit-bit-core are not exercised by {PROBES_DIR}:\n  {}",
+            missing.len(),
+            missing.join("\n  ")
+        );
+    }
+    println!("✓ Every public item of lit-bit-core is probed");
+    Ok(())
+}
+
+/// Reads `lit-bit-core`'s `rust-version` and checks the other pinned manifests agree.
+fn pinned_msrv(root: &Path) -> Result<String> {
+    let mut pinned: Option<String> = None;
+    for manifest in PINNED_MANIFESTS {
+        let path = root.join(manifest);
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let value: toml::Value =
+            toml::from_str(&text).with_context(|| format!("invalid {}", path.display()))?;
+        let version = value
+            .get("package")
+            .and_then(|package| package.get("rust-version"))
+            .and_then(toml::Value::as_str)
+            .with_context(|| format!("{manifest} has no `package.rust-version`"))?;
+        match &pinned {
+            None => pinned = Some(version.to_string()),
+            Some(msrv) if msrv != version => bail!(
+                "{manifest} pins Rust {version}, but {} pins {msrv}",
+                PINNED_MANIFESTS[0]
+            ),
+            Some(_) => {}
+        }
+    }
+    Ok(pinned.expect("PINNED_MANIFESTS is not empty"))
+}
+
+fn cargo(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("cargo")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .context("cannot run cargo")?;
+    if !output.status.success() {
+        bail!(
+            "Command failed: cargo {}\nstderr: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Item kinds `cargo public-api` prints after `pub` and any qualifiers.
+const ITEM_KINDS: [&str; 10] = [
+    "struct", "enum", "union", "trait", "fn", "type", "const", "static", "macro", "use",
+];
+
+/// Returns the paths of the items that live directly in a public module, as listed by
+/// `cargo public-api`. Fields, variants, methods and associated items are left out:
+/// they are exercised through their parent, which needs a probe of its own.
+fn public_items(api: &str) -> BTreeSet<String> {
+    let mut modules = BTreeSet::new();
+    let mut items = Vec::new();
+    for line in api.lines() {
+        let Some(rest) = line.trim().strip_prefix("pub ") else {
+            continue;
+        };
+        let mut words = rest.split_whitespace().peekable();
+        let mut kind = None;
+        while let Some(word) = words.next() {
+            match word {
+                "async" | "unsafe" => {}
+                "extern" => {
+                    // Skip the ABI string, if any.
+                    if words.peek().is_some_and(|next| next.starts_with('"')) {
+                        words.next();
+                    }
+                }
+                "const" if matches!(words.peek(), Some(&("fn" | "async" | "unsafe"))) => {}
+                _ => {
+                    kind = Some(word);
+                    break;
+                }
+            }
+        }
+        let (Some(kind), Some(path)) = (kind, words.next().map(leading_path)) else {
+            continue;
+        };
+        if kind == "mod" {
+            modules.insert(path.to_string());
+        } else if ITEM_KINDS.contains(&kind) {
+            items.push(path.to_string());
+        }
+    }
+    items
+        .into_iter()
+        .filter(|path| {
+            path.rsplit_once("::")
+                .is_some_and(|(parent, _)| modules.contains(parent))
+        })
+        .collect()
+}
+
+/// The longest prefix of `text` that is a `::`-separated path.
+fn leading_path(text: &str) -> &str {
+    let mut end = 0;
+    let mut rest = text;
+    loop {
+        let ident = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if ident == 0 {
+            break;
+        }
+        end += ident;
+        rest = &rest[ident..];
+        match rest.strip_prefix("::") {
+            Some(next) if next.starts_with(|c: char| c.is_alphabetic() || c == '_') => {
+                end += 2;
+                rest = next;
+            }
+            _ => break,
+        }
+    }
+    &text[..end]
+}
+
+/// Collects every `lit_bit_core::` path spelled out in the probe sources, with `use`
+/// trees flattened.
+fn probed_paths(src: &Path) -> Result<BTreeSet<String>> {
+    let mut paths = BTreeSet::new();
+    for entry in std::fs::read_dir(src).with_context(|| format!("cannot read {}", src.display()))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("cannot read {}", path.display()))?;
+            collect_paths(&strip_comments(&text), &mut paths);
+        }
+    }
+    Ok(paths)
+}
+
+fn strip_comments(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_once("//").map_or(line, |(code, _)| code))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn collect_paths(text: &str, paths: &mut BTreeSet<String>) {
+    const ROOT: &str = "lit_bit_core";
+    let mut search = 0;
+    while let Some(offset) = text[search..].find(ROOT) {
+        let start = search + offset;
+        search = start + ROOT.len();
+        let preceded_by_ident = text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if !preceded_by_ident {
+            search = start + parse_tree(&text[start..], "", paths);
+        }
+    }
+}
+
+/// Parses the use tree (or plain path) at the start of `text`, adding the flattened paths
+/// under `prefix`; returns the number of bytes consumed.
+fn parse_tree(text: &str, prefix: &str, paths: &mut BTreeSet<String>) -> usize {
+    let path = leading_path(text);
+    let full = match (prefix.is_empty(), path) {
+        (true, _) => path.to_string(),
+        (false, "self") => prefix.to_string(),
+        (false, _) => format!("{prefix}::{path}"),
+    };
+    let mut consumed = path.len();
+    let Some(group) = text[consumed..].strip_prefix("::{") else {
+        if !path.is_empty() {
+            paths.insert(full);
+        }
+        return consumed;
+    };
+    consumed += 3;
+    let mut inner = group;
+    loop {
+        let trimmed = inner.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        consumed += inner.len() - trimmed.len();
+        inner = trimmed;
+        if inner.starts_with('}') {
+            return consumed + 1;
+        }
+        let used = parse_tree(inner, &full, paths);
+        if used == 0 {
+            // Not a path; give up on the rest of this group.
+            return consumed;
+        }
+        consumed += used;
+        inner = &inner[used..];
+        if let Some(alias) = inner.trim_start().strip_prefix("as ") {
+            let alias = alias.trim_start();
+            let used = inner.len() - alias.len() + leading_path(alias).len();
+            consumed += used;
+            inner = &inner[used..];
+        }
+    }
+}
+
+/// Public items with no probe naming them or something inside them.
+fn unprobed<'a>(items: &'a BTreeSet<String>, probed: &BTreeSet<String>) -> Vec<&'a str> {
+    items
+        .iter()
+        .filter(|item| {
+            !probed.iter().any(|path| {
+                path.strip_prefix(item.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const API: &str = "\
+pub mod lit_bit_core
+pub mod lit_bit_core::actor
+pub enum lit_bit_core::actor::ActorError
+pub lit_bit_core::actor::ActorError::StartupFailure
+pub fn lit_bit_core::actor::ActorError::fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+pub trait lit_bit_core::actor::Actor: core::marker::Send
+pub type lit_bit_core::actor::Actor::Message
+pub async fn lit_bit_core::actor::actor_task<A>(actor: A) -> core::result::Result<(), lit_bit_core::actor::ActorError>
+pub const fn lit_bit_core::actor::ReentrancyGuard::new() -> Self
+pub struct lit_bit_core::actor::ReentrancyGuard
+pub const lit_bit_core::MAX_ACTIVE_REGIONS: usize
+pub macro lit_bit_core::trace!
+pub use lit_bit_core::config::serde
+impl core::fmt::Debug for lit_bit_core::actor::ActorError
+";
+
+    #[test]
+    fn public_items_keeps_only_module_level_items() {
+        let items: Vec<_> = public_items(API).into_iter().collect();
+        assert_eq!(
+            items,
+            [
+                "lit_bit_core::MAX_ACTIVE_REGIONS",
+                "lit_bit_core::actor::Actor",
+                "lit_bit_core::actor::ActorError",
+                "lit_bit_core::actor::ReentrancyGuard",
+                "lit_bit_core::actor::actor_task",
+                "lit_bit_core::trace",
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_paths_flattens_use_trees_and_inline_paths() {
+        let source = "
+use lit_bit_core::{
+    Acceptance, MonotonicClock as Clock,
+    actor::{self, address::Address},
+};
+use lit_bit_core::runtime::StateId;
+// lit_bit_core::Commented
+fn f() -> usize { lit_bit_core::runtime::MAX_ACTIVE_REGIONS + my_lit_bit_core::X }
+fn g() { lit_bit_core::trace!(\"x\"); <T as lit_bit_core::EventKind>::ALL; }
+";
+        let mut paths = BTreeSet::new();
+        collect_paths(&strip_comments(source), &mut paths);
+        let paths: Vec<_> = paths.iter().map(String::as_str).collect();
+        assert_eq!(
+            paths,
+            [
+                "lit_bit_core::Acceptance",
+                "lit_bit_core::EventKind",
+                "lit_bit_core::MonotonicClock",
+                "lit_bit_core::actor",
+                "lit_bit_core::actor::address::Address",
+                "lit_bit_core::runtime::MAX_ACTIVE_REGIONS",
+                "lit_bit_core::runtime::StateId",
+                "lit_bit_core::trace",
+            ]
+        );
+    }
+
+    #[test]
+    fn unprobed_accepts_paths_inside_an_item() {
+        let items = BTreeSet::from([
+            "lit_bit_core::Runtime".to_string(),
+            "lit_bit_core::RuntimeError".to_string(),
+        ]);
+        let probed = BTreeSet::from(["lit_bit_core::Runtime::new".to_string()]);
+        assert_eq!(unprobed(&items, &probed), ["lit_bit_core::RuntimeError"]);
+    }
+}